@@ -0,0 +1,42 @@
+use std::path::Path;
+use std::process::Command;
+
+fn main() {
+    println!(
+        "cargo:rustc-env=EXCEL_CLI_GIT_HASH={}",
+        git_hash().unwrap_or_else(|| "unknown".to_string())
+    );
+    println!(
+        "cargo:rustc-env=EXCEL_CLI_TARGET={}",
+        std::env::var("TARGET").unwrap_or_default()
+    );
+    println!(
+        "cargo:rustc-env=EXCEL_CLI_PROFILE={}",
+        std::env::var("PROFILE").unwrap_or_default()
+    );
+
+    // Rebuild when the checked-out commit moves
+    println!("cargo:rerun-if-changed=build.rs");
+    let head = Path::new(".git/HEAD");
+    if head.exists() {
+        println!("cargo:rerun-if-changed=.git/HEAD");
+        if let Some(reference) = std::fs::read_to_string(head)
+            .ok()
+            .and_then(|text| text.strip_prefix("ref: ").map(|r| r.trim().to_string()))
+        {
+            println!("cargo:rerun-if-changed=.git/{reference}");
+        }
+    }
+}
+
+fn git_hash() -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--short=12", "HEAD"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let hash = String::from_utf8(output.stdout).ok()?.trim().to_string();
+    (!hash.is_empty()).then_some(hash)
+}
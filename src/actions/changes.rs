@@ -0,0 +1,186 @@
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+
+use super::ActionCommand;
+use crate::excel::Cell;
+
+/// A cell as it was when the workbook was opened or last saved, and as it is now
+#[derive(Clone)]
+pub struct CellChange {
+    pub old_value: Cell,
+    pub new_value: Cell,
+}
+
+/// The cells changed since the workbook was opened or last saved, worked out by replaying
+/// the undo history. Deleted and inserted rows and columns move the cells after them along
+#[derive(Clone, Default)]
+pub struct ChangedCells {
+    /// Changes by sheet name, then by 1-based (row, col)
+    sheets: HashMap<String, BTreeMap<(usize, usize), CellChange>>,
+}
+
+impl ChangedCells {
+    /// Replays `actions` on top of `dropped`, the changes of steps no longer in the history
+    pub(super) fn from_actions<'a>(
+        dropped: &Self,
+        actions: impl IntoIterator<Item = &'a ActionCommand>,
+    ) -> Self {
+        let mut changes = dropped.clone();
+        for action in actions {
+            changes.apply(action);
+        }
+        // A cell edited back to what it was is no longer changed
+        for cells in changes.sheets.values_mut() {
+            cells.retain(|_, change| change.old_value != change.new_value);
+        }
+        changes.sheets.retain(|_, cells| !cells.is_empty());
+        changes
+    }
+
+    #[must_use]
+    pub fn contains(&self, sheet_name: &str, cell: (usize, usize)) -> bool {
+        self.sheets
+            .get(sheet_name)
+            .is_some_and(|cells| cells.contains_key(&cell))
+    }
+
+    /// The changed cells of `sheet_name`, row by row
+    pub fn on_sheet(
+        &self,
+        sheet_name: &str,
+    ) -> impl Iterator<Item = (&(usize, usize), &CellChange)> {
+        self.sheets.get(sheet_name).into_iter().flatten()
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.sheets.values().map(BTreeMap::len).sum()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.sheets.is_empty()
+    }
+
+    pub(super) fn apply(&mut self, action: &ActionCommand) {
+        match action {
+            ActionCommand::Cell(action) => {
+                self.record(
+                    &action.sheet_name,
+                    (action.row, action.col),
+                    &action.old_value,
+                    &action.new_value,
+                );
+            }
+            ActionCommand::Range(action) => {
+                for change in &action.changes {
+                    self.record(
+                        &action.sheet_name,
+                        (change.row, change.col),
+                        &change.old_value,
+                        &change.new_value,
+                    );
+                }
+            }
+            ActionCommand::Row(action) => {
+                self.move_cells(&action.sheet_name, |(row, col)| {
+                    match row.cmp(&action.row) {
+                        Ordering::Less => Some((row, col)),
+                        Ordering::Equal => None,
+                        Ordering::Greater => Some((row - 1, col)),
+                    }
+                });
+            }
+            ActionCommand::MultiRow(action) => {
+                self.move_cells(&action.sheet_name, |(row, col)| {
+                    let deleted_before = action.rows.partition_point(|&deleted| deleted < row);
+                    let is_deleted = action.rows.get(deleted_before) == Some(&row);
+                    (!is_deleted).then_some((row - deleted_before, col))
+                });
+            }
+            ActionCommand::InsertRow(action) => {
+                self.move_cells(&action.sheet_name, |(row, col)| {
+                    Some((row + usize::from(row >= action.row), col))
+                });
+            }
+            ActionCommand::Column(action) => {
+                self.move_cells(&action.sheet_name, |(row, col)| {
+                    match col.cmp(&action.col) {
+                        Ordering::Less => Some((row, col)),
+                        Ordering::Equal => None,
+                        Ordering::Greater => Some((row, col - 1)),
+                    }
+                });
+            }
+            ActionCommand::MultiColumn(action) => {
+                let deleted = action.end_col - action.start_col + 1;
+                self.move_cells(&action.sheet_name, |(row, col)| {
+                    if col < action.start_col {
+                        Some((row, col))
+                    } else if col > action.end_col {
+                        Some((row, col - deleted))
+                    } else {
+                        None
+                    }
+                });
+            }
+            ActionCommand::InsertColumn(action) => {
+                self.move_cells(&action.sheet_name, |(row, col)| {
+                    Some((row, col + usize::from(col >= action.col)))
+                });
+            }
+            ActionCommand::Sheet(action) => {
+                self.sheets.remove(&action.sheet_name);
+            }
+            ActionCommand::RenameSheet(action) => {
+                if let Some(cells) = self.sheets.remove(&action.old_name) {
+                    self.sheets.insert(action.new_name.clone(), cells);
+                }
+            }
+            ActionCommand::ColumnWidth(_) => {}
+            ActionCommand::Group(actions) => {
+                for action in actions {
+                    self.apply(action);
+                }
+            }
+        }
+    }
+
+    /// Keeps the first old value of a cell changed more than once, and its latest new value
+    fn record(
+        &mut self,
+        sheet_name: &str,
+        cell: (usize, usize),
+        old_value: &Cell,
+        new_value: &Cell,
+    ) {
+        let cells = self.sheets.entry(sheet_name.to_string()).or_default();
+        match cells.get_mut(&cell) {
+            Some(change) => change.new_value = new_value.clone(),
+            None => {
+                cells.insert(
+                    cell,
+                    CellChange {
+                        old_value: old_value.clone(),
+                        new_value: new_value.clone(),
+                    },
+                );
+            }
+        }
+    }
+
+    /// Moves the changed cells of a sheet where `moved` says, dropping those it returns `None`
+    /// for
+    fn move_cells(
+        &mut self,
+        sheet_name: &str,
+        moved: impl Fn((usize, usize)) -> Option<(usize, usize)>,
+    ) {
+        if let Some(cells) = self.sheets.get_mut(sheet_name) {
+            *cells = std::mem::take(cells)
+                .into_iter()
+                .filter_map(|(cell, change)| moved(cell).map(|cell| (cell, change)))
+                .collect();
+        }
+    }
+}
@@ -6,7 +6,8 @@ pub struct ColumnAction {
     pub sheet_index: usize,
     pub sheet_name: String,
     pub col: usize,
-    pub column_data: Vec<Cell>,
+    /// The column's cells holding something, each with its row
+    pub column_data: Vec<(usize, Cell)>,
     pub column_width: usize,
 }
 
@@ -22,7 +23,7 @@ pub struct MultiColumnAction {
     pub sheet_name: String,
     pub start_col: usize,
     pub end_col: usize,
-    pub columns_data: Vec<Vec<Cell>>,
+    pub columns_data: Vec<Vec<(usize, Cell)>>,
     pub column_widths: Vec<usize>,
 }
 
@@ -31,3 +32,24 @@ impl Command for MultiColumnAction {
         ActionType::DeleteMultiColumns
     }
 }
+
+#[derive(Clone)]
+pub struct ColumnWidthChange {
+    pub col: usize,
+    pub old_width: usize,
+    pub new_width: usize,
+}
+
+/// Column width changes on one sheet, such as a `:cw fit all`, undone as a single step
+#[derive(Clone)]
+pub struct ColumnWidthAction {
+    pub sheet_index: usize,
+    pub sheet_name: String,
+    pub changes: Vec<ColumnWidthChange>,
+}
+
+impl Command for ColumnWidthAction {
+    fn action_type(&self) -> ActionType {
+        ActionType::ColumnWidth
+    }
+}
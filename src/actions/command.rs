@@ -15,6 +15,41 @@ impl ActionCommand {
             ActionCommand::Column(_) => ActionType::DeleteColumn,
             ActionCommand::MultiColumn(_) => ActionType::DeleteMultiColumns,
             ActionCommand::Sheet(action) => action.action_type(),
+            ActionCommand::RenameSheet(action) => action.action_type(),
+            ActionCommand::Range(action) => action.action_type(),
+            ActionCommand::ColumnWidth(action) => action.action_type(),
+            ActionCommand::InsertRow(_) => ActionType::InsertRow,
+            ActionCommand::InsertColumn(_) => ActionType::InsertColumn,
+            ActionCommand::Group(actions) => actions
+                .first()
+                .map_or(ActionType::Edit, ActionCommand::get_action_type),
+        }
+    }
+
+    /// Where this action changed the workbook, for `g;` to jump back to: the sheet's name and
+    /// the changed cell, or the first cell of a changed row or column. Sheet-level actions and
+    /// column widths have no such place
+    #[must_use]
+    pub fn edit_location(&self) -> Option<(&str, (usize, usize))> {
+        match self {
+            ActionCommand::Cell(action) => Some((&action.sheet_name, (action.row, action.col))),
+            ActionCommand::Row(action) | ActionCommand::InsertRow(action) => {
+                Some((&action.sheet_name, (action.row, 1)))
+            }
+            ActionCommand::MultiRow(action) => Some((&action.sheet_name, (action.start_row, 1))),
+            ActionCommand::Column(action) | ActionCommand::InsertColumn(action) => {
+                Some((&action.sheet_name, (1, action.col)))
+            }
+            ActionCommand::MultiColumn(action) => Some((&action.sheet_name, (1, action.start_col))),
+            ActionCommand::Range(action) => action
+                .bounds()
+                .map(|(start, _)| (action.sheet_name.as_str(), start)),
+            ActionCommand::Group(actions) => {
+                actions.iter().rev().find_map(ActionCommand::edit_location)
+            }
+            ActionCommand::Sheet(_)
+            | ActionCommand::RenameSheet(_)
+            | ActionCommand::ColumnWidth(_) => None,
         }
     }
 }
@@ -1,9 +1,42 @@
-use super::ActionCommand;
+use super::{ActionCommand, ChangedCells};
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
 use std::rc::Rc;
 
+/// Steps kept to undo unless `:set undolevels` says otherwise, as in vim
+const DEFAULT_UNDO_LEVELS: usize = 1000;
+
+/// Memory the steps kept to undo may take up unless `:set undomem` says otherwise
+const DEFAULT_UNDO_MEMORY: usize = 256 << 20;
+
+/// Sheets kept by a step whose cells take up more than this are packed
+const PACK_SHEET_MIN_BYTES: usize = 1 << 20;
+
 pub struct UndoHistory {
-    undo_stack: Vec<Rc<ActionCommand>>,
+    /// Steps to undo, oldest first, with the bytes each takes up
+    undo_stack: VecDeque<(Rc<ActionCommand>, usize)>,
     redo_stack: Vec<Rc<ActionCommand>>,
+    /// Actions recorded since the outermost `begin_group`, pushed as one step by `end_group`
+    group: Vec<ActionCommand>,
+    group_depth: usize,
+    /// The cell of the latest change on each sheet, by sheet name. Kept when the history is
+    /// cleared on save, so `g;` still finds it
+    last_edits: HashMap<String, (usize, usize)>,
+    /// The cells changed by steps dropped since the history was last cleared, which
+    /// `changed_cells` still counts
+    dropped_changes: ChangedCells,
+    /// What `changed_cells` last worked out, until the history next changes
+    changed_cells: RefCell<Option<Rc<ChangedCells>>>,
+    /// The bytes the steps to undo take up
+    undo_bytes: usize,
+    /// Most steps kept to undo; the oldest are dropped past it
+    max_steps: usize,
+    /// Most bytes the steps to undo may take up; the oldest are dropped past it, though the
+    /// latest step is always kept
+    max_bytes: Option<usize>,
+    /// Whether steps were dropped since the history was last cleared, so undoing every step
+    /// left no longer gets back to the file as saved
+    dropped_steps: bool,
 }
 
 impl Default for UndoHistory {
@@ -16,19 +49,117 @@ impl UndoHistory {
     #[must_use]
     pub fn new() -> Self {
         Self {
-            undo_stack: Vec::with_capacity(100), // Pre-allocate capacity
+            undo_stack: VecDeque::with_capacity(100), // Pre-allocate capacity
             redo_stack: Vec::with_capacity(20),
+            group: Vec::new(),
+            group_depth: 0,
+            last_edits: HashMap::new(),
+            dropped_changes: ChangedCells::default(),
+            changed_cells: RefCell::new(None),
+            undo_bytes: 0,
+            max_steps: DEFAULT_UNDO_LEVELS,
+            max_bytes: Some(DEFAULT_UNDO_MEMORY),
+            dropped_steps: false,
         }
     }
 
-    pub fn push(&mut self, action: ActionCommand) {
-        // Use Rc to avoid deep cloning the entire action
-        self.undo_stack.push(Rc::new(action));
+    pub fn push(&mut self, mut action: ActionCommand) {
+        self.record_edit_location(&action);
+        self.changed_cells.take();
+        action.pack_sheets(PACK_SHEET_MIN_BYTES);
+        if self.group_depth > 0 {
+            self.group.push(action);
+        } else {
+            // Use Rc to avoid deep cloning the entire action
+            self.push_step(Rc::new(action));
+        }
         self.redo_stack.clear();
     }
 
+    /// Most steps kept to undo
+    #[must_use]
+    pub fn max_steps(&self) -> usize {
+        self.max_steps
+    }
+
+    /// Most bytes the steps to undo may take up, or `None` for no limit
+    #[must_use]
+    pub fn max_bytes(&self) -> Option<usize> {
+        self.max_bytes
+    }
+
+    /// The bytes the steps to undo take up
+    #[must_use]
+    pub fn undo_bytes(&self) -> usize {
+        self.undo_bytes
+    }
+
+    /// Sets how many steps, and how many bytes of them, are kept to undo, dropping the oldest
+    /// steps past either
+    pub fn set_limits(&mut self, max_steps: usize, max_bytes: Option<usize>) {
+        self.max_steps = max_steps.max(1);
+        self.max_bytes = max_bytes;
+        self.drop_oldest_steps();
+    }
+
+    fn push_step(&mut self, action: Rc<ActionCommand>) {
+        let bytes = action.estimated_bytes();
+        self.undo_bytes += bytes;
+        self.undo_stack.push_back((action, bytes));
+        self.drop_oldest_steps();
+    }
+
+    fn pop_step(&mut self) -> Option<Rc<ActionCommand>> {
+        let (action, bytes) = self.undo_stack.pop_back()?;
+        self.undo_bytes -= bytes;
+        Some(action)
+    }
+
+    fn drop_oldest_steps(&mut self) {
+        while self.undo_stack.len() > self.max_steps
+            || (self.undo_stack.len() > 1
+                && self.max_bytes.is_some_and(|max| self.undo_bytes > max))
+        {
+            if let Some((action, bytes)) = self.undo_stack.pop_front() {
+                self.undo_bytes -= bytes;
+                self.dropped_changes.apply(&action);
+                self.dropped_steps = true;
+                self.changed_cells.take();
+            }
+        }
+    }
+
+    /// Starts collecting pushed actions so they undo as a single step. Groups may nest; only
+    /// the outermost `end_group` records the step.
+    pub fn begin_group(&mut self) {
+        self.group_depth += 1;
+    }
+
+    /// Closes the innermost group. Closing the outermost one pushes the collected actions as
+    /// one step, or as a plain action when only one was recorded.
+    pub fn end_group(&mut self) {
+        self.group_depth = self.group_depth.saturating_sub(1);
+        if self.group_depth > 0 {
+            return;
+        }
+
+        self.changed_cells.take();
+        let mut actions = std::mem::take(&mut self.group);
+        match actions.len() {
+            0 => {}
+            1 => {
+                if let Some(action) = actions.pop() {
+                    self.push_step(Rc::new(action));
+                }
+            }
+            _ => self.push_step(Rc::new(ActionCommand::Group(actions))),
+        }
+    }
+
     pub fn undo(&mut self) -> Option<Rc<ActionCommand>> {
-        if let Some(action) = self.undo_stack.pop() {
+        if let Some(action) = self.pop_step() {
+            self.record_edit_location(&action);
+            self.changed_cells.take();
             self.redo_stack.push(Rc::clone(&action));
             Some(action)
         } else {
@@ -38,20 +169,64 @@ impl UndoHistory {
 
     pub fn redo(&mut self) -> Option<Rc<ActionCommand>> {
         if let Some(action) = self.redo_stack.pop() {
-            self.undo_stack.push(Rc::clone(&action));
+            self.record_edit_location(&action);
+            self.changed_cells.take();
+            self.push_step(Rc::clone(&action));
             Some(action)
         } else {
             None
         }
     }
 
+    /// Whether every change since the workbook was opened or last saved is undone
     #[must_use]
     pub fn all_undone(&self) -> bool {
-        self.undo_stack.is_empty()
+        self.undo_stack.is_empty() && !self.dropped_steps
+    }
+
+    /// The cell of the latest change, undo or redo on `sheet_name`
+    #[must_use]
+    pub fn last_edit(&self, sheet_name: &str) -> Option<(usize, usize)> {
+        self.last_edits.get(sheet_name).copied()
+    }
+
+    /// The cells changed since the workbook was opened or last saved: by the actions that
+    /// can be undone, and by those dropped from the history
+    #[must_use]
+    pub fn changed_cells(&self) -> Rc<ChangedCells> {
+        let mut cached = self.changed_cells.borrow_mut();
+        let changes = cached.get_or_insert_with(|| {
+            Rc::new(ChangedCells::from_actions(
+                &self.dropped_changes,
+                self.undo_stack
+                    .iter()
+                    .map(|(action, _)| action.as_ref())
+                    .chain(self.group.iter()),
+            ))
+        });
+        Rc::clone(changes)
+    }
+
+    /// Keeps the latest change of a renamed sheet under its new name
+    pub fn rename_sheet(&mut self, old_name: &str, new_name: &str) {
+        if let Some(cell) = self.last_edits.remove(old_name) {
+            self.last_edits.insert(new_name.to_string(), cell);
+        }
+    }
+
+    fn record_edit_location(&mut self, action: &ActionCommand) {
+        if let Some((sheet_name, cell)) = action.edit_location() {
+            self.last_edits.insert(sheet_name.to_string(), cell);
+        }
     }
 
     pub fn clear(&mut self) {
+        self.changed_cells.take();
         self.undo_stack.clear();
         self.redo_stack.clear();
+        self.group.clear();
+        self.undo_bytes = 0;
+        self.dropped_steps = false;
+        self.dropped_changes = ChangedCells::default();
     }
 }
@@ -1,14 +1,20 @@
 mod cell;
+mod changes;
 mod column;
 mod command;
 mod history;
+mod range;
 mod row;
 mod sheet;
+mod snapshot;
 mod types;
 
 pub use cell::CellAction;
-pub use column::{ColumnAction, MultiColumnAction};
+pub use changes::{CellChange, ChangedCells};
+pub use column::{ColumnAction, ColumnWidthAction, ColumnWidthChange, MultiColumnAction};
 pub use history::UndoHistory;
+pub use range::{RangeAction, RangeCellChange};
 pub use row::{MultiRowAction, RowAction};
-pub use sheet::{SheetAction, SheetOperation};
+pub use sheet::{SheetAction, SheetOperation, SheetRenameAction};
+pub use snapshot::PackedCells;
 pub use types::{ActionCommand, ActionExecutor, ActionType, Command};
@@ -0,0 +1,45 @@
+use super::{ActionType, Command};
+use crate::excel::Cell;
+
+#[derive(Clone)]
+pub struct RangeCellChange {
+    pub row: usize,
+    pub col: usize,
+    pub old_value: Cell,
+    pub new_value: Cell,
+}
+
+/// A batch of cell changes on one sheet that is undone and redone as a single step
+#[derive(Clone)]
+pub struct RangeAction {
+    pub sheet_index: usize,
+    pub sheet_name: String,
+    pub changes: Vec<RangeCellChange>,
+    pub action_type: ActionType,
+}
+
+impl RangeAction {
+    /// Top-left and bottom-right corners of all changed cells
+    #[must_use]
+    pub fn bounds(&self) -> Option<((usize, usize), (usize, usize))> {
+        let first = self.changes.first()?;
+        let mut top_left = (first.row, first.col);
+        let mut bottom_right = (first.row, first.col);
+
+        for change in &self.changes {
+            top_left = (top_left.0.min(change.row), top_left.1.min(change.col));
+            bottom_right = (
+                bottom_right.0.max(change.row),
+                bottom_right.1.max(change.col),
+            );
+        }
+
+        Some((top_left, bottom_right))
+    }
+}
+
+impl Command for RangeAction {
+    fn action_type(&self) -> ActionType {
+        self.action_type.clone()
+    }
+}
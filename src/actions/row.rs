@@ -6,7 +6,8 @@ pub struct RowAction {
     pub sheet_index: usize,
     pub sheet_name: String,
     pub row: usize,
-    pub row_data: Vec<Cell>,
+    /// The row's cells holding something, each with its column
+    pub row_data: Vec<(usize, Cell)>,
 }
 
 impl Command for RowAction {
@@ -21,7 +22,16 @@ pub struct MultiRowAction {
     pub sheet_name: String,
     pub start_row: usize,
     pub end_row: usize,
-    pub rows_data: Vec<Vec<Cell>>,
+    /// Original row numbers of `rows_data` in ascending order; gaps mean the rows were not contiguous
+    pub rows: Vec<usize>,
+    pub rows_data: Vec<Vec<(usize, Cell)>>,
+}
+
+impl MultiRowAction {
+    #[must_use]
+    pub fn is_contiguous(&self) -> bool {
+        self.rows.len() == self.end_row - self.start_row + 1
+    }
 }
 
 impl Command for MultiRowAction {
@@ -1,5 +1,5 @@
-use super::{ActionType, Command};
-use crate::excel::Sheet;
+use super::{ActionType, Command, PackedCells};
+use crate::excel::{CellGrid, Sheet};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum SheetOperation {
@@ -14,6 +14,53 @@ pub struct SheetAction {
     pub sheet_data: Sheet,
     pub column_widths: Vec<usize>,
     pub operation: SheetOperation,
+    /// The cells of `sheet_data` once the undo history packed them, leaving its grid empty
+    pub packed_cells: Option<PackedCells>,
+}
+
+impl SheetAction {
+    /// The sheet as it was, with its cells unpacked when the undo history packed them
+    pub fn sheet(&self) -> anyhow::Result<Sheet> {
+        let mut sheet = self.sheet_data.clone();
+        if let Some(cells) = &self.packed_cells {
+            sheet.data = cells.unpack()?;
+        }
+        Ok(sheet)
+    }
+
+    /// Packs the sheet's cells when they take up more than `min_bytes`
+    pub fn pack(&mut self, min_bytes: usize) {
+        if self.packed_cells.is_some() || self.sheet_data.data.estimated_bytes() <= min_bytes {
+            return;
+        }
+        // A sheet that fails to pack is kept as it is
+        if let Ok(cells) = PackedCells::pack(&self.sheet_data.data) {
+            self.sheet_data.data = CellGrid::new();
+            self.packed_cells = Some(cells);
+        }
+    }
+
+    /// Roughly the bytes the sheet's cells take up, packed or not
+    #[must_use]
+    pub fn estimated_bytes(&self) -> usize {
+        self.packed_cells
+            .as_ref()
+            .map_or_else(|| self.sheet_data.data.estimated_bytes(), PackedCells::len)
+    }
+}
+
+/// A sheet renamed from `old_name` to `new_name`
+#[derive(Clone)]
+pub struct SheetRenameAction {
+    pub sheet_index: usize,
+    pub old_name: String,
+    pub new_name: String,
+}
+
+impl Command for SheetRenameAction {
+    fn action_type(&self) -> ActionType {
+        ActionType::RenameSheet
+    }
 }
 
 impl Command for SheetAction {
@@ -0,0 +1,298 @@
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use std::io::{self, Read, Write};
+
+use crate::excel::{Cell, CellGrid, CellType, DataTypeInfo, IsoDuration, NumberFormat};
+
+/// The cells of a sheet deflated into bytes, so an undo step holding a deleted sheet takes up
+/// a fraction of the memory its grid would
+#[derive(Clone)]
+pub struct PackedCells {
+    bytes: Vec<u8>,
+}
+
+impl PackedCells {
+    pub fn pack(grid: &CellGrid) -> io::Result<Self> {
+        let mut packer = Packer(DeflateEncoder::new(Vec::new(), Compression::fast()));
+        packer.usize(grid.len())?;
+        for ((row, col), cell) in grid.iter() {
+            packer.usize(row)?;
+            packer.usize(col)?;
+            packer.cell(cell)?;
+        }
+        Ok(Self {
+            bytes: packer.0.finish()?,
+        })
+    }
+
+    pub fn unpack(&self) -> io::Result<CellGrid> {
+        let mut unpacker = Unpacker(DeflateDecoder::new(self.bytes.as_slice()));
+        let mut grid = CellGrid::new();
+        for _ in 0..unpacker.usize()? {
+            let row = unpacker.usize()?;
+            let col = unpacker.usize()?;
+            grid.set(row, col, unpacker.cell()?);
+        }
+        Ok(grid)
+    }
+
+    /// The bytes the packed cells take up
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+}
+
+struct Packer<W: Write>(W);
+
+impl<W: Write> Packer<W> {
+    fn u8(&mut self, value: u8) -> io::Result<()> {
+        self.0.write_all(&[value])
+    }
+
+    fn u64(&mut self, value: u64) -> io::Result<()> {
+        self.0.write_all(&value.to_le_bytes())
+    }
+
+    fn usize(&mut self, value: usize) -> io::Result<()> {
+        self.u64(value as u64)
+    }
+
+    fn f64(&mut self, value: f64) -> io::Result<()> {
+        self.u64(value.to_bits())
+    }
+
+    fn str(&mut self, value: &str) -> io::Result<()> {
+        self.usize(value.len())?;
+        self.0.write_all(value.as_bytes())
+    }
+
+    fn optional_str(&mut self, value: Option<&str>) -> io::Result<()> {
+        match value {
+            Some(value) => {
+                self.u8(1)?;
+                self.str(value)
+            }
+            None => self.u8(0),
+        }
+    }
+
+    fn optional_color(&mut self, value: Option<u32>) -> io::Result<()> {
+        match value {
+            Some(color) => {
+                self.u8(1)?;
+                self.u64(u64::from(color))
+            }
+            None => self.u8(0),
+        }
+    }
+
+    fn cell(&mut self, cell: &Cell) -> io::Result<()> {
+        self.str(&cell.value)?;
+        self.optional_str(cell.formula.as_deref())?;
+        self.u8(u8::from(cell.is_formula))?;
+        self.u8(match cell.cell_type {
+            CellType::Text => 0,
+            CellType::Number => 1,
+            CellType::Date => 2,
+            CellType::Boolean => 3,
+            CellType::Empty => 4,
+        })?;
+        self.original_type(cell.original_type.as_ref())?;
+        self.optional_str(cell.comment.as_deref())?;
+        self.optional_str(cell.hyperlink.as_deref())?;
+        self.number_format(cell.number_format.as_ref())?;
+        self.u8(u8::from(cell.unlocked))?;
+        self.optional_color(cell.fill_color)?;
+        self.optional_color(cell.font_color)
+    }
+
+    fn original_type(&mut self, value: Option<&DataTypeInfo>) -> io::Result<()> {
+        match value {
+            None => self.u8(0),
+            Some(DataTypeInfo::Empty) => self.u8(1),
+            Some(DataTypeInfo::String) => self.u8(2),
+            Some(DataTypeInfo::Float(number)) => {
+                self.u8(3)?;
+                self.f64(*number)
+            }
+            Some(DataTypeInfo::Int(number)) => {
+                self.u8(4)?;
+                self.u64(number.cast_unsigned())
+            }
+            Some(DataTypeInfo::Bool(value)) => {
+                self.u8(5)?;
+                self.u8(u8::from(*value))
+            }
+            Some(DataTypeInfo::DateTime(serial)) => {
+                self.u8(6)?;
+                self.f64(*serial)
+            }
+            Some(DataTypeInfo::Duration(days)) => {
+                self.u8(7)?;
+                self.f64(*days)
+            }
+            Some(DataTypeInfo::DateTimeIso(text)) => {
+                self.u8(8)?;
+                self.str(text)
+            }
+            Some(DataTypeInfo::DurationIso(duration)) => {
+                self.u8(9)?;
+                self.f64(duration.total_seconds)
+            }
+            Some(DataTypeInfo::Error) => self.u8(10),
+        }
+    }
+
+    fn number_format(&mut self, value: Option<&NumberFormat>) -> io::Result<()> {
+        match value {
+            None => self.u8(0),
+            Some(NumberFormat::Thousands { decimals }) => {
+                self.u8(1)?;
+                self.usize(*decimals)
+            }
+            Some(NumberFormat::Fixed { decimals }) => {
+                self.u8(2)?;
+                self.usize(*decimals)
+            }
+            Some(NumberFormat::Percent { decimals }) => {
+                self.u8(3)?;
+                self.usize(*decimals)
+            }
+            Some(NumberFormat::Currency { symbol, decimals }) => {
+                self.u8(4)?;
+                self.str(symbol)?;
+                self.usize(*decimals)
+            }
+            Some(NumberFormat::Date { pattern }) => {
+                self.u8(5)?;
+                self.str(pattern)
+            }
+        }
+    }
+}
+
+struct Unpacker<R: Read>(R);
+
+impl<R: Read> Unpacker<R> {
+    fn u8(&mut self) -> io::Result<u8> {
+        let mut byte = [0];
+        self.0.read_exact(&mut byte)?;
+        Ok(byte[0])
+    }
+
+    fn u64(&mut self) -> io::Result<u64> {
+        let mut bytes = [0; 8];
+        self.0.read_exact(&mut bytes)?;
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    fn usize(&mut self) -> io::Result<usize> {
+        usize::try_from(self.u64()?).map_err(|_| corrupt())
+    }
+
+    fn f64(&mut self) -> io::Result<f64> {
+        Ok(f64::from_bits(self.u64()?))
+    }
+
+    fn bool(&mut self) -> io::Result<bool> {
+        Ok(self.u8()? != 0)
+    }
+
+    fn str(&mut self) -> io::Result<String> {
+        let mut bytes = vec![0; self.usize()?];
+        self.0.read_exact(&mut bytes)?;
+        String::from_utf8(bytes).map_err(|_| corrupt())
+    }
+
+    fn optional_str(&mut self) -> io::Result<Option<String>> {
+        if self.bool()? {
+            self.str().map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn optional_color(&mut self) -> io::Result<Option<u32>> {
+        if self.bool()? {
+            u32::try_from(self.u64()?).map(Some).map_err(|_| corrupt())
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn cell(&mut self) -> io::Result<Cell> {
+        Ok(Cell {
+            value: self.str()?,
+            formula: self.optional_str()?,
+            is_formula: self.bool()?,
+            cell_type: match self.u8()? {
+                0 => CellType::Text,
+                1 => CellType::Number,
+                2 => CellType::Date,
+                3 => CellType::Boolean,
+                4 => CellType::Empty,
+                _ => return Err(corrupt()),
+            },
+            original_type: self.original_type()?,
+            comment: self.optional_str()?,
+            hyperlink: self.optional_str()?,
+            number_format: self.number_format()?,
+            unlocked: self.bool()?,
+            fill_color: self.optional_color()?,
+            font_color: self.optional_color()?,
+        })
+    }
+
+    fn original_type(&mut self) -> io::Result<Option<DataTypeInfo>> {
+        Ok(Some(match self.u8()? {
+            0 => return Ok(None),
+            1 => DataTypeInfo::Empty,
+            2 => DataTypeInfo::String,
+            3 => DataTypeInfo::Float(self.f64()?),
+            4 => DataTypeInfo::Int(self.u64()?.cast_signed()),
+            5 => DataTypeInfo::Bool(self.bool()?),
+            6 => DataTypeInfo::DateTime(self.f64()?),
+            7 => DataTypeInfo::Duration(self.f64()?),
+            8 => DataTypeInfo::DateTimeIso(self.str()?),
+            9 => DataTypeInfo::DurationIso(IsoDuration {
+                total_seconds: self.f64()?,
+            }),
+            10 => DataTypeInfo::Error,
+            _ => return Err(corrupt()),
+        }))
+    }
+
+    fn number_format(&mut self) -> io::Result<Option<NumberFormat>> {
+        Ok(Some(match self.u8()? {
+            0 => return Ok(None),
+            1 => NumberFormat::Thousands {
+                decimals: self.usize()?,
+            },
+            2 => NumberFormat::Fixed {
+                decimals: self.usize()?,
+            },
+            3 => NumberFormat::Percent {
+                decimals: self.usize()?,
+            },
+            4 => NumberFormat::Currency {
+                symbol: self.str()?,
+                decimals: self.usize()?,
+            },
+            5 => NumberFormat::Date {
+                pattern: self.str()?,
+            },
+            _ => return Err(corrupt()),
+        }))
+    }
+}
+
+fn corrupt() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "packed cells are corrupt")
+}
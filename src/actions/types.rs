@@ -1,3 +1,7 @@
+use std::mem::size_of;
+
+use crate::excel::Cell;
+
 #[derive(Clone, Debug)]
 pub enum ActionType {
     Edit,
@@ -7,8 +11,21 @@ pub enum ActionType {
     DeleteRow,
     DeleteColumn,
     DeleteSheet,
+    RenameSheet,
     DeleteMultiRows,
     DeleteMultiColumns,
+    Transpose,
+    Trim,
+    ChangeCase,
+    Comment,
+    Format,
+    ColumnWidth,
+    MoveRows,
+    MoveColumns,
+    InsertRow,
+    InsertColumn,
+    JoinColumns,
+    Substitute,
 }
 
 // Executor for actions in the application
@@ -38,6 +55,14 @@ pub trait ActionExecutor {
         &mut self,
         action: &crate::actions::MultiColumnAction,
     ) -> Result<(), anyhow::Error>;
+    fn execute_range_action(
+        &mut self,
+        action: &crate::actions::RangeAction,
+    ) -> Result<(), anyhow::Error>;
+    fn execute_column_width_action(
+        &mut self,
+        action: &crate::actions::ColumnWidthAction,
+    ) -> Result<(), anyhow::Error>;
 }
 
 // Command interface for actions that can be executed and undone
@@ -60,6 +85,65 @@ pub enum ActionCommand {
     Row(crate::actions::RowAction),
     Column(crate::actions::ColumnAction),
     Sheet(crate::actions::SheetAction),
+    RenameSheet(crate::actions::SheetRenameAction),
     MultiRow(crate::actions::MultiRowAction),
     MultiColumn(crate::actions::MultiColumnAction),
+    Range(crate::actions::RangeAction),
+    ColumnWidth(crate::actions::ColumnWidthAction),
+    /// A row inserted at `row`; the inverse of `Row`
+    InsertRow(crate::actions::RowAction),
+    /// A column inserted at `col`; the inverse of `Column`
+    InsertColumn(crate::actions::ColumnAction),
+    /// Actions recorded between `UndoHistory::begin_group` and `end_group`, in order
+    Group(Vec<ActionCommand>),
+}
+
+impl ActionCommand {
+    /// Roughly the bytes the action takes up, mostly in the cells it keeps to undo or redo
+    #[must_use]
+    pub fn estimated_bytes(&self) -> usize {
+        fn cells<'a>(cells: impl IntoIterator<Item = &'a Cell>) -> usize {
+            cells.into_iter().map(Cell::estimated_bytes).sum()
+        }
+        fn placed<'a>(cells: impl IntoIterator<Item = &'a (usize, Cell)>) -> usize {
+            cells
+                .into_iter()
+                .map(|(_, cell)| size_of::<usize>() + cell.estimated_bytes())
+                .sum()
+        }
+
+        let held = match self {
+            Self::Cell(action) => cells([&action.old_value, &action.new_value]),
+            Self::Row(action) | Self::InsertRow(action) => placed(&action.row_data),
+            Self::Column(action) | Self::InsertColumn(action) => placed(&action.column_data),
+            Self::Sheet(action) => action.estimated_bytes(),
+            Self::RenameSheet(action) => action.old_name.len() + action.new_name.len(),
+            Self::MultiRow(action) => placed(action.rows_data.iter().flatten()),
+            Self::MultiColumn(action) => placed(action.columns_data.iter().flatten()),
+            Self::Range(action) => cells(
+                action
+                    .changes
+                    .iter()
+                    .flat_map(|change| [&change.old_value, &change.new_value]),
+            ),
+            Self::ColumnWidth(action) => {
+                action.changes.len() * size_of::<crate::actions::ColumnWidthChange>()
+            }
+            Self::Group(actions) => actions.iter().map(Self::estimated_bytes).sum(),
+        };
+        held + size_of::<Self>()
+    }
+
+    /// Packs the cells of sheets the action keeps when they take up more than `min_bytes`
+    pub fn pack_sheets(&mut self, min_bytes: usize) {
+        match self {
+            Self::Sheet(action) => action.pack(min_bytes),
+            Self::Group(actions) => {
+                for action in actions {
+                    action.pack_sheets(min_bytes);
+                }
+            }
+            _ => {}
+        }
+    }
 }
@@ -0,0 +1,393 @@
+use std::collections::VecDeque;
+use std::io::Write;
+
+use crate::excel::Cell;
+
+/// How many yanks the numbered registers `"0` to `"9` keep
+const REGISTER_COUNT: usize = 10;
+
+/// Where yanked cells are copied to outside the application
+enum SystemClipboard {
+    /// Only the internal copy, as in tests and when no clipboard can be reached
+    None,
+    Native(arboard::Clipboard),
+    /// Written through the terminal with the OSC 52 escape sequence, which works over SSH but
+    /// cannot be read back
+    Osc52,
+}
+
+/// How `p` puts a yank back
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum YankKind {
+    /// Written over the cells from the cursor
+    #[default]
+    Cells,
+    /// Whole rows from `yy`, inserted below the cursor's row
+    Rows,
+    /// Whole columns from `yc`, one row per cell, inserted right of the cursor's column
+    Columns,
+}
+
+/// One yanked block, one row per `Vec`
+#[derive(Clone)]
+pub struct Yank {
+    pub kind: YankKind,
+    pub block: Vec<Vec<Cell>>,
+}
+
+impl YankKind {
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Cells => "cells",
+            Self::Rows => "rows",
+            Self::Columns => "columns",
+        }
+    }
+}
+
+/// Cells yanked with `y` or cut with `d`. With the system clipboard on,
+/// they are also copied there as tab-separated text for other applications, and text copied
+/// elsewhere since is what `p` pastes. Earlier yanks stay in numbered registers
+pub struct Clipboard {
+    /// Yanked blocks, newest first: register `"0` is the last yank, `"1` the one before
+    registers: VecDeque<Yank>,
+    /// The text last written to the system clipboard, to tell a copy made elsewhere from ours
+    written: Option<String>,
+    system: SystemClipboard,
+}
+
+/// What `p` pastes
+pub enum ClipboardContent {
+    /// Cells yanked here, with their formulas, formats and comments
+    Yank(Yank),
+    /// Text copied in another application
+    Text(String),
+}
+
+impl Default for Clipboard {
+    fn default() -> Self {
+        Self {
+            registers: VecDeque::new(),
+            written: None,
+            system: SystemClipboard::None,
+        }
+    }
+}
+
+impl Clipboard {
+    /// A clipboard that also uses the system one: the native clipboard locally, and OSC 52
+    /// over SSH or when the native one is unavailable
+    #[must_use]
+    pub fn system() -> Self {
+        let over_ssh =
+            std::env::var_os("SSH_TTY").is_some() || std::env::var_os("SSH_CONNECTION").is_some();
+        let native = if over_ssh {
+            None
+        } else {
+            arboard::Clipboard::new().ok()
+        };
+        let system = native.map_or(SystemClipboard::Osc52, SystemClipboard::Native);
+        Self {
+            system,
+            ..Self::default()
+        }
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.registers.is_empty()
+    }
+
+    /// The yank in register `"index`, 0 being the last one
+    #[must_use]
+    pub fn register(&self, index: usize) -> Option<&Yank> {
+        self.registers.get(index)
+    }
+
+    /// Yanks, newest first
+    pub fn registers(&self) -> impl Iterator<Item = &Yank> {
+        self.registers.iter()
+    }
+
+    /// Keeps cells for `p`, shifting earlier yanks down the registers, and copies their values
+    /// to the system clipboard
+    pub fn set(&mut self, block: Vec<Vec<Cell>>) {
+        self.push(YankKind::Cells, block);
+    }
+
+    /// Like `set`, for whole rows or columns
+    pub fn push(&mut self, kind: YankKind, block: Vec<Vec<Cell>>) {
+        let text = block_text(&block);
+        self.push_with_text(kind, block, text);
+    }
+
+    /// Like `set`, copying `text` to the system clipboard instead of tab-separated values,
+    /// as `:yank md` does with a Markdown table
+    pub fn set_with_text(&mut self, block: Vec<Vec<Cell>>, text: String) {
+        self.push_with_text(YankKind::Cells, block, text);
+    }
+
+    fn push_with_text(&mut self, kind: YankKind, block: Vec<Vec<Cell>>, text: String) {
+        self.registers.push_front(Yank { kind, block });
+        self.registers.truncate(REGISTER_COUNT);
+
+        let copied = match &mut self.system {
+            SystemClipboard::None => return,
+            SystemClipboard::Native(native) => native.set_text(text.as_str()).is_ok(),
+            SystemClipboard::Osc52 => write_osc52(&text).is_ok(),
+        };
+        self.written = copied.then_some(text);
+    }
+
+    /// Text copied in another application since the last yank, or else the yanked cells
+    pub fn get(&mut self) -> Option<ClipboardContent> {
+        if let SystemClipboard::Native(native) = &mut self.system {
+            if let Ok(text) = native.get_text() {
+                if !text.is_empty() && self.written.as_ref() != Some(&text) {
+                    return Some(ClipboardContent::Text(text));
+                }
+            }
+        }
+        self.registers.front().cloned().map(ClipboardContent::Yank)
+    }
+
+    /// Replaces the last yank, as `yy` does with the cell its first `y` copied
+    pub fn replace_last(&mut self, kind: YankKind, block: Vec<Vec<Cell>>) {
+        self.registers.pop_front();
+        self.push(kind, block);
+    }
+}
+
+/// The block as other applications paste it: tab-separated values, one line per row
+pub(crate) fn block_text(block: &[Vec<Cell>]) -> String {
+    block
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|cell| cell.value.as_str())
+                .collect::<Vec<_>>()
+                .join("\t")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Formats `:yank` copies a block to the system clipboard in
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TextFormat {
+    /// A Markdown table, the first row being its header
+    Markdown,
+    Csv,
+}
+
+impl TextFormat {
+    #[must_use]
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "md" | "markdown" => Some(Self::Markdown),
+            "csv" => Some(Self::Csv),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Markdown => "Markdown",
+            Self::Csv => "CSV",
+        }
+    }
+
+    /// The block's values as text in this format
+    #[must_use]
+    pub fn format(self, block: &[Vec<Cell>]) -> String {
+        let lines: Vec<String> = match self {
+            Self::Markdown => {
+                let width = block.iter().map(Vec::len).max().unwrap_or(0).max(1);
+                let row_line = |row: &Vec<Cell>| {
+                    let values: Vec<String> = (0..width)
+                        .map(|col| {
+                            row.get(col)
+                                .map_or_else(String::new, |cell| markdown_cell_text(&cell.value))
+                        })
+                        .collect();
+                    format!("| {} |", values.join(" | "))
+                };
+                let mut lines: Vec<String> = block.iter().take(1).map(row_line).collect();
+                lines.push(format!("|{}", " --- |".repeat(width)));
+                lines.extend(block.iter().skip(1).map(row_line));
+                lines
+            }
+            Self::Csv => block
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .map(|cell| csv_field(&cell.value))
+                        .collect::<Vec<_>>()
+                        .join(",")
+                })
+                .collect(),
+        };
+        lines.join("\n")
+    }
+}
+
+/// Escapes text for a Markdown table cell, which must stay on one line
+fn markdown_cell_text(value: &str) -> String {
+    value
+        .replace('\r', "")
+        .replace('\n', "<br>")
+        .replace('|', "\\|")
+}
+
+/// Quotes a CSV field when it holds a comma, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Sets the terminal's clipboard with OSC 52, wrapped for tmux so it reaches the outer terminal
+fn write_osc52(text: &str) -> std::io::Result<()> {
+    let sequence = format!("\x1b]52;c;{}\x07", base64(text.as_bytes()));
+    let sequence = if std::env::var_os("TMUX").is_some() {
+        format!("\x1bPtmux;{}\x1b\\", sequence.replace('\x1b', "\x1b\x1b"))
+    } else {
+        sequence
+    };
+    let mut stdout = std::io::stdout();
+    stdout.write_all(sequence.as_bytes())?;
+    stdout.flush()
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (i, &byte)| {
+            group | u32::from(byte) << (16 - 8 * i)
+        });
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(group >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+/// Splits text copied elsewhere into rows of tab-separated cells
+#[must_use]
+pub fn text_block(text: &str) -> Vec<Vec<Cell>> {
+    let text = text.replace("\r\n", "\n").replace('\r', "\n");
+    text.trim_end_matches('\n')
+        .split('\n')
+        .map(|line| {
+            line.split('\t')
+                .map(|value| Cell::new(value.to_string(), value.starts_with('=')))
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{base64, block_text, text_block, Clipboard, ClipboardContent, TextFormat};
+    use crate::app::AppState;
+    use crate::excel::{Cell, Sheet, Workbook};
+    use std::path::PathBuf;
+
+    #[test]
+    fn blocks_become_tab_separated_text_and_back() {
+        let block = vec![
+            vec![
+                Cell::new("Name".to_string(), false),
+                Cell::new("Total".to_string(), false),
+            ],
+            vec![
+                Cell::new("Ann".to_string(), false),
+                Cell::new("12".to_string(), false),
+            ],
+        ];
+        assert_eq!(block_text(&block), "Name\tTotal\nAnn\t12");
+
+        let parsed = text_block("a\tb\r\n=SUM(A1:A2)\n");
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[0][1].value, "b");
+        assert!(parsed[1][0].is_formula);
+
+        let mut clipboard = Clipboard::default();
+        assert!(clipboard.is_empty());
+        clipboard.set(block);
+        assert!(matches!(
+            clipboard.get(),
+            Some(ClipboardContent::Yank(yank)) if yank.block[1][1].value == "12"
+        ));
+    }
+
+    #[test]
+    fn blocks_format_as_markdown_tables_and_csv() {
+        let cell = |value: &str| Cell::new(value.to_string(), false);
+        let block = vec![
+            vec![cell("Name"), cell("Note")],
+            vec![cell("Ann"), cell("a|b")],
+            vec![cell("Bo, Jr."), cell("said \"hi\"\nbye")],
+        ];
+        assert_eq!(
+            TextFormat::Markdown.format(&block),
+            "| Name | Note |\n| --- | --- |\n| Ann | a\\|b |\n| Bo, Jr. | said \"hi\"<br>bye |"
+        );
+        assert_eq!(
+            TextFormat::Csv.format(&block),
+            "Name,Note\nAnn,a|b\n\"Bo, Jr.\",\"said \"\"hi\"\"\nbye\""
+        );
+        assert_eq!(TextFormat::parse("MD"), Some(TextFormat::Markdown));
+        assert_eq!(TextFormat::parse("tsv"), None);
+    }
+
+    #[test]
+    fn osc52_payload_is_base64() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+        assert_eq!(base64("12\t€".as_bytes()), "MTIJ4oKs");
+    }
+
+    #[test]
+    fn yref_and_yformula_copy_the_location_and_formula_text() {
+        let workbook = Workbook::from_sheets_for_test(vec![Sheet::blank("Q1 Sales".to_string())]);
+        let mut app = AppState::new(workbook, PathBuf::from("test.xlsx")).unwrap();
+        app.workbook.set_cell_value(1, 1, "5".to_string()).unwrap();
+        let copied = |app: &AppState| app.clipboard.register(0).unwrap().block[0][0].value.clone();
+
+        app.selected_cell = (42, 3);
+        app.copy_reference();
+        assert_eq!(copied(&app), "'Q1 Sales'!C42");
+        app.selected_cell = (2, 1);
+        app.anchor_selection();
+        app.selected_cell = (4, 2);
+        app.copy_reference();
+        assert_eq!(copied(&app), "'Q1 Sales'!A2:B4");
+        assert!(app.selection_anchor.is_none());
+
+        app.selected_cell = (1, 1);
+        app.copy_formula();
+        assert_eq!(
+            app.notification_messages.last().unwrap().text,
+            "A1 holds no formula"
+        );
+        app.workbook
+            .get_current_sheet_mut()
+            .data
+            .update_cell(1, 1, |cell| cell.formula = Some("=SUM(B1:B9)".to_string()));
+        app.copy_formula();
+        assert_eq!(copied(&app), "=SUM(B1:B9)");
+        assert!(app.clipboard.register(0).unwrap().block[0][0].is_formula);
+    }
+}
@@ -0,0 +1,211 @@
+use crate::app::AppState;
+use crate::excel::{parse_cf_range, ConditionalFormat};
+
+const CF_USAGE: &str = "Usage: :cf <range> <condition> <color>, like :cf C \">100\" red";
+
+/// Excel's standard colors, which `:cf` takes by name
+const NAMED_COLORS: [(&str, u32); 10] = [
+    ("darkred", 0xC00000),
+    ("red", 0xFF0000),
+    ("orange", 0xFFC000),
+    ("yellow", 0xFFFF00),
+    ("lightgreen", 0x92D050),
+    ("green", 0x00B050),
+    ("lightblue", 0x00B0F0),
+    ("blue", 0x0070C0),
+    ("darkblue", 0x002060),
+    ("purple", 0x7030A0),
+];
+
+impl AppState<'_> {
+    /// Handles `:cf <range> <condition> <color>`: fills the cells of the range whose value
+    /// meets the condition, such as `:cf C ">100" red`. `:cf` alone lists the sheet's rules
+    pub fn handle_cf_command(&mut self, args: &str) {
+        let args = args.trim();
+        if args.is_empty() {
+            self.show_conditional_formats();
+            return;
+        }
+
+        let Some(rule) = parse_cf_args(args) else {
+            self.add_warning(CF_USAGE.to_string());
+            return;
+        };
+        let message = format!(
+            "Cells of {} {} are filled {}",
+            rule.range_label(),
+            rule.condition(),
+            describe_color(rule.fill)
+        );
+        self.workbook.add_conditional_format(rule);
+        self.add_notification(message);
+    }
+
+    /// Handles `:nocf [n]`: removes the `n`th rule `:cf` lists, or all of the sheet's rules
+    pub fn remove_conditional_formats(&mut self, arg: &str) {
+        let arg = arg.trim();
+        let index = if arg.is_empty() {
+            None
+        } else {
+            match arg.parse::<usize>() {
+                Ok(number) if number >= 1 => Some(number - 1),
+                _ => {
+                    self.add_warning("Usage: :nocf [n]".to_string());
+                    return;
+                }
+            }
+        };
+
+        let message = match (self.workbook.remove_conditional_formats(index), index) {
+            (0, None) => "No conditional formats".to_string(),
+            (0, Some(index)) => format!("No conditional format {}", index + 1),
+            (_, Some(index)) => format!("Removed conditional format {}", index + 1),
+            (1, None) => "Removed 1 conditional format".to_string(),
+            (removed, None) => format!("Removed {removed} conditional formats"),
+        };
+        self.add_notification(message);
+    }
+
+    fn show_conditional_formats(&mut self) {
+        let rules = &self.workbook.get_current_sheet().conditional_formats;
+        if rules.is_empty() {
+            self.add_notification(format!("No conditional formats. {CF_USAGE}"));
+            return;
+        }
+
+        let labels: Vec<(String, String)> = rules
+            .iter()
+            .map(|rule| (rule.range_label(), rule.condition()))
+            .collect();
+        let range_width = labels
+            .iter()
+            .map(|(range, _)| range.len())
+            .max()
+            .unwrap_or(0);
+        let condition_width = labels
+            .iter()
+            .map(|(_, condition)| condition.chars().count())
+            .max()
+            .unwrap_or(0);
+        let lines = rules
+            .iter()
+            .zip(&labels)
+            .enumerate()
+            .map(|(index, (rule, (range, condition)))| {
+                let mut colors = Vec::new();
+                if rule.fill.is_some() {
+                    colors.push(format!("fill {}", describe_color(rule.fill)));
+                }
+                if rule.font.is_some() {
+                    colors.push(format!("text {}", describe_color(rule.font)));
+                }
+                format!(
+                    "{:>2}  {range:<range_width$}  {condition:<condition_width$}  {}",
+                    index + 1,
+                    colors.join(", ")
+                )
+            })
+            .collect();
+        let title = format!("Conditional formats: {}", rules.len());
+        self.open_pager(title, lines);
+    }
+}
+
+/// Reads `<range> <condition> <color>`. The condition may be quoted and hold spaces
+fn parse_cf_args(args: &str) -> Option<ConditionalFormat> {
+    let (range, rest) = args.split_once(char::is_whitespace)?;
+    let (condition, color) = rest.trim().rsplit_once(char::is_whitespace)?;
+    let condition = condition.trim();
+    let condition = condition
+        .strip_prefix('"')
+        .and_then(|inner| inner.strip_suffix('"'))
+        .unwrap_or(condition);
+
+    let (start, end) = parse_cf_range(range)?;
+    let (operator, value) = ConditionalFormat::parse_condition(condition)?;
+    Some(ConditionalFormat {
+        start,
+        end,
+        operator,
+        value,
+        fill: Some(parse_color(color)?),
+        font: None,
+    })
+}
+
+/// A color name from [`NAMED_COLORS`] or `#RRGGBB`
+fn parse_color(color: &str) -> Option<u32> {
+    if let Some(hex) = color.strip_prefix('#') {
+        return (hex.len() == 6)
+            .then(|| u32::from_str_radix(hex, 16).ok())
+            .flatten();
+    }
+    NAMED_COLORS
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(color))
+        .map(|&(_, rgb)| rgb)
+}
+
+/// The name `:cf` knows a color by, else `#RRGGBB`
+fn describe_color(color: Option<u32>) -> String {
+    let Some(rgb) = color else {
+        return "none".to_string();
+    };
+    NAMED_COLORS
+        .iter()
+        .find(|&&(_, named)| named == rgb)
+        .map_or_else(|| format!("#{rgb:06X}"), |(name, _)| (*name).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::app::{AppState, InputMode};
+    use crate::excel::{CfOperator, Sheet, Workbook, EXCEL_MAX_ROWS};
+    use std::path::PathBuf;
+
+    #[test]
+    fn cf_adds_lists_and_removes_rules_of_the_current_sheet() {
+        let workbook = Workbook::from_sheets_for_test(vec![Sheet::blank("Data".to_string())]);
+        let mut app = AppState::new(workbook, PathBuf::from("test.xlsx")).unwrap();
+        let run = |app: &mut AppState, command: &str| {
+            app.input_buffer = command.to_string();
+            app.execute_command();
+            app.notification_messages.last().unwrap().text.clone()
+        };
+
+        assert_eq!(
+            run(&mut app, "cf C >100"),
+            "Usage: :cf <range> <condition> <color>, like :cf C \">100\" red"
+        );
+        assert!(run(&mut app, "cf C >100 mauve").starts_with("Usage: :cf"));
+        assert_eq!(
+            run(&mut app, "cf C \">100\" red"),
+            "Cells of C >100 are filled red"
+        );
+        assert_eq!(
+            run(&mut app, "cf B2:B9 \"= on hold\" #FFC7CE"),
+            "Cells of B2:B9 =on hold are filled #FFC7CE"
+        );
+        let rules = &app.workbook.get_current_sheet().conditional_formats;
+        assert_eq!(rules[0].start, (1, 3));
+        assert_eq!(rules[0].end, (EXCEL_MAX_ROWS, 3));
+        assert_eq!(rules[0].operator, CfOperator::Greater);
+        assert_eq!(rules[1].value, "on hold");
+        assert_eq!(rules[1].fill, Some(0xFFC7CE));
+        assert!(app.workbook.is_modified());
+
+        run(&mut app, "cf");
+        assert!(matches!(app.input_mode, InputMode::Pager));
+        let pager = app.pager.as_ref().unwrap();
+        assert_eq!(pager.title, "Conditional formats: 2");
+        assert_eq!(pager.lines[0], " 1  C      >100      fill red");
+        assert_eq!(pager.lines[1], " 2  B2:B9  =on hold  fill #FFC7CE");
+        app.input_mode = InputMode::Normal;
+
+        assert_eq!(run(&mut app, "nocf 3"), "No conditional format 3");
+        assert_eq!(run(&mut app, "nocf 1"), "Removed conditional format 1");
+        assert_eq!(run(&mut app, "nocf"), "Removed 1 conditional format");
+        assert_eq!(run(&mut app, "nocf"), "No conditional formats");
+        assert_eq!(run(&mut app, "nocf x"), "Usage: :nocf [n]");
+    }
+}
@@ -1,15 +1,22 @@
 use crate::actions::{ActionCommand, ActionType, CellAction};
-use crate::app::AppState;
 use crate::app::InputMode;
+use crate::app::{AppState, PasteOptions, TextFormat, YankKind};
 use crate::app::{Transition, VimMode, VimState};
+use crate::excel::{
+    formula_references, quote_sheet_name, Cell, FormulaReference, EXCEL_MAX_COLS, EXCEL_MAX_ROWS,
+};
+use crate::utils::{cell_reference, index_to_col_name};
 use anyhow::Result;
 use ratatui::style::{Modifier, Style};
 use tui_textarea::Input;
 
 impl AppState<'_> {
     pub fn start_editing(&mut self) {
+        if self.refuse_locked_range(self.selected_cell, self.selected_cell) {
+            return;
+        }
         self.input_mode = InputMode::Editing;
-        let content = self.get_cell_content(self.selected_cell.0, self.selected_cell.1);
+        let content = self.raw_cell_content(self.selected_cell.0, self.selected_cell.1);
         self.input_buffer.clone_from(&content);
 
         // Initialize TextArea with content and settings
@@ -23,6 +30,30 @@ impl AppState<'_> {
         self.vim_state = Some(VimState::new(VimMode::Normal));
     }
 
+    /// References on the current sheet made by the formula being edited, in the order they
+    /// appear in the text
+    #[must_use]
+    pub fn editing_formula_references(&self) -> Vec<FormulaReference> {
+        if !matches!(self.input_mode, InputMode::Editing) {
+            return Vec::new();
+        }
+        let content = self.text_area.lines().join("\n");
+        if !content.trim_start().starts_with('=') {
+            return Vec::new();
+        }
+
+        let sheet_name = self.workbook.get_current_sheet_name();
+        formula_references(&content)
+            .into_iter()
+            .filter(|reference| {
+                reference
+                    .sheet
+                    .as_ref()
+                    .is_none_or(|sheet| sheet.eq_ignore_ascii_case(&sheet_name))
+            })
+            .collect()
+    }
+
     pub fn handle_vim_input(&mut self, input: Input) -> Result<()> {
         if let Some(vim_state) = &mut self.vim_state {
             match vim_state.transition(input, &mut self.text_area) {
@@ -54,7 +85,12 @@ impl AppState<'_> {
             let sheet_index = self.workbook.get_current_sheet_index();
             let sheet_name = self.workbook.get_current_sheet_name();
 
-            let old_cell = self.workbook.get_current_sheet().data[row][col].clone();
+            let old_cell = self
+                .workbook
+                .get_current_sheet()
+                .data
+                .cell(row, col)
+                .clone();
 
             let mut new_cell = old_cell.clone();
             new_cell.value.clone_from(&content);
@@ -80,26 +116,207 @@ impl AppState<'_> {
         Ok(())
     }
 
+    /// Adds, replaces or, with `None`, removes the note on the current cell
+    pub fn set_comment(&mut self, comment: Option<String>) {
+        let (row, col) = self.selected_cell;
+        if self.refuse_locked_range(self.selected_cell, self.selected_cell) {
+            return;
+        }
+        self.workbook.ensure_cell_exists(row, col);
+        self.ensure_column_widths();
+
+        let cell_ref = cell_reference(self.selected_cell);
+        let old_cell = self
+            .workbook
+            .get_current_sheet()
+            .data
+            .cell(row, col)
+            .clone();
+        if old_cell.comment == comment {
+            if comment.is_none() {
+                self.add_notification(format!("No comment on {cell_ref}"));
+            }
+            return;
+        }
+
+        let mut new_cell = old_cell.clone();
+        new_cell.comment.clone_from(&comment);
+        let message = match (&old_cell.comment, &comment) {
+            (_, None) => format!("Comment removed from {cell_ref}"),
+            (None, Some(_)) => format!("Comment added to {cell_ref}"),
+            (Some(_), Some(_)) => format!("Comment updated on {cell_ref}"),
+        };
+
+        let cell_action = CellAction::new(
+            self.workbook.get_current_sheet_index(),
+            self.workbook.get_current_sheet_name(),
+            row,
+            col,
+            old_cell,
+            new_cell,
+            ActionType::Comment,
+        );
+        self.undo_history.push(ActionCommand::Cell(cell_action));
+
+        self.workbook.set_cell_comment(row, col, comment);
+        self.add_notification(message);
+    }
+
+    /// Copies the visual selection as a block, or the selected cell when nothing is selected
     pub fn copy_cell(&mut self) {
-        let content = self.get_cell_content_mut(self.selected_cell.0, self.selected_cell.1);
-        self.clipboard = Some(content);
+        if let Some(((start_row, start_col), (end_row, end_col))) = self.selection_range() {
+            let block = (start_row..=end_row)
+                .map(|row| {
+                    (start_col..=end_col)
+                        .map(|col| self.cell_at(row, col))
+                        .collect()
+                })
+                .collect();
+            self.clipboard.set(block);
+            self.clear_selection();
+            self.add_notification(format!(
+                "Copied {}x{} block {}:{}",
+                end_row - start_row + 1,
+                end_col - start_col + 1,
+                cell_reference((start_row, start_col)),
+                cell_reference((end_row, end_col))
+            ));
+            return;
+        }
+
+        let (row, col) = self.selected_cell;
+        let cell = self.cell_at(row, col);
+        self.clipboard.set(vec![vec![cell]]);
         self.add_notification("Cell content copied".to_string());
     }
 
+    /// Yanks `count` whole rows from the cursor's, as `yy` does, for `p` to insert
+    pub fn yank_rows(&mut self, count: usize) {
+        let sheet = self.workbook.get_current_sheet();
+        let first = self.selected_cell.0;
+        let last = (first + count.max(1) - 1).min(EXCEL_MAX_ROWS);
+        let width = sheet.max_cols.max(1);
+        let block = (first..=last)
+            .map(|row| (1..=width).map(|col| self.cell_at(row, col)).collect())
+            .collect();
+        self.clipboard.replace_last(YankKind::Rows, block);
+
+        let message = if last == first {
+            format!("Yanked row {first}")
+        } else {
+            format!("Yanked rows {first}-{last}")
+        };
+        self.add_notification(message);
+    }
+
+    /// Yanks `count` whole columns from the cursor's, as `yc` does, for `p` to insert
+    pub fn yank_columns(&mut self, count: usize) {
+        let sheet = self.workbook.get_current_sheet();
+        let first = self.selected_cell.1;
+        let last = (first + count.max(1) - 1).min(EXCEL_MAX_COLS);
+        let height = sheet.max_rows.max(1);
+        let block = (1..=height)
+            .map(|row| (first..=last).map(|col| self.cell_at(row, col)).collect())
+            .collect();
+        self.clipboard.replace_last(YankKind::Columns, block);
+
+        let message = if last == first {
+            format!("Yanked column {}", index_to_col_name(first))
+        } else {
+            format!(
+                "Yanked columns {}-{}",
+                index_to_col_name(first),
+                index_to_col_name(last)
+            )
+        };
+        self.add_notification(message);
+    }
+
+    /// Copies the location of the selection or the selected cell, like `Sheet1!C42`, for
+    /// pasting into documents or other formulas
+    pub fn copy_reference(&mut self) {
+        let (start, end) = self
+            .selection_range()
+            .unwrap_or((self.selected_cell, self.selected_cell));
+        self.clear_selection();
+        let mut reference = format!(
+            "{}!{}",
+            quote_sheet_name(&self.workbook.get_current_sheet_name()),
+            cell_reference(start)
+        );
+        if end != start {
+            reference.push_str(&format!(":{}", cell_reference(end)));
+        }
+
+        self.clipboard
+            .set(vec![vec![Cell::new(reference.clone(), false)]]);
+        self.add_notification(format!("Copied reference {reference}"));
+    }
+
+    /// Handles `:yank md` and `:yank csv`: copies the selection, or the selected cell, to the
+    /// system clipboard as a Markdown table or CSV. `p` still pastes the cells themselves
+    pub fn yank_as(&mut self, format: &str) {
+        let Some(format) = TextFormat::parse(format.trim()) else {
+            self.add_warning("Usage: :yank md|csv".to_string());
+            return;
+        };
+        let (start, end) = self
+            .selection_range()
+            .unwrap_or((self.selected_cell, self.selected_cell));
+        self.clear_selection();
+
+        let block: Vec<Vec<Cell>> = (start.0..=end.0)
+            .map(|row| {
+                (start.1..=end.1)
+                    .map(|col| self.cell_at(row, col))
+                    .collect()
+            })
+            .collect();
+        let text = format.format(&block);
+        self.clipboard.set_with_text(block, text);
+        self.add_notification(format!(
+            "Copied {}x{} block {}:{} as {}",
+            end.0 - start.0 + 1,
+            end.1 - start.1 + 1,
+            cell_reference(start),
+            cell_reference(end),
+            format.name()
+        ));
+    }
+
+    /// Copies the selected cell's formula text rather than its value
+    pub fn copy_formula(&mut self) {
+        let (row, col) = self.selected_cell;
+        let Some(formula) = self.cell_formula(row, col) else {
+            self.add_notification(format!("{} holds no formula", cell_reference((row, col))));
+            return;
+        };
+
+        self.clipboard
+            .set(vec![vec![Cell::new(formula.clone(), true)]]);
+        self.add_notification(format!("Copied formula {formula}"));
+    }
+
     pub fn cut_cell(&mut self) -> Result<()> {
         let (row, col) = self.selected_cell;
+        if self.refuse_locked_range(self.selected_cell, self.selected_cell) {
+            return Ok(());
+        }
 
         self.workbook.ensure_cell_exists(row, col);
 
         self.ensure_column_widths();
 
-        let content = self.get_cell_content(row, col);
-        self.clipboard = Some(content);
-
         let sheet_index = self.workbook.get_current_sheet_index();
         let sheet_name = self.workbook.get_current_sheet_name();
 
-        let old_cell = self.workbook.get_current_sheet().data[row][col].clone();
+        let old_cell = self
+            .workbook
+            .get_current_sheet()
+            .data
+            .cell(row, col)
+            .clone();
+        self.clipboard.set(vec![vec![old_cell.clone()]]);
 
         let mut new_cell = old_cell.clone();
         new_cell.value = String::new();
@@ -122,36 +339,45 @@ impl AppState<'_> {
     }
 
     pub fn paste_cell(&mut self) -> Result<()> {
-        if let Some(content) = self.clipboard.clone() {
-            let (row, col) = self.selected_cell;
+        self.paste_clipboard(PasteOptions::default());
+        Ok(())
+    }
 
-            self.workbook.ensure_cell_exists(row, col);
-            self.ensure_column_widths();
+    pub(crate) fn paste_into_current_cell(&mut self, content: String) -> Result<()> {
+        let (row, col) = self.selected_cell;
+        if self.refuse_locked_range(self.selected_cell, self.selected_cell) {
+            return Ok(());
+        }
 
-            let sheet_index = self.workbook.get_current_sheet_index();
-            let sheet_name = self.workbook.get_current_sheet_name();
+        self.workbook.ensure_cell_exists(row, col);
+        self.ensure_column_widths();
 
-            let old_cell = self.workbook.get_current_sheet().data[row][col].clone();
+        let sheet_index = self.workbook.get_current_sheet_index();
+        let sheet_name = self.workbook.get_current_sheet_name();
 
-            let mut new_cell = old_cell.clone();
-            new_cell.value.clone_from(&content);
+        let old_cell = self
+            .workbook
+            .get_current_sheet()
+            .data
+            .cell(row, col)
+            .clone();
 
-            let cell_action = CellAction::new(
-                sheet_index,
-                sheet_name,
-                row,
-                col,
-                old_cell,
-                new_cell,
-                ActionType::Paste,
-            );
+        let mut new_cell = old_cell.clone();
+        new_cell.value.clone_from(&content);
 
-            self.undo_history.push(ActionCommand::Cell(cell_action));
-            self.workbook.set_cell_value(row, col, content)?;
-            self.add_notification("Content pasted".to_string());
-        } else {
-            self.add_notification("Clipboard is empty".to_string());
-        }
+        let cell_action = CellAction::new(
+            sheet_index,
+            sheet_name,
+            row,
+            col,
+            old_cell,
+            new_cell,
+            ActionType::Paste,
+        );
+
+        self.undo_history.push(ActionCommand::Cell(cell_action));
+        self.workbook.set_cell_value(row, col, content)?;
+        self.add_notification("Content pasted".to_string());
         Ok(())
     }
 }
@@ -0,0 +1,243 @@
+use crate::app::{AppState, ResultItem, SearchScope};
+use crate::excel::{Cell, CellType, DataTypeInfo};
+
+const FIND_USAGE: &str =
+    "Usage: :find formulas|empty|errors|text|dates|bools|numbers[>|>=|<|<=|=|!=N]";
+
+const EXCEL_ERROR_VALUES: &[&str] = &[
+    "#DIV/0!", "#N/A", "#NAME?", "#NULL!", "#NUM!", "#REF!", "#VALUE!", "#SPILL!", "#CALC!",
+];
+
+/// Whether a cell holds an Excel error such as `#DIV/0!`
+pub(crate) fn is_error_cell(cell: &Cell) -> bool {
+    cell.original_type == Some(DataTypeInfo::Error)
+        || EXCEL_ERROR_VALUES.contains(&cell.value.trim())
+}
+
+/// Comparison in `:find numbers>1000`
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Comparison {
+    Greater,
+    GreaterOrEqual,
+    Less,
+    LessOrEqual,
+    Equal,
+    NotEqual,
+}
+
+impl Comparison {
+    fn holds(self, value: f64, limit: f64) -> bool {
+        match self {
+            Self::Greater => value > limit,
+            Self::GreaterOrEqual => value >= limit,
+            Self::Less => value < limit,
+            Self::LessOrEqual => value <= limit,
+            Self::Equal => value == limit,
+            Self::NotEqual => value != limit,
+        }
+    }
+}
+
+/// What `:find` looks for: cells of one kind rather than cells containing some text
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum CellKind {
+    Formulas,
+    Empty,
+    Errors,
+    Text,
+    Dates,
+    Booleans,
+    Numbers(Option<(Comparison, f64)>),
+}
+
+impl CellKind {
+    fn parse(query: &str) -> Option<Self> {
+        let query: String = query.split_whitespace().collect();
+        let kind = match query.as_str() {
+            "formulas" | "formula" => Self::Formulas,
+            "empty" | "blank" => Self::Empty,
+            "errors" | "error" => Self::Errors,
+            "text" => Self::Text,
+            "dates" | "date" => Self::Dates,
+            "bools" | "booleans" => Self::Booleans,
+            "numbers" | "number" => Self::Numbers(None),
+            _ => {
+                let condition = query
+                    .strip_prefix("numbers")
+                    .or_else(|| query.strip_prefix("number"))?;
+                let (comparison, limit) = [
+                    (">=", Comparison::GreaterOrEqual),
+                    ("<=", Comparison::LessOrEqual),
+                    ("!=", Comparison::NotEqual),
+                    ("<>", Comparison::NotEqual),
+                    (">", Comparison::Greater),
+                    ("<", Comparison::Less),
+                    ("=", Comparison::Equal),
+                ]
+                .into_iter()
+                .find_map(|(operator, comparison)| {
+                    condition
+                        .strip_prefix(operator)
+                        .map(|limit| (comparison, limit))
+                })?;
+                Self::Numbers(Some((comparison, limit.parse().ok()?)))
+            }
+        };
+        Some(kind)
+    }
+
+    fn matches(self, cell: Option<&Cell>) -> bool {
+        let Some(cell) = cell.filter(|cell| !cell.value.is_empty()) else {
+            return self == Self::Empty;
+        };
+
+        match self {
+            Self::Formulas => cell.is_formula || cell.formula.is_some(),
+            Self::Empty => false,
+            Self::Errors => is_error_cell(cell),
+            Self::Text => {
+                cell.cell_type == CellType::Text && !cell.is_formula && !is_error_cell(cell)
+            }
+            Self::Dates => {
+                cell.cell_type == CellType::Date
+                    || matches!(
+                        cell.original_type,
+                        Some(DataTypeInfo::DateTime(_) | DataTypeInfo::DateTimeIso(_))
+                    )
+            }
+            Self::Booleans => {
+                cell.cell_type == CellType::Boolean
+                    || matches!(cell.original_type, Some(DataTypeInfo::Bool(_)))
+            }
+            Self::Numbers(condition) => match cell.value.trim().parse::<f64>() {
+                Ok(value) => {
+                    condition.is_none_or(|(comparison, limit)| comparison.holds(value, limit))
+                }
+                Err(_) => false,
+            },
+        }
+    }
+}
+
+impl AppState<'_> {
+    /// Handles `:find <kind>`: highlights the cells of that kind like search matches, so `n`/`N`
+    /// step through them, and lists them in the results panel
+    pub fn find_cells(&mut self, query: &str) {
+        let query = query.trim();
+        let Some(kind) = CellKind::parse(query) else {
+            self.add_warning(FIND_USAGE.to_string());
+            return;
+        };
+
+        self.search_bounds = self.search_scope_bounds();
+        if self.search_scope == SearchScope::Selection {
+            self.clear_selection();
+        }
+        let sheet = self.workbook.get_current_sheet();
+        let ((first_row, first_col), (last_row, last_col)) = self
+            .search_bounds
+            .unwrap_or(((1, 1), (sheet.max_rows, sheet.max_cols)));
+        let mut found = Vec::new();
+        for row in first_row..=last_row.min(sheet.max_rows) {
+            for col in first_col..=last_col.min(sheet.max_cols) {
+                let cell = sheet.data.get(row, col);
+                if kind.matches(cell) {
+                    found.push((row, col));
+                }
+            }
+        }
+
+        // The cells are no text query, so n/N must not search for one when they run out
+        self.search_query.clear();
+        self.search_results = found;
+        self.current_search_idx = None;
+        self.search_direction = true;
+
+        let sheet_name = self.workbook.get_current_sheet_name();
+        let items = self
+            .search_results
+            .iter()
+            .map(|&(row, col)| ResultItem {
+                sheet_name: sheet_name.clone(),
+                cell: (row, col),
+                text: self.get_cell_content(row, col),
+            })
+            .collect();
+        self.set_results(format!("Find: {query}"), items, false);
+
+        if self.search_results.is_empty() {
+            self.add_notification(format!("No cells found for :find {query}"));
+            return;
+        }
+        self.jump_to_next_search_result();
+        let count = self.search_results.len();
+        let noun = if count == 1 { "cell" } else { "cells" };
+        self.add_notification(format!("{count} {noun} found for :find {query}"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::app::AppState;
+    use crate::excel::{Cell, CellType, DataTypeInfo, Sheet, Workbook};
+    use std::path::PathBuf;
+
+    #[test]
+    fn find_matches_cells_by_kind_and_value() {
+        let mut sheet = Sheet::blank("Data".to_string());
+        sheet.data.set(1, 1, Cell::new("Region".to_string(), false));
+        sheet.data.set(1, 2, Cell::new("1500".to_string(), false));
+        sheet.data.set(1, 3, Cell::new("250.5".to_string(), false));
+        sheet
+            .data
+            .set(2, 1, Cell::new("2024-03-01".to_string(), false));
+        sheet.data.set(
+            2,
+            2,
+            Cell::new_with_type(
+                "#DIV/0!".to_string(),
+                true,
+                CellType::Text,
+                Some(DataTypeInfo::Error),
+            ),
+        );
+        sheet
+            .data
+            .update_cell(2, 2, |cell| cell.formula = Some("=B1/0".to_string()));
+        sheet.data.set(3, 1, Cell::new("true".to_string(), false));
+        sheet.max_rows = 3;
+        sheet.max_cols = 3;
+        let workbook = Workbook::from_sheets_for_test(vec![sheet]);
+        let mut app = AppState::new(workbook, PathBuf::from("test.xlsx")).unwrap();
+
+        let mut find = |query: &str| {
+            app.find_cells(query);
+            app.search_results.clone()
+        };
+        assert_eq!(find("formulas"), vec![(2, 2)]);
+        assert_eq!(find("errors"), vec![(2, 2)]);
+        assert_eq!(find("numbers"), vec![(1, 2), (1, 3)]);
+        assert_eq!(find("numbers > 1000"), vec![(1, 2)]);
+        assert_eq!(find("numbers<=250.5"), vec![(1, 3)]);
+        assert_eq!(find("empty"), vec![(2, 3), (3, 2), (3, 3)]);
+        assert_eq!(find("text"), vec![(1, 1)]);
+        assert_eq!(find("dates"), vec![(2, 1)]);
+        assert_eq!(find("bools"), vec![(3, 1)]);
+
+        app.find_cells("numbers>1000");
+        assert_eq!(app.selected_cell, (1, 2));
+        assert_eq!(
+            app.notification_messages.last().unwrap().text,
+            "1 cell found for :find numbers>1000"
+        );
+        assert_eq!(app.results.as_ref().unwrap().title, "Find: numbers>1000");
+
+        app.find_cells("numbers>abc");
+        assert!(app
+            .notification_messages
+            .last()
+            .unwrap()
+            .text
+            .starts_with("Usage: :find"));
+    }
+}
@@ -0,0 +1,177 @@
+use crate::actions::{ActionType, RangeCellChange};
+use crate::app::AppState;
+use crate::excel::NumberFormat;
+use crate::utils::{cell_reference, index_to_col_name};
+
+impl AppState<'_> {
+    /// Sets or, with `None`, clears the display format of the visual selection or the current
+    /// cell. Undone in one step.
+    pub fn set_cell_number_format(&mut self, format: Option<NumberFormat>) {
+        let ((start_row, start_col), (end_row, end_col)) = self
+            .selection_range()
+            .unwrap_or((self.selected_cell, self.selected_cell));
+        self.clear_selection();
+        if self.refuse_locked_range((start_row, start_col), (end_row, end_col)) {
+            return;
+        }
+
+        let mut changes = Vec::new();
+        for row in start_row..=end_row {
+            for col in start_col..=end_col {
+                let old_value = self.cell_at(row, col);
+                if old_value.number_format == format {
+                    continue;
+                }
+                let mut new_value = old_value.clone();
+                new_value.number_format.clone_from(&format);
+                changes.push(RangeCellChange {
+                    row,
+                    col,
+                    old_value,
+                    new_value,
+                });
+            }
+        }
+
+        let target = if (start_row, start_col) == (end_row, end_col) {
+            cell_reference((start_row, start_col))
+        } else {
+            format!(
+                "{}:{}",
+                cell_reference((start_row, start_col)),
+                cell_reference((end_row, end_col))
+            )
+        };
+        self.commit_range_changes(changes, ActionType::Format);
+        self.add_notification(match &format {
+            Some(format) => format!("Formatted {target} as {format}"),
+            None => format!("Cleared format of {target}"),
+        });
+    }
+
+    /// Sets or clears the display format of the selected columns, or the current column
+    pub fn set_column_number_format(&mut self, format: Option<NumberFormat>) {
+        let (start_col, end_col) = self.selection_range().map_or(
+            (self.selected_cell.1, self.selected_cell.1),
+            |(start, end)| (start.1, end.1),
+        );
+        self.clear_selection();
+        if self.refuse_on_protected_sheet("format columns") {
+            return;
+        }
+
+        for col in start_col..=end_col {
+            self.workbook.set_column_number_format(col, format.clone());
+        }
+
+        let columns = if start_col == end_col {
+            format!("Column {}", index_to_col_name(start_col))
+        } else {
+            format!(
+                "Columns {}:{}",
+                index_to_col_name(start_col),
+                index_to_col_name(end_col)
+            )
+        };
+        self.add_notification(match &format {
+            Some(format) => format!("{columns} formatted as {format}"),
+            None => format!("{columns} format cleared"),
+        });
+    }
+
+    /// Reports the display format in effect for the current cell
+    pub fn show_number_format(&mut self) {
+        let (row, col) = self.selected_cell;
+        let sheet = self.workbook.get_current_sheet();
+        let cell_format = sheet.data.cell(row, col).number_format.as_ref();
+        let message = match (cell_format, sheet.column_formats.get(&col)) {
+            (Some(format), _) => format!("Format of {}: {format}", cell_reference((row, col))),
+            (None, Some(format)) => format!(
+                "Format of {}: {format} (column {})",
+                cell_reference((row, col)),
+                index_to_col_name(col)
+            ),
+            (None, None) => format!("No format on {}", cell_reference((row, col))),
+        };
+        self.add_notification(message);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::app::AppState;
+    use crate::excel::{Cell, Sheet, Workbook};
+    use std::path::PathBuf;
+
+    fn app_with_numbers() -> AppState<'static> {
+        let mut sheet = Sheet::blank("Data".to_string());
+        sheet.data.set(1, 1, Cell::new("1234.5".to_string(), false));
+        sheet.data.set(2, 1, Cell::new("0.25".to_string(), false));
+        sheet.data.set(1, 2, Cell::new("label".to_string(), false));
+        sheet.max_rows = 2;
+        sheet.max_cols = 2;
+        AppState::new(
+            Workbook::from_sheets_for_test(vec![sheet]),
+            PathBuf::from("test.xlsx"),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn cell_formats_change_display_only_and_undo_in_one_step() {
+        let mut app = app_with_numbers();
+        app.selected_cell = (1, 1);
+
+        app.input_buffer = "fmt currency € 2".to_string();
+        app.execute_command();
+        assert_eq!(app.get_cell_content(1, 1), "€1,234.50");
+        assert_eq!(app.raw_cell_content(1, 1), "1234.5");
+        assert!(app.workbook.is_modified());
+
+        app.start_editing();
+        assert_eq!(app.text_area.lines().join(""), "1234.5");
+        app.input_mode = crate::app::InputMode::Normal;
+
+        app.undo().unwrap();
+        assert_eq!(app.get_cell_content(1, 1), "1234.5");
+    }
+
+    #[test]
+    fn column_formats_apply_to_numbers_and_yield_to_cell_formats() {
+        let mut app = app_with_numbers();
+        app.selected_cell = (2, 1);
+
+        app.input_buffer = "fmt col percent 1".to_string();
+        app.execute_command();
+        assert_eq!(app.get_cell_content(2, 1), "25.0%");
+
+        app.input_buffer = "fmt fixed 3".to_string();
+        app.execute_command();
+        assert_eq!(app.get_cell_content(2, 1), "0.250");
+        assert_eq!(app.get_cell_content(1, 1), "123450.0%");
+
+        app.input_buffer = "fmt col off".to_string();
+        app.execute_command();
+        assert_eq!(app.get_cell_content(1, 1), "1234.5");
+
+        app.input_buffer = "fmt col thousands".to_string();
+        app.selected_cell = (1, 2);
+        app.execute_command();
+        assert_eq!(app.get_cell_content(1, 2), "label");
+        app.workbook.delete_column(1).unwrap();
+        assert!(app
+            .workbook
+            .get_current_sheet()
+            .column_formats
+            .contains_key(&1));
+
+        app.input_buffer = "fmt scientific".to_string();
+        app.execute_command();
+        assert!(app
+            .notification_messages
+            .last()
+            .unwrap()
+            .text
+            .starts_with("Unknown format: scientific"));
+    }
+}
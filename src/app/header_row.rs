@@ -0,0 +1,135 @@
+use crate::app::AppState;
+use crate::excel::EXCEL_MAX_ROWS;
+
+const HEADER_ROW_USAGE: &str = "Usage: :headerrow <n>";
+
+impl AppState<'_> {
+    /// Handles `:headerrow <n>`: makes row `n` the current sheet's header. It stays in view
+    /// below any frozen rows while scrolling, its values label the column letters, and
+    /// `:headers` and `:col` read it
+    pub fn set_header_row(&mut self, arg: &str) {
+        let row = match arg.trim().parse::<usize>() {
+            Ok(row) if (1..=EXCEL_MAX_ROWS).contains(&row) => row,
+            _ => {
+                self.add_warning(HEADER_ROW_USAGE.to_string());
+                return;
+            }
+        };
+
+        self.sheet_header_rows
+            .insert(self.workbook.get_current_sheet_name(), row);
+        self.handle_scrolling();
+        self.add_notification(format!(
+            "Row {row} is the header row; :noheaderrow clears it"
+        ));
+    }
+
+    /// Handles `:noheaderrow`: the current sheet goes back to having no header row of its own
+    pub fn clear_header_row(&mut self) {
+        let sheet_name = self.workbook.get_current_sheet_name();
+        if self.sheet_header_rows.remove(&sheet_name).is_none() {
+            self.add_notification("No header row".to_string());
+            return;
+        }
+        self.handle_scrolling();
+        self.add_notification("Cleared the header row".to_string());
+    }
+
+    /// Handles `:headerrow` alone: shows the current sheet's header row
+    pub fn show_header_row(&mut self) {
+        match self.designated_header_row() {
+            Some(row) => self.add_notification(format!("Header row: {row}")),
+            None => self.add_warning(HEADER_ROW_USAGE.to_string()),
+        }
+    }
+
+    /// The row `:headerrow` made the current sheet's header
+    #[must_use]
+    pub fn designated_header_row(&self) -> Option<usize> {
+        self.sheet_header_rows
+            .get(&self.workbook.get_current_sheet_name())
+            .copied()
+    }
+
+    /// The header row kept in view once scrolled past: one set with `:headerrow` below the
+    /// frozen rows, which stay in view anyway
+    #[must_use]
+    pub fn pinned_header_row(&self) -> Option<usize> {
+        let frozen_rows = self.workbook.get_current_sheet().freeze_panes.rows;
+        self.designated_header_row()
+            .filter(|&row| row > frozen_rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::app::AppState;
+    use crate::excel::{Sheet, Workbook};
+    use std::path::PathBuf;
+
+    #[test]
+    fn headerrow_pins_a_row_and_labels_headers_until_noheaderrow() {
+        let workbook = Workbook::from_sheets_for_test(vec![
+            Sheet::blank("Data".to_string()),
+            Sheet::blank("Other".to_string()),
+        ]);
+        let mut app = AppState::new(workbook, PathBuf::from("test.xlsx")).unwrap();
+        for (row, value) in ["Quarterly report", "", "Name", "Ada", "Bob"]
+            .into_iter()
+            .enumerate()
+        {
+            app.workbook
+                .set_cell_value(row + 1, 1, value.to_string())
+                .unwrap();
+        }
+
+        for command in ["headerrow", "headerrow 0", "headerrow three"] {
+            app.input_buffer = command.to_string();
+            app.execute_command();
+            assert_eq!(
+                app.notification_messages.last().unwrap().text,
+                "Usage: :headerrow <n>"
+            );
+        }
+
+        app.input_buffer = "headerrow 3".to_string();
+        app.execute_command();
+        assert_eq!(app.designated_header_row(), Some(3));
+        assert_eq!(app.pinned_header_row(), Some(3));
+        assert_eq!(app.header_row(), 3);
+        app.input_buffer = "headerrow".to_string();
+        app.execute_command();
+        assert_eq!(
+            app.notification_messages.last().unwrap().text,
+            "Header row: 3"
+        );
+
+        app.jump_to_column_by_header("name");
+        assert_eq!(
+            app.notification_messages.last().unwrap().text,
+            "Column A: Name"
+        );
+
+        app.workbook.get_current_sheet_mut().freeze_panes.rows = 3;
+        assert_eq!(app.pinned_header_row(), None);
+        app.workbook.get_current_sheet_mut().freeze_panes.rows = 0;
+
+        app.switch_to_sheet("Other");
+        assert_eq!(app.designated_header_row(), None);
+        assert_eq!(app.header_row(), 1);
+        app.switch_to_sheet("Data");
+
+        app.input_buffer = "noheaderrow".to_string();
+        app.execute_command();
+        assert_eq!(app.designated_header_row(), None);
+        assert_eq!(
+            app.notification_messages.last().unwrap().text,
+            "Cleared the header row"
+        );
+        app.clear_header_row();
+        assert_eq!(
+            app.notification_messages.last().unwrap().text,
+            "No header row"
+        );
+    }
+}
@@ -8,6 +8,12 @@ pub struct HelpSection {
     pub entries: &'static [HelpEntry],
 }
 
+/// Whether `text` contains `filter`, which is lowercase, ignoring case
+#[must_use]
+pub fn help_text_matches(text: &str, filter: &str) -> bool {
+    text.to_lowercase().contains(filter)
+}
+
 pub const LEFT_HELP_SECTIONS: &[HelpSection] = &[
     HelpSection {
         title: "NAVIGATION",
@@ -17,8 +23,8 @@ pub const LEFT_HELP_SECTIONS: &[HelpSection] = &[
                 description: "Move cell",
             },
             HelpEntry {
-                keys: "[ / ]",
-                description: "Switch sheet",
+                keys: "[ / ] / { / }",
+                description: "Switch sheet / scroll tabs",
             },
             HelpEntry {
                 keys: "gg / G",
@@ -28,9 +34,41 @@ pub const LEFT_HELP_SECTIONS: &[HelpSection] = &[
                 keys: "0 / ^ / $",
                 description: "Row start / first non-empty / end",
             },
+            HelpEntry {
+                keys: "10j / 3] / 25G",
+                description: "Count: repeat / go to row",
+            },
+            HelpEntry {
+                keys: "m{a-z} / '{a-z}",
+                description: "Set mark / jump to mark",
+            },
+            HelpEntry {
+                keys: "g; / '.",
+                description: "Jump to last change",
+            },
             HelpEntry {
                 keys: "Ctrl+arrows",
-                description: "Jump to next non-empty cell",
+                description: "Jump to data block edge",
+            },
+            HelpEntry {
+                keys: "Home / End",
+                description: "First / last column",
+            },
+            HelpEntry {
+                keys: "Ctrl+D/U / Ctrl+F/B",
+                description: "Scroll half / full screen",
+            },
+            HelpEntry {
+                keys: "zz / zt / zb",
+                description: "Row to middle / top / bottom",
+            },
+            HelpEntry {
+                keys: "Ctrl+Home / Ctrl+End",
+                description: "A1 / last used cell",
+            },
+            HelpEntry {
+                keys: "PgUp / PgDn",
+                description: "Scroll one screen",
             },
         ],
     },
@@ -38,17 +76,37 @@ pub const LEFT_HELP_SECTIONS: &[HelpSection] = &[
         title: "SEARCH",
         entries: &[
             HelpEntry {
-                keys: "/",
-                description: "Search forward",
+                keys: "/ / ?",
+                description: "Search forward / backward",
             },
             HelpEntry {
-                keys: "?",
-                description: "Search backward",
+                keys: "Up / Down at / prompt",
+                description: "Recall earlier searches",
             },
             HelpEntry {
                 keys: "n / N",
                 description: "Next/previous search result",
             },
+            HelpEntry {
+                keys: "\\C / \\c in query",
+                description: "Match case / ignore case",
+            },
+            HelpEntry {
+                keys: ":set [no]matchcell",
+                description: "Match whole cells only",
+            },
+            HelpEntry {
+                keys: ":set searchscope=col",
+                description: "Search column/selection/sheet",
+            },
+            HelpEntry {
+                keys: ":find <kind>",
+                description: "Find formulas/errors/numbers>N",
+            },
+            HelpEntry {
+                keys: ":sortmode <mode>",
+                description: "Order matches natural/lexical",
+            },
             HelpEntry {
                 keys: ":noh / :nohlsearch",
                 description: "Disable search highlighting",
@@ -62,6 +120,18 @@ pub const LEFT_HELP_SECTIONS: &[HelpSection] = &[
                 keys: ":<cell>",
                 description: "Jump to cell, e.g. :B10",
             },
+            HelpEntry {
+                keys: ":name [label]",
+                description: "Name selection / list names",
+            },
+            HelpEntry {
+                keys: ":goto <name>",
+                description: "Jump to named range",
+            },
+            HelpEntry {
+                keys: ":col <header>",
+                description: "Jump to column by header",
+            },
             HelpEntry {
                 keys: ":sheet <name|index>",
                 description: "Switch sheet",
@@ -70,6 +140,22 @@ pub const LEFT_HELP_SECTIONS: &[HelpSection] = &[
                 keys: ":addsheet <name>",
                 description: "Add sheet after current",
             },
+            HelpEntry {
+                keys: ":newsheet [name]",
+                description: "Append empty sheet at end",
+            },
+            HelpEntry {
+                keys: ":rename <name>",
+                description: "Rename current sheet",
+            },
+            HelpEntry {
+                keys: ":dupsheet [name]",
+                description: "Copy current sheet after it",
+            },
+            HelpEntry {
+                keys: ":showhidden",
+                description: "Toggle hidden sheets in tabs",
+            },
             HelpEntry {
                 keys: ":delsheet",
                 description: "Delete current sheet",
@@ -107,6 +193,10 @@ pub const LEFT_HELP_SECTIONS: &[HelpSection] = &[
                 keys: ":dr <start> <end>",
                 description: "Delete row range",
             },
+            HelpEntry {
+                keys: ":mr <n>",
+                description: "Move row(s) n down (-n up)",
+            },
             HelpEntry {
                 keys: ":dc / :dc <col>",
                 description: "Delete current/specific column",
@@ -116,13 +206,41 @@ pub const LEFT_HELP_SECTIONS: &[HelpSection] = &[
                 description: "Delete column range",
             },
             HelpEntry {
-                keys: ":freeze [cell]",
-                description: "Freeze panes at cell",
+                keys: ":mc <n>",
+                description: "Move column(s) n right (-n left)",
+            },
+            HelpEntry {
+                keys: ":dupr / :dupc",
+                description: "Duplicate current row / column",
+            },
+            HelpEntry {
+                keys: ":freeze [cell|N|col]",
+                description: "Freeze at cell / N rows / to col",
             },
             HelpEntry {
                 keys: ":unfreeze",
                 description: "Clear frozen panes",
             },
+            HelpEntry {
+                keys: ":dedupe [cols]",
+                description: "Remove duplicate rows",
+            },
+            HelpEntry {
+                keys: ":trim [col|all]",
+                description: "Normalize whitespace",
+            },
+            HelpEntry {
+                keys: ":upper / :lower / :title",
+                description: "Change case",
+            },
+            HelpEntry {
+                keys: ":transpose [range] [new]",
+                description: "Swap rows and columns",
+            },
+            HelpEntry {
+                keys: ":joincols B D \"-\" [new]",
+                description: "Join columns with separator",
+            },
         ],
     },
 ];
@@ -133,11 +251,19 @@ pub const RIGHT_HELP_SECTIONS: &[HelpSection] = &[
         entries: &[
             HelpEntry {
                 keys: "Enter",
-                description: "Edit cell",
+                description: "Edit cell / follow sheet link",
+            },
+            HelpEntry {
+                keys: "i / K",
+                description: "Edit cell / view whole value",
+            },
+            HelpEntry {
+                keys: "gx",
+                description: "Open link in cell",
             },
             HelpEntry {
                 keys: "y / :y",
-                description: "Copy current cell",
+                description: "Copy cell or selection",
             },
             HelpEntry {
                 keys: "d / :d",
@@ -145,7 +271,67 @@ pub const RIGHT_HELP_SECTIONS: &[HelpSection] = &[
             },
             HelpEntry {
                 keys: "p / :put / :pu",
-                description: "Paste to current cell",
+                description: "Paste at current cell",
+            },
+            HelpEntry {
+                keys: ":paste [options]",
+                description: "values, transpose, skipblanks",
+            },
+            HelpEntry {
+                keys: "v / Esc",
+                description: "Select cells / clear",
+            },
+            HelpEntry {
+                keys: "Shift+arrows",
+                description: "Extend selection",
+            },
+            HelpEntry {
+                keys: "Shift+Ctrl+arrows",
+                description: "Extend selection to data edge",
+            },
+            HelpEntry {
+                keys: "Alt+j / Alt+k",
+                description: "Move row(s) down / up",
+            },
+            HelpEntry {
+                keys: "Alt+h / Alt+l",
+                description: "Move column(s) left / right",
+            },
+            HelpEntry {
+                keys: "Terminal paste",
+                description: "Paste text, blocks ask to split",
+            },
+            HelpEntry {
+                keys: ":comment <text>",
+                description: "Add or edit cell comment",
+            },
+            HelpEntry {
+                keys: ":delcomment",
+                description: "Delete cell comment",
+            },
+            HelpEntry {
+                keys: ":fmt [col] <format|off>",
+                description: "Set number/date display format",
+            },
+            HelpEntry {
+                keys: ":headers / :errors",
+                description: "List headers / error cells",
+            },
+            HelpEntry {
+                keys: ":freq [col]",
+                description: "Count values in a column",
+            },
+            HelpEntry {
+                keys: ":dupes [cols]",
+                description: "List duplicate rows",
+            },
+            HelpEntry {
+                keys: "gr / :copen / :cclose",
+                description: "Toggle/focus/close results",
+            },
+            HelpEntry {
+                keys: ":cn / :cp",
+                description: "Next / previous result",
             },
             HelpEntry {
                 keys: "u",
@@ -156,8 +342,8 @@ pub const RIGHT_HELP_SECTIONS: &[HelpSection] = &[
                 description: "Redo",
             },
             HelpEntry {
-                keys: "+ / = / -",
-                description: "Resize info panel",
+                keys: "+ / - / _",
+                description: "Resize / hide info panel",
             },
         ],
     },
@@ -165,8 +351,8 @@ pub const RIGHT_HELP_SECTIONS: &[HelpSection] = &[
         title: "FILE & APP",
         entries: &[
             HelpEntry {
-                keys: ":w",
-                description: "Save file",
+                keys: ":w / :changes",
+                description: "Save file / list unsaved edits",
             },
             HelpEntry {
                 keys: ":wq / :x",
@@ -181,8 +367,44 @@ pub const RIGHT_HELP_SECTIONS: &[HelpSection] = &[
                 description: "Force quit without saving",
             },
             HelpEntry {
-                keys: ":help",
-                description: "Show this overlay",
+                keys: ":help / :messages",
+                description: "This overlay / past messages",
+            },
+            HelpEntry {
+                keys: ":version",
+                description: "Show version and build info",
+            },
+            HelpEntry {
+                keys: ":unlock",
+                description: "Allow edits on protected sheet",
+            },
+            HelpEntry {
+                keys: ":copysheet <file>",
+                description: "Copy sheet into another xlsx",
+            },
+            HelpEntry {
+                keys: ":importsheet <f> <sheet>",
+                description: "Copy in a sheet from a file",
+            },
+            HelpEntry {
+                keys: "gt / gT",
+                description: "Next/previous open workbook",
+            },
+            HelpEntry {
+                keys: ":b [n] / :bn / :bp",
+                description: "List or switch workbooks",
+            },
+            HelpEntry {
+                keys: ":split / :vsplit [sheet]",
+                description: "Split view into two panes",
+            },
+            HelpEntry {
+                keys: "Ctrl+W w / c / o",
+                description: "Switch / close / keep pane",
+            },
+            HelpEntry {
+                keys: ":minimap",
+                description: "Toggle sheet overview map",
             },
         ],
     },
@@ -205,6 +427,14 @@ pub const RIGHT_HELP_SECTIONS: &[HelpSection] = &[
                 keys: ":eja <h|v> <rows>",
                 description: "Export all with header settings",
             },
+            HelpEntry {
+                keys: ":exporttz [offset|off]",
+                description: "Add offset to exported datetimes",
+            },
+            HelpEntry {
+                keys: ":exportdur [iso|seconds]",
+                description: "Export durations as ISO or seconds",
+            },
         ],
     },
     HelpSection {
@@ -271,6 +501,10 @@ pub const RIGHT_HELP_SECTIONS: &[HelpSection] = &[
                 keys: "Home / End",
                 description: "Jump to top/bottom",
             },
+            HelpEntry {
+                keys: "/",
+                description: "Filter entries, Esc clears",
+            },
         ],
     },
 ];
@@ -0,0 +1,259 @@
+use std::path::{Path, PathBuf};
+
+use crate::app::AppState;
+
+/// File in the configuration directory that keeps past search queries, oldest first
+const SEARCH_HISTORY_FILE: &str = "search_history";
+
+/// File in the configuration directory that keeps past `:` commands, oldest first
+const COMMAND_HISTORY_FILE: &str = "command_history";
+
+/// How many entries a history keeps; the oldest are dropped first
+const MAX_HISTORY_ENTRIES: usize = 100;
+
+/// Past entries of a prompt, recalled with Up and Down like vim's command-line history. Only
+/// entries starting with what was typed before browsing are recalled
+#[derive(Clone, Debug, Default)]
+pub struct InputHistory {
+    entries: Vec<String>,
+    /// Entry shown while browsing
+    position: Option<usize>,
+    /// What was typed before browsing started, shown again after the newest entry
+    draft: String,
+}
+
+impl InputHistory {
+    #[must_use]
+    pub fn from_entries(entries: Vec<String>) -> Self {
+        let mut history = Self::default();
+        for entry in entries {
+            history.push(&entry);
+        }
+        history
+    }
+
+    /// Entries, oldest first
+    #[must_use]
+    pub fn entries(&self) -> &[String] {
+        &self.entries
+    }
+
+    /// Adds an entry as the newest, moving it there if it was used before
+    pub fn push(&mut self, entry: &str) {
+        self.reset();
+        if entry.is_empty() {
+            return;
+        }
+        self.entries.retain(|existing| existing != entry);
+        self.entries.push(entry.to_string());
+        if self.entries.len() > MAX_HISTORY_ENTRIES {
+            self.entries.remove(0);
+        }
+    }
+
+    /// The next older entry matching `typed`, or `None` at the oldest
+    pub fn older(&mut self, typed: &str) -> Option<String> {
+        let before = match self.position {
+            Some(position) => position,
+            None => {
+                self.draft = typed.to_string();
+                self.entries.len()
+            }
+        };
+        let found = self.entries[..before]
+            .iter()
+            .rposition(|entry| entry.starts_with(&self.draft))?;
+        self.position = Some(found);
+        Some(self.entries[found].clone())
+    }
+
+    /// The next newer entry matching what was typed, and past the newest the typed text itself.
+    /// `None` when not browsing
+    pub fn newer(&mut self) -> Option<String> {
+        let position = self.position?;
+        let found = self.entries[position + 1..]
+            .iter()
+            .position(|entry| entry.starts_with(&self.draft))
+            .map(|offset| position + 1 + offset);
+        self.position = found;
+        match found {
+            Some(found) => Some(self.entries[found].clone()),
+            None => Some(std::mem::take(&mut self.draft)),
+        }
+    }
+
+    /// Stops browsing, so the next Up starts from the newest entry again
+    pub fn reset(&mut self) {
+        self.position = None;
+        self.draft.clear();
+    }
+}
+
+impl AppState<'_> {
+    /// Replaces the search prompt's text with an older (`Up`) or newer (`Down`) past query
+    pub fn recall_search_history(&mut self, older: bool) {
+        let typed = self.text_area.lines().join("\n");
+        let recalled = if older {
+            self.search_history.older(&typed)
+        } else {
+            self.search_history.newer()
+        };
+        let Some(query) = recalled else {
+            return;
+        };
+
+        let mut text_area = tui_textarea::TextArea::new(vec![query]);
+        text_area.set_cursor_line_style(self.text_area.cursor_line_style());
+        text_area.set_cursor_style(self.text_area.cursor_style());
+        text_area.move_cursor(tui_textarea::CursorMove::End);
+        self.text_area = text_area;
+        self.search_query_changed();
+    }
+
+    /// Replaces the `:` prompt's text with an older (`Up`) or newer (`Down`) past command,
+    /// to run again as it is or after editing
+    pub fn recall_command_history(&mut self, older: bool) {
+        let recalled = if older {
+            self.command_history.older(&self.input_buffer)
+        } else {
+            self.command_history.newer()
+        };
+        if let Some(command) = recalled {
+            self.input_buffer = command;
+        }
+    }
+
+    /// Loads the queries searched for and the commands run in earlier sessions
+    pub fn load_history(&mut self) {
+        self.load_history_from(crate::config::config_dir());
+    }
+
+    pub(crate) fn load_history_from(&mut self, config_dir: Option<PathBuf>) {
+        let Some(dir) = config_dir else {
+            return;
+        };
+        if let Some(history) = read_history(&dir.join(SEARCH_HISTORY_FILE)) {
+            self.search_history = history;
+        }
+        if let Some(history) = read_history(&dir.join(COMMAND_HISTORY_FILE)) {
+            self.command_history = history;
+        }
+    }
+
+    /// Saves the search and command histories in `config_dir` for the next session, after
+    /// what other workbooks closed earlier saved there. Nothing is saved with
+    /// `:set nosavehistory`
+    pub fn save_history_in(&self, config_dir: &Path) -> std::io::Result<()> {
+        if !self.save_history {
+            return Ok(());
+        }
+        std::fs::create_dir_all(config_dir)?;
+        append_history(&config_dir.join(SEARCH_HISTORY_FILE), &self.search_history)?;
+        append_history(
+            &config_dir.join(COMMAND_HISTORY_FILE),
+            &self.command_history,
+        )
+    }
+}
+
+fn read_history(file: &Path) -> Option<InputHistory> {
+    let text = std::fs::read_to_string(file).ok()?;
+    Some(InputHistory::from_entries(
+        text.lines().map(String::from).collect(),
+    ))
+}
+
+/// Writes `history`'s entries after those already in `file`, as the newest
+fn append_history(file: &Path, history: &InputHistory) -> std::io::Result<()> {
+    let mut saved = read_history(file).unwrap_or_default();
+    for entry in history.entries() {
+        saved.push(entry);
+    }
+
+    let mut text = saved.entries().join("\n");
+    text.push('\n');
+    std::fs::write(file, text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::InputHistory;
+    use crate::app::AppState;
+    use crate::excel::{Sheet, Workbook};
+    use std::path::PathBuf;
+
+    #[test]
+    fn up_and_down_walk_entries_starting_with_the_typed_text() {
+        let mut history = InputHistory::from_entries(
+            ["total", "tax", "name", "total"]
+                .into_iter()
+                .map(String::from)
+                .collect(),
+        );
+        assert_eq!(history.entries(), ["tax", "name", "total"]);
+
+        assert_eq!(history.older("t").as_deref(), Some("total"));
+        assert_eq!(history.older("ignored").as_deref(), Some("tax"));
+        assert_eq!(history.older("ignored"), None);
+        assert_eq!(history.newer().as_deref(), Some("total"));
+        assert_eq!(history.newer().as_deref(), Some("t"));
+        assert_eq!(history.newer(), None);
+
+        assert_eq!(history.older("").as_deref(), Some("total"));
+        assert_eq!(history.older("").as_deref(), Some("name"));
+    }
+
+    #[test]
+    fn searches_and_commands_are_recalled_and_kept_across_sessions() {
+        let dir = std::env::temp_dir().join("excel_cli_search_history_test");
+        let _ = std::fs::remove_dir_all(&dir);
+        let app = || {
+            let workbook = Workbook::from_sheets_for_test(vec![Sheet::blank("Data".to_string())]);
+            AppState::new(workbook, PathBuf::from("test.xlsx")).unwrap()
+        };
+
+        let mut first = app();
+        for query in ["alpha", "beta"] {
+            first.start_search_forward();
+            first.text_area.insert_str(query);
+            first.execute_search();
+        }
+        for command in ["cw 12", "cw 14"] {
+            first.start_command_mode();
+            first.input_buffer = command.to_string();
+            first.execute_command();
+        }
+        first.save_history_in(&dir).unwrap();
+
+        let mut second = app();
+        second.load_history_from(Some(dir.clone()));
+        second.start_search_backward();
+        second.recall_search_history(true);
+        assert_eq!(second.text_area.lines(), ["beta"]);
+        second.recall_search_history(true);
+        assert_eq!(second.text_area.lines(), ["alpha"]);
+        second.recall_search_history(false);
+        second.recall_search_history(false);
+        assert_eq!(second.text_area.lines(), [""]);
+
+        // Up recalls commands starting with what was typed, to edit before running
+        second.start_command_mode();
+        second.add_char_to_input('c');
+        second.recall_command_history(true);
+        assert_eq!(second.input_buffer, "cw 14");
+        second.delete_char_from_input();
+        second.add_char_to_input('6');
+        second.execute_command();
+        assert_eq!(
+            second.command_history.entries(),
+            ["cw 12", "cw 14", "cw 16"]
+        );
+
+        second.save_history = false;
+        second.save_history_in(&dir).unwrap();
+        let saved = std::fs::read_to_string(dir.join(super::COMMAND_HISTORY_FILE)).unwrap();
+        assert_eq!(saved, "cw 12\ncw 14\n");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
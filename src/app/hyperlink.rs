@@ -0,0 +1,111 @@
+use std::process::{Command, Stdio};
+
+use crate::app::AppState;
+use crate::utils::{cell_reference, detect_url, parse_sheet_link};
+
+impl AppState<'_> {
+    /// Link target of a cell: its xlsx hyperlink, or its text when that looks like a URL
+    #[must_use]
+    pub fn link_at(&self, row: usize, col: usize) -> Option<String> {
+        let cell = self.workbook.get_current_sheet().data.get(row, col)?;
+
+        cell.hyperlink.clone().or_else(|| {
+            (!cell.is_formula)
+                .then(|| detect_url(&cell.value))
+                .flatten()
+        })
+    }
+
+    /// Opens the link under the cursor. Links to a place in the workbook are followed in the
+    /// grid; other targets go to the system opener.
+    pub fn open_link_under_cursor(&mut self) {
+        let (row, col) = self.selected_cell;
+        let Some(target) = self.link_at(row, col) else {
+            self.add_notification(format!("No link in {}", cell_reference((row, col))));
+            return;
+        };
+
+        if let Some(location) = target.strip_prefix('#') {
+            match parse_sheet_link(location, &self.workbook.get_sheet_names()) {
+                Some((index, cell)) => self.jump_to_sheet_location(index, cell),
+                None => self.add_notification(format!("Link target {location} not found")),
+            }
+            return;
+        }
+
+        match open_with_system_opener(&target) {
+            Ok(()) => self.add_notification(format!("Opening {target}")),
+            Err(e) => self.add_error(format!("Failed to open {target}: {e}")),
+        }
+    }
+}
+
+/// Hands `target` to the platform's default handler without waiting for it to finish
+fn open_with_system_opener(target: &str) -> std::io::Result<()> {
+    #[cfg(target_os = "macos")]
+    let mut command = Command::new("open");
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        let mut command = Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    };
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let mut command = Command::new("xdg-open");
+
+    let mut child = command
+        .arg(target)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::app::AppState;
+    use crate::excel::{Cell, Sheet, Workbook};
+    use std::path::PathBuf;
+
+    #[test]
+    fn links_come_from_hyperlinks_or_url_text_and_internal_ones_are_followed() {
+        let mut index = Sheet::blank("Index".to_string());
+        index
+            .data
+            .set(1, 1, Cell::new("www.example.com".to_string(), false));
+        index.data.set(2, 1, Cell::new("Sales".to_string(), false));
+        index.data.update_cell(2, 1, |cell| {
+            cell.hyperlink = Some("#'Q1 Sales'!B4".to_string())
+        });
+        index
+            .data
+            .set(3, 1, Cell::new("plain text".to_string(), false));
+        index.max_rows = 3;
+        index.max_cols = 1;
+        let workbook =
+            Workbook::from_sheets_for_test(vec![index, Sheet::blank("Q1 Sales".to_string())]);
+        let mut app = AppState::new(workbook, PathBuf::from("test.xlsx")).unwrap();
+
+        assert_eq!(
+            app.link_at(1, 1).as_deref(),
+            Some("https://www.example.com")
+        );
+        assert_eq!(app.link_at(3, 1), None);
+
+        app.selected_cell = (3, 1);
+        app.open_link_under_cursor();
+        assert_eq!(
+            app.notification_messages
+                .last()
+                .map(|notification| notification.text.as_str()),
+            Some("No link in A3")
+        );
+
+        app.selected_cell = (2, 1);
+        app.open_link_under_cursor();
+        assert_eq!(app.workbook.get_current_sheet_name(), "Q1 Sales");
+        assert_eq!(app.selected_cell, (4, 2));
+    }
+}
@@ -0,0 +1,218 @@
+use std::time::{Duration, Instant};
+
+use crate::app::{AppState, CellRange, InputMode};
+
+/// Sheets with more cells than this are searched once typing pauses, not on every keystroke
+const IMMEDIATE_SEARCH_CELLS: usize = 250_000;
+
+/// How long typing has to pause before a large sheet is searched
+const SEARCH_DEBOUNCE: Duration = Duration::from_millis(150);
+
+/// What the search prompt was opened over, put back when the search is cancelled. While the
+/// prompt is open, matches for the query typed so far are highlighted and the cursor previews
+/// the one `Enter` would jump to
+#[derive(Clone, Debug)]
+pub struct IncSearch {
+    selected_cell: (usize, usize),
+    start_row: usize,
+    start_col: usize,
+    search_results: Vec<(usize, usize)>,
+    current_search_idx: Option<usize>,
+    search_bounds: Option<CellRange>,
+    /// When the query last changed on a sheet too large to search on every keystroke
+    pending_since: Option<Instant>,
+}
+
+impl AppState<'_> {
+    /// Remembers the cursor and matches the prompt opens over, when `:set incsearch` is on
+    pub(crate) fn begin_incremental_search(&mut self) {
+        self.incsearch = self.incremental_search.then(|| IncSearch {
+            selected_cell: self.selected_cell,
+            start_row: self.start_row,
+            start_col: self.start_col,
+            search_results: self.search_results.clone(),
+            current_search_idx: self.current_search_idx,
+            search_bounds: self.search_bounds,
+            pending_since: None,
+        });
+    }
+
+    /// Updates the preview after the query was edited, right away on ordinary sheets and once
+    /// typing pauses on large ones
+    pub fn search_query_changed(&mut self) {
+        let sheet = self.workbook.get_current_sheet();
+        let cells = sheet.max_rows.saturating_mul(sheet.max_cols);
+        let Some(incsearch) = self.incsearch.as_mut() else {
+            return;
+        };
+
+        if cells > IMMEDIATE_SEARCH_CELLS {
+            incsearch.pending_since = Some(Instant::now());
+        } else {
+            self.preview_search();
+        }
+    }
+
+    /// Runs a preview held back while typing on a large sheet, once typing has paused.
+    /// Called from the event loop between key presses
+    pub fn run_pending_incremental_search(&mut self) {
+        if self
+            .incsearch
+            .as_ref()
+            .and_then(|incsearch| incsearch.pending_since)
+            .is_some_and(|since| since.elapsed() >= SEARCH_DEBOUNCE)
+        {
+            self.preview_search();
+        }
+    }
+
+    /// Closes the search prompt, putting back the cursor, view and matches it was opened over
+    pub fn cancel_search(&mut self) {
+        if let Some(incsearch) = self.incsearch.take() {
+            self.selected_cell = incsearch.selected_cell;
+            self.start_row = incsearch.start_row;
+            self.start_col = incsearch.start_col;
+            self.search_results = incsearch.search_results;
+            self.current_search_idx = incsearch.current_search_idx;
+            self.search_bounds = incsearch.search_bounds;
+        }
+        self.input_mode = InputMode::Normal;
+        self.input_buffer = String::new();
+        self.text_area = tui_textarea::TextArea::default();
+    }
+
+    /// Puts the cursor and view back where the prompt was opened, so `Enter` searches from there
+    pub(crate) fn finish_incremental_search(&mut self) {
+        if let Some(incsearch) = self.incsearch.take() {
+            self.selected_cell = incsearch.selected_cell;
+            self.start_row = incsearch.start_row;
+            self.start_col = incsearch.start_col;
+        }
+    }
+
+    fn preview_search(&mut self) {
+        let Some(incsearch) = self.incsearch.as_mut() else {
+            return;
+        };
+        incsearch.pending_since = None;
+        let origin = incsearch.selected_cell;
+        self.selected_cell = origin;
+        self.start_row = incsearch.start_row;
+        self.start_col = incsearch.start_col;
+
+        let query = self.text_area.lines().join("\n");
+        if query.is_empty() {
+            self.search_results.clear();
+            self.current_search_idx = None;
+            return;
+        }
+
+        self.search_bounds = self.search_scope_bounds();
+        self.search_results = self.find_all_matches(&query);
+        let len = self.search_results.len();
+        if len == 0 {
+            self.current_search_idx = None;
+            return;
+        }
+
+        // The match Enter would jump to, found without the wrap notices n and N give
+        let idx = if matches!(self.input_mode, InputMode::SearchBackward) {
+            self.search_results
+                .iter()
+                .rposition(|&pos| pos < origin)
+                .unwrap_or(len - 1)
+        } else {
+            self.search_results
+                .iter()
+                .position(|&pos| pos > origin)
+                .unwrap_or(0)
+        };
+        self.current_search_idx = Some(idx);
+        self.selected_cell = self.search_results[idx];
+        self.handle_scrolling();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::app::{AppState, InputMode};
+    use crate::excel::{Sheet, Workbook};
+    use std::path::PathBuf;
+    use std::time::{Duration, Instant};
+
+    fn app_with_values(max_rows: usize) -> AppState<'static> {
+        let workbook = Workbook::from_sheets_for_test(vec![Sheet::blank("Data".to_string())]);
+        let mut app = AppState::new(workbook, PathBuf::from("test.xlsx")).unwrap();
+        for (row, value) in ["apple", "banana", "cherry", "band"]
+            .into_iter()
+            .enumerate()
+        {
+            app.workbook
+                .set_cell_value(row + 1, 1, value.to_string())
+                .unwrap();
+        }
+        app.workbook.get_current_sheet_mut().max_rows = max_rows;
+        app
+    }
+
+    fn type_query(app: &mut AppState, text: &str) {
+        for ch in text.chars() {
+            app.text_area.insert_char(ch);
+            app.search_query_changed();
+        }
+    }
+
+    #[test]
+    fn typing_previews_matches_and_escape_puts_the_cursor_back() {
+        let mut app = app_with_values(10);
+        app.selected_cell = (3, 1);
+        app.start_search_forward();
+
+        type_query(&mut app, "ban");
+        assert_eq!(app.search_results, vec![(2, 1), (4, 1)]);
+        assert_eq!(app.selected_cell, (4, 1));
+        type_query(&mut app, "a");
+        assert_eq!(app.search_results, vec![(2, 1)]);
+        assert_eq!(app.selected_cell, (2, 1));
+
+        app.cancel_search();
+        assert!(matches!(app.input_mode, InputMode::Normal));
+        assert_eq!(app.selected_cell, (3, 1));
+        assert!(app.search_results.is_empty());
+
+        app.start_search_forward();
+        type_query(&mut app, "ban");
+        app.execute_search();
+        assert_eq!(app.selected_cell, (4, 1));
+        assert_eq!(app.search_query, "ban");
+    }
+
+    #[test]
+    fn large_sheets_are_searched_once_typing_pauses() {
+        let mut app = app_with_values(300_000);
+        app.start_search_backward();
+
+        type_query(&mut app, "cher");
+        assert!(app.search_results.is_empty());
+        app.run_pending_incremental_search();
+        assert!(app.search_results.is_empty());
+
+        if let Some(incsearch) = app.incsearch.as_mut() {
+            incsearch.pending_since = Instant::now().checked_sub(Duration::from_secs(1));
+        }
+        app.run_pending_incremental_search();
+        assert_eq!(app.search_results, vec![(3, 1)]);
+        assert_eq!(app.selected_cell, (3, 1));
+    }
+
+    #[test]
+    fn noincsearch_waits_for_enter() {
+        let mut app = app_with_values(10);
+        app.handle_set_command("noincsearch");
+        app.start_search_forward();
+
+        type_query(&mut app, "cherry");
+        assert!(app.search_results.is_empty());
+        assert_eq!(app.selected_cell, (1, 1));
+    }
+}
@@ -0,0 +1,132 @@
+use crate::app::{AppState, InputMode};
+
+/// Rows the info panel takes below the grid, changed with `+` and `-`
+const HEIGHT_RANGE: (usize, usize) = (6, 16);
+/// Columns the info panel takes beside the grid with `:set infopanelpos=right`
+const WIDTH_RANGE: (usize, usize) = (24, 80);
+const WIDTH_STEP: usize = 4;
+
+/// Where the cell details and notifications are drawn
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum InfoPanelPosition {
+    #[default]
+    Bottom,
+    Right,
+}
+
+impl InfoPanelPosition {
+    #[must_use]
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "bottom" => Some(Self::Bottom),
+            "right" => Some(Self::Right),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Bottom => "bottom",
+            Self::Right => "right",
+        }
+    }
+}
+
+impl AppState<'_> {
+    /// Hides the info panel, giving its space to the grid, or shows it again
+    pub fn toggle_info_panel(&mut self) {
+        self.show_info_panel = !self.show_info_panel;
+        let state = if self.show_info_panel {
+            "shown"
+        } else {
+            "hidden; _ shows it"
+        };
+        self.add_notification(format!("Info panel {state}"));
+    }
+
+    /// Whether the info panel is drawn. Editing a cell shows it even when hidden, since the
+    /// editor lives there
+    #[must_use]
+    pub fn info_panel_visible(&self) -> bool {
+        self.show_info_panel || matches!(self.input_mode, InputMode::Editing)
+    }
+
+    /// Grows or shrinks the info panel by `delta` steps: rows below the grid, or columns
+    /// beside it
+    pub fn adjust_info_panel_size(&mut self, delta: isize) {
+        if !self.show_info_panel {
+            self.toggle_info_panel();
+            return;
+        }
+
+        match self.info_panel_position {
+            InfoPanelPosition::Bottom => {
+                let (min, max) = HEIGHT_RANGE;
+                let height = self.info_panel_height.saturating_add_signed(delta);
+                let height = height.clamp(min, max);
+                if height != self.info_panel_height {
+                    self.info_panel_height = height;
+                    self.add_notification(format!("Info panel height: {height}"));
+                }
+            }
+            InfoPanelPosition::Right => {
+                let (min, max) = WIDTH_RANGE;
+                let step = delta * WIDTH_STEP as isize;
+                let width = self.info_panel_width.saturating_add_signed(step);
+                let width = width.clamp(min, max);
+                if width != self.info_panel_width {
+                    self.info_panel_width = width;
+                    self.add_notification(format!("Info panel width: {width}"));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::InfoPanelPosition;
+    use crate::app::{AppState, InputMode};
+    use crate::excel::{Sheet, Workbook};
+    use std::path::PathBuf;
+
+    #[test]
+    fn info_panel_hides_moves_right_and_resizes_by_position() {
+        let workbook = Workbook::from_sheets_for_test(vec![Sheet::blank("Data".to_string())]);
+        let mut app = AppState::new(workbook, PathBuf::from("test.xlsx")).unwrap();
+
+        app.adjust_info_panel_size(1);
+        assert_eq!(app.info_panel_height, 11);
+        app.adjust_info_panel_size(-20);
+        assert_eq!(app.info_panel_height, 6);
+
+        app.toggle_info_panel();
+        assert!(!app.info_panel_visible());
+        app.input_mode = InputMode::Editing;
+        assert!(app.info_panel_visible());
+        app.input_mode = InputMode::Normal;
+        // Growing a hidden panel brings it back at its old size
+        app.adjust_info_panel_size(1);
+        assert!(app.info_panel_visible());
+        assert_eq!(app.info_panel_height, 6);
+
+        app.handle_set_command("infopanelpos=right");
+        assert_eq!(app.info_panel_position, InfoPanelPosition::Right);
+        app.adjust_info_panel_size(1);
+        assert_eq!(app.info_panel_width, 44);
+        assert_eq!(
+            app.notification_messages.last().unwrap().text,
+            "Info panel width: 44"
+        );
+        assert_eq!(app.info_panel_height, 6);
+
+        app.handle_set_command("infopanelpos=left");
+        assert_eq!(
+            app.notification_messages.last().unwrap().text,
+            "infopanelpos is bottom or right, not left"
+        );
+        app.handle_set_command("noinfopanel");
+        assert!(!app.show_info_panel);
+    }
+}
@@ -0,0 +1,175 @@
+use crate::app::AppState;
+use crate::utils::cell_reference;
+
+/// What the key after `m` or `'` does with the mark it names
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MarkAction {
+    Set,
+    Jump,
+}
+
+/// A cell remembered with `m{a-z}`, found again by its sheet's name
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Mark {
+    pub sheet_name: String,
+    pub cell: (usize, usize),
+}
+
+impl AppState<'_> {
+    /// Finishes `m{a-z}` or `'{a-z}` with the key typed after it
+    pub fn apply_mark_key(&mut self, action: MarkAction, key: char) {
+        if action == MarkAction::Jump && key == '.' {
+            self.jump_to_last_edit();
+            return;
+        }
+        if !key.is_ascii_lowercase() {
+            self.add_notification(format!("Marks are named a-z, not {key}"));
+            return;
+        }
+
+        match action {
+            MarkAction::Set => self.set_mark(key),
+            MarkAction::Jump => self.jump_to_mark(key),
+        }
+    }
+
+    fn set_mark(&mut self, key: char) {
+        let mark = Mark {
+            sheet_name: self.workbook.get_current_sheet_name(),
+            cell: self.selected_cell,
+        };
+        let message = format!("Mark {key} set at {}", describe_mark(&mark));
+        self.marks.insert(key, mark);
+        self.add_notification(message);
+    }
+
+    fn jump_to_mark(&mut self, key: char) {
+        let Some(mark) = self.marks.get(&key).cloned() else {
+            self.add_notification(format!("Mark {key} is not set"));
+            return;
+        };
+        let Some(index) = self
+            .workbook
+            .get_sheet_names()
+            .iter()
+            .position(|name| *name == mark.sheet_name)
+        else {
+            self.add_notification(format!(
+                "Sheet {} of mark {key} no longer exists",
+                mark.sheet_name
+            ));
+            return;
+        };
+
+        self.clear_selection();
+        self.jump_to_sheet_location(index, Some(mark.cell));
+        self.add_notification(format!("Jumped to mark {key} ({})", describe_mark(&mark)));
+    }
+
+    /// Jumps to the latest change on the current sheet, as `g;` and `'.` do
+    pub fn jump_to_last_edit(&mut self) {
+        let sheet_name = self.workbook.get_current_sheet_name();
+        let Some(cell) = self.undo_history.last_edit(&sheet_name) else {
+            self.add_notification(format!("No changes on sheet {sheet_name} yet"));
+            return;
+        };
+
+        self.clear_selection();
+        self.selected_cell = Self::clamp_cell_to_excel_bounds(cell);
+        self.handle_scrolling();
+        self.add_notification(format!("Jumped to last change at {}", cell_reference(cell)));
+    }
+
+    /// Keeps marks on a renamed sheet pointing at it
+    pub(crate) fn rename_marks(&mut self, old_name: &str, new_name: &str) {
+        for mark in self.marks.values_mut() {
+            if mark.sheet_name == old_name {
+                mark.sheet_name = new_name.to_string();
+            }
+        }
+    }
+}
+
+fn describe_mark(mark: &Mark) -> String {
+    format!("{}!{}", mark.sheet_name, cell_reference(mark.cell))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MarkAction;
+    use crate::app::AppState;
+    use crate::excel::{Sheet, Workbook};
+    use std::path::PathBuf;
+
+    #[test]
+    fn marks_jump_back_across_sheets_and_follow_renames() {
+        let workbook = Workbook::from_sheets_for_test(vec![
+            Sheet::blank("Data".to_string()),
+            Sheet::blank("Notes".to_string()),
+        ]);
+        let mut app = AppState::new(workbook, PathBuf::from("test.xlsx")).unwrap();
+
+        app.selected_cell = (5000, 3);
+        app.apply_mark_key(MarkAction::Set, 'a');
+        assert_eq!(
+            app.notification_messages.last().unwrap().text,
+            "Mark a set at Data!C5000"
+        );
+
+        app.next_sheet().unwrap();
+        app.selected_cell = (2, 2);
+        app.apply_mark_key(MarkAction::Jump, 'a');
+        assert_eq!(app.workbook.get_current_sheet_name(), "Data");
+        assert_eq!(app.selected_cell, (5000, 3));
+
+        app.rename_current_sheet("Raw");
+        app.selected_cell = (1, 1);
+        app.apply_mark_key(MarkAction::Jump, 'a');
+        assert_eq!(app.selected_cell, (5000, 3));
+        assert_eq!(
+            app.notification_messages.last().unwrap().text,
+            "Jumped to mark a (Raw!C5000)"
+        );
+
+        app.apply_mark_key(MarkAction::Jump, 'b');
+        assert_eq!(
+            app.notification_messages.last().unwrap().text,
+            "Mark b is not set"
+        );
+        app.apply_mark_key(MarkAction::Set, 'A');
+        assert!(!app.marks.contains_key(&'A'));
+    }
+
+    #[test]
+    fn last_change_is_remembered_per_sheet_and_survives_undo_history_clears() {
+        let workbook = Workbook::from_sheets_for_test(vec![
+            Sheet::blank("Data".to_string()),
+            Sheet::blank("Notes".to_string()),
+        ]);
+        let mut app = AppState::new(workbook, PathBuf::from("test.xlsx")).unwrap();
+
+        app.jump_to_last_edit();
+        assert_eq!(
+            app.notification_messages.last().unwrap().text,
+            "No changes on sheet Data yet"
+        );
+
+        app.selected_cell = (7, 3);
+        app.paste_into_current_cell("edited".to_string()).unwrap();
+        app.undo_history.clear();
+        app.selected_cell = (300, 1);
+        app.apply_mark_key(MarkAction::Jump, '.');
+        assert_eq!(app.selected_cell, (7, 3));
+        assert_eq!(
+            app.notification_messages.last().unwrap().text,
+            "Jumped to last change at C7"
+        );
+
+        app.next_sheet().unwrap();
+        app.jump_to_last_edit();
+        assert_eq!(
+            app.notification_messages.last().unwrap().text,
+            "No changes on sheet Notes yet"
+        );
+    }
+}
@@ -0,0 +1,122 @@
+use std::path::Path;
+
+use crate::app::AppState;
+
+/// How many times its file size a workbook is reckoned to take up once read, as xlsx files
+/// are zipped XML
+const FILE_INFLATION: u64 = 10;
+
+/// Reads a size like `512M`, `2G`, `800K` or a number of bytes. A `B` after the unit, as in
+/// `512MB`, is allowed
+#[must_use]
+pub fn parse_size(text: &str) -> Option<usize> {
+    let text = text.trim().to_ascii_uppercase();
+    let text = text.strip_suffix('B').unwrap_or(&text);
+    let (number, unit) = match text.char_indices().last()? {
+        (at, 'K') => (&text[..at], 1 << 10),
+        (at, 'M') => (&text[..at], 1 << 20),
+        (at, 'G') => (&text[..at], 1 << 30),
+        _ => (text, 1),
+    };
+    let number: f64 = number.trim().parse().ok()?;
+    (number.is_finite() && number > 0.0).then(|| (number * f64::from(unit)) as usize)
+}
+
+/// A byte count as `1.5 GB`, `312 KB` or `80 B`
+#[must_use]
+pub fn format_size(bytes: usize) -> String {
+    const UNITS: [&str; 3] = ["KB", "MB", "GB"];
+    let mut size = bytes as f64;
+    let mut unit = "B";
+    for next in UNITS {
+        if size < 1024.0 {
+            break;
+        }
+        size /= 1024.0;
+        unit = next;
+    }
+    if unit == "B" || size >= 100.0 {
+        format!("{size:.0} {unit}")
+    } else {
+        format!("{size:.1} {unit}")
+    }
+}
+
+/// Whether the workbook at `path` may take up more than `cap` bytes once read, so it is
+/// better opened with its sheets left unread until shown
+#[must_use]
+pub fn may_pass_memory_cap(path: &Path, cap: usize) -> bool {
+    std::fs::metadata(path).is_ok_and(|file| {
+        file.len().saturating_mul(FILE_INFLATION) > u64::try_from(cap).unwrap_or(u64::MAX)
+    })
+}
+
+impl AppState<'_> {
+    /// Handles `:mem`: lists what each sheet's cells take up in memory, against `memcap`
+    pub fn show_memory(&mut self) {
+        let names = self.workbook.get_sheet_names();
+        let name_width = names.iter().map(String::len).max().unwrap_or(0).max(5);
+        let mut lines = vec![format!(
+            "{:<name_width$}  {:<22}  {:>9}  {:>9}",
+            "Sheet", "State", "Cells", "Memory"
+        )];
+        for (index, name) in names.iter().enumerate() {
+            let Some(sheet) = self.workbook.get_sheet_by_index(index) else {
+                continue;
+            };
+            let held = self
+                .sheet_loads
+                .iter()
+                .any(|load| load.sheet_name == *name && load.held_at_memory_cap);
+            let state = if held {
+                format!("held at memcap, {} rows", sheet.max_rows)
+            } else if self.workbook.is_sheet_streaming(index) {
+                format!("loading, {} rows", sheet.max_rows)
+            } else if sheet.is_loaded {
+                "loaded".to_string()
+            } else {
+                "not loaded".to_string()
+            };
+            lines.push(format!(
+                "{name:<name_width$}  {state:<22}  {:>9}  {:>9}",
+                sheet.data.len(),
+                format_size(sheet.data.estimated_bytes())
+            ));
+        }
+
+        let title = format!(
+            "Memory: about {} in cells, memcap={}",
+            format_size(self.workbook.estimated_bytes()),
+            self.memory_cap
+                .map_or_else(|| "off".to_string(), format_size)
+        );
+        self.open_pager(title, lines);
+    }
+
+    /// The bytes the workbook's cells take up and the `memcap` they reach, if they do
+    pub(crate) fn memory_over_cap(&self) -> Option<(usize, usize)> {
+        let cap = self.memory_cap?;
+        let bytes = self.workbook.estimated_bytes();
+        (bytes >= cap).then_some((bytes, cap))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_size, parse_size};
+
+    #[test]
+    fn sizes_read_with_units_and_show_in_the_largest_one() {
+        assert_eq!(parse_size("512M"), Some(512 << 20));
+        assert_eq!(parse_size("1.5gb"), Some(3 << 29));
+        assert_eq!(parse_size("800 K"), Some(800 << 10));
+        assert_eq!(parse_size("4096"), Some(4096));
+        assert_eq!(parse_size("0"), None);
+        assert_eq!(parse_size("lots"), None);
+
+        assert_eq!(format_size(80), "80 B");
+        assert_eq!(format_size(1536), "1.5 KB");
+        assert_eq!(format_size(512 << 20), "512 MB");
+        assert_eq!(format_size(3 << 29), "1.5 GB");
+    }
+}
@@ -0,0 +1,208 @@
+use std::collections::VecDeque;
+
+use chrono::{DateTime, Local};
+
+use crate::app::AppState;
+
+/// How many messages `:messages` keeps; the oldest are dropped first
+const MAX_LOGGED_MESSAGES: usize = 500;
+
+/// How much a message matters, which sets its color in the notifications panel
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MessageLevel {
+    Info,
+    /// A command was used wrongly or needs confirming, like `:q` with unsaved changes
+    Warning,
+    /// Something the user asked for failed
+    Error,
+}
+
+impl MessageLevel {
+    /// The letter `:messages` marks the level with, as vim's `E` codes do
+    #[must_use]
+    pub fn marker(self) -> char {
+        match self {
+            Self::Info => 'I',
+            Self::Warning => 'W',
+            Self::Error => 'E',
+        }
+    }
+}
+
+/// A message of the notifications panel
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Notification {
+    pub level: MessageLevel,
+    pub text: String,
+}
+
+/// A notification as `:messages` lists it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LoggedMessage {
+    pub time: DateTime<Local>,
+    pub notification: Notification,
+}
+
+/// Every notification of the session, oldest first, while the notifications panel only
+/// shows the last few
+#[derive(Clone, Debug, Default)]
+pub struct MessageLog {
+    messages: VecDeque<LoggedMessage>,
+}
+
+impl MessageLog {
+    pub fn push(&mut self, notification: Notification) {
+        if self.messages.len() == MAX_LOGGED_MESSAGES {
+            self.messages.pop_front();
+        }
+        self.messages.push_back(LoggedMessage {
+            time: Local::now(),
+            notification,
+        });
+    }
+
+    /// Takes back the newest message, when a later one replaces it
+    pub fn pop(&mut self) -> Option<LoggedMessage> {
+        self.messages.pop_back()
+    }
+
+    pub fn clear(&mut self) {
+        self.messages.clear();
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    /// Messages, oldest first
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &LoggedMessage> {
+        self.messages.iter()
+    }
+}
+
+impl AppState<'_> {
+    /// Reports a failure, shown in the theme's error color
+    pub fn add_error(&mut self, message: String) {
+        self.push_notification(MessageLevel::Error, message);
+    }
+
+    /// Reports a command used wrongly, or one that needs confirming, in the warning color
+    pub fn add_warning(&mut self, message: String) {
+        self.push_notification(MessageLevel::Warning, message);
+    }
+
+    /// Takes back the newest notification, from the panel and `:messages`, so a fuller one
+    /// can replace it
+    pub fn retract_notification(&mut self) {
+        self.notification_messages.pop();
+        self.message_log.pop();
+    }
+
+    /// Handles `:messages`: every notification of the session with its time, in a pager
+    /// opened at the newest. `:messages clear` empties the log
+    pub fn handle_messages_command(&mut self, arg: &str) {
+        match arg.trim() {
+            "" => {}
+            "clear" => {
+                self.message_log.clear();
+                self.notification_messages.clear();
+                return;
+            }
+            _ => {
+                self.add_warning("Usage: :messages [clear]".to_string());
+                return;
+            }
+        }
+
+        if self.message_log.is_empty() {
+            self.add_notification("No messages".to_string());
+            return;
+        }
+
+        let lines: Vec<String> = self
+            .message_log
+            .iter()
+            .map(|message| {
+                format!(
+                    "{} {} {}",
+                    message.time.format("%H:%M:%S"),
+                    message.notification.level.marker(),
+                    message.notification.text
+                )
+            })
+            .collect();
+        let title = format!("Messages: {}", lines.len());
+        self.open_pager(title, lines);
+        if let Some(pager) = self.pager.as_mut() {
+            // Drawing clamps this so the newest message ends the page
+            pager.scroll = pager.lines.len();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MessageLevel, MessageLog, Notification, MAX_LOGGED_MESSAGES};
+    use crate::app::{AppState, InputMode};
+    use crate::excel::{Sheet, Workbook};
+    use std::path::PathBuf;
+
+    #[test]
+    fn messages_keeps_notifications_past_the_panel_with_times_and_levels() {
+        let workbook = Workbook::from_sheets_for_test(vec![Sheet::blank("Data".to_string())]);
+        let mut app = AppState::new(workbook, PathBuf::from("test.xlsx")).unwrap();
+        for index in 1..=8 {
+            app.add_notification(format!("Message {index}"));
+        }
+        app.add_error("Save failed: disk full".to_string());
+        assert_eq!(app.notification_messages.len(), app.max_notifications);
+
+        app.input_buffer = "messages".to_string();
+        app.execute_command();
+        assert!(matches!(app.input_mode, InputMode::Pager));
+        let pager = app.pager.as_ref().unwrap();
+        assert_eq!(pager.title, "Messages: 9");
+        assert!(
+            pager.lines[0].ends_with(" I Message 1"),
+            "{}",
+            pager.lines[0]
+        );
+        assert!(pager.lines[8].ends_with(" E Save failed: disk full"));
+        // A time like 09:41:07 leads each line
+        assert_eq!(pager.lines[0].find(' '), Some(8));
+        app.close_pager();
+
+        app.input_buffer = "messages clear".to_string();
+        app.execute_command();
+        assert!(app.message_log.is_empty());
+        app.input_buffer = "messages".to_string();
+        app.execute_command();
+        assert_eq!(
+            app.notification_messages.last().unwrap().text,
+            "No messages"
+        );
+    }
+
+    #[test]
+    fn the_log_drops_its_oldest_messages_when_full() {
+        let mut log = MessageLog::default();
+        for index in 0..=MAX_LOGGED_MESSAGES {
+            log.push(Notification {
+                level: MessageLevel::Info,
+                text: index.to_string(),
+            });
+        }
+        assert_eq!(log.len(), MAX_LOGGED_MESSAGES);
+        assert_eq!(log.iter().next().unwrap().notification.text, "1");
+        assert_eq!(
+            log.pop().unwrap().notification.text,
+            MAX_LOGGED_MESSAGES.to_string()
+        );
+    }
+}
@@ -1,14 +1,65 @@
+mod clipboard;
+mod conditional_format;
 mod edit;
+mod find;
+mod format;
+mod header_row;
 mod help;
+mod history;
+mod hyperlink;
+mod incsearch;
+mod info_panel;
+mod marks;
+mod memory;
+mod messages;
+mod names;
 mod navigation;
+mod onboarding;
+mod options;
+mod pager;
+mod paste;
+mod protection;
+mod reorder;
+mod results;
+mod resume;
+mod row_filter;
 mod search;
+mod search_index;
+mod selection;
+mod session;
 mod sheet;
+mod sheet_load;
+mod split;
 mod state;
+mod substitute;
+mod syntax;
+mod transform;
 mod ui;
 mod undo_manager;
 mod vim;
 mod word;
 
+pub use clipboard::{text_block, Clipboard, ClipboardContent, TextFormat, Yank, YankKind};
 pub use help::*;
+pub use history::InputHistory;
+pub use incsearch::IncSearch;
+pub use info_panel::InfoPanelPosition;
+pub use marks::{Mark, MarkAction};
+pub use memory::{format_size, may_pass_memory_cap, parse_size};
+pub use messages::{LoggedMessage, MessageLevel, MessageLog, Notification};
+pub use navigation::ViewPosition;
+pub use pager::PagerView;
+pub use paste::{FixedWidthImport, PasteOptions, PendingPaste};
+pub use results::{ResultItem, ResultsPanel};
+pub use row_filter::RowFilter;
+pub use search::{SearchPattern, SearchScope};
+pub use search_index::SearchIndex;
+pub use selection::SelectionStats;
+pub use session::{BufferRequest, Session};
+pub use sheet_load::SheetLoad;
+pub use split::{SplitDirection, SplitView, Viewport};
 pub use state::*;
+pub(crate) use substitute::is_substitute_command;
+pub use syntax::{Syntax, TokenKind};
+pub use transform::{CellRange, TextCase};
 pub use vim::*;
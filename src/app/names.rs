@@ -0,0 +1,165 @@
+use crate::app::AppState;
+use crate::excel::{is_valid_defined_name, parse_sheet_range, DefinedName};
+use crate::utils::parse_cell_reference;
+
+impl AppState<'_> {
+    /// Defines `name` for the visual selection, or the current cell, on the current sheet
+    pub fn define_name_for_selection(&mut self, name: &str) {
+        if !is_valid_defined_name(name) {
+            self.add_warning(format!(
+                "Invalid name {name}: start with a letter or _, use letters, digits, _ or ., \
+                 and don't use a cell reference"
+            ));
+            return;
+        }
+
+        let (start, end) = self
+            .selection_range()
+            .unwrap_or((self.selected_cell, self.selected_cell));
+        self.clear_selection();
+
+        let sheet_name = self.workbook.get_current_sheet_name();
+        let defined = DefinedName::for_range(name, &sheet_name, start, end);
+        let refers_to = defined.refers_to.clone();
+        let verb = if self.workbook.define_name(defined) {
+            "Redefined"
+        } else {
+            "Defined"
+        };
+        self.add_notification(format!("{verb} {name} as {refers_to}"));
+    }
+
+    /// Jumps to a defined name, or to a `Sheet!A1:B2` reference, selecting the whole range
+    pub fn goto_name(&mut self, target: &str) {
+        if let Some(cell) = parse_cell_reference(target) {
+            self.jump_to_cell(cell);
+            return;
+        }
+
+        let defined = self.workbook.find_defined_name(target).cloned();
+        let location = match &defined {
+            Some(defined) => defined.target(),
+            None => parse_sheet_range(target),
+        };
+        let Some((sheet_name, start, end)) = location else {
+            match defined {
+                Some(defined) => self.add_notification(format!(
+                    "{} refers to {}, not a cell range",
+                    defined.name, defined.refers_to
+                )),
+                None => self.add_warning(format!("Unknown name: {target}")),
+            }
+            return;
+        };
+
+        let Some(index) = self
+            .workbook
+            .get_sheet_names()
+            .iter()
+            .position(|name| name.eq_ignore_ascii_case(&sheet_name))
+        else {
+            self.add_notification(format!("Sheet {sheet_name} of {target} does not exist"));
+            return;
+        };
+
+        self.clear_selection();
+        self.jump_to_sheet_location(index, Some(start));
+        if start != end {
+            self.selection_anchor = Some(Self::clamp_cell_to_excel_bounds(end));
+        }
+
+        let label = defined.map_or_else(
+            || target.to_string(),
+            |defined| format!("{} ({})", defined.name, defined.refers_to),
+        );
+        self.add_notification(format!("Jumped to {label}"));
+    }
+
+    /// Lists the workbook's defined names and what they refer to
+    pub fn show_defined_names(&mut self) {
+        let names = self.workbook.defined_names();
+        if names.is_empty() {
+            self.add_notification("No defined names. Use :name <label> to add one".to_string());
+            return;
+        }
+
+        let width = names
+            .iter()
+            .map(|d| d.name.chars().count())
+            .max()
+            .unwrap_or(0);
+        let lines = names
+            .iter()
+            .map(|defined| format!("{:<width$}  {}", defined.name, defined.refers_to))
+            .collect();
+        let title = format!("Defined names: {}", names.len());
+        self.open_pager(title, lines);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::app::AppState;
+    use crate::excel::{Cell, DefinedName, Sheet, Workbook};
+    use std::path::PathBuf;
+
+    fn app_with_two_sheets() -> AppState<'static> {
+        let mut data = Sheet::blank("Data".to_string());
+        data.data.set(3, 3, Cell::new("x".to_string(), false));
+        data.max_rows = 3;
+        data.max_cols = 3;
+        let summary = Sheet::blank("Q1 Sales".to_string());
+        let mut workbook = Workbook::from_sheets_for_test(vec![data, summary]);
+        workbook.define_name(DefinedName::new("Rate", "=0.075"));
+
+        AppState::new(workbook, PathBuf::from("test.xlsx")).unwrap()
+    }
+
+    #[test]
+    fn defines_names_for_the_selection_and_jumps_back_to_them() {
+        let mut app = app_with_two_sheets();
+        app.selection_anchor = Some((2, 2));
+        app.selected_cell = (3, 3);
+
+        app.input_buffer = "name Totals".to_string();
+        app.execute_command();
+        assert_eq!(
+            app.workbook.find_defined_name("totals").unwrap().refers_to,
+            "Data!$B$2:$C$3"
+        );
+        assert!(app.selection_anchor.is_none());
+
+        app.input_buffer = "sheet 2".to_string();
+        app.execute_command();
+        app.input_buffer = "goto TOTALS".to_string();
+        app.execute_command();
+        assert_eq!(app.workbook.get_current_sheet_name(), "Data");
+        assert_eq!(app.selected_cell, (2, 2));
+        assert_eq!(app.selection_text().as_deref(), Some("B2:C3"));
+
+        app.goto_name("'Q1 Sales'!D4");
+        assert_eq!(app.workbook.get_current_sheet_name(), "Q1 Sales");
+        assert_eq!(app.selected_cell, (4, 4));
+        assert!(app.selection_anchor.is_none());
+    }
+
+    #[test]
+    fn rejects_bad_names_and_non_range_targets() {
+        let mut app = app_with_two_sheets();
+
+        app.define_name_for_selection("B2");
+        assert_eq!(app.workbook.defined_names().len(), 1);
+
+        app.goto_name("Rate");
+        assert_eq!(
+            app.notification_messages.last().unwrap().text,
+            "Rate refers to 0.075, not a cell range"
+        );
+
+        app.goto_name("Missing");
+        assert_eq!(
+            app.notification_messages.last().unwrap().text,
+            "Unknown name: Missing"
+        );
+    }
+}
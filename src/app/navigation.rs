@@ -1,13 +1,22 @@
 use crate::app::AppState;
 use crate::excel::{EXCEL_MAX_COLS, EXCEL_MAX_ROWS};
-use crate::utils::find_non_empty_cell;
 use crate::utils::Direction;
+use crate::utils::{cell_reference, find_non_empty_cell, index_to_col_name};
+
+/// Where `zt`, `zz` and `zb` put the cursor's row on screen
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ViewPosition {
+    Top,
+    Center,
+    Bottom,
+}
 
 impl AppState<'_> {
     pub fn move_cursor(&mut self, delta_row: isize, delta_col: isize) {
-        // Calculate new position
-        let new_row =
-            (self.selected_cell.0 as isize + delta_row).clamp(1, EXCEL_MAX_ROWS as isize) as usize;
+        // Calculate new position, stepping over rows hidden by :only
+        let new_row = self
+            .step_shown_rows(self.selected_cell.0, delta_row)
+            .min(EXCEL_MAX_ROWS);
         let new_col =
             (self.selected_cell.1 as isize + delta_col).clamp(1, EXCEL_MAX_COLS as isize) as usize;
 
@@ -19,6 +28,7 @@ impl AppState<'_> {
     }
 
     pub fn handle_scrolling(&mut self) {
+        self.snap_to_shown_row();
         let frozen_rows = self.workbook.get_current_sheet().freeze_panes.rows;
         let min_scroll_row = frozen_rows + 1;
 
@@ -27,24 +37,100 @@ impl AppState<'_> {
         }
 
         if self.selected_cell.0 > frozen_rows {
-            let frozen_rows_visible = if self.visible_rows > 1 {
-                frozen_rows.min(self.visible_rows - 1)
-            } else {
-                0
+            let scroll_rows_visible = self.scroll_rows_visible();
+
+            let below_view = match self.filtered_rows_from(self.start_row) {
+                Some(rows) => {
+                    rows.partition_point(|&row| row <= self.selected_cell.0) > scroll_rows_visible
+                }
+                None => self.selected_cell.0 >= self.start_row + scroll_rows_visible,
             };
-            let scroll_rows_visible = self.visible_rows.saturating_sub(frozen_rows_visible).max(1);
 
             if self.selected_cell.0 < self.start_row {
                 self.start_row = self.selected_cell.0.max(min_scroll_row);
-            } else if self.selected_cell.0 >= self.start_row + scroll_rows_visible {
-                self.start_row =
-                    (self.selected_cell.0 - scroll_rows_visible + 1).max(min_scroll_row);
+            } else if below_view {
+                self.start_row = self
+                    .step_shown_rows(self.selected_cell.0, 1 - scroll_rows_visible as isize)
+                    .max(min_scroll_row);
             }
         }
 
         self.handle_column_scrolling();
     }
 
+    /// Rows shown below the frozen panes and the pinned header row
+    fn scroll_rows_visible(&self) -> usize {
+        let frozen_rows = self.workbook.get_current_sheet().freeze_panes.rows
+            + usize::from(self.pinned_header_row().is_some());
+        let frozen_rows_visible = if self.visible_rows > 1 {
+            frozen_rows.min(self.visible_rows - 1)
+        } else {
+            0
+        };
+        self.visible_rows.saturating_sub(frozen_rows_visible).max(1)
+    }
+
+    /// Moves the cursor and the view by `pages` screenfuls, keeping the cursor's place on screen
+    pub fn scroll_pages(&mut self, pages: isize) {
+        self.scroll_rows(pages.saturating_mul(self.scroll_rows_visible() as isize));
+    }
+
+    /// Moves the cursor and the view by `halves` half screenfuls, as `Ctrl+D` and `Ctrl+U` do
+    pub fn scroll_half_pages(&mut self, halves: isize) {
+        let half = (self.scroll_rows_visible() / 2).max(1);
+        self.scroll_rows(halves.saturating_mul(half as isize));
+    }
+
+    fn scroll_rows(&mut self, step: isize) {
+        let frozen_rows = self.workbook.get_current_sheet().freeze_panes.rows;
+        let max_start_row = EXCEL_MAX_ROWS
+            .saturating_sub(self.scroll_rows_visible() - 1)
+            .max(frozen_rows + 1);
+
+        self.start_row = self
+            .step_shown_rows(self.start_row, step)
+            .clamp(frozen_rows + 1, max_start_row);
+        let row = self
+            .step_shown_rows(self.selected_cell.0, step)
+            .clamp(1, EXCEL_MAX_ROWS);
+        self.selected_cell = (row, self.selected_cell.1);
+        self.handle_scrolling();
+    }
+
+    /// Scrolls so the cursor's row sits at the top, middle or bottom of the view, as `zt`,
+    /// `zz` and `zb` do, without moving the cursor
+    pub fn recenter_view(&mut self, position: ViewPosition) {
+        let frozen_rows = self.workbook.get_current_sheet().freeze_panes.rows;
+        let row = self.selected_cell.0;
+        if row <= frozen_rows {
+            return;
+        }
+
+        let rows_visible = self.scroll_rows_visible();
+        let rows_above = match position {
+            ViewPosition::Top => 0,
+            ViewPosition::Center => (rows_visible - 1) / 2,
+            ViewPosition::Bottom => rows_visible - 1,
+        };
+        self.start_row = self
+            .step_shown_rows(row, -(rows_above as isize))
+            .max(frozen_rows + 1);
+    }
+
+    pub fn jump_to_first_cell(&mut self) {
+        self.selected_cell = (1, 1);
+        self.handle_scrolling();
+        self.add_notification("Jumped to A1".to_string());
+    }
+
+    /// Jumps to the bottom-right corner of the used range, like Ctrl+End in Excel
+    pub fn jump_to_last_used_cell(&mut self) {
+        let sheet = self.workbook.get_current_sheet();
+        self.selected_cell = (sheet.max_rows.max(1), sheet.max_cols.max(1));
+        self.handle_scrolling();
+        self.add_notification("Jumped to last used cell".to_string());
+    }
+
     pub fn jump_to_first_row(&mut self) {
         let current_col = self.selected_cell.1;
         self.selected_cell = (1, current_col);
@@ -52,6 +138,66 @@ impl AppState<'_> {
         self.add_notification("Jumped to first row".to_string());
     }
 
+    /// Jumps to `row` in the current column, as `10G` or `10gg` do
+    pub fn jump_to_row(&mut self, row: usize) {
+        self.selected_cell = Self::clamp_cell_to_excel_bounds((row, self.selected_cell.1));
+        self.handle_scrolling();
+        self.add_notification(format!("Jumped to row {}", self.selected_cell.0));
+    }
+
+    /// The row holding column labels: the one set with `:headerrow`, else the last frozen
+    /// row, or row 1 when none are frozen
+    pub fn header_row(&self) -> usize {
+        self.designated_header_row()
+            .unwrap_or_else(|| self.workbook.get_current_sheet().freeze_panes.rows.max(1))
+    }
+
+    /// Moves to the column whose header matches `label`, as `:col` does. An exact,
+    /// case-insensitive match wins; otherwise headers containing `label` are tried, starting
+    /// after the current column so repeating the command steps through them
+    pub fn jump_to_column_by_header(&mut self, label: &str) {
+        let label = label.trim().to_lowercase();
+        let header_row = self.header_row();
+        let max_cols = self.workbook.get_current_sheet().max_cols;
+        let headers: Vec<(usize, String)> = (1..=max_cols)
+            .map(|col| (col, self.get_cell_content(header_row, col)))
+            .collect();
+
+        let current_col = self.selected_cell.1;
+        let exact = headers
+            .iter()
+            .find(|(_, header)| header.trim().to_lowercase() == label);
+        let partial = || {
+            let mut matches = headers
+                .iter()
+                .filter(|(_, header)| header.to_lowercase().contains(&label));
+            let first = matches.clone().next();
+            matches.find(|(col, _)| *col > current_col).or(first)
+        };
+        let Some((col, header)) = exact.or_else(partial) else {
+            self.add_notification(format!(
+                "No header in row {header_row} matches '{}'",
+                label.trim()
+            ));
+            return;
+        };
+
+        let message = format!("Column {}: {header}", index_to_col_name(*col));
+        self.selected_cell = (self.selected_cell.0, *col);
+        self.handle_scrolling();
+        self.add_notification(message);
+    }
+
+    /// Adds a digit to the count typed before a motion, capped at the sheet row limit
+    pub fn push_count_digit(&mut self, digit: u32) {
+        let count = self
+            .pending_count
+            .unwrap_or(0)
+            .saturating_mul(10)
+            .saturating_add(digit as usize);
+        self.pending_count = Some(count.min(EXCEL_MAX_ROWS));
+    }
+
     pub fn jump_to_last_row(&mut self) {
         let sheet = self.workbook.get_current_sheet();
         let current_col = self.selected_cell.1;
@@ -76,15 +222,12 @@ impl AppState<'_> {
 
         let mut first_non_empty_col = 1; // Default to first column
 
-        if current_row < sheet.data.len() {
-            for col in 1..=sheet.max_cols {
-                if col < sheet.data[current_row].len()
-                    && !sheet.data[current_row][col].value.is_empty()
-                {
-                    first_non_empty_col = col;
-                    break;
-                }
-            }
+        if let Some((col, _)) = sheet
+            .data
+            .row_range(current_row, 1, sheet.max_cols)
+            .find(|(_, cell)| !cell.value.is_empty())
+        {
+            first_non_empty_col = col;
         }
 
         self.selected_cell = (current_row, first_non_empty_col);
@@ -123,14 +266,15 @@ impl AppState<'_> {
             let sheet = self.workbook.get_current_sheet();
 
             let (row, col) = self.selected_cell;
-            let is_cell_empty = row >= sheet.data.len()
-                || col >= sheet.data[row].len()
-                || sheet.data[row][col].value.is_empty();
+            let is_cell_empty = sheet.data.cell(row, col).value.is_empty();
 
             let message = if is_cell_empty {
-                format!("Jumped to first non-empty cell ({dir_name})")
+                format!("No more data {dir_name}; jumped to the edge of the used range")
             } else {
-                format!("Jumped to last non-empty cell ({dir_name})")
+                format!(
+                    "Jumped {dir_name} to {}",
+                    cell_reference(self.selected_cell)
+                )
             };
 
             self.add_notification(message);
@@ -199,3 +343,124 @@ impl AppState<'_> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::ViewPosition;
+    use crate::app::AppState;
+    use crate::excel::{Cell, Sheet, Workbook};
+    use std::path::PathBuf;
+
+    fn app_with_used_range(rows: usize, cols: usize) -> AppState<'static> {
+        let mut sheet = Sheet::blank("Data".to_string());
+        for row in 1..=rows {
+            for col in 1..=cols {
+                sheet.data.set(row, col, Cell::new("x".to_string(), false));
+            }
+        }
+        sheet.max_rows = rows;
+        sheet.max_cols = cols;
+        AppState::new(
+            Workbook::from_sheets_for_test(vec![sheet]),
+            PathBuf::from("test.xlsx"),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn page_keys_move_cursor_and_view_by_a_screenful() {
+        let mut app = app_with_used_range(50, 3);
+        app.visible_rows = 10;
+        app.selected_cell = (3, 2);
+
+        app.scroll_pages(1);
+        assert_eq!((app.selected_cell, app.start_row), ((13, 2), 11));
+
+        app.scroll_pages(-1);
+        assert_eq!((app.selected_cell, app.start_row), ((3, 2), 1));
+
+        app.scroll_pages(-1);
+        assert_eq!((app.selected_cell, app.start_row), ((1, 2), 1));
+    }
+
+    #[test]
+    fn half_page_scrolls_move_cursor_and_view_by_half_a_screenful() {
+        let mut app = app_with_used_range(50, 3);
+        app.visible_rows = 10;
+        app.selected_cell = (3, 2);
+
+        app.scroll_half_pages(1);
+        assert_eq!((app.selected_cell, app.start_row), ((8, 2), 6));
+
+        app.scroll_half_pages(2);
+        assert_eq!((app.selected_cell, app.start_row), ((18, 2), 16));
+
+        app.scroll_half_pages(-1);
+        assert_eq!((app.selected_cell, app.start_row), ((13, 2), 11));
+    }
+
+    #[test]
+    fn recentering_scrolls_the_view_around_the_cursor() {
+        let mut app = app_with_used_range(100, 3);
+        app.visible_rows = 10;
+        app.selected_cell = (50, 2);
+        app.handle_scrolling();
+
+        app.recenter_view(ViewPosition::Center);
+        assert_eq!((app.selected_cell, app.start_row), ((50, 2), 46));
+        app.recenter_view(ViewPosition::Top);
+        assert_eq!(app.start_row, 50);
+        app.recenter_view(ViewPosition::Bottom);
+        assert_eq!(app.start_row, 41);
+
+        app.selected_cell = (3, 2);
+        app.recenter_view(ViewPosition::Bottom);
+        assert_eq!(app.start_row, 1);
+    }
+
+    #[test]
+    fn col_jumps_to_the_column_with_a_matching_header() {
+        let mut app = app_with_used_range(5, 4);
+        let headers = ["Region", "Total Q3 Net", "Total Q3 Gross", "Notes"];
+        for (col, header) in headers.iter().enumerate() {
+            app.workbook.get_current_sheet_mut().data.set(
+                1,
+                col + 1,
+                Cell::new((*header).to_string(), false),
+            );
+        }
+        app.selected_cell = (4, 1);
+
+        app.jump_to_column_by_header("total q3 gross");
+        assert_eq!(app.selected_cell, (4, 3));
+
+        app.jump_to_column_by_header("total");
+        assert_eq!(app.selected_cell, (4, 2));
+        app.jump_to_column_by_header("total");
+        assert_eq!(app.selected_cell, (4, 3));
+        assert_eq!(
+            app.notification_messages.last().unwrap().text,
+            "Column C: Total Q3 Gross"
+        );
+
+        app.jump_to_column_by_header("Margin");
+        assert_eq!(app.selected_cell, (4, 3));
+        assert_eq!(
+            app.notification_messages.last().unwrap().text,
+            "No header in row 1 matches 'margin'"
+        );
+    }
+
+    #[test]
+    fn ctrl_home_and_end_jump_to_corners_of_used_range() {
+        let mut app = app_with_used_range(40, 7);
+        app.selected_cell = (5, 3);
+
+        app.jump_to_last_used_cell();
+        assert_eq!(app.selected_cell, (40, 7));
+
+        app.jump_to_first_cell();
+        assert_eq!(app.selected_cell, (1, 1));
+        assert_eq!(app.start_row, 1);
+    }
+}
@@ -0,0 +1,105 @@
+use anyhow::Result;
+use std::path::PathBuf;
+
+use crate::app::{AppState, InputMode};
+use crate::excel::{open_workbook, write_sample_workbook};
+
+impl AppState<'_> {
+    /// Shows the welcome overlay when excel-cli has no configuration directory yet
+    pub fn show_onboarding_if_first_run(&mut self) {
+        self.show_onboarding_unless_configured(crate::config::config_dir());
+    }
+
+    pub(crate) fn show_onboarding_unless_configured(&mut self, config_dir: Option<PathBuf>) {
+        let Some(dir) = config_dir else {
+            return;
+        };
+        if dir.exists() {
+            return;
+        }
+
+        self.onboarding_config_dir = Some(dir);
+        self.input_mode = InputMode::Onboarding;
+    }
+
+    /// Closes the welcome overlay and creates the configuration directory so it stays closed
+    pub fn finish_onboarding(&mut self) {
+        self.restore_mode_after_overlay();
+        if let Some(dir) = self.onboarding_config_dir.take() {
+            if let Err(e) = std::fs::create_dir_all(&dir) {
+                self.add_error(format!(
+                    "Could not create config directory {}: {e}",
+                    dir.display()
+                ));
+            }
+        }
+    }
+
+    /// Writes the bundled sample workbook to the temp directory and switches to it
+    pub fn open_sample_workbook(&mut self) {
+        self.finish_onboarding();
+        let path = std::env::temp_dir().join("excel-cli-sample.xlsx");
+        match self.load_sample_workbook(path.clone()) {
+            Ok(()) => self.add_notification(format!(
+                "Opened sample workbook {}; :help lists every key",
+                path.display()
+            )),
+            Err(e) => self.add_error(format!("Failed to open sample workbook: {e}")),
+        }
+    }
+
+    fn load_sample_workbook(&mut self, path: PathBuf) -> Result<()> {
+        write_sample_workbook(&path)?;
+        let workbook = open_workbook(&path, false)?;
+        let notifications = std::mem::take(&mut self.notification_messages);
+        let message_log = std::mem::take(&mut self.message_log);
+        let keymap = std::mem::take(&mut self.keymap);
+        *self = AppState::new(workbook, path)?;
+        self.notification_messages = notifications;
+        self.message_log = message_log;
+        self.keymap = keymap;
+        Ok(())
+    }
+
+    fn restore_mode_after_overlay(&mut self) {
+        let index = self.workbook.get_current_sheet_index();
+        self.input_mode =
+            if self.workbook.is_lazy_loading() && !self.workbook.is_sheet_loaded(index) {
+                InputMode::LazyLoading
+            } else {
+                InputMode::Normal
+            };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::app::{AppState, InputMode};
+    use crate::excel::{Sheet, Workbook};
+    use std::path::PathBuf;
+
+    fn app() -> AppState<'static> {
+        let workbook = Workbook::from_sheets_for_test(vec![Sheet::blank("Sheet1".to_string())]);
+        AppState::new(workbook, PathBuf::from("test.xlsx")).unwrap()
+    }
+
+    #[test]
+    fn onboarding_shows_until_config_directory_exists() {
+        let dir = std::env::temp_dir().join("excel_cli_onboarding_test");
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let mut first_run = app();
+        first_run.show_onboarding_unless_configured(Some(dir.clone()));
+        assert!(matches!(first_run.input_mode, InputMode::Onboarding));
+
+        first_run.finish_onboarding();
+        assert!(matches!(first_run.input_mode, InputMode::Normal));
+        assert!(dir.is_dir());
+
+        let mut later_run = app();
+        later_run.show_onboarding_unless_configured(Some(dir.clone()));
+        assert!(matches!(later_run.input_mode, InputMode::Normal));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,332 @@
+use std::collections::BTreeMap;
+
+use crate::app::{format_size, parse_size, AppState, InfoPanelPosition, SearchScope};
+use crate::config::OptionSetting;
+
+impl AppState<'_> {
+    /// Handles `:set`, which takes vim-style settings: `name` and `noname` turn an option on
+    /// and off, `name!` toggles it, `name=value` sets one with a value and `name?` shows
+    /// either kind. With no settings, lists every option
+    pub fn handle_set_command(&mut self, args: &str) {
+        let args = args.trim();
+        if args.is_empty() {
+            let options = self.describe_options();
+            self.add_notification(format!("Options: {options}"));
+            return;
+        }
+
+        let mut shown = Vec::new();
+        for setting in args.split_whitespace() {
+            match self.apply_setting(setting) {
+                Ok(description) => shown.push(description),
+                Err(message) => {
+                    self.add_warning(message);
+                    return;
+                }
+            }
+        }
+        self.add_notification(shown.join(" "));
+    }
+
+    /// Applies the `[options]` section of the config file, as `:set` would
+    pub fn apply_option_settings(
+        &mut self,
+        options: &BTreeMap<String, OptionSetting>,
+    ) -> Result<(), String> {
+        for (name, value) in options {
+            let setting = match value {
+                OptionSetting::Switch(true) => name.clone(),
+                OptionSetting::Switch(false) => format!("no{name}"),
+                OptionSetting::Value(value) => format!("{name}={value}"),
+            };
+            self.apply_setting(&setting)
+                .map_err(|e| format!("{e} in the config file's [options]"))?;
+        }
+        Ok(())
+    }
+
+    /// Applies one setting, returning how the option now reads
+    fn apply_setting(&mut self, setting: &str) -> Result<String, String> {
+        if let Some((name, value)) = setting.split_once('=') {
+            return self.set_value_option(name, value);
+        }
+        // Options with a value are shown by name alone, as in vim
+        let name = setting.strip_suffix('?').unwrap_or(setting);
+        if let Some(value) = self.value_option(name) {
+            return Ok(format!("{name}={value}"));
+        }
+
+        let (name, change): (&str, fn(bool) -> bool) = if let Some(name) = setting.strip_suffix('?')
+        {
+            (name, |value| value)
+        } else if let Some(name) = setting.strip_suffix('!') {
+            (name, |value| !value)
+        } else if let Some(name) = setting.strip_prefix("inv") {
+            (name, |value| !value)
+        } else if let Some(name) = setting
+            .strip_prefix("no")
+            .filter(|name| self.bool_option(name).is_some())
+        {
+            (name, |_| false)
+        } else {
+            (setting, |_| true)
+        };
+
+        let Some(option) = self.bool_option(name) else {
+            return Err(format!("Unknown option: {name}"));
+        };
+        let before = *option;
+        *option = change(before);
+        let after = *option;
+
+        if after != before {
+            self.refresh_search_results();
+        }
+        Ok(describe_bool(name, after))
+    }
+
+    fn set_value_option(&mut self, name: &str, value: &str) -> Result<String, String> {
+        match name {
+            "searchscope" => {
+                self.search_scope = SearchScope::parse(value).ok_or_else(|| {
+                    format!("searchscope is sheet, column or selection, not {value}")
+                })?;
+            }
+            "infopanelpos" => {
+                self.info_panel_position = InfoPanelPosition::parse(value)
+                    .ok_or_else(|| format!("infopanelpos is bottom or right, not {value}"))?;
+            }
+            "memcap" => {
+                self.memory_cap = match value {
+                    "off" | "0" => None,
+                    _ => Some(parse_size(value).ok_or_else(|| {
+                        format!("memcap is a size like 512M or 2G, or off, not {value}")
+                    })?),
+                };
+                self.release_streams_below_memory_cap();
+            }
+            "undolevels" | "ul" => {
+                let levels = value
+                    .parse()
+                    .ok()
+                    .filter(|levels| *levels > 0)
+                    .ok_or_else(|| format!("undolevels is a number of steps, not {value}"))?;
+                let max_bytes = self.undo_history.max_bytes();
+                self.undo_history.set_limits(levels, max_bytes);
+            }
+            "undomem" => {
+                let max_bytes = match value {
+                    "off" | "0" => None,
+                    _ => Some(parse_size(value).ok_or_else(|| {
+                        format!("undomem is a size like 256M or 1G, or off, not {value}")
+                    })?),
+                };
+                let levels = self.undo_history.max_steps();
+                self.undo_history.set_limits(levels, max_bytes);
+            }
+            _ if self.bool_option(name).is_some() => {
+                return Err(format!("Option {name} is on or off and takes no value"));
+            }
+            _ => return Err(format!("Unknown option: {name}")),
+        }
+        let value = self.value_option(name).unwrap_or_default();
+        Ok(format!("{name}={value}"))
+    }
+
+    fn value_option(&self, name: &str) -> Option<String> {
+        match name {
+            "searchscope" => Some(self.search_scope.name().to_string()),
+            "infopanelpos" => Some(self.info_panel_position.name().to_string()),
+            "memcap" => Some(describe_size(self.memory_cap)),
+            "undolevels" | "ul" => Some(self.undo_history.max_steps().to_string()),
+            "undomem" => Some(describe_size(self.undo_history.max_bytes())),
+            _ => None,
+        }
+    }
+
+    fn bool_option(&mut self, name: &str) -> Option<&mut bool> {
+        match name {
+            "matchcell" => Some(&mut self.match_cell),
+            "incsearch" | "is" => Some(&mut self.incremental_search),
+            "searchindex" => Some(&mut self.use_search_index),
+            "wrap" => Some(&mut self.wrap_cells),
+            "stripes" => Some(&mut self.stripes),
+            "crosshair" => Some(&mut self.crosshair),
+            "gridlines" => Some(&mut self.gridlines),
+            "showformulas" => Some(&mut self.show_formulas),
+            "cellcolors" => Some(&mut self.cell_colors),
+            "infopanel" => Some(&mut self.show_info_panel),
+            "savehistory" => Some(&mut self.save_history),
+            _ => None,
+        }
+    }
+
+    fn describe_options(&self) -> String {
+        [
+            describe_bool("matchcell", self.match_cell),
+            describe_bool("incsearch", self.incremental_search),
+            describe_bool("searchindex", self.use_search_index),
+            describe_bool("wrap", self.wrap_cells),
+            describe_bool("stripes", self.stripes),
+            describe_bool("crosshair", self.crosshair),
+            describe_bool("gridlines", self.gridlines),
+            describe_bool("showformulas", self.show_formulas),
+            describe_bool("cellcolors", self.cell_colors),
+            describe_bool("infopanel", self.show_info_panel),
+            describe_bool("savehistory", self.save_history),
+            format!("searchscope={}", self.search_scope.name()),
+            format!("infopanelpos={}", self.info_panel_position.name()),
+            format!("memcap={}", describe_size(self.memory_cap)),
+            format!("undolevels={}", self.undo_history.max_steps()),
+            format!("undomem={}", describe_size(self.undo_history.max_bytes())),
+        ]
+        .join(" ")
+    }
+}
+
+/// A size as `:set` shows it, like `512MB`, or `off` for none
+fn describe_size(size: Option<usize>) -> String {
+    size.map_or_else(
+        || "off".to_string(),
+        |size| format_size(size).replace(' ', ""),
+    )
+}
+
+fn describe_bool(name: &str, value: bool) -> String {
+    if value {
+        name.to_string()
+    } else {
+        format!("no{name}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::app::{AppState, SearchScope};
+    use crate::config::OptionSetting;
+    use crate::excel::{Sheet, Workbook};
+    use std::path::PathBuf;
+
+    #[test]
+    fn set_turns_options_on_off_and_reports_them() {
+        let workbook = Workbook::from_sheets_for_test(vec![Sheet::blank("Data".to_string())]);
+        let mut app = AppState::new(workbook, PathBuf::from("test.xlsx")).unwrap();
+        for (row, value) in ["2023", "2023-01-05", "FY2023"].into_iter().enumerate() {
+            app.workbook
+                .set_cell_value(row + 1, 1, value.to_string())
+                .unwrap();
+        }
+        app.search_query = "2023".to_string();
+        app.search_results = app.find_all_matches("2023");
+        assert_eq!(app.search_results.len(), 3);
+
+        app.handle_set_command("matchcell");
+        assert!(app.match_cell);
+        assert_eq!(app.search_results, vec![(1, 1)]);
+        assert_eq!(app.notification_messages.last().unwrap().text, "matchcell");
+
+        app.handle_set_command("matchcell!");
+        assert!(!app.match_cell);
+        assert_eq!(app.search_results.len(), 3);
+        app.handle_set_command("invmatchcell");
+        app.handle_set_command("nomatchcell");
+        assert!(!app.match_cell);
+
+        app.handle_set_command("");
+        assert_eq!(
+            app.notification_messages.last().unwrap().text,
+            "Options: nomatchcell incsearch searchindex nowrap nostripes nocrosshair gridlines noshowformulas cellcolors infopanel savehistory searchscope=sheet infopanelpos=bottom memcap=off undolevels=1000 undomem=256MB"
+        );
+        app.handle_set_command("matchcell?");
+        assert_eq!(
+            app.notification_messages.last().unwrap().text,
+            "nomatchcell"
+        );
+        app.handle_set_command("bogus");
+        assert_eq!(
+            app.notification_messages.last().unwrap().text,
+            "Unknown option: bogus"
+        );
+    }
+
+    #[test]
+    fn config_options_apply_like_set() {
+        let workbook = Workbook::from_sheets_for_test(vec![Sheet::blank("Data".to_string())]);
+        let mut app = AppState::new(workbook, PathBuf::from("test.xlsx")).unwrap();
+        let options = [
+            ("stripes", OptionSetting::Switch(true)),
+            ("gridlines", OptionSetting::Switch(false)),
+            ("searchscope", OptionSetting::Value("column".to_string())),
+        ]
+        .into_iter()
+        .map(|(name, value)| (name.to_string(), value))
+        .collect();
+        app.apply_option_settings(&options).unwrap();
+        assert!(app.stripes && !app.gridlines);
+        assert_eq!(app.search_scope, SearchScope::Column);
+
+        let options = [("zebra".to_string(), OptionSetting::Switch(true))]
+            .into_iter()
+            .collect();
+        assert_eq!(
+            app.apply_option_settings(&options),
+            Err("Unknown option: zebra in the config file's [options]".to_string())
+        );
+    }
+
+    #[test]
+    fn search_scope_limits_searches_to_the_column_or_selection() {
+        let workbook = Workbook::from_sheets_for_test(vec![Sheet::blank("Data".to_string())]);
+        let mut app = AppState::new(workbook, PathBuf::from("test.xlsx")).unwrap();
+        for row in 1..=4 {
+            for col in 1..=3 {
+                app.workbook
+                    .set_cell_value(row, col, format!("2023-0{row}-0{col}"))
+                    .unwrap();
+            }
+        }
+        let search = |app: &mut AppState, query: &str| {
+            app.start_search_forward();
+            app.text_area.insert_str(query);
+            app.execute_search();
+        };
+
+        app.handle_set_command("searchscope=column");
+        assert_eq!(app.search_scope, SearchScope::Column);
+        app.selected_cell = (1, 2);
+        search(&mut app, "2023");
+        assert_eq!(app.search_results, vec![(1, 2), (2, 2), (3, 2), (4, 2)]);
+        assert_eq!(
+            app.notification_messages.last().unwrap().text,
+            "4 matches found for: 2023 in column B"
+        );
+
+        app.handle_set_command("searchscope=selection");
+        app.selected_cell = (2, 1);
+        app.anchor_selection();
+        app.selected_cell = (3, 2);
+        search(&mut app, "2023");
+        assert_eq!(app.search_results, vec![(2, 1), (2, 2), (3, 1), (3, 2)]);
+        assert!(app.selection_anchor.is_none());
+        assert_eq!(
+            app.notification_messages.last().unwrap().text,
+            "4 matches found for: 2023 in A2:B3"
+        );
+
+        search(&mut app, "2023");
+        assert_eq!(app.search_results.len(), 12);
+
+        app.handle_set_command("searchscope?");
+        assert_eq!(
+            app.notification_messages.last().unwrap().text,
+            "searchscope=selection"
+        );
+        app.handle_set_command("searchscope=row");
+        assert_eq!(app.search_scope, SearchScope::Selection);
+        app.handle_set_command("matchcell=1");
+        assert_eq!(
+            app.notification_messages.last().unwrap().text,
+            "Option matchcell is on or off and takes no value"
+        );
+    }
+}
@@ -0,0 +1,487 @@
+use std::collections::HashMap;
+
+use crate::app::find::is_error_cell;
+use crate::app::{AppState, InputMode, Syntax, TokenKind};
+use crate::utils::{cell_reference, index_to_col_name, split_to_width, text_width};
+
+/// Scrollable full-screen view for command output that does not fit in the notification area
+pub struct PagerView {
+    pub title: String,
+    pub lines: Vec<String>,
+    pub scroll: usize,
+    pub visible_lines: usize,
+    /// Query being typed after `/`, if the search prompt is open
+    pub search_input: Option<String>,
+    pub query: String,
+    /// Indices of lines containing `query`
+    pub matches: Vec<usize>,
+    pub current_match: Option<usize>,
+    /// Text re-wrapped into `lines` whenever the pager's width changes, for views of long
+    /// text rather than lists
+    wrap_source: Option<Vec<String>>,
+    wrap_width: usize,
+    /// The language of the text from a line of `wrap_source` on, colored when drawn
+    syntax: Option<(Syntax, usize)>,
+    /// Colored pieces of each line from `highlight_start` on
+    highlighted: Vec<Vec<(TokenKind, String)>>,
+    highlight_start: usize,
+}
+
+impl PagerView {
+    #[must_use]
+    pub fn new(title: String, lines: Vec<String>) -> Self {
+        Self {
+            title,
+            lines,
+            scroll: 0,
+            visible_lines: 20,
+            search_input: None,
+            query: String::new(),
+            matches: Vec::new(),
+            current_match: None,
+            wrap_source: None,
+            wrap_width: 0,
+            syntax: None,
+            highlighted: Vec::new(),
+            highlight_start: 0,
+        }
+    }
+
+    /// A pager that wraps `lines` to its width instead of cutting them off
+    #[must_use]
+    pub fn wrapped(title: String, lines: Vec<String>) -> Self {
+        Self {
+            wrap_source: Some(lines),
+            ..Self::new(title, Vec::new())
+        }
+    }
+
+    /// A wrapping pager whose text from line `first_line` on is in `syntax`, colored when
+    /// drawn
+    #[must_use]
+    pub fn wrapped_with_syntax(
+        title: String,
+        lines: Vec<String>,
+        syntax: Syntax,
+        first_line: usize,
+    ) -> Self {
+        Self {
+            syntax: Some((syntax, first_line)),
+            ..Self::wrapped(title, lines)
+        }
+    }
+
+    /// Wraps the text of a wrapping pager to `width` columns, keeping each line's
+    /// indentation on the lines it continues onto
+    pub fn wrap_to(&mut self, width: usize) {
+        let Some(source) = &self.wrap_source else {
+            return;
+        };
+        if width == self.wrap_width || width == 0 {
+            return;
+        }
+        self.wrap_width = width;
+        let wrapped: Vec<Vec<String>> = source.iter().map(|line| wrap_line(line, width)).collect();
+        if let Some((syntax, first_line)) = self.syntax {
+            self.highlight_start = wrapped.iter().take(first_line).map(Vec::len).sum();
+            let lines: Vec<&String> = wrapped.iter().skip(first_line).flatten().collect();
+            self.highlighted = syntax.highlight(&lines);
+        }
+        self.lines = wrapped.into_iter().flatten().collect();
+        if !self.query.is_empty() {
+            let query = self.query.clone();
+            self.search(&query);
+        }
+    }
+
+    /// The colored pieces of line `index`, when it is in a language the pager colors
+    #[must_use]
+    pub fn highlighted_line(&self, index: usize) -> Option<&[(TokenKind, String)]> {
+        let offset = index.checked_sub(self.highlight_start)?;
+        self.highlighted.get(offset).map(Vec::as_slice)
+    }
+
+    #[must_use]
+    pub fn max_scroll(&self) -> usize {
+        self.lines.len().saturating_sub(self.visible_lines.max(1))
+    }
+
+    pub fn scroll_by(&mut self, delta: isize) {
+        self.scroll = self
+            .scroll
+            .saturating_add_signed(delta)
+            .min(self.max_scroll());
+    }
+
+    pub fn scroll_to_top(&mut self) {
+        self.scroll = 0;
+    }
+
+    pub fn scroll_to_bottom(&mut self) {
+        self.scroll = self.max_scroll();
+    }
+
+    /// Finds all lines containing `query` (case-insensitive) and jumps to the first one at or
+    /// below the current scroll position
+    pub fn search(&mut self, query: &str) {
+        self.query = query.to_string();
+        let query = query.to_lowercase();
+        self.matches = if query.is_empty() {
+            Vec::new()
+        } else {
+            self.lines
+                .iter()
+                .enumerate()
+                .filter(|(_, line)| line.to_lowercase().contains(&query))
+                .map(|(index, _)| index)
+                .collect()
+        };
+
+        self.current_match = None;
+        let start = self
+            .matches
+            .iter()
+            .position(|&line| line >= self.scroll)
+            .unwrap_or(0);
+        if !self.matches.is_empty() {
+            self.show_match(start);
+        }
+    }
+
+    /// Moves to the next (or previous) match, wrapping around
+    pub fn next_match(&mut self, forward: bool) {
+        let len = self.matches.len();
+        if len == 0 {
+            return;
+        }
+        let index = match (self.current_match, forward) {
+            (Some(index), true) => (index + 1) % len,
+            (Some(index), false) => (index + len - 1) % len,
+            (None, true) => 0,
+            (None, false) => len - 1,
+        };
+        self.show_match(index);
+    }
+
+    fn show_match(&mut self, index: usize) {
+        self.current_match = Some(index);
+        let line = self.matches[index];
+        if line < self.scroll || line >= self.scroll + self.visible_lines.max(1) {
+            self.scroll = line.min(self.max_scroll());
+        }
+    }
+}
+
+/// Breaks `line` at spaces into pieces at most `width` columns wide, splitting words that do
+/// not fit on a line of their own
+fn wrap_line(line: &str, width: usize) -> Vec<String> {
+    if text_width(line) <= width {
+        return vec![line.to_string()];
+    }
+
+    let body = line.trim_start();
+    let mut indent = &line[..line.len() - body.len()];
+    if text_width(indent) * 2 > width {
+        indent = "";
+    }
+    let room = width - text_width(indent);
+
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    let mut used = 0;
+    for word in body.split(' ') {
+        let word_width = text_width(word);
+        if used > 0 && used + 1 + word_width <= room {
+            current.push(' ');
+            current.push_str(word);
+            used += 1 + word_width;
+            continue;
+        }
+        if used > 0 {
+            lines.push(format!("{indent}{}", std::mem::take(&mut current)));
+            used = 0;
+        }
+        let mut pieces = split_to_width(word, room).into_iter().peekable();
+        while let Some(piece) = pieces.next() {
+            if pieces.peek().is_some() {
+                lines.push(format!("{indent}{piece}"));
+            } else {
+                current.push_str(piece);
+                used = text_width(piece);
+            }
+        }
+    }
+    lines.push(format!("{indent}{current}"));
+    lines
+}
+
+impl AppState<'_> {
+    pub fn open_pager(&mut self, title: String, lines: Vec<String>) {
+        self.pager = Some(PagerView::new(title, lines));
+        self.input_mode = InputMode::Pager;
+    }
+
+    pub fn close_pager(&mut self) {
+        self.pager = None;
+        self.input_mode = InputMode::Normal;
+    }
+
+    /// Handles `K` and `:show`: the current cell's whole value in a wrapping pager, with
+    /// JSON and XML indented and colored, and its formula above it
+    pub fn show_cell_content(&mut self) {
+        let (row, col) = self.selected_cell;
+        let content = self.get_cell_content(row, col);
+        let formula = self
+            .cell_formula(row, col)
+            .filter(|formula| *formula != content);
+        let cell = cell_reference(self.selected_cell);
+        if content.is_empty() && formula.is_none() {
+            self.add_notification(format!("Cell {cell} is empty"));
+            return;
+        }
+
+        let mut lines = Vec::new();
+        if let Some(formula) = formula {
+            lines.push(format!("Formula: {formula}"));
+            lines.push(String::new());
+        }
+        let first_line = lines.len();
+        let structured = Syntax::pretty_print(&content);
+        let kind = structured
+            .as_ref()
+            .map_or("text", |(syntax, _)| syntax.name());
+        let title = format!("Cell {cell} ({kind})");
+        self.pager = Some(match structured {
+            Some((syntax, pretty)) => {
+                lines.extend(pretty.lines().map(str::to_string));
+                PagerView::wrapped_with_syntax(title, lines, syntax, first_line)
+            }
+            None => {
+                lines.extend(content.lines().map(str::to_string));
+                PagerView::wrapped(title, lines)
+            }
+        });
+        self.input_mode = InputMode::Pager;
+    }
+
+    /// Lists each column letter with its header from the header row
+    pub fn show_headers(&mut self) {
+        let max_cols = self.workbook.get_current_sheet().max_cols;
+        let header_row = self.header_row();
+        let lines = (1..=max_cols)
+            .map(|col| {
+                format!(
+                    "{:<4} {}",
+                    index_to_col_name(col),
+                    self.get_cell_content(header_row, col)
+                )
+            })
+            .collect();
+
+        let title = format!("Headers: {}", self.workbook.get_current_sheet_name());
+        self.open_pager(title, lines);
+    }
+
+    /// Counts distinct values in a column, most frequent first
+    pub fn show_frequencies(&mut self, col: usize) {
+        let sheet = self.workbook.get_current_sheet();
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for row in 1..=sheet.max_rows {
+            let value = sheet.data.cell(row, col).value.as_str();
+            *counts.entry(value).or_default() += 1;
+        }
+
+        let mut entries: Vec<(&str, usize)> = counts.into_iter().collect();
+        entries.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| self.sort_mode.compare(a.0, b.0)));
+
+        let lines = entries
+            .iter()
+            .map(|(value, count)| {
+                let value = if value.is_empty() { "(blank)" } else { value };
+                format!("{count:>8}  {value}")
+            })
+            .collect();
+
+        let title = format!(
+            "Frequencies: column {} ({} distinct)",
+            index_to_col_name(col),
+            entries.len()
+        );
+        self.open_pager(title, lines);
+    }
+
+    /// Lists cells holding Excel error values such as #DIV/0! or #N/A
+    pub fn show_errors(&mut self) {
+        let sheet = self.workbook.get_current_sheet();
+        let mut lines = Vec::new();
+        for ((row, col), cell) in sheet.data.iter() {
+            if is_error_cell(cell) {
+                lines.push(format!("{:<8} {}", cell_reference((row, col)), cell.value));
+            }
+        }
+
+        if lines.is_empty() {
+            self.add_notification("No error values found".to_string());
+            return;
+        }
+
+        let title = format!("Errors: {} cells", lines.len());
+        self.open_pager(title, lines);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PagerView;
+    use crate::app::{AppState, InputMode, TokenKind};
+    use crate::excel::{
+        Cell, CellGrid, ColumnFormats, FreezePanes, Sheet, SheetProtection, SheetVisibility,
+        Workbook,
+    };
+    use std::path::PathBuf;
+
+    fn pager(lines: usize) -> PagerView {
+        let mut pager = PagerView::new(
+            "Test".to_string(),
+            (1..=lines).map(|line| format!("line {line}")).collect(),
+        );
+        pager.visible_lines = 10;
+        pager
+    }
+
+    #[test]
+    fn scrolling_is_clamped_to_last_page() {
+        let mut pager = pager(25);
+
+        pager.scroll_by(100);
+        assert_eq!(pager.scroll, 15);
+        pager.scroll_by(-4);
+        assert_eq!(pager.scroll, 11);
+        pager.scroll_to_top();
+        assert_eq!(pager.scroll, 0);
+    }
+
+    #[test]
+    fn search_scrolls_to_matches_and_wraps() {
+        let mut pager = pager(25);
+
+        pager.search("LINE 2");
+        assert_eq!(pager.matches, vec![1, 19, 20, 21, 22, 23, 24]);
+        assert_eq!(pager.scroll, 0);
+
+        pager.next_match(true);
+        assert_eq!(pager.scroll, 15);
+        pager.next_match(false);
+        pager.next_match(false);
+        assert_eq!(pager.current_match, Some(6));
+    }
+
+    #[test]
+    fn freq_command_opens_pager_with_counts_most_frequent_first() {
+        let mut data = CellGrid::new();
+        for (row, value) in ["b", "a", "b", "", "#N/A"].iter().enumerate() {
+            data.set(row + 1, 1, Cell::new((*value).to_string(), false));
+        }
+        let sheet = Sheet {
+            name: "Data".to_string(),
+            data,
+            max_rows: 5,
+            max_cols: 1,
+            is_loaded: true,
+            freeze_panes: FreezePanes::none(),
+            column_formats: ColumnFormats::new(),
+            protection: SheetProtection::Unprotected,
+            visibility: SheetVisibility::Visible,
+            tab_color: None,
+            conditional_formats: Vec::new(),
+        };
+        let mut app = AppState::new(
+            Workbook::from_sheets_for_test(vec![sheet]),
+            PathBuf::from("test.xlsx"),
+        )
+        .unwrap();
+
+        app.input_buffer = "freq A".to_string();
+        app.execute_command();
+
+        assert!(matches!(app.input_mode, InputMode::Pager));
+        let pager = app.pager.as_ref().unwrap();
+        assert_eq!(pager.title, "Frequencies: column A (4 distinct)");
+        assert_eq!(pager.lines[0], "       2  b");
+        assert_eq!(pager.lines[1], "       1  (blank)");
+        assert_eq!(pager.lines[2], "       1  #N/A");
+
+        app.close_pager();
+        app.input_buffer = "errors".to_string();
+        app.execute_command();
+        assert_eq!(app.pager.as_ref().unwrap().lines, vec!["A5       #N/A"]);
+    }
+
+    #[test]
+    fn show_opens_the_cell_wrapped_with_json_and_xml_indented_and_colored() {
+        let mut app = AppState::new(
+            Workbook::from_sheets_for_test(vec![Sheet::blank("Data".to_string())]),
+            PathBuf::from("test.xlsx"),
+        )
+        .unwrap();
+        app.workbook
+            .set_cell_value(1, 1, r#"{"id":7,"tags":["a","b"]}"#.to_string())
+            .unwrap();
+        app.workbook
+            .set_cell_value(2, 1, "the quick brown fox jumps over".to_string())
+            .unwrap();
+
+        app.input_buffer = "show".to_string();
+        app.execute_command();
+        assert!(matches!(app.input_mode, InputMode::Pager));
+        let pager = app.pager.as_mut().unwrap();
+        assert_eq!(pager.title, "Cell A1 (JSON)");
+        pager.wrap_to(40);
+        assert_eq!(pager.lines[..3], ["{", "  \"id\": 7,", "  \"tags\": ["]);
+        assert_eq!(
+            pager.highlighted_line(1).unwrap()[1],
+            (TokenKind::Key, "\"id\"".to_string())
+        );
+
+        app.close_pager();
+        app.workbook
+            .set_cell_value(4, 1, "<a><b>1</b></a>".to_string())
+            .unwrap();
+        app.selected_cell = (4, 1);
+        app.show_cell_content();
+        let pager = app.pager.as_mut().unwrap();
+        assert_eq!(pager.title, "Cell A4 (XML)");
+        pager.wrap_to(40);
+        assert_eq!(pager.lines, ["<a>", "  <b>1</b>", "</a>"]);
+        assert_eq!(
+            pager.highlighted_line(1).unwrap()[2],
+            (TokenKind::Tag, "b".to_string())
+        );
+
+        app.close_pager();
+        app.selected_cell = (2, 1);
+        app.show_cell_content();
+        let pager = app.pager.as_mut().unwrap();
+        assert_eq!(pager.title, "Cell A2 (text)");
+        pager.wrap_to(12);
+        assert_eq!(pager.lines, ["the quick", "brown fox", "jumps over"]);
+
+        app.close_pager();
+        app.selected_cell = (3, 1);
+        app.show_cell_content();
+        assert!(app.pager.is_none());
+        assert_eq!(
+            app.notification_messages.last().unwrap().text,
+            "Cell A3 is empty"
+        );
+    }
+
+    #[test]
+    fn wrapping_keeps_indentation_and_splits_long_words() {
+        assert_eq!(
+            super::wrap_line("    \"key\": \"a long value\"", 16),
+            ["    \"key\": \"a", "    long value\""]
+        );
+        assert_eq!(super::wrap_line("abcdefgh", 3), ["abc", "def", "gh"]);
+    }
+}
@@ -0,0 +1,600 @@
+use crate::actions::{ActionType, RangeCellChange};
+use crate::app::clipboard::block_text;
+use crate::app::{text_block, AppState, ClipboardContent, InputMode, Yank, YankKind};
+use crate::excel::{Cell, EXCEL_MAX_COLS, EXCEL_MAX_ROWS};
+use crate::utils::cell_reference;
+
+/// How much of a register's values `:reg` shows
+const REGISTER_PREVIEW_CHARS: usize = 60;
+
+/// State of the column-guessing dialog shown when space-aligned text is pasted
+pub struct FixedWidthImport {
+    pub lines: Vec<String>,
+    /// Character positions where a new column starts, ascending
+    pub breaks: Vec<usize>,
+    pub cursor: usize,
+    pub scroll: usize,
+    /// Top-left cell the columns are written to
+    pub target: (usize, usize),
+}
+
+impl FixedWidthImport {
+    #[must_use]
+    pub fn new(lines: Vec<String>, target: (usize, usize)) -> Self {
+        let breaks = guess_column_breaks(&lines);
+        let cursor = breaks.first().copied().unwrap_or(0);
+        Self {
+            lines,
+            breaks,
+            cursor,
+            scroll: 0,
+            target,
+        }
+    }
+
+    /// Length in characters of the longest line
+    #[must_use]
+    pub fn width(&self) -> usize {
+        self.lines
+            .iter()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn move_cursor(&mut self, delta: isize) {
+        self.cursor = self.cursor.saturating_add_signed(delta).min(self.width());
+    }
+
+    /// Moves the cursor to the next (or previous) break, if there is one
+    pub fn jump_to_break(&mut self, forward: bool) {
+        let next = if forward {
+            self.breaks.iter().find(|&&pos| pos > self.cursor)
+        } else {
+            self.breaks.iter().rev().find(|&&pos| pos < self.cursor)
+        };
+        if let Some(&pos) = next {
+            self.cursor = pos;
+        }
+    }
+
+    pub fn toggle_break(&mut self) {
+        if self.cursor == 0 {
+            return;
+        }
+        match self.breaks.binary_search(&self.cursor) {
+            Ok(index) => {
+                self.breaks.remove(index);
+            }
+            Err(index) => self.breaks.insert(index, self.cursor),
+        }
+    }
+
+    /// The pasted lines split at the current breaks, with each field trimmed
+    #[must_use]
+    pub fn rows(&self) -> Vec<Vec<String>> {
+        self.lines
+            .iter()
+            .map(|line| split_fixed_width(line, &self.breaks))
+            .collect()
+    }
+}
+
+/// Multi-cell text from a terminal paste, held until the user confirms spreading it out
+pub struct PendingPaste {
+    /// The pasted text with line endings normalised, for pasting into a single cell instead
+    pub text: String,
+    pub rows: Vec<Vec<String>>,
+    /// Top-left cell the block is written to
+    pub target: (usize, usize),
+}
+
+impl PendingPaste {
+    /// Rows and columns the block covers
+    #[must_use]
+    pub fn size(&self) -> (usize, usize) {
+        let width = self.rows.iter().map(Vec::len).max().unwrap_or(0);
+        (self.rows.len(), width)
+    }
+}
+
+/// How `p` and `:paste` write the copied block
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PasteOptions {
+    /// Write the values formulas evaluated to, keeping the destination's formats
+    pub values_only: bool,
+    /// Swap the block's rows and columns
+    pub transpose: bool,
+    /// Leave destination cells alone where the copied cell is empty
+    pub skip_blanks: bool,
+}
+
+impl PasteOptions {
+    /// Parses the words after `:paste`, e.g. `values skipblanks`
+    pub fn parse(args: &str) -> Result<Self, String> {
+        let mut options = Self::default();
+        for word in args.split_whitespace() {
+            match word {
+                "values" | "v" => options.values_only = true,
+                "transpose" | "t" => options.transpose = true,
+                "skipblanks" | "s" => options.skip_blanks = true,
+                other => return Err(format!("Unknown paste option: {other}")),
+            }
+        }
+        Ok(options)
+    }
+
+    fn describe(self) -> Vec<&'static str> {
+        [
+            (self.values_only, "values"),
+            (self.transpose, "transposed"),
+            (self.skip_blanks, "skipping blanks"),
+        ]
+        .into_iter()
+        .filter_map(|(enabled, label)| enabled.then_some(label))
+        .collect()
+    }
+}
+
+/// Guess column starts for space-aligned text: a column starts wherever a character position
+/// holds text on some line right after a position that is blank on every line
+#[must_use]
+pub fn guess_column_breaks(lines: &[String]) -> Vec<usize> {
+    let rows: Vec<Vec<char>> = lines.iter().map(|line| line.chars().collect()).collect();
+    let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let is_blank_column = |pos: usize| {
+        rows.iter()
+            .all(|row| row.get(pos).is_none_or(|ch| ch.is_whitespace()))
+    };
+
+    let blank: Vec<bool> = (0..width).map(is_blank_column).collect();
+    (1..width)
+        .filter(|&pos| blank[pos - 1] && !blank[pos])
+        .collect()
+}
+
+/// Split `line` at the given character positions, trimming each field
+#[must_use]
+pub fn split_fixed_width(line: &str, breaks: &[usize]) -> Vec<String> {
+    let chars: Vec<char> = line.chars().collect();
+    let mut fields = Vec::with_capacity(breaks.len() + 1);
+    let mut start = 0;
+
+    for &end in breaks.iter().chain(std::iter::once(&usize::MAX)) {
+        let end = end.min(chars.len());
+        let field: String = chars[start.min(end)..end].iter().collect();
+        fields.push(field.trim().to_string());
+        start = end;
+    }
+
+    fields
+}
+
+/// Whether pasted lines look like a space-aligned report rather than free text
+#[must_use]
+pub fn looks_fixed_width(lines: &[String]) -> bool {
+    let non_empty = lines.iter().filter(|line| !line.trim().is_empty()).count();
+    non_empty >= 2
+        && lines.iter().all(|line| !line.contains('\t'))
+        && !guess_column_breaks(lines).is_empty()
+}
+
+impl AppState<'_> {
+    /// Handles text pasted into the terminal while in normal mode. Single values go into the
+    /// current cell, space-aligned text opens the fixed-width import dialog, and other
+    /// tab- or newline-separated text asks before spreading across cells.
+    pub fn paste_text(&mut self, text: &str) {
+        let text = text.replace("\r\n", "\n").replace('\r', "\n");
+        let text = text.trim_end_matches('\n');
+        if text.is_empty() {
+            return;
+        }
+
+        let lines: Vec<String> = text.lines().map(str::to_string).collect();
+
+        if text.contains('\t') {
+            let rows = lines
+                .iter()
+                .map(|line| line.split('\t').map(str::to_string).collect())
+                .collect();
+            self.ask_to_paste_block(text, rows);
+        } else if lines.len() == 1 {
+            if let Err(e) = self.paste_into_current_cell(text.to_string()) {
+                self.add_error(format!("Paste failed: {e}"));
+            }
+        } else if looks_fixed_width(&lines) {
+            self.fixed_width_import = Some(FixedWidthImport::new(lines, self.selected_cell));
+            self.input_mode = InputMode::FixedWidthImport;
+        } else {
+            let rows = lines.into_iter().map(|line| vec![line]).collect();
+            self.ask_to_paste_block(text, rows);
+        }
+    }
+
+    fn ask_to_paste_block(&mut self, text: &str, rows: Vec<Vec<String>>) {
+        self.pending_paste = Some(PendingPaste {
+            text: text.to_string(),
+            rows,
+            target: self.selected_cell,
+        });
+        self.input_mode = InputMode::ConfirmPaste;
+    }
+
+    /// Spreads the pending paste across cells from its target
+    pub fn confirm_block_paste(&mut self) {
+        self.input_mode = InputMode::Normal;
+        if let Some(paste) = self.pending_paste.take() {
+            self.write_text_block(paste.rows, paste.target);
+        }
+    }
+
+    /// Puts the whole pending paste into its target cell as multi-line text
+    pub fn paste_pending_into_one_cell(&mut self) {
+        self.input_mode = InputMode::Normal;
+        if let Some(paste) = self.pending_paste.take() {
+            self.selected_cell = paste.target;
+            if let Err(e) = self.paste_into_current_cell(paste.text) {
+                self.add_error(format!("Paste failed: {e}"));
+            }
+        }
+    }
+
+    pub fn cancel_block_paste(&mut self) {
+        self.input_mode = InputMode::Normal;
+        self.pending_paste = None;
+        self.add_notification("Paste cancelled".to_string());
+    }
+
+    pub fn confirm_fixed_width_import(&mut self) {
+        self.input_mode = InputMode::Normal;
+        if let Some(import) = self.fixed_width_import.take() {
+            self.write_text_block(import.rows(), import.target);
+        }
+    }
+
+    pub fn cancel_fixed_width_import(&mut self) {
+        self.input_mode = InputMode::Normal;
+        self.fixed_width_import = None;
+        self.add_notification("Paste cancelled".to_string());
+    }
+
+    /// Writes the copied cells at the selected cell as one undoable paste. Rows yanked with
+    /// `yy` and columns yanked with `yc` are inserted instead, unless `options` ask for a
+    /// special paste
+    pub fn paste_clipboard(&mut self, options: PasteOptions) {
+        match self.clipboard.get() {
+            Some(ClipboardContent::Yank(yank)) => self.put_yank(yank, options),
+            Some(ClipboardContent::Text(text)) => self.paste_block(text_block(&text), options),
+            None => self.add_notification("Clipboard is empty".to_string()),
+        }
+    }
+
+    /// Pastes an earlier yank, as `"1p` does: register 0 holds the last yank, 1 the one before
+    pub fn paste_register(&mut self, index: usize) {
+        match self.clipboard.register(index).cloned() {
+            Some(yank) => self.put_yank(yank, PasteOptions::default()),
+            None => self.add_notification(format!("Register \"{index} is empty")),
+        }
+    }
+
+    fn put_yank(&mut self, yank: Yank, options: PasteOptions) {
+        let special = options != PasteOptions::default();
+        match yank.kind {
+            YankKind::Rows if !special => self.insert_yanked_rows(yank.block),
+            YankKind::Columns if !special => self.insert_yanked_columns(yank.block),
+            _ => self.paste_block(yank.block, options),
+        }
+    }
+
+    /// Lists the numbered registers with their size and first values, for `:reg`
+    pub fn show_registers(&mut self) {
+        let lines: Vec<String> = self
+            .clipboard
+            .registers()
+            .enumerate()
+            .map(|(index, yank)| {
+                let block = &yank.block;
+                let width = block.iter().map(Vec::len).max().unwrap_or(0);
+                let preview: String = block_text(block)
+                    .replace(['\t', '\n'], " ")
+                    .chars()
+                    .take(REGISTER_PREVIEW_CHARS)
+                    .collect();
+                format!(
+                    "\"{index}  {:<7} {:>7}  {preview}",
+                    yank.kind.name(),
+                    format!("{}x{width}", block.len())
+                )
+            })
+            .collect();
+        if lines.is_empty() {
+            self.add_notification("No registers: nothing has been yanked".to_string());
+            return;
+        }
+        self.open_pager("Registers".to_string(), lines);
+    }
+
+    fn paste_block(&mut self, mut block: Vec<Vec<Cell>>, options: PasteOptions) {
+        if options.transpose {
+            block = transpose_block(block);
+        }
+
+        let top_left = self.selected_cell;
+        let height = block.len();
+        let width = block.iter().map(Vec::len).max().unwrap_or(0);
+        if height == 0 || width == 0 {
+            return;
+        }
+
+        let end = (top_left.0 + height - 1, top_left.1 + width - 1);
+        if end.0 > EXCEL_MAX_ROWS || end.1 > EXCEL_MAX_COLS {
+            self.add_notification("Paste failed: block exceeds Excel limits".to_string());
+            return;
+        }
+        if self.refuse_locked_range(top_left, end) {
+            return;
+        }
+
+        let mut changes = Vec::new();
+        for (row_offset, cells) in block.into_iter().enumerate() {
+            for (col_offset, source) in cells.into_iter().enumerate() {
+                if options.skip_blanks && source.value.is_empty() && !source.is_formula {
+                    continue;
+                }
+                let (row, col) = (top_left.0 + row_offset, top_left.1 + col_offset);
+                let old_value = self.cell_at(row, col);
+                let new_value = if options.values_only {
+                    let mut cell = Cell::new(source.value, false);
+                    cell.inherit_annotations(&old_value);
+                    cell
+                } else {
+                    source
+                };
+                changes.push(RangeCellChange {
+                    row,
+                    col,
+                    old_value,
+                    new_value,
+                });
+            }
+        }
+
+        self.commit_range_changes(changes, ActionType::Paste);
+        self.handle_scrolling();
+
+        let mut message = if height == 1 && width == 1 {
+            "Content pasted".to_string()
+        } else {
+            format!(
+                "Pasted {height}x{width} block into {}:{}",
+                cell_reference(top_left),
+                cell_reference(end)
+            )
+        };
+        let details = options.describe();
+        if !details.is_empty() {
+            message.push_str(&format!(" ({})", details.join(", ")));
+        }
+        self.add_notification(message);
+    }
+
+    /// Writes `rows` into the current sheet starting at `top_left` as one undoable paste
+    pub(crate) fn write_text_block(&mut self, rows: Vec<Vec<String>>, top_left: (usize, usize)) {
+        let (start_row, start_col) = top_left;
+        let height = rows.len();
+        let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+        if height == 0 || width == 0 {
+            return;
+        }
+
+        let end = (start_row + height - 1, start_col + width - 1);
+        if end.0 > EXCEL_MAX_ROWS || end.1 > EXCEL_MAX_COLS {
+            self.add_notification("Paste failed: block exceeds Excel limits".to_string());
+            return;
+        }
+        if self.refuse_locked_range(top_left, end) {
+            return;
+        }
+
+        let mut changes = Vec::new();
+        for (row_offset, fields) in rows.into_iter().enumerate() {
+            for (col_offset, value) in fields.into_iter().enumerate() {
+                let (row, col) = (start_row + row_offset, start_col + col_offset);
+                let old_value = self.cell_at(row, col);
+                if old_value.value == value {
+                    continue;
+                }
+                let is_formula = value.starts_with('=');
+                let mut new_value = Cell::new(value, is_formula);
+                new_value.inherit_annotations(&old_value);
+                changes.push(RangeCellChange {
+                    row,
+                    col,
+                    old_value,
+                    new_value,
+                });
+            }
+        }
+
+        self.commit_range_changes(changes, ActionType::Paste);
+        self.selected_cell = top_left;
+        self.handle_scrolling();
+        self.add_notification(format!(
+            "Pasted {height}x{width} block into {}:{}",
+            cell_reference(top_left),
+            cell_reference(end)
+        ));
+    }
+}
+
+/// Turns a block's rows into columns, padding short rows with empty cells
+fn transpose_block(block: Vec<Vec<Cell>>) -> Vec<Vec<Cell>> {
+    let width = block.iter().map(Vec::len).max().unwrap_or(0);
+    let mut transposed = vec![Vec::with_capacity(block.len()); width];
+    for row in block {
+        let len = row.len();
+        for (col, cell) in row.into_iter().enumerate() {
+            transposed[col].push(cell);
+        }
+        for column in transposed.iter_mut().skip(len) {
+            column.push(Cell::empty());
+        }
+    }
+    transposed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{guess_column_breaks, looks_fixed_width, split_fixed_width};
+    use crate::app::{AppState, InputMode};
+    use crate::excel::{
+        Cell, CellGrid, ColumnFormats, FreezePanes, Sheet, SheetProtection, SheetVisibility,
+        Workbook,
+    };
+    use std::path::PathBuf;
+
+    fn lines(text: &str) -> Vec<String> {
+        text.lines().map(str::to_string).collect()
+    }
+
+    fn empty_app() -> AppState<'static> {
+        let sheet = Sheet {
+            name: "Data".to_string(),
+            data: CellGrid::new(),
+            max_rows: 0,
+            max_cols: 0,
+            is_loaded: true,
+            freeze_panes: FreezePanes::none(),
+            column_formats: ColumnFormats::new(),
+            protection: SheetProtection::Unprotected,
+            visibility: SheetVisibility::Visible,
+            tab_color: None,
+            conditional_formats: Vec::new(),
+        };
+
+        AppState::new(
+            Workbook::from_sheets_for_test(vec![sheet]),
+            PathBuf::from("test.xlsx"),
+        )
+        .unwrap()
+    }
+
+    const REPORT: &str = "PID   USER   COMMAND\n  1   root   init\n 42   ada    cargo build";
+
+    #[test]
+    fn guesses_breaks_at_starts_of_aligned_columns() {
+        let report = lines(REPORT);
+
+        assert_eq!(guess_column_breaks(&report), vec![6, 13]);
+        assert!(looks_fixed_width(&report));
+        assert_eq!(
+            split_fixed_width(&report[2], &[6, 13]),
+            vec!["42", "ada", "cargo build"]
+        );
+        assert!(!looks_fixed_width(&lines("one line only")));
+        assert!(!looks_fixed_width(&lines("a\tb\nc\td")));
+    }
+
+    #[test]
+    fn fixed_width_paste_opens_dialog_and_imports_adjusted_columns() {
+        let mut app = empty_app();
+
+        app.paste_text(REPORT);
+        assert!(matches!(app.input_mode, InputMode::FixedWidthImport));
+
+        let import = app.fixed_width_import.as_mut().unwrap();
+        import.cursor = 13;
+        import.toggle_break();
+        assert_eq!(import.breaks, vec![6]);
+
+        app.confirm_fixed_width_import();
+
+        assert!(matches!(app.input_mode, InputMode::Normal));
+        assert_eq!(app.get_cell_content(1, 2), "USER   COMMAND");
+        assert_eq!(app.get_cell_content(3, 1), "42");
+        assert_eq!(app.get_cell_content(3, 2), "ada    cargo build");
+
+        app.undo().unwrap();
+        assert_eq!(app.get_cell_content(3, 1), "");
+    }
+
+    #[test]
+    fn tab_separated_paste_spreads_across_cells() {
+        let mut app = empty_app();
+        app.selected_cell = (2, 2);
+
+        app.paste_text("a\tb\r\nc\td\r\n");
+        assert!(matches!(app.input_mode, InputMode::ConfirmPaste));
+        assert_eq!(app.pending_paste.as_ref().unwrap().size(), (2, 2));
+        assert_eq!(app.get_cell_content(2, 2), "");
+
+        app.confirm_block_paste();
+
+        assert!(matches!(app.input_mode, InputMode::Normal));
+        assert_eq!(app.get_cell_content(2, 2), "a");
+        assert_eq!(app.get_cell_content(3, 3), "d");
+        assert_eq!(app.workbook.get_current_sheet().max_rows, 3);
+    }
+
+    #[test]
+    fn multi_line_paste_can_go_into_one_cell_or_be_cancelled() {
+        let mut app = empty_app();
+        app.selected_cell = (1, 1);
+
+        app.paste_text("first line\nsecond line\n");
+        app.paste_pending_into_one_cell();
+        assert_eq!(app.get_cell_content(1, 1), "first line\nsecond line");
+        assert_eq!(app.get_cell_content(2, 1), "");
+
+        app.paste_text("x\ty");
+        app.cancel_block_paste();
+        assert!(matches!(app.input_mode, InputMode::Normal));
+        assert!(app.pending_paste.is_none());
+        assert_eq!(app.get_cell_content(1, 1), "first line\nsecond line");
+    }
+
+    #[test]
+    fn paste_options_control_formulas_layout_and_blanks() {
+        let mut app = empty_app();
+        let mut doubled = Cell::new("2".to_string(), false);
+        doubled.formula = Some("=A1*2".to_string());
+        doubled.is_formula = true;
+        let sheet = app.workbook.get_current_sheet_mut();
+        sheet.data.set(1, 1, Cell::new("1".to_string(), false));
+        sheet.data.set(1, 2, doubled);
+        sheet.data.set(2, 1, Cell::new("x".to_string(), false));
+        sheet.data.set(11, 2, Cell::new("keep".to_string(), false));
+
+        app.selection_anchor = Some((1, 1));
+        app.selected_cell = (2, 2);
+        app.copy_cell();
+        assert!(app.selection_anchor.is_none());
+
+        app.selected_cell = (4, 1);
+        app.paste_cell().unwrap();
+        assert_eq!(app.cell_at(4, 2).formula.as_deref(), Some("=A1*2"));
+        assert_eq!(app.get_cell_content(5, 1), "x");
+
+        app.selected_cell = (4, 4);
+        app.input_buffer = "paste values".to_string();
+        app.execute_command();
+        assert_eq!(app.get_cell_content(4, 5), "2");
+        assert!(app.cell_at(4, 5).formula.is_none());
+
+        app.selected_cell = (7, 1);
+        app.input_buffer = "paste transpose".to_string();
+        app.execute_command();
+        assert_eq!(app.get_cell_content(7, 2), "x");
+        assert_eq!(app.cell_at(8, 1).formula.as_deref(), Some("=A1*2"));
+
+        app.selected_cell = (10, 1);
+        app.input_buffer = "paste skipblanks".to_string();
+        app.execute_command();
+        assert_eq!(app.get_cell_content(11, 1), "x");
+        assert_eq!(app.get_cell_content(11, 2), "keep");
+
+        app.undo().unwrap();
+        assert_eq!(app.get_cell_content(11, 1), "");
+        assert_eq!(app.get_cell_content(11, 2), "keep");
+    }
+}
@@ -0,0 +1,133 @@
+use crate::app::AppState;
+use crate::excel::SheetProtection;
+use crate::utils::cell_reference;
+
+impl AppState<'_> {
+    /// Lifts the current sheet's protection for this session. The sheet is still written as
+    /// protected on save.
+    pub fn unlock_current_sheet(&mut self) {
+        let name = self.workbook.get_current_sheet_name();
+        match self.workbook.get_current_sheet().protection {
+            SheetProtection::Unprotected => {
+                self.add_notification(format!("Sheet {name} is not protected"));
+            }
+            SheetProtection::Unlocked => {
+                self.add_notification(format!("Sheet {name} is already unlocked"));
+            }
+            SheetProtection::Protected => {
+                self.workbook.get_current_sheet_mut().protection = SheetProtection::Unlocked;
+                self.add_notification(format!(
+                    "Unlocked {name} for editing; it stays protected in saved files"
+                ));
+            }
+        }
+    }
+
+    /// Refuses, with a notification, an edit touching a locked cell in `start..=end` of the
+    /// current sheet. Returns true when the edit must not go ahead.
+    pub(crate) fn refuse_locked_range(
+        &mut self,
+        start: (usize, usize),
+        end: (usize, usize),
+    ) -> bool {
+        if self.refuse_while_streaming() {
+            return true;
+        }
+        let sheet = self.workbook.get_current_sheet();
+        if sheet.protection != SheetProtection::Protected {
+            return false;
+        }
+
+        let locked = (start.0..=end.0)
+            .flat_map(|row| (start.1..=end.1).map(move |col| (row, col)))
+            .find(|&(row, col)| sheet.is_cell_locked(row, col));
+        let Some(cell) = locked else {
+            return false;
+        };
+
+        let message = format!(
+            "{} is locked: sheet {} is protected. Use :unlock to edit it anyway",
+            cell_reference(cell),
+            sheet.name
+        );
+        self.add_warning(message);
+        true
+    }
+
+    /// Refuses, with a notification, a change such as deleting or moving rows that a
+    /// protected sheet never allows. Returns true when the change must not go ahead.
+    pub(crate) fn refuse_on_protected_sheet(&mut self, action: &str) -> bool {
+        if self.refuse_while_streaming() {
+            return true;
+        }
+        let sheet = self.workbook.get_current_sheet();
+        if sheet.protection != SheetProtection::Protected {
+            return false;
+        }
+
+        let message = format!(
+            "Cannot {action}: sheet {} is protected. Use :unlock to edit it anyway",
+            sheet.name
+        );
+        self.add_warning(message);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::app::{AppState, MessageLevel};
+    use crate::excel::{Cell, Sheet, SheetProtection, Workbook};
+    use std::path::PathBuf;
+
+    fn protected_app() -> AppState<'static> {
+        let mut sheet = Sheet::blank("Form".to_string());
+        sheet.data.set(1, 1, Cell::new("Name".to_string(), false));
+        sheet.data.update_cell(1, 2, |cell| cell.unlocked = true);
+        sheet.max_rows = 2;
+        sheet.max_cols = 2;
+        sheet.protection = SheetProtection::Protected;
+
+        let workbook = Workbook::from_sheets_for_test(vec![sheet]);
+        AppState::new(workbook, PathBuf::from("protected.xlsx")).unwrap()
+    }
+
+    #[test]
+    fn locked_cells_refuse_edits_until_unlock() {
+        let mut app = protected_app();
+
+        app.selected_cell = (1, 1);
+        app.start_editing();
+        assert!(!matches!(app.input_mode, crate::app::InputMode::Editing));
+        app.cut_cell().unwrap();
+        assert_eq!(
+            app.workbook.get_current_sheet().data.cell(1, 1).value,
+            "Name"
+        );
+        assert!(app
+            .notification_messages
+            .last()
+            .is_some_and(|message| message.text.contains("A1 is locked")
+                && message.level == MessageLevel::Warning));
+
+        app.selected_cell = (1, 2);
+        app.paste_into_current_cell("Ada".to_string()).unwrap();
+        assert_eq!(
+            app.workbook.get_current_sheet().data.cell(1, 2).value,
+            "Ada"
+        );
+        assert!(app.workbook.get_current_sheet().data.cell(1, 2).unlocked);
+
+        app.delete_row(1).unwrap();
+        assert_eq!(app.workbook.get_current_sheet().max_rows, 2);
+
+        app.unlock_current_sheet();
+        assert_eq!(
+            app.workbook.get_current_sheet().protection,
+            SheetProtection::Unlocked
+        );
+        app.selected_cell = (1, 1);
+        app.cut_cell().unwrap();
+        assert_eq!(app.workbook.get_current_sheet().data.cell(1, 1).value, "");
+    }
+}
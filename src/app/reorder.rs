@@ -0,0 +1,279 @@
+use std::ops::RangeInclusive;
+
+use crate::actions::{ActionType, RangeCellChange};
+use crate::app::AppState;
+use crate::excel::{Cell, EXCEL_MAX_COLS, EXCEL_MAX_ROWS};
+use crate::utils::index_to_col_name;
+
+impl AppState<'_> {
+    /// Moves the selected rows, or the current row, `offset` rows down (up when negative) as
+    /// one undoable step. The rows in between shift to fill the gap.
+    pub fn move_rows(&mut self, offset: isize) {
+        let (start, end) = self.selection_range().map_or(
+            (self.selected_cell.0, self.selected_cell.0),
+            |(start, end)| (start.0, end.0),
+        );
+        if offset == 0 || self.refuse_on_protected_sheet("move rows") {
+            return;
+        }
+
+        let Some(new_start) = start.checked_add_signed(offset).filter(|&row| row >= 1) else {
+            self.add_warning("Cannot move rows above row 1".to_string());
+            return;
+        };
+        let new_end = new_start + (end - start);
+        if new_end > EXCEL_MAX_ROWS {
+            self.add_warning("Cannot move rows past the last row".to_string());
+            return;
+        }
+
+        let first = start.min(new_start);
+        let last = end.max(new_end);
+        let order = reordered_span(first, last, start..=end, new_start);
+
+        let sheet = self.workbook.get_current_sheet();
+        let width = (first..=last)
+            .filter_map(|row| sheet.data.row(row).next_back().map(|(col, _)| col + 1))
+            .max()
+            .unwrap_or(0);
+
+        let mut changes = Vec::new();
+        for (row, source_row) in (first..=last).zip(order) {
+            for col in 1..width {
+                let old_value = self.cell_at(row, col);
+                let new_value = self.cell_at(source_row, col);
+                if is_blank(&old_value) && is_blank(&new_value) {
+                    continue;
+                }
+                changes.push(RangeCellChange {
+                    row,
+                    col,
+                    old_value,
+                    new_value,
+                });
+            }
+        }
+
+        self.commit_range_changes(changes, ActionType::MoveRows);
+
+        self.selected_cell.0 = self.selected_cell.0.saturating_add_signed(offset);
+        if let Some(anchor) = self.selection_anchor.as_mut() {
+            anchor.0 = anchor.0.saturating_add_signed(offset);
+        }
+        self.handle_scrolling();
+
+        let rows = if start == end {
+            format!("row {start}")
+        } else {
+            format!("rows {start}-{end}")
+        };
+        let direction = if offset < 0 { "up" } else { "down" };
+        self.add_notification(format!(
+            "Moved {rows} {direction} {}",
+            offset.unsigned_abs()
+        ));
+    }
+
+    /// Moves the selected columns, or the current column, `offset` columns right (left when
+    /// negative). Column widths travel with the data, and the move undoes as one step.
+    pub fn move_columns(&mut self, offset: isize) {
+        let (start, end) = self.selection_range().map_or(
+            (self.selected_cell.1, self.selected_cell.1),
+            |(start, end)| (start.1, end.1),
+        );
+        if offset == 0 || self.refuse_on_protected_sheet("move columns") {
+            return;
+        }
+
+        let Some(new_start) = start.checked_add_signed(offset).filter(|&col| col >= 1) else {
+            self.add_warning("Cannot move columns left of column A".to_string());
+            return;
+        };
+        let new_end = new_start + (end - start);
+        if new_end > EXCEL_MAX_COLS {
+            self.add_warning("Cannot move columns past the last column".to_string());
+            return;
+        }
+
+        let first = start.min(new_start);
+        let last = end.max(new_end);
+        let order = reordered_span(first, last, start..=end, new_start);
+
+        let height = self
+            .workbook
+            .get_current_sheet()
+            .data
+            .rows()
+            .next_back()
+            .map_or(0, |(row, _)| row + 1);
+        let mut changes = Vec::new();
+        let mut widths = Vec::new();
+        for (col, source_col) in (first..=last).zip(order) {
+            for row in 1..height {
+                let old_value = self.cell_at(row, col);
+                let new_value = self.cell_at(row, source_col);
+                if is_blank(&old_value) && is_blank(&new_value) {
+                    continue;
+                }
+                changes.push(RangeCellChange {
+                    row,
+                    col,
+                    old_value,
+                    new_value,
+                });
+            }
+            widths.push((col, self.get_column_width(source_col)));
+        }
+
+        self.undo_history.begin_group();
+        self.commit_range_changes(changes, ActionType::MoveColumns);
+        self.commit_column_widths(&widths);
+        self.undo_history.end_group();
+
+        self.selected_cell.1 = self.selected_cell.1.saturating_add_signed(offset);
+        if let Some(anchor) = self.selection_anchor.as_mut() {
+            anchor.1 = anchor.1.saturating_add_signed(offset);
+        }
+        self.handle_scrolling();
+
+        let columns = if start == end {
+            format!("column {}", index_to_col_name(start))
+        } else {
+            format!(
+                "columns {}-{}",
+                index_to_col_name(start),
+                index_to_col_name(end)
+            )
+        };
+        let direction = if offset < 0 { "left" } else { "right" };
+        self.add_notification(format!(
+            "Moved {columns} {direction} {}",
+            offset.unsigned_abs()
+        ));
+    }
+}
+
+/// Indices `first..=last` in their new order after `moved` is shifted to begin at `new_start`;
+/// the indices it passes over close up behind it
+fn reordered_span(
+    first: usize,
+    last: usize,
+    moved: RangeInclusive<usize>,
+    new_start: usize,
+) -> Vec<usize> {
+    let mut order: Vec<usize> = (first..=last).filter(|i| !moved.contains(i)).collect();
+    let insert_at = new_start - first;
+    order.splice(insert_at..insert_at, moved);
+    order
+}
+
+fn is_blank(cell: &Cell) -> bool {
+    cell.value.is_empty()
+        && cell.formula.is_none()
+        && cell.comment.is_none()
+        && cell.hyperlink.is_none()
+        && cell.number_format.is_none()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::app::AppState;
+    use crate::excel::{
+        Cell, CellGrid, ColumnFormats, FreezePanes, Sheet, SheetProtection, SheetVisibility,
+        Workbook,
+    };
+    use std::path::PathBuf;
+
+    fn app_with_rows(rows: &[&str]) -> AppState<'static> {
+        let mut data = vec![vec![Cell::empty(); 3]];
+        for text in rows {
+            data.push(vec![
+                Cell::empty(),
+                Cell::new((*text).to_string(), false),
+                Cell::new(format!("{text}!"), false),
+            ]);
+        }
+        let sheet = Sheet {
+            name: "Data".to_string(),
+            data: CellGrid::from(data),
+            max_rows: rows.len(),
+            max_cols: 2,
+            is_loaded: true,
+            freeze_panes: FreezePanes::none(),
+            column_formats: ColumnFormats::new(),
+            protection: SheetProtection::Unprotected,
+            visibility: SheetVisibility::Visible,
+            tab_color: None,
+            conditional_formats: Vec::new(),
+        };
+
+        AppState::new(
+            Workbook::from_sheets_for_test(vec![sheet]),
+            PathBuf::from("test.xlsx"),
+        )
+        .unwrap()
+    }
+
+    fn column_a(app: &AppState) -> Vec<String> {
+        (1..=app.workbook.get_current_sheet().max_rows)
+            .map(|row| app.get_cell_content(row, 1))
+            .collect()
+    }
+
+    #[test]
+    fn moves_selected_rows_and_undoes_in_one_step() {
+        let mut app = app_with_rows(&["a", "b", "c", "d", "e"]);
+        app.selection_anchor = Some((2, 1));
+        app.selected_cell = (3, 1);
+
+        app.input_buffer = "mr 2".to_string();
+        app.execute_command();
+        assert_eq!(column_a(&app), ["a", "d", "e", "b", "c"]);
+        assert_eq!(app.get_cell_content(4, 2), "b!");
+        assert_eq!(app.selected_cell, (5, 1));
+        assert_eq!(app.selection_anchor, Some((4, 1)));
+
+        app.move_rows(-3);
+        assert_eq!(column_a(&app), ["b", "c", "a", "d", "e"]);
+
+        app.undo().unwrap();
+        assert_eq!(column_a(&app), ["a", "d", "e", "b", "c"]);
+        app.undo().unwrap();
+        assert_eq!(column_a(&app), ["a", "b", "c", "d", "e"]);
+    }
+
+    #[test]
+    fn refuses_to_move_above_the_first_row() {
+        let mut app = app_with_rows(&["a", "b"]);
+        app.selected_cell = (1, 1);
+
+        app.move_rows(-1);
+
+        assert_eq!(column_a(&app), ["a", "b"]);
+        assert_eq!(
+            app.notification_messages.last().unwrap().text,
+            "Cannot move rows above row 1"
+        );
+    }
+
+    #[test]
+    fn moving_a_column_carries_its_width_and_undoes_in_one_step() {
+        let mut app = app_with_rows(&["a", "b"]);
+        app.selected_cell = (1, 1);
+        app.set_column_width(1, 30);
+        app.set_column_width(2, 12);
+
+        app.input_buffer = "mc 1".to_string();
+        app.execute_command();
+        assert_eq!(app.get_cell_content(2, 1), "b!");
+        assert_eq!(app.get_cell_content(2, 2), "b");
+        assert_eq!(app.get_column_width(1), 12);
+        assert_eq!(app.get_column_width(2), 30);
+        assert_eq!(app.selected_cell, (1, 2));
+
+        app.undo().unwrap();
+        assert_eq!(app.get_cell_content(2, 1), "b");
+        assert_eq!(app.get_column_width(1), 30);
+        assert_eq!(app.get_column_width(2), 12);
+    }
+}
@@ -0,0 +1,425 @@
+use crate::actions::CellChange;
+use crate::app::{AppState, InputMode};
+use crate::excel::Cell;
+use crate::utils::cell_reference;
+
+/// One location in the results panel
+#[derive(Clone, Debug, PartialEq)]
+pub struct ResultItem {
+    pub sheet_name: String,
+    pub cell: (usize, usize),
+    pub text: String,
+}
+
+/// A list of cell locations produced by search or data checks, shown below the grid.
+/// Items remember their sheet, so the list stays valid across sheet switches.
+pub struct ResultsPanel {
+    pub title: String,
+    pub items: Vec<ResultItem>,
+    pub selected: usize,
+    pub scroll: usize,
+    pub visible_lines: usize,
+    pub is_open: bool,
+}
+
+impl ResultsPanel {
+    #[must_use]
+    pub fn new(title: String, items: Vec<ResultItem>) -> Self {
+        Self {
+            title,
+            items,
+            selected: 0,
+            scroll: 0,
+            visible_lines: 8,
+            is_open: false,
+        }
+    }
+
+    pub fn select(&mut self, index: usize) {
+        if self.items.is_empty() {
+            return;
+        }
+        self.selected = index.min(self.items.len() - 1);
+        let visible = self.visible_lines.max(1);
+        if self.selected < self.scroll {
+            self.scroll = self.selected;
+        } else if self.selected >= self.scroll + visible {
+            self.scroll = self.selected + 1 - visible;
+        }
+    }
+
+    pub fn move_selection(&mut self, delta: isize) {
+        self.select(self.selected.saturating_add_signed(delta));
+    }
+}
+
+impl AppState<'_> {
+    /// Replaces the results list. The panel keeps its open state, and `open` forces it open
+    /// and focused.
+    pub fn set_results(&mut self, title: String, items: Vec<ResultItem>, open: bool) {
+        let was_open = self.results.as_ref().is_some_and(|panel| panel.is_open);
+        let mut panel = ResultsPanel::new(title, items);
+        panel.is_open = was_open;
+        self.results = Some(panel);
+
+        if open {
+            self.open_results();
+        }
+    }
+
+    /// Shows and focuses the results panel
+    pub fn open_results(&mut self) {
+        match self.results.as_mut() {
+            Some(panel) if !panel.items.is_empty() => {
+                panel.is_open = true;
+                self.input_mode = InputMode::Results;
+            }
+            _ => self.add_notification("No results".to_string()),
+        }
+    }
+
+    pub fn close_results(&mut self) {
+        if let Some(panel) = self.results.as_mut() {
+            panel.is_open = false;
+        }
+        if matches!(self.input_mode, InputMode::Results) {
+            self.input_mode = InputMode::Normal;
+        }
+    }
+
+    /// Shows and focuses the results panel, or hides it when it is showing
+    pub fn toggle_results(&mut self) {
+        if self.results.as_ref().is_some_and(|panel| panel.is_open) {
+            self.close_results();
+        } else {
+            self.open_results();
+        }
+    }
+
+    /// Moves focus from the results panel back to the grid, leaving the panel visible
+    pub fn unfocus_results(&mut self) {
+        self.input_mode = InputMode::Normal;
+    }
+
+    /// Jumps to the selected result, switching sheets if needed
+    pub fn jump_to_selected_result(&mut self) {
+        let Some(item) = self
+            .results
+            .as_ref()
+            .and_then(|panel| panel.items.get(panel.selected))
+            .cloned()
+        else {
+            return;
+        };
+
+        if item.sheet_name != self.workbook.get_current_sheet_name() {
+            let Some(index) = self
+                .workbook
+                .get_sheet_names()
+                .iter()
+                .position(|name| name == &item.sheet_name)
+            else {
+                self.add_notification(format!("Sheet {} no longer exists", item.sheet_name));
+                return;
+            };
+            if let Err(e) = self.switch_sheet_by_index(index) {
+                self.add_error(format!("Cannot switch to sheet {}: {e}", item.sheet_name));
+                return;
+            }
+        }
+
+        self.selected_cell = Self::clamp_cell_to_excel_bounds(item.cell);
+        self.handle_scrolling();
+    }
+
+    /// Selects and jumps to the next (or previous) result from the grid
+    pub fn step_result(&mut self, forward: bool) {
+        let Some(panel) = self
+            .results
+            .as_mut()
+            .filter(|panel| !panel.items.is_empty())
+        else {
+            self.add_notification("No results".to_string());
+            return;
+        };
+
+        let len = panel.items.len();
+        let index = if forward {
+            (panel.selected + 1) % len
+        } else {
+            (panel.selected + len - 1) % len
+        };
+        panel.select(index);
+        let position = format!("({}/{len})", index + 1);
+
+        self.jump_to_selected_result();
+        self.add_notification(format!("Result {position}"));
+    }
+
+    /// Keeps the panel's selection on the match `n`/`N` moved to, while it lists the matches
+    pub(crate) fn select_current_search_result(&mut self) {
+        let (Some(idx), Some(panel)) = (self.current_search_idx, self.results.as_mut()) else {
+            return;
+        };
+        let lists_matches = panel.items.len() == self.search_results.len()
+            && panel
+                .items
+                .iter()
+                .zip(&self.search_results)
+                .all(|(item, &cell)| item.cell == cell);
+        if lists_matches {
+            panel.select(idx);
+        }
+    }
+
+    /// Fills the results list with the current search matches
+    pub(crate) fn set_search_results_list(&mut self) {
+        let sheet_name = self.workbook.get_current_sheet_name();
+        let items = self
+            .search_results
+            .iter()
+            .map(|&(row, col)| ResultItem {
+                sheet_name: sheet_name.clone(),
+                cell: (row, col),
+                text: self.get_cell_content(row, col),
+            })
+            .collect();
+
+        let title = format!("Search: {}", self.search_query);
+        self.set_results(title, items, false);
+    }
+
+    /// Lists duplicate rows in the results panel without deleting them
+    pub fn show_duplicate_rows(&mut self, key_cols: Option<Vec<usize>>) {
+        let duplicates = self.find_duplicate_rows(key_cols);
+        if duplicates.is_empty() {
+            self.add_notification("No duplicate rows found".to_string());
+            return;
+        }
+
+        let sheet_name = self.workbook.get_current_sheet_name();
+        let items = duplicates
+            .into_iter()
+            .map(|(row, first_row)| ResultItem {
+                sheet_name: sheet_name.clone(),
+                cell: (row, 1),
+                text: format!("duplicates row {first_row}"),
+            })
+            .collect::<Vec<_>>();
+
+        let title = format!("Duplicate rows: {}", items.len());
+        self.set_results(title, items, true);
+    }
+
+    /// Handles `:changes`: the cells changed since the file was opened or last saved, in
+    /// the results panel, sheet by sheet
+    pub fn show_changes(&mut self) {
+        let changes = self.undo_history.changed_cells();
+        let items: Vec<ResultItem> = self
+            .workbook
+            .get_sheet_names()
+            .iter()
+            .flat_map(|sheet_name| {
+                changes
+                    .on_sheet(sheet_name)
+                    .map(|(&cell, change)| ResultItem {
+                        sheet_name: sheet_name.clone(),
+                        cell,
+                        text: describe_change(change),
+                    })
+            })
+            .collect();
+        if items.is_empty() {
+            self.add_notification("No changes since the file was opened or saved".to_string());
+            return;
+        }
+
+        let noun = if items.len() == 1 { "cell" } else { "cells" };
+        let title = format!("Changes: {} {noun}", items.len());
+        self.set_results(title, items, true);
+    }
+}
+
+/// What became of a changed cell: its old and new content, or what else changed when the
+/// content did not
+fn describe_change(change: &CellChange) -> String {
+    let content = |cell: &Cell| {
+        let content = cell.formula.as_deref().unwrap_or(&cell.value);
+        if content.is_empty() {
+            "(blank)".to_string()
+        } else {
+            content.to_string()
+        }
+    };
+    let (old, new) = (&change.old_value, &change.new_value);
+    if content(old) != content(new) {
+        format!("{} → {}", content(old), content(new))
+    } else if old.comment != new.comment {
+        "comment changed".to_string()
+    } else if old.hyperlink != new.hyperlink {
+        "link changed".to_string()
+    } else {
+        "format changed".to_string()
+    }
+}
+
+impl ResultItem {
+    /// Location label such as "Sheet1!B3"
+    #[must_use]
+    pub fn location(&self) -> String {
+        format!("{}!{}", self.sheet_name, cell_reference(self.cell))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::app::{AppState, InputMode};
+    use crate::excel::{
+        Cell, CellGrid, ColumnFormats, FreezePanes, Sheet, SheetProtection, SheetVisibility,
+        Workbook,
+    };
+    use std::path::PathBuf;
+
+    fn app_with_two_sheets() -> AppState<'static> {
+        let make_sheet = |name: &str, values: &[&str]| {
+            let mut data = CellGrid::new();
+            for (row, value) in values.iter().enumerate() {
+                data.set(row + 1, 1, Cell::new((*value).to_string(), false));
+            }
+            Sheet {
+                name: name.to_string(),
+                data,
+                max_rows: values.len(),
+                max_cols: 1,
+                is_loaded: true,
+                freeze_panes: FreezePanes::none(),
+                column_formats: ColumnFormats::new(),
+                protection: SheetProtection::Unprotected,
+                visibility: SheetVisibility::Visible,
+                tab_color: None,
+                conditional_formats: Vec::new(),
+            }
+        };
+
+        AppState::new(
+            Workbook::from_sheets_for_test(vec![
+                make_sheet("Data", &["a", "b", "a", "b"]),
+                make_sheet("Other", &["x"]),
+            ]),
+            PathBuf::from("test.xlsx"),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn dupes_fill_results_panel_and_enter_jumps_across_sheets() {
+        let mut app = app_with_two_sheets();
+
+        app.input_buffer = "dupes".to_string();
+        app.execute_command();
+
+        assert!(matches!(app.input_mode, InputMode::Results));
+        let panel = app.results.as_ref().unwrap();
+        assert_eq!(panel.items.len(), 2);
+        assert_eq!(panel.items[1].location(), "Data!A4");
+        assert_eq!(panel.items[1].text, "duplicates row 2");
+
+        app.unfocus_results();
+        app.switch_to_sheet("Other");
+        app.results.as_mut().unwrap().select(1);
+        app.jump_to_selected_result();
+
+        assert_eq!(app.workbook.get_current_sheet_name(), "Data");
+        assert_eq!(app.selected_cell, (4, 1));
+        assert!(app.results.as_ref().unwrap().is_open);
+
+        app.step_result(true);
+        assert_eq!(app.selected_cell, (3, 1));
+    }
+
+    #[test]
+    fn changes_lists_cells_changed_since_opening_and_follows_undo() {
+        let mut app = app_with_two_sheets();
+        let run = |app: &mut AppState, command: &str| {
+            app.input_buffer = command.to_string();
+            app.execute_command();
+        };
+
+        run(&mut app, "changes");
+        assert_eq!(
+            app.notification_messages.last().unwrap().text,
+            "No changes since the file was opened or saved"
+        );
+
+        run(&mut app, "upper");
+        app.delete_row(1).unwrap();
+        app.switch_to_sheet("Other");
+        run(&mut app, "upper");
+        assert!(app.undo_history.changed_cells().contains("Data", (3, 1)));
+        assert!(!app.undo_history.changed_cells().contains("Data", (4, 1)));
+
+        run(&mut app, "changes");
+        assert!(matches!(app.input_mode, InputMode::Results));
+        let panel = app.results.as_ref().unwrap();
+        assert_eq!(panel.title, "Changes: 4 cells");
+        assert_eq!(panel.items[0].location(), "Data!A1");
+        assert_eq!(panel.items[0].text, "b → B");
+        assert_eq!(panel.items[3].location(), "Other!A1");
+        app.unfocus_results();
+
+        app.undo().unwrap();
+        app.switch_to_sheet("Data");
+        // Lowering the cells back leaves nothing changed on the sheet
+        run(&mut app, "lower");
+        run(&mut app, "changes");
+        assert_eq!(
+            app.notification_messages.last().unwrap().text,
+            "No changes since the file was opened or saved"
+        );
+    }
+
+    #[test]
+    fn changes_still_lists_cells_whose_steps_were_dropped_from_the_history() {
+        let mut app = app_with_two_sheets();
+        app.handle_set_command("undolevels=1");
+        app.input_buffer = "upper".to_string();
+        app.execute_command();
+
+        // Deleting a row drops the step that upper-cased the column
+        app.delete_row(1).unwrap();
+        app.undo().unwrap();
+        assert!(app.undo_history.changed_cells().contains("Data", (1, 1)));
+
+        app.input_buffer = "changes".to_string();
+        app.execute_command();
+        assert_eq!(app.results.as_ref().unwrap().title, "Changes: 4 cells");
+    }
+
+    #[test]
+    fn toggled_search_results_follow_n_and_jump_to_the_selected_match() {
+        let mut app = app_with_two_sheets();
+        app.start_search_forward();
+        app.text_area.insert_str("b");
+        app.execute_search();
+        assert_eq!(app.selected_cell, (2, 1));
+
+        app.toggle_results();
+        assert!(matches!(app.input_mode, InputMode::Results));
+        let panel = app.results.as_ref().unwrap();
+        assert_eq!(panel.title, "Search: b");
+        assert_eq!(panel.items[1].location(), "Data!A4");
+        assert_eq!(panel.items[1].text, "b");
+
+        app.unfocus_results();
+        app.jump_to_next_search_result();
+        assert_eq!(app.selected_cell, (4, 1));
+        assert_eq!(app.results.as_ref().unwrap().selected, 1);
+
+        app.results.as_mut().unwrap().select(0);
+        app.jump_to_selected_result();
+        assert_eq!(app.selected_cell, (2, 1));
+
+        app.toggle_results();
+        assert!(!app.results.as_ref().unwrap().is_open);
+    }
+}
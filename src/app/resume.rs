@@ -0,0 +1,155 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::app::{AppState, CellPosition};
+use crate::utils::cell_reference;
+
+/// File in the configuration directory that remembers where each workbook was left
+const POSITIONS_FILE: &str = "positions.json";
+
+/// How many workbooks are remembered; the least recently closed are forgotten first
+const MAX_REMEMBERED_FILES: usize = 200;
+
+/// Where a workbook was left: its active sheet, cursor and scroll offsets
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct LastPosition {
+    path: PathBuf,
+    sheet: String,
+    cell: (usize, usize),
+    view: (usize, usize),
+}
+
+impl AppState<'_> {
+    /// Puts the cursor back where this workbook was left the last time it was closed
+    pub fn restore_last_position(&mut self) {
+        self.restore_last_position_from(crate::config::config_dir());
+    }
+
+    pub(crate) fn restore_last_position_from(&mut self, config_dir: Option<PathBuf>) {
+        let Some(dir) = config_dir else {
+            return;
+        };
+        let key = position_key(&self.file_path);
+        let Some(position) = read_positions(&dir)
+            .into_iter()
+            .find(|position| position.path == key)
+        else {
+            return;
+        };
+        let Some(index) = self
+            .workbook
+            .get_sheet_names()
+            .iter()
+            .position(|name| *name == position.sheet)
+        else {
+            return;
+        };
+
+        let saved = CellPosition {
+            selected: position.cell,
+            view: position.view,
+        };
+        if index == self.workbook.get_current_sheet_index() {
+            self.selected_cell = Self::clamp_cell_to_excel_bounds(saved.selected);
+            self.start_row = saved.view.0;
+            self.start_col = saved.view.1;
+            self.clamp_selected_cell_to_excel_bounds();
+            self.handle_scrolling();
+        } else {
+            self.sheet_cell_positions
+                .insert(position.sheet.clone(), saved);
+            if self.switch_sheet_by_index(index).is_err() {
+                return;
+            }
+        }
+
+        self.add_notification(format!(
+            "Resumed at {}!{}",
+            position.sheet,
+            cell_reference(self.selected_cell)
+        ));
+    }
+
+    /// Remembers the active sheet, cursor and scroll offsets in `config_dir` for the next time
+    /// this workbook is opened
+    pub fn remember_last_position_in(&self, config_dir: &Path) -> std::io::Result<()> {
+        let current = LastPosition {
+            path: position_key(&self.file_path),
+            sheet: self.workbook.get_current_sheet_name(),
+            cell: self.selected_cell,
+            view: (self.start_row, self.start_col),
+        };
+
+        let mut positions = read_positions(config_dir);
+        positions.retain(|position| position.path != current.path);
+        positions.insert(0, current);
+        positions.truncate(MAX_REMEMBERED_FILES);
+
+        std::fs::create_dir_all(config_dir)?;
+        let json = serde_json::to_string_pretty(&positions)?;
+        std::fs::write(config_dir.join(POSITIONS_FILE), json)
+    }
+}
+
+/// The absolute path, so a workbook opened from different directories is remembered once
+fn position_key(path: &Path) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Remembered positions, newest first. A missing or unreadable file remembers nothing
+fn read_positions(config_dir: &Path) -> Vec<LastPosition> {
+    std::fs::read_to_string(config_dir.join(POSITIONS_FILE))
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::app::AppState;
+    use crate::excel::{Sheet, Workbook};
+    use std::path::PathBuf;
+
+    fn app(file: &str) -> AppState<'static> {
+        let workbook = Workbook::from_sheets_for_test(vec![
+            Sheet::blank("Data".to_string()),
+            Sheet::blank("Notes".to_string()),
+        ]);
+        AppState::new(workbook, PathBuf::from(file)).unwrap()
+    }
+
+    #[test]
+    fn last_position_is_restored_per_file() {
+        let dir = std::env::temp_dir().join("excel_cli_resume_test");
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let mut first = app("budget.xlsx");
+        first.next_sheet().unwrap();
+        first.selected_cell = (5000, 3);
+        first.handle_scrolling();
+        first.remember_last_position_in(&dir).unwrap();
+        app("other.xlsx").remember_last_position_in(&dir).unwrap();
+
+        let mut reopened = app("budget.xlsx");
+        reopened.restore_last_position_from(Some(dir.clone()));
+        assert_eq!(reopened.workbook.get_current_sheet_name(), "Notes");
+        assert_eq!(reopened.selected_cell, (5000, 3));
+        assert_eq!(reopened.start_row, first.start_row);
+        assert_eq!(
+            reopened.notification_messages.last().unwrap().text,
+            "Resumed at Notes!C5000"
+        );
+
+        let mut unknown = app("new.xlsx");
+        unknown.restore_last_position_from(Some(dir.clone()));
+        assert_eq!(unknown.workbook.get_current_sheet_name(), "Data");
+        assert_eq!(unknown.selected_cell, (1, 1));
+
+        std::fs::write(dir.join("positions.json"), "not json").unwrap();
+        let mut corrupt = app("budget.xlsx");
+        corrupt.restore_last_position_from(Some(dir.clone()));
+        assert_eq!(corrupt.selected_cell, (1, 1));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,170 @@
+use crate::app::AppState;
+
+/// Rows `:only` leaves showing on one sheet: those holding a search match. Frozen header rows
+/// always show. The view is filtered, not the workbook, and the rows are kept by number
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RowFilter {
+    sheet_name: String,
+    /// Sorted and without repeats
+    rows: Vec<usize>,
+}
+
+impl AppState<'_> {
+    /// Handles `:only`: hides every row of the current sheet without a current search match
+    pub fn filter_rows_to_matches(&mut self) {
+        if self.search_results.is_empty() {
+            self.add_notification("No search matches to filter by".to_string());
+            return;
+        }
+
+        let mut rows: Vec<usize> = self.search_results.iter().map(|&(row, _)| row).collect();
+        rows.sort_unstable();
+        rows.dedup();
+        let count = rows.len();
+        self.row_filter = Some(RowFilter {
+            sheet_name: self.workbook.get_current_sheet_name(),
+            rows,
+        });
+        self.handle_scrolling();
+
+        let noun = if count == 1 { "row" } else { "rows" };
+        self.add_notification(format!(
+            "Showing {count} {noun} with matches; :nofilter shows all rows"
+        ));
+    }
+
+    /// Handles `:nofilter`: shows every row again
+    pub fn clear_row_filter(&mut self) {
+        if self.row_filter.take().is_none() {
+            self.add_notification("No row filter".to_string());
+            return;
+        }
+        self.handle_scrolling();
+        self.add_notification("Showing all rows".to_string());
+    }
+
+    /// The unfrozen rows `:only` leaves showing on the current sheet, from `start` on.
+    /// `None` when the sheet is not filtered
+    #[must_use]
+    pub fn filtered_rows_from(&self, start: usize) -> Option<&[usize]> {
+        let filter = self
+            .row_filter
+            .as_ref()
+            .filter(|filter| filter.sheet_name == self.workbook.get_current_sheet_name())?;
+        let frozen_rows = self.workbook.get_current_sheet().freeze_panes.rows;
+        let first = filter
+            .rows
+            .partition_point(|&row| row < start.max(frozen_rows + 1));
+        Some(&filter.rows[first..])
+    }
+
+    /// The row `delta` showing rows away from `row`, stopping at the first and last showing row
+    pub(crate) fn step_shown_rows(&self, row: usize, delta: isize) -> usize {
+        let Some(rows) = self.filtered_rows_from(1) else {
+            return row.saturating_add_signed(delta).max(1);
+        };
+        let frozen_rows = self.workbook.get_current_sheet().freeze_panes.rows;
+        let position = if row <= frozen_rows {
+            row - 1
+        } else {
+            frozen_rows + rows.partition_point(|&shown| shown < row)
+        };
+        let last = (frozen_rows + rows.len()).saturating_sub(1);
+        let position = position.saturating_add_signed(delta).min(last);
+        if position < frozen_rows {
+            position + 1
+        } else {
+            rows.get(position - frozen_rows).copied().unwrap_or(row)
+        }
+    }
+
+    /// Moves the cursor off a row `:only` hides, to the next showing row or else the last one
+    pub(crate) fn snap_to_shown_row(&mut self) {
+        let row = self.selected_cell.0;
+        let frozen_rows = self.workbook.get_current_sheet().freeze_panes.rows;
+        let Some(rows) = self.filtered_rows_from(1) else {
+            return;
+        };
+        if row <= frozen_rows || rows.binary_search(&row).is_ok() {
+            return;
+        }
+        let shown = rows
+            .iter()
+            .find(|&&shown| shown > row)
+            .or_else(|| rows.last())
+            .copied();
+        if let Some(shown) = shown {
+            self.selected_cell.0 = shown;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::app::AppState;
+    use crate::excel::{Sheet, Workbook};
+    use std::path::PathBuf;
+
+    #[test]
+    fn only_hides_rows_without_matches_until_nofilter() {
+        let workbook = Workbook::from_sheets_for_test(vec![
+            Sheet::blank("Data".to_string()),
+            Sheet::blank("Other".to_string()),
+        ]);
+        let mut app = AppState::new(workbook, PathBuf::from("test.xlsx")).unwrap();
+        for (row, value) in ["Name", "apple", "kiwi", "apple pie", "plum", "crab apple"]
+            .into_iter()
+            .enumerate()
+        {
+            app.workbook
+                .set_cell_value(row + 1, 1, value.to_string())
+                .unwrap();
+        }
+        app.workbook.get_current_sheet_mut().freeze_panes.rows = 1;
+        app.visible_rows = 3;
+
+        app.filter_rows_to_matches();
+        assert_eq!(
+            app.notification_messages.last().unwrap().text,
+            "No search matches to filter by"
+        );
+
+        app.start_search_forward();
+        app.text_area.insert_str("apple");
+        app.execute_search();
+        app.input_buffer = "only".to_string();
+        app.execute_command();
+        assert_eq!(app.filtered_rows_from(1), Some(&[2, 4, 6][..]));
+        assert_eq!(
+            app.notification_messages.last().unwrap().text,
+            "Showing 3 rows with matches; :nofilter shows all rows"
+        );
+
+        app.selected_cell = (1, 1);
+        app.move_cursor(1, 0);
+        assert_eq!(app.selected_cell, (2, 1));
+        app.move_cursor(1, 0);
+        assert_eq!(app.selected_cell, (4, 1));
+        app.move_cursor(1, 0);
+        assert_eq!(app.selected_cell, (6, 1));
+        assert_eq!(app.start_row, 4);
+        app.move_cursor(5, 0);
+        assert_eq!(app.selected_cell, (6, 1));
+        app.move_cursor(-3, 0);
+        assert_eq!(app.selected_cell, (1, 1));
+
+        app.jump_to_row(5);
+        assert_eq!(app.selected_cell, (6, 1));
+
+        app.switch_to_sheet("Other");
+        assert_eq!(app.filtered_rows_from(1), None);
+        app.switch_to_sheet("Data");
+        assert!(app.filtered_rows_from(1).is_some());
+
+        app.input_buffer = "nofilter".to_string();
+        app.execute_command();
+        assert_eq!(app.filtered_rows_from(1), None);
+        app.move_cursor(-1, 0);
+        assert_eq!(app.selected_cell.0, 5);
+    }
+}
@@ -1,7 +1,100 @@
 use crate::app::AppState;
+use crate::app::CellRange;
 use crate::app::InputMode;
+use crate::excel::EXCEL_MAX_ROWS;
+use crate::utils::{cell_reference, index_to_col_name, SortMode};
 use ratatui::style::{Modifier, Style};
 
+/// A `/` query with its `\c` or `\C` modifier taken out. Matching ignores case unless the
+/// query contains `\C`, and with `:set matchcell` the whole cell has to match
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SearchPattern {
+    text: String,
+    case_sensitive: bool,
+    whole_cell: bool,
+}
+
+impl SearchPattern {
+    #[must_use]
+    pub fn parse(query: &str, whole_cell: bool) -> Self {
+        // Like vim, `\c` wins when both modifiers are given
+        let case_sensitive = query.contains("\\C") && !query.contains("\\c");
+        let text = query.replace("\\C", "").replace("\\c", "");
+        let text = if case_sensitive {
+            text
+        } else {
+            text.to_lowercase()
+        };
+
+        Self {
+            text,
+            case_sensitive,
+            whole_cell,
+        }
+    }
+
+    #[must_use]
+    pub fn matches(&self, value: &str) -> bool {
+        self.matches_in(value, &mut String::new())
+    }
+
+    /// Like [`Self::matches`], lowering `value` into `buffer` rather than a string of its own,
+    /// so a search through many cells reuses one allocation
+    pub fn matches_in(&self, value: &str, buffer: &mut String) -> bool {
+        let value = if self.case_sensitive {
+            value
+        } else if value.is_ascii() {
+            buffer.clear();
+            buffer.push_str(value);
+            buffer.make_ascii_lowercase();
+            buffer
+        } else {
+            // Lowering a whole string treats a final sigma as such, which lowering char by
+            // char would not
+            *buffer = value.to_lowercase();
+            buffer
+        };
+
+        if self.whole_cell {
+            value == self.text
+        } else {
+            value.contains(&self.text)
+        }
+    }
+}
+
+/// Which cells `/` and `?` look at, set with `:set searchscope=`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SearchScope {
+    #[default]
+    Sheet,
+    /// The column of the cell the search starts from
+    Column,
+    /// The visual selection when the search starts, or the whole sheet without one
+    Selection,
+}
+
+impl SearchScope {
+    #[must_use]
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "sheet" => Some(Self::Sheet),
+            "column" | "col" => Some(Self::Column),
+            "selection" | "sel" => Some(Self::Selection),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Sheet => "sheet",
+            Self::Column => "column",
+            Self::Selection => "selection",
+        }
+    }
+}
+
 impl AppState<'_> {
     pub fn start_search_forward(&mut self) {
         self.input_mode = InputMode::SearchForward;
@@ -15,6 +108,8 @@ impl AppState<'_> {
 
         self.add_notification("Search forward mode".to_string());
         self.highlight_enabled = true;
+        self.begin_incremental_search();
+        self.search_history.reset();
     }
 
     pub fn start_search_backward(&mut self) {
@@ -29,6 +124,8 @@ impl AppState<'_> {
 
         self.add_notification("Search backward mode".to_string());
         self.highlight_enabled = true;
+        self.begin_incremental_search();
+        self.search_history.reset();
     }
 
     pub fn execute_search(&mut self) {
@@ -36,12 +133,14 @@ impl AppState<'_> {
         self.input_buffer.clone_from(&query);
 
         if query.is_empty() {
-            self.input_mode = InputMode::Normal;
+            self.cancel_search();
             return;
         }
+        self.finish_incremental_search();
 
         // Save the query for n/N commands
         self.search_query.clone_from(&query);
+        self.search_history.push(&query);
 
         // Set search direction based on mode
         match self.input_mode {
@@ -50,16 +149,23 @@ impl AppState<'_> {
             _ => {}
         }
 
+        self.search_bounds = self.search_scope_bounds();
+        if self.search_scope == SearchScope::Selection {
+            // Jumping between matches would otherwise stretch the selection
+            self.clear_selection();
+        }
         self.search_results = self.find_all_matches(&query);
+        self.set_search_results_list();
 
+        let within = self.search_bounds_text();
         if self.search_results.is_empty() {
-            self.add_notification(format!("Pattern not found: {query}"));
+            self.add_notification(format!("Pattern not found: {query}{within}"));
             self.current_search_idx = None;
         } else {
             // Find the appropriate result to jump to based on search direction and current position
             self.jump_to_next_search_result();
             self.add_notification(format!(
-                "{} matches found for: {}",
+                "{} matches found for: {}{within}",
                 self.search_results.len(),
                 query
             ));
@@ -70,42 +176,101 @@ impl AppState<'_> {
         self.text_area = tui_textarea::TextArea::default();
     }
 
+    /// The area the search scope limits a new search to
+    pub(crate) fn search_scope_bounds(&self) -> Option<CellRange> {
+        match self.search_scope {
+            SearchScope::Sheet => None,
+            SearchScope::Column => {
+                let col = self.selected_cell.1;
+                Some(((1, col), (EXCEL_MAX_ROWS, col)))
+            }
+            SearchScope::Selection => self.selection_range(),
+        }
+    }
+
+    fn search_bounds_text(&self) -> String {
+        match self.search_bounds {
+            None => String::new(),
+            Some(((_, col), (EXCEL_MAX_ROWS, end_col))) if col == end_col => {
+                format!(" in column {}", index_to_col_name(col))
+            }
+            Some((start, end)) => {
+                format!(" in {}:{}", cell_reference(start), cell_reference(end))
+            }
+        }
+    }
+
     pub fn find_all_matches(&self, query: &str) -> Vec<(usize, usize)> {
         let sheet = self.workbook.get_current_sheet();
-        let query_lower = query.to_lowercase();
+        let pattern = SearchPattern::parse(query, self.match_cell);
+        let ((first_row, first_col), (last_row, last_col)) = self
+            .search_bounds
+            .unwrap_or(((1, 1), (sheet.max_rows, sheet.max_cols)));
 
-        // Pre-allocate with reasonable capacity
-        let mut results = Vec::with_capacity(32);
+        let mut results = self.indexed_search(query).unwrap_or_else(|| {
+            // Pre-allocate with reasonable capacity
+            let mut results = Vec::with_capacity(32);
+            let mut lowered = String::new();
 
-        // row-first, column-second order
-        for row in 1..=sheet.max_rows {
-            for col in 1..=sheet.max_cols {
-                if row < sheet.data.len() && col < sheet.data[row].len() {
-                    let cell_content = &sheet.data[row][col].value;
+            // row-first, column-second order
+            for row in first_row..=last_row.min(sheet.max_rows) {
+                for col in first_col..=last_col.min(sheet.max_cols) {
+                    let cell_content = &sheet.data.cell(row, col).value;
 
                     if cell_content.is_empty() {
                         continue;
                     }
 
-                    if Self::case_insensitive_contains(cell_content, &query_lower) {
+                    if pattern.matches_in(cell_content, &mut lowered) {
                         results.push((row, col));
                     }
                 }
             }
+            results
+        });
+
+        // Natural mode walks matches in value order; the stable sort keeps row-first order for ties
+        if self.sort_mode == SortMode::Natural {
+            results.sort_by(|a, b| {
+                self.sort_mode.compare(
+                    &sheet.data.cell(a.0, a.1).value,
+                    &sheet.data.cell(b.0, b.1).value,
+                )
+            });
         }
 
         results
     }
 
-    fn case_insensitive_contains(haystack: &str, needle: &str) -> bool {
-        if needle.is_empty() {
-            return true;
-        }
-        if haystack.is_empty() {
-            return false;
+    /// The cells matching `query`, in row-first order, found by checking only the cells the
+    /// search index gives. `None` when there is no finished index of the current sheet, or the
+    /// query is case-sensitive or has no word to look up
+    pub(crate) fn indexed_search(&self, query: &str) -> Option<Vec<(usize, usize)>> {
+        let sheet = self.workbook.get_current_sheet();
+        let pattern = SearchPattern::parse(query, self.match_cell);
+        if pattern.case_sensitive {
+            return None;
         }
+        let candidates =
+            self.search_index
+                .as_ref()?
+                .candidates(&sheet.name, &sheet.data, &pattern.text)?;
 
-        haystack.to_lowercase().contains(needle)
+        let ((first_row, first_col), (last_row, last_col)) = self
+            .search_bounds
+            .unwrap_or(((1, 1), (sheet.max_rows, sheet.max_cols)));
+        let rows = first_row..=last_row.min(sheet.max_rows);
+        let cols = first_col..=last_col.min(sheet.max_cols);
+        let mut lowered = String::new();
+        Some(
+            candidates
+                .into_iter()
+                .filter(|(row, col)| rows.contains(row) && cols.contains(col))
+                .filter(|&(row, col)| {
+                    pattern.matches_in(&sheet.data.cell(row, col).value, &mut lowered)
+                })
+                .collect(),
+        )
     }
 
     pub fn jump_to_next_search_result(&mut self) {
@@ -115,6 +280,13 @@ impl AppState<'_> {
 
         self.highlight_enabled = true;
 
+        if self.sort_mode == SortMode::Natural {
+            self.step_search_result_in_value_order();
+            self.select_current_search_result();
+            self.handle_scrolling();
+            return;
+        }
+
         let current_pos = self.selected_cell;
 
         if self.search_direction {
@@ -150,9 +322,59 @@ impl AppState<'_> {
             }
         }
 
+        self.select_current_search_result();
         self.handle_scrolling();
     }
 
+    /// Moves to the neighbouring match in `search_results` order, starting from the selected cell
+    /// when it is a match, and wrapping at either end
+    fn step_search_result_in_value_order(&mut self) {
+        let len = self.search_results.len();
+        let current_idx = self
+            .current_search_idx
+            .filter(|&idx| self.search_results.get(idx) == Some(&self.selected_cell))
+            .or_else(|| {
+                self.search_results
+                    .iter()
+                    .position(|&pos| pos == self.selected_cell)
+            });
+
+        let next_idx = match (current_idx, self.search_direction) {
+            (Some(idx), true) if idx + 1 < len => idx + 1,
+            (Some(_), true) => {
+                self.add_notification("Search wrapped to top".to_string());
+                0
+            }
+            (Some(idx), false) if idx > 0 => idx - 1,
+            (Some(_), false) => {
+                self.add_notification("Search wrapped to bottom".to_string());
+                len - 1
+            }
+            (None, true) => 0,
+            (None, false) => len - 1,
+        };
+
+        self.current_search_idx = Some(next_idx);
+        self.selected_cell = self.search_results[next_idx];
+    }
+
+    pub fn set_sort_mode(&mut self, mode: SortMode) {
+        self.sort_mode = mode;
+        self.refresh_search_results();
+        self.add_notification(format!("Sort mode: {}", mode.name()));
+    }
+
+    /// Searches again for the last query after an option that changes its matches was set
+    pub(crate) fn refresh_search_results(&mut self) {
+        if !self.search_query.is_empty() && !self.search_results.is_empty() {
+            self.search_results = self.find_all_matches(&self.search_query);
+            self.current_search_idx = self
+                .search_results
+                .iter()
+                .position(|&pos| pos == self.selected_cell);
+        }
+    }
+
     pub fn jump_to_prev_search_result(&mut self) {
         if self.search_results.is_empty() {
             return;
@@ -165,8 +387,58 @@ impl AppState<'_> {
         self.search_direction = !self.search_direction;
     }
 
+    /// The 1-based number of the match under the cursor and how many there are, for the
+    /// status bar's "match 7/42". The number is `None` when the cursor is not on a match
+    pub fn search_match_position(&self) -> Option<(Option<usize>, usize)> {
+        if !self.highlight_enabled || self.search_results.is_empty() {
+            return None;
+        }
+        let idx = self
+            .current_search_idx
+            .filter(|&idx| self.search_results.get(idx) == Some(&self.selected_cell))
+            .or_else(|| {
+                self.search_results
+                    .iter()
+                    .position(|&pos| pos == self.selected_cell)
+            });
+        Some((idx.map(|idx| idx + 1), self.search_results.len()))
+    }
+
     pub fn disable_search_highlight(&mut self) {
         self.highlight_enabled = false;
         self.add_notification("Search highlighting disabled".to_string());
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::SearchPattern;
+
+    #[test]
+    fn search_modifiers_control_case_and_whole_cell_matching() {
+        let default = SearchPattern::parse("Total", false);
+        assert!(default.matches("Grand TOTAL"));
+
+        let sensitive = SearchPattern::parse("\\CTotal", false);
+        assert!(sensitive.matches("Grand Total"));
+        assert!(!sensitive.matches("Grand TOTAL"));
+        assert!(SearchPattern::parse("Total\\c\\C", false).matches("total"));
+
+        let whole = SearchPattern::parse("2023", true);
+        assert!(whole.matches("2023"));
+        assert!(!whole.matches("2023-01-05"));
+        assert!(SearchPattern::parse("total", true).matches("TOTAL"));
+        assert!(!SearchPattern::parse("total\\C", true).matches("TOTAL"));
+    }
+
+    #[test]
+    fn one_buffer_serves_a_whole_search() {
+        let pattern = SearchPattern::parse("straße", false);
+        let mut buffer = String::new();
+        assert!(pattern.matches_in("STRASSE STRAßE", &mut buffer));
+        assert!(!pattern.matches_in("STRASSE", &mut buffer));
+        assert!(SearchPattern::parse("abc", false).matches_in("xABCx", &mut buffer));
+        assert_eq!(buffer, "xabcx");
+        assert!(SearchPattern::parse("ος", true).matches_in("ΟΣ", &mut buffer));
+    }
+}
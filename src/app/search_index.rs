@@ -0,0 +1,164 @@
+use std::collections::HashMap;
+
+use crate::app::AppState;
+use crate::excel::CellGrid;
+
+/// Sheets with fewer cells holding something are searched cell by cell, which is quick enough
+const INDEX_MIN_CELLS: usize = 50_000;
+
+/// Cells indexed in one pass of the event loop, so building the index never holds up a key
+const INDEX_CHUNK_CELLS: usize = 20_000;
+
+/// The lowercase words of the current sheet's cells and where each occurs, built a chunk at a
+/// time between keypresses. A search looks up the words holding the longest word of its query
+/// and checks only those cells instead of every cell of the sheet
+pub struct SearchIndex {
+    sheet_name: String,
+    /// The revision of the sheet's cells the index is built from
+    revision: u64,
+    words: HashMap<String, Vec<(usize, usize)>>,
+    /// The row to index next, or `None` once every row is indexed
+    next_row: Option<usize>,
+}
+
+impl SearchIndex {
+    fn new(sheet_name: &str, data: &CellGrid) -> Self {
+        Self {
+            sheet_name: sheet_name.to_string(),
+            revision: data.revision(),
+            words: HashMap::new(),
+            next_row: Some(0),
+        }
+    }
+
+    fn is_for(&self, sheet_name: &str, data: &CellGrid) -> bool {
+        self.sheet_name == sheet_name && self.revision == data.revision()
+    }
+
+    /// Indexes the next rows of `data`, up to about [`INDEX_CHUNK_CELLS`] cells
+    fn index_rows(&mut self, data: &CellGrid) {
+        let Some(start) = self.next_row else {
+            return;
+        };
+        let mut indexed = 0;
+        for (row, cells) in data.rows_from(start) {
+            if indexed >= INDEX_CHUNK_CELLS {
+                self.next_row = Some(row);
+                return;
+            }
+            for (col, cell) in cells {
+                indexed += 1;
+                for word in words(&cell.value.to_lowercase()) {
+                    let places = self.words.entry(word.to_string()).or_default();
+                    // A word twice in a cell lists the cell once
+                    if places.last() != Some(&(row, col)) {
+                        places.push((row, col));
+                    }
+                }
+            }
+        }
+        self.next_row = None;
+    }
+
+    /// The cells of `sheet_name` that may hold `query`, ignoring case, row by row: those with
+    /// a word containing the longest word of `query`. `None` when the index is unfinished or
+    /// out of date, or `query` has no word to look up
+    #[must_use]
+    pub fn candidates(
+        &self,
+        sheet_name: &str,
+        data: &CellGrid,
+        query: &str,
+    ) -> Option<Vec<(usize, usize)>> {
+        if self.next_row.is_some() || !self.is_for(sheet_name, data) {
+            return None;
+        }
+        let query = query.to_lowercase();
+        let longest = words(&query).max_by_key(|word| word.len())?;
+        let mut cells: Vec<(usize, usize)> = self
+            .words
+            .iter()
+            .filter(|(word, _)| word.contains(longest))
+            .flat_map(|(_, places)| places.iter().copied())
+            .collect();
+        cells.sort_unstable();
+        cells.dedup();
+        Some(cells)
+    }
+}
+
+fn words(text: &str) -> impl Iterator<Item = &str> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+}
+
+impl AppState<'_> {
+    /// Builds the search index of the current sheet a chunk at a time, starting over when
+    /// its cells change or another sheet is shown. Called every pass of the event loop
+    pub fn poll_search_index(&mut self) {
+        let sheet = self.workbook.get_current_sheet();
+        if !self.use_search_index || !sheet.is_loaded || sheet.data.len() < INDEX_MIN_CELLS {
+            self.search_index = None;
+            return;
+        }
+
+        let index = match &mut self.search_index {
+            Some(index) if index.is_for(&sheet.name, &sheet.data) => index,
+            index => index.insert(SearchIndex::new(&sheet.name, &sheet.data)),
+        };
+        index.index_rows(&sheet.data);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::app::AppState;
+    use crate::excel::{Cell, Sheet, Workbook};
+    use std::path::PathBuf;
+
+    #[test]
+    fn long_sheets_are_searched_through_an_index_kept_up_to_date() {
+        let mut sheet = Sheet::blank("Log".to_string());
+        for row in 1..=50_000 {
+            let value = format!(
+                "order-{row} {}",
+                if row % 1000 == 0 { "Refund" } else { "paid" }
+            );
+            sheet.data.set(row, 1, Cell::new(value, false));
+        }
+        sheet.max_rows = 50_000;
+        sheet.max_cols = 1;
+        let mut app = AppState::new(
+            Workbook::from_sheets_for_test(vec![sheet]),
+            PathBuf::from("log.xlsx"),
+        )
+        .unwrap();
+        let expected: Vec<(usize, usize)> = (1..=50).map(|n| (n * 1000, 1)).collect();
+        assert_eq!(app.find_all_matches("refund"), expected);
+
+        // The index is built over a few passes of the event loop
+        app.poll_search_index();
+        app.poll_search_index();
+        assert!(app.indexed_search("refund").is_none());
+        app.poll_search_index();
+        assert_eq!(app.indexed_search("refund"), Some(expected.clone()));
+        assert_eq!(app.find_all_matches("REFUND"), expected);
+        assert_eq!(app.find_all_matches("r-12 "), vec![(12, 1)]);
+        // Case-sensitive queries, and queries without a word, are not looked up
+        assert!(app.indexed_search("\\CRefund").is_none());
+        assert_eq!(app.find_all_matches("\\Crefund"), vec![]);
+        assert!(app.indexed_search("-").is_none());
+
+        app.workbook.get_current_sheet_mut().data.set(
+            3,
+            1,
+            Cell::new("late refund".to_string(), false),
+        );
+        assert!(app.indexed_search("refund").is_none());
+        assert_eq!(app.find_all_matches("refund")[0], (3, 1));
+
+        app.handle_set_command("nosearchindex");
+        app.poll_search_index();
+        assert!(app.search_index.is_none());
+    }
+}
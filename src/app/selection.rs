@@ -0,0 +1,101 @@
+use crate::app::{AppState, CellRange};
+use crate::excel::numeric_value;
+use crate::utils::cell_reference;
+
+/// Figures over the numbers in a visual selection, shown in the status bar
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SelectionStats {
+    /// Cells holding a number; text and blank cells are left out of every figure
+    pub count: usize,
+    pub sum: f64,
+    pub min: f64,
+    pub max: f64,
+}
+
+impl SelectionStats {
+    #[must_use]
+    pub fn average(&self) -> f64 {
+        self.sum / self.count as f64
+    }
+}
+
+impl AppState<'_> {
+    /// Starts a visual selection anchored at the selected cell, or ends the current one
+    pub fn toggle_visual_selection(&mut self) {
+        if self.selection_anchor.take().is_none() {
+            self.selection_anchor = Some(self.selected_cell);
+        }
+    }
+
+    /// Anchors a selection at the selected cell unless one is already active
+    pub fn anchor_selection(&mut self) {
+        if self.selection_anchor.is_none() {
+            self.selection_anchor = Some(self.selected_cell);
+        }
+    }
+
+    pub fn clear_selection(&mut self) {
+        self.selection_anchor = None;
+    }
+
+    /// The rectangle spanned by the selection anchor and the selected cell
+    pub fn selection_range(&self) -> Option<CellRange> {
+        let (anchor_row, anchor_col) = self.selection_anchor?;
+        let (row, col) = self.selected_cell;
+        Some((
+            (anchor_row.min(row), anchor_col.min(col)),
+            (anchor_row.max(row), anchor_col.max(col)),
+        ))
+    }
+
+    pub fn is_cell_selected(&self, row: usize, col: usize) -> bool {
+        self.selection_range()
+            .is_some_and(|((start_row, start_col), (end_row, end_col))| {
+                (start_row..=end_row).contains(&row) && (start_col..=end_col).contains(&col)
+            })
+    }
+
+    /// Takes the visual selection for a command to act on, falling back to the used rows of the
+    /// current column
+    pub(crate) fn take_selection_or_current_column(&mut self) -> CellRange {
+        if let Some(range) = self.selection_range() {
+            self.clear_selection();
+            return range;
+        }
+
+        let col = self.selected_cell.1;
+        let max_rows = self.workbook.get_current_sheet().max_rows.max(1);
+        ((1, col), (max_rows, col))
+    }
+
+    /// A1-style text for the selection, e.g. "B2:D5"
+    pub fn selection_text(&self) -> Option<String> {
+        self.selection_range()
+            .map(|(start, end)| format!("{}:{}", cell_reference(start), cell_reference(end)))
+    }
+
+    /// Count, sum, minimum and maximum of the numeric cells in the selection. `None` without
+    /// a selection or when it holds no numbers
+    #[must_use]
+    pub fn selection_stats(&self) -> Option<SelectionStats> {
+        let ((start_row, start_col), (end_row, end_col)) = self.selection_range()?;
+        let data = &self.workbook.get_current_sheet().data;
+        let mut stats: Option<SelectionStats> = None;
+        for (_, cell) in data.cells_in((start_row, start_col), (end_row, end_col)) {
+            let Some(value) = numeric_value(cell) else {
+                continue;
+            };
+            let stats = stats.get_or_insert(SelectionStats {
+                count: 0,
+                sum: 0.0,
+                min: value,
+                max: value,
+            });
+            stats.count += 1;
+            stats.sum += value;
+            stats.min = stats.min.min(value);
+            stats.max = stats.max.max(value);
+        }
+        stats
+    }
+}
@@ -0,0 +1,265 @@
+use std::path::PathBuf;
+
+use crate::app::{AppState, Clipboard};
+
+/// A change of workbook asked for from inside one, which only the session can carry out
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BufferRequest {
+    Next,
+    Prev,
+    /// 0-based position of the buffer to switch to
+    Goto(usize),
+    List,
+}
+
+/// The workbooks opened together, one buffer each. Every buffer keeps its own cursor, undo
+/// history and mode; only the current one is drawn and receives keys. The clipboard follows
+/// the current buffer so cells can be copied from one workbook into another
+pub struct Session<'a> {
+    buffers: Vec<AppState<'a>>,
+    current: usize,
+    /// Configuration directory where each workbook's last position and the search history
+    /// are saved as it closes
+    state_dir: Option<PathBuf>,
+}
+
+impl<'a> Session<'a> {
+    #[must_use]
+    pub fn new(first: AppState<'a>, rest: Vec<AppState<'a>>) -> Self {
+        let mut session = Self {
+            buffers: std::iter::once(first).chain(rest).collect(),
+            current: 0,
+            state_dir: None,
+        };
+        session.update_buffer_positions();
+        session
+    }
+
+    /// Saves where each workbook was left and the search history in `config_dir` when it
+    /// closes, for `restore_last_position` and `load_history` in the next session
+    #[must_use]
+    pub fn saving_state_in(mut self, config_dir: Option<PathBuf>) -> Self {
+        self.state_dir = config_dir;
+        self
+    }
+
+    /// Copies yanked cells to the system clipboard too, and pastes text copied elsewhere
+    #[must_use]
+    pub fn using_system_clipboard(mut self) -> Self {
+        self.current_mut().clipboard = Clipboard::system();
+        self
+    }
+
+    pub fn current(&self) -> &AppState<'a> {
+        &self.buffers[self.current]
+    }
+
+    pub fn current_mut(&mut self) -> &mut AppState<'a> {
+        &mut self.buffers[self.current]
+    }
+
+    /// Whether every buffer has been closed
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.buffers.is_empty()
+    }
+
+    /// Carries out what the current buffer asked for while handling the last key: closing it
+    /// after `:q`, or switching to or listing the other buffers
+    pub fn apply_requests(&mut self) {
+        if self.buffers[self.current].should_quit {
+            self.close_current();
+            return;
+        }
+
+        let Some(request) = self.buffers[self.current].buffer_request.take() else {
+            return;
+        };
+        let count = self.buffers.len();
+        let target = match request {
+            BufferRequest::List => {
+                let listing = self.buffer_listing();
+                self.current_mut().add_notification(listing);
+                return;
+            }
+            _ if count == 1 => {
+                self.current_mut()
+                    .add_notification("Only one workbook is open".to_string());
+                return;
+            }
+            BufferRequest::Next => (self.current + 1) % count,
+            BufferRequest::Prev => (self.current + count - 1) % count,
+            BufferRequest::Goto(index) if index < count => index,
+            BufferRequest::Goto(index) => {
+                self.current_mut().add_notification(format!(
+                    "No buffer {}: {count} workbooks are open",
+                    index + 1
+                ));
+                return;
+            }
+        };
+        self.switch_to(target);
+    }
+
+    fn switch_to(&mut self, target: usize) {
+        let clipboard = std::mem::take(&mut self.buffers[self.current].clipboard);
+        self.buffers[target].clipboard = clipboard;
+        self.current = target;
+
+        let message = format!(
+            "Buffer {}/{}: {}",
+            target + 1,
+            self.buffers.len(),
+            self.current().file_path.display()
+        );
+        self.current_mut().add_notification(message);
+    }
+
+    fn close_current(&mut self) {
+        let closed = self.buffers.remove(self.current);
+        if let Some(dir) = &self.state_dir {
+            // The buffer is going away, so there is nowhere left to report a failure
+            let _ = closed.remember_last_position_in(dir);
+            let _ = closed.save_history_in(dir);
+        }
+        if self.buffers.is_empty() {
+            return;
+        }
+
+        self.current = self.current.min(self.buffers.len() - 1);
+        self.current_mut().clipboard = closed.clipboard;
+        self.update_buffer_positions();
+        let message = format!(
+            "Closed {}; switched to {}",
+            closed.file_path.display(),
+            self.current().file_path.display()
+        );
+        self.current_mut().add_notification(message);
+    }
+
+    fn buffer_listing(&self) -> String {
+        let entries = self
+            .buffers
+            .iter()
+            .enumerate()
+            .map(|(index, buffer)| {
+                let marker = if index == self.current { "%" } else { "" };
+                let modified = if buffer.workbook.is_modified() {
+                    " +"
+                } else {
+                    ""
+                };
+                format!(
+                    "{}{marker} {}{modified}",
+                    index + 1,
+                    buffer.file_path.display()
+                )
+            })
+            .collect::<Vec<_>>();
+        format!("Buffers: {}", entries.join(", "))
+    }
+
+    fn update_buffer_positions(&mut self) {
+        let count = self.buffers.len();
+        for (index, buffer) in self.buffers.iter_mut().enumerate() {
+            buffer.buffer_position = (count > 1).then_some((index + 1, count));
+        }
+    }
+}
+
+impl AppState<'_> {
+    /// Handles `:buffer`, `:b N`, `:bn` and `:bp`, leaving the switch to the session
+    pub(crate) fn handle_buffer_command(&mut self, args: &str) {
+        let args = args.trim();
+        let request = match args {
+            "" => BufferRequest::List,
+            _ => match args.parse::<usize>() {
+                Ok(number) if number > 0 => BufferRequest::Goto(number - 1),
+                _ => {
+                    self.add_warning("Usage: :buffer [number]".to_string());
+                    return;
+                }
+            },
+        };
+        self.buffer_request = Some(request);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Session;
+    use crate::app::AppState;
+    use crate::excel::{Cell, Sheet, Workbook};
+    use std::path::PathBuf;
+
+    fn buffer(file: &str, value: &str) -> AppState<'static> {
+        let mut sheet = Sheet::blank("Data".to_string());
+        sheet.data.set(1, 1, Cell::new(value.to_string(), false));
+        AppState::new(
+            Workbook::from_sheets_for_test(vec![sheet]),
+            PathBuf::from(file),
+        )
+        .unwrap()
+    }
+
+    fn run(session: &mut Session, command: &str) {
+        session.current_mut().input_buffer = command.to_string();
+        session.current_mut().execute_command();
+        session.apply_requests();
+    }
+
+    #[test]
+    fn buffers_keep_their_own_state_and_share_the_clipboard() {
+        let mut session = Session::new(buffer("a.xlsx", "left"), vec![buffer("b.xlsx", "right")]);
+        assert_eq!(session.current().buffer_position, Some((1, 2)));
+
+        session.current_mut().copy_cell();
+        session.current_mut().selected_cell = (3, 2);
+        run(&mut session, "bn");
+        assert_eq!(session.current().file_path, PathBuf::from("b.xlsx"));
+        assert_eq!(session.current().selected_cell, (1, 1));
+        assert_eq!(
+            session.current().notification_messages.last().unwrap().text,
+            "Buffer 2/2: b.xlsx"
+        );
+
+        session.current_mut().selected_cell = (2, 1);
+        session.current_mut().paste_cell().unwrap();
+        assert_eq!(session.current().get_cell_content(2, 1), "left");
+        assert!(session.current().workbook.is_modified());
+
+        run(&mut session, "b 1");
+        assert_eq!(session.current().selected_cell, (3, 2));
+        assert!(!session.current().workbook.is_modified());
+        run(&mut session, "buffer");
+        assert_eq!(
+            session.current().notification_messages.last().unwrap().text,
+            "Buffers: 1% a.xlsx, 2 b.xlsx +"
+        );
+        run(&mut session, "b 3");
+        assert_eq!(
+            session.current().notification_messages.last().unwrap().text,
+            "No buffer 3: 2 workbooks are open"
+        );
+    }
+
+    #[test]
+    fn quitting_closes_one_buffer_at_a_time() {
+        let mut session = Session::new(buffer("a.xlsx", "left"), vec![buffer("b.xlsx", "right")]);
+
+        run(&mut session, "bp");
+        assert_eq!(session.current().file_path, PathBuf::from("b.xlsx"));
+        run(&mut session, "q");
+        assert_eq!(session.current().file_path, PathBuf::from("a.xlsx"));
+        assert_eq!(session.current().buffer_position, None);
+        assert!(!session.is_finished());
+
+        run(&mut session, "bn");
+        assert_eq!(
+            session.current().notification_messages.last().unwrap().text,
+            "Only one workbook is open"
+        );
+        run(&mut session, "q");
+        assert!(session.is_finished());
+    }
+}
@@ -1,33 +1,109 @@
 use crate::actions::{
-    ActionCommand, ColumnAction, MultiColumnAction, MultiRowAction, RowAction, SheetAction,
-    SheetOperation,
+    ActionCommand, ColumnAction, ColumnWidthAction, ColumnWidthChange, MultiColumnAction,
+    MultiRowAction, RowAction, SheetAction, SheetOperation, SheetRenameAction,
 };
 use crate::app::AppState;
-use crate::utils::index_to_col_name;
+use crate::excel::{open_workbook, Cell, Sheet, SheetVisibility, EXCEL_MAX_COLS, EXCEL_MAX_ROWS};
+use crate::utils::{index_to_col_name, parse_sheet_link, text_width};
 use anyhow::Result;
+use std::collections::BTreeSet;
+use std::path::Path;
+
+/// Sheets longer than this fit column widths to a sample of their rows unless `:cw fit` is
+/// asked for `exact`
+const FIT_SAMPLE_ROWS: usize = 2_000;
 
 impl AppState<'_> {
     pub fn next_sheet(&mut self) -> Result<()> {
+        self.step_sheets(1, true)
+    }
+
+    pub fn prev_sheet(&mut self) -> Result<()> {
+        self.step_sheets(1, false)
+    }
+
+    /// Moves `count` sheets forward or back, as `3]` does, stopping at the first or last sheet.
+    /// Hidden sheets are skipped unless shown
+    pub fn step_sheets(&mut self, count: usize, forward: bool) -> Result<()> {
         let sheet_count = self.workbook.get_sheet_names().len();
         let current_index = self.workbook.get_current_sheet_index();
+        let shown: Vec<usize> = if forward {
+            (current_index + 1..sheet_count)
+                .filter(|&i| self.is_sheet_shown(i))
+                .collect()
+        } else {
+            (0..current_index)
+                .rev()
+                .filter(|&i| self.is_sheet_shown(i))
+                .collect()
+        };
 
-        if current_index >= sheet_count - 1 {
-            self.add_notification("Already at the last sheet".to_string());
+        let Some(&index) = shown.get(count.max(1) - 1).or(shown.last()) else {
+            let edge = if forward { "last" } else { "first" };
+            self.add_notification(format!("Already at the {edge} sheet"));
             return Ok(());
-        }
+        };
 
-        self.switch_sheet_by_index(current_index + 1)
+        self.switch_sheet_by_index(index)
     }
 
-    pub fn prev_sheet(&mut self) -> Result<()> {
-        let current_index = self.workbook.get_current_sheet_index();
+    /// Scrolls the tab bar `count` tabs right, or left when negative, leaving the current sheet
+    /// as it is. Switching sheets brings the current tab back into view
+    pub fn scroll_tabs(&mut self, count: isize) {
+        let shown = (0..self.workbook.get_sheet_names().len())
+            .filter(|&index| self.is_sheet_shown(index))
+            .count();
+        self.first_tab = self
+            .first_tab
+            .saturating_add_signed(count)
+            .min(shown.saturating_sub(1));
+        self.tabs_scrolled_on = Some(self.workbook.get_current_sheet_index());
+    }
 
-        if current_index == 0 {
-            self.add_notification("Already at the first sheet".to_string());
-            return Ok(());
+    /// Whether the tab bar and `[`/`]` include the sheet at `index`. Hidden sheets only
+    /// show after `:showhidden`, or while they are the current sheet
+    pub fn is_sheet_shown(&self, index: usize) -> bool {
+        self.show_hidden_sheets
+            || index == self.workbook.get_current_sheet_index()
+            || self
+                .workbook
+                .get_sheet_by_index(index)
+                .is_some_and(|sheet| !sheet.visibility.is_hidden())
+    }
+
+    /// Toggles whether hidden sheets show in the tab bar, switching to the first of them when
+    /// they are revealed. The sheets stay hidden in saved files
+    pub fn toggle_hidden_sheets(&mut self) {
+        let hidden: Vec<usize> = (0..self.workbook.get_sheet_names().len())
+            .filter(|&index| {
+                self.workbook
+                    .get_sheet_by_index(index)
+                    .is_some_and(|sheet| sheet.visibility.is_hidden())
+            })
+            .collect();
+        let Some(&first_hidden) = hidden.first() else {
+            self.add_notification("This workbook has no hidden sheets".to_string());
+            return;
+        };
+
+        self.show_hidden_sheets = !self.show_hidden_sheets;
+        if !self.show_hidden_sheets {
+            self.add_notification("Hidden sheets are left out of the tab bar again".to_string());
+            return;
         }
 
-        self.switch_sheet_by_index(current_index - 1)
+        if !hidden.contains(&self.workbook.get_current_sheet_index()) {
+            if let Err(e) = self.switch_sheet_by_index(first_hidden) {
+                self.add_error(format!("Failed to switch to hidden sheet: {e}"));
+            }
+        }
+        let names = hidden
+            .iter()
+            .filter_map(|&index| self.workbook.get_sheet_by_index(index))
+            .map(|sheet| sheet.name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+        self.add_notification(format!("Showing hidden sheets: {names}"));
     }
 
     pub fn switch_sheet_by_index(&mut self, index: usize) -> Result<()> {
@@ -50,6 +126,7 @@ impl AppState<'_> {
             .insert(current_sheet_name, current_position);
 
         self.workbook.switch_sheet(index)?;
+        self.clear_selection();
 
         let new_sheet_name = self.workbook.get_current_sheet_name();
 
@@ -98,9 +175,7 @@ impl AppState<'_> {
         if is_lazy_loading && !is_sheet_loaded {
             // If the sheet is not loaded, switch to LazyLoading mode
             self.input_mode = crate::app::InputMode::LazyLoading;
-            self.add_notification(format!(
-                "Switched to sheet: {new_sheet_name} (press Enter to load)"
-            ));
+            self.add_notification(format!("Switched to sheet: {new_sheet_name} (loading)"));
         } else {
             self.add_notification(format!("Switched to sheet: {new_sheet_name}"));
         }
@@ -108,6 +183,45 @@ impl AppState<'_> {
         Ok(())
     }
 
+    /// Follows the current cell when its text, or its xlsx hyperlink, names a sheet or a
+    /// `Sheet!A1` location. Returns `false` when the cell is not such a link.
+    pub fn follow_sheet_link(&mut self) -> bool {
+        let (row, col) = self.selected_cell;
+        let sheet = self.workbook.get_current_sheet();
+        let Some(cell) = sheet.data.get(row, col) else {
+            return false;
+        };
+        let sheet_names = self.workbook.get_sheet_names();
+        let internal_link = cell
+            .hyperlink
+            .as_deref()
+            .and_then(|link| link.strip_prefix('#'))
+            .and_then(|location| parse_sheet_link(location, &sheet_names));
+        let text_link = (!cell.is_formula)
+            .then(|| parse_sheet_link(&cell.value, &sheet_names))
+            .flatten();
+        let Some((index, target)) = internal_link.or(text_link) else {
+            return false;
+        };
+
+        self.jump_to_sheet_location(index, target);
+        true
+    }
+
+    /// Switches to the sheet at `index` and, when given, selects `cell` there
+    pub(crate) fn jump_to_sheet_location(&mut self, index: usize, cell: Option<(usize, usize)>) {
+        if index != self.workbook.get_current_sheet_index() {
+            if let Err(e) = self.switch_sheet_by_index(index) {
+                self.add_error(format!("Failed to follow link: {e}"));
+                return;
+            }
+        }
+        if let Some(cell) = cell {
+            self.selected_cell = Self::clamp_cell_to_excel_bounds(cell);
+            self.handle_scrolling();
+        }
+    }
+
     pub fn switch_to_sheet(&mut self, name_or_index: &str) {
         // Get all sheet names
         let sheet_names = self.workbook.get_sheet_names();
@@ -121,7 +235,7 @@ impl AppState<'_> {
                 match self.switch_sheet_by_index(zero_based_index) {
                     Ok(()) => return,
                     Err(e) => {
-                        self.add_notification(format!("Failed to switch to sheet {index}: {e}"));
+                        self.add_error(format!("Failed to switch to sheet {index}: {e}"));
                         return;
                     }
                 }
@@ -134,9 +248,7 @@ impl AppState<'_> {
                 match self.switch_sheet_by_index(i) {
                     Ok(()) => return,
                     Err(e) => {
-                        self.add_notification(format!(
-                            "Failed to switch to sheet '{name_or_index}': {e}"
-                        ));
+                        self.add_error(format!("Failed to switch to sheet '{name_or_index}': {e}"));
                         return;
                     }
                 }
@@ -148,19 +260,180 @@ impl AppState<'_> {
     }
 
     pub fn create_sheet(&mut self, name: &str) {
+        self.insert_new_sheet(Sheet::blank(name.to_string()));
+    }
+
+    /// Appends an empty sheet after the last one, named `SheetN` unless `name` is given
+    pub fn append_sheet(&mut self, name: Option<&str>) {
+        let name = name.map_or_else(|| self.workbook.default_sheet_name(), str::to_string);
+        let index = self.workbook.get_sheet_names().len();
+        self.insert_new_sheet_at(Sheet::blank(name), index, None);
+    }
+
+    /// Inserts a copy of the current sheet, with its column widths, after it. The copy is named
+    /// `name`, or like Excel's `Data (2)` when no name is given.
+    pub fn duplicate_current_sheet(&mut self, name: Option<&str>) {
+        let index = self.workbook.get_current_sheet_index();
+        let source_name = self.workbook.get_current_sheet_name();
+        if let Err(e) = self.workbook.ensure_sheet_loaded(index, &source_name) {
+            self.add_error(format!("Cannot duplicate sheet {source_name}: {e}"));
+            return;
+        }
+
+        let name = name.map_or_else(
+            || self.workbook.copy_sheet_name(&source_name),
+            str::to_string,
+        );
+        let mut sheet = self.workbook.get_current_sheet().clone();
+        sheet.name = name;
+        sheet.visibility = SheetVisibility::Visible;
+        if let Some(copy_name) =
+            self.insert_new_sheet_at(sheet, index + 1, Some(self.column_widths.clone()))
+        {
+            self.retract_notification();
+            self.add_notification(format!("Duplicated sheet {source_name} as {copy_name}"));
+        }
+    }
+
+    /// Appends a copy of the current sheet to another xlsx file and saves that workbook as a
+    /// timestamped copy next to it, leaving this workbook untouched
+    pub fn copy_sheet_to_file(&mut self, target: &str) {
+        let target = target.trim().trim_matches(|c| c == '"' || c == '\'');
+        let is_xlsx = Path::new(target)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| {
+                ext.eq_ignore_ascii_case("xlsx") || ext.eq_ignore_ascii_case("xlsm")
+            });
+        if !is_xlsx {
+            self.add_error(format!(
+                "Cannot copy into {target}: only .xlsx and .xlsm files can be written"
+            ));
+            return;
+        }
+
+        let index = self.workbook.get_current_sheet_index();
+        let source_name = self.workbook.get_current_sheet_name();
+        if let Err(e) = self.workbook.ensure_sheet_loaded(index, &source_name) {
+            self.add_error(format!("Cannot copy sheet {source_name}: {e}"));
+            return;
+        }
+
+        let result = open_workbook(target, false).and_then(|mut other| {
+            let mut sheet = self.workbook.get_current_sheet().clone();
+            sheet.name = other.copy_sheet_name(&source_name);
+            sheet.visibility = SheetVisibility::Visible;
+            let end = other.get_sheet_names().len();
+            let copy_name = other.add_sheet_with_data(sheet, end)?;
+            let saved_path = other.save_timestamped_copy()?;
+            Ok((copy_name, saved_path))
+        });
+
+        match result {
+            Ok((copy_name, saved_path)) => {
+                let saved_name = saved_path.file_name().map_or_else(
+                    || saved_path.display().to_string(),
+                    |name| name.to_string_lossy().to_string(),
+                );
+                self.add_notification(format!(
+                    "Copied sheet {source_name} as {copy_name} into {saved_name}"
+                ));
+            }
+            Err(e) => self.add_error(format!("Cannot copy sheet into {target}: {e}")),
+        }
+    }
+
+    /// Handles `:importsheet <file> <sheet>`: reads only that sheet of the other workbook and
+    /// inserts a copy of it after the current sheet. Without a sheet, lists the file's sheets
+    pub fn import_sheet_from_file(&mut self, args: &str) {
+        let (file, sheet_spec) = split_file_argument(args);
+        if file.is_empty() {
+            self.add_warning("Usage: :importsheet <file> <sheet>".to_string());
+            return;
+        }
+        let sheet_spec = sheet_spec.trim_matches(|c| c == '"' || c == '\'');
+
+        // Lazy loading leaves every sheet but the imported one unread
+        let mut other = match open_workbook(file, true) {
+            Ok(other) => other,
+            Err(e) => {
+                self.add_error(format!("Cannot import from {file}: {e}"));
+                return;
+            }
+        };
+        let sheet_names = other.get_sheet_names();
+        if sheet_spec.is_empty() {
+            self.add_notification(format!(
+                "Sheets in {file}: {}. Use :importsheet <file> <sheet>",
+                sheet_names.join(", ")
+            ));
+            return;
+        }
+
+        let index = sheet_names
+            .iter()
+            .position(|name| name.eq_ignore_ascii_case(sheet_spec))
+            .or_else(|| {
+                sheet_spec
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|number| number.checked_sub(1))
+                    .filter(|&index| index < sheet_names.len())
+            });
+        let Some(index) = index else {
+            self.add_notification(format!("Sheet '{sheet_spec}' not found in {file}"));
+            return;
+        };
+
+        let source_name = sheet_names[index].clone();
+        if let Err(e) = other.ensure_sheet_loaded(index, &source_name) {
+            self.add_error(format!("Cannot import sheet {source_name}: {e}"));
+            return;
+        }
+        let Some(mut sheet) = other.get_sheet_by_index(index).cloned() else {
+            return;
+        };
+        sheet.name = self.workbook.copy_sheet_name(&source_name);
+        sheet.visibility = SheetVisibility::Visible;
+
         let insert_index = self.workbook.get_current_sheet_index() + 1;
+        if let Some(name) = self.insert_new_sheet_at(sheet, insert_index, None) {
+            self.retract_notification();
+            self.add_notification(format!(
+                "Imported sheet {source_name} from {file} as {name}"
+            ));
+        }
+    }
 
-        match self.workbook.add_sheet(name, insert_index) {
+    /// Inserts `sheet` after the current one, switches to it and records an undoable creation.
+    /// Returns the final sheet name on success.
+    pub(crate) fn insert_new_sheet(&mut self, sheet: Sheet) -> Option<String> {
+        let insert_index = self.workbook.get_current_sheet_index() + 1;
+        self.insert_new_sheet_at(sheet, insert_index, None)
+    }
+
+    /// Inserts `sheet` at `insert_index`, switches to it and records an undoable creation.
+    /// Columns get `column_widths`, or the default width.
+    pub(crate) fn insert_new_sheet_at(
+        &mut self,
+        sheet: Sheet,
+        insert_index: usize,
+        column_widths: Option<Vec<usize>>,
+    ) -> Option<String> {
+        match self.workbook.add_sheet_with_data(sheet, insert_index) {
             Ok(sheet_name) => {
-                let default_width = 15;
-                let max_cols = self
-                    .workbook
-                    .get_sheet_by_index(insert_index)
-                    .map(|sheet| sheet.max_cols)
-                    .unwrap_or(1);
+                let column_widths = column_widths.unwrap_or_else(|| {
+                    let default_width = 15;
+                    let max_cols = self
+                        .workbook
+                        .get_sheet_by_index(insert_index)
+                        .map(|sheet| sheet.max_cols)
+                        .unwrap_or(1);
+                    vec![default_width; max_cols + 1]
+                });
 
                 self.sheet_column_widths
-                    .insert(sheet_name.clone(), vec![default_width; max_cols + 1]);
+                    .insert(sheet_name.clone(), column_widths);
                 self.sheet_cell_positions.insert(
                     sheet_name.clone(),
                     crate::app::CellPosition {
@@ -173,11 +446,11 @@ impl AppState<'_> {
                     self.sheet_column_widths.remove(&sheet_name);
                     self.sheet_cell_positions.remove(&sheet_name);
                     let _ = self.workbook.delete_sheet_at_index(insert_index);
-                    self.add_notification(format!("Failed to switch to new sheet: {e}"));
-                    return;
+                    self.add_error(format!("Failed to switch to new sheet: {e}"));
+                    return None;
                 }
 
-                self.notification_messages.pop();
+                self.retract_notification();
 
                 let sheet_action = SheetAction {
                     sheet_index: insert_index,
@@ -185,18 +458,75 @@ impl AppState<'_> {
                     sheet_data: self.workbook.get_current_sheet().clone(),
                     column_widths: self.column_widths.clone(),
                     operation: SheetOperation::Create,
+                    packed_cells: None,
                 };
 
                 self.undo_history.push(ActionCommand::Sheet(sheet_action));
                 self.input_mode = crate::app::InputMode::Normal;
                 self.add_notification(format!("Created sheet: {sheet_name}"));
+                Some(sheet_name)
             }
             Err(e) => {
-                self.add_notification(format!("Failed to add sheet: {e}"));
+                self.add_error(format!("Failed to add sheet: {e}"));
+                None
             }
         }
     }
 
+    /// Renames the current sheet, keeping its column widths and cursor position. Undoable.
+    pub fn rename_current_sheet(&mut self, name: &str) {
+        let index = self.workbook.get_current_sheet_index();
+        let old_name = self.workbook.get_current_sheet_name();
+        if name.trim() == old_name {
+            self.add_notification(format!("Sheet is already named {old_name}"));
+            return;
+        }
+
+        match self.rename_sheet_at(index, &old_name, name) {
+            Ok(new_name) => {
+                self.undo_history
+                    .push(ActionCommand::RenameSheet(SheetRenameAction {
+                        sheet_index: index,
+                        old_name: old_name.clone(),
+                        new_name: new_name.clone(),
+                    }));
+                self.add_notification(format!("Renamed sheet {old_name} to {new_name}"));
+            }
+            Err(e) => self.add_error(format!("Cannot rename sheet: {e}")),
+        }
+    }
+
+    /// Renames the sheet at `index` and moves the per-sheet view state kept under its name
+    pub(crate) fn rename_sheet_at(
+        &mut self,
+        index: usize,
+        old_name: &str,
+        name: &str,
+    ) -> Result<String> {
+        let new_name = self.workbook.rename_sheet(index, name)?;
+
+        if let Some(widths) = self.sheet_column_widths.remove(old_name) {
+            self.sheet_column_widths.insert(new_name.clone(), widths);
+        }
+        if let Some(position) = self.sheet_cell_positions.remove(old_name) {
+            self.sheet_cell_positions.insert(new_name.clone(), position);
+        }
+        if let Some(row) = self.sheet_header_rows.remove(old_name) {
+            self.sheet_header_rows.insert(new_name.clone(), row);
+        }
+        self.rename_marks(old_name, &new_name);
+        self.undo_history.rename_sheet(old_name, &new_name);
+        if let Some(results) = self.results.as_mut() {
+            for item in &mut results.items {
+                if item.sheet_name == old_name {
+                    item.sheet_name.clone_from(&new_name);
+                }
+            }
+        }
+
+        Ok(new_name)
+    }
+
     pub fn delete_current_sheet(&mut self) {
         let current_sheet_name = self.workbook.get_current_sheet_name();
         let sheet_index = self.workbook.get_current_sheet_index();
@@ -214,6 +544,7 @@ impl AppState<'_> {
                     sheet_data,
                     column_widths,
                     operation: SheetOperation::Delete,
+                    packed_cells: None,
                 };
 
                 self.undo_history.push(ActionCommand::Sheet(sheet_action));
@@ -261,19 +592,22 @@ impl AppState<'_> {
                     // If the sheet is not loaded, switch to LazyLoading mode
                     self.input_mode = crate::app::InputMode::LazyLoading;
                     self.add_notification(format!(
-                        "Deleted sheet: {current_sheet_name}. Switched to sheet: {new_sheet_name} (press Enter to load)"
+                        "Deleted sheet: {current_sheet_name}. Switched to sheet: {new_sheet_name} (loading)"
                     ));
                 } else {
                     self.add_notification(format!("Deleted sheet: {current_sheet_name}"));
                 }
             }
             Err(e) => {
-                self.add_notification(format!("Failed to delete sheet: {e}"));
+                self.add_error(format!("Failed to delete sheet: {e}"));
             }
         }
     }
 
     pub fn delete_current_row(&mut self) -> Result<()> {
+        if self.refuse_on_protected_sheet("delete rows") {
+            return Ok(());
+        }
         let row = self.selected_cell.0;
         let sheet = self.workbook.get_current_sheet();
 
@@ -287,11 +621,7 @@ impl AppState<'_> {
         let sheet_name = self.workbook.get_current_sheet_name();
 
         // Create a copy of the row data before deletion
-        let row_data = if row < sheet.data.len() {
-            sheet.data[row].clone()
-        } else {
-            Vec::new()
-        };
+        let row_data = sheet.data.row_cells(row);
 
         // Create and add undo action
         let row_action = RowAction {
@@ -317,6 +647,9 @@ impl AppState<'_> {
     }
 
     pub fn delete_row(&mut self, row: usize) -> Result<()> {
+        if self.refuse_on_protected_sheet("delete rows") {
+            return Ok(());
+        }
         let sheet = self.workbook.get_current_sheet();
 
         // If row is outside the valid range, return success
@@ -329,11 +662,7 @@ impl AppState<'_> {
         let sheet_name = self.workbook.get_current_sheet_name();
 
         // Create a copy of the row data before deletion
-        let row_data = if row < sheet.data.len() {
-            sheet.data[row].clone()
-        } else {
-            Vec::new()
-        };
+        let row_data = sheet.data.row_cells(row);
 
         // Create and add undo action
         let row_action = RowAction {
@@ -362,6 +691,9 @@ impl AppState<'_> {
         if start_row == end_row {
             return self.delete_row(start_row);
         }
+        if self.refuse_on_protected_sheet("delete rows") {
+            return Ok(());
+        }
 
         let sheet = self.workbook.get_current_sheet();
 
@@ -382,11 +714,7 @@ impl AppState<'_> {
         let mut rows_data = Vec::with_capacity(rows_to_save);
 
         for row in start_row..=effective_end_row {
-            if row < sheet.data.len() {
-                rows_data.push(sheet.data[row].clone());
-            } else {
-                rows_data.push(Vec::new());
-            }
+            rows_data.push(sheet.data.row_cells(row));
         }
 
         // Create and add batch undo action
@@ -395,6 +723,7 @@ impl AppState<'_> {
             sheet_name,
             start_row,
             end_row: effective_end_row,
+            rows: (start_row..=effective_end_row).collect(),
             rows_data,
         };
 
@@ -415,6 +744,9 @@ impl AppState<'_> {
     }
 
     pub fn delete_current_column(&mut self) -> Result<()> {
+        if self.refuse_on_protected_sheet("delete columns") {
+            return Ok(());
+        }
         let col = self.selected_cell.1;
         let sheet = self.workbook.get_current_sheet();
 
@@ -428,14 +760,7 @@ impl AppState<'_> {
         let sheet_name = self.workbook.get_current_sheet_name();
 
         // Extract the column data from each row
-        let mut column_data = Vec::with_capacity(sheet.data.len());
-        for row in &sheet.data {
-            if col < row.len() {
-                column_data.push(row[col].clone());
-            } else {
-                column_data.push(crate::excel::Cell::empty());
-            }
-        }
+        let column_data = sheet.data.column_cells(col);
 
         // Save the column width
         let column_width = if col < self.column_widths.len() {
@@ -476,6 +801,9 @@ impl AppState<'_> {
     }
 
     pub fn delete_column(&mut self, col: usize) -> Result<()> {
+        if self.refuse_on_protected_sheet("delete columns") {
+            return Ok(());
+        }
         let sheet = self.workbook.get_current_sheet();
 
         // If column is outside the valid range, return success
@@ -488,14 +816,7 @@ impl AppState<'_> {
         let sheet_name = self.workbook.get_current_sheet_name();
 
         // Extract the column data from each row
-        let mut column_data = Vec::with_capacity(sheet.data.len());
-        for row in &sheet.data {
-            if col < row.len() {
-                column_data.push(row[col].clone());
-            } else {
-                column_data.push(crate::excel::Cell::empty());
-            }
-        }
+        let column_data = sheet.data.column_cells(col);
 
         // Save the column width
         let column_width = if col < self.column_widths.len() {
@@ -539,6 +860,9 @@ impl AppState<'_> {
         if start_col == end_col {
             return self.delete_column(start_col);
         }
+        if self.refuse_on_protected_sheet("delete columns") {
+            return Ok(());
+        }
 
         let sheet = self.workbook.get_current_sheet();
 
@@ -561,15 +885,7 @@ impl AppState<'_> {
 
         for col in start_col..=effective_end_col {
             // Extract the column data from each row
-            let mut column_data = Vec::with_capacity(sheet.data.len());
-            for row in &sheet.data {
-                if col < row.len() {
-                    column_data.push(row[col].clone());
-                } else {
-                    column_data.push(crate::excel::Cell::empty());
-                }
-            }
-            columns_data.push(column_data);
+            columns_data.push(sheet.data.column_cells(col));
 
             // Save the column width
             let column_width = if col < self.column_widths.len() {
@@ -619,20 +935,205 @@ impl AppState<'_> {
         Ok(())
     }
 
-    pub fn auto_adjust_column_width(&mut self, col: Option<usize>) {
+    /// Inserts a copy of the current row below it and moves the cursor onto the copy
+    pub fn duplicate_current_row(&mut self) {
+        if self.refuse_on_protected_sheet("insert rows") {
+            return;
+        }
+        let row = self.selected_cell.0;
+        let sheet = self.workbook.get_current_sheet();
+        if row < 1 || row > sheet.max_rows {
+            self.add_notification(format!("Nothing to duplicate on row {row}"));
+            return;
+        }
+        if sheet.max_rows >= EXCEL_MAX_ROWS {
+            self.add_error("Cannot duplicate row: sheet is at Excel's row limit".into());
+            return;
+        }
+
+        let row_action = RowAction {
+            sheet_index: self.workbook.get_current_sheet_index(),
+            sheet_name: self.workbook.get_current_sheet_name(),
+            row: row + 1,
+            row_data: sheet.data.row_cells(row),
+        };
+        self.workbook
+            .insert_row(row_action.row, row_action.row_data.clone());
+        self.undo_history.push(ActionCommand::InsertRow(row_action));
+
+        self.selected_cell.0 = row + 1;
+        self.update_row_number_width();
+        self.handle_scrolling();
+        self.search_results.clear();
+        self.current_search_idx = None;
+        self.add_notification(format!("Duplicated row {row}"));
+    }
+
+    /// Inserts a copy of the current column, with its width, to the right of it and moves the
+    /// cursor onto the copy
+    pub fn duplicate_current_column(&mut self) {
+        if self.refuse_on_protected_sheet("insert columns") {
+            return;
+        }
+        let col = self.selected_cell.1;
+        let sheet = self.workbook.get_current_sheet();
+        let col_name = index_to_col_name(col);
+        if col < 1 || col > sheet.max_cols {
+            self.add_notification(format!("Nothing to duplicate in column {col_name}"));
+            return;
+        }
+        if sheet.max_cols >= EXCEL_MAX_COLS {
+            self.add_error("Cannot duplicate column: sheet is at Excel's column limit".into());
+            return;
+        }
+
+        let column_data = sheet.data.column_cells(col);
+        let column_action = ColumnAction {
+            sheet_index: self.workbook.get_current_sheet_index(),
+            sheet_name: self.workbook.get_current_sheet_name(),
+            col: col + 1,
+            column_data,
+            column_width: self.get_column_width(col),
+        };
+        self.workbook
+            .insert_column(column_action.col, &column_action.column_data);
+        if column_action.col < self.column_widths.len() {
+            self.column_widths
+                .insert(column_action.col, column_action.column_width);
+        } else {
+            self.set_column_width(column_action.col, column_action.column_width);
+        }
+        self.undo_history
+            .push(ActionCommand::InsertColumn(column_action));
+
+        self.selected_cell.1 = col + 1;
+        self.ensure_column_widths();
+        self.handle_scrolling();
+        self.search_results.clear();
+        self.current_search_idx = None;
+        self.add_notification(format!("Duplicated column {col_name}"));
+    }
+
+    /// Inserts rows yanked with `yy` below the cursor's row, as one undoable step
+    pub(crate) fn insert_yanked_rows(&mut self, rows: Vec<Vec<Cell>>) {
+        if self.refuse_on_protected_sheet("insert rows") {
+            return;
+        }
+        let row = self.selected_cell.0;
+        let sheet = self.workbook.get_current_sheet();
+        if sheet.max_rows + rows.len() > EXCEL_MAX_ROWS {
+            self.add_error("Cannot put rows: sheet would exceed Excel's row limit".into());
+            return;
+        }
+
+        let count = rows.len();
+        self.undo_history.begin_group();
+        for (offset, cells) in rows.into_iter().enumerate() {
+            let row_data = (1..)
+                .zip(cells)
+                .filter(|(_, cell)| !cell.is_blank())
+                .collect();
+            let row_action = RowAction {
+                sheet_index: self.workbook.get_current_sheet_index(),
+                sheet_name: self.workbook.get_current_sheet_name(),
+                row: row + 1 + offset,
+                row_data,
+            };
+            self.workbook
+                .insert_row(row_action.row, row_action.row_data.clone());
+            self.undo_history.push(ActionCommand::InsertRow(row_action));
+        }
+        self.undo_history.end_group();
+
+        self.selected_cell.0 = row + 1;
+        self.update_row_number_width();
+        self.handle_scrolling();
+        self.search_results.clear();
+        self.current_search_idx = None;
+        let noun = if count == 1 { "row" } else { "rows" };
+        self.add_notification(format!("Put {count} {noun} below row {row}"));
+    }
+
+    /// Inserts columns yanked with `yc` right of the cursor's column, as one undoable step.
+    /// `block` holds one row per sheet row
+    pub(crate) fn insert_yanked_columns(&mut self, block: Vec<Vec<Cell>>) {
+        if self.refuse_on_protected_sheet("insert columns") {
+            return;
+        }
+        let col = self.selected_cell.1;
+        let count = block.iter().map(Vec::len).max().unwrap_or(0);
+        if self.workbook.get_current_sheet().max_cols + count > EXCEL_MAX_COLS {
+            self.add_error("Cannot put columns: sheet would exceed Excel's column limit".into());
+            return;
+        }
+
+        let column_width = self.get_column_width(col);
+        self.undo_history.begin_group();
+        for offset in 0..count {
+            let column_data = (1..)
+                .zip(&block)
+                .filter_map(|(row, cells)| Some((row, cells.get(offset)?.clone())))
+                .filter(|(_, cell)| !cell.is_blank())
+                .collect();
+            let column_action = ColumnAction {
+                sheet_index: self.workbook.get_current_sheet_index(),
+                sheet_name: self.workbook.get_current_sheet_name(),
+                col: col + 1 + offset,
+                column_data,
+                column_width,
+            };
+            self.workbook
+                .insert_column(column_action.col, &column_action.column_data);
+            if column_action.col < self.column_widths.len() {
+                self.column_widths
+                    .insert(column_action.col, column_action.column_width);
+            } else {
+                self.set_column_width(column_action.col, column_action.column_width);
+            }
+            self.undo_history
+                .push(ActionCommand::InsertColumn(column_action));
+        }
+        self.undo_history.end_group();
+
+        self.selected_cell.1 = col + 1;
+        self.ensure_column_widths();
+        self.handle_scrolling();
+        self.search_results.clear();
+        self.current_search_idx = None;
+        let noun = if count == 1 { "column" } else { "columns" };
+        self.add_notification(format!(
+            "Put {count} {noun} right of column {}",
+            index_to_col_name(col)
+        ));
+    }
+
+    /// Fits the width of `col`, or of every column, to its content. On long sheets only the
+    /// top rows, the rows on screen and rows spread evenly through the rest are measured,
+    /// unless `exact`
+    pub fn auto_adjust_column_width(&mut self, col: Option<usize>, exact: bool) {
         // Get sheet information before any mutable operations
         let is_loaded = self.workbook.get_current_sheet().is_loaded;
         let max_cols = self.workbook.get_current_sheet().max_cols;
         let default_min_width = 5;
 
         if !is_loaded && max_cols == 0 {
-            self.add_notification(
+            self.add_warning(
                 "Cannot adjust column widths in lazy loading mode until sheet is loaded"
                     .to_string(),
             );
             return;
         }
 
+        let rows = self.fit_rows(exact);
+        let sampled = if rows.len() < self.workbook.get_current_sheet().max_rows {
+            format!(
+                " from a sample of {} rows; add exact to read every row",
+                rows.len()
+            )
+        } else {
+            String::new()
+        };
+
         match col {
             // Adjust specific column
             Some(column) => {
@@ -641,13 +1142,13 @@ impl AppState<'_> {
 
                 if column < self.column_widths.len() {
                     // Calculate and set new column width
-                    let width = self.calculate_column_width(column);
-                    self.column_widths[column] = width.max(default_min_width);
+                    let width = self.calculate_column_width(column, &rows);
+                    self.commit_column_widths(&[(column, width.max(default_min_width))]);
 
                     self.ensure_column_visible(column);
 
                     self.add_notification(format!(
-                        "Column {} width adjusted",
+                        "Column {} width adjusted{sampled}",
                         index_to_col_name(column)
                     ));
                 }
@@ -659,49 +1160,57 @@ impl AppState<'_> {
 
                 // Only process columns if there are any
                 if max_cols > 0 {
-                    for col_idx in 1..=max_cols {
-                        let width = self.calculate_column_width(col_idx);
-                        self.column_widths[col_idx] = width.max(default_min_width);
-                    }
+                    let widths: Vec<(usize, usize)> = (1..=max_cols)
+                        .map(|col_idx| {
+                            let width = self.calculate_column_width(col_idx, &rows);
+                            (col_idx, width.max(default_min_width))
+                        })
+                        .collect();
+                    self.commit_column_widths(&widths);
 
                     let column = self.selected_cell.1;
                     self.ensure_column_visible(column);
 
-                    self.add_notification("All column widths adjusted".to_string());
+                    self.add_notification(format!("All column widths adjusted{sampled}"));
                 }
             }
         }
     }
 
-    fn calculate_column_width(&self, col: usize) -> usize {
+    /// The rows `:cw fit` measures: all of them when `exact` or the sheet is short, else the
+    /// top rows, the rows on screen and every so many rows through the rest
+    fn fit_rows(&self, exact: bool) -> Vec<usize> {
+        let max_rows = self.workbook.get_current_sheet().max_rows;
+        if exact || max_rows <= FIT_SAMPLE_ROWS {
+            return (1..=max_rows).collect();
+        }
+
+        let top = 1..=FIT_SAMPLE_ROWS / 4;
+        let on_screen = self.start_row..=(self.start_row + self.visible_rows).min(max_rows);
+        let step = max_rows.div_ceil(FIT_SAMPLE_ROWS / 2);
+        let spread = (1..=max_rows).step_by(step);
+        let rows: BTreeSet<usize> = top.chain(on_screen).chain(spread).collect();
+        rows.into_iter().collect()
+    }
+
+    fn calculate_column_width(&self, col: usize, rows: &[usize]) -> usize {
         let sheet = self.workbook.get_current_sheet();
 
         // Start with minimum width and header width
         let col_name = index_to_col_name(col);
         let mut max_width = 3.max(col_name.len());
 
-        // Calculate max width from all cells in the column
-        for row in 1..=sheet.max_rows {
-            if row >= sheet.data.len() || col >= sheet.data[row].len() {
+        for &row in rows {
+            if sheet.data.get(row, col).is_none() {
                 continue;
             }
 
-            let content = &sheet.data[row][col].value;
+            let content = self.get_cell_content(row, col);
             if content.is_empty() {
                 continue;
             }
 
-            let mut display_width = 0;
-
-            for c in content.chars() {
-                if c.is_ascii() {
-                    display_width += 1;
-                } else {
-                    display_width += 2;
-                }
-            }
-
-            max_width = max_width.max(display_width);
+            max_width = max_width.max(text_width(&content));
         }
         max_width
     }
@@ -714,6 +1223,43 @@ impl AppState<'_> {
         }
     }
 
+    pub(crate) fn set_column_width(&mut self, col: usize, width: usize) {
+        if col >= self.column_widths.len() {
+            self.column_widths.resize(col + 1, 15);
+        }
+        self.column_widths[col] = width;
+    }
+
+    /// Applies `(column, width)` pairs and records the ones that changed as one undo step
+    pub(crate) fn commit_column_widths(&mut self, widths: &[(usize, usize)]) {
+        let changes: Vec<ColumnWidthChange> = widths
+            .iter()
+            .filter_map(|&(col, new_width)| {
+                let old_width = self.get_column_width(col);
+                (old_width != new_width).then_some(ColumnWidthChange {
+                    col,
+                    old_width,
+                    new_width,
+                })
+            })
+            .collect();
+        if changes.is_empty() {
+            return;
+        }
+
+        for change in &changes {
+            self.set_column_width(change.col, change.new_width);
+        }
+
+        let width_action = ColumnWidthAction {
+            sheet_index: self.workbook.get_current_sheet_index(),
+            sheet_name: self.workbook.get_current_sheet_name(),
+            changes,
+        };
+        self.undo_history
+            .push(ActionCommand::ColumnWidth(width_action));
+    }
+
     pub fn ensure_column_widths(&mut self) {
         let sheet = self.workbook.get_current_sheet();
         self.adjust_column_widths(sheet.max_cols);
@@ -735,12 +1281,56 @@ impl AppState<'_> {
     }
 }
 
+/// Splits `"my file.xlsx" Sheet 2` or `other.xlsx Sheet 2` into the file and the rest
+fn split_file_argument(args: &str) -> (&str, &str) {
+    let args = args.trim();
+    for quote in ['"', '\''] {
+        if let Some(quoted) = args.strip_prefix(quote) {
+            if let Some((file, rest)) = quoted.split_once(quote) {
+                return (file, rest.trim());
+            }
+        }
+    }
+    args.split_once(char::is_whitespace)
+        .map_or((args, ""), |(file, rest)| (file, rest.trim()))
+}
+
 #[cfg(test)]
 mod tests {
     use crate::app::AppState;
-    use crate::excel::{Sheet, Workbook};
+    use crate::excel::{Cell, Sheet, SheetVisibility, Workbook};
     use std::path::PathBuf;
 
+    #[test]
+    fn fitting_long_sheets_measures_a_sample_unless_asked_for_exact() {
+        let mut sheet = Sheet::blank("Log".to_string());
+        for row in 1..=10_000 {
+            sheet.data.set(row, 1, Cell::new(format!("{row}"), false));
+        }
+        sheet.data.set(
+            7_777,
+            1,
+            Cell::new("a rather long stray note".to_string(), false),
+        );
+        sheet.max_rows = 10_000;
+        sheet.max_cols = 1;
+        let workbook = Workbook::from_sheets_for_test(vec![sheet]);
+        let mut app = AppState::new(workbook, PathBuf::from("log.xlsx")).unwrap();
+        let run = |app: &mut AppState, command: &str| {
+            app.input_buffer = command.to_string();
+            app.execute_command();
+            app.notification_messages.last().unwrap().text.clone()
+        };
+
+        assert_eq!(
+            run(&mut app, "cw fit"),
+            "Column A width adjusted from a sample of 1450 rows; add exact to read every row"
+        );
+        assert_eq!(app.get_column_width(1), 5);
+        assert_eq!(run(&mut app, "cw fit exact"), "Column A width adjusted");
+        assert_eq!(app.get_column_width(1), 24);
+    }
+
     #[test]
     fn create_sheet_can_be_undone_and_redone() {
         let workbook = Workbook::from_sheets_for_test(vec![Sheet::blank("Sheet1".to_string())]);
@@ -761,4 +1351,416 @@ mod tests {
         assert_eq!(app.workbook.get_current_sheet_name(), "Report");
         assert!(app.workbook.is_modified());
     }
+
+    #[test]
+    fn undo_history_drops_its_oldest_steps_and_packs_deleted_sheets() {
+        let mut big = Sheet::blank("Big".to_string());
+        for row in 1..=20_000 {
+            let mut cell = Cell::new(format!("order {row} shipped to warehouse"), false);
+            cell.comment = (row % 100 == 0).then(|| "checked".to_string());
+            big.data.set(row, 1, cell);
+            big.data
+                .set(row, 2, Cell::new(format!("{}.5", row * 3), false));
+        }
+        big.max_rows = 20_000;
+        big.max_cols = 2;
+        let workbook =
+            Workbook::from_sheets_for_test(vec![Sheet::blank("Sheet1".to_string()), big.clone()]);
+        let mut app = AppState::new(workbook, PathBuf::from("test.xlsx")).unwrap();
+
+        app.handle_set_command("undolevels=2");
+        for name in ["A", "B", "C"] {
+            app.create_sheet(name);
+        }
+        app.undo().unwrap();
+        app.undo().unwrap();
+        app.undo().unwrap();
+        assert_eq!(
+            app.notification_messages.last().unwrap().text,
+            "No operations to undo"
+        );
+        assert_eq!(app.workbook.get_sheet_names(), vec!["Sheet1", "A", "Big"]);
+        // The dropped step still differs from the file
+        assert!(app.workbook.is_modified());
+
+        app.switch_sheet_by_index(2).unwrap();
+        app.delete_current_sheet();
+        assert!(app.undo_history.undo_bytes() < big.data.estimated_bytes() / 10);
+        app.undo().unwrap();
+        assert_eq!(app.workbook.get_sheet_names(), vec!["Sheet1", "A", "Big"]);
+        assert!(app.workbook.get_sheet_by_index(2).unwrap().data == big.data);
+
+        app.handle_set_command("undomem=1K");
+        app.redo().unwrap();
+        app.create_sheet("D");
+        app.undo().unwrap();
+        app.undo().unwrap();
+        assert_eq!(app.workbook.get_sheet_names(), vec!["Sheet1", "A"]);
+        assert_eq!(
+            app.notification_messages.last().unwrap().text,
+            "No operations to undo"
+        );
+    }
+
+    #[test]
+    fn new_sheets_are_appended_with_default_names() {
+        let workbook = Workbook::from_sheets_for_test(vec![
+            Sheet::blank("Data".to_string()),
+            Sheet::blank("Sheet3".to_string()),
+        ]);
+        let mut app = AppState::new(workbook, PathBuf::from("test.xlsx")).unwrap();
+
+        app.input_buffer = "newsheet".to_string();
+        app.execute_command();
+        assert_eq!(
+            app.workbook.get_sheet_names(),
+            vec!["Data", "Sheet3", "Sheet4"]
+        );
+        assert_eq!(app.workbook.get_current_sheet_name(), "Sheet4");
+        assert_eq!(app.sheet_column_widths["Sheet4"].len(), 2);
+
+        app.switch_sheet_by_index(0).unwrap();
+        app.input_buffer = "newsheet Q1 Sales".to_string();
+        app.execute_command();
+        assert_eq!(
+            app.workbook.get_sheet_names(),
+            vec!["Data", "Sheet3", "Sheet4", "Q1 Sales"]
+        );
+        assert_eq!(app.workbook.get_current_sheet_index(), 3);
+
+        app.undo().unwrap();
+        assert_eq!(
+            app.workbook.get_sheet_names(),
+            vec!["Data", "Sheet3", "Sheet4"]
+        );
+
+        app.input_buffer = "newsheet Data".to_string();
+        app.execute_command();
+        assert_eq!(app.workbook.get_sheet_names().len(), 3);
+    }
+
+    #[test]
+    fn renaming_a_sheet_keeps_its_view_state_and_undoes() {
+        let workbook = Workbook::from_sheets_for_test(vec![
+            Sheet::blank("Data".to_string()),
+            Sheet::blank("Notes".to_string()),
+        ]);
+        let mut app = AppState::new(workbook, PathBuf::from("test.xlsx")).unwrap();
+        app.column_widths[1] = 30;
+        app.selected_cell = (2, 1);
+
+        for name in ["notes", "bad/name", "", &"x".repeat(32)] {
+            app.rename_current_sheet(name);
+            assert_eq!(app.workbook.get_current_sheet_name(), "Data");
+        }
+
+        app.rename_current_sheet("Raw data");
+        assert_eq!(app.workbook.get_sheet_names(), vec!["Raw data", "Notes"]);
+        assert_eq!(
+            app.notification_messages.last().unwrap().text,
+            "Renamed sheet Data to Raw data"
+        );
+
+        app.switch_sheet_by_index(1).unwrap();
+        app.switch_sheet_by_index(0).unwrap();
+        assert_eq!(app.column_widths[1], 30);
+        assert_eq!(app.selected_cell, (2, 1));
+
+        app.undo().unwrap();
+        assert_eq!(app.workbook.get_sheet_names(), vec!["Data", "Notes"]);
+        assert_eq!(app.column_widths[1], 30);
+        assert!(!app.workbook.is_modified());
+
+        app.redo().unwrap();
+        assert_eq!(app.workbook.get_current_sheet_name(), "Raw data");
+        assert_eq!(
+            app.notification_messages.last().unwrap().text,
+            "Redid sheet rename: Data to Raw data"
+        );
+    }
+
+    #[test]
+    fn duplicating_a_sheet_copies_data_and_widths() {
+        let mut sheet = Sheet::blank("Data".to_string());
+        sheet.data.set(1, 1, Cell::new("id".to_string(), false));
+        sheet.max_cols = 2;
+        let workbook = Workbook::from_sheets_for_test(vec![sheet, Sheet::blank("Notes".into())]);
+        let mut app = AppState::new(workbook, PathBuf::from("test.xlsx")).unwrap();
+        app.column_widths[2] = 40;
+
+        app.input_buffer = "dupsheet".to_string();
+        app.execute_command();
+        assert_eq!(app.workbook.get_current_sheet_name(), "Data (2)");
+        assert_eq!(app.get_cell_content(1, 1), "id");
+        assert_eq!(app.column_widths[2], 40);
+        app.workbook.get_current_sheet_mut().data.set(
+            1,
+            1,
+            Cell::new("changed".to_string(), false),
+        );
+
+        app.switch_sheet_by_index(0).unwrap();
+        assert_eq!(app.get_cell_content(1, 1), "id");
+        app.input_buffer = "dupsheet".to_string();
+        app.execute_command();
+        assert_eq!(
+            app.workbook.get_sheet_names(),
+            vec!["Data", "Data (3)", "Data (2)", "Notes"]
+        );
+        assert_eq!(
+            app.notification_messages.last().unwrap().text,
+            "Duplicated sheet Data as Data (3)"
+        );
+
+        app.input_buffer = "dupsheet Scratch".to_string();
+        app.execute_command();
+        assert_eq!(app.workbook.get_current_sheet_name(), "Scratch");
+        app.undo().unwrap();
+        assert_eq!(app.workbook.get_sheet_names().len(), 4);
+    }
+
+    #[test]
+    fn copying_a_sheet_into_another_file_saves_a_copy_of_it() {
+        let dir = std::env::temp_dir().join("excel_cli_copysheet_test");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let target = dir.join("other.xlsx");
+        let mut xlsx = rust_xlsxwriter::Workbook::new();
+        xlsx.add_worksheet().set_name("Data").unwrap();
+        xlsx.save(&target).unwrap();
+
+        let mut sheet = Sheet::blank("Data".to_string());
+        sheet
+            .data
+            .set(1, 1, Cell::new("harvested".to_string(), false));
+        let workbook = Workbook::from_sheets_for_test(vec![sheet]);
+        let mut app = AppState::new(workbook, PathBuf::from("test.xlsx")).unwrap();
+
+        app.input_buffer = format!("copysheet {}", target.display());
+        app.execute_command();
+        let message = app.notification_messages.last().unwrap().text.clone();
+        assert!(message.starts_with("Copied sheet Data as Data (2) into other_"));
+        assert!(!app.workbook.is_modified());
+
+        let saved = std::fs::read_dir(&dir)
+            .unwrap()
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .find(|path| path != &target)
+            .unwrap();
+        let other = crate::excel::open_workbook(&saved, false).unwrap();
+        assert_eq!(other.get_sheet_names(), vec!["Data", "Data (2)"]);
+        let copied = other.get_sheet_by_name("Data (2)").unwrap();
+        assert_eq!(copied.data.cell(1, 1).value, "harvested");
+
+        app.input_buffer = "copysheet notes.csv".to_string();
+        app.execute_command();
+        assert!(app
+            .notification_messages
+            .last()
+            .unwrap()
+            .text
+            .contains("only .xlsx and .xlsm"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn importing_a_sheet_inserts_a_copy_after_the_current_one() {
+        let dir = std::env::temp_dir().join("excel_cli_importsheet_test");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let source = dir.join("rate card.xlsx");
+        let mut xlsx = rust_xlsxwriter::Workbook::new();
+        xlsx.add_worksheet().set_name("Lookup").unwrap();
+        xlsx.add_worksheet()
+            .set_name("Rates")
+            .unwrap()
+            .write_string(0, 0, "EUR")
+            .unwrap();
+        xlsx.save(&source).unwrap();
+
+        let workbook = Workbook::from_sheets_for_test(vec![
+            Sheet::blank("Data".to_string()),
+            Sheet::blank("Rates".to_string()),
+        ]);
+        let mut app = AppState::new(workbook, PathBuf::from("test.xlsx")).unwrap();
+
+        app.input_buffer = format!("importsheet \"{}\"", source.display());
+        app.execute_command();
+        assert!(app
+            .notification_messages
+            .last()
+            .unwrap()
+            .text
+            .ends_with("Lookup, Rates. Use :importsheet <file> <sheet>"));
+
+        app.input_buffer = format!("importsheet \"{}\" rates", source.display());
+        app.execute_command();
+        assert_eq!(
+            app.workbook.get_sheet_names(),
+            vec!["Data", "Rates (2)", "Rates"]
+        );
+        assert_eq!(app.workbook.get_current_sheet_name(), "Rates (2)");
+        assert_eq!(app.get_cell_content(1, 1), "EUR");
+
+        app.undo().unwrap();
+        assert_eq!(app.workbook.get_sheet_names(), vec!["Data", "Rates"]);
+
+        app.input_buffer = format!("importsheet \"{}\" Missing", source.display());
+        app.execute_command();
+        assert!(app
+            .notification_messages
+            .last()
+            .unwrap()
+            .text
+            .starts_with("Sheet 'Missing' not found in"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn hidden_sheets_are_skipped_until_shown() {
+        let mut hidden = Sheet::blank("Lookup".to_string());
+        hidden.visibility = SheetVisibility::Hidden;
+        let workbook = Workbook::from_sheets_for_test(vec![
+            Sheet::blank("Data".to_string()),
+            hidden,
+            Sheet::blank("Report".to_string()),
+        ]);
+        let mut app = AppState::new(workbook, PathBuf::from("test.xlsx")).unwrap();
+
+        assert!(!app.is_sheet_shown(1));
+        app.next_sheet().unwrap();
+        assert_eq!(app.workbook.get_current_sheet_name(), "Report");
+        app.prev_sheet().unwrap();
+        assert_eq!(app.workbook.get_current_sheet_name(), "Data");
+
+        app.input_buffer = "showhidden".to_string();
+        app.execute_command();
+        assert_eq!(app.workbook.get_current_sheet_name(), "Lookup");
+        assert_eq!(
+            app.notification_messages.last().unwrap().text,
+            "Showing hidden sheets: Lookup"
+        );
+        app.prev_sheet().unwrap();
+        app.next_sheet().unwrap();
+        assert_eq!(app.workbook.get_current_sheet_name(), "Lookup");
+
+        app.input_buffer = "showhidden".to_string();
+        app.execute_command();
+        assert!(!app.show_hidden_sheets);
+        assert!(app.is_sheet_shown(1));
+        app.prev_sheet().unwrap();
+        assert!(!app.is_sheet_shown(1));
+
+        // A count past the last shown sheet stops there, as 5] does
+        app.step_sheets(5, true).unwrap();
+        assert_eq!(app.workbook.get_current_sheet_name(), "Report");
+        app.step_sheets(2, false).unwrap();
+        assert_eq!(app.workbook.get_current_sheet_name(), "Data");
+    }
+
+    #[test]
+    fn column_width_commands_are_undone_as_single_steps() {
+        let mut sheet = Sheet::blank("Sheet1".to_string());
+        for col in 1..=3 {
+            sheet.data.set(1, col, Cell::new(format!("C{col}"), false));
+        }
+        sheet.max_rows = 1;
+        sheet.max_cols = 3;
+        let workbook = Workbook::from_sheets_for_test(vec![sheet]);
+        let mut app = AppState::new(workbook, PathBuf::from("test.xlsx")).unwrap();
+        app.selected_cell = (1, 2);
+
+        app.input_buffer = "cw 30".to_string();
+        app.execute_command();
+        assert_eq!(app.get_column_width(2), 30);
+
+        app.input_buffer = "cw min all".to_string();
+        app.execute_command();
+        assert_eq!(app.column_widths[1..=3], [5, 5, 5]);
+
+        app.undo().unwrap();
+        assert_eq!(app.column_widths[1..=3], [15, 30, 15]);
+
+        app.undo().unwrap();
+        assert_eq!(app.column_widths[1..=3], [15, 15, 15]);
+
+        app.redo().unwrap();
+        assert_eq!(app.get_column_width(2), 30);
+    }
+
+    #[test]
+    fn duplicating_rows_and_columns_inserts_undoable_copies() {
+        let mut sheet = Sheet::blank("Sheet1".to_string());
+        for row in 1..=2 {
+            for col in 1..=2 {
+                sheet
+                    .data
+                    .set(row, col, Cell::new(format!("R{row}C{col}"), false));
+            }
+        }
+        sheet.max_rows = 2;
+        sheet.max_cols = 2;
+        let workbook = Workbook::from_sheets_for_test(vec![sheet]);
+        let mut app = AppState::new(workbook, PathBuf::from("test.xlsx")).unwrap();
+        app.selected_cell = (1, 1);
+        app.set_column_width(1, 22);
+
+        app.input_buffer = "dupr".to_string();
+        app.execute_command();
+        assert_eq!(app.get_cell_content(2, 2), "R1C2");
+        assert_eq!(app.get_cell_content(3, 1), "R2C1");
+        assert_eq!(app.selected_cell, (2, 1));
+
+        app.input_buffer = "dupc".to_string();
+        app.execute_command();
+        assert_eq!(app.get_cell_content(3, 2), "R2C1");
+        assert_eq!(app.get_cell_content(3, 3), "R2C2");
+        assert_eq!(app.get_column_width(2), 22);
+        assert_eq!(app.selected_cell, (2, 2));
+
+        app.undo().unwrap();
+        assert_eq!(app.get_cell_content(3, 2), "R2C2");
+        assert_eq!(app.get_column_width(2), 15);
+        app.undo().unwrap();
+        assert_eq!(app.get_cell_content(2, 1), "R2C1");
+        assert_eq!(app.workbook.get_current_sheet().max_rows, 2);
+
+        app.redo().unwrap();
+        assert_eq!(app.get_cell_content(2, 2), "R1C2");
+        assert_eq!(app.get_cell_content(3, 1), "R2C1");
+    }
+
+    #[test]
+    fn following_sheet_links_jumps_to_sheet_and_cell() {
+        let mut index = Sheet::blank("Index".to_string());
+        index
+            .data
+            .set(1, 1, Cell::new("'Q1 Sales'!C3".to_string(), false));
+        index
+            .data
+            .set(2, 1, Cell::new("q1 sales".to_string(), false));
+        index.data.set(3, 1, Cell::new("Notes".to_string(), false));
+        index.max_rows = 3;
+        index.max_cols = 1;
+        let workbook =
+            Workbook::from_sheets_for_test(vec![index, Sheet::blank("Q1 Sales".to_string())]);
+        let mut app = AppState::new(workbook, PathBuf::from("test.xlsx")).unwrap();
+
+        app.selected_cell = (1, 1);
+        assert!(app.follow_sheet_link());
+        assert_eq!(app.workbook.get_current_sheet_name(), "Q1 Sales");
+        assert_eq!(app.selected_cell, (3, 3));
+
+        app.switch_to_sheet("Index");
+        app.selected_cell = (2, 1);
+        assert!(app.follow_sheet_link());
+        assert_eq!(app.workbook.get_current_sheet_name(), "Q1 Sales");
+        assert_eq!(app.selected_cell, (3, 3));
+
+        app.switch_to_sheet("Index");
+        app.selected_cell = (3, 1);
+        assert!(!app.follow_sheet_link());
+        assert_eq!(app.workbook.get_current_sheet_name(), "Index");
+    }
 }
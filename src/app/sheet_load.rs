@@ -0,0 +1,666 @@
+use anyhow::Result;
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::thread;
+use std::time::Instant;
+
+use crate::app::memory::format_size;
+use crate::app::{AppState, InputMode};
+use crate::excel::{LoadProgress, StreamedRows, StreamedSheetEnd};
+
+/// Rows read past the bottom of the view or the cursor, whichever is lower, before reading a
+/// streaming sheet pauses
+const STREAM_AHEAD_ROWS: usize = 10_000;
+
+/// What the thread reading a sheet sends back
+enum SheetLoadEvent {
+    Progress(LoadProgress),
+    Rows(StreamedRows),
+    Done(Result<StreamedSheetEnd>),
+}
+
+/// A lazily loaded sheet being read on a background thread, or whose reading failed
+pub struct SheetLoad {
+    pub sheet_name: String,
+    pub started: Instant,
+    /// The latest report from the reading thread
+    pub progress: Option<LoadProgress>,
+    /// Why reading failed; `Enter` tries again
+    pub error: Option<String>,
+    receiver: Receiver<SheetLoadEvent>,
+    /// Tells the reading thread how many rows to read before it pauses; dropping it stops
+    /// the thread
+    wanted_rows: Sender<usize>,
+    /// The most rows asked for so far
+    rows_asked: usize,
+    /// Started ahead of a switch to the sheet, so it finishes or fails without a word unless
+    /// the sheet is current by then
+    preloaded: bool,
+    /// Reading paused as the workbook reached `memcap`; raising it lets reading go on
+    pub held_at_memory_cap: bool,
+}
+
+impl SheetLoad {
+    /// Lets the reading thread go on until `rows` rows are read
+    fn want_rows(&mut self, rows: usize) {
+        if rows > self.rows_asked && !self.held_at_memory_cap {
+            self.rows_asked = rows;
+            let _ = self.wanted_rows.send(rows);
+        }
+    }
+
+    /// Pauses the reading thread once `rows` rows are read, even if more were asked for
+    fn hold_at(&mut self, rows: usize) {
+        self.held_at_memory_cap = true;
+        self.rows_asked = rows;
+        let _ = self.wanted_rows.send(rows);
+    }
+}
+
+impl AppState<'_> {
+    /// Starts reading the current sheet on a background thread when lazy loading left it
+    /// unread, then takes in whatever sheets finished. Called every pass of the event loop,
+    /// so the placeholder shows at once and the data replaces it when ready
+    pub fn poll_sheet_loads(&mut self) {
+        // A sheet read in full some other way, as saving does, needs its stream no more
+        let workbook = &self.workbook;
+        self.sheet_loads.retain(|load| {
+            workbook
+                .get_sheet_by_name(&load.sheet_name)
+                .is_some_and(|sheet| !sheet.is_loaded)
+        });
+        self.start_loading_current_sheet();
+        self.preload_adjacent_sheets();
+        let view_end = (self.start_row + self.visible_rows).max(self.selected_cell.0);
+        if let Some(load) = self.current_sheet_load_mut() {
+            load.want_rows(view_end + STREAM_AHEAD_ROWS);
+        }
+
+        let mut streamed = Vec::new();
+        let mut finished = Vec::new();
+        for (position, load) in self.sheet_loads.iter_mut().enumerate() {
+            if load.error.is_some() {
+                continue;
+            }
+            loop {
+                match load.receiver.try_recv() {
+                    Ok(SheetLoadEvent::Progress(progress)) => load.progress = Some(progress),
+                    Ok(SheetLoadEvent::Rows(rows)) => {
+                        streamed.push((load.sheet_name.clone(), rows));
+                    }
+                    Ok(SheetLoadEvent::Done(result)) => {
+                        finished.push((position, result));
+                        break;
+                    }
+                    Err(TryRecvError::Empty) => break,
+                    Err(TryRecvError::Disconnected) => {
+                        finished.push((position, Err(anyhow::anyhow!("reading stopped"))));
+                        break;
+                    }
+                }
+            }
+        }
+
+        let any_streamed = !streamed.is_empty();
+        for (sheet_name, rows) in streamed {
+            let index = self.workbook.append_streamed_rows(&sheet_name, rows);
+            if index == Some(self.workbook.get_current_sheet_index()) {
+                self.update_row_number_width();
+                if matches!(self.input_mode, InputMode::LazyLoading) {
+                    self.input_mode = InputMode::Normal;
+                }
+            }
+        }
+        if any_streamed {
+            self.hold_streams_at_memory_cap();
+        }
+
+        for (position, result) in finished.into_iter().rev() {
+            let result = result.and_then(|end| self.refuse_past_memory_cap(end));
+            match result {
+                Ok(end) => {
+                    let load = self.sheet_loads.remove(position);
+                    self.finish_loaded_sheet(load, end);
+                }
+                Err(e) => {
+                    let is_current = self.sheet_loads[position].sheet_name
+                        == self.workbook.get_current_sheet_name();
+                    let load = &mut self.sheet_loads[position];
+                    let message = format!("Failed to load sheet {}: {e}", load.sheet_name);
+                    load.error = Some(e.to_string());
+                    if !load.preloaded || is_current {
+                        self.add_notification(message);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Pauses the sheets streaming in once the workbook reaches `memcap`, saying so for the
+    /// ones wanted now
+    fn hold_streams_at_memory_cap(&mut self) {
+        let Some((bytes, cap)) = self.memory_over_cap() else {
+            return;
+        };
+        let current = self.workbook.get_current_sheet_name();
+        let mut messages = Vec::new();
+        for load in &mut self.sheet_loads {
+            let Some(sheet) = self.workbook.get_sheet_by_name(&load.sheet_name) else {
+                continue;
+            };
+            if load.held_at_memory_cap || load.error.is_some() || sheet.is_loaded {
+                continue;
+            }
+            load.hold_at(sheet.max_rows);
+            if !load.preloaded || load.sheet_name == current {
+                messages.push(format!(
+                    "Sheet {} stopped loading at {} rows: the workbook takes about {}, memcap={}. :set memcap= to raise it",
+                    load.sheet_name,
+                    sheet.max_rows,
+                    format_size(bytes),
+                    format_size(cap)
+                ));
+            }
+        }
+        for message in messages {
+            self.add_warning(message);
+        }
+    }
+
+    /// Lets reading go on for the sheets held at `memcap`, after it was raised or turned off
+    pub(crate) fn release_streams_below_memory_cap(&mut self) {
+        if self.memory_over_cap().is_some() {
+            return;
+        }
+        for load in &mut self.sheet_loads {
+            load.held_at_memory_cap = false;
+        }
+    }
+
+    /// The end of a sheet's read, unless its last cells would take the workbook past `memcap`
+    fn refuse_past_memory_cap(&self, end: StreamedSheetEnd) -> Result<StreamedSheetEnd> {
+        let Some(cap) = self.memory_cap else {
+            return Ok(end);
+        };
+        let last_cells: usize = end
+            .rows
+            .cells
+            .iter()
+            .map(|(_, _, cell)| cell.estimated_bytes())
+            .sum();
+        let bytes = self.workbook.estimated_bytes() + last_cells;
+        if bytes > cap {
+            anyhow::bail!(
+                "it would take the workbook to about {}, past memcap={}",
+                format_size(bytes),
+                format_size(cap)
+            );
+        }
+        Ok(end)
+    }
+
+    /// The background read of the current sheet, if one is running or failed
+    #[must_use]
+    pub fn current_sheet_load(&self) -> Option<&SheetLoad> {
+        let sheet_name = self.workbook.get_current_sheet_name();
+        self.sheet_loads
+            .iter()
+            .find(|load| load.sheet_name == sheet_name)
+    }
+
+    fn current_sheet_load_mut(&mut self) -> Option<&mut SheetLoad> {
+        let sheet_name = self.workbook.get_current_sheet_name();
+        self.sheet_loads
+            .iter_mut()
+            .find(|load| load.sheet_name == sheet_name)
+    }
+
+    /// Refuses, with a notification, a change to the current sheet while its rows are still
+    /// streaming in, and reads the rest of them. Returns true when the change must not go
+    /// ahead.
+    pub(crate) fn refuse_while_streaming(&mut self) -> bool {
+        let index = self.workbook.get_current_sheet_index();
+        if !self.workbook.is_sheet_streaming(index) {
+            return false;
+        }
+
+        let sheet = self.workbook.get_current_sheet();
+        let held = self
+            .current_sheet_load()
+            .is_some_and(|load| load.held_at_memory_cap);
+        let message = if held {
+            format!(
+                "Sheet {} is read only up to row {}, where memcap stopped it; it can't be changed unless :set memcap= lets it load",
+                sheet.name, sheet.max_rows
+            )
+        } else {
+            format!(
+                "Sheet {} is still loading ({} rows so far); it can be changed once loaded",
+                sheet.name, sheet.max_rows
+            )
+        };
+        if let Some(load) = self.current_sheet_load_mut() {
+            load.want_rows(usize::MAX);
+        }
+        self.add_warning(message);
+        true
+    }
+
+    /// Forgets a failed read of the current sheet, so the next poll tries again
+    pub fn retry_sheet_load(&mut self) {
+        let sheet_name = self.workbook.get_current_sheet_name();
+        self.sheet_loads
+            .retain(|load| load.sheet_name != sheet_name || load.error.is_none());
+    }
+
+    fn start_loading_current_sheet(&mut self) {
+        self.start_sheet_load(self.workbook.get_current_sheet_index(), false);
+    }
+
+    /// Once the current sheet is read, reads the sheets `[` and `]` lead to, the next one
+    /// first, so switching to them finds them ready. One sheet is read at a time; a sheet
+    /// streaming in counts as read once its first rows are in, since it pauses soon after
+    fn preload_adjacent_sheets(&mut self) {
+        let current = self.workbook.get_current_sheet_index();
+        if !self.workbook.get_current_sheet().is_loaded || self.memory_over_cap().is_some() {
+            return;
+        }
+        let workbook = &self.workbook;
+        let busy = self.sheet_loads.iter().any(|load| {
+            load.error.is_none()
+                && workbook
+                    .get_sheet_by_name(&load.sheet_name)
+                    .is_some_and(|sheet| sheet.max_rows == 0)
+        });
+        if busy {
+            return;
+        }
+
+        let sheet_count = self.workbook.get_sheet_names().len();
+        let next = (current + 1..sheet_count).find(|&index| self.is_sheet_shown(index));
+        let previous = (0..current).rev().find(|&index| self.is_sheet_shown(index));
+        for index in next.into_iter().chain(previous) {
+            if self.start_sheet_load(index, true) {
+                return;
+            }
+        }
+    }
+
+    /// Starts reading the sheet at `index` on a background thread unless it is read or being
+    /// read already, or its reading failed. Returns whether a read started
+    fn start_sheet_load(&mut self, index: usize, preloaded: bool) -> bool {
+        let Some(request) = self.workbook.sheet_load_request(index) else {
+            return false;
+        };
+        if let Some(load) = self
+            .sheet_loads
+            .iter_mut()
+            .find(|load| load.sheet_name == request.sheet_name)
+        {
+            // The sheet is wanted now, so it gets a say once read
+            load.preloaded &= preloaded;
+            return false;
+        }
+
+        // Rows streamed in by a read that failed are read again
+        self.workbook.discard_streamed_rows(index);
+        let (sender, receiver) = mpsc::channel();
+        let (wanted_rows, wanted) = mpsc::channel();
+        let sheet_name = request.sheet_name.clone();
+        thread::spawn(move || {
+            let mut rows_wanted = STREAM_AHEAD_ROWS;
+            let result = request.read(
+                |progress| {
+                    let _ = sender.send(SheetLoadEvent::Progress(progress.clone()));
+                },
+                |rows| {
+                    let rows_read = rows.rows_read;
+                    if sender.send(SheetLoadEvent::Rows(rows)).is_err() {
+                        return false;
+                    }
+                    // Pausing until the view needs more rows keeps a huge sheet from taking
+                    // up more memory than the part looked at
+                    // The latest count wins, as memcap can hold reading at fewer rows
+                    while let Ok(rows) = wanted.try_recv() {
+                        rows_wanted = rows;
+                    }
+                    while rows_read >= rows_wanted {
+                        match wanted.recv() {
+                            Ok(rows) => rows_wanted = rows,
+                            Err(_) => return false,
+                        }
+                    }
+                    true
+                },
+            );
+            let _ = sender.send(SheetLoadEvent::Done(result));
+        });
+        self.sheet_loads.push(SheetLoad {
+            sheet_name,
+            started: Instant::now(),
+            progress: None,
+            error: None,
+            receiver,
+            wanted_rows,
+            rows_asked: STREAM_AHEAD_ROWS,
+            preloaded,
+            held_at_memory_cap: false,
+        });
+        true
+    }
+
+    fn finish_loaded_sheet(&mut self, load: SheetLoad, end: StreamedSheetEnd) {
+        let Some(index) = self.workbook.finish_streamed_sheet(&load.sheet_name, end) else {
+            return;
+        };
+        let rows = self
+            .workbook
+            .get_sheet_by_index(index)
+            .map_or(0, |sheet| sheet.max_rows);
+        if index == self.workbook.get_current_sheet_index() {
+            self.update_row_number_width();
+            if matches!(self.input_mode, InputMode::LazyLoading) {
+                self.input_mode = InputMode::Normal;
+            }
+        } else if load.preloaded {
+            return;
+        }
+        let noun = if rows == 1 { "row" } else { "rows" };
+        self.add_notification(format!(
+            "Loaded sheet {} ({rows} {noun}) in {:.1}s",
+            load.sheet_name,
+            load.started.elapsed().as_secs_f64()
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::app::{AppState, InputMode};
+    use crate::excel::open_workbook;
+    use std::time::{Duration, Instant};
+
+    fn poll_until_loaded(app: &mut AppState) {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let index = app.workbook.get_current_sheet_index();
+        while !app.workbook.is_sheet_loaded(index) {
+            assert!(Instant::now() < deadline, "sheet never loaded");
+            app.poll_sheet_loads();
+            std::thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    #[test]
+    fn lazy_sheets_load_in_the_background_and_replace_the_placeholder() {
+        use rust_xlsxwriter::Workbook as XlsxWorkbook;
+
+        let path = std::env::temp_dir().join("excel_cli_background_sheet_load.xlsx");
+        let mut xlsx = XlsxWorkbook::new();
+        let first = xlsx.add_worksheet();
+        first.set_name("First").unwrap();
+        first.write_string(0, 0, "alpha").unwrap();
+        let second = xlsx.add_worksheet();
+        second.set_name("Second").unwrap();
+        second.set_freeze_panes(1, 0).unwrap();
+        for row in 0..50 {
+            second.write_number(row, 0, f64::from(row)).unwrap();
+        }
+        xlsx.save(&path).unwrap();
+
+        let workbook = open_workbook(&path, true).unwrap();
+        let mut app = AppState::new(workbook, path.clone()).unwrap();
+        assert!(matches!(app.input_mode, InputMode::LazyLoading));
+        // Started without polling, which could take in a sheet this small at once
+        app.start_loading_current_sheet();
+        assert_eq!(app.current_sheet_load().unwrap().sheet_name, "First");
+
+        poll_until_loaded(&mut app);
+        assert_eq!(app.get_cell_content(1, 1), "alpha");
+        assert!(matches!(app.input_mode, InputMode::Normal));
+        assert!(app.current_sheet_load().is_none());
+        assert!(app
+            .notification_messages
+            .last()
+            .unwrap()
+            .text
+            .starts_with("Loaded sheet First (1 row) in "));
+
+        app.switch_to_sheet("Second");
+        assert!(matches!(app.input_mode, InputMode::LazyLoading));
+        poll_until_loaded(&mut app);
+        assert!(matches!(app.input_mode, InputMode::Normal));
+        assert_eq!(app.get_cell_content(50, 1), "49");
+        assert_eq!(app.workbook.get_current_sheet().freeze_panes.rows, 1);
+    }
+
+    #[test]
+    fn huge_sheets_show_their_first_rows_and_read_on_as_needed() {
+        use rust_xlsxwriter::Workbook as XlsxWorkbook;
+
+        let path = std::env::temp_dir().join("excel_cli_streamed_sheet_load.xlsx");
+        let mut xlsx = XlsxWorkbook::new();
+        let sheet = xlsx.add_worksheet();
+        sheet.set_name("Big").unwrap();
+        for row in 0..30_000 {
+            sheet.write_number(row, 0, f64::from(row)).unwrap();
+        }
+        xlsx.save(&path).unwrap();
+
+        let workbook = open_workbook(&path, true).unwrap();
+        let mut app = AppState::new(workbook, path.clone()).unwrap();
+        poll_until_loaded(&mut app);
+        assert!(matches!(app.input_mode, InputMode::Normal));
+        assert!(app.workbook.is_sheet_streaming(0));
+        assert_eq!(app.get_cell_content(2, 1), "1");
+
+        // Reading pauses a little past the view instead of reading the whole sheet
+        let pause = Instant::now() + Duration::from_millis(300);
+        while Instant::now() < pause {
+            app.poll_sheet_loads();
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        let rows = app.workbook.get_current_sheet().max_rows;
+        assert!(rows < 30_000, "{rows}");
+        assert!(app.workbook.is_sheet_streaming(0));
+
+        // A change waits for the whole sheet, which asking for it reads
+        app.input_buffer = "upper".to_string();
+        app.execute_command();
+        let message = &app.notification_messages.last().unwrap().text;
+        assert_eq!(
+            *message,
+            format!(
+                "Sheet Big is still loading ({rows} rows so far); it can be changed once loaded"
+            )
+        );
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while !app.workbook.get_current_sheet().is_loaded {
+            assert!(Instant::now() < deadline, "sheet never finished loading");
+            app.poll_sheet_loads();
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        assert_eq!(app.workbook.get_current_sheet().max_rows, 30_000);
+        assert_eq!(app.get_cell_content(30_000, 1), "29999");
+        assert!(app.current_sheet_load().is_none());
+    }
+
+    #[test]
+    fn streamed_cells_keep_their_columns_once_the_sheet_is_whole() {
+        use rust_xlsxwriter::{Format, Workbook as XlsxWorkbook};
+
+        let path = std::env::temp_dir().join("excel_cli_streamed_sheet_columns.xlsx");
+        let mut xlsx = XlsxWorkbook::new();
+        let shaded = Format::new().set_background_color(0x00FF_EE00);
+        // Styled blank cells put the <dimension> at column A, left of the first value
+        let sheet = xlsx.add_worksheet();
+        sheet.set_name("Shifted").unwrap();
+        for row in 0..12_000 {
+            sheet.write_blank(row, 0, &shaded).unwrap();
+            sheet.write_string(row, 1, format!("r{row}")).unwrap();
+        }
+        // A value left of every earlier one moves the cells streamed before it
+        let sheet = xlsx.add_worksheet();
+        sheet.set_name("Late").unwrap();
+        for row in 0..12_000 {
+            sheet.write_string(row, 2, format!("c{row}")).unwrap();
+        }
+        sheet.write_string(11_500, 1, "late").unwrap();
+        xlsx.save(&path).unwrap();
+        let eager = open_workbook(&path, false).unwrap();
+
+        let workbook = open_workbook(&path, true).unwrap();
+        let mut app = AppState::new(workbook, path.clone()).unwrap();
+        for (index, (name, first)) in [("Shifted", "r0"), ("Late", "c0")].into_iter().enumerate() {
+            app.switch_to_sheet(name);
+            poll_until_loaded(&mut app);
+            assert!(app.workbook.is_sheet_streaming(index));
+            assert_eq!(app.get_cell_content(1, 1), first);
+            assert_eq!(app.workbook.get_current_sheet().max_cols, 1);
+
+            app.input_buffer = "upper".to_string();
+            app.execute_command();
+            let deadline = Instant::now() + Duration::from_secs(10);
+            while !app.workbook.get_current_sheet().is_loaded {
+                assert!(Instant::now() < deadline, "sheet never finished loading");
+                app.poll_sheet_loads();
+                std::thread::sleep(Duration::from_millis(5));
+            }
+            let streamed = app.workbook.get_current_sheet();
+            let read_whole = eager.get_sheet_by_index(index).unwrap();
+            assert_eq!(
+                (streamed.max_rows, streamed.max_cols),
+                (read_whole.max_rows, read_whole.max_cols)
+            );
+            assert!(streamed.data == read_whole.data);
+        }
+        assert_eq!(app.get_cell_content(1, 2), "c0");
+        assert_eq!(app.get_cell_content(11_501, 1), "late");
+    }
+
+    #[test]
+    fn the_sheets_next_to_the_current_one_are_read_ahead_quietly() {
+        use rust_xlsxwriter::Workbook as XlsxWorkbook;
+
+        let path = std::env::temp_dir().join("excel_cli_preload_adjacent_sheets.xlsx");
+        let mut xlsx = XlsxWorkbook::new();
+        for name in ["First", "Second", "Third"] {
+            let sheet = xlsx.add_worksheet();
+            sheet.set_name(name).unwrap();
+            sheet.write_string(0, 0, name.to_lowercase()).unwrap();
+        }
+        xlsx.save(&path).unwrap();
+
+        let workbook = open_workbook(&path, true).unwrap();
+        let mut app = AppState::new(workbook, path.clone()).unwrap();
+        poll_until_loaded(&mut app);
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while !app.workbook.get_sheet_by_index(1).unwrap().is_loaded {
+            assert!(Instant::now() < deadline, "next sheet never preloaded");
+            app.poll_sheet_loads();
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        // Only the sheet next to the current one is read ahead
+        assert!(!app.workbook.get_sheet_by_index(2).unwrap().is_loaded);
+        assert!(!app
+            .notification_messages
+            .iter()
+            .any(|message| message.text.starts_with("Loaded sheet Second")));
+
+        app.switch_to_sheet("Second");
+        assert!(matches!(app.input_mode, InputMode::Normal));
+        assert_eq!(app.get_cell_content(1, 1), "second");
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while !app.workbook.get_sheet_by_index(2).unwrap().is_loaded {
+            assert!(Instant::now() < deadline, "next sheet never preloaded");
+            app.poll_sheet_loads();
+            std::thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    #[test]
+    fn reading_stops_at_the_memory_cap_until_it_is_raised() {
+        use rust_xlsxwriter::Workbook as XlsxWorkbook;
+
+        let path = std::env::temp_dir().join("excel_cli_memory_cap.xlsx");
+        let mut xlsx = XlsxWorkbook::new();
+        let sheet = xlsx.add_worksheet();
+        sheet.set_name("Big").unwrap();
+        for row in 0..30_000 {
+            sheet.write_number(row, 0, f64::from(row)).unwrap();
+        }
+        let sheet = xlsx.add_worksheet();
+        sheet.set_name("Small").unwrap();
+        sheet.write_string(0, 0, "small").unwrap();
+        xlsx.save(&path).unwrap();
+
+        let workbook = open_workbook(&path, true).unwrap();
+        let mut app = AppState::new(workbook, path.clone()).unwrap();
+        app.handle_set_command("memcap=100K");
+        assert_eq!(
+            app.notification_messages.last().unwrap().text,
+            "memcap=100KB"
+        );
+        poll_until_loaded(&mut app);
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while !app.current_sheet_load().unwrap().held_at_memory_cap {
+            assert!(Instant::now() < deadline, "reading never stopped");
+            app.poll_sheet_loads();
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        let rows = app.workbook.get_current_sheet().max_rows;
+        assert!(app
+            .notification_messages
+            .last()
+            .unwrap()
+            .text
+            .starts_with(&format!("Sheet Big stopped loading at {rows} rows: ")));
+
+        // Asking for the rest of a held sheet reads no more of it
+        app.input_buffer = "upper".to_string();
+        app.execute_command();
+        let pause = Instant::now() + Duration::from_millis(200);
+        while Instant::now() < pause {
+            app.poll_sheet_loads();
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        assert!(app.workbook.is_sheet_streaming(0));
+        assert!(app.workbook.get_current_sheet().max_rows < 30_000);
+
+        app.input_buffer = "mem".to_string();
+        app.execute_command();
+        let pager = app.pager.as_ref().unwrap();
+        assert!(pager.title.ends_with(", memcap=100 KB"));
+        assert!(pager.lines[1].starts_with("Big    held at memcap, "));
+        assert!(pager.lines[2].starts_with("Small  not loaded "));
+        app.close_pager();
+
+        // A sheet read whole is refused when it would pass the cap
+        app.switch_to_sheet("Small");
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while app
+            .current_sheet_load()
+            .is_none_or(|load| load.error.is_none())
+        {
+            assert!(Instant::now() < deadline, "sheet never refused");
+            app.poll_sheet_loads();
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        assert!(app
+            .notification_messages
+            .last()
+            .unwrap()
+            .text
+            .contains("past memcap=100 KB"));
+
+        app.switch_to_sheet("Big");
+        app.handle_set_command("memcap=off");
+        app.input_buffer = "upper".to_string();
+        app.execute_command();
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while !app.workbook.get_current_sheet().is_loaded {
+            assert!(Instant::now() < deadline, "sheet never finished loading");
+            app.poll_sheet_loads();
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        assert_eq!(app.get_cell_content(30_000, 1), "29999");
+    }
+}
@@ -0,0 +1,264 @@
+use crate::app::{AppState, InputMode};
+
+/// How `:split` and `:vsplit` lay out the two panes
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SplitDirection {
+    /// One pane above the other
+    Horizontal,
+    /// Panes side by side
+    Vertical,
+}
+
+/// What one pane shows: its sheet, cursor, selection and scroll position
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Viewport {
+    pub sheet_index: usize,
+    pub selected_cell: (usize, usize),
+    pub selection_anchor: Option<(usize, usize)>,
+    pub start_row: usize,
+    pub start_col: usize,
+    pub visible_rows: usize,
+    pub visible_cols: usize,
+}
+
+/// Two panes over the same workbook. The focused pane's viewport lives in `AppState` itself,
+/// so navigation and editing need not know about splits; the other one waits here and is
+/// swapped in when it gets the focus or is drawn
+#[derive(Clone, Debug)]
+pub struct SplitView {
+    pub direction: SplitDirection,
+    pub other: Viewport,
+    /// Whether the focused pane is the bottom or right one
+    pub focus_second: bool,
+}
+
+impl AppState<'_> {
+    /// Splits the view into two panes on the current cell, as `:split` and `:vsplit` do. With
+    /// `sheet`, a name or 1-based number, the new focused pane shows that sheet instead
+    pub fn split_view(&mut self, direction: SplitDirection, sheet: Option<&str>) {
+        if self.split.is_some() {
+            self.add_notification("The view is already split; :close a pane first".to_string());
+            return;
+        }
+
+        let sheet_index = match sheet {
+            Some(sheet) => match self.find_sheet_index(sheet) {
+                Some(index) => Some(index),
+                None => {
+                    self.add_notification(format!("Sheet '{sheet}' not found"));
+                    return;
+                }
+            },
+            None => None,
+        };
+
+        self.split = Some(SplitView {
+            direction,
+            other: self.current_viewport(),
+            focus_second: false,
+        });
+        match sheet_index {
+            Some(index) if index != self.workbook.get_current_sheet_index() => {
+                if let Err(e) = self.switch_sheet_by_index(index) {
+                    self.add_error(format!("Failed to switch sheet: {e}"));
+                }
+            }
+            _ => self.add_notification("Split the view; Ctrl+W w switches panes".to_string()),
+        }
+    }
+
+    /// Moves the focus to the other pane, as `Ctrl+W w` does
+    pub fn focus_other_pane(&mut self) {
+        let Some(split) = self.split.as_mut() else {
+            self.add_notification("Only one pane is open".to_string());
+            return;
+        };
+        split.focus_second = !split.focus_second;
+        let mut viewport = split.other.clone();
+
+        let previous_sheet = self.workbook.get_current_sheet_index();
+        self.swap_viewport(&mut viewport);
+        if let Some(split) = self.split.as_mut() {
+            split.other = viewport;
+        }
+
+        let index = self.workbook.get_current_sheet_index();
+        if index != previous_sheet {
+            // Search results belong to the sheet they were found on
+            self.search_results.clear();
+            self.current_search_idx = None;
+            if self.workbook.is_lazy_loading() && !self.workbook.is_sheet_loaded(index) {
+                self.input_mode = InputMode::LazyLoading;
+            }
+        }
+    }
+
+    /// Focuses the top or left pane, or with `second` the bottom or right one
+    pub fn focus_pane(&mut self, second: bool) {
+        if self
+            .split
+            .as_ref()
+            .is_some_and(|split| split.focus_second != second)
+        {
+            self.focus_other_pane();
+        }
+    }
+
+    /// Closes the focused pane, leaving the other one full size
+    pub fn close_pane(&mut self) {
+        if self.split.is_none() {
+            self.add_notification("Only one pane is open".to_string());
+            return;
+        }
+        self.focus_other_pane();
+        self.split = None;
+    }
+
+    /// Closes every pane but the focused one, as `Ctrl+W o` does
+    pub fn close_other_pane(&mut self) {
+        if self.split.take().is_none() {
+            self.add_notification("Only one pane is open".to_string());
+        }
+    }
+
+    /// Runs `action` with the unfocused pane swapped in, which is how it is drawn. Returns
+    /// `None` when the view is not split
+    pub fn with_other_pane<R>(&mut self, action: impl FnOnce(&mut Self) -> R) -> Option<R> {
+        let mut viewport = self.split.as_ref()?.other.clone();
+        let same_sheet = viewport.sheet_index == self.workbook.get_current_sheet_index();
+
+        self.swap_viewport(&mut viewport);
+        let search_results = (!same_sheet).then(|| std::mem::take(&mut self.search_results));
+        let result = action(self);
+        if let Some(search_results) = search_results {
+            self.search_results = search_results;
+        }
+        self.swap_viewport(&mut viewport);
+
+        if let Some(split) = self.split.as_mut() {
+            split.other = viewport;
+        }
+        Some(result)
+    }
+
+    fn current_viewport(&self) -> Viewport {
+        Viewport {
+            sheet_index: self.workbook.get_current_sheet_index(),
+            selected_cell: self.selected_cell,
+            selection_anchor: self.selection_anchor,
+            start_row: self.start_row,
+            start_col: self.start_col,
+            visible_rows: self.visible_rows,
+            visible_cols: self.visible_cols,
+        }
+    }
+
+    /// Exchanges the focused viewport with `viewport`, switching sheets quietly when they differ
+    fn swap_viewport(&mut self, viewport: &mut Viewport) {
+        let current = self.current_viewport();
+        let sheet_count = self.workbook.get_sheet_names().len();
+        // Sheets may have been deleted since the pane was last shown
+        let index = viewport.sheet_index.min(sheet_count.saturating_sub(1));
+
+        if index != current.sheet_index && self.workbook.switch_sheet(index).is_ok() {
+            let old_name = self.workbook.get_sheet_names()[current.sheet_index].clone();
+            let widths = std::mem::take(&mut self.column_widths);
+            self.sheet_column_widths.insert(old_name, widths);
+
+            let new_name = self.workbook.get_current_sheet_name();
+            self.column_widths = self
+                .sheet_column_widths
+                .get(&new_name)
+                .cloned()
+                .unwrap_or_else(|| vec![15; self.workbook.get_current_sheet().max_cols + 1]);
+        }
+
+        self.selected_cell = Self::clamp_cell_to_excel_bounds(viewport.selected_cell);
+        self.selection_anchor = viewport.selection_anchor;
+        self.start_row = viewport.start_row;
+        self.start_col = viewport.start_col;
+        self.visible_rows = viewport.visible_rows;
+        self.visible_cols = viewport.visible_cols;
+        self.update_row_number_width();
+
+        *viewport = current;
+    }
+
+    /// Finds a sheet by case-insensitive name or 1-based number
+    fn find_sheet_index(&self, name_or_number: &str) -> Option<usize> {
+        let sheet_names = self.workbook.get_sheet_names();
+        sheet_names
+            .iter()
+            .position(|name| name.eq_ignore_ascii_case(name_or_number))
+            .or_else(|| {
+                name_or_number
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|&number| (1..=sheet_names.len()).contains(&number))
+                    .map(|number| number - 1)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SplitDirection;
+    use crate::app::AppState;
+    use crate::excel::{Cell, Sheet, Workbook};
+    use std::path::PathBuf;
+
+    fn app_with_two_sheets() -> AppState<'static> {
+        let mut data = Sheet::blank("Data".to_string());
+        data.data.set(1, 1, Cell::new("Header".to_string(), false));
+        data.max_rows = 2;
+        data.max_cols = 2;
+        let workbook =
+            Workbook::from_sheets_for_test(vec![data, Sheet::blank("Lookup".to_string())]);
+        AppState::new(workbook, PathBuf::from("test.xlsx")).unwrap()
+    }
+
+    #[test]
+    fn panes_keep_their_own_position_on_one_sheet() {
+        let mut app = app_with_two_sheets();
+        app.split_view(SplitDirection::Horizontal, None);
+
+        app.selected_cell = (5000, 2);
+        app.handle_scrolling();
+        app.focus_other_pane();
+        assert_eq!(app.selected_cell, (1, 1));
+        assert_eq!(app.start_row, 1);
+
+        app.focus_other_pane();
+        assert_eq!(app.selected_cell, (5000, 2));
+        assert!(app.start_row > 1);
+
+        let other = app.with_other_pane(|app| app.get_cell_content(1, 1));
+        assert_eq!(other.as_deref(), Some("Header"));
+        assert_eq!(app.selected_cell, (5000, 2));
+    }
+
+    #[test]
+    fn panes_can_show_different_sheets_until_one_is_closed() {
+        let mut app = app_with_two_sheets();
+        app.selected_cell = (2, 2);
+        app.split_view(SplitDirection::Vertical, Some("lookup"));
+        assert_eq!(app.workbook.get_current_sheet_name(), "Lookup");
+
+        app.selected_cell = (3, 1);
+        let other_sheet = app.with_other_pane(|app| app.workbook.get_current_sheet_name());
+        assert_eq!(other_sheet.as_deref(), Some("Data"));
+        assert_eq!(app.workbook.get_current_sheet_name(), "Lookup");
+
+        app.focus_pane(true);
+        assert_eq!(app.workbook.get_current_sheet_name(), "Data");
+        assert_eq!(app.selected_cell, (2, 2));
+
+        app.close_pane();
+        assert!(app.split.is_none());
+        assert_eq!(app.workbook.get_current_sheet_name(), "Lookup");
+        assert_eq!(app.selected_cell, (3, 1));
+
+        app.split_view(SplitDirection::Horizontal, Some("Missing"));
+        assert!(app.split.is_none());
+    }
+}
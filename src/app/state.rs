@@ -4,8 +4,16 @@ use std::path::PathBuf;
 use tui_textarea::TextArea;
 
 use crate::actions::UndoHistory;
-use crate::app::VimState;
-use crate::excel::{Workbook, EXCEL_MAX_COLS, EXCEL_MAX_ROWS};
+use crate::app::{
+    BufferRequest, CellRange, Clipboard, FixedWidthImport, IncSearch, InfoPanelPosition,
+    InputHistory, Mark, MarkAction, MessageLevel, MessageLog, Notification, PagerView,
+    PendingPaste, ResultsPanel, RowFilter, SearchIndex, SearchScope, SheetLoad, SplitView,
+    VimState,
+};
+use crate::excel::{Cell, CellType, DataTypeInfo, Workbook, EXCEL_MAX_COLS, EXCEL_MAX_ROWS};
+use crate::json_export::{excel_date_to_iso_string, ExportOptions};
+use crate::ui::Keymap;
+use crate::utils::SortMode;
 
 /// Represents a cell position in a sheet, including both the selected cell and view position
 #[derive(Clone, Copy)]
@@ -25,12 +33,18 @@ pub enum InputMode {
     Help,
     LazyLoading,
     CommandInLazyLoading,
+    FixedWidthImport,
+    ConfirmPaste,
+    Onboarding,
+    Pager,
+    Results,
 }
 
 pub struct AppState<'a> {
     pub workbook: Workbook,
     pub file_path: PathBuf,
-    pub selected_cell: (usize, usize), // (row, col)
+    pub selected_cell: (usize, usize),            // (row, col)
+    pub selection_anchor: Option<(usize, usize)>, // Fixed corner of the visual selection started with v
     pub start_row: usize,
     pub start_col: usize,
     pub visible_rows: usize,
@@ -42,23 +56,75 @@ pub struct AppState<'a> {
     pub column_widths: Vec<usize>, // Store width for current sheet's columns
     pub sheet_column_widths: HashMap<String, Vec<usize>>, // Store column widths for each sheet
     pub sheet_cell_positions: HashMap<String, CellPosition>, // Store cell positions for each sheet
-    pub clipboard: Option<String>, // Store copied/cut cell content
+    pub sheet_header_rows: HashMap<String, usize>, // Rows made each sheet's header with :headerrow
+    pub clipboard: Clipboard,      // Cells copied or cut with y/d, also on the system clipboard
     pub g_pressed: bool,           // Track if 'g' was pressed for 'gg' command
+    pub z_pressed: bool,           // Track if 'z' was pressed for zz, zt and zb
+    pub y_pressed: bool,           // Track if 'y' was pressed for yy and yc
+    pub pending_count: Option<usize>, // Count typed before a motion, like the 10 in 10j
+    pub pending_mark: Option<MarkAction>, // Set after m or ', waiting for the mark's letter
+    pub register_key_pending: bool, // Set after ", waiting for the register's digit
+    pub pending_register: Option<usize>, // Register the next p pastes from, as in "1p
+    pub marks: HashMap<char, Mark>, // Cells remembered with m{a-z}
     pub row_number_width: usize,   // Width for displaying row numbers
     pub search_query: String,      // Current search query
     pub search_results: Vec<(usize, usize)>, // List of cells matching the search query
     pub current_search_idx: Option<usize>, // Index of current search result
     pub search_direction: bool,    // true for forward, false for backward
     pub highlight_enabled: bool,   // Control whether search results are highlighted
-    pub info_panel_height: usize,
-    pub notification_messages: Vec<String>,
+    pub match_cell: bool,          // :set matchcell, searches match whole cells only
+    pub search_scope: SearchScope, // :set searchscope=, which cells a new search looks at
+    pub search_bounds: Option<CellRange>, // Area the last search was limited to by its scope
+    pub incremental_search: bool,  // :set incsearch, preview matches while the query is typed
+    pub use_search_index: bool,    // :set searchindex, index long sheets to search them quickly
+    pub search_index: Option<SearchIndex>, // Words of the current sheet, while it is long
+    pub memory_cap: Option<usize>, // :set memcap=, most memory the sheets' cells may take
+    pub wrap_cells: bool,          // :set wrap, long cells span several terminal lines
+    pub stripes: bool,             // :set stripes, shade every other row
+    pub crosshair: bool,           // :set crosshair, shade the cursor's row and column
+    pub gridlines: bool,           // :set gridlines, border around the grid
+    pub show_formulas: bool,       // :set showformulas, the grid shows formulas, not results
+    pub cell_colors: bool,         // :set cellcolors, cells keep their fill and font colors
+    pub incsearch: Option<IncSearch>, // What the open search prompt will put back on Esc
+    pub search_history: InputHistory, // Past queries, recalled with Up/Down at the prompt
+    pub command_history: InputHistory, // Past : commands, recalled with Up/Down at the prompt
+    pub save_history: bool,        // :set savehistory, keep both histories for the next session
+    pub row_filter: Option<RowFilter>, // Rows left showing by :only
+    pub sort_mode: SortMode,       // How values are ordered in search results and grouped by dedupe
+    pub keymap: Keymap, // Keys of normal and command mode, as the [keys] config maps them
+    pub export_options: ExportOptions, // Value formatting for :ej / :eja
+    pub show_hidden_sheets: bool, // Include hidden sheets in the tab bar and [ ] switching
+    pub first_tab: usize, // Position among the shown tabs of the first one drawn, kept by the render
+    pub tabs_scrolled_on: Option<usize>, // Sheet that was current when { } scrolled the tabs
+    pub split: Option<SplitView>, // The unfocused pane after :split or :vsplit
+    pub window_key_pending: bool, // Set after Ctrl+W, waiting for the pane command
+    pub show_minimap: bool, // Draw the :minimap overview beside the grid
+    pub show_info_panel: bool, // :set infopanel, hidden with _ to give the grid its space
+    pub info_panel_position: InfoPanelPosition, // :set infopanelpos, below or beside the grid
+    pub info_panel_height: usize, // Rows below the grid, changed with + and -
+    pub info_panel_width: usize, // Columns beside the grid, changed with + and -
+    pub notification_messages: Vec<Notification>,
+    pub message_log: MessageLog, // Every notification of the session, listed by :messages
     pub max_notifications: usize,
-    pub help_text: String,
+    pub help_filter: String, // Typed after / in the help overlay; only matching entries show
+    pub help_filter_typing: bool, // The / prompt of the help overlay is open
     pub help_scroll: usize,
     pub help_visible_lines: usize,
     pub help_total_lines: usize,
     pub undo_history: UndoHistory,
+    pub fixed_width_import: Option<FixedWidthImport>,
+    pub pending_paste: Option<PendingPaste>,
+    /// Configuration directory created once the first-run welcome overlay is dismissed
+    pub onboarding_config_dir: Option<PathBuf>,
+    pub pager: Option<PagerView>,
+    pub results: Option<ResultsPanel>,
     pub vim_state: Option<VimState>,
+    /// Switch or listing of open workbooks asked for with `gt` or `:buffer`, for the session
+    pub buffer_request: Option<BufferRequest>,
+    /// This workbook's 1-based place among the open ones, when more than one is open
+    pub buffer_position: Option<(usize, usize)>,
+    /// Lazily loaded sheets being read on background threads, and reads that failed
+    pub sheet_loads: Vec<SheetLoad>,
 }
 
 impl AppState<'_> {
@@ -116,7 +182,8 @@ impl AppState<'_> {
         let row_number_width = row_number_width.max(4);
 
         // Check if the workbook is using lazy loading and the first sheet is not loaded
-        let is_lazy_loading = workbook.is_lazy_loading() && !workbook.is_sheet_loaded(0);
+        let is_lazy_loading = workbook.is_lazy_loading()
+            && !workbook.is_sheet_loaded(workbook.get_current_sheet_index());
 
         // Set initial input mode based on lazy loading status
         let initial_input_mode = if is_lazy_loading {
@@ -129,6 +196,7 @@ impl AppState<'_> {
             workbook,
             file_path,
             selected_cell: (1, 1), // Excel uses 1-based indexing
+            selection_anchor: None,
             start_row: 1,
             start_col: 1,
             visible_rows: 30, // Default values, will be adjusted based on window size
@@ -140,28 +208,82 @@ impl AppState<'_> {
             column_widths,
             sheet_column_widths,
             sheet_cell_positions,
-            clipboard: None,
+            sheet_header_rows: HashMap::new(),
+            clipboard: Clipboard::default(),
             g_pressed: false,
+            z_pressed: false,
+            y_pressed: false,
+            pending_count: None,
+            pending_mark: None,
+            register_key_pending: false,
+            pending_register: None,
+            marks: HashMap::new(),
             row_number_width,
             search_query: String::new(),
             search_results: Vec::new(),
             current_search_idx: None,
             search_direction: true,  // Default to forward search
             highlight_enabled: true, // Default to showing highlights
+            match_cell: false,
+            search_scope: SearchScope::default(),
+            search_bounds: None,
+            incremental_search: true,
+            use_search_index: true,
+            search_index: None,
+            memory_cap: None,
+            wrap_cells: false,
+            stripes: false,
+            crosshair: false,
+            gridlines: true,
+            show_formulas: false,
+            cell_colors: true,
+            incsearch: None,
+            search_history: InputHistory::default(),
+            command_history: InputHistory::default(),
+            save_history: true,
+            row_filter: None,
+            sort_mode: SortMode::default(),
+            keymap: Keymap::default(),
+            export_options: ExportOptions::default(),
+            show_hidden_sheets: false,
+            first_tab: 0,
+            tabs_scrolled_on: None,
+            split: None,
+            window_key_pending: false,
+            show_minimap: false,
+            show_info_panel: true,
+            info_panel_position: InfoPanelPosition::default(),
             info_panel_height: 10,
+            info_panel_width: 40,
             notification_messages: Vec::new(),
+            message_log: MessageLog::default(),
             max_notifications: 5,
-            help_text: String::new(),
+            help_filter: String::new(),
+            help_filter_typing: false,
             help_scroll: 0,
             help_visible_lines: 20,
             help_total_lines: 0,
             undo_history: UndoHistory::new(),
+            fixed_width_import: None,
+            pending_paste: None,
+            onboarding_config_dir: None,
+            pager: None,
+            results: None,
             vim_state: None,
+            buffer_request: None,
+            buffer_position: None,
+            sheet_loads: Vec::new(),
         })
     }
 
     pub fn add_notification(&mut self, message: String) {
-        self.notification_messages.push(message);
+        self.push_notification(MessageLevel::Info, message);
+    }
+
+    pub(super) fn push_notification(&mut self, level: MessageLevel, text: String) {
+        let notification = Notification { level, text };
+        self.message_log.push(notification.clone());
+        self.notification_messages.push(notification);
 
         if self.notification_messages.len() > self.max_notifications {
             self.notification_messages.remove(0);
@@ -192,39 +314,96 @@ impl AppState<'_> {
         self.start_col = self.start_col.clamp(1, EXCEL_MAX_COLS);
     }
 
-    pub fn adjust_info_panel_height(&mut self, delta: isize) {
-        let new_height = (self.info_panel_height as isize + delta).clamp(6, 16) as usize;
-        if new_height != self.info_panel_height {
-            self.info_panel_height = new_height;
-            self.add_notification(format!("Info panel height: {}", self.info_panel_height));
-        }
+    /// Shows or hides the overview minimap, as `:minimap` does
+    pub fn toggle_minimap(&mut self) {
+        self.show_minimap = !self.show_minimap;
+        let state = if self.show_minimap { "shown" } else { "hidden" };
+        self.add_notification(format!("Minimap {state}"));
     }
 
+    /// Cell text as displayed, with any `:fmt` number format applied
     pub fn get_cell_content(&self, row: usize, col: usize) -> String {
+        self.formatted_cell(row, col)
+            .unwrap_or_else(|| self.raw_cell_content(row, col))
+    }
+
+    /// A cell's value as displayed, with any `:fmt` number format applied but without the
+    /// formula marker, for writing into other cells
+    #[must_use]
+    pub fn displayed_value(&self, row: usize, col: usize) -> String {
+        self.formatted_cell(row, col).unwrap_or_else(|| {
+            self.workbook
+                .get_current_sheet()
+                .data
+                .cell(row, col)
+                .value
+                .clone()
+        })
+    }
+
+    fn formatted_cell(&self, row: usize, col: usize) -> Option<String> {
         let sheet = self.workbook.get_current_sheet();
+        sheet.data.get(row, col).and_then(|cell| {
+            sheet
+                .number_format_at(row, col)
+                .and_then(|format| format.format_cell(cell))
+                .or_else(|| date_display(cell))
+        })
+    }
 
-        if row < sheet.data.len() && col < sheet.data[0].len() {
-            let cell = &sheet.data[row][col];
-            if cell.is_formula {
-                let mut result = String::with_capacity(9 + cell.value.len());
-                result.push_str("Formula: ");
-                result.push_str(&cell.value);
-                result
-            } else {
-                cell.value.clone()
+    /// What the grid shows for a cell: with `:set showformulas` a formula cell's formula,
+    /// otherwise its content
+    #[must_use]
+    pub fn grid_cell_content(&self, row: usize, col: usize) -> String {
+        if self.show_formulas {
+            if let Some(formula) = self.cell_formula(row, col) {
+                return formula;
             }
+        }
+        self.get_cell_content(row, col)
+    }
+
+    /// The formula text of a formula cell, like `=SUM(A1:A9)`
+    #[must_use]
+    pub fn cell_formula(&self, row: usize, col: usize) -> Option<String> {
+        let cell = self.workbook.get_current_sheet().data.get(row, col)?;
+        cell.formula
+            .clone()
+            .or_else(|| cell.is_formula.then(|| cell.value.clone()))
+    }
+
+    /// Cell text as stored, which is what editing and cutting work on
+    pub fn raw_cell_content(&self, row: usize, col: usize) -> String {
+        let sheet = self.workbook.get_current_sheet();
+
+        let cell = sheet.data.cell(row, col);
+        if cell.is_formula {
+            let mut result = String::with_capacity(9 + cell.value.len());
+            result.push_str("Formula: ");
+            result.push_str(&cell.value);
+            result
         } else {
-            String::new()
+            cell.value.clone()
         }
     }
 
+    /// Note attached to a cell of the current sheet, if any
+    #[must_use]
+    pub fn cell_comment(&self, row: usize, col: usize) -> Option<&str> {
+        self.workbook
+            .get_current_sheet()
+            .data
+            .get(row, col)
+            .and_then(|cell| cell.comment.as_deref())
+    }
+
     pub fn get_cell_content_mut(&mut self, row: usize, col: usize) -> String {
         self.workbook.ensure_cell_exists(row, col);
 
         self.ensure_column_widths();
 
         let sheet = self.workbook.get_current_sheet();
-        let cell = &sheet.data[row][col];
+        let cell = sheet.data.cell(row, col);
 
         if cell.is_formula {
             let mut result = String::with_capacity(9 + cell.value.len());
@@ -259,19 +438,33 @@ impl AppState<'_> {
 
     pub fn add_char_to_input(&mut self, c: char) {
         self.input_buffer.push(c);
+        self.command_history.reset();
     }
 
     pub fn delete_char_from_input(&mut self) {
         self.input_buffer.pop();
+        self.command_history.reset();
     }
 
     pub fn start_command_mode(&mut self) {
         self.input_mode = InputMode::Command;
         self.input_buffer = String::new();
+        self.command_history.reset();
     }
 
     pub fn start_command_in_lazy_loading_mode(&mut self) {
         self.input_mode = InputMode::CommandInLazyLoading;
         self.input_buffer = String::new();
+        self.command_history.reset();
+    }
+}
+
+/// Readable text for a date cell read from the workbook, which stores the serial as its value
+fn date_display(cell: &Cell) -> Option<String> {
+    match cell.original_type {
+        Some(DataTypeInfo::DateTime(serial)) if cell.cell_type == CellType::Date => {
+            Some(excel_date_to_iso_string(serial))
+        }
+        _ => None,
     }
 }
@@ -0,0 +1,317 @@
+use crate::actions::ActionType;
+use crate::app::{AppState, CellRange, ResultItem};
+use crate::cli::regex_support;
+
+const SUBSTITUTE_USAGE: &str = "Usage: :[%]s/pattern/replacement/[g][i][n]";
+
+/// A parsed `:s/pattern/replacement/flags` command
+#[derive(Clone, Debug, PartialEq)]
+struct Substitution {
+    /// `%` before the `s`: the whole used range rather than the selection or current row
+    whole_sheet: bool,
+    pattern: String,
+    /// In the regex crate's syntax, with vim's `\1` rewritten as `${1}`
+    replacement: String,
+    /// `g`: every match in a cell, not only the first
+    all: bool,
+    /// `i`: ignore case
+    case_insensitive: bool,
+    /// `n`: list what would change without changing it
+    dry_run: bool,
+}
+
+impl Substitution {
+    /// `None` when `command` is no substitution, like `:sp`, and an error when it is one
+    /// that cannot run
+    fn parse(command: &str) -> Option<Result<Self, String>> {
+        let (whole_sheet, rest) = match command.strip_prefix('%') {
+            Some(rest) => (true, rest),
+            None => (false, command),
+        };
+        let rest = rest.strip_prefix('s')?;
+        let delimiter = rest.chars().next()?;
+        if delimiter.is_alphanumeric() || delimiter.is_whitespace() || delimiter == '\\' {
+            return None;
+        }
+
+        let rest = &rest[delimiter.len_utf8()..];
+        let (pattern, rest) = split_at_delimiter(rest, delimiter);
+        let (replacement, flags) = split_at_delimiter(rest.unwrap_or_default(), delimiter);
+        let mut substitution = Self {
+            whole_sheet,
+            pattern,
+            replacement: vim_replacement(&replacement),
+            all: false,
+            case_insensitive: false,
+            dry_run: false,
+        };
+        if substitution.pattern.is_empty() {
+            return Some(Err(SUBSTITUTE_USAGE.to_string()));
+        }
+
+        for flag in flags.unwrap_or_default().trim().chars() {
+            match flag {
+                'g' => substitution.all = true,
+                'i' => substitution.case_insensitive = true,
+                'I' => substitution.case_insensitive = false,
+                'n' => substitution.dry_run = true,
+                _ => return Some(Err(format!("Unknown flag {flag}. {SUBSTITUTE_USAGE}"))),
+            }
+        }
+        Some(Ok(substitution))
+    }
+}
+
+/// Splits at the first `delimiter` not escaped with a backslash, unescaping `\<delimiter>` in
+/// the part before it. Other escapes are kept for the regex
+fn split_at_delimiter(text: &str, delimiter: char) -> (String, Option<&str>) {
+    let mut part = String::new();
+    let mut chars = text.char_indices();
+    while let Some((index, ch)) = chars.next() {
+        if ch == delimiter {
+            return (part, Some(&text[index + ch.len_utf8()..]));
+        }
+        if ch == '\\' {
+            match chars.next() {
+                Some((_, escaped)) if escaped == delimiter => part.push(escaped),
+                Some((_, escaped)) => {
+                    part.push('\\');
+                    part.push(escaped);
+                }
+                None => part.push('\\'),
+            }
+        } else {
+            part.push(ch);
+        }
+    }
+    (part, None)
+}
+
+/// Rewrites vim's `\1`..`\9` group references and `\\` for the regex crate, which already
+/// understands `$1` and `${name}`
+fn vim_replacement(replacement: &str) -> String {
+    let mut rewritten = String::new();
+    let mut chars = replacement.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            rewritten.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some(group) if group.is_ascii_digit() => {
+                rewritten.push_str(&format!("${{{group}}}"));
+            }
+            Some('\\') | None => rewritten.push('\\'),
+            Some(escaped) => rewritten.push(escaped),
+        }
+    }
+    rewritten
+}
+
+/// Whether a command line is `:s` or `:%s` with a delimiter, as opposed to `:sp` or `:set`
+pub(crate) fn is_substitute_command(command: &str) -> bool {
+    Substitution::parse(command).is_some()
+}
+
+impl AppState<'_> {
+    /// Handles `:s/pattern/replacement/flags`: replaces regex matches in the visual selection,
+    /// the current row, or with `:%s` the whole sheet, as one undoable action. Formula cells
+    /// are left alone. With the `n` flag the cells that would change are listed in the results
+    /// panel instead
+    pub fn substitute(&mut self, command: &str) {
+        let substitution = match Substitution::parse(command) {
+            Some(Ok(substitution)) => substitution,
+            Some(Err(message)) => {
+                self.add_error(message);
+                return;
+            }
+            None => {
+                self.add_warning(SUBSTITUTE_USAGE.to_string());
+                return;
+            }
+        };
+        let regex =
+            match regex_support::compile(&substitution.pattern, substitution.case_insensitive) {
+                Ok(regex) => regex,
+                Err(e) => {
+                    self.add_error(format!("Invalid pattern: {e}"));
+                    return;
+                }
+            };
+        if let Some(group) = regex_support::missing_group(&regex, &substitution.replacement) {
+            self.add_error(format!(
+                "No capture group {group} in pattern: {}",
+                substitution.pattern
+            ));
+            return;
+        }
+
+        let range = self.substitution_range(substitution.whole_sheet);
+        let changes = self.value_changes(range, |value| {
+            regex_support::replace(&regex, value, &substitution.replacement, substitution.all)
+        });
+        if changes.is_empty() {
+            self.add_notification(format!("Pattern not found: {}", substitution.pattern));
+            return;
+        }
+
+        let count = changes.len();
+        let noun = if count == 1 { "cell" } else { "cells" };
+        if substitution.dry_run {
+            let sheet_name = self.workbook.get_current_sheet_name();
+            let items = changes
+                .iter()
+                .map(|change| ResultItem {
+                    sheet_name: sheet_name.clone(),
+                    cell: (change.row, change.col),
+                    text: format!("{} → {}", change.old_value.value, change.new_value.value),
+                })
+                .collect();
+            self.set_results(format!("Substitute preview: {count} {noun}"), items, true);
+            self.add_notification(format!("{count} {noun} would change"));
+            return;
+        }
+
+        let (start, end) = range;
+        if self.refuse_locked_range(start, end) {
+            return;
+        }
+        self.commit_range_changes(changes, ActionType::Substitute);
+        self.add_notification(format!("Replaced in {count} {noun}"));
+    }
+
+    /// The whole used range for `:%s`, otherwise the visual selection or the current row
+    fn substitution_range(&mut self, whole_sheet: bool) -> CellRange {
+        if whole_sheet {
+            return self.used_range();
+        }
+        if let Some(range) = self.selection_range() {
+            self.clear_selection();
+            return range;
+        }
+        let row = self.selected_cell.0;
+        let max_cols = self.workbook.get_current_sheet().max_cols.max(1);
+        ((row, 1), (row, max_cols))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_substitute_command, Substitution};
+    #[cfg(feature = "regex")]
+    use crate::app::{AppState, MessageLevel};
+    #[cfg(feature = "regex")]
+    use crate::excel::{Sheet, Workbook};
+    #[cfg(feature = "regex")]
+    use std::path::PathBuf;
+
+    #[cfg(feature = "regex")]
+    fn app_with_values(values: &[&str]) -> AppState<'static> {
+        let workbook = Workbook::from_sheets_for_test(vec![Sheet::blank("Data".to_string())]);
+        let mut app = AppState::new(workbook, PathBuf::from("test.xlsx")).unwrap();
+        for (row, value) in values.iter().enumerate() {
+            app.workbook
+                .set_cell_value(row + 1, 1, value.to_string())
+                .unwrap();
+        }
+        app
+    }
+
+    #[cfg(feature = "regex")]
+    fn values(app: &AppState) -> Vec<String> {
+        let rows = app.workbook.get_current_sheet().max_rows;
+        (1..=rows).map(|row| app.get_cell_content(row, 1)).collect()
+    }
+
+    #[test]
+    fn parses_delimiters_escapes_and_flags() {
+        let parsed = Substitution::parse(r"%s/(\d+)-(\d+)/$2\/\1/gn")
+            .unwrap()
+            .unwrap();
+        assert!(parsed.whole_sheet);
+        assert_eq!(parsed.pattern, r"(\d+)-(\d+)");
+        assert_eq!(parsed.replacement, "$2/${1}");
+        assert!(parsed.all && parsed.dry_run && !parsed.case_insensitive);
+
+        let parsed = Substitution::parse("s#a/b#c").unwrap().unwrap();
+        assert!(!parsed.whole_sheet);
+        assert_eq!(
+            (parsed.pattern.as_str(), parsed.replacement.as_str()),
+            ("a/b", "c")
+        );
+
+        for command in ["sp", "set wrap", "sortmode natural", "s", "s b"] {
+            assert!(!is_substitute_command(command), "{command}");
+        }
+        assert!(Substitution::parse("s/a/b/x").unwrap().is_err());
+        assert!(Substitution::parse("s//b/").unwrap().is_err());
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn substitutes_capture_groups_across_the_sheet_and_undoes_in_one_step() {
+        let mut app = app_with_values(&["12-34", "5-6 and 7-8", "no digits", "1-2"]);
+
+        app.substitute(r"%s/(\d+)-(\d+)/$2\/$1/g");
+        assert_eq!(values(&app), ["34/12", "6/5 and 8/7", "no digits", "2/1"]);
+        assert_eq!(
+            app.notification_messages.last().unwrap().text,
+            "Replaced in 3 cells"
+        );
+
+        app.undo().unwrap();
+        assert_eq!(values(&app), ["12-34", "5-6 and 7-8", "no digits", "1-2"]);
+
+        app.selected_cell = (2, 1);
+        app.substitute(r"s/(\d)-(\d)/\2-\1/");
+        assert_eq!(values(&app), ["12-34", "6-5 and 7-8", "no digits", "1-2"]);
+
+        app.substitute("%s/NO/yes/i");
+        assert_eq!(app.get_cell_content(3, 1), "yes digits");
+        app.substitute("%s/zzz/y/");
+        assert_eq!(
+            app.notification_messages.last().unwrap().text,
+            "Pattern not found: zzz"
+        );
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn refuses_references_to_groups_the_pattern_lacks() {
+        let mut app = app_with_values(&["12-34", "5"]);
+
+        for command in [
+            r"%s/(\d+)/$2/g",
+            r"%s/(\d+)/\2/g",
+            r"%s/(?<num>\d+)/${count}/g",
+            r"%s/(?<num>\d+)/$num_x/g",
+        ] {
+            app.substitute(command);
+            let last = app.notification_messages.last().unwrap();
+            assert!(last.text.starts_with("No capture group"), "{command}");
+            assert_eq!(last.level, MessageLevel::Error);
+            assert_eq!(values(&app), ["12-34", "5"], "{command}");
+        }
+
+        app.substitute(r"%s/(?<num>\d+)/<${num}$1$$>/");
+        assert_eq!(values(&app), ["<1212$>-34", "<55$>"]);
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn dry_run_lists_changes_without_making_them() {
+        let mut app = app_with_values(&["12-34", "none", "1-2"]);
+
+        app.substitute(r"%s/(\d+)-(\d+)/$2-$1/n");
+        assert_eq!(values(&app), ["12-34", "none", "1-2"]);
+        assert_eq!(
+            app.notification_messages.last().unwrap().text,
+            "2 cells would change"
+        );
+        let panel = app.results.as_ref().unwrap();
+        assert!(panel.is_open);
+        assert_eq!(panel.items[0].cell, (1, 1));
+        assert_eq!(panel.items[0].text, "12-34 → 34-12");
+        assert_eq!(panel.items[1].cell, (3, 1));
+    }
+}
@@ -0,0 +1,333 @@
+use std::ops::Range;
+
+use quick_xml::events::{BytesText, Event};
+
+/// Languages of structured cell contents, which the info panel and `K` indent and color
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Syntax {
+    Json,
+    Xml,
+}
+
+/// What a piece of highlighted text is, which sets its color
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TokenKind {
+    Plain,
+    /// Brackets, commas and colons of JSON, and the angle brackets of XML tags
+    Punctuation,
+    /// Names of JSON object members
+    Key,
+    String,
+    Number,
+    /// `true`, `false` and `null`
+    Keyword,
+    /// Element names of XML
+    Tag,
+    /// Attribute names of XML
+    Attribute,
+    Comment,
+}
+
+impl Syntax {
+    /// The language of `text` and `text` indented in it, for a JSON object or array or an
+    /// XML document; `None` for anything else
+    #[must_use]
+    pub fn pretty_print(text: &str) -> Option<(Self, String)> {
+        let trimmed = text.trim();
+        if trimmed.starts_with('{') || trimmed.starts_with('[') {
+            let value: serde_json::Value = serde_json::from_str(trimmed).ok()?;
+            return Some((Self::Json, serde_json::to_string_pretty(&value).ok()?));
+        }
+        if trimmed.starts_with('<') && trimmed.ends_with('>') {
+            return Some((Self::Xml, pretty_xml(trimmed)?));
+        }
+        None
+    }
+
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Json => "JSON",
+            Self::Xml => "XML",
+        }
+    }
+
+    /// `lines` cut into colored pieces. The lines are read as one text, so a string or tag
+    /// wrapped onto the next line keeps its color
+    #[must_use]
+    pub fn highlight<S: AsRef<str>>(self, lines: &[S]) -> Vec<Vec<(TokenKind, String)>> {
+        let text = lines
+            .iter()
+            .map(AsRef::as_ref)
+            .collect::<Vec<_>>()
+            .join("\n");
+        let tokens = match self {
+            Self::Json => json_tokens(&text),
+            Self::Xml => xml_tokens(&text),
+        };
+
+        let mut highlighted = vec![Vec::new()];
+        for (kind, range) in tokens {
+            let mut pieces = text[range].split('\n');
+            if let Some(first) = pieces.next() {
+                push_piece(&mut highlighted, kind, first);
+            }
+            for piece in pieces {
+                highlighted.push(Vec::new());
+                push_piece(&mut highlighted, kind, piece);
+            }
+        }
+        highlighted
+    }
+}
+
+fn push_piece(lines: &mut [Vec<(TokenKind, String)>], kind: TokenKind, piece: &str) {
+    if piece.is_empty() {
+        return;
+    }
+    if let Some(line) = lines.last_mut() {
+        match line.last_mut() {
+            Some((last_kind, text)) if *last_kind == kind => text.push_str(piece),
+            _ => line.push((kind, piece.to_string())),
+        }
+    }
+}
+
+/// `xml` with each element on a line of its own, indented two spaces a level. Elements
+/// holding only text stay on one line. `None` unless `xml` is well formed
+fn pretty_xml(xml: &str) -> Option<String> {
+    let mut reader = quick_xml::Reader::from_str(xml);
+    let mut writer = quick_xml::Writer::new_with_indent(Vec::new(), b' ', 2);
+    // Text arrives in pieces around entities like `&amp;`, and is written as one
+    let mut text = String::new();
+    let mut depth = 0usize;
+    let mut has_element = false;
+
+    loop {
+        let event = reader.read_event().ok()?;
+        match &event {
+            Event::Text(piece) => {
+                text.push_str(std::str::from_utf8(piece).ok()?);
+                continue;
+            }
+            Event::GeneralRef(reference) => {
+                text.push('&');
+                text.push_str(std::str::from_utf8(reference).ok()?);
+                text.push(';');
+                continue;
+            }
+            _ => {}
+        }
+
+        if !text.trim().is_empty() {
+            writer
+                .write_event(Event::Text(BytesText::from_escaped(text.trim())))
+                .ok()?;
+        }
+        text.clear();
+        match &event {
+            Event::Start(_) => {
+                depth += 1;
+                has_element = true;
+            }
+            Event::End(_) => depth = depth.checked_sub(1)?,
+            Event::Empty(_) => has_element = true,
+            Event::Eof => break,
+            _ => {}
+        }
+        writer.write_event(event).ok()?;
+    }
+
+    if depth > 0 || !has_element {
+        return None;
+    }
+    String::from_utf8(writer.into_inner()).ok()
+}
+
+fn json_tokens(text: &str) -> Vec<(TokenKind, Range<usize>)> {
+    let bytes = text.as_bytes();
+    let mut tokens = Vec::new();
+    let mut index = 0;
+    while index < bytes.len() {
+        let start = index;
+        let kind = match bytes[index] {
+            b'"' => {
+                index = string_end(bytes, index);
+                let rest = text[index..].trim_start();
+                if rest.starts_with(':') {
+                    TokenKind::Key
+                } else {
+                    TokenKind::String
+                }
+            }
+            b'-' | b'0'..=b'9' => {
+                index = scan_while(bytes, index, |byte| {
+                    byte.is_ascii_digit() || matches!(byte, b'-' | b'+' | b'.' | b'e' | b'E')
+                });
+                TokenKind::Number
+            }
+            byte if byte.is_ascii_alphabetic() => {
+                index = scan_while(bytes, index, |byte| byte.is_ascii_alphabetic());
+                TokenKind::Keyword
+            }
+            b'{' | b'}' | b'[' | b']' | b',' | b':' => {
+                index += 1;
+                TokenKind::Punctuation
+            }
+            _ => {
+                index = scan_while(bytes, index + 1, |byte| {
+                    !matches!(byte, b'"' | b'-' | b'{' | b'}' | b'[' | b']' | b',' | b':')
+                        && !byte.is_ascii_alphanumeric()
+                });
+                TokenKind::Plain
+            }
+        };
+        tokens.push((kind, start..index));
+    }
+    tokens
+}
+
+fn xml_tokens(text: &str) -> Vec<(TokenKind, Range<usize>)> {
+    let bytes = text.as_bytes();
+    let mut tokens = Vec::new();
+    let mut index = 0;
+    while index < bytes.len() {
+        let rest = &text[index..];
+        if rest.starts_with("<!--") {
+            let end = rest.find("-->").map_or(text.len(), |end| index + end + 3);
+            tokens.push((TokenKind::Comment, index..end));
+            index = end;
+        } else if rest.starts_with("<![CDATA[") {
+            let end = rest.find("]]>").map_or(text.len(), |end| index + end + 3);
+            tokens.push((TokenKind::String, index..end));
+            index = end;
+        } else if rest.starts_with('<') {
+            index = tag_tokens(text, index, &mut tokens);
+        } else {
+            let end = rest.find('<').map_or(text.len(), |end| index + end);
+            tokens.push((TokenKind::Plain, index..end));
+            index = end;
+        }
+    }
+    tokens
+}
+
+/// Reads the tag starting at `start`, returning where it ends
+fn tag_tokens(text: &str, start: usize, tokens: &mut Vec<(TokenKind, Range<usize>)>) -> usize {
+    let bytes = text.as_bytes();
+    let is_name = |byte: u8| !byte.is_ascii_whitespace() && !b"<>/=?\"'".contains(&byte);
+
+    let mut index = scan_while(bytes, start + 1, |byte| matches!(byte, b'/' | b'?' | b'!'));
+    tokens.push((TokenKind::Punctuation, start..index));
+    let name_start = index;
+    index = scan_while(bytes, index, is_name);
+    tokens.push((TokenKind::Tag, name_start..index));
+
+    while index < bytes.len() {
+        let from = index;
+        let kind = match bytes[index] {
+            b'>' => {
+                tokens.push((TokenKind::Punctuation, from..index + 1));
+                return index + 1;
+            }
+            b'"' | b'\'' => {
+                let quote = bytes[index];
+                index = bytes[index + 1..]
+                    .iter()
+                    .position(|&byte| byte == quote)
+                    .map_or(bytes.len(), |end| index + end + 2);
+                TokenKind::String
+            }
+            b'/' | b'?' | b'=' => {
+                index += 1;
+                TokenKind::Punctuation
+            }
+            byte if byte.is_ascii_whitespace() => {
+                index = scan_while(bytes, index, |byte| byte.is_ascii_whitespace());
+                TokenKind::Plain
+            }
+            _ => {
+                index = scan_while(bytes, index + 1, is_name);
+                TokenKind::Attribute
+            }
+        };
+        tokens.push((kind, from..index));
+    }
+    index
+}
+
+/// Where the JSON string opening at `start` ends, just past its closing quote
+fn string_end(bytes: &[u8], start: usize) -> usize {
+    let mut index = start + 1;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'\\' => index += 2,
+            b'"' => return index + 1,
+            _ => index += 1,
+        }
+    }
+    bytes.len()
+}
+
+fn scan_while(bytes: &[u8], start: usize, keep: impl Fn(u8) -> bool) -> usize {
+    bytes[start.min(bytes.len())..]
+        .iter()
+        .position(|&byte| !keep(byte))
+        .map_or(bytes.len(), |offset| start + offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Syntax, TokenKind};
+
+    #[test]
+    fn json_and_xml_are_indented_and_colored() {
+        let (syntax, json) =
+            Syntax::pretty_print(r#" {"id": 7, "ok": true, "tags": ["a"]} "#).unwrap();
+        assert_eq!(syntax, Syntax::Json);
+        let lines: Vec<&str> = json.lines().collect();
+        assert_eq!(lines[1], r#"  "id": 7,"#);
+        let highlighted = syntax.highlight(&lines);
+        assert_eq!(
+            highlighted[1],
+            vec![
+                (TokenKind::Plain, "  ".to_string()),
+                (TokenKind::Key, "\"id\"".to_string()),
+                (TokenKind::Punctuation, ":".to_string()),
+                (TokenKind::Plain, " ".to_string()),
+                (TokenKind::Number, "7".to_string()),
+                (TokenKind::Punctuation, ",".to_string()),
+            ]
+        );
+        assert_eq!(highlighted[2][4], (TokenKind::Keyword, "true".to_string()));
+        // A string wrapped onto the next line stays a string
+        let wrapped = Syntax::Json.highlight(&[r#"["a long"#, r#"  value"]"#]);
+        assert_eq!(wrapped[1][0], (TokenKind::String, "  value\"".to_string()));
+
+        let (syntax, xml) =
+            Syntax::pretty_print(r#"<order id="7"><item>Tea &amp; cake</item><paid/></order>"#)
+                .unwrap();
+        assert_eq!(syntax, Syntax::Xml);
+        assert_eq!(
+            xml,
+            "<order id=\"7\">\n  <item>Tea &amp; cake</item>\n  <paid/>\n</order>"
+        );
+        let lines: Vec<&str> = xml.lines().collect();
+        assert_eq!(
+            syntax.highlight(&lines)[0],
+            vec![
+                (TokenKind::Punctuation, "<".to_string()),
+                (TokenKind::Tag, "order".to_string()),
+                (TokenKind::Plain, " ".to_string()),
+                (TokenKind::Attribute, "id".to_string()),
+                (TokenKind::Punctuation, "=".to_string()),
+                (TokenKind::String, "\"7\"".to_string()),
+                (TokenKind::Punctuation, ">".to_string()),
+            ]
+        );
+
+        assert_eq!(Syntax::pretty_print("<a><b></a>"), None);
+        assert_eq!(Syntax::pretty_print("{not json}"), None);
+        assert_eq!(Syntax::pretty_print("plain text"), None);
+    }
+}
@@ -0,0 +1,741 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+use crate::actions::{ActionCommand, ActionType, MultiRowAction, RangeAction, RangeCellChange};
+use crate::app::AppState;
+use crate::excel::{Cell, CellGrid, Sheet, EXCEL_MAX_COLS, EXCEL_MAX_ROWS};
+use crate::utils::{cell_reference, index_to_col_name};
+
+/// Inclusive cell rectangle as ((start_row, start_col), (end_row, end_col)), 1-based
+pub type CellRange = ((usize, usize), (usize, usize));
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TextCase {
+    Upper,
+    Lower,
+    Title,
+}
+
+impl TextCase {
+    #[must_use]
+    pub fn apply(self, value: &str) -> String {
+        match self {
+            Self::Upper => value.to_uppercase(),
+            Self::Lower => value.to_lowercase(),
+            Self::Title => {
+                let mut result = String::with_capacity(value.len());
+                let mut word_start = true;
+                for c in value.chars() {
+                    if c.is_alphanumeric() {
+                        if word_start {
+                            result.extend(c.to_uppercase());
+                        } else {
+                            result.extend(c.to_lowercase());
+                        }
+                        word_start = false;
+                    } else {
+                        result.push(c);
+                        word_start = true;
+                    }
+                }
+                result
+            }
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Upper => "upper case",
+            Self::Lower => "lower case",
+            Self::Title => "title case",
+        }
+    }
+}
+
+impl AppState<'_> {
+    /// The rectangle from A1 to the last used row and column of the current sheet
+    pub fn used_range(&self) -> CellRange {
+        let sheet = self.workbook.get_current_sheet();
+        ((1, 1), (sheet.max_rows.max(1), sheet.max_cols.max(1)))
+    }
+
+    pub(crate) fn cell_at(&self, row: usize, col: usize) -> Cell {
+        self.workbook
+            .get_current_sheet()
+            .data
+            .cell(row, col)
+            .clone()
+    }
+
+    /// Records `changes` as one undoable action and writes the new values into the sheet.
+    /// Returns false when there was nothing to change.
+    pub(crate) fn commit_range_changes(
+        &mut self,
+        changes: Vec<RangeCellChange>,
+        action_type: ActionType,
+    ) -> bool {
+        if changes.is_empty() {
+            return false;
+        }
+
+        let range_action = RangeAction {
+            sheet_index: self.workbook.get_current_sheet_index(),
+            sheet_name: self.workbook.get_current_sheet_name(),
+            changes,
+            action_type,
+        };
+
+        self.apply_range_changes(&range_action, false);
+        self.undo_history.push(ActionCommand::Range(range_action));
+
+        self.workbook.recalculate_max_rows();
+        self.workbook.recalculate_max_cols();
+        self.ensure_column_widths();
+        self.update_row_number_width();
+        self.search_results.clear();
+        self.current_search_idx = None;
+        true
+    }
+
+    /// Rewrites the text of every non-formula cell in `range` with `map`, recording the changed
+    /// cells as one undoable action. Returns how many cells changed.
+    pub(crate) fn map_cell_values(
+        &mut self,
+        range: CellRange,
+        action_type: ActionType,
+        map: impl Fn(&str) -> String,
+    ) -> usize {
+        let changes = self.value_changes(range, map);
+        let changed = changes.len();
+        self.commit_range_changes(changes, action_type);
+        changed
+    }
+
+    /// The changes `map_cell_values` would make, without making them
+    pub(crate) fn value_changes(
+        &self,
+        range: CellRange,
+        map: impl Fn(&str) -> String,
+    ) -> Vec<RangeCellChange> {
+        let ((start_row, start_col), (end_row, end_col)) = range;
+        let sheet = self.workbook.get_current_sheet();
+
+        let mut changes = Vec::new();
+        for ((row, col), cell) in sheet
+            .data
+            .cells_in((start_row, start_col), (end_row, end_col))
+        {
+            if cell.is_formula || cell.value.is_empty() {
+                continue;
+            }
+
+            let value = map(&cell.value);
+            if value != cell.value {
+                let mut new_value = Cell::new(value, false);
+                new_value.inherit_annotations(cell);
+                changes.push(RangeCellChange {
+                    row,
+                    col,
+                    old_value: cell.clone(),
+                    new_value,
+                });
+            }
+        }
+        changes
+    }
+
+    /// Strips leading and trailing whitespace and collapses internal whitespace runs to one
+    /// space, in one column or in the whole used range
+    pub fn trim_cells(&mut self, col: Option<usize>) {
+        let ((start_row, start_col), (end_row, end_col)) = self.used_range();
+        let (range, scope) = match col {
+            Some(col) => (
+                ((start_row, col), (end_row, col)),
+                format!("column {}", index_to_col_name(col)),
+            ),
+            None => (
+                ((start_row, start_col), (end_row, end_col)),
+                "sheet".to_string(),
+            ),
+        };
+        if self.refuse_locked_range(range.0, range.1) {
+            return;
+        }
+
+        let changed = self.map_cell_values(range, ActionType::Trim, collapse_whitespace);
+        if changed == 0 {
+            self.add_notification(format!("No whitespace to trim in {scope}"));
+        } else {
+            let noun = if changed == 1 { "cell" } else { "cells" };
+            self.add_notification(format!("Trimmed {changed} {noun} in {scope}"));
+        }
+    }
+
+    /// Converts the case of the visual selection, or of the current column when nothing is
+    /// selected, as one undoable action
+    pub fn change_case(&mut self, case: TextCase) {
+        let (start, end) = self.take_selection_or_current_column();
+        let range_text = format!("{}:{}", cell_reference(start), cell_reference(end));
+        if self.refuse_locked_range(start, end) {
+            return;
+        }
+
+        let changed = self.map_cell_values((start, end), ActionType::ChangeCase, |value| {
+            case.apply(value)
+        });
+        if changed == 0 {
+            self.add_notification(format!("No cells to change in {range_text}"));
+        } else {
+            let noun = if changed == 1 { "cell" } else { "cells" };
+            self.add_notification(format!(
+                "Converted {changed} {noun} in {range_text} to {}",
+                case.name()
+            ));
+        }
+    }
+
+    /// Joins the displayed text of columns `start_col..=end_col` on each row with `separator`,
+    /// skipping blank cells. The result replaces the columns, leaving one column at
+    /// `start_col`, or is appended after the last used column. Undone in one step.
+    pub fn join_columns(
+        &mut self,
+        start_col: usize,
+        end_col: usize,
+        separator: &str,
+        append: bool,
+    ) {
+        let (start_col, end_col) = (start_col.min(end_col), start_col.max(end_col));
+        let span = format!(
+            "{}:{}",
+            index_to_col_name(start_col),
+            index_to_col_name(end_col)
+        );
+        if start_col == end_col {
+            self.add_warning(format!(
+                "Nothing to join in {span}: give at least two columns"
+            ));
+            return;
+        }
+
+        let max_rows = self.workbook.get_current_sheet().max_rows;
+        let target_col = if append {
+            self.workbook.get_current_sheet().max_cols + 1
+        } else {
+            start_col
+        };
+        if target_col > EXCEL_MAX_COLS {
+            self.add_error("Cannot join: no free column left".to_string());
+            return;
+        }
+        let refused = if append {
+            self.refuse_locked_range((1, target_col), (max_rows.max(1), target_col))
+        } else {
+            self.refuse_on_protected_sheet("join columns")
+        };
+        if refused {
+            return;
+        }
+
+        let mut changes = Vec::new();
+        for row in 1..=max_rows {
+            let joined = (start_col..=end_col)
+                .map(|col| self.displayed_value(row, col))
+                .filter(|text| !text.is_empty())
+                .collect::<Vec<_>>()
+                .join(separator);
+
+            let old_value = self.cell_at(row, target_col);
+            if old_value.value == joined && !old_value.is_formula {
+                continue;
+            }
+            let mut new_value = Cell::new(joined, false);
+            new_value.inherit_annotations(&old_value);
+            changes.push(RangeCellChange {
+                row,
+                col: target_col,
+                old_value,
+                new_value,
+            });
+        }
+
+        self.undo_history.begin_group();
+        self.commit_range_changes(changes, ActionType::JoinColumns);
+        if !append {
+            if let Err(e) = self.delete_columns(start_col + 1, end_col) {
+                self.add_error(format!("Join failed: {e}"));
+            }
+        }
+        self.undo_history.end_group();
+
+        self.selected_cell = (self.selected_cell.0.max(1), target_col);
+        self.handle_scrolling();
+        self.add_notification(format!(
+            "Joined columns {span} into column {}",
+            index_to_col_name(target_col)
+        ));
+    }
+
+    /// Swaps rows and columns of `range` (or the visual selection, or the used range), either in place anchored at the
+    /// range's top-left cell or into a new sheet inserted after the current one
+    pub fn transpose(&mut self, range: Option<CellRange>, to_new_sheet: bool) {
+        let range = range.or_else(|| self.selection_range());
+        self.clear_selection();
+        let ((start_row, start_col), (end_row, end_col)) =
+            range.unwrap_or_else(|| self.used_range());
+        let height = end_row - start_row + 1;
+        let width = end_col - start_col + 1;
+        let source_text = format!(
+            "{}:{}",
+            cell_reference((start_row, start_col)),
+            cell_reference((end_row, end_col))
+        );
+
+        if to_new_sheet {
+            if width > EXCEL_MAX_ROWS || height > EXCEL_MAX_COLS {
+                self.add_error(format!(
+                    "Cannot transpose {source_text}: result exceeds Excel limits"
+                ));
+                return;
+            }
+
+            let mut data = CellGrid::new();
+            for (row_offset, row) in (start_row..=end_row).enumerate() {
+                for (col_offset, col) in (start_col..=end_col).enumerate() {
+                    data.set(col_offset + 1, row_offset + 1, self.cell_at(row, col));
+                }
+            }
+
+            let base_name = format!("{} T", self.workbook.get_current_sheet_name());
+            let mut sheet = Sheet::blank(self.workbook.unique_sheet_name(&base_name));
+            sheet.data = data;
+            sheet.max_rows = width;
+            sheet.max_cols = height;
+
+            if let Some(sheet_name) = self.insert_new_sheet(sheet) {
+                self.workbook.recalculate_max_rows();
+                self.workbook.recalculate_max_cols();
+                self.retract_notification();
+                self.add_notification(format!("Transposed {source_text} into sheet {sheet_name}"));
+            }
+            return;
+        }
+
+        if start_row + width - 1 > EXCEL_MAX_ROWS || start_col + height - 1 > EXCEL_MAX_COLS {
+            self.add_error(format!(
+                "Cannot transpose {source_text}: result exceeds Excel limits"
+            ));
+            return;
+        }
+
+        let span = height.max(width);
+        if self.refuse_locked_range(
+            (start_row, start_col),
+            (start_row + span - 1, start_col + span - 1),
+        ) {
+            return;
+        }
+
+        // Only the union of the source block and its transposed footprint changes
+        let mut changes = Vec::with_capacity(height * width * 2);
+        for row_offset in 0..span {
+            for col_offset in 0..span {
+                let in_source = row_offset < height && col_offset < width;
+                let in_result = row_offset < width && col_offset < height;
+                if !in_source && !in_result {
+                    continue;
+                }
+
+                let row = start_row + row_offset;
+                let col = start_col + col_offset;
+                let new_value = if in_result {
+                    self.cell_at(start_row + col_offset, start_col + row_offset)
+                } else {
+                    Cell::empty()
+                };
+
+                changes.push(RangeCellChange {
+                    row,
+                    col,
+                    old_value: self.cell_at(row, col),
+                    new_value,
+                });
+            }
+        }
+
+        self.commit_range_changes(changes, ActionType::Transpose);
+        self.selected_cell = (start_row, start_col);
+        self.handle_scrolling();
+        self.add_notification(format!(
+            "Transposed {source_text} to {}:{}",
+            cell_reference((start_row, start_col)),
+            cell_reference((start_row + width - 1, start_col + height - 1))
+        ));
+    }
+
+    /// Rows whose values in `key_cols` (or all used columns) repeat an earlier row, each paired
+    /// with the row it repeats. Values are grouped according to the current sort mode and rows
+    /// with blank keys are skipped.
+    pub(crate) fn find_duplicate_rows(&self, key_cols: Option<Vec<usize>>) -> Vec<(usize, usize)> {
+        let sheet = self.workbook.get_current_sheet();
+        let key_cols = key_cols.unwrap_or_else(|| (1..=sheet.max_cols).collect());
+
+        let mut first_rows = HashMap::new();
+        let mut duplicates = Vec::new();
+        for row in 1..=sheet.max_rows {
+            let values: Vec<&str> = key_cols
+                .iter()
+                .map(|&col| sheet.data.cell(row, col).value.as_str())
+                .collect();
+
+            if values.iter().all(|value| value.is_empty()) {
+                continue;
+            }
+
+            let key: Vec<String> = values
+                .iter()
+                .map(|value| self.sort_mode.group_key(value))
+                .collect();
+            match first_rows.entry(key) {
+                Entry::Occupied(first) => duplicates.push((row, *first.get())),
+                Entry::Vacant(slot) => {
+                    slot.insert(row);
+                }
+            }
+        }
+
+        duplicates
+    }
+
+    /// Deletes every row found by [`Self::find_duplicate_rows`] as one undoable action
+    pub fn dedupe_rows(&mut self, key_cols: Option<Vec<usize>>) {
+        if self.refuse_on_protected_sheet("delete rows") {
+            return;
+        }
+        let duplicate_rows: Vec<usize> = self
+            .find_duplicate_rows(key_cols)
+            .into_iter()
+            .map(|(row, _)| row)
+            .collect();
+        let sheet = self.workbook.get_current_sheet();
+
+        let (Some(&start_row), Some(&end_row)) = (duplicate_rows.first(), duplicate_rows.last())
+        else {
+            self.add_notification("No duplicate rows found".to_string());
+            return;
+        };
+
+        let rows_data = duplicate_rows
+            .iter()
+            .map(|&row| sheet.data.row_cells(row))
+            .collect();
+        let removed = duplicate_rows.len();
+
+        let multi_row_action = MultiRowAction {
+            sheet_index: self.workbook.get_current_sheet_index(),
+            sheet_name: self.workbook.get_current_sheet_name(),
+            start_row,
+            end_row,
+            rows: duplicate_rows,
+            rows_data,
+        };
+
+        if let Err(e) = self.workbook.delete_row_set(&multi_row_action.rows) {
+            self.add_error(format!("Failed to remove duplicate rows: {e}"));
+            return;
+        }
+        self.undo_history
+            .push(ActionCommand::MultiRow(multi_row_action));
+
+        self.workbook.recalculate_max_rows();
+        self.workbook.recalculate_max_cols();
+        self.clamp_selected_cell_to_excel_bounds();
+        self.update_row_number_width();
+        self.handle_scrolling();
+        self.search_results.clear();
+        self.current_search_idx = None;
+
+        let noun = if removed == 1 { "row" } else { "rows" };
+        self.add_notification(format!("Removed {removed} duplicate {noun}"));
+    }
+}
+
+fn collapse_whitespace(value: &str) -> String {
+    value.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::app::{AppState, TextCase};
+    use crate::excel::{
+        Cell, CellGrid, ColumnFormats, FreezePanes, Sheet, SheetProtection, SheetVisibility,
+        Workbook,
+    };
+    use crate::utils::SortMode;
+    use std::path::PathBuf;
+
+    fn app_with_grid(rows: usize, cols: usize) -> AppState<'static> {
+        let mut data = CellGrid::new();
+        for row in 1..=rows {
+            for col in 1..=cols {
+                data.set(row, col, Cell::new(format!("R{row}C{col}"), false));
+            }
+        }
+
+        let sheet = Sheet {
+            name: "Data".to_string(),
+            data,
+            max_rows: rows,
+            max_cols: cols,
+            is_loaded: true,
+            freeze_panes: FreezePanes::none(),
+            column_formats: ColumnFormats::new(),
+            protection: SheetProtection::Unprotected,
+            visibility: SheetVisibility::Visible,
+            tab_color: None,
+            conditional_formats: Vec::new(),
+        };
+
+        AppState::new(
+            Workbook::from_sheets_for_test(vec![sheet]),
+            PathBuf::from("test.xlsx"),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn transpose_whole_sheet_swaps_dimensions_and_undoes_in_one_step() {
+        let mut app = app_with_grid(3, 2);
+
+        app.input_buffer = "transpose".to_string();
+        app.execute_command();
+
+        let sheet = app.workbook.get_current_sheet();
+        assert_eq!((sheet.max_rows, sheet.max_cols), (2, 3));
+        assert_eq!(app.get_cell_content(1, 3), "R3C1");
+        assert_eq!(app.get_cell_content(2, 1), "R1C2");
+        assert_eq!(app.get_cell_content(3, 1), "");
+
+        app.undo().unwrap();
+
+        let sheet = app.workbook.get_current_sheet();
+        assert_eq!((sheet.max_rows, sheet.max_cols), (3, 2));
+        assert_eq!(app.get_cell_content(3, 1), "R3C1");
+        assert_eq!(app.get_cell_content(1, 3), "");
+        assert!(!app.workbook.is_modified());
+    }
+
+    #[test]
+    fn transpose_range_leaves_cells_outside_the_footprint_untouched() {
+        let mut app = app_with_grid(3, 3);
+
+        app.input_buffer = "transpose A1:B2".to_string();
+        app.execute_command();
+
+        assert_eq!(app.get_cell_content(1, 2), "R2C1");
+        assert_eq!(app.get_cell_content(2, 1), "R1C2");
+        assert_eq!(app.get_cell_content(3, 3), "R3C3");
+        assert_eq!(app.get_cell_content(1, 3), "R1C3");
+    }
+
+    #[test]
+    fn dedupe_removes_repeated_keys_and_undoes_in_one_step() {
+        let mut app = app_with_grid(5, 2);
+        app.workbook
+            .set_cell_value(3, 1, "R1C1".to_string())
+            .unwrap();
+        app.workbook
+            .set_cell_value(5, 1, "R1C1".to_string())
+            .unwrap();
+        app.workbook
+            .set_cell_value(5, 2, "other".to_string())
+            .unwrap();
+
+        app.input_buffer = "dedupe A".to_string();
+        app.execute_command();
+
+        assert_eq!(app.workbook.get_current_sheet().max_rows, 3);
+        assert_eq!(app.get_cell_content(2, 1), "R2C1");
+        assert_eq!(app.get_cell_content(3, 1), "R4C1");
+        assert_eq!(
+            app.notification_messages.last().unwrap().text,
+            "Removed 2 duplicate rows"
+        );
+
+        app.undo().unwrap();
+
+        assert_eq!(app.workbook.get_current_sheet().max_rows, 5);
+        assert_eq!(app.get_cell_content(3, 2), "R3C2");
+        assert_eq!(app.get_cell_content(5, 2), "other");
+
+        app.redo().unwrap();
+        assert_eq!(app.workbook.get_current_sheet().max_rows, 3);
+        assert_eq!(app.get_cell_content(3, 2), "R4C2");
+    }
+
+    #[test]
+    fn dedupe_groups_values_by_natural_sort_mode() {
+        let mut app = app_with_grid(2, 1);
+        app.workbook
+            .set_cell_value(1, 1, "SKU-7".to_string())
+            .unwrap();
+        app.workbook
+            .set_cell_value(2, 1, "sku-007".to_string())
+            .unwrap();
+
+        app.input_buffer = "dedupe".to_string();
+        app.execute_command();
+        assert_eq!(app.workbook.get_current_sheet().max_rows, 2);
+
+        app.sort_mode = SortMode::Natural;
+        app.input_buffer = "dedupe".to_string();
+        app.execute_command();
+        assert_eq!(app.workbook.get_current_sheet().max_rows, 1);
+    }
+
+    #[test]
+    fn trim_collapses_whitespace_in_one_column_and_undoes_in_one_step() {
+        let mut app = app_with_grid(3, 2);
+        app.workbook
+            .set_cell_value(1, 1, "  Ada \t Lovelace ".to_string())
+            .unwrap();
+        app.workbook
+            .set_cell_value(2, 1, " 42 ".to_string())
+            .unwrap();
+        app.workbook
+            .set_cell_value(2, 2, "  keep  ".to_string())
+            .unwrap();
+
+        app.input_buffer = "trim A".to_string();
+        app.execute_command();
+
+        assert_eq!(app.get_cell_content(1, 1), "Ada Lovelace");
+        assert_eq!(app.get_cell_content(2, 1), "42");
+        assert_eq!(app.get_cell_content(2, 2), "  keep  ");
+        assert_eq!(
+            app.notification_messages.last().unwrap().text,
+            "Trimmed 2 cells in column A"
+        );
+
+        app.undo().unwrap();
+        assert_eq!(app.get_cell_content(1, 1), "  Ada \t Lovelace ");
+        assert_eq!(app.get_cell_content(2, 1), " 42 ");
+
+        app.input_buffer = "trim all".to_string();
+        app.execute_command();
+        assert_eq!(app.get_cell_content(2, 2), "keep");
+    }
+
+    #[test]
+    fn case_commands_use_visual_selection_or_current_column() {
+        let mut app = app_with_grid(3, 2);
+        app.workbook
+            .set_cell_value(1, 2, "mary o'neil-smith".to_string())
+            .unwrap();
+
+        app.selected_cell = (1, 2);
+        app.input_buffer = "title".to_string();
+        app.execute_command();
+        assert_eq!(app.get_cell_content(1, 2), "Mary O'Neil-Smith");
+        assert_eq!(app.get_cell_content(3, 2), "R3c2");
+
+        app.selected_cell = (2, 1);
+        app.toggle_visual_selection();
+        app.selected_cell = (3, 2);
+        app.input_buffer = "lower".to_string();
+        app.execute_command();
+
+        assert_eq!(app.selection_anchor, None);
+        assert_eq!(app.get_cell_content(1, 1), "R1C1");
+        assert_eq!(app.get_cell_content(2, 1), "r2c1");
+        assert_eq!(app.get_cell_content(3, 2), "r3c2");
+
+        app.undo().unwrap();
+        assert_eq!(app.get_cell_content(2, 1), "R2C1");
+        assert_eq!(app.get_cell_content(3, 2), "R3c2");
+    }
+
+    #[test]
+    fn transpose_into_new_sheet_keeps_source_and_is_undoable() {
+        let mut app = app_with_grid(3, 2);
+
+        app.input_buffer = "transpose new".to_string();
+        app.execute_command();
+
+        assert_eq!(app.workbook.get_sheet_names(), vec!["Data", "Data T"]);
+        assert_eq!(app.workbook.get_current_sheet_name(), "Data T");
+        assert_eq!(app.get_cell_content(2, 3), "R3C2");
+
+        app.undo().unwrap();
+        assert_eq!(app.workbook.get_sheet_names(), vec!["Data"]);
+        assert_eq!(app.get_cell_content(3, 2), "R3C2");
+    }
+
+    #[test]
+    fn grouped_actions_undo_and_redo_as_one_step() {
+        let mut app = app_with_grid(2, 2);
+        app.selected_cell = (1, 1);
+
+        app.undo_history.begin_group();
+        app.change_case(TextCase::Lower);
+        app.undo_history.begin_group();
+        app.commit_column_widths(&[(1, 30)]);
+        app.undo_history.end_group();
+        app.change_case(TextCase::Title);
+        app.undo_history.end_group();
+        assert_eq!(app.get_cell_content(2, 1), "R2c1");
+        assert_eq!(app.column_widths[1], 30);
+
+        app.undo().unwrap();
+        assert_eq!(app.get_cell_content(2, 1), "R2C1");
+        assert_ne!(app.column_widths[1], 30);
+        assert_eq!(
+            app.notification_messages.last().unwrap().text,
+            "Undid 3 changes as one step"
+        );
+        assert!(app.undo_history.all_undone());
+
+        app.redo().unwrap();
+        assert_eq!(app.get_cell_content(2, 1), "R2c1");
+        assert_eq!(app.column_widths[1], 30);
+    }
+
+    #[test]
+    fn join_columns_replaces_the_span_or_appends_a_new_column() {
+        let mut app = app_with_grid(2, 3);
+        app.workbook
+            .get_current_sheet_mut()
+            .data
+            .set(2, 2, Cell::empty());
+
+        app.input_buffer = "joincols A B \" / \" new".to_string();
+        app.execute_command();
+        assert_eq!(app.get_cell_content(1, 4), "R1C1 / R1C2");
+        assert_eq!(app.get_cell_content(2, 4), "R2C1");
+
+        app.input_buffer = "joincols A C \"-\"".to_string();
+        app.execute_command();
+        assert_eq!(app.get_cell_content(1, 1), "R1C1-R1C2-R1C3");
+        assert_eq!(app.get_cell_content(2, 1), "R2C1-R2C3");
+        assert_eq!(app.get_cell_content(1, 2), "R1C1 / R1C2");
+        assert_eq!(app.workbook.get_current_sheet().max_cols, 2);
+
+        app.undo().unwrap();
+        assert_eq!(app.get_cell_content(1, 1), "R1C1");
+        assert_eq!(app.get_cell_content(1, 3), "R1C3");
+        assert_eq!(app.get_cell_content(1, 4), "R1C1 / R1C2");
+    }
+
+    #[test]
+    fn join_columns_writes_what_formulas_evaluate_to() {
+        let mut app = app_with_grid(1, 2);
+        let mut formula = Cell::new("42".to_string(), true);
+        formula.formula = Some("=40+2".to_string());
+        app.workbook.get_current_sheet_mut().data.set(1, 2, formula);
+
+        app.input_buffer = "joincols A B \"-\"".to_string();
+        app.execute_command();
+        let joined = app.workbook.get_current_sheet().data.cell(1, 1);
+        assert_eq!(joined.value, "R1C1-42");
+        assert!(!joined.is_formula);
+    }
+}
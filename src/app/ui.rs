@@ -4,7 +4,8 @@ use crate::app::InputMode;
 impl AppState<'_> {
     pub fn show_help(&mut self) {
         self.help_scroll = 0;
-        self.help_text = crate::app::help_reference_text();
+        self.help_filter.clear();
+        self.help_filter_typing = false;
         self.help_total_lines = crate::app::help_reference_line_count();
 
         self.input_mode = InputMode::Help;
@@ -24,7 +25,7 @@ impl AppState<'_> {
                 self.should_quit = true;
             }
             Err(e) => {
-                self.add_notification(format!("Save failed: {e}"));
+                self.add_error(format!("Save failed: {e}"));
                 self.input_mode = InputMode::Normal;
             }
         }
@@ -42,7 +43,7 @@ impl AppState<'_> {
                 self.add_notification("File saved".to_string());
             }
             Err(e) => {
-                self.add_notification(format!("Save failed: {e}"));
+                self.add_error(format!("Save failed: {e}"));
             }
         }
         Ok(())
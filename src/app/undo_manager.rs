@@ -1,11 +1,11 @@
 use crate::actions::{
-    ActionCommand, ActionExecutor, ActionType, CellAction, ColumnAction, MultiColumnAction,
-    MultiRowAction, RowAction, SheetAction, SheetOperation,
+    ActionCommand, ActionExecutor, ActionType, CellAction, ColumnAction, ColumnWidthAction,
+    MultiColumnAction, MultiRowAction, RangeAction, RowAction, SheetAction, SheetOperation,
+    SheetRenameAction,
 };
 use crate::app::AppState;
 use crate::utils::index_to_col_name;
 use anyhow::Result;
-use std::rc::Rc;
 
 impl AppState<'_> {
     pub fn undo(&mut self) -> Result<()> {
@@ -46,8 +46,8 @@ impl AppState<'_> {
         Ok(())
     }
 
-    fn apply_action(&mut self, action: &Rc<ActionCommand>, is_undo: bool) -> Result<()> {
-        match action.as_ref() {
+    fn apply_action(&mut self, action: &ActionCommand, is_undo: bool) -> Result<()> {
+        match action {
             ActionCommand::Cell(cell_action) => {
                 let value = if is_undo {
                     &cell_action.old_value
@@ -65,16 +65,60 @@ impl AppState<'_> {
             ActionCommand::Sheet(sheet_action) => {
                 self.apply_sheet_action(sheet_action, is_undo)?;
             }
+            ActionCommand::RenameSheet(rename_action) => {
+                self.apply_sheet_rename_action(rename_action, is_undo)?;
+            }
             ActionCommand::MultiRow(multi_row_action) => {
                 self.apply_multi_row_action(multi_row_action, is_undo)?;
             }
             ActionCommand::MultiColumn(multi_column_action) => {
                 self.apply_multi_column_action(multi_column_action, is_undo)?;
             }
+            ActionCommand::Range(range_action) => {
+                self.apply_range_action(range_action, is_undo)?;
+            }
+            ActionCommand::ColumnWidth(width_action) => {
+                self.apply_column_width_action(width_action, is_undo)?;
+            }
+            ActionCommand::InsertRow(row_action) => {
+                self.apply_insert_row_action(row_action, is_undo)?;
+            }
+            ActionCommand::InsertColumn(column_action) => {
+                self.apply_insert_column_action(column_action, is_undo)?;
+            }
+            ActionCommand::Group(actions) => {
+                self.apply_action_group(actions, is_undo)?;
+            }
         }
         Ok(())
     }
 
+    /// Applies grouped actions newest-first when undoing, and reports them as one step
+    fn apply_action_group(&mut self, actions: &[ActionCommand], is_undo: bool) -> Result<()> {
+        let notifications = std::mem::take(&mut self.notification_messages);
+        let message_log = std::mem::take(&mut self.message_log);
+        let result = if is_undo {
+            actions
+                .iter()
+                .rev()
+                .try_for_each(|action| self.apply_action(action, true))
+        } else {
+            actions
+                .iter()
+                .try_for_each(|action| self.apply_action(action, false))
+        };
+        self.notification_messages = notifications;
+        self.message_log = message_log;
+        result?;
+
+        let action_word = if is_undo { "Undid" } else { "Redid" };
+        self.add_notification(format!(
+            "{action_word} {} changes as one step",
+            actions.len()
+        ));
+        Ok(())
+    }
+
     fn apply_cell_action(
         &mut self,
         cell_action: &CellAction,
@@ -86,7 +130,7 @@ impl AppState<'_> {
 
         if current_sheet_index != cell_action.sheet_index {
             if let Err(e) = self.switch_sheet_by_index(cell_action.sheet_index) {
-                self.add_notification(format!(
+                self.add_error(format!(
                     "Cannot switch to sheet {}: {}",
                     cell_action.sheet_name, e
                 ));
@@ -94,8 +138,11 @@ impl AppState<'_> {
             }
         }
 
-        self.workbook.get_current_sheet_mut().data[cell_action.row][cell_action.col] =
-            value.clone();
+        self.workbook.get_current_sheet_mut().data.set(
+            cell_action.row,
+            cell_action.col,
+            value.clone(),
+        );
 
         self.selected_cell = (cell_action.row, cell_action.col);
         self.handle_scrolling();
@@ -110,6 +157,7 @@ impl AppState<'_> {
             ActionType::Edit => "edit",
             ActionType::Cut => "cut",
             ActionType::Paste => "paste",
+            ActionType::Comment => "comment",
             _ => "cell operation",
         };
 
@@ -135,7 +183,7 @@ impl AppState<'_> {
 
         if current_sheet_index != row_action.sheet_index {
             if let Err(e) = self.switch_sheet_by_index(row_action.sheet_index) {
-                self.add_notification(format!(
+                self.add_error(format!(
                     "Cannot switch to sheet {}: {}",
                     row_action.sheet_name, e
                 ));
@@ -148,7 +196,7 @@ impl AppState<'_> {
         if is_undo {
             sheet
                 .data
-                .insert(row_action.row, row_action.row_data.clone());
+                .insert_row(row_action.row, row_action.row_data.clone());
 
             sheet.max_rows = sheet.max_rows.saturating_add(1);
 
@@ -157,8 +205,8 @@ impl AppState<'_> {
             self.workbook.recalculate_max_cols();
 
             self.add_notification(format!("Undid row {} deletion", row_action.row));
-        } else if row_action.row < sheet.data.len() {
-            sheet.data.remove(row_action.row);
+        } else if row_action.row <= sheet.max_rows {
+            sheet.data.remove_row(row_action.row);
             sheet.max_rows = sheet.max_rows.saturating_sub(1);
 
             self.clamp_selected_cell_to_excel_bounds();
@@ -173,12 +221,83 @@ impl AppState<'_> {
         Ok(())
     }
 
+    fn apply_insert_row_action(&mut self, row_action: &RowAction, is_undo: bool) -> Result<()> {
+        if self.workbook.get_current_sheet_index() != row_action.sheet_index {
+            if let Err(e) = self.switch_sheet_by_index(row_action.sheet_index) {
+                self.add_error(format!(
+                    "Cannot switch to sheet {}: {}",
+                    row_action.sheet_name, e
+                ));
+                return Ok(());
+            }
+        }
+
+        if is_undo {
+            self.workbook.delete_row(row_action.row)?;
+            self.workbook.recalculate_max_rows();
+            self.add_notification(format!("Undid row {} insertion", row_action.row));
+        } else {
+            self.workbook
+                .insert_row(row_action.row, row_action.row_data.clone());
+            self.selected_cell.0 = row_action.row;
+            self.add_notification(format!("Redid row {} insertion", row_action.row));
+        }
+
+        self.update_row_number_width();
+        self.handle_scrolling();
+        self.search_results.clear();
+        self.current_search_idx = None;
+        Ok(())
+    }
+
+    fn apply_insert_column_action(
+        &mut self,
+        column_action: &ColumnAction,
+        is_undo: bool,
+    ) -> Result<()> {
+        if self.workbook.get_current_sheet_index() != column_action.sheet_index {
+            if let Err(e) = self.switch_sheet_by_index(column_action.sheet_index) {
+                self.add_error(format!(
+                    "Cannot switch to sheet {}: {}",
+                    column_action.sheet_name, e
+                ));
+                return Ok(());
+            }
+        }
+
+        let col = column_action.col;
+        let col_name = index_to_col_name(col);
+        if is_undo {
+            self.workbook.delete_column(col)?;
+            self.workbook.recalculate_max_cols();
+            if col < self.column_widths.len() {
+                self.column_widths.remove(col);
+            }
+            self.add_notification(format!("Undid column {col_name} insertion"));
+        } else {
+            self.workbook.insert_column(col, &column_action.column_data);
+            if col < self.column_widths.len() {
+                self.column_widths.insert(col, column_action.column_width);
+            } else {
+                self.set_column_width(col, column_action.column_width);
+            }
+            self.selected_cell.1 = col;
+            self.add_notification(format!("Redid column {col_name} insertion"));
+        }
+
+        self.ensure_column_widths();
+        self.handle_scrolling();
+        self.search_results.clear();
+        self.current_search_idx = None;
+        Ok(())
+    }
+
     fn apply_column_action(&mut self, column_action: &ColumnAction, is_undo: bool) -> Result<()> {
         let current_sheet_index = self.workbook.get_current_sheet_index();
 
         if current_sheet_index != column_action.sheet_index {
             if let Err(e) = self.switch_sheet_by_index(column_action.sheet_index) {
-                self.add_notification(format!(
+                self.add_error(format!(
                     "Cannot switch to sheet {}: {}",
                     column_action.sheet_name, e
                 ));
@@ -190,20 +309,7 @@ impl AppState<'_> {
         let col = column_action.col;
 
         if is_undo {
-            let column_data = &column_action.column_data;
-
-            for (i, row) in sheet.data.iter_mut().enumerate() {
-                if i < column_data.len() {
-                    if col <= row.len() {
-                        row.insert(col, column_data[i].clone());
-                    } else {
-                        while row.len() < col {
-                            row.push(crate::excel::Cell::empty());
-                        }
-                        row.push(column_data[i].clone());
-                    }
-                }
-            }
+            sheet.data.insert_column(col, &column_action.column_data);
 
             // Update both max_cols and max_rows when restoring a column
             sheet.max_cols = sheet.max_cols.saturating_add(1);
@@ -227,11 +333,7 @@ impl AppState<'_> {
             self.ensure_column_visible(col);
             self.add_notification(format!("Undid column {} deletion", index_to_col_name(col)));
         } else {
-            for row in sheet.data.iter_mut() {
-                if col < row.len() {
-                    row.remove(col);
-                }
-            }
+            sheet.data.remove_columns(col, col);
 
             sheet.max_cols = sheet.max_cols.saturating_sub(1);
 
@@ -283,6 +385,24 @@ impl AppState<'_> {
         Ok(())
     }
 
+    fn apply_sheet_rename_action(
+        &mut self,
+        rename_action: &SheetRenameAction,
+        is_undo: bool,
+    ) -> Result<()> {
+        let (from, to) = if is_undo {
+            (&rename_action.new_name, &rename_action.old_name)
+        } else {
+            (&rename_action.old_name, &rename_action.new_name)
+        };
+        self.rename_sheet_at(rename_action.sheet_index, from, to)?;
+        self.switch_sheet_by_index(rename_action.sheet_index)?;
+
+        let action_word = if is_undo { "Undid" } else { "Redid" };
+        self.add_notification(format!("{action_word} sheet rename: {from} to {to}"));
+        Ok(())
+    }
+
     fn cleanup_after_sheet_deletion(&mut self, sheet_name: &str) {
         self.sheet_column_widths.remove(sheet_name);
         self.sheet_cell_positions.remove(sheet_name);
@@ -331,11 +451,11 @@ impl AppState<'_> {
     fn restore_sheet_from_action(&mut self, sheet_action: &SheetAction, notification: String) {
         let sheet_index = sheet_action.sheet_index;
 
-        if let Err(e) = self
-            .workbook
-            .insert_sheet_at_index(sheet_action.sheet_data.clone(), sheet_index)
+        if let Err(e) = sheet_action
+            .sheet()
+            .and_then(|sheet| self.workbook.insert_sheet_at_index(sheet, sheet_index))
         {
-            self.add_notification(format!(
+            self.add_error(format!(
                 "Failed to restore sheet {}: {}",
                 sheet_action.sheet_name, e
             ));
@@ -356,30 +476,30 @@ impl AppState<'_> {
         );
 
         if let Err(e) = self.switch_sheet_by_index(sheet_index) {
-            self.add_notification(format!(
+            self.add_error(format!(
                 "Restored sheet {} but couldn't switch to it: {}",
                 sheet_action.sheet_name, e
             ));
             return;
         }
 
-        self.notification_messages.pop();
+        self.retract_notification();
         self.add_notification(notification);
     }
 
     fn delete_sheet_from_action(&mut self, sheet_action: &SheetAction, notification: String) {
         if let Err(e) = self.switch_sheet_by_index(sheet_action.sheet_index) {
-            self.add_notification(format!(
+            self.add_error(format!(
                 "Cannot switch to sheet {} to delete it: {}",
                 sheet_action.sheet_name, e
             ));
             return;
         }
 
-        self.notification_messages.pop();
+        self.retract_notification();
 
         if let Err(e) = self.workbook.delete_current_sheet() {
-            self.add_notification(format!("Failed to delete sheet: {e}"));
+            self.add_error(format!("Failed to delete sheet: {e}"));
             return;
         }
 
@@ -396,7 +516,7 @@ impl AppState<'_> {
 
         if current_sheet_index != multi_row_action.sheet_index {
             if let Err(e) = self.switch_sheet_by_index(multi_row_action.sheet_index) {
-                self.add_notification(format!(
+                self.add_error(format!(
                     "Cannot switch to sheet {}: {}",
                     multi_row_action.sheet_name, e
                 ));
@@ -406,27 +526,43 @@ impl AppState<'_> {
 
         let start_row = multi_row_action.start_row;
         let end_row = multi_row_action.end_row;
-        let rows_to_restore = end_row - start_row + 1;
+        let contiguous = multi_row_action.is_contiguous();
+        let description = if contiguous {
+            format!("rows {start_row} to {end_row} deletion")
+        } else {
+            format!("deletion of {} rows", multi_row_action.rows.len())
+        };
 
         if is_undo {
             let rows_data = &multi_row_action.rows_data;
             let sheet = self.workbook.get_current_sheet_mut();
 
-            // Optimized restore function
-            Self::restore_rows(sheet, start_row, rows_data);
+            if contiguous {
+                // Optimized restore function
+                Self::restore_rows(sheet, start_row, rows_data);
+            } else {
+                // Ascending inserts land each row back at its original index
+                for (&row, row_data) in multi_row_action.rows.iter().zip(rows_data) {
+                    sheet.data.insert_row(row, row_data.clone());
+                }
+            }
 
-            sheet.max_rows = sheet.max_rows.saturating_add(rows_to_restore);
+            sheet.max_rows = sheet.max_rows.saturating_add(rows_data.len());
 
             // Recalculate max_cols since restoring rows might affect the maximum column count
             self.workbook.recalculate_max_cols();
 
-            self.add_notification(format!("Undid rows {} to {} deletion", start_row, end_row));
+            self.add_notification(format!("Undid {description}"));
         } else {
-            self.workbook.delete_rows(start_row, end_row)?;
+            if contiguous {
+                self.workbook.delete_rows(start_row, end_row)?;
+            } else {
+                self.workbook.delete_row_set(&multi_row_action.rows)?;
+            }
 
             self.clamp_selected_cell_to_excel_bounds();
 
-            self.add_notification(format!("Redid rows {} to {} deletion", start_row, end_row));
+            self.add_notification(format!("Redid {description}"));
         }
 
         self.handle_scrolling();
@@ -445,7 +581,7 @@ impl AppState<'_> {
 
         if current_sheet_index != multi_column_action.sheet_index {
             if let Err(e) = self.switch_sheet_by_index(multi_column_action.sheet_index) {
-                self.add_notification(format!(
+                self.add_error(format!(
                     "Cannot switch to sheet {}: {}",
                     multi_column_action.sheet_name, e
                 ));
@@ -511,36 +647,138 @@ impl AppState<'_> {
         Ok(())
     }
 
+    fn apply_range_action(&mut self, range_action: &RangeAction, is_undo: bool) -> Result<()> {
+        let current_sheet_index = self.workbook.get_current_sheet_index();
+
+        if current_sheet_index != range_action.sheet_index {
+            if let Err(e) = self.switch_sheet_by_index(range_action.sheet_index) {
+                self.add_error(format!(
+                    "Cannot switch to sheet {}: {}",
+                    range_action.sheet_name, e
+                ));
+                return Ok(());
+            }
+        }
+
+        self.apply_range_changes(range_action, is_undo);
+
+        if let Some((top_left, _)) = range_action.bounds() {
+            self.selected_cell = top_left;
+        }
+        self.handle_scrolling();
+        self.search_results.clear();
+        self.current_search_idx = None;
+
+        let operation_text = match range_action.action_type {
+            ActionType::Transpose => "transpose",
+            ActionType::Paste => "paste",
+            ActionType::Trim => "trim",
+            ActionType::ChangeCase => "case change",
+            ActionType::Format => "format",
+            ActionType::MoveRows => "row move",
+            ActionType::MoveColumns => "column move",
+            ActionType::JoinColumns => "column join",
+            ActionType::Substitute => "substitution",
+            _ => "range operation",
+        };
+        let action_word = if is_undo { "Undid" } else { "Redid" };
+        let range_text = range_action
+            .bounds()
+            .map(|(start, end)| {
+                format!(
+                    " of {}:{}",
+                    crate::utils::cell_reference(start),
+                    crate::utils::cell_reference(end)
+                )
+            })
+            .unwrap_or_default();
+        self.add_notification(format!("{action_word} {operation_text}{range_text}"));
+
+        Ok(())
+    }
+
+    fn apply_column_width_action(
+        &mut self,
+        width_action: &ColumnWidthAction,
+        is_undo: bool,
+    ) -> Result<()> {
+        let current_sheet_index = self.workbook.get_current_sheet_index();
+
+        if current_sheet_index != width_action.sheet_index {
+            if let Err(e) = self.switch_sheet_by_index(width_action.sheet_index) {
+                self.add_error(format!(
+                    "Cannot switch to sheet {}: {}",
+                    width_action.sheet_name, e
+                ));
+                return Ok(());
+            }
+        }
+
+        for change in &width_action.changes {
+            let width = if is_undo {
+                change.old_width
+            } else {
+                change.new_width
+            };
+            self.set_column_width(change.col, width);
+        }
+
+        let action_word = if is_undo { "Undid" } else { "Redid" };
+        match width_action.changes.as_slice() {
+            [change] => {
+                self.ensure_column_visible(change.col);
+                self.add_notification(format!(
+                    "{action_word} width change of column {}",
+                    index_to_col_name(change.col)
+                ));
+            }
+            changes => {
+                self.add_notification(format!(
+                    "{action_word} width change of {} columns",
+                    changes.len()
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Writes either the old or the new side of a range action into the current sheet
+    pub(crate) fn apply_range_changes(&mut self, range_action: &RangeAction, use_old: bool) {
+        let Some((_, (max_row, max_col))) = range_action.bounds() else {
+            return;
+        };
+
+        self.workbook.ensure_cell_exists(max_row, max_col);
+
+        let sheet = self.workbook.get_current_sheet_mut();
+        for change in &range_action.changes {
+            let value = if use_old {
+                &change.old_value
+            } else {
+                &change.new_value
+            };
+            sheet.data.set(change.row, change.col, value.clone());
+        }
+
+        self.workbook.set_modified(true);
+        self.ensure_column_widths();
+    }
+
     fn restore_rows(
         sheet: &mut crate::excel::Sheet,
         position: usize,
-        rows_data: &[Vec<crate::excel::Cell>],
+        rows_data: &[Vec<(usize, crate::excel::Cell)>],
     ) {
-        // Pre-allocate space by extending the vector
-        for row_data in rows_data.iter().rev() {
-            sheet.data.insert(position, row_data.clone());
-        }
+        sheet.data.insert_rows(position, rows_data);
     }
 
     fn restore_column_at_position(
         sheet: &mut crate::excel::Sheet,
         position: usize,
-        column_data: &[crate::excel::Cell],
+        column_data: &[(usize, crate::excel::Cell)],
     ) {
-        for (i, row) in sheet.data.iter_mut().enumerate() {
-            if i < column_data.len() {
-                if position <= row.len() {
-                    row.insert(position, column_data[i].clone());
-                } else {
-                    let additional = position - row.len();
-                    row.reserve(additional + 1);
-                    while row.len() < position {
-                        row.push(crate::excel::Cell::empty());
-                    }
-                    row.push(column_data[i].clone());
-                }
-            }
-        }
+        sheet.data.insert_column(position, column_data);
     }
 
     fn restore_column_width(
@@ -591,8 +829,25 @@ impl ActionExecutor for AppState<'_> {
             ActionCommand::Row(action) => self.execute_row_action(action),
             ActionCommand::Column(action) => self.execute_column_action(action),
             ActionCommand::Sheet(action) => self.execute_sheet_action(action),
+            ActionCommand::RenameSheet(action) => self
+                .rename_sheet_at(action.sheet_index, &action.old_name, &action.new_name)
+                .map(|_| ()),
             ActionCommand::MultiRow(action) => self.execute_multi_row_action(action),
             ActionCommand::MultiColumn(action) => self.execute_multi_column_action(action),
+            ActionCommand::Range(action) => self.execute_range_action(action),
+            ActionCommand::ColumnWidth(action) => self.execute_column_width_action(action),
+            ActionCommand::InsertRow(action) => {
+                self.workbook
+                    .insert_row(action.row, action.row_data.clone());
+                Ok(())
+            }
+            ActionCommand::InsertColumn(action) => {
+                self.workbook.insert_column(action.col, &action.column_data);
+                Ok(())
+            }
+            ActionCommand::Group(actions) => actions
+                .iter()
+                .try_for_each(|action| self.execute_action(action)),
         }
     }
 
@@ -613,7 +868,7 @@ impl ActionExecutor for AppState<'_> {
         match action.operation {
             SheetOperation::Create => {
                 self.workbook
-                    .insert_sheet_at_index(action.sheet_data.clone(), action.sheet_index)?;
+                    .insert_sheet_at_index(action.sheet()?, action.sheet_index)?;
                 self.sheet_column_widths
                     .insert(action.sheet_name.clone(), action.column_widths.clone());
                 self.sheet_cell_positions.insert(
@@ -635,11 +890,29 @@ impl ActionExecutor for AppState<'_> {
     }
 
     fn execute_multi_row_action(&mut self, action: &MultiRowAction) -> Result<()> {
-        self.workbook.delete_rows(action.start_row, action.end_row)
+        if action.is_contiguous() {
+            self.workbook.delete_rows(action.start_row, action.end_row)
+        } else {
+            self.workbook.delete_row_set(&action.rows)
+        }
     }
 
     fn execute_multi_column_action(&mut self, action: &MultiColumnAction) -> Result<()> {
         self.workbook
             .delete_columns(action.start_col, action.end_col)
     }
+
+    fn execute_range_action(&mut self, action: &RangeAction) -> Result<()> {
+        self.switch_sheet_by_index(action.sheet_index)?;
+        self.apply_range_changes(action, false);
+        Ok(())
+    }
+
+    fn execute_column_width_action(&mut self, action: &ColumnWidthAction) -> Result<()> {
+        self.switch_sheet_by_index(action.sheet_index)?;
+        for change in &action.changes {
+            self.set_column_width(change.col, change.new_width);
+        }
+        Ok(())
+    }
 }
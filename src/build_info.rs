@@ -0,0 +1,46 @@
+//! Version, commit and compiled-in capabilities of this binary, for bug reports
+
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+/// Short commit hash the binary was built from, or `unknown` outside a git checkout
+pub const GIT_HASH: &str = env!("EXCEL_CLI_GIT_HASH");
+pub const TARGET: &str = env!("EXCEL_CLI_TARGET");
+pub const PROFILE: &str = env!("EXCEL_CLI_PROFILE");
+
+/// Optional cargo features compiled into this binary
+#[must_use]
+pub fn enabled_features() -> Vec<&'static str> {
+    [
+        ("tui", cfg!(feature = "tui")),
+        ("regex", cfg!(feature = "regex")),
+    ]
+    .into_iter()
+    .filter_map(|(name, enabled)| enabled.then_some(name))
+    .collect()
+}
+
+fn features_text() -> String {
+    let features = enabled_features();
+    if features.is_empty() {
+        "none".to_string()
+    } else {
+        features.join(", ")
+    }
+}
+
+/// One-line summary such as `excel-cli 1.3.2 (3ac1e23f0c12) features: tui, regex`
+#[must_use]
+pub fn summary() -> String {
+    format!(
+        "excel-cli {VERSION} ({GIT_HASH}) features: {}",
+        features_text()
+    )
+}
+
+/// Multi-line report printed by `--build-info`
+#[must_use]
+pub fn report() -> String {
+    format!(
+        "excel-cli {VERSION}\ngit: {GIT_HASH}\ntarget: {TARGET}\nprofile: {PROFILE}\nfeatures: {}\n",
+        features_text()
+    )
+}
@@ -7,11 +7,17 @@ use std::path::PathBuf;
     author,
     version,
     about = "Excel CLI for AI, scripting, and terminal users",
-    long_about = None
+    long_about = None,
+    override_usage = "excel-cli <COMMAND>\n       excel-cli --build-info",
+    args_conflicts_with_subcommands = true
 )]
 pub struct Cli {
+    /// Print version, git commit, target and enabled features, then exit
+    #[arg(long, exclusive = true)]
+    pub build_info: bool,
+
     #[command(subcommand)]
-    pub command: Commands,
+    pub command: Option<Commands>,
 }
 
 #[derive(Subcommand)]
@@ -52,9 +58,16 @@ pub enum Commands {
         severity_threshold: SeverityThreshold,
     },
     /// Open interactive TUI browser
+    #[cfg(feature = "tui")]
     Ui {
-        /// Excel file path
-        file: PathBuf,
+        /// Excel file paths; each opens as a buffer, switched with gt/gT or :buffer
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+
+        /// Read each .xlsx/.xlsm sheet on a background thread when first viewed, instead of
+        /// all of them before the grid opens
+        #[arg(long)]
+        lazy_loading: bool,
     },
     /// Search cell values recursively across Excel files
     Grep {
@@ -69,7 +82,7 @@ pub enum Commands {
         case_insensitive: bool,
 
         /// Treat query as a regular expression
-        #[arg(short = 'r', long)]
+        #[arg(short = 'r', long, hide = !cfg!(feature = "regex"))]
         regex: bool,
 
         /// Limit search to a specific sheet name
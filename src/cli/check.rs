@@ -975,7 +975,7 @@ mod tests {
 
     use super::*;
     use crate::cli::error::{EXIT_CHECK_FINDINGS, EXIT_SUCCESS};
-    use crate::excel::{Cell, Sheet, Workbook};
+    use crate::excel::{Cell, CellGrid, Sheet, Workbook};
 
     #[test]
     fn exit_code_uses_one_for_successful_reports_with_findings() {
@@ -1032,11 +1032,15 @@ mod tests {
     fn sheet_with_values(name: &str, values: &[&[&str]]) -> Sheet {
         let max_rows = values.len();
         let max_cols = values.iter().map(|row| row.len()).max().unwrap_or(0);
-        let mut data = vec![vec![Cell::empty(); max_cols + 1]; max_rows + 1];
+        let mut data = CellGrid::new();
 
         for (row_idx, row) in values.iter().enumerate() {
             for (col_idx, value) in row.iter().enumerate() {
-                data[row_idx + 1][col_idx + 1] = Cell::new((*value).to_string(), false);
+                data.set(
+                    row_idx + 1,
+                    col_idx + 1,
+                    Cell::new((*value).to_string(), false),
+                );
             }
         }
 
@@ -1047,6 +1051,11 @@ mod tests {
             max_cols,
             is_loaded: true,
             freeze_panes: crate::excel::FreezePanes::none(),
+            column_formats: crate::excel::ColumnFormats::new(),
+            protection: crate::excel::SheetProtection::Unprotected,
+            visibility: crate::excel::SheetVisibility::Visible,
+            tab_color: None,
+            conditional_formats: Vec::new(),
         }
     }
 
@@ -4,7 +4,13 @@ use crate::cli::args::{Cli, Commands, OutputFormat};
 use crate::cli::error::{AppError, EXIT_SUCCESS};
 
 pub fn dispatch(cli: Cli) -> Result<(Value, OutputFormat, i32), AppError> {
-    match cli.command {
+    let Some(command) = cli.command else {
+        return Err(AppError::InvalidArgs {
+            message: "a subcommand is required; see `excel-cli --help`".to_string(),
+        });
+    };
+
+    match command {
         Commands::Inspect { subcommand } => {
             let format = match &subcommand {
                 crate::cli::args::InspectCommands::Workbook { format, .. } => format.clone(),
@@ -36,12 +42,69 @@ pub fn dispatch(cli: Cli) -> Result<(Value, OutputFormat, i32), AppError> {
                 crate::cli::check::handle(file, sheet, rules, severity_threshold)?;
             Ok((value, OutputFormat::Json, exit_code))
         }
-        Commands::Ui { file } => {
-            let workbook = crate::excel::open_workbook(&file, false)
-                .map_err(crate::cli::error::anyhow_to_app_error)?;
-            let app_state = crate::app::AppState::new(workbook, file)
+        #[cfg(feature = "tui")]
+        Commands::Ui {
+            files,
+            lazy_loading,
+        } => {
+            let settings = crate::config::Settings::load();
+            // A memcap the workbooks may pass leaves their sheets unread until shown, so
+            // opening one never reads more than the cap allows
+            let memory_cap = settings.as_ref().ok().and_then(|settings| {
+                match settings.options.get("memcap")? {
+                    crate::config::OptionSetting::Value(value) => crate::app::parse_size(value),
+                    crate::config::OptionSetting::Switch(_) => None,
+                }
+            });
+            let lazy_loading = lazy_loading
+                || memory_cap.is_some_and(|cap| {
+                    files
+                        .iter()
+                        .any(|file| crate::app::may_pass_memory_cap(file, cap))
+                });
+            let keymap = match &settings {
+                Ok(settings) => crate::ui::Keymap::from_settings(&settings.keys),
+                Err(_) => Ok(crate::ui::Keymap::default()),
+            };
+            // The loading screen is drawn in the configured theme
+            let style_error = settings
+                .as_ref()
+                .err()
+                .cloned()
+                .or_else(|| {
+                    let settings = settings.as_ref().ok()?;
+                    crate::ui::use_theme_settings(&settings.theme).err()
+                })
+                .or_else(|| keymap.as_ref().err().cloned());
+            let keymap = keymap.unwrap_or_default();
+            let start = |workbooks: Vec<crate::excel::Workbook>| {
+                let mut buffers = workbooks
+                    .into_iter()
+                    .zip(files.iter().cloned())
+                    .map(|(workbook, file)| {
+                        let mut buffer = crate::app::AppState::new(workbook, file)?;
+                        buffer.keymap = keymap.clone();
+                        buffer.restore_last_position();
+                        buffer.load_history();
+                        if let Ok(settings) = &settings {
+                            if let Err(e) = buffer.apply_option_settings(&settings.options) {
+                                buffer.add_error(e);
+                            }
+                        }
+                        Ok(buffer)
+                    })
+                    .collect::<anyhow::Result<Vec<_>>>()?;
+                let mut first = buffers.remove(0);
+                first.show_onboarding_if_first_run();
+                if let Some(e) = style_error {
+                    first.add_error(e);
+                }
+                Ok(crate::app::Session::new(first, buffers)
+                    .saving_state_in(crate::config::config_dir())
+                    .using_system_clipboard())
+            };
+            crate::ui::run_app(&files, lazy_loading, start)
                 .map_err(crate::cli::error::anyhow_to_app_error)?;
-            crate::ui::run_app(app_state).map_err(crate::cli::error::anyhow_to_app_error)?;
             Ok((
                 crate::cli::envelope::success_envelope(
                     "ui",
@@ -1,4 +1,3 @@
-use regex::RegexBuilder;
 use serde_json::{json, Value};
 use std::collections::{HashSet, VecDeque};
 use std::path::{Path, PathBuf};
@@ -7,6 +6,7 @@ use std::thread;
 
 use crate::cli::envelope;
 use crate::cli::error::AppError;
+use crate::cli::regex_support;
 use crate::excel::open_workbook;
 use crate::utils::{cell_reference, parse_cell_reference};
 
@@ -32,12 +32,11 @@ pub fn handle(
 
     // 1. Compile regex pattern if requested
     let regex_pattern = if is_regex {
-        let pattern = RegexBuilder::new(&query)
-            .case_insensitive(case_insensitive)
-            .build()
-            .map_err(|e| AppError::InvalidQuery {
+        let pattern = regex_support::compile(&query, case_insensitive).map_err(|e| {
+            AppError::InvalidQuery {
                 message: format!("Invalid regex: {}", e),
-            })?;
+            }
+        })?;
         Some(pattern)
     } else {
         None
@@ -182,14 +181,8 @@ pub fn handle(
                     };
 
                     for row in 1..=sheet_obj.max_rows {
-                        if row >= sheet_obj.data.len() {
-                            break;
-                        }
                         for col in 1..=sheet_obj.max_cols {
-                            if col >= sheet_obj.data[row].len() {
-                                break;
-                            }
-                            let cell = &sheet_obj.data[row][col];
+                            let cell = sheet_obj.data.cell(row, col);
                             let cell_ref = cell_reference((row, col));
 
                             let is_match = if let Some(ref re) = *regex_pattern {
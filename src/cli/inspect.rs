@@ -187,13 +187,10 @@ fn inspect_sample(
     let data = if let Some(header_row_idx) = resolved_header {
         // Build records with headers
         let mut headers = Vec::new();
-        if header_row_idx < sheet_obj.data.len() {
+        if header_row_idx <= sheet_obj.max_rows {
             for col in bounds.start_col..=bounds.end_col {
-                let val = if col < sheet_obj.data[header_row_idx].len() {
-                    sheet_obj.data[header_row_idx][col].value.clone()
-                } else {
-                    String::new()
-                };
+                let val = cell_at(sheet_obj, header_row_idx, col)
+                    .map_or_else(String::new, |cell| cell.value.clone());
                 headers.push(val);
             }
         }
@@ -203,7 +200,7 @@ fn inspect_sample(
             if row == header_row_idx {
                 continue;
             }
-            if row >= sheet_obj.data.len() {
+            if row > sheet_obj.max_rows {
                 break;
             }
             let mut record = serde_json::Map::new();
@@ -214,11 +211,8 @@ fn inspect_sample(
                 } else {
                     key
                 };
-                let value = if col < sheet_obj.data[row].len() {
-                    crate::json_export::process_cell_value(&sheet_obj.data[row][col])
-                } else {
-                    Value::Null
-                };
+                let value = cell_at(sheet_obj, row, col)
+                    .map_or(Value::Null, crate::json_export::process_cell_value);
                 record.insert(key, value);
             }
             records.push(Value::Object(record));
@@ -233,16 +227,13 @@ fn inspect_sample(
         // Raw rows
         let mut row_values = Vec::new();
         for row in bounds.start_row..=sample_end_row {
-            if row >= sheet_obj.data.len() {
+            if row > sheet_obj.max_rows {
                 break;
             }
             let mut cols = Vec::new();
             for col in bounds.start_col..=bounds.end_col {
-                let value = if col < sheet_obj.data[row].len() {
-                    crate::json_export::process_cell_value(&sheet_obj.data[row][col])
-                } else {
-                    Value::Null
-                };
+                let value = cell_at(sheet_obj, row, col)
+                    .map_or(Value::Null, crate::json_export::process_cell_value);
                 cols.push(value);
             }
             row_values.push(Value::Array(cols));
@@ -752,18 +743,11 @@ fn text_count(sheet: &Sheet, row: usize, start_col: usize, end_col: usize) -> us
 }
 
 fn cell_has_value(sheet: &Sheet, row: usize, col: usize) -> bool {
-    sheet
-        .data
-        .get(row)
-        .and_then(|row_data| row_data.get(col))
+    cell_at(sheet, row, col)
         .map(|cell| !cell.value.trim().is_empty() || cell.formula.is_some())
         .unwrap_or(false)
 }
 
 fn table_cell_type(sheet: &Sheet, row: usize, col: usize) -> Option<CellType> {
-    sheet
-        .data
-        .get(row)
-        .and_then(|row_data| row_data.get(col))
-        .map(|cell| cell.cell_type.clone())
+    cell_at(sheet, row, col).map(|cell| cell.cell_type.clone())
 }
@@ -8,4 +8,5 @@ pub mod grep;
 pub mod inspect;
 pub mod output;
 pub mod read;
+pub(crate) mod regex_support;
 pub mod sheet_query;
@@ -1,4 +1,3 @@
-use regex::Regex;
 use serde_json::{json, Value};
 use std::path::PathBuf;
 
@@ -6,8 +5,9 @@ use crate::cli::args::{OutputFormat, OutputShape, ReadCommands};
 use crate::cli::common::{file_format, format_bounds, sheet_by_index};
 use crate::cli::envelope;
 use crate::cli::error::AppError;
+use crate::cli::regex_support::{self, Regex};
 use crate::cli::sheet_query::{
-    load_target_sheet, read_header_values, resolve_bounds, resolve_optional_header_row,
+    cell_at, load_target_sheet, read_header_values, resolve_bounds, resolve_optional_header_row,
     stable_record_keys,
 };
 use crate::excel::{open_workbook, CellType, Sheet};
@@ -119,9 +119,7 @@ fn read_cell(
     let sheet_obj = sheet_by_index(&workbook, resolved_sheet.index, &resolved_sheet.name)?;
 
     let cell_ref = cell.to_ascii_uppercase();
-    let in_bounds = row < sheet_obj.data.len() && col < sheet_obj.data[row].len();
-    let (value, cell_type, formula) = if in_bounds {
-        let c = &sheet_obj.data[row][col];
+    let (value, cell_type, formula) = if let Some(c) = cell_at(sheet_obj, row, col) {
         let formula =
             workbook.formula_for_cell(resolved_sheet.index, &resolved_sheet.name, &cell_ref);
         let type_str = if c.is_formula || formula.is_some() {
@@ -235,17 +233,13 @@ fn invalid_query(message: impl Into<String>) -> AppError {
 }
 
 fn sheet_row_values(sheet: &Sheet, row: usize, bounds: RowBounds) -> Option<Vec<Value>> {
-    if row >= sheet.data.len() {
+    if row > sheet.max_rows {
         return None;
     }
 
     let values = (bounds.start_col..=bounds.end_col)
         .map(|col| {
-            if col < sheet.data[row].len() {
-                crate::json_export::process_cell_value(&sheet.data[row][col])
-            } else {
-                Value::Null
-            }
+            cell_at(sheet, row, col).map_or(Value::Null, crate::json_export::process_cell_value)
         })
         .collect();
 
@@ -392,7 +386,7 @@ fn parse_filters(filters: Vec<String>, columns: &[String]) -> Result<Vec<FilterS
 
             let regex = if matches!(op, FilterOp::Regex) {
                 Some(
-                    Regex::new(&value)
+                    regex_support::compile(&value, false)
                         .map_err(|err| invalid_query(format!("Invalid regex filter: {err}")))?,
                 )
             } else {
@@ -518,11 +512,8 @@ fn read_range(
     for row in bounds.start_row..=bounds.end_row {
         let mut cols = Vec::new();
         for col in bounds.start_col..=bounds.end_col {
-            let value = if row < sheet_obj.data.len() && col < sheet_obj.data[row].len() {
-                crate::json_export::process_cell_value(&sheet_obj.data[row][col])
-            } else {
-                Value::Null
-            };
+            let value = cell_at(sheet_obj, row, col)
+                .map_or(Value::Null, crate::json_export::process_cell_value);
             cols.push(value);
         }
         rows.push(Value::Array(cols));
@@ -0,0 +1,84 @@
+//! Regular expressions for `grep --regex`, the `regex` row filter and the TUI's `:s`. Builds
+//! without the `regex` cargo feature reject regex queries instead of linking the regex engine.
+
+#[cfg(feature = "regex")]
+pub(crate) use regex::Regex;
+
+#[cfg(feature = "regex")]
+pub(crate) fn compile(pattern: &str, case_insensitive: bool) -> Result<Regex, String> {
+    regex::RegexBuilder::new(pattern)
+        .case_insensitive(case_insensitive)
+        .build()
+        .map_err(|err| err.to_string())
+}
+
+/// Replaces the first match, or with `all` every match, expanding `$1` and `${name}` in
+/// `replacement`
+#[cfg(all(feature = "regex", feature = "tui"))]
+pub(crate) fn replace(regex: &Regex, text: &str, replacement: &str, all: bool) -> String {
+    let limit = if all { 0 } else { 1 };
+    regex.replacen(text, limit, replacement).into_owned()
+}
+
+/// The first `$1` or `${name}` reference in `replacement` to a group `regex` does not have.
+/// The regex crate would expand it to nothing
+#[cfg(all(feature = "regex", feature = "tui"))]
+pub(crate) fn missing_group(regex: &Regex, replacement: &str) -> Option<String> {
+    let mut rest = replacement;
+    while let Some(index) = rest.find('$') {
+        rest = &rest[index + 1..];
+        if let Some(after) = rest.strip_prefix('$') {
+            rest = after;
+            continue;
+        }
+        let (name, after) = if let Some(braced) = rest.strip_prefix('{') {
+            match braced.find('}') {
+                Some(end) => (&braced[..end], &braced[end + 1..]),
+                None => continue,
+            }
+        } else {
+            let end = rest
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(rest.len());
+            rest.split_at(end)
+        };
+        rest = after;
+        if name.is_empty() {
+            continue;
+        }
+        let known = match name.parse::<usize>() {
+            Ok(group) => group < regex.captures_len(),
+            Err(_) => regex.capture_names().flatten().any(|group| group == name),
+        };
+        if !known {
+            return Some(name.to_string());
+        }
+    }
+    None
+}
+
+/// Uninhabited stand-in, so regex match paths compile but can never run
+#[cfg(not(feature = "regex"))]
+pub(crate) enum Regex {}
+
+#[cfg(not(feature = "regex"))]
+impl Regex {
+    pub(crate) fn is_match(&self, _text: &str) -> bool {
+        match *self {}
+    }
+}
+
+#[cfg(all(not(feature = "regex"), feature = "tui"))]
+pub(crate) fn replace(regex: &Regex, _text: &str, _replacement: &str, _all: bool) -> String {
+    match *regex {}
+}
+
+#[cfg(all(not(feature = "regex"), feature = "tui"))]
+pub(crate) fn missing_group(regex: &Regex, _replacement: &str) -> Option<String> {
+    match *regex {}
+}
+
+#[cfg(not(feature = "regex"))]
+pub(crate) fn compile(_pattern: &str, _case_insensitive: bool) -> Result<Regex, String> {
+    Err("this build of excel-cli was compiled without the `regex` feature".to_string())
+}
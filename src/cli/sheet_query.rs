@@ -126,8 +126,9 @@ pub(crate) fn resolve_optional_header_row(
         .filter(|row| *row >= 1 && *row <= sheet.max_rows))
 }
 
+/// The cell at `(row, col)`, empty when it holds nothing; `None` outside the sheet's used range
 pub(crate) fn cell_at(sheet: &Sheet, row: usize, col: usize) -> Option<&Cell> {
-    sheet.data.get(row).and_then(|row_data| row_data.get(col))
+    (row <= sheet.max_rows && col <= sheet.max_cols).then(|| sheet.data.cell(row, col))
 }
 
 pub(crate) fn cell_has_formula(cell: &Cell) -> bool {
@@ -177,11 +178,7 @@ pub(crate) fn read_header_values(
 ) -> Vec<String> {
     (bounds.start_col..=bounds.end_col)
         .map(|col| {
-            if header_row < sheet.data.len() && col < sheet.data[header_row].len() {
-                sheet.data[header_row][col].value.clone()
-            } else {
-                String::new()
-            }
+            cell_at(sheet, header_row, col).map_or_else(String::new, |cell| cell.value.clone())
         })
         .collect()
 }
@@ -189,16 +186,22 @@ pub(crate) fn read_header_values(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::excel::{CellType, FreezePanes, Sheet};
+    use crate::excel::{
+        CellGrid, CellType, ColumnFormats, FreezePanes, Sheet, SheetProtection, SheetVisibility,
+    };
 
     fn sheet_with_values(name: &str, values: &[&[&str]]) -> Sheet {
         let max_rows = values.len();
         let max_cols = values.iter().map(|row| row.len()).max().unwrap_or(0);
-        let mut data = vec![vec![Cell::empty(); max_cols + 1]; max_rows + 1];
+        let mut data = CellGrid::new();
 
         for (row_idx, row) in values.iter().enumerate() {
             for (col_idx, value) in row.iter().enumerate() {
-                data[row_idx + 1][col_idx + 1] = Cell::new((*value).to_string(), false);
+                data.set(
+                    row_idx + 1,
+                    col_idx + 1,
+                    Cell::new((*value).to_string(), false),
+                );
             }
         }
 
@@ -209,6 +212,11 @@ mod tests {
             max_cols,
             is_loaded: true,
             freeze_panes: FreezePanes::none(),
+            column_formats: ColumnFormats::new(),
+            protection: SheetProtection::Unprotected,
+            visibility: SheetVisibility::Visible,
+            tab_color: None,
+            conditional_formats: Vec::new(),
         }
     }
 
@@ -284,20 +292,40 @@ mod tests {
     #[test]
     fn header_and_cell_helpers_preserve_existing_formula_semantics() {
         let mut sheet = sheet_with_values("Orders", &[&["order_id", ""], &["1001", "Alice"]]);
-        sheet.data[1][2] = Cell {
-            value: "total".to_string(),
-            formula: Some("=UPPER(\"total\")".to_string()),
-            is_formula: false,
-            cell_type: CellType::Text,
-            original_type: None,
-        };
-        sheet.data[2][2] = Cell {
-            value: String::new(),
-            formula: Some("=A2".to_string()),
-            is_formula: false,
-            cell_type: CellType::Text,
-            original_type: None,
-        };
+        sheet.data.set(
+            1,
+            2,
+            Cell {
+                value: "total".to_string(),
+                formula: Some("=UPPER(\"total\")".to_string()),
+                is_formula: false,
+                cell_type: CellType::Text,
+                original_type: None,
+                comment: None,
+                hyperlink: None,
+                number_format: None,
+                unlocked: false,
+                fill_color: None,
+                font_color: None,
+            },
+        );
+        sheet.data.set(
+            2,
+            2,
+            Cell {
+                value: String::new(),
+                formula: Some("=A2".to_string()),
+                is_formula: false,
+                cell_type: CellType::Text,
+                original_type: None,
+                comment: None,
+                hyperlink: None,
+                number_format: None,
+                unlocked: false,
+                fill_color: None,
+                font_color: None,
+            },
+        );
 
         assert_eq!(header_value(&sheet, 1, 1), "order_id");
         assert_eq!(header_value(&sheet, 1, 2), "");
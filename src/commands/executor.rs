@@ -1,9 +1,16 @@
 use std::path::Path;
 
-use crate::app::AppState;
-use crate::excel::{EXCEL_MAX_COLS, EXCEL_MAX_ROWS};
-use crate::json_export::{export_all_sheets_json, export_json, HeaderDirection};
-use crate::utils::{cell_reference, col_name_to_index, index_to_col_name, parse_cell_reference};
+use crate::app::{
+    is_substitute_command, AppState, BufferRequest, PasteOptions, SplitDirection, TextCase,
+};
+use crate::excel::{NumberFormat, EXCEL_MAX_COLS, EXCEL_MAX_ROWS};
+use crate::json_export::{
+    export_all_sheets_json, export_json, DurationFormat, ExportOptions, HeaderDirection,
+};
+use crate::utils::{
+    cell_reference, col_name_to_index, index_to_col_name, parse_cell_reference, parse_range,
+    SortMode,
+};
 
 impl AppState<'_> {
     pub fn execute_command(&mut self) {
@@ -14,6 +21,7 @@ impl AppState<'_> {
         if command.is_empty() {
             return;
         }
+        self.command_history.push(&command);
 
         // Handle cell navigation (e.g., :A1, :B10)
         if let Some(cell_ref) = parse_cell_reference(&command) {
@@ -21,17 +29,23 @@ impl AppState<'_> {
             return;
         }
 
-        // Handle commands
-        match command.as_str() {
+        // Whatever a command records is undone as one step
+        self.undo_history.begin_group();
+        self.run_command(&command);
+        self.undo_history.end_group();
+    }
+
+    fn run_command(&mut self, command: &str) {
+        match command {
             "w" => {
                 if let Err(e) = self.save() {
-                    self.add_notification(format!("Save failed: {e}"));
+                    self.add_error(format!("Save failed: {e}"));
                 }
             }
             "wq" | "x" => self.save_and_exit(),
             "q" => {
                 if self.workbook.is_modified() {
-                    self.add_notification(
+                    self.add_warning(
                         "File has unsaved changes. Use :q! to force quit or :wq to save and quit."
                             .to_string(),
                     );
@@ -41,14 +55,18 @@ impl AppState<'_> {
             }
             "q!" => self.exit_without_saving(),
             "y" => self.copy_cell(),
+            "yref" => self.copy_reference(),
+            "yformula" => self.copy_formula(),
+            "yank" => self.add_warning("Usage: :yank md|csv".to_string()),
             "d" => {
                 if let Err(e) = self.cut_cell() {
-                    self.add_notification(format!("Cut failed: {e}"));
+                    self.add_error(format!("Cut failed: {e}"));
                 }
             }
-            "put" | "pu" => {
+            "reg" | "registers" => self.show_registers(),
+            "put" | "pu" | "paste" => {
                 if let Err(e) = self.paste_cell() {
-                    self.add_notification(format!("Paste failed: {e}"));
+                    self.add_error(format!("Paste failed: {e}"));
                 }
             }
             "nohlsearch" | "noh" => self.disable_search_highlight(),
@@ -56,34 +74,367 @@ impl AppState<'_> {
             "delsheet" => self.delete_current_sheet(),
             "freeze" => self.freeze_at_cell(self.selected_cell),
             "unfreeze" => self.clear_freeze_panes(),
-            "addsheet" => self.add_notification("Usage: :addsheet <name>".to_string()),
+            "split" | "sp" => self.split_view(SplitDirection::Horizontal, None),
+            "vsplit" | "vs" => self.split_view(SplitDirection::Vertical, None),
+            "close" | "clo" => self.close_pane(),
+            "minimap" => self.toggle_minimap(),
+            "set" | "se" => self.handle_set_command(""),
+            "find" => self.find_cells(""),
+            "addsheet" => self.add_warning("Usage: :addsheet <name>".to_string()),
+            "newsheet" => self.append_sheet(None),
+            "dupsheet" => self.duplicate_current_sheet(None),
+            "copysheet" => self.add_warning("Usage: :copysheet <file.xlsx>".to_string()),
+            "importsheet" => {
+                self.add_warning("Usage: :importsheet <file> <sheet>".to_string());
+            }
+            "rename" => self.add_warning("Usage: :rename <name>".to_string()),
+            "mr" => self.add_warning("Usage: :mr <n>".to_string()),
+            "mc" => self.add_warning("Usage: :mc <n>".to_string()),
+            "joincols" => self.add_warning(JOINCOLS_USAGE.to_string()),
+            "name" => self.show_defined_names(),
+            "goto" => self.add_warning("Usage: :goto <name>".to_string()),
+            "dupr" => self.duplicate_current_row(),
+            "dupc" => self.duplicate_current_column(),
+            "unlock" => self.unlock_current_sheet(),
+            "showhidden" => self.toggle_hidden_sheets(),
+            "buffer" | "buffers" | "ls" => self.handle_buffer_command(""),
+            "bn" | "bnext" => self.buffer_request = Some(BufferRequest::Next),
+            "bp" | "bprev" => self.buffer_request = Some(BufferRequest::Prev),
+            "transpose" => self.transpose(None, false),
+            "dedupe" => self.dedupe_rows(None),
+            "upper" => self.change_case(TextCase::Upper),
+            "lower" => self.change_case(TextCase::Lower),
+            "title" => self.change_case(TextCase::Title),
+            "copen" | "results" => self.open_results(),
+            "cclose" => self.close_results(),
+            "ctoggle" => self.toggle_results(),
+            "only" => self.filter_rows_to_matches(),
+            "nofilter" => self.clear_row_filter(),
+            "headerrow" => self.show_header_row(),
+            "noheaderrow" => self.clear_header_row(),
+            "cf" => self.handle_cf_command(""),
+            "nocf" => self.remove_conditional_formats(""),
+            "messages" | "mes" => self.handle_messages_command(""),
+            "changes" => self.show_changes(),
+            "mem" => self.show_memory(),
+            "cn" | "cnext" => self.step_result(true),
+            "cp" | "cprev" => self.step_result(false),
+            "dupes" => self.show_duplicate_rows(None),
+            "headers" => self.show_headers(),
+            "show" => self.show_cell_content(),
+            "errors" => self.show_errors(),
+            "freq" => self.show_frequencies(self.selected_cell.1),
+            "trim" => self.trim_cells(Some(self.selected_cell.1)),
+            "comment" => {
+                let (row, col) = self.selected_cell;
+                match self.cell_comment(row, col).map(str::to_string) {
+                    Some(text) => self.add_notification(format!("Comment: {text}")),
+                    None => self.add_warning("Usage: :comment <text>".to_string()),
+                }
+            }
+            "delcomment" => self.set_comment(None),
+            "fmt" => self.show_number_format(),
+            "exporttz" => self.handle_export_timezone_command(""),
+            "exportdur" => self.add_notification(format!(
+                "Export durations as: {}",
+                self.export_options.duration_format.name()
+            )),
+            "version" => self.add_notification(crate::build_info::summary()),
+            "col" => self.add_warning("Usage: :col <header>".to_string()),
+            "sortmode" => {
+                self.add_notification(format!("Sort mode: {}", self.sort_mode.name()));
+            }
             _ => {
                 // Handle commands with parameters
-                if command.starts_with("cw ") {
-                    self.handle_column_width_command(&command);
+                if is_substitute_command(command) {
+                    self.substitute(command);
+                } else if let Some(format) = command.strip_prefix("yank ") {
+                    self.yank_as(format);
+                } else if command.starts_with("cw ") {
+                    self.handle_column_width_command(command);
                 } else if command.starts_with("ej") {
-                    self.handle_json_export_command(&command);
+                    self.handle_json_export_command(command);
                 } else if let Some(sheet_name) = command.strip_prefix("addsheet ") {
                     self.create_sheet(sheet_name.trim());
+                } else if let Some(sheet_name) = command.strip_prefix("newsheet ") {
+                    self.append_sheet(Some(sheet_name.trim()));
+                } else if let Some(args) = command
+                    .strip_prefix("buffer ")
+                    .or_else(|| command.strip_prefix("b "))
+                {
+                    self.handle_buffer_command(args);
+                } else if let Some(args) = command.strip_prefix("importsheet ") {
+                    self.import_sheet_from_file(args);
+                } else if let Some(target) = command.strip_prefix("copysheet ") {
+                    self.copy_sheet_to_file(target);
+                } else if let Some(sheet_name) = command.strip_prefix("dupsheet ") {
+                    self.duplicate_current_sheet(Some(sheet_name.trim()));
+                } else if let Some(sheet_name) = command.strip_prefix("rename ") {
+                    self.rename_current_sheet(sheet_name);
+                } else if let Some(sheet) = ["split ", "sp "]
+                    .iter()
+                    .find_map(|prefix| command.strip_prefix(prefix))
+                {
+                    self.split_view(SplitDirection::Horizontal, Some(sheet.trim()));
+                } else if let Some(sheet) = ["vsplit ", "vs "]
+                    .iter()
+                    .find_map(|prefix| command.strip_prefix(prefix))
+                {
+                    self.split_view(SplitDirection::Vertical, Some(sheet.trim()));
                 } else if command.starts_with("sheet ") {
                     let sheet_name = command.strip_prefix("sheet ").unwrap().trim();
                     self.switch_to_sheet(sheet_name);
                 } else if command.starts_with("dr") {
-                    self.handle_delete_row_command(&command);
+                    self.handle_delete_row_command(command);
                 } else if command.starts_with("dc") {
-                    self.handle_delete_column_command(&command);
+                    self.handle_delete_column_command(command);
                 } else if let Some(cell_ref) = command.strip_prefix("freeze ") {
                     self.handle_freeze_command(cell_ref.trim());
+                } else if let Some(row) = command.strip_prefix("headerrow ") {
+                    self.set_header_row(row);
+                } else if let Some(args) = command.strip_prefix("cf ") {
+                    self.handle_cf_command(args);
+                } else if let Some(arg) = command.strip_prefix("nocf ") {
+                    self.remove_conditional_formats(arg);
+                } else if let Some(arg) = command.strip_prefix("messages ") {
+                    self.handle_messages_command(arg);
+                } else if let Some(args) = command.strip_prefix("transpose ") {
+                    self.handle_transpose_command(args);
+                } else if let Some(arg) = command.strip_prefix("mr ") {
+                    match arg.trim().parse::<isize>() {
+                        Ok(offset) => self.move_rows(offset),
+                        Err(_) => self.add_warning("Usage: :mr <n>".to_string()),
+                    }
+                } else if let Some(arg) = command.strip_prefix("mc ") {
+                    match arg.trim().parse::<isize>() {
+                        Ok(offset) => self.move_columns(offset),
+                        Err(_) => self.add_warning("Usage: :mc <n>".to_string()),
+                    }
+                } else if let Some(name) = command.strip_prefix("name ") {
+                    self.define_name_for_selection(name.trim());
+                } else if let Some(target) = command.strip_prefix("goto ") {
+                    self.goto_name(target.trim());
+                } else if let Some(args) = command.strip_prefix("joincols ") {
+                    self.handle_join_columns_command(args);
+                } else if let Some(args) = command.strip_prefix("paste ") {
+                    match PasteOptions::parse(args) {
+                        Ok(options) => self.paste_clipboard(options),
+                        Err(e) => self.add_error(format!(
+                            "{e}. Usage: :paste [values] [transpose] [skipblanks]"
+                        )),
+                    }
+                } else if let Some(args) = command.strip_prefix("dedupe ") {
+                    match parse_column_list(args) {
+                        Some(cols) => self.dedupe_rows(Some(cols)),
+                        None => self.add_warning("Usage: :dedupe [cols]".to_string()),
+                    }
+                } else if let Some(args) = command.strip_prefix("dupes ") {
+                    match parse_column_list(args) {
+                        Some(cols) => self.show_duplicate_rows(Some(cols)),
+                        None => self.add_warning("Usage: :dupes [cols]".to_string()),
+                    }
+                } else if let Some(arg) = command.strip_prefix("freq ") {
+                    match parse_column_list(arg).as_deref() {
+                        Some(&[col]) => self.show_frequencies(col),
+                        _ => self.add_warning("Usage: :freq [col]".to_string()),
+                    }
+                } else if let Some(label) = command.strip_prefix("col ") {
+                    self.jump_to_column_by_header(label);
+                } else if let Some(arg) = command.strip_prefix("trim ") {
+                    self.handle_trim_command(arg.trim());
+                } else if let Some(text) = command.strip_prefix("comment ") {
+                    let text = text.trim();
+                    if text.is_empty() {
+                        self.add_warning("Usage: :comment <text>".to_string());
+                    } else {
+                        self.set_comment(Some(text.to_string()));
+                    }
+                } else if let Some(args) = command.strip_prefix("fmt ") {
+                    self.handle_format_command(args);
+                } else if let Some(arg) = command.strip_prefix("exportdur ") {
+                    match arg.trim().parse::<DurationFormat>() {
+                        Ok(format) => {
+                            self.export_options.duration_format = format;
+                            self.add_notification(format!(
+                                "Export durations as: {}",
+                                format.name()
+                            ));
+                        }
+                        Err(()) => self.add_warning("Usage: :exportdur [iso|seconds]".to_string()),
+                    }
+                } else if let Some(arg) = command.strip_prefix("exporttz ") {
+                    self.handle_export_timezone_command(arg.trim());
+                } else if let Some(query) = command.strip_prefix("find ") {
+                    self.find_cells(query);
+                } else if let Some(args) = command
+                    .strip_prefix("set ")
+                    .or_else(|| command.strip_prefix("se "))
+                {
+                    self.handle_set_command(args);
+                } else if let Some(mode) = command.strip_prefix("sortmode ") {
+                    match SortMode::parse(mode.trim()) {
+                        Some(mode) => self.set_sort_mode(mode),
+                        None => self.add_warning("Usage: :sortmode [natural|lexical]".to_string()),
+                    }
                 } else {
-                    self.add_notification(format!("Unknown command: {}", command));
+                    self.add_warning(format!("Unknown command: {}", command));
+                }
+            }
+        }
+    }
+
+    fn handle_export_timezone_command(&mut self, arg: &str) {
+        if arg.is_empty() {
+            let current = self
+                .export_options
+                .timezone_offset
+                .map_or_else(|| "off".to_string(), |offset| offset.to_string());
+            self.add_notification(format!("Export timezone offset: {current}"));
+            return;
+        }
+
+        if arg.eq_ignore_ascii_case("off") {
+            self.export_options.timezone_offset = None;
+            self.add_notification("Export timezone offset: off".to_string());
+            return;
+        }
+
+        match ExportOptions::parse_timezone_offset(arg) {
+            Some(offset) => {
+                self.export_options.timezone_offset = Some(offset);
+                self.add_notification(format!("Export timezone offset: {offset}"));
+            }
+            None => self.add_warning("Usage: :exporttz [+HH:MM|off]".to_string()),
+        }
+    }
+
+    /// `:fmt [col] <spec|off>`: formats the selection or current cell, or with `col` whole
+    /// columns
+    fn handle_format_command(&mut self, args: &str) {
+        let args = args.trim();
+        let (whole_column, spec) = match args.split_once(char::is_whitespace) {
+            Some((first, rest)) if first.eq_ignore_ascii_case("col") => (true, rest.trim()),
+            _ => (false, args),
+        };
+
+        let format = if spec.eq_ignore_ascii_case("off") {
+            None
+        } else {
+            match spec.parse::<NumberFormat>() {
+                Ok(format) => Some(format),
+                Err(e) => {
+                    self.add_error(format!(
+                        "{e}. Usage: :fmt [col] <thousands|fixed|percent|currency|date|off> [...]"
+                    ));
+                    return;
                 }
             }
+        };
+
+        if whole_column {
+            self.set_column_number_format(format);
+        } else {
+            self.set_cell_number_format(format);
         }
     }
 
-    fn handle_freeze_command(&mut self, cell_ref: &str) {
-        let Some(cell) = parse_cell_reference(cell_ref) else {
-            self.add_notification("Usage: :freeze [cell]".to_string());
+    fn handle_trim_command(&mut self, arg: &str) {
+        if arg.eq_ignore_ascii_case("all") {
+            self.trim_cells(None);
+            return;
+        }
+
+        match parse_column_list(arg).as_deref() {
+            Some(&[col]) => self.trim_cells(Some(col)),
+            _ => self.add_warning("Usage: :trim [col|all]".to_string()),
+        }
+    }
+
+    fn handle_transpose_command(&mut self, args: &str) {
+        let mut range = None;
+        let mut to_new_sheet = false;
+
+        for arg in args.split_whitespace() {
+            if arg.eq_ignore_ascii_case("new") {
+                to_new_sheet = true;
+            } else if let Some(((row1, col1), (row2, col2))) = parse_range(arg) {
+                range = Some((
+                    (row1.min(row2), col1.min(col2)),
+                    (row1.max(row2), col1.max(col2)),
+                ));
+            } else {
+                self.add_warning("Usage: :transpose [range] [new]".to_string());
+                return;
+            }
+        }
+
+        self.transpose(range, to_new_sheet);
+    }
+
+    /// Parses `<start> <end> ["separator"] [new]`; an unquoted third word is also taken as the
+    /// separator, which defaults to a single space
+    fn handle_join_columns_command(&mut self, args: &str) {
+        let (words, quoted) = match args.split_once('"') {
+            Some((before, rest)) => match rest.rsplit_once('"') {
+                Some((separator, after)) => (format!("{before} {after}"), Some(separator)),
+                None => {
+                    self.add_warning(JOINCOLS_USAGE.to_string());
+                    return;
+                }
+            },
+            None => (args.to_string(), None),
+        };
+
+        let mut words: Vec<&str> = words.split_whitespace().collect();
+        let append = words
+            .last()
+            .is_some_and(|word| word.eq_ignore_ascii_case("new"));
+        if append {
+            words.pop();
+        }
+        let separator = match (quoted, words.len()) {
+            (Some(separator), 2) => separator,
+            (None, 3) => words.pop().unwrap_or(" "),
+            (None, 2) => " ",
+            _ => {
+                self.add_warning(JOINCOLS_USAGE.to_string());
+                return;
+            }
+        };
+
+        match (parse_column_list(words[0]), parse_column_list(words[1])) {
+            (Some(start), Some(end)) if start.len() == 1 && end.len() == 1 => {
+                self.join_columns(start[0], end[0], separator, append);
+            }
+            _ => self.add_warning(JOINCOLS_USAGE.to_string()),
+        }
+    }
+
+    fn handle_freeze_command(&mut self, target: &str) {
+        // `:freeze 2` pins rows 1-2 and `:freeze B` columns A-B, leaving the other axis as is
+        let frozen = &self.workbook.get_current_sheet().freeze_panes;
+        let (frozen_rows, frozen_cols) = (frozen.rows, frozen.cols);
+        if let Ok(rows) = target.parse::<usize>() {
+            if rows >= EXCEL_MAX_ROWS {
+                self.add_warning(format!("Cannot freeze {rows} rows"));
+                return;
+            }
+            self.freeze_at_cell((rows + 1, frozen_cols + 1));
+            return;
+        }
+        if target.len() <= 3 {
+            if let Some(cols) = col_name_to_index(target).filter(|&col| col > 0) {
+                if cols >= EXCEL_MAX_COLS {
+                    self.add_warning(format!("Cannot freeze columns up to {target}"));
+                    return;
+                }
+                self.freeze_at_cell((frozen_rows + 1, cols + 1));
+                return;
+            }
+        }
+
+        let Some(cell) = parse_cell_reference(target) else {
+            self.add_warning("Usage: :freeze [cell|rows|column]".to_string());
             return;
         };
 
@@ -113,33 +464,33 @@ impl AppState<'_> {
         let parts: Vec<&str> = cmd.split_whitespace().collect();
 
         if parts.len() < 2 {
-            self.add_notification("Usage: :cw [fit|min|number] [all]".to_string());
+            self.add_warning("Usage: :cw [fit|min|number] [all] [exact]".to_string());
             return;
         }
 
         let action = parts[1];
-        let apply_to_all = parts.len() > 2 && parts[2] == "all";
+        let apply_to_all = parts.get(2) == Some(&"all");
+        let exact = parts[2..].contains(&"exact");
 
         match action {
             "fit" => {
                 if apply_to_all {
-                    self.auto_adjust_column_width(None);
+                    self.auto_adjust_column_width(None, exact);
                 } else {
-                    self.auto_adjust_column_width(Some(self.selected_cell.1));
+                    self.auto_adjust_column_width(Some(self.selected_cell.1), exact);
                 }
             }
             "min" => {
                 if apply_to_all {
                     // Set all columns to minimum width
-                    let sheet = self.workbook.get_current_sheet();
-                    for col in 1..=sheet.max_cols {
-                        self.column_widths[col] = 5; // Minimum width
-                    }
+                    let max_cols = self.workbook.get_current_sheet().max_cols;
+                    let widths: Vec<(usize, usize)> = (1..=max_cols).map(|col| (col, 5)).collect();
+                    self.commit_column_widths(&widths);
                     self.add_notification("All columns set to minimum width".to_string());
                 } else {
                     // Set current column to minimum width
                     let col = self.selected_cell.1;
-                    self.column_widths[col] = 5; // Minimum width
+                    self.commit_column_widths(&[(col, 5)]); // Minimum width
                     self.add_notification(format!("Column {} set to minimum width", col));
                 }
             }
@@ -147,10 +498,10 @@ impl AppState<'_> {
                 // Try to parse as a number
                 if let Ok(width) = action.parse::<usize>() {
                     let col = self.selected_cell.1;
-                    self.column_widths[col] = width.clamp(5, 50); // Clamp between 5 and 50
+                    self.commit_column_widths(&[(col, width.clamp(5, 50))]); // Clamp between 5 and 50
                     self.add_notification(format!("Column {} width set to {}", col, width));
                 } else {
-                    self.add_notification(format!("Invalid column width: {}", action));
+                    self.add_warning(format!("Invalid column width: {}", action));
                 }
             }
         }
@@ -162,7 +513,7 @@ impl AppState<'_> {
         if parts.len() == 1 {
             // Delete current row
             if let Err(e) = self.delete_current_row() {
-                self.add_notification(format!("Failed to delete row: {e}"));
+                self.add_error(format!("Failed to delete row: {e}"));
             }
             return;
         }
@@ -171,10 +522,10 @@ impl AppState<'_> {
             // Delete specific row
             if let Ok(row) = parts[1].parse::<usize>() {
                 if let Err(e) = self.delete_row(row) {
-                    self.add_notification(format!("Failed to delete row {}: {}", row, e));
+                    self.add_error(format!("Failed to delete row {}: {}", row, e));
                 }
             } else {
-                self.add_notification(format!("Invalid row number: {}", parts[1]));
+                self.add_warning(format!("Invalid row number: {}", parts[1]));
             }
             return;
         }
@@ -185,18 +536,18 @@ impl AppState<'_> {
                 (parts[1].parse::<usize>(), parts[2].parse::<usize>())
             {
                 if let Err(e) = self.delete_rows(start_row, end_row) {
-                    self.add_notification(format!(
+                    self.add_error(format!(
                         "Failed to delete rows {} to {}: {}",
                         start_row, end_row, e
                     ));
                 }
             } else {
-                self.add_notification("Invalid row range".to_string());
+                self.add_warning("Invalid row range".to_string());
             }
             return;
         }
 
-        self.add_notification("Usage: :dr [row] [end_row]".to_string());
+        self.add_warning("Usage: :dr [row] [end_row]".to_string());
     }
 
     fn handle_delete_column_command(&mut self, cmd: &str) {
@@ -205,7 +556,7 @@ impl AppState<'_> {
         if parts.len() == 1 {
             // Delete current column
             if let Err(e) = self.delete_current_column() {
-                self.add_notification(format!("Failed to delete column: {e}"));
+                self.add_error(format!("Failed to delete column: {e}"));
             }
             return;
         }
@@ -217,7 +568,7 @@ impl AppState<'_> {
             // Try to parse as a column letter (A, B, C, etc.)
             if let Some(col) = col_name_to_index(&col_str) {
                 if let Err(e) = self.delete_column(col) {
-                    self.add_notification(format!("Failed to delete column {}: {}", col_str, e));
+                    self.add_error(format!("Failed to delete column {}: {}", col_str, e));
                 }
                 return;
             }
@@ -225,12 +576,12 @@ impl AppState<'_> {
             // Try to parse as a column number
             if let Ok(col) = col_str.parse::<usize>() {
                 if let Err(e) = self.delete_column(col) {
-                    self.add_notification(format!("Failed to delete column {}: {}", col, e));
+                    self.add_error(format!("Failed to delete column {}: {}", col, e));
                 }
                 return;
             }
 
-            self.add_notification(format!("Invalid column: {}", col_str));
+            self.add_warning(format!("Invalid column: {}", col_str));
             return;
         }
 
@@ -246,18 +597,18 @@ impl AppState<'_> {
 
             if let (Some(start), Some(end)) = (start_col, end_col) {
                 if let Err(e) = self.delete_columns(start, end) {
-                    self.add_notification(format!(
+                    self.add_error(format!(
                         "Failed to delete columns {} to {}: {}",
                         start_col_str, end_col_str, e
                     ));
                 }
             } else {
-                self.add_notification("Invalid column range".to_string());
+                self.add_warning("Invalid column range".to_string());
             }
             return;
         }
 
-        self.add_notification("Usage: :dc [col] [end_col]".to_string());
+        self.add_warning("Usage: :dc [col] [end_col]".to_string());
     }
 
     fn handle_json_export_command(&mut self, cmd: &str) {
@@ -282,16 +633,16 @@ impl AppState<'_> {
             // No arguments provided, use default values
             vec!["h", "1"] // Default to horizontal headers with 1 header row
         } else {
-            self.add_notification("Invalid JSON export command".to_string());
+            self.add_warning("Invalid JSON export command".to_string());
             return;
         };
 
         // Check if we have enough arguments for direction and header count
         if parts.len() < 2 {
             if export_all {
-                self.add_notification("Usage: :eja [h|v] [rows]".to_string());
+                self.add_warning("Usage: :eja [h|v] [rows]".to_string());
             } else {
-                self.add_notification("Usage: :ej [h|v] [rows]".to_string());
+                self.add_warning("Usage: :ej [h|v] [rows]".to_string());
             }
             return;
         }
@@ -302,7 +653,7 @@ impl AppState<'_> {
         let direction = match direction_str.parse::<HeaderDirection>() {
             Ok(dir) => dir,
             Err(_) => {
-                self.add_notification(format!(
+                self.add_warning(format!(
                     "Invalid header direction: {}. Use 'h' or 'v'",
                     direction_str
                 ));
@@ -313,7 +664,7 @@ impl AppState<'_> {
         let header_count = match header_count_str.parse::<usize>() {
             Ok(count) => count,
             Err(_) => {
-                self.add_notification(format!("Invalid header count: {}", header_count_str));
+                self.add_warning(format!("Invalid header count: {}", header_count_str));
                 return;
             }
         };
@@ -343,12 +694,19 @@ impl AppState<'_> {
 
         // Export to JSON
         let result = if export_all {
-            export_all_sheets_json(&self.workbook, direction, header_count, &new_filepath)
+            export_all_sheets_json(
+                &self.workbook,
+                direction,
+                header_count,
+                &self.export_options,
+                &new_filepath,
+            )
         } else {
             export_json(
                 self.workbook.get_current_sheet(),
                 direction,
                 header_count,
+                &self.export_options,
                 &new_filepath,
             )
         };
@@ -358,12 +716,12 @@ impl AppState<'_> {
                 self.add_notification(format!("Exported to {}", new_filepath.display()));
             }
             Err(e) => {
-                self.add_notification(format!("Export failed: {e}"));
+                self.add_error(format!("Export failed: {e}"));
             }
         }
     }
 
-    fn jump_to_cell(&mut self, cell_ref: (usize, usize)) {
+    pub(crate) fn jump_to_cell(&mut self, cell_ref: (usize, usize)) {
         let (row, col) = cell_ref; // Fixed: cell_ref is already (row, col)
 
         if row > EXCEL_MAX_ROWS || col > EXCEL_MAX_COLS {
@@ -381,19 +739,55 @@ impl AppState<'_> {
     }
 }
 
+const JOINCOLS_USAGE: &str = "Usage: :joincols <start> <end> [\"separator\"] [new]";
+
+/// Parse columns given as letters or 1-based numbers, separated by spaces or commas,
+/// with `A:C` spans expanded (e.g. "A C", "a,c", "B:D 7")
+fn parse_column_list(args: &str) -> Option<Vec<usize>> {
+    let parse_col = |name: &str| {
+        let name = name.trim();
+        if name.is_empty() {
+            return None;
+        }
+        col_name_to_index(name)
+            .or_else(|| name.parse::<usize>().ok())
+            .filter(|&col| (1..=EXCEL_MAX_COLS).contains(&col))
+    };
+
+    let mut cols = Vec::new();
+    for part in args.split([' ', ',']).filter(|part| !part.is_empty()) {
+        if let Some((start, end)) = part.split_once(':') {
+            let (start, end) = (parse_col(start)?, parse_col(end)?);
+            cols.extend(start.min(end)..=start.max(end));
+        } else {
+            cols.push(parse_col(part)?);
+        }
+    }
+
+    if cols.is_empty() {
+        return None;
+    }
+    cols.sort_unstable();
+    cols.dedup();
+    Some(cols)
+}
+
 #[cfg(test)]
 mod tests {
-    use super::parse_cell_reference;
+    use super::{parse_cell_reference, parse_column_list};
     use crate::app::AppState;
-    use crate::excel::{Cell, FreezePanes, Sheet, Workbook, EXCEL_MAX_COLS, EXCEL_MAX_ROWS};
+    use crate::excel::{
+        Cell, CellGrid, ColumnFormats, FreezePanes, Sheet, SheetProtection, SheetVisibility,
+        Workbook, EXCEL_MAX_COLS, EXCEL_MAX_ROWS,
+    };
     use std::path::PathBuf;
 
     fn app_with_sheet() -> AppState<'static> {
-        let mut data = vec![vec![Cell::empty(); 3]; 3];
-        data[1][1] = Cell::new("Name".to_string(), false);
-        data[1][2] = Cell::new("Name".to_string(), false);
-        data[2][1] = Cell::new("Ada".to_string(), false);
-        data[2][2] = Cell::new("10".to_string(), false);
+        let mut data = CellGrid::new();
+        data.set(1, 1, Cell::new("Name".to_string(), false));
+        data.set(1, 2, Cell::new("Name".to_string(), false));
+        data.set(2, 1, Cell::new("Ada".to_string(), false));
+        data.set(2, 2, Cell::new("10".to_string(), false));
         let sheet = Sheet {
             name: "Data".to_string(),
             data,
@@ -401,6 +795,11 @@ mod tests {
             max_cols: 2,
             is_loaded: true,
             freeze_panes: FreezePanes::none(),
+            column_formats: ColumnFormats::new(),
+            protection: SheetProtection::Unprotected,
+            visibility: SheetVisibility::Visible,
+            tab_color: None,
+            conditional_formats: Vec::new(),
         };
 
         AppState::new(
@@ -416,6 +815,15 @@ mod tests {
         assert_eq!(parse_cell_reference("BC12"), Some((12, 55)));
     }
 
+    #[test]
+    fn parses_column_lists_with_spans() {
+        assert_eq!(parse_column_list("A c"), Some(vec![1, 3]));
+        assert_eq!(parse_column_list("D:B,7"), Some(vec![2, 3, 4, 7]));
+        assert_eq!(parse_column_list("A 1"), Some(vec![1]));
+        assert_eq!(parse_column_list("A 0"), None);
+        assert_eq!(parse_column_list("A?"), None);
+    }
+
     #[test]
     fn ignores_commands_with_non_ascii_arguments() {
         assert_eq!(parse_cell_reference("addsheet 测试1"), None);
@@ -432,7 +840,9 @@ mod tests {
         assert_eq!(app.selected_cell, (3, 1));
         assert_eq!(app.get_cell_content(3, 1), "");
         assert_eq!(
-            app.notification_messages.last().map(String::as_str),
+            app.notification_messages
+                .last()
+                .map(|notification| notification.text.as_str()),
             Some("Jumped to cell A3")
         );
     }
@@ -457,7 +867,9 @@ mod tests {
 
         assert_eq!(app.selected_cell, (1, 1));
         assert_eq!(
-            app.notification_messages.last().map(String::as_str),
+            app.notification_messages
+                .last()
+                .map(|notification| notification.text.as_str()),
             Some("Cell reference out of range: XFE1048577")
         );
     }
@@ -493,6 +905,41 @@ mod tests {
         assert!(!app.workbook.get_current_sheet().freeze_panes.is_frozen());
     }
 
+    #[test]
+    fn freeze_command_pins_header_rows_and_columns_separately() {
+        let mut app = app_with_sheet();
+
+        app.input_buffer = "freeze 1".to_string();
+        app.execute_command();
+        let freeze_panes = &app.workbook.get_current_sheet().freeze_panes;
+        assert_eq!((freeze_panes.rows, freeze_panes.cols), (1, 0));
+
+        app.input_buffer = "freeze b".to_string();
+        app.execute_command();
+        let freeze_panes = &app.workbook.get_current_sheet().freeze_panes;
+        assert_eq!((freeze_panes.rows, freeze_panes.cols), (1, 2));
+        assert_eq!(
+            app.notification_messages
+                .last()
+                .map(|notification| notification.text.as_str()),
+            Some("Frozen panes at C2")
+        );
+
+        app.input_buffer = "freeze 0".to_string();
+        app.execute_command();
+        let freeze_panes = &app.workbook.get_current_sheet().freeze_panes;
+        assert_eq!((freeze_panes.rows, freeze_panes.cols), (0, 2));
+
+        app.input_buffer = "freeze 1x".to_string();
+        app.execute_command();
+        assert_eq!(
+            app.notification_messages
+                .last()
+                .map(|notification| notification.text.as_str()),
+            Some("Usage: :freeze [cell|rows|column]")
+        );
+    }
+
     #[test]
     fn unfreeze_command_clears_freeze_panes() {
         let mut app = app_with_sheet();
@@ -503,4 +950,70 @@ mod tests {
 
         assert!(!app.workbook.get_current_sheet().freeze_panes.is_frozen());
     }
+
+    #[test]
+    fn natural_sort_mode_orders_search_matches_by_value() {
+        let mut app = app_with_sheet();
+        app.workbook
+            .set_cell_value(1, 1, "item10".to_string())
+            .unwrap();
+        app.workbook
+            .set_cell_value(1, 2, "item2".to_string())
+            .unwrap();
+        app.workbook
+            .set_cell_value(2, 1, "Item1".to_string())
+            .unwrap();
+
+        app.input_buffer = "sortmode natural".to_string();
+        app.execute_command();
+        assert_eq!(app.find_all_matches("item"), vec![(2, 1), (1, 2), (1, 1)]);
+
+        app.search_query = "item".to_string();
+        app.search_results = app.find_all_matches("item");
+        app.selected_cell = (1, 2);
+        app.jump_to_next_search_result();
+        assert_eq!(app.selected_cell, (1, 1));
+        app.jump_to_next_search_result();
+        assert_eq!(app.selected_cell, (2, 1));
+
+        app.input_buffer = "sortmode lexical".to_string();
+        app.execute_command();
+        assert_eq!(app.search_results, vec![(1, 1), (1, 2), (2, 1)]);
+    }
+
+    #[test]
+    fn comment_commands_edit_notes_and_undo_in_one_step() {
+        let mut app = app_with_sheet();
+        app.selected_cell = (2, 1);
+
+        app.input_buffer = "comment Checked by finance".to_string();
+        app.execute_command();
+        assert_eq!(app.cell_comment(2, 1), Some("Checked by finance"));
+        assert_eq!(app.get_cell_content(2, 1), "Ada");
+        assert!(app.workbook.is_modified());
+
+        app.input_buffer = "delcomment".to_string();
+        app.execute_command();
+        assert_eq!(app.cell_comment(2, 1), None);
+
+        app.undo().unwrap();
+        assert_eq!(app.cell_comment(2, 1), Some("Checked by finance"));
+        app.undo().unwrap();
+        assert_eq!(app.cell_comment(2, 1), None);
+        app.redo().unwrap();
+        assert_eq!(app.cell_comment(2, 1), Some("Checked by finance"));
+    }
+
+    #[test]
+    fn version_command_reports_build_info() {
+        let mut app = app_with_sheet();
+
+        app.input_buffer = "version".to_string();
+        app.execute_command();
+
+        let message = &app.notification_messages.last().unwrap().text;
+        assert!(message.starts_with(&format!("excel-cli {}", env!("CARGO_PKG_VERSION"))));
+        assert!(message.contains(crate::build_info::GIT_HASH));
+        assert!(message.contains("features: "));
+    }
 }
@@ -0,0 +1,131 @@
+//! Where excel-cli keeps per-user settings
+
+use std::path::PathBuf;
+#[cfg(feature = "tui")]
+use std::{collections::BTreeMap, path::Path};
+
+#[cfg(feature = "tui")]
+use serde::Deserialize;
+
+/// Overrides the configuration directory, e.g. for portable installs or tests
+pub const CONFIG_DIR_ENV: &str = "EXCEL_CLI_CONFIG_DIR";
+
+/// The TUI's settings file in the configuration directory
+#[cfg(feature = "tui")]
+pub const CONFIG_FILE: &str = "config.toml";
+
+/// `$EXCEL_CLI_CONFIG_DIR`, else `excel-cli` under the platform's config directory
+/// (`$XDG_CONFIG_HOME` or `~/.config` on Unix, `%APPDATA%` on Windows)
+#[must_use]
+pub fn config_dir() -> Option<PathBuf> {
+    if let Some(dir) = non_empty_env(CONFIG_DIR_ENV) {
+        return Some(PathBuf::from(dir));
+    }
+
+    let base = if cfg!(windows) {
+        non_empty_env("APPDATA").map(PathBuf::from)
+    } else {
+        non_empty_env("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| non_empty_env("HOME").map(|home| PathBuf::from(home).join(".config")))
+    };
+
+    base.map(|dir| dir.join("excel-cli"))
+}
+
+fn non_empty_env(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|value| !value.is_empty())
+}
+
+/// What `config.toml` sets. Every section and key is optional
+#[cfg(feature = "tui")]
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Settings {
+    pub theme: ThemeSettings,
+    pub keys: KeySettings,
+    /// `:set` options to start with, like `stripes = true` or `searchscope = "column"`
+    pub options: BTreeMap<String, OptionSetting>,
+}
+
+/// The value of an option in the `[options]` section
+#[cfg(feature = "tui")]
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum OptionSetting {
+    Switch(bool),
+    Value(String),
+}
+
+/// The `[theme]` section: a built-in `preset`, and colors replacing the preset's, such as
+/// `accent = "#38bdf8"` or `selection = "blue"`
+#[cfg(feature = "tui")]
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ThemeSettings {
+    pub preset: Option<String>,
+    #[serde(flatten)]
+    pub colors: BTreeMap<String, String>,
+}
+
+/// The `[keys.normal]` and `[keys.command]` sections: keys, like `"n"` or `"pagedown"`, mapped
+/// to the names of actions, like `"move_down"`, or to `"none"`
+#[cfg(feature = "tui")]
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct KeySettings {
+    pub normal: BTreeMap<String, String>,
+    pub command: BTreeMap<String, String>,
+}
+
+#[cfg(feature = "tui")]
+impl Settings {
+    /// Reads `config.toml` from the configuration directory. A missing file gives the defaults
+    pub fn load() -> Result<Self, String> {
+        Self::load_from(config_dir().as_deref())
+    }
+
+    pub(crate) fn load_from(config_dir: Option<&Path>) -> Result<Self, String> {
+        let Some(path) = config_dir.map(|dir| dir.join(CONFIG_FILE)) else {
+            return Ok(Self::default());
+        };
+        let Ok(text) = std::fs::read_to_string(&path) else {
+            return Ok(Self::default());
+        };
+        toml::from_str(&text).map_err(|e| format!("Invalid {}: {}", path.display(), e.message()))
+    }
+}
+
+#[cfg(all(test, feature = "tui"))]
+mod tests {
+    use super::{OptionSetting, Settings, CONFIG_FILE};
+
+    #[test]
+    fn settings_are_read_from_config_toml() {
+        let dir = std::env::temp_dir().join("excel_cli_settings_test");
+        let _ = std::fs::remove_dir_all(&dir);
+        assert!(Settings::load_from(Some(&dir))
+            .unwrap()
+            .theme
+            .preset
+            .is_none());
+
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join(CONFIG_FILE),
+            "[theme]\npreset = \"light\"\naccent = \"#ff8800\"\n\n[options]\nstripes = true\n",
+        )
+        .unwrap();
+        let settings = Settings::load_from(Some(&dir)).unwrap();
+        assert_eq!(settings.theme.preset.as_deref(), Some("light"));
+        assert_eq!(settings.theme.colors["accent"], "#ff8800");
+        assert_eq!(settings.options["stripes"], OptionSetting::Switch(true));
+
+        std::fs::write(dir.join(CONFIG_FILE), "[colours]\n").unwrap();
+        assert!(Settings::load_from(Some(&dir))
+            .unwrap_err()
+            .contains("unknown field `colours`"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -1,10 +1,26 @@
-#[derive(Clone)]
+use std::mem::size_of;
+
+use crate::excel::{IsoDuration, NumberFormat};
+
+#[derive(Clone, PartialEq)]
 pub struct Cell {
     pub value: String,
     pub formula: Option<String>,
     pub is_formula: bool,
     pub cell_type: CellType,
     pub original_type: Option<DataTypeInfo>,
+    /// Note attached to the cell, read from and saved to xlsx comments
+    pub comment: Option<String>,
+    /// Link target read from xlsx hyperlinks; `#Sheet!A1` for places inside the workbook
+    pub hyperlink: Option<String>,
+    /// Display format set with `:fmt`, overriding the column's format
+    pub number_format: Option<NumberFormat>,
+    /// Set when the cell's style leaves it editable on a protected sheet
+    pub unlocked: bool,
+    /// The solid fill of the cell's xlsx style, as 0xRRGGBB
+    pub fill_color: Option<u32>,
+    /// The text color of the cell's xlsx style, as 0xRRGGBB
+    pub font_color: Option<u32>,
 }
 
 #[derive(Clone, PartialEq)]
@@ -26,7 +42,7 @@ pub enum DataTypeInfo {
     DateTime(f64),
     Duration(f64),
     DateTimeIso(String),
-    DurationIso(String),
+    DurationIso(IsoDuration),
     Error,
 }
 
@@ -63,16 +79,70 @@ impl Cell {
             is_formula,
             cell_type,
             original_type,
+            comment: None,
+            hyperlink: None,
+            number_format: None,
+            unlocked: false,
+            fill_color: None,
+            font_color: None,
         }
     }
 
-    pub fn empty() -> Self {
+    /// Copies the comment, hyperlink, number format, lock state and colors of `other`, which
+    /// stay with a cell when its value changes
+    pub fn inherit_annotations(&mut self, other: &Cell) {
+        self.comment.clone_from(&other.comment);
+        self.hyperlink.clone_from(&other.hyperlink);
+        self.number_format.clone_from(&other.number_format);
+        self.unlocked = other.unlocked;
+        self.fill_color = other.fill_color;
+        self.font_color = other.font_color;
+    }
+
+    /// Whether the cell holds no value, formula or annotation, so it can be left out of the
+    /// sheet's grid
+    #[must_use]
+    pub fn is_blank(&self) -> bool {
+        self.value.is_empty()
+            && self.formula.is_none()
+            && self.comment.is_none()
+            && self.hyperlink.is_none()
+            && self.number_format.is_none()
+            && !self.unlocked
+            && self.fill_color.is_none()
+            && self.font_color.is_none()
+    }
+
+    /// Roughly the bytes the cell takes up, with the text it holds
+    #[must_use]
+    pub fn estimated_bytes(&self) -> usize {
+        let iso = match &self.original_type {
+            Some(DataTypeInfo::DateTimeIso(text)) => text.capacity(),
+            _ => 0,
+        };
+        [&self.formula, &self.comment, &self.hyperlink]
+            .into_iter()
+            .flatten()
+            .map(String::capacity)
+            .sum::<usize>()
+            + self.value.capacity()
+            + iso
+            + size_of::<Self>()
+    }
+
+    pub const fn empty() -> Self {
         Self {
             value: String::new(),
             formula: None,
             is_formula: false,
             cell_type: CellType::Empty,
             original_type: Some(DataTypeInfo::Empty),
+            comment: None,
+            hyperlink: None,
+            number_format: None,
+            unlocked: false,
+            fill_color: None,
+            font_color: None,
         }
     }
 }
@@ -0,0 +1,201 @@
+use crate::excel::{numeric_value, Cell, EXCEL_MAX_COLS, EXCEL_MAX_ROWS};
+use crate::utils::{cell_reference, col_name_to_index, index_to_col_name, parse_cell_reference};
+
+/// How a conditional format compares a cell with its value
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CfOperator {
+    Equal,
+    NotEqual,
+    Greater,
+    GreaterOrEqual,
+    Less,
+    LessOrEqual,
+}
+
+impl CfOperator {
+    #[must_use]
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Self::Equal => "=",
+            Self::NotEqual => "<>",
+            Self::Greater => ">",
+            Self::GreaterOrEqual => ">=",
+            Self::Less => "<",
+            Self::LessOrEqual => "<=",
+        }
+    }
+}
+
+/// A rule coloring the cells of a range whose value meets a condition, like Excel's
+/// "Highlight Cells Rules"
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConditionalFormat {
+    /// Top-left and bottom-right cells of the range the rule covers, 1-based
+    pub start: (usize, usize),
+    pub end: (usize, usize),
+    pub operator: CfOperator,
+    /// What cells are compared with: a number, or text matched ignoring case
+    pub value: String,
+    pub fill: Option<u32>, // 0xRRGGBB
+    pub font: Option<u32>, // 0xRRGGBB
+}
+
+impl ConditionalFormat {
+    /// Reads a condition like `>100`, `<>done` or `=0`; a bare value means `=`
+    #[must_use]
+    pub fn parse_condition(condition: &str) -> Option<(CfOperator, String)> {
+        let condition = condition.trim();
+        let (operator, value) = [
+            (">=", CfOperator::GreaterOrEqual),
+            ("<=", CfOperator::LessOrEqual),
+            ("<>", CfOperator::NotEqual),
+            ("!=", CfOperator::NotEqual),
+            (">", CfOperator::Greater),
+            ("<", CfOperator::Less),
+            ("=", CfOperator::Equal),
+        ]
+        .iter()
+        .find_map(|&(prefix, operator)| {
+            condition
+                .strip_prefix(prefix)
+                .map(|value| (operator, value.trim()))
+        })
+        .unwrap_or((CfOperator::Equal, condition));
+
+        (!value.is_empty()).then(|| (operator, value.to_string()))
+    }
+
+    /// The condition as `:cf` takes it, like `>100`
+    #[must_use]
+    pub fn condition(&self) -> String {
+        format!("{}{}", self.operator.symbol(), self.value)
+    }
+
+    /// The range as `:cf` takes it: `C` or `C:E` for whole columns, else `C2:C50`
+    #[must_use]
+    pub fn range_label(&self) -> String {
+        if self.start.0 == 1 && self.end.0 == EXCEL_MAX_ROWS {
+            let (first, last) = (
+                index_to_col_name(self.start.1),
+                index_to_col_name(self.end.1),
+            );
+            return if first == last {
+                first
+            } else {
+                format!("{first}:{last}")
+            };
+        }
+        if self.start == self.end {
+            return cell_reference(self.start);
+        }
+        format!(
+            "{}:{}",
+            cell_reference(self.start),
+            cell_reference(self.end)
+        )
+    }
+
+    #[must_use]
+    pub fn covers(&self, row: usize, col: usize) -> bool {
+        (self.start.0..=self.end.0).contains(&row) && (self.start.1..=self.end.1).contains(&col)
+    }
+
+    /// Whether `cell` meets the condition. Numbers compare as numbers; text only matches
+    /// `=` and `<>`, ignoring case. Blank cells never match
+    #[must_use]
+    pub fn matches(&self, cell: &Cell) -> bool {
+        if cell.value.is_empty() {
+            return false;
+        }
+
+        if let (Some(actual), Ok(expected)) = (numeric_value(cell), self.value.parse::<f64>()) {
+            return match self.operator {
+                CfOperator::Equal => actual == expected,
+                CfOperator::NotEqual => actual != expected,
+                CfOperator::Greater => actual > expected,
+                CfOperator::GreaterOrEqual => actual >= expected,
+                CfOperator::Less => actual < expected,
+                CfOperator::LessOrEqual => actual <= expected,
+            };
+        }
+
+        let equal = cell.value.trim().to_lowercase() == self.value.to_lowercase();
+        match self.operator {
+            CfOperator::Equal => equal,
+            CfOperator::NotEqual => !equal,
+            _ => false,
+        }
+    }
+}
+
+/// Reads the range a conditional format covers: a column like `C`, columns like `C:E`, a
+/// cell like `C2` or cells like `C2:D50`. Corners are returned top-left first
+#[must_use]
+pub fn parse_cf_range(range: &str) -> Option<((usize, usize), (usize, usize))> {
+    let (first, last) = range
+        .trim()
+        .split_once(':')
+        .unwrap_or((range.trim(), range.trim()));
+    let column =
+        |name: &str| col_name_to_index(name).filter(|&col| (1..=EXCEL_MAX_COLS).contains(&col));
+
+    let (start, end) = match (column(first), column(last)) {
+        (Some(first), Some(last)) => ((1, first), (EXCEL_MAX_ROWS, last)),
+        _ => (parse_cell_reference(first)?, parse_cell_reference(last)?),
+    };
+    Some((
+        (start.0.min(end.0), start.1.min(end.1)),
+        (start.0.max(end.0), start.1.max(end.1)),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_cf_range, CfOperator, ConditionalFormat};
+    use crate::excel::{Cell, EXCEL_MAX_ROWS};
+
+    #[test]
+    fn conditions_compare_numbers_as_numbers_and_text_ignoring_case() {
+        let rule = |condition: &str| {
+            let (operator, value) = ConditionalFormat::parse_condition(condition).unwrap();
+            ConditionalFormat {
+                start: (1, 1),
+                end: (EXCEL_MAX_ROWS, 1),
+                operator,
+                value,
+                fill: Some(0xFF0000),
+                font: None,
+            }
+        };
+        let cell = |value: &str| Cell::new(value.to_string(), false);
+
+        assert_eq!(
+            ConditionalFormat::parse_condition(">= 10"),
+            Some((CfOperator::GreaterOrEqual, "10".to_string()))
+        );
+        assert_eq!(
+            ConditionalFormat::parse_condition("done"),
+            Some((CfOperator::Equal, "done".to_string()))
+        );
+        assert_eq!(ConditionalFormat::parse_condition(">"), None);
+
+        assert!(rule(">100").matches(&cell("150")));
+        assert!(!rule(">100").matches(&cell("100")));
+        assert!(rule("<=100").matches(&cell("99.5")));
+        assert!(!rule("<100").matches(&cell("")));
+        assert!(!rule(">100").matches(&cell("n/a")));
+        assert!(rule("=Done").matches(&cell("done")));
+        assert!(rule("!=done").matches(&cell("open")));
+        assert_eq!(rule("!=done").condition(), "<>done");
+
+        assert_eq!(parse_cf_range("c"), Some(((1, 3), (EXCEL_MAX_ROWS, 3))));
+        assert_eq!(parse_cf_range("E:C"), Some(((1, 3), (EXCEL_MAX_ROWS, 5))));
+        assert_eq!(parse_cf_range("D9:C2"), Some(((2, 3), (9, 4))));
+        assert_eq!(parse_cf_range("C2:E"), None);
+        let mut columns = rule(">1");
+        (columns.start, columns.end) = parse_cf_range("C:E").unwrap();
+        assert_eq!(columns.range_label(), "C:E");
+        assert!(columns.covers(500, 4));
+        assert!(!columns.covers(500, 6));
+    }
+}
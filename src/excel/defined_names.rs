@@ -0,0 +1,161 @@
+use crate::excel::{EXCEL_MAX_COLS, EXCEL_MAX_ROWS};
+use crate::utils::{cell_reference, parse_cell_reference};
+
+const MAX_NAME_LEN: usize = 255;
+
+/// A sheet name with the top-left and bottom-right corners of a range on it
+pub type SheetRange = (String, (usize, usize), (usize, usize));
+
+/// A workbook-level defined name such as `Totals` referring to `Summary!$B$2:$B$9`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DefinedName {
+    pub name: String,
+    /// The formula the name refers to, without a leading `=`
+    pub refers_to: String,
+}
+
+impl DefinedName {
+    #[must_use]
+    pub fn new(name: impl Into<String>, refers_to: impl Into<String>) -> Self {
+        let refers_to = refers_to.into();
+        Self {
+            name: name.into(),
+            refers_to: refers_to
+                .strip_prefix('=')
+                .unwrap_or(&refers_to)
+                .to_string(),
+        }
+    }
+
+    /// A name covering `start..=end` on `sheet`, written with absolute references
+    #[must_use]
+    pub fn for_range(
+        name: impl Into<String>,
+        sheet: &str,
+        start: (usize, usize),
+        end: (usize, usize),
+    ) -> Self {
+        let absolute = |cell: (usize, usize)| {
+            let reference = cell_reference(cell);
+            let digits = reference.find(|c: char| c.is_ascii_digit()).unwrap_or(0);
+            format!("${}${}", &reference[..digits], &reference[digits..])
+        };
+        let range = if start == end {
+            absolute(start)
+        } else {
+            format!("{}:{}", absolute(start), absolute(end))
+        };
+        Self::new(name, format!("{}!{range}", quote_sheet_name(sheet)))
+    }
+
+    /// The sheet and cell range the name points at, when it refers to a single block of
+    /// cells rather than a constant or formula
+    #[must_use]
+    pub fn target(&self) -> Option<SheetRange> {
+        parse_sheet_range(&self.refers_to)
+    }
+}
+
+/// Parses `Sheet1!$A$1:$B$3` or `'Q1 Sales'!B2` into the sheet name and the range corners
+#[must_use]
+pub fn parse_sheet_range(text: &str) -> Option<SheetRange> {
+    let (sheet_part, cells) = text.trim().rsplit_once('!')?;
+    let sheet = match sheet_part
+        .strip_prefix('\'')
+        .and_then(|name| name.strip_suffix('\''))
+    {
+        Some(quoted) => quoted.replace("''", "'"),
+        None => sheet_part.to_string(),
+    };
+    if sheet.is_empty() {
+        return None;
+    }
+
+    let cells = cells.replace('$', "");
+    let (start, end) = match cells.split_once(':') {
+        Some((start, end)) => (parse_cell_reference(start)?, parse_cell_reference(end)?),
+        None => {
+            let cell = parse_cell_reference(&cells)?;
+            (cell, cell)
+        }
+    };
+
+    Some((
+        sheet,
+        (start.0.min(end.0), start.1.min(end.1)),
+        (start.0.max(end.0), start.1.max(end.1)),
+    ))
+}
+
+/// Whether Excel accepts `name` as a defined name: a letter, `_` or `\` first, then letters,
+/// digits, `_` or `.`, and not something that reads as a cell reference such as `AB12` or `R1C1`
+#[must_use]
+pub fn is_valid_defined_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    let Some(first) = chars.next() else {
+        return false;
+    };
+    if name.len() > MAX_NAME_LEN
+        || !(first.is_alphabetic() || first == '_' || first == '\\')
+        || !chars.all(|c| c.is_alphanumeric() || c == '_' || c == '.')
+    {
+        return false;
+    }
+
+    let upper = name.to_ascii_uppercase();
+    let looks_like_r1c1 = upper
+        .strip_prefix('R')
+        .map(|rest| rest.trim_start_matches(|c: char| c.is_ascii_digit()))
+        .and_then(|rest| rest.strip_prefix('C'))
+        .is_some_and(|rest| rest.chars().all(|c| c.is_ascii_digit()));
+    let is_single_letter_rc = upper == "R" || upper == "C";
+
+    let looks_like_a1 = parse_cell_reference(name)
+        .is_some_and(|(row, col)| row <= EXCEL_MAX_ROWS && col <= EXCEL_MAX_COLS);
+
+    !looks_like_a1 && !looks_like_r1c1 && !is_single_letter_rc
+}
+
+/// The sheet name as formulas write it, quoted when it has spaces or punctuation
+pub fn quote_sheet_name(sheet: &str) -> String {
+    if sheet.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        sheet.to_string()
+    } else {
+        format!("'{}'", sheet.replace('\'', "''"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_valid_defined_name, parse_sheet_range, DefinedName};
+
+    #[test]
+    fn builds_and_parses_sheet_ranges() {
+        let name = DefinedName::for_range("Totals", "Q1 Sales", (2, 2), (9, 3));
+        assert_eq!(name.refers_to, "'Q1 Sales'!$B$2:$C$9");
+        assert_eq!(
+            name.target(),
+            Some(("Q1 Sales".to_string(), (2, 2), (9, 3)))
+        );
+
+        let cell = DefinedName::new("Rate", "=Data!$D$1");
+        assert_eq!(cell.refers_to, "Data!$D$1");
+        assert_eq!(cell.target(), Some(("Data".to_string(), (1, 4), (1, 4))));
+
+        assert_eq!(parse_sheet_range("0.075"), None);
+        assert_eq!(parse_sheet_range("SUM(Data!A1:A3)"), None);
+    }
+
+    #[test]
+    fn validates_names_like_excel() {
+        assert!(is_valid_defined_name("Totals"));
+        assert!(is_valid_defined_name("_tax.rate"));
+        assert!(is_valid_defined_name("Sales2024"));
+        assert!(!is_valid_defined_name(""));
+        assert!(!is_valid_defined_name("1st"));
+        assert!(!is_valid_defined_name("AB12"));
+        assert!(!is_valid_defined_name("R1C1"));
+        assert!(!is_valid_defined_name("c"));
+        assert!(!is_valid_defined_name("tax rate"));
+    }
+}
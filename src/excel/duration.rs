@@ -0,0 +1,169 @@
+use std::fmt;
+
+const SECONDS_PER_MINUTE: f64 = 60.0;
+const SECONDS_PER_HOUR: f64 = 3600.0;
+const SECONDS_PER_DAY: f64 = 86_400.0;
+
+/// An ISO-8601 duration such as `PT2H30M`, stored as a signed number of seconds.
+/// Years and months are rejected because their length depends on the calendar.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct IsoDuration {
+    pub total_seconds: f64,
+}
+
+impl IsoDuration {
+    /// Parses `[-]P[nW][nD][T[nH][nM][nS]]`, allowing a decimal fraction on any component
+    #[must_use]
+    pub fn parse(input: &str) -> Option<Self> {
+        let input = input.trim();
+        let (negative, rest) = match input.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, input.strip_prefix('+').unwrap_or(input)),
+        };
+        let rest = rest.strip_prefix(['P', 'p'])?;
+        if rest.is_empty() {
+            return None;
+        }
+
+        let mut total = 0.0;
+        let mut in_time = false;
+        let mut number = String::new();
+        let mut saw_component = false;
+
+        for ch in rest.chars() {
+            match ch.to_ascii_uppercase() {
+                'T' if !in_time && number.is_empty() => in_time = true,
+                '0'..='9' => number.push(ch),
+                '.' | ',' => number.push('.'),
+                unit => {
+                    let value: f64 = number.parse().ok()?;
+                    let scale = match (unit, in_time) {
+                        ('W', false) => 7.0 * SECONDS_PER_DAY,
+                        ('D', false) => SECONDS_PER_DAY,
+                        ('H', true) => SECONDS_PER_HOUR,
+                        ('M', true) => SECONDS_PER_MINUTE,
+                        ('S', true) => 1.0,
+                        _ => return None,
+                    };
+                    total += value * scale;
+                    number.clear();
+                    saw_component = true;
+                }
+            }
+        }
+
+        if !number.is_empty() || !saw_component {
+            return None;
+        }
+
+        Some(Self {
+            total_seconds: if negative { -total } else { total },
+        })
+    }
+
+    /// Days, hours, minutes and seconds of the absolute length
+    fn components(&self) -> (u64, u64, u64, f64) {
+        let total = self.total_seconds.abs();
+        let whole = total.trunc() as u64;
+        let seconds = (whole % 60) as f64 + total.fract();
+        (
+            whole / SECONDS_PER_DAY as u64,
+            (whole % SECONDS_PER_DAY as u64) / SECONDS_PER_HOUR as u64,
+            (whole % SECONDS_PER_HOUR as u64) / SECONDS_PER_MINUTE as u64,
+            seconds,
+        )
+    }
+
+    /// Canonical ISO-8601 form, e.g. `P1DT2H30M` or `PT0S`
+    #[must_use]
+    pub fn to_iso(&self) -> String {
+        let (days, hours, minutes, seconds) = self.components();
+        let mut iso = String::from(if self.total_seconds < 0.0 { "-P" } else { "P" });
+
+        if days > 0 {
+            iso.push_str(&format!("{days}D"));
+        }
+        if hours > 0 || minutes > 0 || seconds > 0.0 || days == 0 {
+            iso.push('T');
+            if hours > 0 {
+                iso.push_str(&format!("{hours}H"));
+            }
+            if minutes > 0 {
+                iso.push_str(&format!("{minutes}M"));
+            }
+            if seconds > 0.0 || (days == 0 && hours == 0 && minutes == 0) {
+                iso.push_str(&format!("{}S", format_seconds(seconds)));
+            }
+        }
+
+        iso
+    }
+}
+
+fn format_seconds(seconds: f64) -> String {
+    let rounded = (seconds * 1000.0).round() / 1000.0;
+    if rounded.fract() == 0.0 {
+        format!("{}", rounded as u64)
+    } else {
+        format!("{rounded}")
+    }
+}
+
+/// Readable form such as `2h 30m` or `1d 4h 5s`
+impl fmt::Display for IsoDuration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (days, hours, minutes, seconds) = self.components();
+        let mut parts = Vec::new();
+
+        if days > 0 {
+            parts.push(format!("{days}d"));
+        }
+        if hours > 0 {
+            parts.push(format!("{hours}h"));
+        }
+        if minutes > 0 {
+            parts.push(format!("{minutes}m"));
+        }
+        if seconds > 0.0 || parts.is_empty() {
+            parts.push(format!("{}s", format_seconds(seconds)));
+        }
+
+        if self.total_seconds < 0.0 {
+            write!(f, "-")?;
+        }
+        write!(f, "{}", parts.join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IsoDuration;
+
+    #[test]
+    fn parses_and_renders_iso_durations() {
+        let duration = IsoDuration::parse("PT2H30M").unwrap();
+        assert_eq!(duration.total_seconds, 9000.0);
+        assert_eq!(duration.to_string(), "2h 30m");
+        assert_eq!(duration.to_iso(), "PT2H30M");
+
+        let long = IsoDuration::parse("P1W1DT0H0M5.5S").unwrap();
+        assert_eq!(long.to_string(), "8d 5.5s");
+        assert_eq!(long.to_iso(), "P8DT5.5S");
+
+        let negative = IsoDuration::parse("-PT90S").unwrap();
+        assert_eq!(negative.to_string(), "-1m 30s");
+        assert_eq!(negative.to_iso(), "-PT1M30S");
+
+        assert_eq!(IsoDuration::parse("PT0S").unwrap().to_string(), "0s");
+        assert_eq!(IsoDuration::parse("P1D").unwrap().to_iso(), "P1D");
+    }
+
+    #[test]
+    fn rejects_calendar_units_and_malformed_text() {
+        assert_eq!(IsoDuration::parse("P1Y2M"), None);
+        assert_eq!(IsoDuration::parse("P"), None);
+        assert_eq!(IsoDuration::parse("PT"), None);
+        assert_eq!(IsoDuration::parse("PT5"), None);
+        assert_eq!(IsoDuration::parse("2h 30m"), None);
+    }
+}
@@ -0,0 +1,203 @@
+use std::ops::Range;
+
+use crate::excel::{EXCEL_MAX_COLS, EXCEL_MAX_ROWS};
+use crate::utils::col_name_to_index;
+
+/// Top-left and bottom-right corners of a referenced range
+type CellSpan = ((usize, usize), (usize, usize));
+
+/// A cell or range referenced by a formula, e.g. `B2`, `$A$1:C3` or `'Q1 Sales'!A1`
+#[derive(Clone, Debug, PartialEq)]
+pub struct FormulaReference {
+    /// Sheet qualifier, without quotes, when the reference names one
+    pub sheet: Option<String>,
+    pub start: (usize, usize),
+    pub end: (usize, usize),
+    /// Byte range of the reference in the formula text, including any sheet qualifier
+    pub span: Range<usize>,
+}
+
+impl FormulaReference {
+    #[must_use]
+    pub fn contains(&self, cell: (usize, usize)) -> bool {
+        (self.start.0..=self.end.0).contains(&cell.0)
+            && (self.start.1..=self.end.1).contains(&cell.1)
+    }
+}
+
+/// Extracts cell and range references from formula text. String literals and function names
+/// are skipped; whole-row/column references and defined names are not reported.
+#[must_use]
+pub fn formula_references(formula: &str) -> Vec<FormulaReference> {
+    let chars: Vec<(usize, char)> = formula.char_indices().collect();
+    let byte_at = |index: usize| chars.get(index).map_or(formula.len(), |&(pos, _)| pos);
+    let mut references = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let ch = chars[i].1;
+
+        if ch == '"' {
+            i = skip_quoted(&chars, i, '"');
+            continue;
+        }
+
+        if i > 0 && is_name_char(chars[i - 1].1) {
+            i += 1;
+            continue;
+        }
+
+        if ch == '\'' {
+            let end = skip_quoted(&chars, i, '\'');
+            if chars.get(end).map(|&(_, c)| c) == Some('!') {
+                let name: String = chars[i + 1..end - 1].iter().map(|&(_, c)| c).collect();
+                if let Some(((start, last), next)) = parse_range_at(&chars, end + 1) {
+                    references.push(FormulaReference {
+                        sheet: Some(name.replace("''", "'")),
+                        start,
+                        end: last,
+                        span: byte_at(i)..byte_at(next),
+                    });
+                    i = next;
+                    continue;
+                }
+            }
+            i = end;
+            continue;
+        }
+
+        if let Some(((start, last), next)) = parse_range_at(&chars, i) {
+            references.push(FormulaReference {
+                sheet: None,
+                start,
+                end: last,
+                span: byte_at(i)..byte_at(next),
+            });
+            i = next;
+            continue;
+        }
+
+        if is_name_char(ch) {
+            let mut end = i;
+            while end < chars.len() && is_name_char(chars[end].1) {
+                end += 1;
+            }
+            if chars.get(end).map(|&(_, c)| c) == Some('!') {
+                if let Some(((start, last), next)) = parse_range_at(&chars, end + 1) {
+                    let name: String = chars[i..end].iter().map(|&(_, c)| c).collect();
+                    references.push(FormulaReference {
+                        sheet: Some(name),
+                        start,
+                        end: last,
+                        span: byte_at(i)..byte_at(next),
+                    });
+                    i = next;
+                    continue;
+                }
+            }
+            i = end;
+            continue;
+        }
+
+        i += 1;
+    }
+
+    references
+}
+
+fn is_name_char(ch: char) -> bool {
+    ch.is_alphanumeric() || ch == '_' || ch == '.'
+}
+
+/// Index just past the closing quote of a quoted run starting at `start`. Doubled quotes are
+/// escapes.
+fn skip_quoted(chars: &[(usize, char)], start: usize, quote: char) -> usize {
+    let mut i = start + 1;
+    while i < chars.len() {
+        if chars[i].1 == quote {
+            if chars.get(i + 1).map(|&(_, c)| c) == Some(quote) {
+                i += 2;
+                continue;
+            }
+            return i + 1;
+        }
+        i += 1;
+    }
+    chars.len()
+}
+
+/// Parses `A1` or `A1:B2` at `start`, returning the normalized corners and the index after it
+fn parse_range_at(chars: &[(usize, char)], start: usize) -> Option<(CellSpan, usize)> {
+    let (first, next) = parse_cell_at(chars, start)?;
+    if chars.get(next).map(|&(_, c)| c) == Some(':') {
+        if let Some((second, after)) = parse_cell_at(chars, next + 1) {
+            let top_left = (first.0.min(second.0), first.1.min(second.1));
+            let bottom_right = (first.0.max(second.0), first.1.max(second.1));
+            return Some(((top_left, bottom_right), after));
+        }
+    }
+    Some(((first, first), next))
+}
+
+/// Parses `$A$1`-style cell references. The reference must not run into a longer name or be
+/// followed by `(`, so `LOG10(` is not mistaken for a cell.
+fn parse_cell_at(chars: &[(usize, char)], start: usize) -> Option<((usize, usize), usize)> {
+    let mut i = start;
+    let char_at = |index: usize| chars.get(index).map(|&(_, c)| c);
+
+    if char_at(i) == Some('$') {
+        i += 1;
+    }
+    let col_start = i;
+    while char_at(i).is_some_and(|c| c.is_ascii_alphabetic()) {
+        i += 1;
+    }
+    let col_len = i - col_start;
+    if col_len == 0 || col_len > 3 {
+        return None;
+    }
+    let col_name: String = chars[col_start..i].iter().map(|&(_, c)| c).collect();
+
+    if char_at(i) == Some('$') {
+        i += 1;
+    }
+    let row_start = i;
+    while char_at(i).is_some_and(|c| c.is_ascii_digit()) {
+        i += 1;
+    }
+    if i == row_start {
+        return None;
+    }
+    if char_at(i).is_some_and(|c| is_name_char(c) || c == '(' || c == '!') {
+        return None;
+    }
+
+    let row_text: String = chars[row_start..i].iter().map(|&(_, c)| c).collect();
+    let row = row_text.parse::<usize>().ok()?;
+    let col = col_name_to_index(&col_name)?;
+    if row == 0 || row > EXCEL_MAX_ROWS || col > EXCEL_MAX_COLS {
+        return None;
+    }
+
+    Some(((row, col), i))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::formula_references;
+
+    #[test]
+    fn extracts_cells_ranges_and_sheet_qualified_references() {
+        let formula = "=SUM($A$1:B3)+LOG10(C2)+'Q1 Sales'!D4+Data!A5&\"B7\"";
+        let references = formula_references(formula);
+
+        assert_eq!(references.len(), 4);
+        assert_eq!((references[0].start, references[0].end), ((1, 1), (3, 2)));
+        assert_eq!(&formula[references[0].span.clone()], "$A$1:B3");
+        assert_eq!(references[1].start, (2, 3));
+        assert_eq!(references[2].sheet.as_deref(), Some("Q1 Sales"));
+        assert_eq!(&formula[references[2].span.clone()], "'Q1 Sales'!D4");
+        assert_eq!(references[3].sheet.as_deref(), Some("Data"));
+        assert_eq!(references[3].start, (5, 1));
+    }
+}
@@ -0,0 +1,389 @@
+use std::collections::BTreeMap;
+use std::mem::size_of;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::excel::Cell;
+
+/// What a cell holding nothing reads as
+static EMPTY_CELL: Cell = Cell::empty();
+
+/// The last revision handed out. Grids share the count, so two grids with the same revision
+/// are copies of each other that neither has changed since
+static LAST_REVISION: AtomicU64 = AtomicU64::new(0);
+
+fn next_revision() -> u64 {
+    LAST_REVISION.fetch_add(1, Ordering::Relaxed) + 1
+}
+
+/// The cells of a sheet by 1-based (row, col). Only cells holding a value or an annotation
+/// take up memory, so a sheet with a few cells at row 100,000 costs no more than one with
+/// them at the top, and inserting or deleting rows and columns only moves the cells there are
+#[derive(Clone)]
+pub struct CellGrid {
+    rows: BTreeMap<usize, BTreeMap<usize, Cell>>,
+    /// Changes whenever a cell may have, so what is worked out from the cells, like the
+    /// search index, can tell it is out of date
+    revision: u64,
+}
+
+impl Default for CellGrid {
+    fn default() -> Self {
+        Self {
+            rows: BTreeMap::new(),
+            revision: next_revision(),
+        }
+    }
+}
+
+impl PartialEq for CellGrid {
+    fn eq(&self, other: &Self) -> bool {
+        self.rows == other.rows
+    }
+}
+
+impl CellGrid {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// The cell at `(row, col)`, or `None` when it holds nothing
+    #[must_use]
+    pub fn get(&self, row: usize, col: usize) -> Option<&Cell> {
+        self.rows.get(&row)?.get(&col)
+    }
+
+    /// The cell at `(row, col)`, empty when it holds nothing
+    #[must_use]
+    pub fn cell(&self, row: usize, col: usize) -> &Cell {
+        self.get(row, col).unwrap_or(&EMPTY_CELL)
+    }
+
+    /// Changes the cell at `(row, col)` with `change`, starting from an empty cell when it
+    /// holds nothing. A cell the change leaves blank is taken out
+    pub fn update_cell(&mut self, row: usize, col: usize, change: impl FnOnce(&mut Cell)) {
+        let mut cell = self.remove(row, col).unwrap_or_else(Cell::empty);
+        change(&mut cell);
+        self.set(row, col, cell);
+    }
+
+    /// Puts `cell` at `(row, col)`; a blank cell takes no room. Row and column 0 hold nothing
+    pub fn set(&mut self, row: usize, col: usize, cell: Cell) {
+        self.revision = next_revision();
+        if cell.is_blank() || row == 0 || col == 0 {
+            self.remove(row, col);
+        } else {
+            self.rows.entry(row).or_default().insert(col, cell);
+        }
+    }
+
+    /// Empties the cell at `(row, col)`, returning what it held
+    pub fn remove(&mut self, row: usize, col: usize) -> Option<Cell> {
+        self.revision = next_revision();
+        let cells = self.rows.get_mut(&row)?;
+        let cell = cells.remove(&col);
+        if cells.is_empty() {
+            self.rows.remove(&row);
+        }
+        cell
+    }
+
+    /// How many cells hold something
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.rows.values().map(BTreeMap::len).sum()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Roughly the bytes the grid takes up: its cells, the text they hold and the tree nodes
+    /// keeping them
+    #[must_use]
+    pub fn estimated_bytes(&self) -> usize {
+        // A B-tree entry costs its key and value and a share of its node
+        const ROW_BYTES: usize = 2 * size_of::<usize>() + size_of::<BTreeMap<usize, Cell>>();
+        const KEY_BYTES: usize = 2 * size_of::<usize>();
+        self.rows
+            .values()
+            .map(|cells| {
+                let cell_bytes: usize = cells.values().map(Cell::estimated_bytes).sum();
+                ROW_BYTES + cells.len() * KEY_BYTES + cell_bytes
+            })
+            .sum()
+    }
+
+    /// The cells of `row` holding something, left to right
+    pub fn row(&self, row: usize) -> impl DoubleEndedIterator<Item = (usize, &Cell)> {
+        self.rows
+            .get(&row)
+            .into_iter()
+            .flatten()
+            .map(|(&col, cell)| (col, cell))
+    }
+
+    /// The cells holding something, row by row
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &Cell)> {
+        self.rows
+            .iter()
+            .flat_map(|(&row, cells)| cells.iter().map(move |(&col, cell)| ((row, col), cell)))
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = ((usize, usize), &mut Cell)> {
+        self.revision = next_revision();
+        self.rows
+            .iter_mut()
+            .flat_map(|(&row, cells)| cells.iter_mut().map(move |(&col, cell)| ((row, col), cell)))
+    }
+
+    /// The rows holding something, top to bottom, each with its cells left to right
+    pub fn rows(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = (usize, impl Iterator<Item = (usize, &Cell)>)> {
+        self.rows
+            .iter()
+            .map(|(&row, cells)| (row, cells.iter().map(|(&col, cell)| (col, cell))))
+    }
+
+    /// The rows holding something from `row` down, each with its cells left to right
+    pub fn rows_from(
+        &self,
+        row: usize,
+    ) -> impl Iterator<Item = (usize, impl Iterator<Item = (usize, &Cell)>)> {
+        self.rows
+            .range(row..)
+            .map(|(&row, cells)| (row, cells.iter().map(|(&col, cell)| (col, cell))))
+    }
+
+    /// The cells of `row` holding something from `start` to `end` columns, left to right
+    pub fn row_range(
+        &self,
+        row: usize,
+        start: usize,
+        end: usize,
+    ) -> impl DoubleEndedIterator<Item = (usize, &Cell)> {
+        self.rows
+            .get(&row)
+            .filter(|_| start <= end)
+            .into_iter()
+            .flat_map(move |cells| cells.range(start..=end))
+            .map(|(&col, cell)| (col, cell))
+    }
+
+    /// The cells holding something in the rectangle from `start` to `end`, row by row
+    pub fn cells_in(
+        &self,
+        start: (usize, usize),
+        end: (usize, usize),
+    ) -> impl Iterator<Item = ((usize, usize), &Cell)> {
+        let rows = (start.0 <= end.0 && start.1 <= end.1).then(|| self.rows.range(start.0..=end.0));
+        rows.into_iter().flatten().flat_map(move |(&row, cells)| {
+            cells
+                .range(start.1..=end.1)
+                .map(move |(&col, cell)| ((row, col), cell))
+        })
+    }
+
+    /// The cells of `row` holding something with their columns, as row deletions keep them to
+    /// put back
+    #[must_use]
+    pub fn row_cells(&self, row: usize) -> Vec<(usize, Cell)> {
+        self.row(row)
+            .map(|(col, cell)| (col, cell.clone()))
+            .collect()
+    }
+
+    /// The cells of `col` holding something with their rows, as column deletions keep them to
+    /// put back
+    #[must_use]
+    pub fn column_cells(&self, col: usize) -> Vec<(usize, Cell)> {
+        self.rows
+            .iter()
+            .filter_map(|(&row, cells)| Some((row, cells.get(&col)?.clone())))
+            .collect()
+    }
+
+    /// The last row with a cell holding a value, or 0
+    #[must_use]
+    pub fn last_row_with_value(&self) -> usize {
+        self.rows
+            .iter()
+            .rev()
+            .find(|(_, cells)| cells.values().any(|cell| !cell.value.is_empty()))
+            .map_or(0, |(&row, _)| row)
+    }
+
+    /// The last column with a cell holding a value, or 0
+    #[must_use]
+    pub fn last_col_with_value(&self) -> usize {
+        self.rows
+            .values()
+            .filter_map(|cells| {
+                cells
+                    .iter()
+                    .rev()
+                    .find(|(_, cell)| !cell.value.is_empty())
+                    .map(|(&col, _)| col)
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Puts `cells`, each with its column, in as row `row`, moving the rows from there down one
+    pub fn insert_row(&mut self, row: usize, cells: Vec<(usize, Cell)>) {
+        self.insert_rows(row, &[cells]);
+    }
+
+    /// Puts `rows`, their cells each with its column, in from row `row` down, moving the rows
+    /// from there down past them
+    pub fn insert_rows(&mut self, row: usize, rows: &[Vec<(usize, Cell)>]) {
+        self.revision = next_revision();
+        let below = self.rows.split_off(&row);
+        self.rows.extend(
+            below
+                .into_iter()
+                .map(|(moved, cells)| (moved + rows.len(), cells)),
+        );
+        for (offset, cells) in rows.iter().enumerate() {
+            for (col, cell) in cells {
+                self.set(row + offset, *col, cell.clone());
+            }
+        }
+    }
+
+    /// Takes out row `row`, moving the rows below it up one
+    pub fn remove_row(&mut self, row: usize) {
+        self.remove_rows(&[row]);
+    }
+
+    /// Takes out the ascending `rows`, moving the rows below each up
+    pub fn remove_rows(&mut self, rows: &[usize]) {
+        let Some(&first) = rows.first() else {
+            return;
+        };
+        self.revision = next_revision();
+        let below = self.rows.split_off(&first);
+        for (row, cells) in below {
+            if rows.binary_search(&row).is_err() {
+                let removed = rows.partition_point(|&removed| removed < row);
+                self.rows.insert(row - removed, cells);
+            }
+        }
+    }
+
+    /// Puts `cells`, each with its row, in as column `col`, moving the columns from there right
+    /// one
+    pub fn insert_column(&mut self, col: usize, cells: &[(usize, Cell)]) {
+        self.insert_blank_columns(col, 1);
+        for (row, cell) in cells {
+            self.set(*row, col, cell.clone());
+        }
+    }
+
+    /// Moves the columns from `col` right `count` columns, leaving empty ones in their place
+    pub fn insert_blank_columns(&mut self, col: usize, count: usize) {
+        self.revision = next_revision();
+        for row_cells in self.rows.values_mut() {
+            let right = row_cells.split_off(&col);
+            row_cells.extend(right.into_iter().map(|(moved, cell)| (moved + count, cell)));
+        }
+    }
+
+    /// Takes out the columns `start..=end`, moving the columns right of them left
+    pub fn remove_columns(&mut self, start: usize, end: usize) {
+        if start > end {
+            return;
+        }
+        self.revision = next_revision();
+        let removed = end - start + 1;
+        for cells in self.rows.values_mut() {
+            let right = cells.split_off(&start);
+            cells.extend(
+                right
+                    .into_iter()
+                    .filter(|&(col, _)| col > end)
+                    .map(|(col, cell)| (col - removed, cell)),
+            );
+        }
+        self.rows.retain(|_, cells| !cells.is_empty());
+    }
+}
+
+impl From<Vec<Vec<Cell>>> for CellGrid {
+    /// A grid holding `rows`, indexed by row then column
+    fn from(rows: Vec<Vec<Cell>>) -> Self {
+        let mut grid = Self::new();
+        for (row, cells) in rows.into_iter().enumerate() {
+            for (col, cell) in cells.into_iter().enumerate() {
+                grid.set(row, col, cell);
+            }
+        }
+        grid
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CellGrid;
+    use crate::excel::Cell;
+
+    fn text(value: &str) -> Cell {
+        Cell::new(value.to_string(), false)
+    }
+
+    fn values(grid: &CellGrid) -> Vec<((usize, usize), String)> {
+        grid.iter()
+            .map(|(cell, content)| (cell, content.value.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn far_cells_take_no_room_and_rows_and_columns_move_them() {
+        let mut grid = CellGrid::new();
+        grid.set(1, 1, text("top"));
+        grid.set(100_000, 3, text("far"));
+        grid.set(5, 5, Cell::empty());
+        grid.update_cell(6, 6, |cell| cell.comment = Some("note".to_string()));
+        grid.update_cell(6, 6, |cell| cell.comment = None);
+        assert_eq!(grid.len(), 2);
+        assert_eq!(grid.cell(100_000, 3).value, "far");
+        assert_eq!(grid.cell(7, 7).value, "");
+        assert_eq!(grid.last_row_with_value(), 100_000);
+        assert_eq!(grid.last_col_with_value(), 3);
+
+        grid.insert_row(2, vec![(1, text("new"))]);
+        assert_eq!(
+            values(&grid),
+            vec![
+                ((1, 1), "top".to_string()),
+                ((2, 1), "new".to_string()),
+                ((100_001, 3), "far".to_string()),
+            ]
+        );
+        grid.remove_rows(&[1, 50]);
+        assert_eq!(
+            values(&grid),
+            vec![
+                ((1, 1), "new".to_string()),
+                ((99_999, 3), "far".to_string()),
+            ]
+        );
+
+        grid.insert_column(1, &[(1, text("left"))]);
+        assert_eq!(grid.cell(1, 1).value, "left");
+        assert_eq!(grid.cell(1, 2).value, "new");
+        assert_eq!(grid.row_cells(1).len(), 2);
+        grid.remove_columns(1, 2);
+        assert_eq!(values(&grid), vec![((99_999, 2), "far".to_string())]);
+        let column = grid.column_cells(2);
+        assert_eq!(column.len(), 1);
+        assert_eq!((column[0].0, column[0].1.value.as_str()), (99_999, "far"));
+    }
+}
@@ -1,7 +1,21 @@
 mod cell;
+mod conditional_format;
+mod defined_names;
+mod duration;
+mod formula_refs;
+mod grid;
+mod number_format;
+mod sample;
 mod sheet;
 mod workbook;
 
 pub use cell::*;
+pub use conditional_format::*;
+pub use defined_names::*;
+pub use duration::*;
+pub use formula_refs::*;
+pub use grid::*;
+pub use number_format::*;
+pub use sample::*;
 pub use sheet::*;
 pub use workbook::*;
@@ -0,0 +1,422 @@
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::excel::{Cell, DataTypeInfo};
+
+/// A display format for numeric and date cells, set with `:fmt` and saved as an xlsx number
+/// format
+#[derive(Clone, Debug, PartialEq)]
+pub enum NumberFormat {
+    /// `1,234,567` or `1,234.50`
+    Thousands { decimals: usize },
+    /// `1234.50`
+    Fixed { decimals: usize },
+    /// `12.5%` for 0.125
+    Percent { decimals: usize },
+    /// `$1,234.50` or `-€12.00`
+    Currency { symbol: String, decimals: usize },
+    /// Excel-style date pattern such as `yyyy-mm-dd` or `d mmm yyyy hh:mm`
+    Date { pattern: String },
+}
+
+/// Formats set on whole columns of a sheet, keyed by column index
+pub type ColumnFormats = HashMap<usize, NumberFormat>;
+
+const MAX_DECIMALS: usize = 10;
+
+impl NumberFormat {
+    /// Formats the cell for display, or `None` when the format does not apply to its value
+    #[must_use]
+    pub fn format_cell(&self, cell: &Cell) -> Option<String> {
+        if cell.is_formula || cell.value.is_empty() {
+            return None;
+        }
+
+        match self {
+            Self::Thousands { decimals } => {
+                numeric_value(cell).map(|value| group_thousands(value, *decimals))
+            }
+            Self::Fixed { decimals } => {
+                numeric_value(cell).map(|value| format!("{value:.decimals$}"))
+            }
+            Self::Percent { decimals } => {
+                numeric_value(cell).map(|value| format!("{:.decimals$}%", value * 100.0))
+            }
+            Self::Currency { symbol, decimals } => numeric_value(cell).map(|value| {
+                let amount = group_thousands(value.abs(), *decimals);
+                if value < 0.0 && amount.chars().any(|ch| ch.is_ascii_digit() && ch != '0') {
+                    format!("-{symbol}{amount}")
+                } else {
+                    format!("{symbol}{amount}")
+                }
+            }),
+            Self::Date { pattern } => date_value(cell)
+                .map(|datetime| datetime.format(&chrono_pattern(pattern)).to_string()),
+        }
+    }
+
+    /// The xlsx number format code, e.g. `#,##0.00` or `"€"#,##0.00`
+    #[must_use]
+    pub fn excel_code(&self) -> String {
+        match self {
+            Self::Thousands { decimals } => format!("#,##0{}", decimal_zeros(*decimals)),
+            Self::Fixed { decimals } => format!("0{}", decimal_zeros(*decimals)),
+            Self::Percent { decimals } => format!("0{}%", decimal_zeros(*decimals)),
+            Self::Currency { symbol, decimals } => format!(
+                "\"{}\"#,##0{}",
+                symbol.replace('"', "\"\""),
+                decimal_zeros(*decimals)
+            ),
+            Self::Date { pattern } => pattern.clone(),
+        }
+    }
+
+    /// The value the cell should be saved as so the format takes effect in Excel: the number
+    /// itself, or the date serial for date formats
+    #[must_use]
+    pub fn serial_value(&self, cell: &Cell) -> Option<f64> {
+        if cell.is_formula {
+            return None;
+        }
+        match self {
+            Self::Date { .. } => date_value(cell).map(datetime_to_serial),
+            _ => numeric_value(cell),
+        }
+    }
+}
+
+/// Parses specs such as `thousands 2`, `fixed 1`, `percent`, `currency € 2` or
+/// `date dd/mm/yyyy`
+impl FromStr for NumberFormat {
+    type Err = String;
+
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        let mut parts = spec.split_whitespace();
+        let kind = parts.next().ok_or("Missing format")?.to_lowercase();
+        let rest: Vec<&str> = parts.collect();
+
+        let decimals = |arg: Option<&&str>, default: usize| match arg {
+            None => Ok(default),
+            Some(text) => match text.parse::<usize>() {
+                Ok(decimals) if decimals <= MAX_DECIMALS => Ok(decimals),
+                _ => Err(format!(
+                    "Invalid decimal places: {text} (use 0-{MAX_DECIMALS})"
+                )),
+            },
+        };
+
+        let format = match kind.as_str() {
+            "thousands" | "comma" => Self::Thousands {
+                decimals: decimals(rest.first(), 0)?,
+            },
+            "fixed" | "decimal" => Self::Fixed {
+                decimals: decimals(rest.first(), 2)?,
+            },
+            "percent" | "pct" | "%" => Self::Percent {
+                decimals: decimals(rest.first(), 0)?,
+            },
+            "currency" | "cur" => {
+                // The symbol is optional: `currency 0` keeps `$` and sets the decimals
+                let (symbol, places) = match rest.first() {
+                    Some(first) if first.parse::<usize>().is_err() => {
+                        ((*first).to_string(), rest.get(1))
+                    }
+                    _ => ("$".to_string(), rest.first()),
+                };
+                Self::Currency {
+                    symbol,
+                    decimals: decimals(places, 2)?,
+                }
+            }
+            "date" => {
+                let pattern = rest.join(" ");
+                if pattern.is_empty() {
+                    Self::Date {
+                        pattern: "yyyy-mm-dd".to_string(),
+                    }
+                } else if pattern
+                    .chars()
+                    .any(|ch| matches!(ch.to_ascii_lowercase(), 'y' | 'm' | 'd' | 'h' | 's'))
+                {
+                    Self::Date { pattern }
+                } else {
+                    return Err(format!("Invalid date pattern: {pattern}"));
+                }
+            }
+            _ => return Err(format!("Unknown format: {kind}")),
+        };
+
+        if kind != "currency" && kind != "cur" && kind != "date" && rest.len() > 1 {
+            return Err(format!("Too many arguments for {kind}"));
+        }
+
+        Ok(format)
+    }
+}
+
+/// The spec that parses back to this format, as shown by `:fmt`
+impl fmt::Display for NumberFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Thousands { decimals } => write!(f, "thousands {decimals}"),
+            Self::Fixed { decimals } => write!(f, "fixed {decimals}"),
+            Self::Percent { decimals } => write!(f, "percent {decimals}"),
+            Self::Currency { symbol, decimals } => write!(f, "currency {symbol} {decimals}"),
+            Self::Date { pattern } => write!(f, "date {pattern}"),
+        }
+    }
+}
+
+fn decimal_zeros(decimals: usize) -> String {
+    if decimals == 0 {
+        String::new()
+    } else {
+        format!(".{}", "0".repeat(decimals))
+    }
+}
+
+/// The number a cell holds, dates as their serial number
+pub(crate) fn numeric_value(cell: &Cell) -> Option<f64> {
+    match &cell.original_type {
+        Some(DataTypeInfo::Float(value) | DataTypeInfo::DateTime(value)) => Some(*value),
+        Some(DataTypeInfo::Int(value)) => Some(*value as f64),
+        _ => cell
+            .value
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .filter(|value| value.is_finite()),
+    }
+}
+
+fn group_thousands(value: f64, decimals: usize) -> String {
+    let fixed = format!("{value:.decimals$}");
+    let (sign, digits) = match fixed.strip_prefix('-') {
+        Some(digits) => ("-", digits),
+        None => ("", fixed.as_str()),
+    };
+    let (integer, fraction) = match digits.split_once('.') {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (digits, None),
+    };
+
+    let mut grouped = String::with_capacity(fixed.len() + integer.len() / 3);
+    for (index, ch) in integer.chars().enumerate() {
+        if index > 0 && (integer.len() - index) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(ch);
+    }
+
+    match fraction {
+        Some(fraction) => format!("{sign}{grouped}.{fraction}"),
+        None => format!("{sign}{grouped}"),
+    }
+}
+
+/// Day zero of the 1900 date system, shifted so serials after the phantom 1900-02-29 line up
+fn serial_epoch() -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(1899, 12, 30)
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .expect("valid epoch")
+}
+
+fn serial_to_datetime(serial: f64) -> Option<NaiveDateTime> {
+    if !(0.0..2_958_466.0).contains(&serial) {
+        return None;
+    }
+    // Serials before March 1900 sit one day later because of Excel's 1900 leap-year bug
+    let serial = if serial < 61.0 { serial + 1.0 } else { serial };
+    let seconds = (serial * 86_400.0).round() as i64;
+    serial_epoch().checked_add_signed(Duration::seconds(seconds))
+}
+
+fn datetime_to_serial(datetime: NaiveDateTime) -> f64 {
+    let serial = (datetime - serial_epoch()).num_seconds() as f64 / 86_400.0;
+    if serial < 61.0 {
+        serial - 1.0
+    } else {
+        serial
+    }
+}
+
+fn date_value(cell: &Cell) -> Option<NaiveDateTime> {
+    if let Some(serial) = numeric_value(cell) {
+        return serial_to_datetime(serial);
+    }
+
+    let text = cell.value.trim();
+    NaiveDateTime::parse_from_str(text, "%Y-%m-%dT%H:%M:%S")
+        .or_else(|_| NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S"))
+        .ok()
+        .or_else(|| {
+            NaiveDate::parse_from_str(text, "%Y-%m-%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+        })
+}
+
+/// Translates an Excel date pattern into a chrono format string. `m` means minutes right after
+/// an hour token or right before a seconds token, as in Excel.
+fn chrono_pattern(pattern: &str) -> String {
+    let chars: Vec<char> = pattern.chars().collect();
+    let mut tokens: Vec<(char, usize)> = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let ch = chars[i];
+        let lower = ch.to_ascii_lowercase();
+        if matches!(lower, 'y' | 'm' | 'd' | 'h' | 's') {
+            let mut run = 1;
+            while i + run < chars.len() && chars[i + run].to_ascii_lowercase() == lower {
+                run += 1;
+            }
+            tokens.push((lower, run));
+            i += run;
+        } else if ch == '"' {
+            let end = chars[i + 1..]
+                .iter()
+                .position(|&c| c == '"')
+                .map_or(chars.len(), |offset| i + 1 + offset);
+            for &literal in &chars[i + 1..end.min(chars.len())] {
+                tokens.push((literal, 0));
+            }
+            i = end + 1;
+        } else if ch == '\\' && i + 1 < chars.len() {
+            tokens.push((chars[i + 1], 0));
+            i += 2;
+        } else {
+            tokens.push((ch, 0));
+            i += 1;
+        }
+    }
+
+    let is_minutes = |index: usize| {
+        let previous = tokens[..index].iter().rev().find(|(_, run)| *run > 0);
+        let next = tokens[index + 1..].iter().find(|(_, run)| *run > 0);
+        matches!(previous, Some(('h', _))) || matches!(next, Some(('s', _)))
+    };
+
+    let mut output = String::new();
+    for (index, &(token, run)) in tokens.iter().enumerate() {
+        let spec = match (token, run) {
+            ('%', 0) => "%%",
+            (literal, 0) => {
+                output.push(literal);
+                continue;
+            }
+            ('y', 1 | 2) => "%y",
+            ('y', _) => "%Y",
+            ('m', 1 | 2) if is_minutes(index) => {
+                if run == 1 {
+                    "%-M"
+                } else {
+                    "%M"
+                }
+            }
+            ('m', 1) => "%-m",
+            ('m', 2) => "%m",
+            ('m', 3) => "%b",
+            ('m', _) => "%B",
+            ('d', 1) => "%-d",
+            ('d', 2) => "%d",
+            ('d', 3) => "%a",
+            ('d', _) => "%A",
+            ('h', 1) => "%-H",
+            ('h', _) => "%H",
+            ('s', 1) => "%-S",
+            ('s', _) => "%S",
+            _ => "",
+        };
+        output.push_str(spec);
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NumberFormat;
+    use crate::excel::{Cell, CellType, DataTypeInfo};
+
+    fn number(value: &str) -> Cell {
+        Cell::new(value.to_string(), false)
+    }
+
+    fn format(spec: &str, cell: &Cell) -> Option<String> {
+        spec.parse::<NumberFormat>().unwrap().format_cell(cell)
+    }
+
+    #[test]
+    fn formats_numbers_with_separators_decimals_percent_and_currency() {
+        assert_eq!(
+            format("thousands", &number("1234567.4")).as_deref(),
+            Some("1,234,567")
+        );
+        assert_eq!(
+            format("thousands 2", &number("-1234.5")).as_deref(),
+            Some("-1,234.50")
+        );
+        assert_eq!(
+            format("fixed 1", &number("3.14159")).as_deref(),
+            Some("3.1")
+        );
+        assert_eq!(
+            format("percent 1", &number("0.125")).as_deref(),
+            Some("12.5%")
+        );
+        assert_eq!(
+            format("currency", &number("-1234.5")).as_deref(),
+            Some("-$1,234.50")
+        );
+        assert_eq!(
+            format("currency € 0", &number("999.6")).as_deref(),
+            Some("€1,000")
+        );
+        assert_eq!(format("thousands", &number("n/a")), None);
+    }
+
+    #[test]
+    fn formats_date_serials_and_iso_text_with_excel_patterns() {
+        let serial = Cell::new_with_type(
+            "45306.75".to_string(),
+            false,
+            CellType::Date,
+            Some(DataTypeInfo::DateTime(45306.75)),
+        );
+        assert_eq!(
+            format("date dd/mm/yyyy hh:mm", &serial).as_deref(),
+            Some("15/01/2024 18:00")
+        );
+        assert_eq!(
+            format("date d mmm yyyy", &number("2024-03-05")).as_deref(),
+            Some("5 Mar 2024")
+        );
+        assert_eq!(format("date", &number("1")).as_deref(), Some("1900-01-01"));
+
+        let date: NumberFormat = "date yyyy-mm-dd".parse().unwrap();
+        assert_eq!(date.serial_value(&number("2024-01-15")), Some(45306.0));
+    }
+
+    #[test]
+    fn parses_specs_and_reports_excel_codes() {
+        let cases = [
+            ("thousands 2", "#,##0.00"),
+            ("fixed 0", "0"),
+            ("percent", "0%"),
+            ("currency €", "\"€\"#,##0.00"),
+            ("date dd/mm/yyyy", "dd/mm/yyyy"),
+        ];
+        for (spec, code) in cases {
+            let format: NumberFormat = spec.parse().unwrap();
+            assert_eq!(format.excel_code(), code, "{spec}");
+            assert_eq!(format.to_string().parse::<NumberFormat>(), Ok(format));
+        }
+
+        assert!("fixed 12".parse::<NumberFormat>().is_err());
+        assert!("percent 1 2".parse::<NumberFormat>().is_err());
+        assert!("date 123".parse::<NumberFormat>().is_err());
+        assert!("scientific".parse::<NumberFormat>().is_err());
+    }
+}
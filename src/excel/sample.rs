@@ -0,0 +1,206 @@
+use anyhow::Result;
+use rust_xlsxwriter::{ExcelDateTime, Format, Formula, Workbook as XlsxWorkbook};
+use std::path::Path;
+
+struct SampleOrder {
+    date: (u16, u8, u8),
+    region: &'static str,
+    product: &'static str,
+    units: u32,
+    unit_price: f64,
+    notes: &'static str,
+}
+
+impl SampleOrder {
+    fn total(&self) -> f64 {
+        f64::from(self.units) * self.unit_price
+    }
+}
+
+const ORDERS: [SampleOrder; 8] = [
+    SampleOrder {
+        date: (2025, 1, 6),
+        region: "North",
+        product: "Notebook",
+        units: 120,
+        unit_price: 2.5,
+        notes: "Back-to-school restock",
+    },
+    SampleOrder {
+        date: (2025, 1, 14),
+        region: "South",
+        product: "Stapler",
+        units: 15,
+        unit_price: 12.0,
+        notes: "",
+    },
+    SampleOrder {
+        date: (2025, 2, 3),
+        region: "East",
+        product: "Desk lamp",
+        units: 8,
+        unit_price: 34.9,
+        notes: "Replacement for the units damaged in transit",
+    },
+    SampleOrder {
+        date: (2025, 2, 20),
+        region: "West",
+        product: "Notebook",
+        units: 60,
+        unit_price: 2.5,
+        notes: "",
+    },
+    SampleOrder {
+        date: (2025, 3, 11),
+        region: "North",
+        product: "Monitor arm",
+        units: 4,
+        unit_price: 89.0,
+        notes: "Ergonomics pilot; review usage at the end of the quarter before ordering more",
+    },
+    SampleOrder {
+        date: (2025, 3, 28),
+        region: "South",
+        product: "Pens (box)",
+        units: 40,
+        unit_price: 6.75,
+        notes: "",
+    },
+    SampleOrder {
+        date: (2025, 4, 9),
+        region: "East",
+        product: "Whiteboard",
+        units: 2,
+        unit_price: 149.0,
+        notes: "Meeting rooms 2 and 3",
+    },
+    SampleOrder {
+        date: (2025, 4, 30),
+        region: "West",
+        product: "Desk lamp",
+        units: 12,
+        unit_price: 34.9,
+        notes: "",
+    },
+];
+
+const REGIONS: [(&str, &str, f64); 4] = [
+    ("North", "Ada Lovelace", 1000.0),
+    ("South", "Grace Hopper", 600.0),
+    ("East", "Alan Turing", 800.0),
+    ("West", "Edsger Dijkstra", 700.0),
+];
+
+const TIPS: [&str; 8] = [
+    "Welcome to the excel-cli sample workbook.",
+    "Move with h/j/k/l or the arrow keys, and switch sheets with [ and ].",
+    "Orders has dates, formulas (Total) and a wide Notes column; try :cw fit all.",
+    "Press Enter on a cell to edit it, then Enter again to save the edit.",
+    "Search with /, then n and N step through matches.",
+    "Press Enter on a cell below to jump to the sheet it names.",
+    "Orders!F10",
+    "Regions",
+];
+
+/// Writes a small demo workbook with several sheets, dates, formulas and wide columns
+pub fn write_sample_workbook(path: &Path) -> Result<()> {
+    let mut workbook = XlsxWorkbook::new();
+    let bold = Format::new().set_bold();
+    let date_format = Format::new().set_num_format("yyyy-mm-dd");
+
+    let tips = workbook.add_worksheet();
+    tips.set_name("Read Me")?;
+    for (row, tip) in TIPS.iter().enumerate() {
+        tips.write_string(row as u32, 0, *tip)?;
+    }
+    tips.set_column_width(0, 70)?;
+
+    let orders = workbook.add_worksheet();
+    orders.set_name("Orders")?;
+    let headers = [
+        "Date",
+        "Region",
+        "Product",
+        "Units",
+        "Unit price",
+        "Total",
+        "Notes",
+    ];
+    for (col, header) in headers.iter().enumerate() {
+        orders.write_string_with_format(0, col as u16, *header, &bold)?;
+    }
+
+    for (index, order) in ORDERS.iter().enumerate() {
+        let row = index as u32 + 1;
+        let excel_row = row + 1;
+        let (year, month, day) = order.date;
+        let date = ExcelDateTime::from_ymd(year, month, day)?;
+        orders.write_datetime_with_format(row, 0, &date, &date_format)?;
+        orders.write_string(row, 1, order.region)?;
+        orders.write_string(row, 2, order.product)?;
+        orders.write_number(row, 3, order.units)?;
+        orders.write_number(row, 4, order.unit_price)?;
+        orders.write_formula(
+            row,
+            5,
+            Formula::new(format!("=D{excel_row}*E{excel_row}"))
+                .set_result(order.total().to_string()),
+        )?;
+        if !order.notes.is_empty() {
+            orders.write_string(row, 6, order.notes)?;
+        }
+    }
+
+    let total_row = ORDERS.len() as u32 + 1;
+    let grand_total: f64 = ORDERS.iter().map(SampleOrder::total).sum();
+    orders.write_string_with_format(total_row, 4, "Total", &bold)?;
+    orders.write_formula(
+        total_row,
+        5,
+        Formula::new(format!("=SUM(F2:F{total_row})")).set_result(grand_total.to_string()),
+    )?;
+    orders.set_column_width(0, 12)?;
+    orders.set_column_width(6, 60)?;
+
+    let regions = workbook.add_worksheet();
+    regions.set_name("Regions")?;
+    for (col, header) in ["Region", "Manager", "Target"].iter().enumerate() {
+        regions.write_string_with_format(0, col as u16, *header, &bold)?;
+    }
+    for (index, (region, manager, target)) in REGIONS.iter().enumerate() {
+        let row = index as u32 + 1;
+        regions.write_string(row, 0, *region)?;
+        regions.write_string(row, 1, *manager)?;
+        regions.write_number(row, 2, *target)?;
+    }
+
+    workbook.save(path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::write_sample_workbook;
+    use crate::excel::{open_workbook, CellType};
+
+    #[test]
+    fn sample_workbook_opens_with_dates_formulas_and_wide_text() {
+        let path = std::env::temp_dir().join("excel_cli_sample_workbook_test.xlsx");
+        let _ = std::fs::remove_file(&path);
+
+        write_sample_workbook(&path).unwrap();
+        let workbook = open_workbook(&path, false).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(
+            workbook.get_sheet_names(),
+            vec!["Read Me", "Orders", "Regions"]
+        );
+        let orders = workbook
+            .get_sheet_by_name("Orders")
+            .expect("Orders sheet should exist");
+        assert!(orders.data.cell(2, 1).cell_type == CellType::Date);
+        assert!(orders.data.cell(2, 6).is_formula);
+        assert!(orders.data.cell(6, 7).value.len() > 60);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::excel::Cell;
+use crate::excel::{CellGrid, ColumnFormats, ConditionalFormat, NumberFormat};
 use crate::utils::cell_reference;
 
 pub const EXCEL_MAX_COLS: usize = 16_384;
@@ -46,14 +46,56 @@ impl Default for FreezePanes {
     }
 }
 
+/// Whether a sheet's cells may be edited, read from the xlsx `sheetProtection` element
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SheetProtection {
+    #[default]
+    Unprotected,
+    /// Only cells whose style unlocks them can be edited
+    Protected,
+    /// Protected in the file, but lifted with `:unlock` for this session; still written on save
+    Unlocked,
+}
+
+impl SheetProtection {
+    #[must_use]
+    pub fn is_protected(self) -> bool {
+        !matches!(self, Self::Unprotected)
+    }
+}
+
+/// Whether a sheet shows in Excel's tab bar, read from the xlsx `state` attribute
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SheetVisibility {
+    #[default]
+    Visible,
+    /// Hidden, but Excel's Unhide dialog lists it
+    Hidden,
+    /// Hidden, and only a macro can bring it back in Excel
+    VeryHidden,
+}
+
+impl SheetVisibility {
+    #[must_use]
+    pub fn is_hidden(self) -> bool {
+        !matches!(self, Self::Visible)
+    }
+}
+
 #[derive(Clone)]
 pub struct Sheet {
     pub name: String,
-    pub data: Vec<Vec<Cell>>,
+    pub data: CellGrid,
     pub max_rows: usize,
     pub max_cols: usize,
     pub is_loaded: bool,
     pub freeze_panes: FreezePanes,
+    pub column_formats: ColumnFormats,
+    pub protection: SheetProtection,
+    pub visibility: SheetVisibility,
+    pub tab_color: Option<u32>, // 0xRRGGBB, as set in Excel's Tab Color menu
+    /// Rules coloring cells by value, read from the xlsx or added with `:cf`
+    pub conditional_formats: Vec<ConditionalFormat>,
 }
 
 impl Sheet {
@@ -61,11 +103,33 @@ impl Sheet {
     pub fn blank(name: String) -> Self {
         Self {
             name,
-            data: vec![vec![Cell::empty(); 2]; 2],
+            data: CellGrid::new(),
             max_rows: 1,
             max_cols: 1,
             is_loaded: true,
             freeze_panes: FreezePanes::none(),
+            column_formats: ColumnFormats::new(),
+            protection: SheetProtection::Unprotected,
+            visibility: SheetVisibility::Visible,
+            tab_color: None,
+            conditional_formats: Vec::new(),
         }
     }
+
+    /// Whether editing the cell is refused: the sheet is protected and the cell's style
+    /// doesn't unlock it. Cells outside the grid use the default, locked style
+    #[must_use]
+    pub fn is_cell_locked(&self, row: usize, col: usize) -> bool {
+        self.protection == SheetProtection::Protected && !self.data.cell(row, col).unlocked
+    }
+
+    /// Display format of a cell: its own format, else its column's
+    #[must_use]
+    pub fn number_format_at(&self, row: usize, col: usize) -> Option<&NumberFormat> {
+        self.data
+            .cell(row, col)
+            .number_format
+            .as_ref()
+            .or_else(|| self.column_formats.get(&col))
+    }
 }
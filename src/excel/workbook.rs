@@ -1,22 +1,83 @@
 use anyhow::{Context, Result};
-use calamine::{open_workbook_auto, Reader, Xls, Xlsx};
-use std::collections::HashSet;
+use calamine::{open_workbook_auto, Reader, SheetVisible, Sheets, Xls, Xlsx};
+use std::collections::{BTreeSet, HashSet};
 use std::fs::File;
 use std::io::BufReader;
 use std::panic::{catch_unwind, AssertUnwindSafe};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-use crate::excel::{Cell, CellType, FreezePanes, Sheet};
+use crate::excel::{
+    Cell, CellGrid, CellType, ColumnFormats, ConditionalFormat, DefinedName, FreezePanes,
+    NumberFormat, Sheet, SheetVisibility,
+};
 use crate::utils::{index_to_col_name, parse_cell_reference};
 
+mod comments;
+mod conditional_formats;
 mod formula_lookup;
 mod freeze_panes;
+mod hyperlinks;
+mod kept_sheets;
+mod progress;
+mod protection;
 mod save;
 mod sheet_parse;
+mod styles_lookup;
+mod styles_merge;
+mod tab_colors;
 
+use comments::{apply_comments, lookup_comments_in_xlsx};
+use conditional_formats::lookup_conditional_formats_in_xlsx;
 use formula_lookup::lookup_formula_in_xlsx;
 use freeze_panes::lookup_freeze_panes_in_xlsx;
-use sheet_parse::create_sheet_from_range;
+use hyperlinks::{apply_hyperlinks, lookup_hyperlinks_in_xlsx};
+use kept_sheets::{modified_at, OriginalSheet};
+use progress::read_xlsx_range;
+pub use progress::{LoadProgress, SheetLoadRequest, StreamedRows, StreamedSheetEnd};
+use protection::{
+    apply_unlocked_cells, lookup_sheet_protection_in_xlsx, lookup_unlocked_cells_in_xlsx,
+};
+use sheet_parse::{apply_formula_metadata, create_sheet_from_range};
+use styles_lookup::{apply_cell_colors, lookup_cell_colors_in_xlsx};
+use tab_colors::lookup_tab_color_in_xlsx;
+
+/// Attaches xlsx comments, hyperlinks, unlocked cells, cell colors and conditional formats,
+/// which calamine does not read, to a parsed sheet
+fn attach_xlsx_annotations(sheet: &mut Sheet, file: &Path, sheet_name: &str) {
+    XlsxAnnotations::read(file, sheet_name).apply(sheet);
+}
+
+/// The comments, hyperlinks, unlocked cells, cell colors and conditional formats of an xlsx
+/// sheet, read apart from its cells
+struct XlsxAnnotations {
+    colors: Vec<((usize, usize), styles_lookup::StyleColors)>,
+    comments: Vec<((usize, usize), String)>,
+    hyperlinks: Vec<((usize, usize), String)>,
+    unlocked_cells: Vec<(usize, usize)>,
+    conditional_formats: Vec<ConditionalFormat>,
+}
+
+impl XlsxAnnotations {
+    fn read(file: &Path, sheet_name: &str) -> Self {
+        Self {
+            colors: lookup_cell_colors_in_xlsx(file, sheet_name),
+            comments: lookup_comments_in_xlsx(file, sheet_name),
+            hyperlinks: lookup_hyperlinks_in_xlsx(file, sheet_name),
+            unlocked_cells: lookup_unlocked_cells_in_xlsx(file, sheet_name),
+            conditional_formats: lookup_conditional_formats_in_xlsx(file, sheet_name),
+        }
+    }
+
+    /// Attaches the annotations to `sheet`, whose size must be set first
+    fn apply(self, sheet: &mut Sheet) {
+        apply_cell_colors(sheet, self.colors);
+        let data = &mut sheet.data;
+        apply_comments(data, self.comments);
+        apply_hyperlinks(data, self.hyperlinks);
+        apply_unlocked_cells(data, self.unlocked_cells);
+        sheet.conditional_formats = self.conditional_formats;
+    }
+}
 
 pub enum CalamineWorkbook {
     Xlsx(Box<Xlsx<BufReader<File>>>),
@@ -38,6 +99,12 @@ pub struct Workbook {
     calamine_workbook: CalamineWorkbook,
     lazy_loading: bool,
     loaded_sheets: HashSet<usize>, // Track which sheets have been loaded
+    defined_names: Vec<DefinedName>,
+    /// The sheets as read from the file, which saving copies from it while they are unchanged
+    original_sheets: Vec<OriginalSheet>,
+    /// When the file was last written as of opening it; saving copies nothing from a file
+    /// written since
+    file_modified_at: Option<std::time::SystemTime>,
 }
 
 impl Clone for Workbook {
@@ -50,19 +117,30 @@ impl Clone for Workbook {
             calamine_workbook: CalamineWorkbook::None,
             lazy_loading: false,
             loaded_sheets: self.loaded_sheets.clone(),
+            defined_names: self.defined_names.clone(),
+            original_sheets: self.original_sheets.clone(),
+            file_modified_at: self.file_modified_at,
         }
     }
 }
 
 pub fn open_workbook<P: AsRef<Path>>(path: P, enable_lazy_loading: bool) -> Result<Workbook> {
+    open_workbook_reporting(path, enable_lazy_loading, |_| {})
+}
+
+/// Opens a workbook like [`open_workbook`], calling `on_progress` as each sheet is read
+pub fn open_workbook_reporting<P: AsRef<Path>>(
+    path: P,
+    enable_lazy_loading: bool,
+    mut on_progress: impl FnMut(&LoadProgress),
+) -> Result<Workbook> {
     let path_str = path.as_ref().to_string_lossy().to_string();
 
-    let hook = std::panic::take_hook();
-    std::panic::set_hook(Box::new(|_| {}));
+    // The panic hook stays as it is: this runs on a background thread, and swapping the
+    // process-wide hook here would race with the UI thread and with sheet loads
     let result = catch_unwind(AssertUnwindSafe(|| {
-        open_workbook_impl(path.as_ref(), enable_lazy_loading)
+        open_workbook_impl(path.as_ref(), enable_lazy_loading, &mut on_progress)
     }));
-    std::panic::set_hook(hook);
 
     match result {
         Ok(inner) => inner,
@@ -73,7 +151,11 @@ pub fn open_workbook<P: AsRef<Path>>(path: P, enable_lazy_loading: bool) -> Resu
     }
 }
 
-fn open_workbook_impl<P: AsRef<Path>>(path: P, enable_lazy_loading: bool) -> Result<Workbook> {
+fn open_workbook_impl<P: AsRef<Path>>(
+    path: P,
+    enable_lazy_loading: bool,
+    on_progress: &mut dyn FnMut(&LoadProgress),
+) -> Result<Workbook> {
     let path_str = path.as_ref().to_string_lossy().to_string();
     let path_ref = path.as_ref();
 
@@ -92,6 +174,25 @@ fn open_workbook_impl<P: AsRef<Path>>(path: P, enable_lazy_loading: bool) -> Res
         .with_context(|| format!("Unable to parse Excel file: {}", path_str))?;
 
     let sheet_names = workbook.sheet_names().to_vec();
+    let visibility_by_name = workbook
+        .sheets_metadata()
+        .iter()
+        .map(|meta| {
+            let visibility = match meta.visible {
+                SheetVisible::Visible => SheetVisibility::Visible,
+                SheetVisible::Hidden => SheetVisibility::Hidden,
+                SheetVisible::VeryHidden => SheetVisibility::VeryHidden,
+            };
+            (meta.name.clone(), visibility)
+        })
+        .collect::<std::collections::HashMap<_, _>>();
+    // Built-in names such as `_xlnm.Print_Area` belong to features this editor does not keep
+    let defined_names = workbook
+        .defined_names()
+        .iter()
+        .filter(|(name, _)| !name.starts_with("_xlnm."))
+        .map(|(name, refers_to)| DefinedName::new(name.as_str(), refers_to.as_str()))
+        .collect();
     let freeze_panes_by_name = sheet_names
         .iter()
         .map(|name| {
@@ -115,11 +216,16 @@ fn open_workbook_impl<P: AsRef<Path>>(path: P, enable_lazy_loading: bool) -> Res
             // Create a minimal sheet with just the name
             let sheet = Sheet {
                 name: name.to_string(),
-                data: vec![vec![Cell::empty(); 1]; 1],
+                data: CellGrid::new(),
                 max_rows: 0,
                 max_cols: 0,
                 is_loaded: false,
                 freeze_panes: freeze_panes_by_name.get(name).cloned().unwrap_or_default(),
+                column_formats: ColumnFormats::new(),
+                protection: lookup_sheet_protection_in_xlsx(path_ref, name),
+                visibility: visibility_by_name.get(name).copied().unwrap_or_default(),
+                tab_color: lookup_tab_color_in_xlsx(path_ref, name),
+                conditional_formats: Vec::new(),
             };
 
             sheets.push(sheet);
@@ -144,8 +250,24 @@ fn open_workbook_impl<P: AsRef<Path>>(path: P, enable_lazy_loading: bool) -> Res
         }
     } else {
         // For formats that don't support lazy loading or if lazy loading is disabled,
-        for name in &sheet_names {
-            let range = workbook.worksheet_range(name).with_context(|| {
+        for (index, name) in sheet_names.iter().enumerate() {
+            let mut report = |rows_read, total_rows| {
+                on_progress(&LoadProgress {
+                    sheet_name: name.clone(),
+                    sheet_index: index,
+                    sheet_count: sheet_names.len(),
+                    rows_read,
+                    total_rows,
+                });
+            };
+            report(0, None);
+            let range = match &mut workbook {
+                Sheets::Xlsx(xlsx) => {
+                    read_xlsx_range(xlsx, name, &mut report).map_err(anyhow::Error::from)
+                }
+                workbook => workbook.worksheet_range(name).map_err(anyhow::Error::from),
+            }
+            .with_context(|| {
                 format!(
                     "Unable to parse Excel file: {} (unable to read worksheet: {})",
                     path_str, name
@@ -154,8 +276,12 @@ fn open_workbook_impl<P: AsRef<Path>>(path: P, enable_lazy_loading: bool) -> Res
 
             let formula_range = workbook.worksheet_formula(name).ok();
             let mut sheet = create_sheet_from_range(name, range, formula_range);
+            attach_xlsx_annotations(&mut sheet, path_ref, name);
             sheet.is_loaded = true;
             sheet.freeze_panes = freeze_panes_by_name.get(name).cloned().unwrap_or_default();
+            sheet.protection = lookup_sheet_protection_in_xlsx(path_ref, name);
+            sheet.visibility = visibility_by_name.get(name).copied().unwrap_or_default();
+            sheet.tab_color = lookup_tab_color_in_xlsx(path_ref, name);
             sheets.push(sheet);
         }
     }
@@ -172,14 +298,23 @@ fn open_workbook_impl<P: AsRef<Path>>(path: P, enable_lazy_loading: bool) -> Res
         }
     }
 
+    // Open on the first sheet Excel would show rather than a hidden helper sheet
+    let current_sheet_index = sheets
+        .iter()
+        .position(|sheet| !sheet.visibility.is_hidden())
+        .unwrap_or(0);
+
     Ok(Workbook {
+        original_sheets: sheets.iter().map(OriginalSheet::of).collect(),
+        file_modified_at: modified_at(path_ref),
         sheets,
-        current_sheet_index: 0,
+        current_sheet_index,
         file_path: path_str,
         is_modified: false,
         calamine_workbook,
         lazy_loading: supports_lazy_loading,
         loaded_sheets,
+        defined_names,
     })
 }
 
@@ -205,6 +340,22 @@ fn shrink_freeze_cols(freeze_panes: &mut FreezePanes, start_col: usize, end_col:
     true
 }
 
+/// Drops formats of deleted columns and moves formats right of them left
+fn shift_column_formats(formats: &mut ColumnFormats, start_col: usize, end_col: usize) {
+    let removed = end_col - start_col + 1;
+    *formats = std::mem::take(formats)
+        .into_iter()
+        .filter(|(col, _)| !(start_col..=end_col).contains(col))
+        .map(|(col, format)| {
+            if col > end_col {
+                (col - removed, format)
+            } else {
+                (col, format)
+            }
+        })
+        .collect();
+}
+
 impl Workbook {
     pub fn get_current_sheet(&self) -> &Sheet {
         &self.sheets[self.current_sheet_index]
@@ -230,12 +381,20 @@ impl Workbook {
                     Ok(Ok(range)) => {
                         let formula_range = xlsx.worksheet_formula(sheet_name).ok();
                         let freeze_panes = self.sheets[sheet_index].freeze_panes.clone();
+                        let protection = self.sheets[sheet_index].protection;
+                        let visibility = self.sheets[sheet_index].visibility;
+                        let tab_color = self.sheets[sheet_index].tab_color;
                         let mut sheet = create_sheet_from_range(sheet_name, range, formula_range);
+                        attach_xlsx_annotations(&mut sheet, Path::new(&self.file_path), sheet_name);
                         let original_name = self.sheets[sheet_index].name.clone();
                         sheet.name = original_name;
                         sheet.freeze_panes = freeze_panes;
+                        sheet.protection = protection;
+                        sheet.visibility = visibility;
+                        sheet.tab_color = tab_color;
                         self.sheets[sheet_index] = sheet;
                         self.loaded_sheets.insert(sheet_index);
+                        self.note_sheet_read(sheet_index);
                     }
                     Ok(Err(err)) => {
                         return Err(anyhow::anyhow!(
@@ -260,12 +419,20 @@ impl Workbook {
                     Ok(Ok(range)) => {
                         let formula_range = xls.worksheet_formula(sheet_name).ok();
                         let freeze_panes = self.sheets[sheet_index].freeze_panes.clone();
+                        let protection = self.sheets[sheet_index].protection;
+                        let visibility = self.sheets[sheet_index].visibility;
+                        let tab_color = self.sheets[sheet_index].tab_color;
                         let mut sheet = create_sheet_from_range(sheet_name, range, formula_range);
+                        attach_xlsx_annotations(&mut sheet, Path::new(&self.file_path), sheet_name);
                         let original_name = self.sheets[sheet_index].name.clone();
                         sheet.name = original_name;
                         sheet.freeze_panes = freeze_panes;
+                        sheet.protection = protection;
+                        sheet.visibility = visibility;
+                        sheet.tab_color = tab_color;
                         self.sheets[sheet_index] = sheet;
                         self.loaded_sheets.insert(sheet_index);
+                        self.note_sheet_read(sheet_index);
                     }
                     Ok(Err(err)) => {
                         return Err(anyhow::anyhow!(
@@ -315,12 +482,20 @@ impl Workbook {
                     Ok(Ok(range)) => {
                         let formula_range = xlsx.worksheet_formula(sheet_name).ok();
                         let freeze_panes = self.sheets[sheet_index].freeze_panes.clone();
+                        let protection = self.sheets[sheet_index].protection;
+                        let visibility = self.sheets[sheet_index].visibility;
+                        let tab_color = self.sheets[sheet_index].tab_color;
                         let mut sheet = create_sheet_from_range(sheet_name, range, formula_range);
+                        attach_xlsx_annotations(&mut sheet, Path::new(&self.file_path), sheet_name);
                         let original_name = self.sheets[sheet_index].name.clone();
                         sheet.name = original_name;
                         sheet.freeze_panes = freeze_panes;
+                        sheet.protection = protection;
+                        sheet.visibility = visibility;
+                        sheet.tab_color = tab_color;
                         self.sheets[sheet_index] = sheet;
                         self.loaded_sheets.insert(sheet_index);
+                        self.note_sheet_read(sheet_index);
                         Ok(true)
                     }
                     Ok(Err(err)) => {
@@ -354,12 +529,20 @@ impl Workbook {
                     Ok(Ok(range)) => {
                         let formula_range = xls.worksheet_formula(sheet_name).ok();
                         let freeze_panes = self.sheets[sheet_index].freeze_panes.clone();
+                        let protection = self.sheets[sheet_index].protection;
+                        let visibility = self.sheets[sheet_index].visibility;
+                        let tab_color = self.sheets[sheet_index].tab_color;
                         let mut sheet = create_sheet_from_range(sheet_name, range, formula_range);
+                        attach_xlsx_annotations(&mut sheet, Path::new(&self.file_path), sheet_name);
                         let original_name = self.sheets[sheet_index].name.clone();
                         sheet.name = original_name;
                         sheet.freeze_panes = freeze_panes;
+                        sheet.protection = protection;
+                        sheet.visibility = visibility;
+                        sheet.tab_color = tab_color;
                         self.sheets[sheet_index] = sheet;
                         self.loaded_sheets.insert(sheet_index);
+                        self.note_sheet_read(sheet_index);
                         Ok(true)
                     }
                     Ok(Err(err)) => {
@@ -401,6 +584,21 @@ impl Workbook {
         self.sheets.get(index)
     }
 
+    /// Roughly the bytes the cells of all sheets take up
+    #[must_use]
+    pub fn estimated_bytes(&self) -> usize {
+        self.sheets
+            .iter()
+            .map(|sheet| sheet.data.estimated_bytes())
+            .sum()
+    }
+
+    /// All sheets in tab order, hidden ones included
+    #[must_use]
+    pub fn sheets(&self) -> &[Sheet] {
+        &self.sheets
+    }
+
     pub fn get_sheet_by_name(&self, name: &str) -> Option<&Sheet> {
         self.sheets.iter().find(|s| s.name == name)
     }
@@ -415,8 +613,7 @@ impl Workbook {
         let loaded_formula = self
             .sheets
             .get(sheet_index)
-            .and_then(|sheet| sheet.data.get(row))
-            .and_then(|cells| cells.get(col))
+            .and_then(|sheet| sheet.data.get(row, col))
             .and_then(|cell| cell.formula.clone());
 
         loaded_formula
@@ -468,15 +665,13 @@ impl Workbook {
             .get(sheet_index)
             .ok_or_else(|| anyhow::anyhow!("Sheet index out of range"))?;
 
-        let mut count = 0;
-        for row in 1..=sheet.max_rows {
-            if row < sheet.data.len() {
-                let has_data = sheet.data[row].iter().any(|cell| !cell.value.is_empty());
-                if has_data {
-                    count += 1;
-                }
-            }
-        }
+        let count = sheet
+            .data
+            .rows()
+            .filter(|&(row, _)| (1..=sheet.max_rows).contains(&row))
+            .map(|(_, mut cells)| cells.any(|(_, cell)| !cell.value.is_empty()))
+            .filter(|&has_data| has_data)
+            .count();
         Ok(count)
     }
 
@@ -487,20 +682,15 @@ impl Workbook {
             .get(sheet_index)
             .ok_or_else(|| anyhow::anyhow!("Sheet index out of range"))?;
 
-        let mut count = 0;
-        for col in 1..=sheet.max_cols {
-            let has_data = sheet.data.iter().any(|row| {
-                if col < row.len() {
-                    !row[col].value.is_empty()
-                } else {
-                    false
-                }
-            });
-            if has_data {
-                count += 1;
-            }
-        }
-        Ok(count)
+        let cols: BTreeSet<usize> = sheet
+            .data
+            .iter()
+            .filter(|&((_, col), cell)| {
+                (1..=sheet.max_cols).contains(&col) && !cell.value.is_empty()
+            })
+            .map(|((_, col), _)| col)
+            .collect();
+        Ok(cols.len())
     }
 
     /// Find header row candidates for a sheet.
@@ -522,18 +712,14 @@ impl Workbook {
         let max_scan = sheet.max_rows.min(20);
 
         for row in 1..=max_scan {
-            if row >= sheet.data.len() {
-                break;
-            }
-            let row_data = &sheet.data[row];
-            let non_empty_count = row_data
-                .iter()
-                .take(sheet.max_cols + 1)
-                .filter(|c| !c.value.is_empty())
-                .count();
+            let row_data: Vec<&Cell> = sheet
+                .data
+                .row_range(row, 0, sheet.max_cols)
+                .map(|(_, cell)| cell)
+                .collect();
+            let non_empty_count = row_data.iter().filter(|c| !c.value.is_empty()).count();
             let text_count = row_data
                 .iter()
-                .take(sheet.max_cols + 1)
                 .filter(|c| {
                     !c.value.is_empty()
                         && (c.cell_type == CellType::Text || c.cell_type == CellType::Boolean)
@@ -574,56 +760,94 @@ impl Workbook {
         Ok(format!("A1:{}{}", end_col, sheet.max_rows))
     }
 
+    /// Widens the current sheet's used range to take in `(row, col)`
     pub fn ensure_cell_exists(&mut self, row: usize, col: usize) {
         let sheet = &mut self.sheets[self.current_sheet_index];
+        sheet.max_rows = sheet.max_rows.max(row);
+        sheet.max_cols = sheet.max_cols.max(col);
+    }
 
-        // Expand rows if needed
-        if row >= sheet.data.len() {
-            let default_row_len = if sheet.data.is_empty() {
-                col + 1
-            } else {
-                sheet.data[0].len()
-            };
-            let rows_to_add = row + 1 - sheet.data.len();
-
-            sheet
-                .data
-                .extend(vec![vec![Cell::empty(); default_row_len]; rows_to_add]);
-            sheet.max_rows = sheet.max_rows.max(row);
+    /// Sets or, with `None`, removes the note on a cell of the current sheet
+    pub fn set_cell_comment(&mut self, row: usize, col: usize, comment: Option<String>) {
+        self.ensure_cell_exists(row, col);
+        let data = &mut self.sheets[self.current_sheet_index].data;
+        if data.cell(row, col).comment != comment {
+            let mut cell = data.cell(row, col).clone();
+            cell.comment = comment;
+            data.set(row, col, cell);
+            self.is_modified = true;
         }
+    }
 
-        // Expand columns if needed
-        if col >= sheet.data[0].len() {
-            for row_data in &mut sheet.data {
-                row_data.resize_with(col + 1, Cell::empty);
-            }
+    /// Sets or clears the `:fmt` display format of one cell
+    pub fn set_cell_number_format(&mut self, row: usize, col: usize, format: Option<NumberFormat>) {
+        self.ensure_cell_exists(row, col);
+        let data = &mut self.sheets[self.current_sheet_index].data;
+        if data.cell(row, col).number_format != format {
+            let mut cell = data.cell(row, col).clone();
+            cell.number_format = format;
+            data.set(row, col, cell);
+            self.is_modified = true;
+        }
+    }
 
-            sheet.max_cols = sheet.max_cols.max(col);
+    /// Sets or clears the `:fmt` display format of a whole column
+    pub fn set_column_number_format(&mut self, col: usize, format: Option<NumberFormat>) {
+        let formats = &mut self.sheets[self.current_sheet_index].column_formats;
+        if formats.get(&col) == format.as_ref() {
+            return;
         }
+        match format {
+            Some(format) => formats.insert(col, format),
+            None => formats.remove(&col),
+        };
+        self.is_modified = true;
     }
 
     pub fn set_cell_value(&mut self, row: usize, col: usize, value: String) -> Result<()> {
         self.ensure_cell_exists(row, col);
 
         let sheet = &mut self.sheets[self.current_sheet_index];
-        let current_value = &sheet.data[row][col].value;
+        let current_value = &sheet.data.cell(row, col).value;
 
         // Only set modified flag if value actually changes
         if current_value != &value {
             let is_formula = value.starts_with('=');
-            sheet.data[row][col] = Cell::new(value, is_formula);
-
-            // Update max_cols if needed
-            if col > sheet.max_cols && !sheet.data[row][col].value.is_empty() {
-                sheet.max_cols = col;
-            }
-
+            let mut cell = Cell::new(value, is_formula);
+            cell.inherit_annotations(sheet.data.cell(row, col));
+            sheet.data.set(row, col, cell);
             self.is_modified = true;
         }
 
         Ok(())
     }
 
+    /// Adds a conditional format to the current sheet, after its others
+    pub fn add_conditional_format(&mut self, rule: ConditionalFormat) {
+        self.sheets[self.current_sheet_index]
+            .conditional_formats
+            .push(rule);
+        self.is_modified = true;
+    }
+
+    /// Removes the `index`th conditional format of the current sheet, or all of them with
+    /// `None`. Returns how many were removed
+    pub fn remove_conditional_formats(&mut self, index: Option<usize>) -> usize {
+        let rules = &mut self.sheets[self.current_sheet_index].conditional_formats;
+        let removed = match index {
+            Some(index) if index < rules.len() => {
+                rules.remove(index);
+                1
+            }
+            Some(_) => 0,
+            None => rules.drain(..).count(),
+        };
+        if removed > 0 {
+            self.is_modified = true;
+        }
+        removed
+    }
+
     pub fn set_freeze_panes(&mut self, rows: usize, cols: usize) {
         let sheet = &mut self.sheets[self.current_sheet_index];
 
@@ -663,12 +887,99 @@ impl Workbook {
     }
 
     pub fn add_sheet(&mut self, name: &str, index: usize) -> Result<String> {
-        let sheet_name = name.trim();
+        self.add_sheet_with_data(Sheet::blank(name.to_string()), index)
+    }
 
-        self.validate_sheet_name(sheet_name)?;
-        self.insert_sheet_at_index(Sheet::blank(sheet_name.to_string()), index)?;
+    /// Insert a populated sheet after validating its name, returning the name used
+    pub fn add_sheet_with_data(&mut self, mut sheet: Sheet, index: usize) -> Result<String> {
+        let sheet_name = sheet.name.trim().to_string();
 
-        Ok(sheet_name.to_string())
+        self.validate_sheet_name(&sheet_name, None)?;
+        sheet.name.clone_from(&sheet_name);
+        self.insert_sheet_at_index(sheet, index)?;
+
+        Ok(sheet_name)
+    }
+
+    /// Renames the sheet at `index` after validating the name, returning the name used. Defined
+    /// names pointing at the sheet follow it.
+    pub fn rename_sheet(&mut self, index: usize, name: &str) -> Result<String> {
+        let new_name = name.trim().to_string();
+        let old_name = self
+            .sheets
+            .get(index)
+            .map(|sheet| sheet.name.clone())
+            .ok_or_else(|| anyhow::anyhow!("Sheet index out of range"))?;
+        self.validate_sheet_name(&new_name, Some(index))?;
+
+        // Lazy sheets are read from the file by their original name
+        self.ensure_sheet_loaded(index, &old_name)?;
+        self.sheets[index].name.clone_from(&new_name);
+
+        for defined in &mut self.defined_names {
+            if let Some((sheet, start, end)) = defined.target() {
+                if sheet.eq_ignore_ascii_case(&old_name) {
+                    *defined = DefinedName::for_range(defined.name.as_str(), &new_name, start, end);
+                }
+            }
+        }
+
+        self.is_modified = true;
+        Ok(new_name)
+    }
+
+    /// Returns `base` if no sheet uses it yet, otherwise `base` with a numeric suffix
+    pub fn unique_sheet_name(&self, base: &str) -> String {
+        let is_taken = |candidate: &str| {
+            self.sheets
+                .iter()
+                .any(|sheet| sheet.name.eq_ignore_ascii_case(candidate))
+        };
+
+        let base: String = base.chars().take(28).collect();
+        if !is_taken(&base) {
+            return base;
+        }
+
+        (2..)
+            .map(|n| format!("{base}{n}"))
+            .find(|candidate| !is_taken(candidate))
+            .unwrap_or(base)
+    }
+
+    /// `name` when no sheet uses it yet, otherwise the first free `name (n)`, the way Excel
+    /// names a copied sheet, shortened to fit the 31 character limit
+    pub fn copy_sheet_name(&self, name: &str) -> String {
+        let is_taken = |candidate: &str| {
+            self.sheets
+                .iter()
+                .any(|sheet| sheet.name.eq_ignore_ascii_case(candidate))
+        };
+        if !is_taken(name) {
+            return name.to_string();
+        }
+
+        (2..)
+            .map(|n| {
+                let suffix = format!(" ({n})");
+                let base: String = name.chars().take(31 - suffix.chars().count()).collect();
+                format!("{base}{suffix}")
+            })
+            .find(|candidate| !is_taken(candidate))
+            .unwrap_or_default()
+    }
+
+    /// The first `SheetN` name not in use, counting on from the number of sheets as Excel does
+    pub fn default_sheet_name(&self) -> String {
+        (self.sheets.len() + 1..)
+            .map(|n| format!("Sheet{n}"))
+            .find(|candidate| {
+                !self
+                    .sheets
+                    .iter()
+                    .any(|sheet| sheet.name.eq_ignore_ascii_case(candidate))
+            })
+            .unwrap_or_default()
     }
 
     pub fn delete_current_sheet(&mut self) -> Result<()> {
@@ -712,12 +1023,9 @@ impl Workbook {
 
         let freeze_changed = shrink_freeze_rows(&mut sheet.freeze_panes, row, row);
 
-        // Only remove the row if it exists in the data
-        if row < sheet.data.len() {
-            sheet.data.remove(row);
-            self.recalculate_max_cols();
-            self.is_modified = true;
-        }
+        sheet.data.remove_row(row);
+        self.recalculate_max_cols();
+        self.is_modified = true;
 
         if freeze_changed {
             self.is_modified = true;
@@ -726,6 +1034,17 @@ impl Workbook {
         Ok(())
     }
 
+    /// Inserts `cells` as row `row` of the current sheet, shifting the rows below it down
+    pub fn insert_row(&mut self, row: usize, cells: Vec<(usize, Cell)>) {
+        self.sheets[self.current_sheet_index]
+            .data
+            .insert_row(row, cells);
+
+        self.recalculate_max_rows();
+        self.recalculate_max_cols();
+        self.is_modified = true;
+    }
+
     // Delete a range of rows from the current sheet
     pub fn delete_rows(&mut self, start_row: usize, end_row: usize) -> Result<()> {
         let sheet = &mut self.sheets[self.current_sheet_index];
@@ -740,28 +1059,40 @@ impl Workbook {
             return Ok(());
         }
 
-        // Adjust end_row to not exceed the data length
-        let adjusted_end_row = end_row.min(sheet.data.len() - 1);
-
         // If start_row is valid but end_row exceeds max_rows, adjust end_row to max_rows
-        let effective_end_row = if end_row > sheet.max_rows {
-            sheet.max_rows
-        } else {
-            adjusted_end_row
-        };
+        let effective_end_row = end_row.min(sheet.max_rows);
 
         let freeze_changed =
             shrink_freeze_rows(&mut sheet.freeze_panes, start_row, effective_end_row);
 
-        // Only proceed if there are rows to delete
-        if start_row <= effective_end_row && start_row < sheet.data.len() {
-            // Remove rows in reverse order to avoid index shifting issues
-            for row in (start_row..=effective_end_row).rev() {
-                if row < sheet.data.len() {
-                    sheet.data.remove(row);
-                }
-            }
+        let rows: Vec<usize> = (start_row..=effective_end_row).collect();
+        sheet.data.remove_rows(&rows);
+        self.recalculate_max_cols();
+        self.is_modified = true;
+
+        if freeze_changed {
+            self.is_modified = true;
+        }
 
+        Ok(())
+    }
+
+    /// Delete an ascending list of possibly non-contiguous rows from the current sheet in one pass
+    pub fn delete_row_set(&mut self, rows: &[usize]) -> Result<()> {
+        let sheet = &mut self.sheets[self.current_sheet_index];
+
+        let mut freeze_changed = false;
+        for &row in rows.iter().rev() {
+            freeze_changed |= shrink_freeze_rows(&mut sheet.freeze_panes, row, row);
+        }
+
+        let rows: Vec<usize> = rows
+            .iter()
+            .copied()
+            .filter(|&row| (1..=sheet.max_rows).contains(&row))
+            .collect();
+        if !rows.is_empty() {
+            sheet.data.remove_rows(&rows);
             self.recalculate_max_cols();
             self.is_modified = true;
         }
@@ -787,19 +1118,12 @@ impl Workbook {
         }
 
         let freeze_changed = shrink_freeze_cols(&mut sheet.freeze_panes, col, col);
-        let mut has_data = false;
-        for row in &sheet.data {
-            if col < row.len() && !row[col].value.is_empty() {
-                has_data = true;
-                break;
-            }
-        }
-
-        for row in sheet.data.iter_mut() {
-            if col < row.len() {
-                row.remove(col);
-            }
-        }
+        shift_column_formats(&mut sheet.column_formats, col, col);
+        let has_data = sheet
+            .data
+            .iter()
+            .any(|((_, cell_col), cell)| cell_col == col && !cell.value.is_empty());
+        sheet.data.remove_columns(col, col);
 
         self.recalculate_max_cols();
         self.recalculate_max_rows();
@@ -811,6 +1135,28 @@ impl Workbook {
         Ok(())
     }
 
+    /// Inserts `cells`, each with its row, as column `col` of the current sheet, shifting the
+    /// columns right of it, and their formats, one place right
+    pub fn insert_column(&mut self, col: usize, cells: &[(usize, Cell)]) {
+        let sheet = &mut self.sheets[self.current_sheet_index];
+        sheet.data.insert_column(col, cells);
+
+        sheet.column_formats = std::mem::take(&mut sheet.column_formats)
+            .into_iter()
+            .map(|(format_col, format)| {
+                if format_col >= col {
+                    (format_col + 1, format)
+                } else {
+                    (format_col, format)
+                }
+            })
+            .collect();
+
+        self.recalculate_max_rows();
+        self.recalculate_max_cols();
+        self.is_modified = true;
+    }
+
     // Delete a range of columns from the current sheet
     pub fn delete_columns(&mut self, start_col: usize, end_col: usize) -> Result<()> {
         let sheet = &mut self.sheets[self.current_sheet_index];
@@ -830,26 +1176,11 @@ impl Workbook {
 
         let freeze_changed =
             shrink_freeze_cols(&mut sheet.freeze_panes, start_col, effective_end_col);
-        let mut has_data = false;
-        for row in &sheet.data {
-            for col in start_col..=effective_end_col {
-                if col < row.len() && !row[col].value.is_empty() {
-                    has_data = true;
-                    break;
-                }
-            }
-            if has_data {
-                break;
-            }
-        }
-
-        for row in sheet.data.iter_mut() {
-            for col in (start_col..=effective_end_col).rev() {
-                if col < row.len() {
-                    row.remove(col);
-                }
-            }
-        }
+        shift_column_formats(&mut sheet.column_formats, start_col, effective_end_col);
+        let has_data = sheet.data.iter().any(|((_, col), cell)| {
+            (start_col..=effective_end_col).contains(&col) && !cell.value.is_empty()
+        });
+        sheet.data.remove_columns(start_col, effective_end_col);
 
         self.recalculate_max_cols();
         self.recalculate_max_rows();
@@ -877,12 +1208,88 @@ impl Workbook {
         self.lazy_loading
     }
 
+    /// What a background thread needs to read the sheet at `index`, or `None` when lazy loading
+    /// left nothing to read
+    pub fn sheet_load_request(&self, index: usize) -> Option<SheetLoadRequest> {
+        let sheet = self.sheets.get(index)?;
+        if !self.lazy_loading || sheet.is_loaded {
+            return None;
+        }
+        Some(SheetLoadRequest {
+            sheet_name: sheet.name.clone(),
+            sheet_index: index,
+            sheet_count: self.sheets.len(),
+            file_path: PathBuf::from(&self.file_path),
+        })
+    }
+
+    /// Completes the unloaded sheet named `sheet_name` with the end of a read for a
+    /// [`SheetLoadRequest`], after the rows streamed in before it. The sheet keeps the freeze
+    /// panes, protection, visibility and tab color read at open. Returns the sheet's index, or
+    /// `None` when the sheet was deleted or loaded meanwhile
+    pub fn finish_streamed_sheet(
+        &mut self,
+        sheet_name: &str,
+        end: StreamedSheetEnd,
+    ) -> Option<usize> {
+        let index = self.append_streamed_rows(sheet_name, end.rows)?;
+        let sheet = &mut self.sheets[index];
+        (sheet.max_rows, sheet.max_cols) = end.size;
+        apply_formula_metadata(&mut sheet.data, end.size, end.formulas);
+        end.annotations.apply(sheet);
+        sheet.is_loaded = true;
+        self.loaded_sheets.insert(index);
+        self.note_sheet_read(index);
+        Some(index)
+    }
+
+    /// Adds rows read ahead of the rest of a sheet to the unloaded sheet of that name, which
+    /// can then be viewed while reading goes on. Returns the sheet's index, or `None` when the
+    /// sheet was deleted or loaded meanwhile
+    pub fn append_streamed_rows(&mut self, sheet_name: &str, rows: StreamedRows) -> Option<usize> {
+        let index = self
+            .sheets
+            .iter()
+            .position(|sheet| !sheet.is_loaded && sheet.name == sheet_name)?;
+        let sheet = &mut self.sheets[index];
+        if rows.moved_cols > 0 {
+            sheet.data.insert_blank_columns(1, rows.moved_cols);
+            sheet.max_cols += rows.moved_cols;
+        }
+        sheet.max_rows = sheet.max_rows.max(rows.rows_read);
+        for (row, col, cell) in rows.cells {
+            sheet.max_cols = sheet.max_cols.max(col);
+            sheet.data.set(row, col, cell);
+        }
+        Some(index)
+    }
+
+    /// Drops the rows streamed into the unloaded sheet at `index`, so a fresh read of it
+    /// starts from nothing
+    pub fn discard_streamed_rows(&mut self, index: usize) {
+        if let Some(sheet) = self.sheets.get_mut(index).filter(|sheet| !sheet.is_loaded) {
+            sheet.data = CellGrid::new();
+            sheet.max_rows = 0;
+            sheet.max_cols = 0;
+        }
+    }
+
+    /// Whether the sheet at `index` can be shown: read in full, or with its first rows
+    /// streamed in while the rest is read
     pub fn is_sheet_loaded(&self, sheet_index: usize) -> bool {
         if !self.lazy_loading || sheet_index >= self.sheets.len() {
             return true;
         }
 
-        self.sheets[sheet_index].is_loaded
+        self.sheets[sheet_index].is_loaded || self.is_sheet_streaming(sheet_index)
+    }
+
+    /// Whether the sheet at `index` shows the rows read so far while the rest is still read.
+    /// Such a sheet is not changed until it is whole
+    pub fn is_sheet_streaming(&self, sheet_index: usize) -> bool {
+        self.sheets
+            .get(sheet_index)
+            .is_some_and(|sheet| self.lazy_loading && !sheet.is_loaded && sheet.max_rows > 0)
     }
 
     pub fn insert_sheet_at_index(&mut self, sheet: Sheet, index: usize) -> Result<()> {
@@ -903,42 +1310,49 @@ impl Workbook {
         Ok(())
     }
 
-    pub fn recalculate_max_cols(&mut self) {
-        let sheet = &mut self.sheets[self.current_sheet_index];
+    #[must_use]
+    pub fn defined_names(&self) -> &[DefinedName] {
+        &self.defined_names
+    }
 
-        // Find maximum non-empty column across all rows
-        let actual_max_col = sheet
-            .data
+    /// Finds a defined name, ignoring case as Excel does
+    #[must_use]
+    pub fn find_defined_name(&self, name: &str) -> Option<&DefinedName> {
+        self.defined_names
             .iter()
-            .map(|row| {
-                // Find last non-empty cell in this row
-                row.iter()
-                    .enumerate()
-                    .rev()
-                    .find(|(_, cell)| !cell.value.is_empty())
-                    .map(|(idx, _)| idx)
-                    .unwrap_or(0)
-            })
-            .max()
-            .unwrap_or(0);
+            .find(|defined| defined.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Adds `defined`, replacing a name that differs only in case. Returns true when an
+    /// existing name was replaced.
+    pub fn define_name(&mut self, defined: DefinedName) -> bool {
+        self.is_modified = true;
+        match self
+            .defined_names
+            .iter_mut()
+            .find(|existing| existing.name.eq_ignore_ascii_case(&defined.name))
+        {
+            Some(existing) => {
+                *existing = defined;
+                true
+            }
+            None => {
+                self.defined_names.push(defined);
+                false
+            }
+        }
+    }
 
-        sheet.max_cols = actual_max_col.max(1);
+    pub fn recalculate_max_cols(&mut self) {
+        let sheet = &mut self.sheets[self.current_sheet_index];
+
+        sheet.max_cols = sheet.data.last_col_with_value().max(1);
     }
 
     pub fn recalculate_max_rows(&mut self) {
         let sheet = &mut self.sheets[self.current_sheet_index];
 
-        // Find last row with any non-empty cells
-        let actual_max_row = sheet
-            .data
-            .iter()
-            .enumerate()
-            .rev()
-            .find(|(_, row)| row.iter().any(|cell| !cell.value.is_empty()))
-            .map(|(idx, _)| idx)
-            .unwrap_or(0);
-
-        sheet.max_rows = actual_max_row.max(1);
+        sheet.max_rows = sheet.data.last_row_with_value().max(1);
     }
 
     fn ensure_all_sheets_loaded(&mut self) -> Result<()> {
@@ -961,7 +1375,9 @@ impl Workbook {
         Ok(())
     }
 
-    fn validate_sheet_name(&self, name: &str) -> Result<()> {
+    /// Checks Excel's sheet name rules. The sheet at `renaming` may already use the name, so a
+    /// rename that only changes case is allowed.
+    fn validate_sheet_name(&self, name: &str, renaming: Option<usize>) -> Result<()> {
         if name.is_empty() {
             anyhow::bail!("Sheet name cannot be empty");
         }
@@ -984,7 +1400,8 @@ impl Workbook {
         if self
             .sheets
             .iter()
-            .any(|sheet| sheet.name.eq_ignore_ascii_case(name))
+            .enumerate()
+            .any(|(index, sheet)| Some(index) != renaming && sheet.name.eq_ignore_ascii_case(name))
         {
             anyhow::bail!("Sheet '{}' already exists", name);
         }
@@ -1004,6 +1421,9 @@ impl Workbook {
             calamine_workbook: CalamineWorkbook::None,
             lazy_loading: false,
             loaded_sheets,
+            defined_names: Vec::new(),
+            original_sheets: Vec::new(),
+            file_modified_at: None,
         }
     }
 }
@@ -0,0 +1,119 @@
+use quick_xml::events::Event;
+use std::path::Path;
+use zip::ZipArchive;
+
+use crate::excel::CellGrid;
+use crate::utils::parse_cell_reference;
+
+use super::formula_lookup::{
+    attr_value, open_xlsx_archive, read_sheet_relationships, read_zip_entry,
+    resolve_xlsx_sheet_path,
+};
+
+const COMMENTS_RELATIONSHIP_SUFFIX: &str = "/comments";
+
+/// Reads the notes attached to cells of `sheet_name` as `((row, col), text)` pairs
+pub(super) fn lookup_comments_in_xlsx(
+    file: &Path,
+    sheet_name: &str,
+) -> Vec<((usize, usize), String)> {
+    read_comments(file, sheet_name).unwrap_or_default()
+}
+
+/// Attaches `comments` to the matching cells, even those outside the data
+pub(super) fn apply_comments(data: &mut CellGrid, comments: Vec<((usize, usize), String)>) {
+    for ((row, col), text) in comments {
+        data.update_cell(row, col, |cell| cell.comment = Some(text));
+    }
+}
+
+fn read_comments(file: &Path, sheet_name: &str) -> Option<Vec<((usize, usize), String)>> {
+    let mut archive = open_xlsx_archive(file)?;
+    let sheet_path = resolve_xlsx_sheet_path(&mut archive, sheet_name)?;
+    let comments_path = resolve_comments_path(&mut archive, &sheet_path)?;
+    let comments_xml = read_zip_entry(&mut archive, &comments_path)?;
+
+    let mut reader = quick_xml::Reader::from_str(&comments_xml);
+    reader.config_mut().trim_text(false);
+    let mut buf = Vec::new();
+    let mut comments = Vec::new();
+    let mut current: Option<((usize, usize), String)> = None;
+    let mut in_text = false;
+
+    loop {
+        match reader.read_event_into(&mut buf).ok()? {
+            Event::Start(event) if event.name().as_ref() == b"comment" => {
+                current = attr_value(&reader, &event, b"ref")
+                    .and_then(|reference| parse_cell_reference(&reference))
+                    .map(|cell| (cell, String::new()));
+            }
+            Event::End(event) if event.name().as_ref() == b"comment" => {
+                if let Some((cell, text)) = current.take() {
+                    let text = text.trim().to_string();
+                    if !text.is_empty() {
+                        comments.push((cell, text));
+                    }
+                }
+            }
+            Event::Start(event) if event.name().as_ref() == b"t" => in_text = true,
+            Event::End(event) if event.name().as_ref() == b"t" => in_text = false,
+            Event::Text(text) if in_text => {
+                if let Some((_, comment)) = current.as_mut() {
+                    let decoded = text.decode().ok()?;
+                    let unescaped = quick_xml::escape::unescape(decoded.as_ref()).ok()?;
+                    comment.push_str(&unescaped);
+                }
+            }
+            // Entities such as `&amp;` arrive as separate events
+            Event::GeneralRef(reference) if in_text => {
+                if let Some((_, comment)) = current.as_mut() {
+                    if let Some(ch) = reference.resolve_char_ref().ok()? {
+                        comment.push(ch);
+                    } else {
+                        let name = reference.decode().ok()?;
+                        comment.push_str(quick_xml::escape::resolve_xml_entity(&name)?);
+                    }
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Some(comments)
+}
+
+/// Finds the comments part linked from a worksheet's relationships file
+fn resolve_comments_path<R: std::io::Read + std::io::Seek>(
+    archive: &mut ZipArchive<R>,
+    sheet_path: &str,
+) -> Option<String> {
+    let (sheet_dir, _) = sheet_path.rsplit_once('/')?;
+    read_sheet_relationships(archive, sheet_path)
+        .into_iter()
+        .find(|relationship| relationship.kind.ends_with(COMMENTS_RELATIONSHIP_SUFFIX))
+        .map(|relationship| resolve_relative_path(sheet_dir, &relationship.target))
+}
+
+/// Resolves a relationship target such as `../comments1.xml` against the part's directory
+pub(super) fn resolve_relative_path(base_dir: &str, target: &str) -> String {
+    if let Some(absolute) = target.strip_prefix('/') {
+        return absolute.to_string();
+    }
+
+    let mut parts: Vec<&str> = base_dir
+        .split('/')
+        .filter(|part| !part.is_empty())
+        .collect();
+    for part in target.split('/') {
+        match part {
+            ".." => {
+                parts.pop();
+            }
+            "." | "" => {}
+            part => parts.push(part),
+        }
+    }
+    parts.join("/")
+}
@@ -0,0 +1,252 @@
+use quick_xml::events::Event;
+use std::path::Path;
+
+use crate::excel::{parse_cf_range, CfOperator, ConditionalFormat};
+
+use super::formula_lookup::{
+    attr_value, open_xlsx_archive, read_zip_entry, resolve_xlsx_sheet_path,
+};
+use super::styles_lookup::color_value;
+use super::tab_colors::parse_theme_colors;
+
+/// The colors of a `dxf` style, which conditional formats apply on top of a cell's own
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct DxfColors {
+    fill: Option<u32>,
+    font: Option<u32>,
+}
+
+/// The parts of a `cellIs` rule read so far
+struct RuleSpec {
+    operator: Option<CfOperator>,
+    dxf_id: Option<usize>,
+    formulas: Vec<String>,
+}
+
+/// Reads the "cell value" conditional formats of `sheet_name` that compare with a number or
+/// text. Rules with formulas, data bars, color scales and the like are left out
+pub(super) fn lookup_conditional_formats_in_xlsx(
+    file: &Path,
+    sheet_name: &str,
+) -> Vec<ConditionalFormat> {
+    read_conditional_formats(file, sheet_name).unwrap_or_default()
+}
+
+fn read_conditional_formats(file: &Path, sheet_name: &str) -> Option<Vec<ConditionalFormat>> {
+    let mut archive = open_xlsx_archive(file)?;
+    let sheet_path = resolve_xlsx_sheet_path(&mut archive, sheet_name)?;
+    let sheet_xml = read_zip_entry(&mut archive, &sheet_path)?;
+    // Most sheets have none, so skip reading the styles
+    if !sheet_xml.contains("<conditionalFormatting") {
+        return Some(Vec::new());
+    }
+
+    let styles_xml = read_zip_entry(&mut archive, "xl/styles.xml")?;
+    let theme_colors = read_zip_entry(&mut archive, "xl/theme/theme1.xml")
+        .and_then(|theme_xml| parse_theme_colors(&theme_xml))
+        .unwrap_or_default();
+    let dxfs = parse_dxf_colors(&styles_xml, &theme_colors)?;
+
+    let mut reader = quick_xml::Reader::from_str(&sheet_xml);
+    reader.config_mut().trim_text(true);
+    let mut buf = Vec::new();
+    let mut ranges = Vec::new();
+    let mut rule = None;
+    let mut in_formula = false;
+    let mut formats = Vec::new();
+
+    loop {
+        match reader.read_event_into(&mut buf).ok()? {
+            Event::Start(event) if event.name().as_ref() == b"conditionalFormatting" => {
+                ranges = attr_value(&reader, &event, b"sqref")
+                    .unwrap_or_default()
+                    .split_whitespace()
+                    .filter_map(parse_cf_range)
+                    .collect();
+            }
+            Event::Start(event) if event.name().as_ref() == b"cfRule" => {
+                let is_cell_value =
+                    attr_value(&reader, &event, b"type").as_deref() == Some("cellIs");
+                rule = is_cell_value.then(|| RuleSpec {
+                    operator: attr_value(&reader, &event, b"operator")
+                        .as_deref()
+                        .and_then(parse_operator),
+                    dxf_id: attr_value(&reader, &event, b"dxfId")
+                        .and_then(|id| id.parse::<usize>().ok()),
+                    formulas: Vec::new(),
+                });
+            }
+            Event::Start(event) if event.name().as_ref() == b"formula" => {
+                in_formula = true;
+                if let Some(rule) = rule.as_mut() {
+                    rule.formulas.push(String::new());
+                }
+            }
+            Event::Text(text) if in_formula => {
+                if let Some(formula) = rule.as_mut().and_then(|rule| rule.formulas.last_mut()) {
+                    let decoded = text.decode().ok()?;
+                    formula.push_str(&quick_xml::escape::unescape(decoded.as_ref()).ok()?);
+                }
+            }
+            // Entities such as `&quot;` arrive as separate events
+            Event::GeneralRef(reference) if in_formula => {
+                if let Some(formula) = rule.as_mut().and_then(|rule| rule.formulas.last_mut()) {
+                    if let Some(ch) = reference.resolve_char_ref().ok()? {
+                        formula.push(ch);
+                    } else {
+                        let name = reference.decode().ok()?;
+                        formula.push_str(quick_xml::escape::resolve_xml_entity(&name)?);
+                    }
+                }
+            }
+            Event::End(event) if event.name().as_ref() == b"formula" => in_formula = false,
+            Event::End(event) if event.name().as_ref() == b"cfRule" => {
+                // Between rules have two formulas and are not read
+                let Some(RuleSpec {
+                    operator: Some(operator),
+                    dxf_id: Some(dxf_id),
+                    formulas,
+                }) = rule.take()
+                else {
+                    continue;
+                };
+                let [formula] = formulas.as_slice() else {
+                    continue;
+                };
+                let (Some(value), Some(colors)) = (plain_value(formula), dxfs.get(dxf_id)) else {
+                    continue;
+                };
+                if colors.fill.is_none() && colors.font.is_none() {
+                    continue;
+                }
+                formats.extend(ranges.iter().map(|&(start, end)| ConditionalFormat {
+                    start,
+                    end,
+                    operator,
+                    value: value.clone(),
+                    fill: colors.fill,
+                    font: colors.font,
+                }));
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Some(formats)
+}
+
+fn parse_operator(operator: &str) -> Option<CfOperator> {
+    match operator {
+        "equal" => Some(CfOperator::Equal),
+        "notEqual" => Some(CfOperator::NotEqual),
+        "greaterThan" => Some(CfOperator::Greater),
+        "greaterThanOrEqual" => Some(CfOperator::GreaterOrEqual),
+        "lessThan" => Some(CfOperator::Less),
+        "lessThanOrEqual" => Some(CfOperator::LessOrEqual),
+        _ => None,
+    }
+}
+
+/// The number or quoted text a rule's formula holds; `None` for references and expressions
+fn plain_value(formula: &str) -> Option<String> {
+    let formula = formula.trim();
+    if let Some(text) = formula
+        .strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+    {
+        return Some(text.replace("\"\"", "\""));
+    }
+    formula.parse::<f64>().is_ok().then(|| formula.to_string())
+}
+
+/// The colors of each `dxf` entry, by index. Fills of a `dxf` name their color with
+/// `bgColor`, unlike cell fills
+fn parse_dxf_colors(styles_xml: &str, theme_colors: &[u32]) -> Option<Vec<DxfColors>> {
+    let mut reader = quick_xml::Reader::from_str(styles_xml);
+    reader.config_mut().trim_text(true);
+    let mut buf = Vec::new();
+
+    let mut in_dxfs = false;
+    let mut in_font = false;
+    let mut in_fill = false;
+    let mut current = DxfColors::default();
+    let mut dxfs = Vec::new();
+
+    loop {
+        let event = reader.read_event_into(&mut buf).ok()?;
+        match &event {
+            Event::Eof => break,
+            Event::Start(start) if start.name().as_ref() == b"dxfs" => in_dxfs = true,
+            Event::End(end) if end.name().as_ref() == b"dxfs" => in_dxfs = false,
+            _ if !in_dxfs => {}
+
+            Event::Start(start) if start.name().as_ref() == b"dxf" => {
+                current = DxfColors::default();
+            }
+            Event::Empty(empty) if empty.name().as_ref() == b"dxf" => {
+                dxfs.push(DxfColors::default());
+            }
+            Event::End(end) if end.name().as_ref() == b"dxf" => dxfs.push(current),
+            Event::Start(start) if start.name().as_ref() == b"font" => in_font = true,
+            Event::End(end) if end.name().as_ref() == b"font" => in_font = false,
+            Event::Start(start) if start.name().as_ref() == b"fill" => in_fill = true,
+            Event::End(end) if end.name().as_ref() == b"fill" => in_fill = false,
+            Event::Start(element) | Event::Empty(element)
+                if in_font && element.name().as_ref() == b"color" =>
+            {
+                current.font = color_value(&reader, element, theme_colors);
+            }
+            Event::Start(element) | Event::Empty(element)
+                if in_fill && element.name().as_ref() == b"bgColor" =>
+            {
+                current.fill = color_value(&reader, element, theme_colors);
+            }
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Some(dxfs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_dxf_colors, plain_value, DxfColors};
+
+    #[test]
+    fn reads_dxf_colors_and_plain_rule_values() {
+        let styles_xml = r#"<styleSheet>
+            <fills count="1"><fill><patternFill patternType="solid"><fgColor rgb="FF00FF00"/></patternFill></fill></fills>
+            <dxfs count="3">
+                <dxf><font><color rgb="FF9C0006"/></font><fill><patternFill><bgColor rgb="FFFFC7CE"/></patternFill></fill></dxf>
+                <dxf><font><b/></font></dxf>
+                <dxf><fill><patternFill><bgColor theme="4" tint="0"/></patternFill></fill></dxf>
+            </dxfs>
+        </styleSheet>"#;
+        let theme = [0xFFFFFF, 0x000000, 0xE7E6E6, 0x44546A, 0x4472C4];
+
+        assert_eq!(
+            parse_dxf_colors(styles_xml, &theme).unwrap(),
+            vec![
+                DxfColors {
+                    fill: Some(0xFFC7CE),
+                    font: Some(0x9C0006)
+                },
+                DxfColors::default(),
+                DxfColors {
+                    fill: Some(0x4472C4),
+                    font: None
+                },
+            ]
+        );
+
+        assert_eq!(plain_value("100").as_deref(), Some("100"));
+        assert_eq!(
+            plain_value(r#""say ""hi""""#).as_deref(),
+            Some(r#"say "hi""#)
+        );
+        assert_eq!(plain_value("$B$1"), None);
+    }
+}
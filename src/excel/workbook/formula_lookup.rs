@@ -86,6 +86,74 @@ pub(super) fn read_zip_entry<R: Read + Seek>(
     Some(contents)
 }
 
+/// Opens `file` as a zip archive when it is an xlsx or xlsm workbook
+pub(super) fn open_xlsx_archive(file: &Path) -> Option<ZipArchive<File>> {
+    let extension = file
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_ascii_lowercase())?;
+    if extension != "xlsx" && extension != "xlsm" {
+        return None;
+    }
+
+    ZipArchive::new(File::open(file).ok()?).ok()
+}
+
+/// One entry of a worksheet's relationships part
+pub(super) struct SheetRelationship {
+    pub id: String,
+    pub kind: String,
+    pub target: String,
+    /// The target is a URL outside the file, as a hyperlink's is
+    pub external: bool,
+}
+
+/// Reads the relationships of the worksheet stored at `sheet_path`
+pub(super) fn read_sheet_relationships<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    sheet_path: &str,
+) -> Vec<SheetRelationship> {
+    let Some((sheet_dir, sheet_file)) = sheet_path.rsplit_once('/') else {
+        return Vec::new();
+    };
+    let Some(rels_xml) = read_zip_entry(archive, &format!("{sheet_dir}/_rels/{sheet_file}.rels"))
+    else {
+        return Vec::new();
+    };
+
+    let mut reader = quick_xml::Reader::from_str(&rels_xml);
+    reader.config_mut().trim_text(true);
+    let mut buf = Vec::new();
+    let mut relationships = Vec::new();
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(event) | Event::Empty(event))
+                if event.name().as_ref() == b"Relationship" =>
+            {
+                let id = attr_value(&reader, &event, b"Id");
+                let kind = attr_value(&reader, &event, b"Type");
+                let target = attr_value(&reader, &event, b"Target");
+                let external = attr_value(&reader, &event, b"TargetMode")
+                    .is_some_and(|mode| mode.eq_ignore_ascii_case("External"));
+                if let (Some(id), Some(kind), Some(target)) = (id, kind, target) {
+                    relationships.push(SheetRelationship {
+                        id,
+                        kind,
+                        target,
+                        external,
+                    });
+                }
+            }
+            Ok(Event::Eof) | Err(_) => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    relationships
+}
+
 pub(super) fn attr_value(
     reader: &quick_xml::Reader<&[u8]>,
     event: &quick_xml::events::BytesStart<'_>,
@@ -0,0 +1,74 @@
+use quick_xml::events::Event;
+use std::path::Path;
+
+use crate::excel::CellGrid;
+use crate::utils::parse_cell_reference;
+
+use super::formula_lookup::{
+    attr_value, open_xlsx_archive, read_sheet_relationships, read_zip_entry,
+    resolve_xlsx_sheet_path,
+};
+
+/// Reads the hyperlinks of `sheet_name` as `((row, col), target)` pairs. Links to a place
+/// inside the workbook are returned as `#Sheet!A1`.
+pub(super) fn lookup_hyperlinks_in_xlsx(
+    file: &Path,
+    sheet_name: &str,
+) -> Vec<((usize, usize), String)> {
+    read_hyperlinks(file, sheet_name).unwrap_or_default()
+}
+
+/// Attaches hyperlink targets to the matching cells
+pub(super) fn apply_hyperlinks(data: &mut CellGrid, links: Vec<((usize, usize), String)>) {
+    for ((row, col), target) in links {
+        data.update_cell(row, col, |cell| cell.hyperlink = Some(target));
+    }
+}
+
+fn read_hyperlinks(file: &Path, sheet_name: &str) -> Option<Vec<((usize, usize), String)>> {
+    let mut archive = open_xlsx_archive(file)?;
+    let sheet_path = resolve_xlsx_sheet_path(&mut archive, sheet_name)?;
+    let sheet_xml = read_zip_entry(&mut archive, &sheet_path)?;
+    if !sheet_xml.contains("<hyperlink ") {
+        return Some(Vec::new());
+    }
+    let relationships = read_sheet_relationships(&mut archive, &sheet_path);
+
+    let mut reader = quick_xml::Reader::from_str(&sheet_xml);
+    reader.config_mut().trim_text(true);
+    let mut buf = Vec::new();
+    let mut links = Vec::new();
+
+    loop {
+        match reader.read_event_into(&mut buf).ok()? {
+            Event::Start(event) | Event::Empty(event) if event.name().as_ref() == b"hyperlink" => {
+                // A link over a range is attached to its top-left cell
+                let cell = attr_value(&reader, &event, b"ref").and_then(|reference| {
+                    parse_cell_reference(reference.split(':').next().unwrap_or_default())
+                });
+                let external = attr_value(&reader, &event, b"r:id").and_then(|id| {
+                    relationships
+                        .iter()
+                        .find(|relationship| relationship.id == id)
+                        .map(|relationship| relationship.target.clone())
+                });
+                let location = attr_value(&reader, &event, b"location");
+
+                let target = match (external, location) {
+                    (Some(url), Some(location)) => Some(format!("{url}#{location}")),
+                    (Some(url), None) => Some(url),
+                    (None, Some(location)) => Some(format!("#{location}")),
+                    (None, None) => None,
+                };
+                if let (Some(cell), Some(target)) = (cell, target) {
+                    links.push((cell, target));
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Some(links)
+}
@@ -0,0 +1,588 @@
+use anyhow::{Context, Result};
+use quick_xml::escape::escape;
+use quick_xml::events::{BytesText, Event};
+use quick_xml::name::QName;
+use quick_xml::{Reader, Writer};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::{Cursor, Read, Seek, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use super::comments::resolve_relative_path;
+use super::formula_lookup::{
+    attr_value, open_xlsx_archive, read_sheet_relationships, read_zip_entry,
+    resolve_xlsx_sheet_path,
+};
+use super::styles_merge::{
+    map_attributes, merge_styles, rewrite_attributes, shifted, StyleOffsets,
+};
+use super::Workbook;
+use crate::excel::{ColumnFormats, ConditionalFormat, Sheet, SheetVisibility};
+
+/// Links a copied sheet, and the parts it links to, may have. They lead only to parts of the
+/// sheet's own, such as its comments and charts; a sheet with other links, such as to a pivot
+/// table's cache, is written anew
+const COPYABLE_RELATIONSHIPS: [&str; 12] = [
+    "comments",
+    "vmlDrawing",
+    "drawing",
+    "table",
+    "printerSettings",
+    "hyperlink",
+    "image",
+    "chart",
+    "chartUserShapes",
+    "chartStyle",
+    "chartColorStyle",
+    "themeOverride",
+];
+
+/// A sheet as read from the file, less its cells. While a sheet still matches it, saving
+/// copies the sheet's XML from the file instead of writing it anew
+#[derive(Clone, PartialEq)]
+pub(super) struct OriginalSheet {
+    name: String,
+    /// The revision of the sheet's cells once read, or `None` while they are unread
+    revision: Option<u64>,
+    freeze_panes: (usize, usize),
+    protected: bool,
+    visibility: SheetVisibility,
+    tab_color: Option<u32>,
+    column_formats: ColumnFormats,
+    conditional_formats: Vec<ConditionalFormat>,
+}
+
+impl OriginalSheet {
+    pub(super) fn of(sheet: &Sheet) -> Self {
+        Self {
+            name: sheet.name.clone(),
+            revision: sheet.is_loaded.then(|| sheet.data.revision()),
+            freeze_panes: (sheet.freeze_panes.rows, sheet.freeze_panes.cols),
+            // `:unlock` lifts protection for the session only, so the file's stays right
+            protected: sheet.protection.is_protected(),
+            visibility: sheet.visibility,
+            tab_color: sheet.tab_color,
+            column_formats: sheet.column_formats.clone(),
+            conditional_formats: sheet.conditional_formats.clone(),
+        }
+    }
+}
+
+/// When the file at `path` was last written, to tell later that it is unchanged
+pub(super) fn modified_at(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|file| file.modified())
+        .ok()
+}
+
+/// The sheets saving copies from the original xlsx, with where the file stores each
+#[derive(Default)]
+pub(super) struct KeptSheets {
+    source: PathBuf,
+    /// The name of each kept sheet and its path in the file, in tab order
+    sheets: Vec<(String, String)>,
+}
+
+impl KeptSheets {
+    pub(super) fn is_empty(&self) -> bool {
+        self.sheets.is_empty()
+    }
+
+    pub(super) fn contains(&self, sheet_name: &str) -> bool {
+        self.sheets.iter().any(|(name, _)| name == sheet_name)
+    }
+
+    /// `written`, an xlsx with blank stand-ins for the kept sheets, with each stand-in
+    /// replaced by the sheet from the original file and the parts it links to. The
+    /// original's styles are added to the written ones and its shared strings are written
+    /// into the cells. `selected` names the sheet whose tab is selected
+    pub(super) fn copy_into(&self, written: Vec<u8>, selected: &str) -> Result<Vec<u8>> {
+        let mut original = open_xlsx_archive(&self.source).context("original file unreadable")?;
+        let mut written = ZipArchive::new(Cursor::new(written))?;
+
+        let mut replaced = BTreeMap::new();
+        let mut offsets = StyleOffsets::default();
+        if let (Some(ours), Some(theirs)) = (
+            read_zip_entry(&mut written, "xl/styles.xml"),
+            read_zip_entry(&mut original, "xl/styles.xml"),
+        ) {
+            let (styles, moved) = merge_styles(&ours, &theirs)?;
+            offsets = moved;
+            replaced.insert("xl/styles.xml".to_string(), styles);
+        }
+        let shared_strings = read_zip_entry(&mut original, "xl/sharedStrings.xml")
+            .map(|xml| read_shared_strings(&xml))
+            .transpose()?
+            .unwrap_or_default();
+
+        let mut parts = PartCopier::new(written.file_names());
+        for (name, from) in &self.sheets {
+            let to = resolve_xlsx_sheet_path(&mut written, name)
+                .with_context(|| format!("sheet {name} missing from the written workbook"))?;
+            let xml = read_zip_entry(&mut original, from)
+                .with_context(|| format!("sheet {name} unreadable in the original"))?;
+            let xml = copy_sheet_xml(&xml, &shared_strings, offsets, name == selected)?;
+            replaced.insert(to.clone(), xml);
+            parts.copy_linked(&mut original, from, &to)?;
+        }
+        let content_types = merge_content_types(
+            &read_zip_entry(&mut written, "[Content_Types].xml").context("no content types")?,
+            &read_zip_entry(&mut original, "[Content_Types].xml").unwrap_or_default(),
+            &parts.copied,
+        )?;
+        replaced.insert("[Content_Types].xml".to_string(), content_types);
+        replaced.append(&mut parts.relationships);
+
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        for index in 0..written.len() {
+            let file = written.by_index_raw(index)?;
+            match replaced.remove(file.name()) {
+                Some(xml) => {
+                    let name = file.name().to_string();
+                    drop(file);
+                    zip.start_file(name, options)?;
+                    zip.write_all(xml.as_bytes())?;
+                }
+                None => zip.raw_copy_file(file)?,
+            }
+        }
+        for (name, xml) in replaced {
+            zip.start_file(name, options)?;
+            zip.write_all(xml.as_bytes())?;
+        }
+        for part in &parts.copied {
+            if part.kind == "table" {
+                // Tables point at the differential formats of their styled columns
+                let xml = read_zip_entry(&mut original, &part.from).context("table unreadable")?;
+                let xml = rewrite_attributes(&xml, |_, key, value| {
+                    key.ends_with(b"DxfId")
+                        .then(|| shifted(value, offsets.dxfs))
+                        .flatten()
+                })?;
+                zip.start_file(part.to.as_str(), options)?;
+                zip.write_all(xml.as_bytes())?;
+            } else {
+                let index = original
+                    .index_for_name(&part.from)
+                    .with_context(|| format!("{} missing from the original", part.from))?;
+                zip.raw_copy_file_rename(original.by_index_raw(index)?, &part.to)?;
+            }
+        }
+        Ok(zip.finish()?.into_inner())
+    }
+}
+
+impl Workbook {
+    /// Notes that the sheet at `index` was just read in full from the file, so its cells
+    /// count as unchanged until edited
+    pub(super) fn note_sheet_read(&mut self, index: usize) {
+        let sheet = &self.sheets[index];
+        if let Some(original) = self
+            .original_sheets
+            .iter_mut()
+            .find(|original| original.name == sheet.name && original.revision.is_none())
+        {
+            original.revision = Some(sheet.data.revision());
+        }
+    }
+
+    /// The sheets unchanged since they were read, which saving can copy from the file as
+    /// they are: none unless the file is an xlsx unchanged since it was opened
+    pub(super) fn plan_kept_sheets(&self) -> KeptSheets {
+        let source = PathBuf::from(&self.file_path);
+        if self.original_sheets.is_empty() || modified_at(&source) != self.file_modified_at {
+            return KeptSheets::default();
+        }
+        let Some(mut archive) = open_xlsx_archive(&source) else {
+            return KeptSheets::default();
+        };
+
+        let mut sheets = Vec::new();
+        for sheet in &self.sheets {
+            if !self.original_sheets.contains(&OriginalSheet::of(sheet)) {
+                continue;
+            }
+            let Some(path) = resolve_xlsx_sheet_path(&mut archive, &sheet.name) else {
+                continue;
+            };
+            if links_only_copyable_parts(&mut archive, &path, &mut HashSet::new()) {
+                sheets.push((sheet.name.clone(), path));
+            }
+        }
+        KeptSheets { source, sheets }
+    }
+}
+
+/// Whether every part `part` links to, and every part those link to, can be copied along
+fn links_only_copyable_parts<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    part: &str,
+    seen: &mut HashSet<String>,
+) -> bool {
+    let dir = part.rsplit_once('/').map_or("", |(dir, _)| dir);
+    read_sheet_relationships(archive, part)
+        .into_iter()
+        .all(|relationship| {
+            if relationship.external {
+                return true;
+            }
+            if !COPYABLE_RELATIONSHIPS.contains(&kind_of(&relationship.kind)) {
+                return false;
+            }
+            let target = resolve_relative_path(dir, &relationship.target);
+            !seen.insert(target.clone()) || links_only_copyable_parts(archive, &target, seen)
+        })
+}
+
+/// The last segment of a relationship type, such as `comments`
+fn kind_of(relationship_type: &str) -> &str {
+    relationship_type
+        .rsplit_once('/')
+        .map_or(relationship_type, |(_, kind)| kind)
+}
+
+/// A part of the original file copied along with a kept sheet
+struct CopiedPart {
+    from: String,
+    to: String,
+    kind: String,
+}
+
+/// Copies the parts kept sheets link to under names free in the written workbook, writing
+/// relationship files that lead to the copies
+struct PartCopier {
+    taken: HashSet<String>,
+    copied: Vec<CopiedPart>,
+    /// The relationship files of the kept sheets and copied parts, by path
+    relationships: BTreeMap<String, String>,
+}
+
+impl PartCopier {
+    fn new<'a>(taken: impl Iterator<Item = &'a str>) -> Self {
+        Self {
+            taken: taken.map(str::to_string).collect(),
+            copied: Vec::new(),
+            relationships: BTreeMap::new(),
+        }
+    }
+
+    /// Copies the parts `from` links to, and what they link to in turn, for its copy at `to`
+    fn copy_linked<R: Read + Seek>(
+        &mut self,
+        original: &mut ZipArchive<R>,
+        from: &str,
+        to: &str,
+    ) -> Result<()> {
+        let relationships = read_sheet_relationships(original, from);
+        if relationships.is_empty() {
+            return Ok(());
+        }
+        let from_dir = from.rsplit_once('/').map_or("", |(dir, _)| dir);
+        let (to_dir, to_file) = to.rsplit_once('/').unwrap_or(("", to));
+
+        let mut xml = String::from(concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n",
+            "<Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">",
+        ));
+        for relationship in relationships {
+            let target = if relationship.external {
+                relationship.target
+            } else {
+                let part = resolve_relative_path(from_dir, &relationship.target);
+                let copy = match self.copied.iter().find(|copied| copied.from == part) {
+                    Some(copied) => copied.to.clone(),
+                    None => {
+                        let copy = self.free_name(&part);
+                        self.copied.push(CopiedPart {
+                            from: part.clone(),
+                            to: copy.clone(),
+                            kind: kind_of(&relationship.kind).to_string(),
+                        });
+                        self.copy_linked(original, &part, &copy)?;
+                        copy
+                    }
+                };
+                relative_target(to_dir, &copy)
+            };
+            xml.push_str(&format!(
+                r#"<Relationship Id="{}" Type="{}" Target="{}"{}/>"#,
+                escape(&relationship.id),
+                escape(&relationship.kind),
+                escape(&target),
+                if relationship.external {
+                    r#" TargetMode="External""#
+                } else {
+                    ""
+                }
+            ));
+        }
+        xml.push_str("</Relationships>");
+        self.relationships
+            .insert(format!("{to_dir}/_rels/{to_file}.rels"), xml);
+        Ok(())
+    }
+
+    /// `part`, or when the written workbook has a part of that name, `part` with a number
+    /// after its name
+    fn free_name(&mut self, part: &str) -> String {
+        let name = if self.taken.contains(part) {
+            let (stem, extension) = part.rsplit_once('.').unwrap_or((part, ""));
+            (2..)
+                .map(|n| format!("{stem}_{n}.{extension}"))
+                .find(|name| !self.taken.contains(name))
+                .unwrap_or_default()
+        } else {
+            part.to_string()
+        };
+        self.taken.insert(name.clone());
+        name
+    }
+}
+
+/// The relationship target leading from a part in `from_dir` to the part at `to`
+fn relative_target(from_dir: &str, to: &str) -> String {
+    let from: Vec<&str> = from_dir
+        .split('/')
+        .filter(|part| !part.is_empty())
+        .collect();
+    let to: Vec<&str> = to.split('/').collect();
+    let common = from
+        .iter()
+        .zip(&to[..to.len() - 1])
+        .take_while(|(from, to)| from == to)
+        .count();
+    let mut path = vec![".."; from.len() - common];
+    path.extend(&to[common..]);
+    path.join("/")
+}
+
+/// The contents of each string of `sharedStrings.xml`, plain or in runs, which an inline
+/// string holds the same way
+fn read_shared_strings(xml: &str) -> Result<Vec<String>> {
+    let mut reader = Reader::from_str(xml);
+    let mut strings = Vec::new();
+    loop {
+        match reader.read_event()? {
+            Event::Start(start) if start.local_name().as_ref() == b"si" => {
+                let name = start.name().as_ref().to_vec();
+                let span = reader.read_to_end(QName(&name))?;
+                let range = usize::try_from(span.start)?..usize::try_from(span.end)?;
+                strings.push(xml[range].to_string());
+            }
+            Event::Empty(start) if start.local_name().as_ref() == b"si" => {
+                strings.push(String::new());
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(strings)
+}
+
+/// `xml`, a sheet of the original file, made to fit the written workbook: its style indices
+/// moved by `offsets`, its shared strings written inline, and its tab selected only when
+/// `selected`, as Excel groups sheets whose tabs are selected together
+fn copy_sheet_xml(
+    xml: &str,
+    shared_strings: &[String],
+    offsets: StyleOffsets,
+    selected: bool,
+) -> Result<String> {
+    let mut reader = Reader::from_str(xml);
+    let mut writer = Writer::new(Vec::with_capacity(xml.len()));
+    let mut shared_string_cell = false;
+    let mut first_view = true;
+
+    loop {
+        let (start, is_empty) = match reader.read_event()? {
+            Event::Start(start) => (start, false),
+            Event::Empty(start) => (start, true),
+            Event::Eof => break,
+            event => {
+                writer.write_event(event)?;
+                continue;
+            }
+        };
+        let start = match start.local_name().as_ref() {
+            b"c" => {
+                shared_string_cell = !is_empty
+                    && start.attributes().flatten().any(|attribute| {
+                        attribute.key.as_ref() == b"t" && attribute.value.as_ref() == b"s"
+                    });
+                map_attributes(&start, |key, value| match key {
+                    b"s" => shifted(value, offsets.cell_xfs),
+                    b"t" if shared_string_cell => Some("inlineStr".to_string()),
+                    _ => None,
+                })
+            }
+            b"v" if shared_string_cell && !is_empty => {
+                let name = start.name().as_ref().to_vec();
+                let index = reader.read_text(QName(&name))?;
+                let string = index
+                    .trim()
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|index| shared_strings.get(index))
+                    .map_or("", String::as_str);
+                let inline = format!("<is>{string}</is>");
+                writer.write_event(Event::Text(BytesText::from_escaped(inline)))?;
+                continue;
+            }
+            b"row" => map_attributes(&start, |key, value| {
+                (key == b"s")
+                    .then(|| shifted(value, offsets.cell_xfs))
+                    .flatten()
+            }),
+            b"col" => map_attributes(&start, |key, value| {
+                (key == b"style")
+                    .then(|| shifted(value, offsets.cell_xfs))
+                    .flatten()
+            }),
+            b"cfRule" => map_attributes(&start, |key, value| {
+                (key == b"dxfId")
+                    .then(|| shifted(value, offsets.dxfs))
+                    .flatten()
+            }),
+            b"sheetView" if first_view => {
+                first_view = false;
+                let mut has_tab_selected = false;
+                let mut start = map_attributes(&start, |key, _| {
+                    has_tab_selected |= key == b"tabSelected";
+                    (key == b"tabSelected").then(|| u8::from(selected).to_string())
+                });
+                if selected && !has_tab_selected {
+                    start.push_attribute(("tabSelected", "1"));
+                }
+                start
+            }
+            b"sheetView" => map_attributes(&start, |key, _| {
+                (key == b"tabSelected").then(|| "0".to_string())
+            }),
+            _ => start.into_owned(),
+        };
+        writer.write_event(if is_empty {
+            Event::Empty(start)
+        } else {
+            Event::Start(start)
+        })?;
+    }
+    String::from_utf8(writer.into_inner()).context("sheet XML is not UTF-8")
+}
+
+/// `written`, the content types of the written workbook, with those the copied parts had in
+/// `original`
+fn merge_content_types(written: &str, original: &str, copied: &[CopiedPart]) -> Result<String> {
+    let (written_defaults, _) = read_content_types(written);
+    let (original_defaults, original_overrides) = read_content_types(original);
+
+    let mut added = String::new();
+    let mut extensions = HashSet::new();
+    for part in copied {
+        if let Some(kind) = original_overrides.get(&format!("/{}", part.from)) {
+            added.push_str(&format!(
+                r#"<Override PartName="/{}" ContentType="{}"/>"#,
+                escape(&part.to),
+                escape(kind)
+            ));
+            continue;
+        }
+        let extension = part
+            .to
+            .rsplit_once('.')
+            .map_or(String::new(), |(_, extension)| {
+                extension.to_ascii_lowercase()
+            });
+        if written_defaults.contains_key(&extension) || !extensions.insert(extension.clone()) {
+            continue;
+        }
+        if let Some(kind) = original_defaults.get(&extension) {
+            added.push_str(&format!(
+                r#"<Default Extension="{}" ContentType="{}"/>"#,
+                escape(&extension),
+                escape(kind)
+            ));
+        }
+    }
+
+    let end = written
+        .rfind("</Types>")
+        .context("content types without an end")?;
+    Ok(format!("{}{added}{}", &written[..end], &written[end..]))
+}
+
+/// The content types of `[Content_Types].xml` by lowercase extension and by part name
+fn read_content_types(xml: &str) -> (HashMap<String, String>, HashMap<String, String>) {
+    let mut reader = Reader::from_str(xml);
+    let mut defaults = HashMap::new();
+    let mut overrides = HashMap::new();
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(event) | Event::Empty(event)) => {
+                let kind = attr_value(&reader, &event, b"ContentType");
+                match (event.local_name().as_ref(), kind) {
+                    (b"Default", Some(kind)) => {
+                        if let Some(extension) = attr_value(&reader, &event, b"Extension") {
+                            defaults.insert(extension.to_ascii_lowercase(), kind);
+                        }
+                    }
+                    (b"Override", Some(kind)) => {
+                        if let Some(part) = attr_value(&reader, &event, b"PartName") {
+                            overrides.insert(part, kind);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Ok(Event::Eof) | Err(_) => break,
+            _ => {}
+        }
+    }
+    (defaults, overrides)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{copy_sheet_xml, relative_target, StyleOffsets};
+
+    #[test]
+    fn copied_sheets_move_their_styles_and_hold_their_strings_inline() {
+        let sheet = concat!(
+            r#"<worksheet><sheetViews><sheetView tabSelected="1" workbookViewId="0"/></sheetViews>"#,
+            r#"<cols><col min="1" max="1" width="20" style="2"/></cols><sheetData>"#,
+            r#"<row r="1" s="1" customFormat="1"><c r="A1" s="3" t="s"><v>1</v></c><c r="B1"><v>7</v></c></row>"#,
+            r#"</sheetData><conditionalFormatting sqref="B1"><cfRule type="cellIs" dxfId="0" priority="1"/>"#,
+            r#"</conditionalFormatting></worksheet>"#,
+        );
+        let strings = ["skip".to_string(), "<r><t>Q1 &amp; Q2</t></r>".to_string()];
+        let offsets = StyleOffsets {
+            cell_xfs: 5,
+            dxfs: 2,
+        };
+        assert_eq!(
+            copy_sheet_xml(sheet, &strings, offsets, false).unwrap(),
+            concat!(
+                r#"<worksheet><sheetViews><sheetView tabSelected="0" workbookViewId="0"/></sheetViews>"#,
+                r#"<cols><col min="1" max="1" width="20" style="7"/></cols><sheetData>"#,
+                r#"<row r="1" s="6" customFormat="1"><c r="A1" s="8" t="inlineStr"><is><r><t>Q1 &amp; Q2</t></r></is></c>"#,
+                r#"<c r="B1"><v>7</v></c></row></sheetData><conditionalFormatting sqref="B1">"#,
+                r#"<cfRule type="cellIs" dxfId="2" priority="1"/></conditionalFormatting></worksheet>"#,
+            )
+        );
+
+        assert_eq!(
+            relative_target("xl/worksheets", "xl/drawings/drawing1.xml"),
+            "../drawings/drawing1.xml"
+        );
+        assert_eq!(
+            relative_target("xl/worksheets", "xl/worksheets/sheet2.xml"),
+            "sheet2.xml"
+        );
+        assert_eq!(
+            relative_target("xl/drawings", "xl/media/image1.png"),
+            "../media/image1.png"
+        );
+    }
+}
@@ -0,0 +1,255 @@
+use anyhow::{anyhow, Context, Result};
+use calamine::{
+    open_workbook, Cell as CalamineCell, Data, DataRef, Dimensions, Range, Reader, Xlsx, XlsxError,
+};
+use std::io::{Read, Seek};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::PathBuf;
+
+use super::sheet_parse::cell_value_parts;
+use super::XlsxAnnotations;
+use crate::excel::Cell;
+
+/// Rows read between two progress reports
+const PROGRESS_INTERVAL_ROWS: usize = 2_000;
+
+/// Rows read between two batches of cells sent ahead of a sheet that is still being read
+const STREAM_CHUNK_ROWS: usize = 5_000;
+
+/// How far reading a workbook has got, reported while its sheets are parsed
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LoadProgress {
+    pub sheet_name: String,
+    /// 0-based place of the sheet among the sheets being read
+    pub sheet_index: usize,
+    pub sheet_count: usize,
+    pub rows_read: usize,
+    /// Rows the sheet declares it spans; `None` when the file does not say
+    pub total_rows: Option<usize>,
+}
+
+/// Cells of a sheet still being read, sent ahead of the rest so the top of a huge sheet can
+/// be shown long before its last row is read
+#[derive(Clone, Default)]
+pub struct StreamedRows {
+    /// Cells by 1-based (row, col), counted from the first row and column holding a value
+    /// as a loaded sheet's are
+    pub cells: Vec<(usize, usize, Cell)>,
+    /// Columns the cells sent before move right by, as a value turned up left of all of them
+    pub moved_cols: usize,
+    /// Rows read so far. No cell of these rows arrives later
+    pub rows_read: usize,
+}
+
+/// Rows and columns a sheet spans, counted as a loaded sheet's are
+type SheetSize = (usize, usize);
+
+/// The end of a sheet read in chunks: its last cells, and the formulas and annotations read
+/// once every cell is in. The cells sent before make up the rest of the sheet
+pub struct StreamedSheetEnd {
+    pub rows: StreamedRows,
+    pub(super) size: SheetSize,
+    pub(super) formulas: Option<Range<String>>,
+    pub(super) annotations: XlsxAnnotations,
+}
+
+/// Reads a worksheet the way calamine's `worksheet_range` does, one cell at a time, calling
+/// `on_rows` with the rows read so far and the rows the sheet declares every few thousand rows
+pub(super) fn read_xlsx_range<RS: Read + Seek>(
+    xlsx: &mut Xlsx<RS>,
+    name: &str,
+    mut on_rows: impl FnMut(usize, Option<usize>),
+) -> Result<Range<Data>, XlsxError> {
+    let mut reader = match xlsx.worksheet_cells_reader(name) {
+        Ok(reader) => reader,
+        // Chart sheets hold no cells
+        Err(XlsxError::NotAWorksheet(_)) => return Ok(Range::default()),
+        Err(e) => return Err(e),
+    };
+    let total_rows = declared_rows(reader.dimensions());
+
+    let mut cells: Vec<CalamineCell<Data>> = Vec::new();
+    let mut next_report = 0;
+    while let Some(cell) = reader.next_cell()? {
+        let row = cell.get_position().0 as usize;
+        if row >= next_report {
+            on_rows(row, total_rows);
+            next_report = row + PROGRESS_INTERVAL_ROWS;
+        }
+        if !matches!(cell.get_value(), DataRef::Empty) {
+            cells.push(CalamineCell::new(
+                cell.get_position(),
+                Data::from(cell.get_value().clone()),
+            ));
+        }
+    }
+    Ok(Range::from_sparse(cells))
+}
+
+/// Reads a worksheet as [`read_xlsx_range`] does, but hands the cells read to `on_chunk`
+/// every few thousand rows instead of keeping them. Reading stops, returning `None`, when
+/// `on_chunk` returns false. Returns the cells read since the last chunk and the sheet's size
+fn stream_xlsx_cells<RS: Read + Seek>(
+    xlsx: &mut Xlsx<RS>,
+    name: &str,
+    mut on_rows: impl FnMut(usize, Option<usize>),
+    on_chunk: &mut dyn FnMut(StreamedRows) -> bool,
+) -> Result<Option<(StreamedRows, SheetSize)>, XlsxError> {
+    let mut reader = match xlsx.worksheet_cells_reader(name) {
+        Ok(reader) => reader,
+        Err(XlsxError::NotAWorksheet(_)) => return Ok(Some((StreamedRows::default(), (0, 0)))),
+        Err(e) => return Err(e),
+    };
+    let total_rows = declared_rows(reader.dimensions());
+
+    let mut stream = CellStream::default();
+    let mut next_report = 0;
+    let mut next_chunk = STREAM_CHUNK_ROWS;
+    while let Some(cell) = reader.next_cell()? {
+        let (row, col) = cell.get_position();
+        let (row, col) = (row as usize, col as usize);
+        if row >= next_report {
+            on_rows(row, total_rows);
+            next_report = row + PROGRESS_INTERVAL_ROWS;
+        }
+        if let Some((first_row, _)) = stream.start {
+            let rows_read = row.saturating_sub(first_row);
+            if rows_read >= next_chunk {
+                next_chunk = rows_read + STREAM_CHUNK_ROWS;
+                if !on_chunk(stream.take_chunk(rows_read)) {
+                    return Ok(None);
+                }
+            }
+        }
+        if !matches!(cell.get_value(), DataRef::Empty) {
+            stream.push(row, col, streamed_cell(cell.get_value()));
+        }
+    }
+    let size = stream.size();
+    Ok(Some((stream.take_chunk(size.0), size)))
+}
+
+/// Rows a sheet's `<dimension>` says it spans, when it says
+fn declared_rows(dimensions: Dimensions) -> Option<usize> {
+    (dimensions.end.0 > 0).then(|| dimensions.end.0 as usize + 1)
+}
+
+/// The cells read since the last chunk, by 0-based place in the sheet, and where the values
+/// read so far start and end. Cells go out placed from the first row and leftmost column
+/// holding a value, as `Range::from_sparse` places them
+#[derive(Default)]
+struct CellStream {
+    pending: Vec<(usize, usize, Cell)>,
+    /// The first row and leftmost column holding a value
+    start: Option<(usize, usize)>,
+    /// The last row and rightmost column holding a value
+    end: (usize, usize),
+    /// The column the cells sent so far are placed from
+    sent_from_col: Option<usize>,
+}
+
+impl CellStream {
+    fn push(&mut self, row: usize, col: usize, cell: Cell) {
+        let start = self.start.get_or_insert((row, col));
+        start.1 = start.1.min(col);
+        self.end = (self.end.0.max(row), self.end.1.max(col));
+        self.pending.push((row, col, cell));
+    }
+
+    fn take_chunk(&mut self, rows_read: usize) -> StreamedRows {
+        let Some((first_row, first_col)) = self.start else {
+            return StreamedRows::default();
+        };
+        for (row, col, _) in &mut self.pending {
+            *row = row.saturating_sub(first_row) + 1;
+            *col = *col - first_col + 1;
+        }
+        let moved_cols = self
+            .sent_from_col
+            .map_or(0, |sent_from| sent_from - first_col);
+        self.sent_from_col = Some(first_col);
+        StreamedRows {
+            cells: std::mem::take(&mut self.pending),
+            moved_cols,
+            rows_read,
+        }
+    }
+
+    fn size(&self) -> SheetSize {
+        self.start.map_or((0, 0), |(first_row, first_col)| {
+            (self.end.0 - first_row + 1, self.end.1 - first_col + 1)
+        })
+    }
+}
+
+/// A cell as [`create_sheet_from_range`](super::create_sheet_from_range) makes it, short of the formula it learns later
+fn streamed_cell(value: &DataRef) -> Cell {
+    let (value, cell_type, original_type) = cell_value_parts(&Data::from(value.clone()));
+    let is_formula = value.starts_with('=');
+    Cell::new_with_type(value, is_formula, cell_type, original_type)
+}
+
+/// What a background thread needs to read a lazily loaded sheet from the file while the
+/// workbook stays in use. The sheet it reads goes back in with
+/// [`Workbook::install_loaded_sheet`](super::Workbook::install_loaded_sheet)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SheetLoadRequest {
+    pub sheet_name: String,
+    pub sheet_index: usize,
+    pub sheet_count: usize,
+    pub(super) file_path: PathBuf,
+}
+
+impl SheetLoadRequest {
+    /// Reads the sheet from its own handle on the file, calling `on_progress` as rows are read
+    /// and handing the cells read since to `on_chunk` every few thousand rows. Reading stops
+    /// with an error when `on_chunk` returns false
+    pub fn read(
+        &self,
+        mut on_progress: impl FnMut(&LoadProgress),
+        mut on_chunk: impl FnMut(StreamedRows) -> bool,
+    ) -> Result<StreamedSheetEnd> {
+        // Loads run on worker threads, possibly two at once, so the process-wide panic hook
+        // is left alone
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            self.read_sheet(&mut on_progress, &mut on_chunk)
+        }));
+
+        result.unwrap_or_else(|_| {
+            Err(anyhow!(
+                "Unable to read worksheet '{}': parser panic: malformed workbook data",
+                self.sheet_name
+            ))
+        })
+    }
+
+    fn read_sheet(
+        &self,
+        on_progress: &mut dyn FnMut(&LoadProgress),
+        on_chunk: &mut dyn FnMut(StreamedRows) -> bool,
+    ) -> Result<StreamedSheetEnd> {
+        let name = &self.sheet_name;
+        let mut report = |rows_read, total_rows| {
+            on_progress(&LoadProgress {
+                sheet_name: name.clone(),
+                sheet_index: self.sheet_index,
+                sheet_count: self.sheet_count,
+                rows_read,
+                total_rows,
+            });
+        };
+        report(0, None);
+
+        let mut xlsx: Xlsx<_> = open_workbook(&self.file_path)
+            .with_context(|| format!("Unable to open {}", self.file_path.display()))?;
+        let (rows, size) = stream_xlsx_cells(&mut xlsx, name, &mut report, on_chunk)
+            .with_context(|| format!("Unable to read worksheet '{name}'"))?
+            .ok_or_else(|| anyhow!("Reading worksheet '{name}' was stopped"))?;
+        Ok(StreamedSheetEnd {
+            rows,
+            size,
+            formulas: xlsx.worksheet_formula(name).ok(),
+            annotations: XlsxAnnotations::read(&self.file_path, name),
+        })
+    }
+}
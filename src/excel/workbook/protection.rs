@@ -0,0 +1,132 @@
+use quick_xml::events::Event;
+use std::collections::HashSet;
+use std::path::Path;
+
+use crate::excel::{CellGrid, SheetProtection};
+use crate::utils::parse_cell_reference;
+
+use super::formula_lookup::{
+    attr_value, open_xlsx_archive, read_zip_entry, resolve_xlsx_sheet_path,
+};
+
+/// Reads whether `sheet_name` carries a `sheetProtection` element that turns protection on
+pub(super) fn lookup_sheet_protection_in_xlsx(file: &Path, sheet_name: &str) -> SheetProtection {
+    read_sheet_protection(file, sheet_name).unwrap_or_default()
+}
+
+/// Reads the cells of `sheet_name` whose style clears the `locked` flag
+pub(super) fn lookup_unlocked_cells_in_xlsx(file: &Path, sheet_name: &str) -> Vec<(usize, usize)> {
+    read_unlocked_cells(file, sheet_name).unwrap_or_default()
+}
+
+/// Marks `cells` as editable on a protected sheet
+pub(super) fn apply_unlocked_cells(data: &mut CellGrid, cells: Vec<(usize, usize)>) {
+    for (row, col) in cells {
+        data.update_cell(row, col, |cell| cell.unlocked = true);
+    }
+}
+
+fn read_sheet_protection(file: &Path, sheet_name: &str) -> Option<SheetProtection> {
+    let mut archive = open_xlsx_archive(file)?;
+    let sheet_path = resolve_xlsx_sheet_path(&mut archive, sheet_name)?;
+    let sheet_xml = read_zip_entry(&mut archive, &sheet_path)?;
+    if !sheet_xml.contains("<sheetProtection") {
+        return Some(SheetProtection::Unprotected);
+    }
+
+    let mut reader = quick_xml::Reader::from_str(&sheet_xml);
+    reader.config_mut().trim_text(true);
+    let mut buf = Vec::new();
+
+    loop {
+        match reader.read_event_into(&mut buf).ok()? {
+            Event::Start(event) | Event::Empty(event)
+                if event.name().as_ref() == b"sheetProtection" =>
+            {
+                let sheet = attr_value(&reader, &event, b"sheet");
+                return Some(if matches!(sheet.as_deref(), Some("1" | "true")) {
+                    SheetProtection::Protected
+                } else {
+                    SheetProtection::Unprotected
+                });
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Some(SheetProtection::Unprotected)
+}
+
+fn read_unlocked_cells(file: &Path, sheet_name: &str) -> Option<Vec<(usize, usize)>> {
+    let mut archive = open_xlsx_archive(file)?;
+    let styles_xml = read_zip_entry(&mut archive, "xl/styles.xml")?;
+    let unlocked_styles = parse_unlocked_styles(&styles_xml)?;
+    // Most workbooks never unlock a cell, so skip the sheet scan
+    if unlocked_styles.is_empty() {
+        return Some(Vec::new());
+    }
+
+    let sheet_path = resolve_xlsx_sheet_path(&mut archive, sheet_name)?;
+    let sheet_xml = read_zip_entry(&mut archive, &sheet_path)?;
+    let mut reader = quick_xml::Reader::from_str(&sheet_xml);
+    reader.config_mut().trim_text(true);
+    let mut buf = Vec::new();
+    let mut cells = Vec::new();
+
+    loop {
+        match reader.read_event_into(&mut buf).ok()? {
+            Event::Start(event) | Event::Empty(event) if event.name().as_ref() == b"c" => {
+                let style =
+                    attr_value(&reader, &event, b"s").and_then(|style| style.parse::<usize>().ok());
+                if style.is_some_and(|style| unlocked_styles.contains(&style)) {
+                    if let Some(cell) = attr_value(&reader, &event, b"r")
+                        .and_then(|reference| parse_cell_reference(&reference))
+                    {
+                        cells.push(cell);
+                    }
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Some(cells)
+}
+
+/// Indexes into `cellXfs` whose `<protection locked="0"/>` child leaves cells editable
+fn parse_unlocked_styles(styles_xml: &str) -> Option<HashSet<usize>> {
+    let mut reader = quick_xml::Reader::from_str(styles_xml);
+    reader.config_mut().trim_text(true);
+    let mut buf = Vec::new();
+    let mut unlocked = HashSet::new();
+    let mut in_cell_xfs = false;
+    let mut xf_index = 0;
+
+    loop {
+        match reader.read_event_into(&mut buf).ok()? {
+            Event::Start(event) if event.name().as_ref() == b"cellXfs" => in_cell_xfs = true,
+            Event::End(event) if event.name().as_ref() == b"cellXfs" => in_cell_xfs = false,
+            Event::Empty(event) if in_cell_xfs && event.name().as_ref() == b"xf" => {
+                xf_index += 1;
+            }
+            Event::End(event) if in_cell_xfs && event.name().as_ref() == b"xf" => xf_index += 1,
+            Event::Start(event) | Event::Empty(event)
+                if in_cell_xfs && event.name().as_ref() == b"protection" =>
+            {
+                let locked = attr_value(&reader, &event, b"locked");
+                if matches!(locked.as_deref(), Some("0" | "false")) {
+                    unlocked.insert(xf_index);
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Some(unlocked)
+}
@@ -1,10 +1,14 @@
 use anyhow::Result;
 use chrono::Local;
-use rust_xlsxwriter::{Format, Workbook as XlsxWorkbook, Worksheet};
+use rust_xlsxwriter::{
+    Color, ConditionalFormatCell, ConditionalFormatCellRule, ConditionalFormatValue, Format, Note,
+    Url, Workbook as XlsxWorkbook, Worksheet,
+};
 use std::path::{Path, PathBuf};
 
+use super::kept_sheets::KeptSheets;
 use super::Workbook;
-use crate::excel::{Cell, CellType, Sheet};
+use crate::excel::{Cell, CellType, CfOperator, ConditionalFormat, Sheet, SheetVisibility};
 
 impl Workbook {
     pub fn save(&mut self) -> Result<()> {
@@ -13,22 +17,135 @@ impl Workbook {
             return Ok(());
         }
 
-        self.ensure_all_sheets_loaded()?;
+        self.save_timestamped_copy()?;
+        Ok(())
+    }
 
-        let mut workbook = XlsxWorkbook::new();
+    /// Writes the workbook next to its file as `name_YYYYMMDD_HHMMSS.xlsx`, whether or not it
+    /// changed, and returns the path written
+    pub fn save_timestamped_copy(&mut self) -> Result<PathBuf> {
         let new_filepath = timestamped_save_path(&self.file_path);
+
+        // Sheets unchanged since they were read are copied from the file, which needs no
+        // reading of them and keeps what this editor doesn't read, such as charts and cell
+        // styles. Should copying fail, every sheet is written anew
+        let kept = self.plan_kept_sheets();
+        if kept.is_empty() || self.save_keeping(&kept, &new_filepath).is_err() {
+            self.ensure_all_sheets_loaded()?;
+            self.xlsx_workbook(&KeptSheets::default())?
+                .save(&new_filepath)?;
+        }
+        self.is_modified = false;
+
+        Ok(new_filepath)
+    }
+
+    /// Writes the changed sheets anew and copies the `kept` ones from the file
+    fn save_keeping(&mut self, kept: &KeptSheets, path: &Path) -> Result<()> {
+        let unread: Vec<(usize, String)> = self
+            .sheets
+            .iter()
+            .enumerate()
+            .filter(|(_, sheet)| !sheet.is_loaded && !kept.contains(&sheet.name))
+            .map(|(index, sheet)| (index, sheet.name.clone()))
+            .collect();
+        for (index, name) in unread {
+            self.ensure_sheet_loaded(index, &name)?;
+        }
+
+        let written = self.xlsx_workbook(kept)?.save_to_buffer()?;
+        let selected = &self.sheets[self.first_shown_sheet()].name;
+        std::fs::write(path, kept.copy_into(written, selected)?)?;
+        Ok(())
+    }
+
+    /// The workbook as rust_xlsxwriter writes it, with blank stand-ins for the `kept` sheets
+    fn xlsx_workbook(&self, kept: &KeptSheets) -> Result<XlsxWorkbook> {
+        let mut workbook = XlsxWorkbook::new();
         let number_format = Format::new().set_num_format("General");
         let date_format = Format::new().set_num_format("yyyy-mm-dd");
 
         for sheet in &self.sheets {
-            write_sheet(&mut workbook, sheet, &number_format, &date_format)?;
+            if kept.contains(&sheet.name) {
+                write_stand_in(&mut workbook, sheet)?;
+            } else {
+                write_sheet(&mut workbook, sheet, &number_format, &date_format)?;
+            }
         }
 
-        workbook.save(&new_filepath)?;
-        self.is_modified = false;
+        // Excel opens on the first sheet unless told otherwise, so skip hidden ones
+        let shown = self.first_shown_sheet();
+        if shown > 0 {
+            workbook.worksheet_from_index(shown)?.set_active(true);
+        }
 
-        Ok(())
+        // Names the writer rejects, such as ones pointing at a deleted sheet, are dropped
+        for defined in self.defined_names.iter().filter(|d| !d.name.is_empty()) {
+            let _ = workbook.define_name(&defined.name, &format!("={}", defined.refers_to));
+        }
+
+        Ok(workbook)
+    }
+
+    /// The index of the first sheet not hidden, which Excel opens on
+    fn first_shown_sheet(&self) -> usize {
+        self.sheets
+            .iter()
+            .position(|sheet| !sheet.visibility.is_hidden())
+            .unwrap_or(0)
+    }
+}
+
+/// Adds a blank sheet in place of one copied from the file after writing, so the workbook
+/// lists it by name and with its visibility
+fn write_stand_in(workbook: &mut XlsxWorkbook, sheet: &Sheet) -> Result<()> {
+    let worksheet = workbook.add_worksheet().set_name(&sheet.name)?;
+    match sheet.visibility {
+        SheetVisibility::Visible => {}
+        SheetVisibility::Hidden => {
+            worksheet.set_hidden(true);
+        }
+        SheetVisibility::VeryHidden => {
+            worksheet.set_very_hidden(true);
+        }
     }
+    Ok(())
+}
+
+/// Writes a conditional format as an Excel "cell value" rule
+fn write_conditional_format(worksheet: &mut Worksheet, rule: &ConditionalFormat) -> Result<()> {
+    let mut format = Format::new();
+    if let Some(rgb) = rule.fill {
+        format = format.set_background_color(Color::RGB(rgb));
+    }
+    if let Some(rgb) = rule.font {
+        format = format.set_font_color(Color::RGB(rgb));
+    }
+
+    let value = match rule.value.parse::<f64>() {
+        Ok(number) => ConditionalFormatValue::from(number),
+        Err(_) => ConditionalFormatValue::from(rule.value.as_str()),
+    };
+    let cell_rule = match rule.operator {
+        CfOperator::Equal => ConditionalFormatCellRule::EqualTo(value),
+        CfOperator::NotEqual => ConditionalFormatCellRule::NotEqualTo(value),
+        CfOperator::Greater => ConditionalFormatCellRule::GreaterThan(value),
+        CfOperator::GreaterOrEqual => ConditionalFormatCellRule::GreaterThanOrEqualTo(value),
+        CfOperator::Less => ConditionalFormatCellRule::LessThan(value),
+        CfOperator::LessOrEqual => ConditionalFormatCellRule::LessThanOrEqualTo(value),
+    };
+    let conditional_format = ConditionalFormatCell::new()
+        .set_rule(cell_rule)
+        .set_format(format);
+
+    worksheet.add_conditional_format(
+        (rule.start.0 - 1) as u32,
+        (rule.start.1 - 1) as u16,
+        (rule.end.0 - 1) as u32,
+        (rule.end.1 - 1) as u16,
+        &conditional_format,
+    )?;
+    Ok(())
 }
 
 fn timestamped_save_path(file_path: &str) -> PathBuf {
@@ -48,6 +165,25 @@ fn write_sheet(
 ) -> Result<()> {
     let worksheet = workbook.add_worksheet().set_name(&sheet.name)?;
 
+    // The password, if any, can't be recovered from the file's hash, so it is not kept
+    if sheet.protection.is_protected() {
+        worksheet.protect();
+    }
+
+    match sheet.visibility {
+        SheetVisibility::Visible => {}
+        SheetVisibility::Hidden => {
+            worksheet.set_hidden(true);
+        }
+        SheetVisibility::VeryHidden => {
+            worksheet.set_very_hidden(true);
+        }
+    }
+
+    if let Some(rgb) = sheet.tab_color {
+        worksheet.set_tab_color(Color::RGB(rgb));
+    }
+
     if sheet.freeze_panes.is_frozen() {
         worksheet.set_freeze_panes(
             sheet.freeze_panes.rows as u32,
@@ -59,32 +195,106 @@ fn write_sheet(
         worksheet.set_column_width(col as u16, 15)?;
     }
 
-    for row in 1..sheet.data.len() {
-        if row > sheet.max_rows {
+    for rule in &sheet.conditional_formats {
+        write_conditional_format(worksheet, rule)?;
+    }
+
+    // Column formats also cover cells typed into the column later in Excel
+    for (col, format) in &sheet.column_formats {
+        if *col >= 1 {
+            let format = Format::new().set_num_format(format.excel_code());
+            worksheet.set_column_format((col - 1) as u16, &format)?;
+        }
+    }
+
+    for ((row, col), cell) in sheet.data.iter() {
+        let row_idx = (row - 1) as u32;
+        let col_idx = (col - 1) as u16;
+
+        // Notes can sit on blank cells outside the used range
+        if let Some(comment) = &cell.comment {
+            let note = Note::new(comment).add_author_prefix(false);
+            worksheet.insert_note(row_idx, col_idx, &note)?;
+        }
+
+        if row > sheet.max_rows || col > sheet.max_cols || cell.value.is_empty() {
             continue;
         }
 
-        for col in 1..sheet.data[0].len() {
-            if col > sheet.max_cols {
+        // Links the writer rejects, such as unknown schemes, are saved as plain values
+        if let Some(link) = cell.hyperlink.as_deref().filter(|_| !cell.is_formula) {
+            let url = match link.strip_prefix('#') {
+                Some(location) => Url::new(format!("internal:{location}")),
+                None => Url::new(link),
+            };
+            if worksheet
+                .write_url_with_text(row_idx, col_idx, url, &cell.value)
+                .is_ok()
+            {
                 continue;
             }
+        }
 
-            let cell = &sheet.data[row][col];
-            if cell.value.is_empty() {
+        if let Some(format) = sheet.number_format_at(row, col) {
+            if let Some(value) = format.serial_value(cell) {
+                let format = Format::new().set_num_format(format.excel_code());
+                worksheet.write_number_with_format(row_idx, col_idx, value, &format)?;
                 continue;
             }
+        }
 
-            let row_idx = (row - 1) as u32;
-            let col_idx = (col - 1) as u16;
-            write_cell(
-                worksheet,
-                cell,
-                row_idx,
-                col_idx,
-                number_format,
-                date_format,
-            )?;
+        write_cell(
+            worksheet,
+            cell,
+            row_idx,
+            col_idx,
+            number_format,
+            date_format,
+        )?;
+    }
+
+    write_cell_styles(worksheet, sheet, number_format, date_format)?;
+
+    Ok(())
+}
+
+/// Re-applies the format each colored or unlocked cell was written with, plus its fill and font
+/// colors and minus the lock, so a protected form's input cells, blank ones included, stay
+/// editable in Excel
+fn write_cell_styles(
+    worksheet: &mut Worksheet,
+    sheet: &Sheet,
+    number_format: &Format,
+    date_format: &Format,
+) -> Result<()> {
+    for ((row, col), cell) in sheet.data.iter() {
+        let unlocked = cell.unlocked && sheet.protection.is_protected();
+        if !unlocked && cell.fill_color.is_none() && cell.font_color.is_none() {
+            continue;
+        }
+
+        let format = if let Some(format) = sheet.number_format_at(row, col) {
+            Format::new().set_num_format(format.excel_code())
+        } else if cell.is_formula {
+            Format::new()
+        } else {
+            match cell.cell_type {
+                CellType::Number => number_format.clone(),
+                CellType::Date => date_format.clone(),
+                _ => Format::new(),
+            }
+        };
+        let mut format = format;
+        if unlocked {
+            format = format.set_unlocked();
+        }
+        if let Some(rgb) = cell.fill_color {
+            format = format.set_background_color(Color::RGB(rgb));
+        }
+        if let Some(rgb) = cell.font_color {
+            format = format.set_font_color(Color::RGB(rgb));
         }
+        worksheet.set_cell_format((row - 1) as u32, (col - 1) as u16, &format)?;
     }
 
     Ok(())
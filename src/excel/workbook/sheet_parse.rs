@@ -1,6 +1,12 @@
-use calamine::{Data, Range};
+use calamine::{Data, ExcelDateTime, ExcelDateTimeType, Range};
 
-use crate::excel::{Cell, CellType, DataTypeInfo, FreezePanes, Sheet};
+use crate::excel::{
+    Cell, CellGrid, CellType, ColumnFormats, DataTypeInfo, FreezePanes, IsoDuration, Sheet,
+    SheetProtection, SheetVisibility,
+};
+
+/// Days between the 1900 and 1904 date system epochs
+const DATE_1904_OFFSET_DAYS: f64 = 1462.0;
 
 pub(super) fn create_sheet_from_range(
     name: &str,
@@ -8,17 +14,20 @@ pub(super) fn create_sheet_from_range(
     formula_range: Option<Range<String>>,
 ) -> Sheet {
     let (height, width) = range.get_size();
-    let mut data = vec![vec![Cell::empty(); width + 1]; height + 1];
+    let mut data = CellGrid::new();
 
     for (row_idx, col_idx, cell) in range.used_cells() {
         let (value, cell_type, original_type) = cell_value_parts(cell);
         let is_formula = !value.is_empty() && value.starts_with('=');
 
-        data[row_idx + 1][col_idx + 1] =
-            Cell::new_with_type(value, is_formula, cell_type, original_type);
+        data.set(
+            row_idx + 1,
+            col_idx + 1,
+            Cell::new_with_type(value, is_formula, cell_type, original_type),
+        );
     }
 
-    apply_formula_metadata(&mut data, formula_range);
+    apply_formula_metadata(&mut data, (height, width), formula_range);
 
     Sheet {
         name: name.to_string(),
@@ -27,10 +36,15 @@ pub(super) fn create_sheet_from_range(
         max_cols: width,
         is_loaded: true,
         freeze_panes: FreezePanes::none(),
+        column_formats: ColumnFormats::new(),
+        protection: SheetProtection::Unprotected,
+        visibility: SheetVisibility::Visible,
+        tab_color: None,
+        conditional_formats: Vec::new(),
     }
 }
 
-fn cell_value_parts(cell: &Data) -> (String, CellType, Option<DataTypeInfo>) {
+pub(super) fn cell_value_parts(cell: &Data) -> (String, CellType, Option<DataTypeInfo>) {
     match cell {
         Data::Empty => (String::new(), CellType::Empty, Some(DataTypeInfo::Empty)),
         Data::String(s) => (s.clone(), CellType::Text, Some(DataTypeInfo::String)),
@@ -58,31 +72,58 @@ fn cell_value_parts(cell: &Data) -> (String, CellType, Option<DataTypeInfo>) {
             value.push_str(&format!("{:?}", e));
             (value, CellType::Text, Some(DataTypeInfo::Error))
         }
-        Data::DateTime(dt) => (
-            dt.to_string(),
-            CellType::Date,
-            Some(DataTypeInfo::DateTime(dt.as_f64())),
-        ),
-        Data::DateTimeIso(s) => {
-            let value = s.clone();
+        Data::DateTime(dt) => {
+            let serial = serial_in_1900_system(dt);
             (
-                value.clone(),
+                serial.to_string(),
                 CellType::Date,
-                Some(DataTypeInfo::DateTimeIso(value)),
+                Some(DataTypeInfo::DateTime(serial)),
             )
         }
-        Data::DurationIso(s) => {
+        Data::DateTimeIso(s) => {
             let value = s.clone();
             (
                 value.clone(),
-                CellType::Text,
-                Some(DataTypeInfo::DurationIso(value)),
+                CellType::Date,
+                Some(DataTypeInfo::DateTimeIso(value)),
             )
         }
+        Data::DurationIso(s) => match IsoDuration::parse(s) {
+            Some(duration) => (
+                duration.to_string(),
+                CellType::Text,
+                Some(DataTypeInfo::DurationIso(duration)),
+            ),
+            None => (s.clone(), CellType::Text, Some(DataTypeInfo::String)),
+        },
+    }
+}
+
+/// Serial date in the 1900 date system, which display, export and save all assume. Workbooks
+/// saved with the 1904 date system (common for Mac-origin files) count days from 1904-01-01,
+/// and calamine flags their date cells accordingly.
+fn serial_in_1900_system(dt: &ExcelDateTime) -> f64 {
+    let datetime_type = if dt.is_duration() {
+        ExcelDateTimeType::TimeDelta
+    } else {
+        ExcelDateTimeType::DateTime
+    };
+    let is_1904 = *dt == ExcelDateTime::new(dt.as_f64(), datetime_type, true);
+
+    // Time-only values (below one day) mean the same thing in both systems
+    if is_1904 && dt.is_datetime() && dt.as_f64() >= 1.0 {
+        dt.as_f64() + DATE_1904_OFFSET_DAYS
+    } else {
+        dt.as_f64()
     }
 }
 
-fn apply_formula_metadata(data: &mut [Vec<Cell>], formula_range: Option<Range<String>>) {
+/// Marks the cells of the `(rows, cols)` data that hold formulas, keeping their text
+pub(super) fn apply_formula_metadata(
+    data: &mut CellGrid,
+    (rows, cols): (usize, usize),
+    formula_range: Option<Range<String>>,
+) {
     let Some(formulas) = formula_range else {
         return;
     };
@@ -101,10 +142,11 @@ fn apply_formula_metadata(data: &mut [Vec<Cell>], formula_range: Option<Range<St
 
         let row = start_row as usize + row_idx + 1;
         let col = start_col as usize + col_idx + 1;
-        if row < data.len() && col < data[row].len() {
-            let cell = &mut data[row][col];
-            cell.is_formula = true;
-            cell.formula = Some(normalized);
+        if row <= rows && col <= cols {
+            data.update_cell(row, col, |cell| {
+                cell.is_formula = true;
+                cell.formula = Some(normalized);
+            });
         }
     }
 }
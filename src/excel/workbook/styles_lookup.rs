@@ -1,160 +1,267 @@
-use quick_xml::events::Event;
+use quick_xml::events::{BytesStart, Event};
 use std::collections::HashMap;
-use std::fs::File;
 use std::path::Path;
-use zip::ZipArchive;
 
-use super::formula_lookup::{attr_value, read_zip_entry, resolve_xlsx_sheet_path};
+use crate::excel::Sheet;
+use crate::utils::parse_cell_reference;
 
-/// Look up cell background styles (Hex RRGGBB) in an XLSX file for a given sheet.
-pub(crate) fn lookup_styles_in_xlsx(
+use super::formula_lookup::{
+    attr_value, open_xlsx_archive, read_zip_entry, resolve_xlsx_sheet_path,
+};
+use super::tab_colors::{apply_tint, parse_argb, parse_theme_colors};
+
+/// The solid fill and font color a cell style gives its cells, as `0xRRGGBB`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(super) struct StyleColors {
+    fill: Option<u32>,
+    font: Option<u32>,
+}
+
+/// The part of `styles.xml` being read
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Section {
+    Other,
+    Fonts,
+    Fills,
+    CellXfs,
+}
+
+/// Reads the fill and font colors of the cells of `sheet_name`. Explicit and theme colors are
+/// resolved; legacy indexed colors and the color of the default font are not
+pub(super) fn lookup_cell_colors_in_xlsx(
     file: &Path,
     sheet_name: &str,
-) -> Option<HashMap<String, String>> {
-    let extension = file
-        .extension()
-        .and_then(|ext| ext.to_str())
-        .map(|ext| ext.to_ascii_lowercase())?;
-    if extension != "xlsx" && extension != "xlsm" {
-        return None;
-    }
+) -> Vec<((usize, usize), StyleColors)> {
+    read_cell_colors(file, sheet_name).unwrap_or_default()
+}
 
-    let archive_file = File::open(file).ok()?;
-    let mut archive = ZipArchive::new(archive_file).ok()?;
+/// Colors the cells styled in Excel. Styled blank cells past the data are left out rather
+/// than growing the grid
+pub(super) fn apply_cell_colors(sheet: &mut Sheet, cells: Vec<((usize, usize), StyleColors)>) {
+    for ((row, col), colors) in cells {
+        if row <= sheet.max_rows && col <= sheet.max_cols {
+            sheet.data.update_cell(row, col, |cell| {
+                cell.fill_color = colors.fill;
+                cell.font_color = colors.font;
+            });
+        }
+    }
+}
 
-    // 1. Read xl/styles.xml
+fn read_cell_colors(file: &Path, sheet_name: &str) -> Option<Vec<((usize, usize), StyleColors)>> {
+    let mut archive = open_xlsx_archive(file)?;
     let styles_xml = read_zip_entry(&mut archive, "xl/styles.xml")?;
-    let (fills, cell_xfs) = parse_styles_xml(&styles_xml)?;
+    let theme_colors = read_zip_entry(&mut archive, "xl/theme/theme1.xml")
+        .and_then(|theme_xml| parse_theme_colors(&theme_xml))
+        .unwrap_or_default();
+    let styles = parse_style_colors(&styles_xml, &theme_colors)?;
+    // Most workbooks never color a cell, so skip the sheet scan
+    if styles.is_empty() {
+        return Some(Vec::new());
+    }
 
-    // 2. Resolve sheet path and read sheet XML
     let sheet_path = resolve_xlsx_sheet_path(&mut archive, sheet_name)?;
     let sheet_xml = read_zip_entry(&mut archive, &sheet_path)?;
-
-    // 3. Parse sheet XML to map cells to color hex
-    let cell_colors = parse_sheet_styles(&sheet_xml, &fills, &cell_xfs)?;
-    Some(cell_colors)
-}
-
-fn parse_styles_xml(xml: &str) -> Option<(Vec<Option<String>>, Vec<usize>)> {
-    let mut reader = quick_xml::Reader::from_str(xml);
+    let mut reader = quick_xml::Reader::from_str(&sheet_xml);
     reader.config_mut().trim_text(true);
     let mut buf = Vec::new();
-
-    let mut fills = Vec::new();
-    let mut cell_xfs = Vec::new();
-
-    let mut in_fills = false;
-    let mut in_cell_xfs = false;
-    let mut current_fill_fg_color = None;
+    let mut cells = Vec::new();
 
     loop {
-        match reader.read_event_into(&mut buf) {
-            Ok(Event::Start(event)) => {
-                let tag_name = event.name();
-                if tag_name.as_ref() == b"fills" {
-                    in_fills = true;
-                } else if tag_name.as_ref() == b"cellXfs" {
-                    in_cell_xfs = true;
-                } else if in_fills && tag_name.as_ref() == b"fill" {
-                    current_fill_fg_color = None;
-                } else if in_fills && tag_name.as_ref() == b"fgColor" {
-                    if let Some(rgb) = attr_value(&reader, &event, b"rgb") {
-                        current_fill_fg_color = Some(rgb);
-                    }
-                } else if in_cell_xfs && tag_name.as_ref() == b"xf" {
-                    if let Some(fill_id_str) = attr_value(&reader, &event, b"fillId") {
-                        if let Ok(fill_id) = fill_id_str.parse::<usize>() {
-                            cell_xfs.push(fill_id);
-                        } else {
-                            cell_xfs.push(0);
-                        }
-                    } else {
-                        cell_xfs.push(0);
+        match reader.read_event_into(&mut buf).ok()? {
+            Event::Start(event) | Event::Empty(event) if event.name().as_ref() == b"c" => {
+                let colors = attr_value(&reader, &event, b"s")
+                    .and_then(|style| style.parse::<usize>().ok())
+                    .and_then(|style| styles.get(&style));
+                if let Some(colors) = colors {
+                    if let Some(cell) = attr_value(&reader, &event, b"r")
+                        .and_then(|reference| parse_cell_reference(&reference))
+                    {
+                        cells.push((cell, *colors));
                     }
                 }
             }
-            Ok(Event::Empty(event)) => {
-                let tag_name = event.name();
-                if in_fills && tag_name.as_ref() == b"fgColor" {
-                    if let Some(rgb) = attr_value(&reader, &event, b"rgb") {
-                        current_fill_fg_color = Some(rgb);
-                    }
-                } else if in_cell_xfs && tag_name.as_ref() == b"xf" {
-                    if let Some(fill_id_str) = attr_value(&reader, &event, b"fillId") {
-                        if let Ok(fill_id) = fill_id_str.parse::<usize>() {
-                            cell_xfs.push(fill_id);
-                        } else {
-                            cell_xfs.push(0);
-                        }
-                    } else {
-                        cell_xfs.push(0);
-                    }
-                } else if in_fills && tag_name.as_ref() == b"fill" {
-                    fills.push(None);
-                }
-            }
-            Ok(Event::End(event)) => {
-                let tag_name = event.name();
-                if tag_name.as_ref() == b"fills" {
-                    in_fills = false;
-                } else if tag_name.as_ref() == b"cellXfs" {
-                    in_cell_xfs = false;
-                } else if in_fills && tag_name.as_ref() == b"fill" {
-                    fills.push(current_fill_fg_color.clone());
-                    current_fill_fg_color = None;
-                }
-            }
-            Ok(Event::Eof) => break,
-            Err(_) => return None,
+            Event::Eof => break,
             _ => {}
         }
         buf.clear();
     }
 
-    Some((fills, cell_xfs))
+    Some(cells)
 }
 
-fn parse_sheet_styles(
-    xml: &str,
-    fills: &[Option<String>],
-    cell_xfs: &[usize],
-) -> Option<HashMap<String, String>> {
-    let mut reader = quick_xml::Reader::from_str(xml);
+/// The colors of each `cellXfs` entry that has any, by index
+fn parse_style_colors(
+    styles_xml: &str,
+    theme_colors: &[u32],
+) -> Option<HashMap<usize, StyleColors>> {
+    let mut reader = quick_xml::Reader::from_str(styles_xml);
     reader.config_mut().trim_text(true);
     let mut buf = Vec::new();
-    let mut cell_colors = HashMap::new();
+
+    let mut section = Section::Other;
+    let mut fonts = Vec::new();
+    let mut fills = Vec::new();
+    let mut xfs = Vec::new();
+    let mut color = None;
+    let mut solid = false;
 
     loop {
-        match reader.read_event_into(&mut buf) {
-            Ok(Event::Start(event)) | Ok(Event::Empty(event)) if event.name().as_ref() == b"c" => {
-                if let Some(r) = attr_value(&reader, &event, b"r") {
-                    if let Some(s_str) = attr_value(&reader, &event, b"s") {
-                        if let Ok(s_idx) = s_str.parse::<usize>() {
-                            if s_idx < cell_xfs.len() {
-                                let fill_id = cell_xfs[s_idx];
-                                if fill_id < fills.len() {
-                                    if let Some(ref color) = fills[fill_id] {
-                                        // color is ARGB format (like FFFF0000)
-                                        // We only want the last 6 chars (RRGGBB)
-                                        let clean_color = if color.len() == 8 {
-                                            color[2..].to_string()
-                                        } else {
-                                            color.clone()
-                                        };
-                                        cell_colors.insert(r, clean_color);
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
+        let event = reader.read_event_into(&mut buf).ok()?;
+        match (&event, section) {
+            (Event::Start(start), _) if section == Section::Other => {
+                section = match start.name().as_ref() {
+                    b"fonts" => Section::Fonts,
+                    b"fills" => Section::Fills,
+                    b"cellXfs" => Section::CellXfs,
+                    _ => Section::Other,
+                };
+            }
+            (Event::End(end), _)
+                if matches!(end.name().as_ref(), b"fonts" | b"fills" | b"cellXfs") =>
+            {
+                section = Section::Other;
+            }
+
+            (Event::Start(start), Section::Fonts) if start.name().as_ref() == b"font" => {
+                color = None;
+            }
+            (Event::Empty(empty), Section::Fonts) if empty.name().as_ref() == b"font" => {
+                fonts.push(None);
+            }
+            (Event::Start(element) | Event::Empty(element), Section::Fonts)
+                if element.name().as_ref() == b"color" =>
+            {
+                color = color_value(&reader, element, theme_colors);
+            }
+            (Event::End(end), Section::Fonts) if end.name().as_ref() == b"font" => {
+                fonts.push(color.take());
+            }
+
+            (Event::Start(start), Section::Fills) if start.name().as_ref() == b"fill" => {
+                color = None;
+                solid = false;
+            }
+            (Event::Empty(empty), Section::Fills) if empty.name().as_ref() == b"fill" => {
+                fills.push(None);
+            }
+            (Event::Start(element) | Event::Empty(element), Section::Fills)
+                if element.name().as_ref() == b"patternFill" =>
+            {
+                solid = attr_value(&reader, element, b"patternType").as_deref() == Some("solid");
+            }
+            (Event::Start(element) | Event::Empty(element), Section::Fills)
+                if element.name().as_ref() == b"fgColor" && solid =>
+            {
+                color = color_value(&reader, element, theme_colors);
+            }
+            (Event::End(end), Section::Fills) if end.name().as_ref() == b"fill" => {
+                fills.push(color.take());
             }
-            Ok(Event::Eof) => break,
-            Err(_) => return None,
+
+            (Event::Start(element) | Event::Empty(element), Section::CellXfs)
+                if element.name().as_ref() == b"xf" =>
+            {
+                let id = |key| {
+                    attr_value(&reader, element, key)
+                        .and_then(|id| id.parse::<usize>().ok())
+                        .unwrap_or(0)
+                };
+                xfs.push((id(b"fontId"), id(b"fillId")));
+            }
+            (Event::Eof, _) => break,
             _ => {}
         }
         buf.clear();
     }
 
-    Some(cell_colors)
+    let styles = xfs
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, (font_id, fill_id))| {
+            // The default font's color is the text color of every unstyled cell
+            let font = fonts
+                .get(font_id)
+                .copied()
+                .flatten()
+                .filter(|_| font_id > 0);
+            let fill = fills.get(fill_id).copied().flatten();
+            (font.is_some() || fill.is_some()).then_some((index, StyleColors { fill, font }))
+        })
+        .collect();
+    Some(styles)
+}
+
+/// The color a `color`, `fgColor` or `bgColor` element names
+pub(super) fn color_value(
+    reader: &quick_xml::Reader<&[u8]>,
+    element: &BytesStart<'_>,
+    theme_colors: &[u32],
+) -> Option<u32> {
+    if let Some(rgb) = attr_value(reader, element, b"rgb") {
+        return parse_argb(&rgb);
+    }
+    let index = attr_value(reader, element, b"theme")?
+        .parse::<usize>()
+        .ok()?;
+    let tint = attr_value(reader, element, b"tint")
+        .and_then(|tint| tint.parse().ok())
+        .unwrap_or(0.0);
+    theme_colors.get(index).map(|&base| apply_tint(base, tint))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_style_colors, StyleColors};
+
+    #[test]
+    fn reads_fill_and_font_colors_of_each_cell_style() {
+        let styles_xml = r#"<styleSheet>
+            <fonts count="3">
+                <font><sz val="11"/><color theme="1"/><name val="Calibri"/></font>
+                <font><b/><color rgb="FFFF0000"/></font>
+                <font><color theme="4" tint="-0.5"/></font>
+            </fonts>
+            <fills count="4">
+                <fill><patternFill patternType="none"/></fill>
+                <fill><patternFill patternType="gray125"/></fill>
+                <fill><patternFill patternType="solid"><fgColor rgb="FFFFFF00"/><bgColor indexed="64"/></patternFill></fill>
+                <fill><patternFill patternType="solid"><fgColor indexed="10"/></patternFill></fill>
+            </fills>
+            <cellStyleXfs count="1"><xf numFmtId="0" fontId="1" fillId="2"/></cellStyleXfs>
+            <cellXfs count="5">
+                <xf numFmtId="0" fontId="0" fillId="0" xfId="0"/>
+                <xf numFmtId="0" fontId="1" fillId="0" xfId="0" applyFont="1"/>
+                <xf numFmtId="0" fontId="0" fillId="2" xfId="0" applyFill="1"><alignment horizontal="center"/></xf>
+                <xf numFmtId="0" fontId="2" fillId="3" xfId="0"/>
+                <xf numFmtId="0" fontId="0" fillId="3" xfId="0"/>
+            </cellXfs>
+            <dxfs count="1"><dxf><fill><patternFill><bgColor rgb="FF00FF00"/></patternFill></fill></dxf></dxfs>
+        </styleSheet>"#;
+        let theme = [0xFFFFFF, 0x000000, 0xE7E6E6, 0x44546A, 0x4472C4];
+
+        let styles = parse_style_colors(styles_xml, &theme).unwrap();
+        assert_eq!(styles.len(), 3);
+        assert_eq!(
+            styles[&1],
+            StyleColors {
+                fill: None,
+                font: Some(0xFF0000)
+            }
+        );
+        assert_eq!(
+            styles[&2],
+            StyleColors {
+                fill: Some(0xFFFF00),
+                font: None
+            }
+        );
+        assert_eq!(
+            styles[&3],
+            StyleColors {
+                fill: None,
+                font: Some(0x223962)
+            }
+        );
+    }
 }
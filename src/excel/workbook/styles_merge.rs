@@ -0,0 +1,317 @@
+use anyhow::{Context, Result};
+use quick_xml::events::{BytesStart, BytesText, Event};
+use quick_xml::name::QName;
+use quick_xml::{Reader, Writer};
+use std::collections::HashMap;
+
+/// The first id of the number formats a workbook defines; lower ids are Excel's own
+const FIRST_CUSTOM_NUM_FMT: usize = 164;
+
+/// The lists of `styles.xml` that cells and conditional formats point into by index
+const SECTIONS: [&[u8]; 6] = [
+    b"numFmts", b"fonts", b"fills", b"borders", b"cellXfs", b"dxfs",
+];
+
+/// How far the style indices of a sheet copied from another workbook move once that
+/// workbook's styles follow the ones already written
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(super) struct StyleOffsets {
+    /// Added to the `s` of cells and rows and the `style` of columns
+    pub cell_xfs: usize,
+    /// Added to the `dxfId` of conditional formats and tables
+    pub dxfs: usize,
+}
+
+/// The entries of each list of a `styles.xml`, as written in it
+#[derive(Default)]
+struct StyleLists {
+    entries: HashMap<&'static [u8], Vec<String>>,
+}
+
+impl StyleLists {
+    fn read(xml: &str) -> Result<Self> {
+        let mut reader = Reader::from_str(xml);
+        reader.config_mut().trim_text(true);
+        let mut lists = Self::default();
+        let mut section = None;
+
+        loop {
+            let from = position(&reader);
+            match reader.read_event()? {
+                Event::Start(start) if section.is_none() => {
+                    section = section_of(&start);
+                }
+                Event::Start(start) => {
+                    let name = start.name().as_ref().to_vec();
+                    reader.read_to_end(QName(&name))?;
+                    lists.push(section, &xml[from..position(&reader)]);
+                }
+                Event::Empty(_) if section.is_some() => {
+                    lists.push(section, &xml[from..position(&reader)]);
+                }
+                Event::End(end) if section == Some(end.local_name().as_ref()) => section = None,
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+        Ok(lists)
+    }
+
+    fn push(&mut self, section: Option<&'static [u8]>, entry: &str) {
+        if let Some(section) = section {
+            self.entries
+                .entry(section)
+                .or_default()
+                .push(entry.trim().to_string());
+        }
+    }
+
+    fn list(&self, section: &[u8]) -> &[String] {
+        self.entries.get(section).map_or(&[], Vec::as_slice)
+    }
+
+    fn count(&self, section: &[u8]) -> usize {
+        self.list(section).len()
+    }
+
+    /// The ids of the workbook's own number formats
+    fn num_fmt_ids(&self) -> Vec<usize> {
+        self.list(b"numFmts")
+            .iter()
+            .filter_map(|entry| first_attribute(entry, b"numFmtId")?.parse().ok())
+            .collect()
+    }
+}
+
+/// `written`, the `styles.xml` of a workbook, followed in each of its lists by the entries of
+/// `copied`, another workbook's, so that sheets copied from that workbook keep their styles
+/// once their indices move by the returned offsets
+pub(super) fn merge_styles(written: &str, copied: &str) -> Result<(String, StyleOffsets)> {
+    let ours = StyleLists::read(written).context("unreadable styles.xml")?;
+    let theirs = StyleLists::read(copied).context("unreadable styles.xml in the original")?;
+
+    // The copied workbook's own number formats take ids after ours
+    let first_free = ours
+        .num_fmt_ids()
+        .into_iter()
+        .map(|id| id + 1)
+        .max()
+        .unwrap_or(FIRST_CUSTOM_NUM_FMT)
+        .max(FIRST_CUSTOM_NUM_FMT);
+    let num_fmt_ids: HashMap<usize, usize> = theirs
+        .num_fmt_ids()
+        .into_iter()
+        .filter(|&id| id >= FIRST_CUSTOM_NUM_FMT)
+        .zip(first_free..)
+        .collect();
+    let num_fmt_id = |value: &[u8]| {
+        let id: usize = std::str::from_utf8(value).ok()?.parse().ok()?;
+        num_fmt_ids.get(&id).map(usize::to_string)
+    };
+
+    let mut appended = HashMap::new();
+    for section in SECTIONS {
+        let entries = theirs.list(section).iter().map(|entry| match section {
+            b"numFmts" => rewrite_attributes(entry, |_, key, value| {
+                (key == b"numFmtId").then(|| num_fmt_id(value)).flatten()
+            }),
+            // The copied cells keep their formats but not the named cell style they came
+            // from, which only Excel's Cell Styles gallery shows
+            b"cellXfs" => rewrite_attributes(entry, |element, key, value| match (element, key) {
+                (b"xf", b"numFmtId") => num_fmt_id(value),
+                (b"xf", b"fontId") => shifted(value, ours.count(b"fonts")),
+                (b"xf", b"fillId") => shifted(value, ours.count(b"fills")),
+                (b"xf", b"borderId") => shifted(value, ours.count(b"borders")),
+                (b"xf", b"xfId") => Some("0".to_string()),
+                _ => None,
+            }),
+            _ => Ok(entry.clone()),
+        });
+        appended.insert(section, entries.collect::<Result<Vec<_>>>()?);
+    }
+
+    let offsets = StyleOffsets {
+        cell_xfs: ours.count(b"cellXfs"),
+        dxfs: ours.count(b"dxfs"),
+    };
+    let merged = append_entries(written, &ours, &appended)?;
+    Ok((merged, offsets))
+}
+
+/// Writes `xml` with `appended` added to the end of each list, and a number format list
+/// put before the fonts when `xml` has none
+fn append_entries(
+    xml: &str,
+    ours: &StyleLists,
+    appended: &HashMap<&[u8], Vec<String>>,
+) -> Result<String> {
+    let mut reader = Reader::from_str(xml);
+    let mut writer = Writer::new(Vec::new());
+    let mut has_num_fmts = false;
+
+    let open_list = |writer: &mut Writer<Vec<u8>>, start: &BytesStart<'_>, section: &[u8]| {
+        let count = (ours.count(section) + appended[section].len()).to_string();
+        let start = map_attributes(start, |key, _| (key == b"count").then(|| count.clone()));
+        writer.write_event(Event::Start(start))
+    };
+    let close_list = |writer: &mut Writer<Vec<u8>>, start: &BytesStart<'_>, section: &[u8]| {
+        for entry in &appended[section] {
+            writer.write_event(Event::Text(BytesText::from_escaped(entry.as_str())))?;
+        }
+        writer.write_event(Event::End(start.to_end()))
+    };
+
+    let mut open = None;
+    loop {
+        let event = reader.read_event()?;
+        let section = match &event {
+            Event::Start(start) | Event::Empty(start) => section_of(start),
+            _ => None,
+        };
+        if section == Some(b"fonts") && !has_num_fmts && !appended[b"numFmts".as_slice()].is_empty()
+        {
+            has_num_fmts = true;
+            let start = BytesStart::new("numFmts").with_attributes([("count", "0")]);
+            open_list(&mut writer, &start, b"numFmts")?;
+            close_list(&mut writer, &start, b"numFmts")?;
+        }
+        has_num_fmts |= section == Some(b"numFmts");
+
+        match (event, section) {
+            (Event::Start(start), Some(section)) => {
+                open_list(&mut writer, &start, section)?;
+                open = Some((start.into_owned(), section));
+            }
+            (Event::Empty(start), Some(section)) => {
+                let start = start.into_owned();
+                open_list(&mut writer, &start, section)?;
+                close_list(&mut writer, &start, section)?;
+            }
+            (Event::End(end), _)
+                if open
+                    .as_ref()
+                    .is_some_and(|(start, _)| start.name() == end.name()) =>
+            {
+                if let Some((start, section)) = open.take() {
+                    close_list(&mut writer, &start, section)?;
+                }
+            }
+            (Event::Eof, _) => break,
+            (event, _) => writer.write_event(event)?,
+        }
+    }
+    String::from_utf8(writer.into_inner()).context("styles.xml is not UTF-8")
+}
+
+fn section_of(start: &BytesStart<'_>) -> Option<&'static [u8]> {
+    SECTIONS
+        .into_iter()
+        .find(|name| *name == start.local_name().as_ref())
+}
+
+/// `xml` with each attribute `map` gives a value for set to it, by element and attribute name
+pub(super) fn rewrite_attributes(
+    xml: &str,
+    map: impl Fn(&[u8], &[u8], &[u8]) -> Option<String>,
+) -> Result<String> {
+    let mut reader = Reader::from_str(xml);
+    let mut writer = Writer::new(Vec::new());
+    loop {
+        match reader.read_event()? {
+            Event::Start(start) => {
+                let element = start.local_name().as_ref().to_vec();
+                let start = map_attributes(&start, |key, value| map(&element, key, value));
+                writer.write_event(Event::Start(start))?;
+            }
+            Event::Empty(start) => {
+                let element = start.local_name().as_ref().to_vec();
+                let start = map_attributes(&start, |key, value| map(&element, key, value));
+                writer.write_event(Event::Empty(start))?;
+            }
+            Event::Eof => break,
+            event => writer.write_event(event)?,
+        }
+    }
+    String::from_utf8(writer.into_inner()).context("XML is not UTF-8")
+}
+
+/// `start` with each attribute `map` gives a value for set to it, by the attribute's name
+/// without its prefix and its value as written
+pub(super) fn map_attributes(
+    start: &BytesStart<'_>,
+    mut map: impl FnMut(&[u8], &[u8]) -> Option<String>,
+) -> BytesStart<'static> {
+    let mut mapped = start.clone().into_owned();
+    mapped.clear_attributes();
+    for attribute in start.attributes().with_checks(false).flatten() {
+        match map(attribute.key.local_name().as_ref(), &attribute.value) {
+            Some(value) => mapped.push_attribute((attribute.key.as_ref(), value.as_bytes())),
+            None => mapped.push_attribute(attribute),
+        }
+    }
+    mapped
+}
+
+/// A numeric attribute value moved up by `by`
+pub(super) fn shifted(value: &[u8], by: usize) -> Option<String> {
+    let index: usize = std::str::from_utf8(value).ok()?.parse().ok()?;
+    Some((index + by).to_string())
+}
+
+fn first_attribute(entry: &str, key: &[u8]) -> Option<String> {
+    let mut reader = Reader::from_str(entry);
+    match reader.read_event().ok()? {
+        Event::Start(start) | Event::Empty(start) => start
+            .attributes()
+            .flatten()
+            .find(|attribute| attribute.key.local_name().as_ref() == key)
+            .and_then(|attribute| String::from_utf8(attribute.value.into_owned()).ok()),
+        _ => None,
+    }
+}
+
+/// Where `reader` is in the text it reads, as an index into it
+pub(super) fn position(reader: &Reader<&[u8]>) -> usize {
+    usize::try_from(reader.buffer_position()).unwrap_or(usize::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{merge_styles, StyleOffsets};
+
+    #[test]
+    fn copied_styles_follow_the_written_ones_with_their_ids_moved() {
+        let written = concat!(
+            r#"<styleSheet><fonts count="1"><font><sz val="11"/></font></fonts>"#,
+            r#"<fills count="2"><fill/><fill/></fills><borders count="1"><border/></borders>"#,
+            r#"<cellXfs count="1"><xf numFmtId="0" fontId="0" fillId="0" borderId="0" xfId="0"/></cellXfs>"#,
+            r#"<dxfs count="0"/></styleSheet>"#,
+        );
+        let copied = concat!(
+            r#"<styleSheet><numFmts count="1"><numFmt numFmtId="170" formatCode="0.0%"/></numFmts>"#,
+            r#"<fonts count="1"><font><b/></font></fonts><fills count="1"><fill/></fills>"#,
+            r#"<borders count="1"><border/></borders><cellStyleXfs count="2"><xf/><xf/></cellStyleXfs>"#,
+            r#"<cellXfs count="1"><xf numFmtId="170" fontId="0" fillId="0" borderId="0" xfId="1">"#,
+            r#"<alignment wrapText="1"/></xf></cellXfs><dxfs count="1"><dxf><font><i/></font></dxf></dxfs></styleSheet>"#,
+        );
+        let (merged, offsets) = merge_styles(written, copied).unwrap();
+        assert_eq!(
+            offsets,
+            StyleOffsets {
+                cell_xfs: 1,
+                dxfs: 0
+            }
+        );
+        assert_eq!(
+            merged,
+            concat!(
+                r#"<styleSheet><numFmts count="1"><numFmt numFmtId="164" formatCode="0.0%"/></numFmts>"#,
+                r#"<fonts count="2"><font><sz val="11"/></font><font><b/></font></fonts>"#,
+                r#"<fills count="3"><fill/><fill/><fill/></fills><borders count="2"><border/><border/></borders>"#,
+                r#"<cellXfs count="2"><xf numFmtId="0" fontId="0" fillId="0" borderId="0" xfId="0"/>"#,
+                r#"<xf numFmtId="164" fontId="1" fillId="2" borderId="1" xfId="0"><alignment wrapText="1"/></xf></cellXfs>"#,
+                r#"<dxfs count="1"><dxf><font><i/></font></dxf></dxfs></styleSheet>"#,
+            )
+        );
+    }
+}
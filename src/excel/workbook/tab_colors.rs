@@ -0,0 +1,165 @@
+use quick_xml::events::Event;
+use std::path::Path;
+
+use super::formula_lookup::{
+    attr_value, open_xlsx_archive, read_zip_entry, resolve_xlsx_sheet_path,
+};
+
+/// How a `tabColor` element names its color
+enum TabColorSpec {
+    Rgb(u32),
+    Theme { index: usize, tint: f64 },
+}
+
+/// Reads the tab color of `sheet_name` as `0xRRGGBB`. Explicit and theme colors are
+/// resolved; legacy indexed colors are not
+pub(super) fn lookup_tab_color_in_xlsx(file: &Path, sheet_name: &str) -> Option<u32> {
+    let mut archive = open_xlsx_archive(file)?;
+    let sheet_path = resolve_xlsx_sheet_path(&mut archive, sheet_name)?;
+    let sheet_xml = read_zip_entry(&mut archive, &sheet_path)?;
+
+    match read_tab_color_spec(&sheet_xml)? {
+        TabColorSpec::Rgb(rgb) => Some(rgb),
+        TabColorSpec::Theme { index, tint } => {
+            let theme_xml = read_zip_entry(&mut archive, "xl/theme/theme1.xml")?;
+            let base = parse_theme_colors(&theme_xml)?.get(index).copied()?;
+            Some(apply_tint(base, tint))
+        }
+    }
+}
+
+fn read_tab_color_spec(sheet_xml: &str) -> Option<TabColorSpec> {
+    let mut reader = quick_xml::Reader::from_str(sheet_xml);
+    reader.config_mut().trim_text(true);
+    let mut buf = Vec::new();
+
+    loop {
+        match reader.read_event_into(&mut buf).ok()? {
+            Event::Start(event) | Event::Empty(event) if event.name().as_ref() == b"tabColor" => {
+                if let Some(rgb) = attr_value(&reader, &event, b"rgb") {
+                    return parse_argb(&rgb).map(TabColorSpec::Rgb);
+                }
+                let index = attr_value(&reader, &event, b"theme")?.parse().ok()?;
+                let tint = attr_value(&reader, &event, b"tint")
+                    .and_then(|tint| tint.parse().ok())
+                    .unwrap_or(0.0);
+                return Some(TabColorSpec::Theme { index, tint });
+            }
+            // `sheetPr` comes before the cells, so a large sheet need not be scanned
+            Event::Start(event) if event.name().as_ref() == b"sheetData" => return None,
+            Event::Eof => return None,
+            _ => {}
+        }
+        buf.clear();
+    }
+}
+
+/// Parses `FFRRGGBB` or `RRGGBB`, dropping the alpha byte
+pub(super) fn parse_argb(value: &str) -> Option<u32> {
+    let rgb = match value.len() {
+        8 => &value[2..],
+        6 => value,
+        _ => return None,
+    };
+    u32::from_str_radix(rgb, 16).ok()
+}
+
+/// The theme's colors in the order `theme` attributes index them. The scheme lists the dark
+/// colors first, but index 0 and 1 (and 2 and 3) refer to the light color before the dark one
+pub(super) fn parse_theme_colors(theme_xml: &str) -> Option<Vec<u32>> {
+    let mut reader = quick_xml::Reader::from_str(theme_xml);
+    reader.config_mut().trim_text(true);
+    let mut buf = Vec::new();
+    let mut colors = Vec::new();
+    let mut in_scheme = false;
+
+    loop {
+        match reader.read_event_into(&mut buf).ok()? {
+            Event::Start(event) if event.local_name().as_ref() == b"clrScheme" => {
+                in_scheme = true;
+            }
+            Event::End(event) if event.local_name().as_ref() == b"clrScheme" => break,
+            Event::Start(event) | Event::Empty(event) if in_scheme => {
+                let color = match event.local_name().as_ref() {
+                    b"srgbClr" => attr_value(&reader, &event, b"val"),
+                    b"sysClr" => attr_value(&reader, &event, b"lastClr"),
+                    _ => None,
+                };
+                if let Some(rgb) = color.as_deref().and_then(parse_argb) {
+                    colors.push(rgb);
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    if colors.len() < 4 {
+        return None;
+    }
+    colors.swap(0, 1);
+    colors.swap(2, 3);
+    Some(colors)
+}
+
+/// Lightens (positive `tint`) or darkens (negative) a color, close to how Excel shades
+/// theme colors
+pub(super) fn apply_tint(rgb: u32, tint: f64) -> u32 {
+    let shade = |channel: u32| {
+        let channel = f64::from(channel);
+        let shaded = if tint < 0.0 {
+            channel * (1.0 + tint)
+        } else {
+            channel + (255.0 - channel) * tint
+        };
+        shaded.round().clamp(0.0, 255.0) as u32
+    };
+    (shade(rgb >> 16 & 0xFF) << 16) | (shade(rgb >> 8 & 0xFF) << 8) | shade(rgb & 0xFF)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{apply_tint, parse_theme_colors, read_tab_color_spec, TabColorSpec};
+
+    #[test]
+    fn reads_rgb_and_theme_tab_colors() {
+        let rgb =
+            r#"<worksheet><sheetPr><tabColor rgb="FF00B050"/></sheetPr><sheetData/></worksheet>"#;
+        assert!(matches!(
+            read_tab_color_spec(rgb),
+            Some(TabColorSpec::Rgb(0x00B050))
+        ));
+
+        let theme =
+            r#"<worksheet><sheetPr><tabColor theme="5" tint="-0.25"/></sheetPr></worksheet>"#;
+        let Some(TabColorSpec::Theme { index, tint }) = read_tab_color_spec(theme) else {
+            panic!("expected a theme tab color");
+        };
+        assert_eq!((index, tint), (5, -0.25));
+
+        let plain = r#"<worksheet><sheetData><row r="1"/></sheetData></worksheet>"#;
+        assert!(read_tab_color_spec(plain).is_none());
+    }
+
+    #[test]
+    fn resolves_theme_colors_with_light_colors_first() {
+        let theme_xml = r#"<a:theme><a:themeElements><a:clrScheme name="Office">
+            <a:dk1><a:sysClr val="windowText" lastClr="000000"/></a:dk1>
+            <a:lt1><a:sysClr val="window" lastClr="FFFFFF"/></a:lt1>
+            <a:dk2><a:srgbClr val="44546A"/></a:dk2>
+            <a:lt2><a:srgbClr val="E7E6E6"/></a:lt2>
+            <a:accent1><a:srgbClr val="4472C4"/></a:accent1>
+            <a:accent2><a:srgbClr val="ED7D31"/></a:accent2>
+        </a:clrScheme></a:themeElements></a:theme>"#;
+        let colors = parse_theme_colors(theme_xml).unwrap();
+        assert_eq!(
+            colors,
+            vec![0xFFFFFF, 0x000000, 0xE7E6E6, 0x44546A, 0x4472C4, 0xED7D31]
+        );
+
+        assert_eq!(apply_tint(0xED7D31, 0.0), 0xED7D31);
+        assert_eq!(apply_tint(0x000000, 0.5), 0x808080);
+        assert_eq!(apply_tint(0xFFFFFF, -0.5), 0x808080);
+    }
+}
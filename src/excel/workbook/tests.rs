@@ -2,8 +2,8 @@ use std::fs::File;
 use std::io::Read;
 use std::path::{Path, PathBuf};
 
-use super::{open_workbook, Workbook};
-use crate::excel::{Cell, FreezePanes, Sheet};
+use super::{open_workbook, open_workbook_reporting, LoadProgress, Workbook};
+use crate::excel::{Cell, FreezePanes, Sheet, SheetProtection, SheetVisibility};
 
 fn blank_sheet(name: &str) -> Sheet {
     Sheet::blank(name.to_string())
@@ -95,8 +95,7 @@ fn adds_blank_sheet_after_current_sheet() {
     assert_eq!(added_sheet.max_rows, 1);
     assert_eq!(added_sheet.max_cols, 1);
     assert!(added_sheet.is_loaded);
-    assert_eq!(added_sheet.data.len(), 2);
-    assert_eq!(added_sheet.data[1].len(), 2);
+    assert!(added_sheet.data.is_empty());
 }
 
 #[test]
@@ -120,6 +119,27 @@ fn rejects_invalid_sheet_names() {
         .is_err());
 }
 
+#[test]
+fn renaming_a_sheet_allows_case_changes_and_moves_defined_names() {
+    let mut workbook =
+        Workbook::from_sheets_for_test(vec![blank_sheet("Summary"), blank_sheet("Data")]);
+    workbook.define_name(crate::excel::DefinedName::for_range(
+        "Totals",
+        "Data",
+        (2, 2),
+        (9, 2),
+    ));
+
+    assert!(workbook.rename_sheet(0, "data").is_err());
+    assert_eq!(workbook.rename_sheet(1, " data ").unwrap(), "data");
+    assert_eq!(workbook.rename_sheet(1, "Q1 Sales").unwrap(), "Q1 Sales");
+    assert_eq!(workbook.get_sheet_names(), vec!["Summary", "Q1 Sales"]);
+    assert_eq!(
+        workbook.find_defined_name("Totals").unwrap().refers_to,
+        "'Q1 Sales'!$B$2:$B$9"
+    );
+}
+
 #[test]
 fn counts_sheet_name_length_by_characters() {
     let mut workbook = Workbook::from_sheets_for_test(vec![blank_sheet("Sheet1")]);
@@ -197,6 +217,36 @@ fn open_workbook_restores_xlsx_freeze_panes_metadata() {
     assert_eq!(sheet.freeze_panes.split_cell_ref(), "B2");
 }
 
+#[test]
+fn open_workbook_reporting_counts_the_rows_of_each_sheet() {
+    use rust_xlsxwriter::Workbook as XlsxWorkbook;
+
+    let path = temp_path("excel_cli_workbook_progress.xlsx");
+    let mut xlsx = XlsxWorkbook::new();
+    let big = xlsx.add_worksheet();
+    big.set_name("Big").unwrap();
+    for row in 0..5_000 {
+        big.write_number(row, 0, f64::from(row)).unwrap();
+    }
+    xlsx.add_worksheet().set_name("Small").unwrap();
+    xlsx.save(&path).unwrap();
+
+    let mut reports: Vec<LoadProgress> = Vec::new();
+    let workbook = open_workbook_reporting(&path, false, |progress| {
+        reports.push(progress.clone());
+    })
+    .unwrap();
+
+    assert_eq!(workbook.get_current_sheet().max_rows, 5_000);
+    assert_eq!(reports[0].sheet_name, "Big");
+    assert_eq!((reports[0].rows_read, reports[0].sheet_count), (0, 2));
+    assert!(reports
+        .iter()
+        .any(|progress| progress.rows_read >= 4_000 && progress.total_rows == Some(5_000)));
+    assert_eq!(reports.last().unwrap().sheet_name, "Small");
+    assert_eq!(reports.last().unwrap().sheet_index, 1);
+}
+
 #[test]
 fn lazy_loaded_sheet_preserves_freeze_panes_after_loading() {
     let path = temp_path("excel_cli_workbook_lazy_freeze_lookup.xlsx");
@@ -239,8 +289,9 @@ fn save_writes_freeze_panes_to_xlsx_xml() {
 #[test]
 fn deleting_rows_and_columns_shrinks_freeze_panes() {
     let mut sheet = Sheet::blank("Frozen".to_string());
-    sheet.data = vec![vec![Cell::empty(); 5]; 5];
-    sheet.data[4][4] = Cell::new("keep bounds".to_string(), false);
+    sheet
+        .data
+        .set(4, 4, Cell::new("keep bounds".to_string(), false));
     sheet.max_rows = 4;
     sheet.max_cols = 4;
     sheet.freeze_panes = FreezePanes { rows: 2, cols: 2 };
@@ -259,3 +310,510 @@ fn deleting_rows_and_columns_shrinks_freeze_panes() {
     assert_eq!(workbook.get_current_sheet().freeze_panes.rows, 1);
     assert_eq!(workbook.get_current_sheet().freeze_panes.cols, 1);
 }
+
+#[test]
+fn dates_from_1904_workbooks_are_shifted_to_the_1900_system() {
+    use super::sheet_parse::create_sheet_from_range;
+    use crate::excel::DataTypeInfo;
+    use crate::json_export::process_cell_value;
+    use calamine::{Data, ExcelDateTime, ExcelDateTimeType, Range};
+
+    let mut range = Range::new((0, 0), (0, 2));
+    range.set_value(
+        (0, 0),
+        Data::DateTime(ExcelDateTime::new(1.5, ExcelDateTimeType::DateTime, true)),
+    );
+    range.set_value(
+        (0, 2),
+        Data::DateTime(ExcelDateTime::new(0.75, ExcelDateTimeType::DateTime, true)),
+    );
+    range.set_value(
+        (0, 1),
+        Data::DateTime(ExcelDateTime::new(
+            45943.0,
+            ExcelDateTimeType::DateTime,
+            false,
+        )),
+    );
+
+    let sheet = create_sheet_from_range("Dates", range, None);
+
+    assert!(matches!(
+        sheet.data.cell(1, 1).original_type,
+        Some(DataTypeInfo::DateTime(serial)) if serial == 1463.5
+    ));
+    assert_eq!(
+        process_cell_value(sheet.data.cell(1, 1)),
+        "1904-01-02T12:00:00"
+    );
+    assert_eq!(process_cell_value(sheet.data.cell(1, 2)), "2025-10-13");
+    assert_eq!(process_cell_value(sheet.data.cell(1, 3)), "18:00:00");
+}
+
+#[test]
+fn cell_comments_round_trip_through_xlsx() {
+    use rust_xlsxwriter::{Note, Workbook as XlsxWorkbook};
+
+    let prefix = "excel_cli_comments_";
+    remove_temp_outputs(prefix);
+    let source = temp_path(&format!("{prefix}source.xlsx"));
+    let mut xlsx = XlsxWorkbook::new();
+    let sheet = xlsx.add_worksheet();
+    sheet.set_name("Notes").unwrap();
+    sheet.write_string(0, 0, "name").unwrap();
+    sheet
+        .insert_note(0, 0, &Note::new("Header & key").add_author_prefix(false))
+        .unwrap();
+    sheet
+        .insert_note(3, 2, &Note::new("Blank cell note").add_author_prefix(false))
+        .unwrap();
+    xlsx.save(&source).unwrap();
+
+    let mut workbook = open_workbook(&source, false).unwrap();
+    let data = &workbook.get_current_sheet().data;
+    assert_eq!(data.cell(1, 1).comment.as_deref(), Some("Header & key"));
+    assert_eq!(data.cell(4, 3).comment.as_deref(), Some("Blank cell note"));
+    assert_eq!(data.cell(1, 2).comment, None);
+
+    workbook.set_cell_comment(1, 1, Some("Edited".to_string()));
+    assert!(workbook.is_modified());
+    workbook.save().unwrap();
+    std::fs::remove_file(&source).unwrap();
+
+    let saved_path = find_temp_output(prefix);
+    let reopened = open_workbook(&saved_path, false).unwrap();
+    let data = &reopened.get_current_sheet().data;
+    assert_eq!(data.cell(1, 1).comment.as_deref(), Some("Edited"));
+    assert_eq!(data.cell(4, 3).comment.as_deref(), Some("Blank cell note"));
+    remove_temp_outputs(prefix);
+}
+
+#[test]
+fn hyperlinks_round_trip_through_xlsx() {
+    use rust_xlsxwriter::{Url, Workbook as XlsxWorkbook};
+
+    let prefix = "excel_cli_hyperlinks_";
+    remove_temp_outputs(prefix);
+    let source = temp_path(&format!("{prefix}source.xlsx"));
+    let mut xlsx = XlsxWorkbook::new();
+    let sheet = xlsx.add_worksheet();
+    sheet.set_name("Links").unwrap();
+    sheet
+        .write_url_with_text(0, 0, Url::new("https://example.com/docs"), "Docs")
+        .unwrap();
+    sheet
+        .write_url_with_text(1, 0, Url::new("internal:'Q1 Sales'!B2"), "Sales")
+        .unwrap();
+    xlsx.add_worksheet().set_name("Q1 Sales").unwrap();
+    xlsx.save(&source).unwrap();
+
+    let mut workbook = open_workbook(&source, false).unwrap();
+    let data = &workbook.get_current_sheet().data;
+    assert_eq!(data.cell(1, 1).value, "Docs");
+    assert_eq!(
+        data.cell(1, 1).hyperlink.as_deref(),
+        Some("https://example.com/docs")
+    );
+    assert_eq!(data.cell(2, 1).hyperlink.as_deref(), Some("#'Q1 Sales'!B2"));
+
+    workbook.set_cell_value(1, 1, "Manual".to_string()).unwrap();
+    workbook.save().unwrap();
+    std::fs::remove_file(&source).unwrap();
+
+    let saved_path = find_temp_output(prefix);
+    let reopened = open_workbook(&saved_path, false).unwrap();
+    let data = &reopened.get_current_sheet().data;
+    assert_eq!(data.cell(1, 1).value, "Manual");
+    assert_eq!(
+        data.cell(1, 1).hyperlink.as_deref(),
+        Some("https://example.com/docs")
+    );
+    assert_eq!(data.cell(2, 1).hyperlink.as_deref(), Some("#'Q1 Sales'!B2"));
+    remove_temp_outputs(prefix);
+}
+
+#[test]
+fn save_writes_number_formats_for_formatted_cells_and_columns() {
+    use crate::excel::NumberFormat;
+
+    let prefix = "excel_cli_number_format_";
+    remove_temp_outputs(prefix);
+
+    let mut sheet = Sheet::blank("Report".to_string());
+    sheet.data.set(1, 1, Cell::new("1234.5".to_string(), false));
+    sheet.data.update_cell(1, 1, |cell| {
+        cell.number_format = Some("thousands 2".parse::<NumberFormat>().unwrap())
+    });
+    sheet
+        .data
+        .set(1, 2, Cell::new("2024-01-15".to_string(), false));
+    sheet.max_rows = 1;
+    sheet.max_cols = 2;
+    sheet
+        .column_formats
+        .insert(2, "date dd/mm/yyyy".parse().unwrap());
+    let mut workbook = Workbook::from_sheets_for_test(vec![sheet]);
+    workbook.file_path = temp_path(&format!("{prefix}source.xlsx"))
+        .to_string_lossy()
+        .to_string();
+    workbook.set_modified(true);
+
+    workbook.save().unwrap();
+
+    let saved_path = find_temp_output(prefix);
+    let styles = worksheet_xml(&saved_path, "xl/styles.xml");
+    assert!(styles.contains(r##"formatCode="#,##0.00""##), "{styles}");
+    assert!(styles.contains(r#"formatCode="dd/mm/yyyy""#), "{styles}");
+    let xml = worksheet_xml(&saved_path, "xl/worksheets/sheet1.xml");
+    assert!(xml.contains("<v>1234.5</v>"), "{xml}");
+    assert!(xml.contains("<v>45306</v>"), "{xml}");
+    remove_temp_outputs(prefix);
+}
+
+#[test]
+fn defined_names_round_trip_through_xlsx() {
+    use rust_xlsxwriter::Workbook as XlsxWorkbook;
+
+    let prefix = "excel_cli_defined_names_";
+    remove_temp_outputs(prefix);
+    let source = temp_path(&format!("{prefix}source.xlsx"));
+    let mut xlsx = XlsxWorkbook::new();
+    xlsx.add_worksheet().set_name("Data").unwrap();
+    xlsx.add_worksheet().set_name("Q1 Sales").unwrap();
+    xlsx.define_name("Rate", "=0.075").unwrap();
+    xlsx.define_name("Header", "=Data!$A$1:$C$1").unwrap();
+    xlsx.save(&source).unwrap();
+
+    let mut workbook = open_workbook(&source, false).unwrap();
+    assert_eq!(
+        workbook
+            .find_defined_name("header")
+            .map(|name| name.refers_to.as_str()),
+        Some("Data!$A$1:$C$1")
+    );
+    assert_eq!(
+        workbook
+            .find_defined_name("Rate")
+            .map(|name| name.refers_to.as_str()),
+        Some("0.075")
+    );
+
+    workbook.define_name(crate::excel::DefinedName::for_range(
+        "Totals",
+        "Q1 Sales",
+        (2, 2),
+        (9, 2),
+    ));
+    workbook.save().unwrap();
+    std::fs::remove_file(&source).unwrap();
+
+    let saved_path = find_temp_output(prefix);
+    let reopened = open_workbook(&saved_path, false).unwrap();
+    assert_eq!(reopened.defined_names().len(), 3);
+    assert_eq!(
+        reopened
+            .find_defined_name("Totals")
+            .and_then(|name| name.target()),
+        Some(("Q1 Sales".to_string(), (2, 2), (9, 2)))
+    );
+    remove_temp_outputs(prefix);
+}
+
+#[test]
+fn sheet_protection_and_unlocked_cells_round_trip_through_xlsx() {
+    use rust_xlsxwriter::{Format, Workbook as XlsxWorkbook};
+
+    let prefix = "excel_cli_protection_";
+    remove_temp_outputs(prefix);
+    let source = temp_path(&format!("{prefix}source.xlsx"));
+    let mut xlsx = XlsxWorkbook::new();
+    let unlocked = Format::new().set_unlocked();
+    let sheet = xlsx.add_worksheet();
+    sheet.set_name("Form").unwrap();
+    sheet.protect();
+    sheet.write_string(0, 0, "Name").unwrap();
+    sheet
+        .write_string_with_format(0, 1, "Ada", &unlocked)
+        .unwrap();
+    sheet.write_blank(1, 1, &unlocked).unwrap();
+    xlsx.add_worksheet().set_name("Notes").unwrap();
+    xlsx.save(&source).unwrap();
+
+    let mut workbook = open_workbook(&source, false).unwrap();
+    let form = workbook.get_current_sheet();
+    assert_eq!(form.protection, SheetProtection::Protected);
+    assert!(form.is_cell_locked(1, 1));
+    assert!(!form.is_cell_locked(1, 2));
+    assert!(!form.is_cell_locked(2, 2));
+    assert!(form.is_cell_locked(5, 5));
+    assert_eq!(
+        workbook.get_sheet_by_name("Notes").unwrap().protection,
+        SheetProtection::Unprotected
+    );
+
+    workbook.set_cell_value(1, 2, "Grace".to_string()).unwrap();
+    workbook.save().unwrap();
+    std::fs::remove_file(&source).unwrap();
+
+    let saved_path = find_temp_output(prefix);
+    let reopened = open_workbook(&saved_path, false).unwrap();
+    let form = reopened.get_current_sheet();
+    assert_eq!(form.protection, SheetProtection::Protected);
+    assert_eq!(form.data.cell(1, 2).value, "Grace");
+    assert!(form.is_cell_locked(1, 1));
+    assert!(!form.is_cell_locked(1, 2));
+    assert!(!form.is_cell_locked(2, 2));
+    remove_temp_outputs(prefix);
+}
+
+#[test]
+fn hidden_sheets_round_trip_through_xlsx() {
+    use rust_xlsxwriter::Workbook as XlsxWorkbook;
+
+    let prefix = "excel_cli_hidden_sheets_";
+    remove_temp_outputs(prefix);
+    let source = temp_path(&format!("{prefix}source.xlsx"));
+    let mut xlsx = XlsxWorkbook::new();
+    xlsx.add_worksheet().set_name("Lookup").unwrap();
+    xlsx.add_worksheet()
+        .set_name("Data")
+        .unwrap()
+        .write_string(0, 0, "Total")
+        .unwrap();
+    xlsx.add_worksheet().set_name("Macros").unwrap();
+    xlsx.worksheet_from_index(1).unwrap().set_active(true);
+    xlsx.worksheet_from_index(0).unwrap().set_hidden(true);
+    xlsx.worksheet_from_index(2).unwrap().set_very_hidden(true);
+    xlsx.save(&source).unwrap();
+
+    let mut workbook = open_workbook(&source, true).unwrap();
+    let visibility =
+        |workbook: &Workbook, name: &str| workbook.get_sheet_by_name(name).unwrap().visibility;
+    assert_eq!(visibility(&workbook, "Lookup"), SheetVisibility::Hidden);
+    assert_eq!(visibility(&workbook, "Data"), SheetVisibility::Visible);
+    assert_eq!(visibility(&workbook, "Macros"), SheetVisibility::VeryHidden);
+    assert_eq!(workbook.get_current_sheet_name(), "Data");
+
+    workbook.ensure_sheet_loaded(0, "Lookup").unwrap();
+    workbook.ensure_sheet_loaded(1, "Data").unwrap();
+    assert_eq!(visibility(&workbook, "Lookup"), SheetVisibility::Hidden);
+
+    workbook.set_cell_value(1, 1, "42".to_string()).unwrap();
+    workbook.save().unwrap();
+    std::fs::remove_file(&source).unwrap();
+
+    let saved_path = find_temp_output(prefix);
+    let reopened = open_workbook(&saved_path, false).unwrap();
+    assert_eq!(visibility(&reopened, "Lookup"), SheetVisibility::Hidden);
+    assert_eq!(visibility(&reopened, "Data"), SheetVisibility::Visible);
+    assert_eq!(visibility(&reopened, "Macros"), SheetVisibility::VeryHidden);
+    assert_eq!(reopened.get_current_sheet().data.cell(1, 1).value, "42");
+    let workbook_xml = worksheet_xml(&saved_path, "xl/workbook.xml");
+    assert!(workbook_xml.contains("activeTab=\"1\""));
+    remove_temp_outputs(prefix);
+}
+
+#[test]
+fn tab_colors_round_trip_through_xlsx() {
+    use rust_xlsxwriter::{Color, Workbook as XlsxWorkbook};
+
+    let prefix = "excel_cli_tab_colors_";
+    remove_temp_outputs(prefix);
+    let source = temp_path(&format!("{prefix}source.xlsx"));
+    let mut xlsx = XlsxWorkbook::new();
+    xlsx.add_worksheet()
+        .set_name("Sales")
+        .unwrap()
+        .set_tab_color(Color::RGB(0x00B050));
+    xlsx.add_worksheet()
+        .set_name("Costs")
+        .unwrap()
+        .set_tab_color(Color::Theme(5, 0));
+    // The writer's theme is Office 2007's, whose second accent is 0xC0504D
+    xlsx.add_worksheet().set_name("Notes").unwrap();
+    xlsx.save(&source).unwrap();
+
+    let mut workbook = open_workbook(&source, true).unwrap();
+    let tab_color =
+        |workbook: &Workbook, name: &str| workbook.get_sheet_by_name(name).unwrap().tab_color;
+    assert_eq!(tab_color(&workbook, "Sales"), Some(0x00B050));
+    assert_eq!(tab_color(&workbook, "Costs"), Some(0xC0504D));
+    assert_eq!(tab_color(&workbook, "Notes"), None);
+
+    workbook.ensure_sheet_loaded(1, "Costs").unwrap();
+    assert_eq!(tab_color(&workbook, "Costs"), Some(0xC0504D));
+
+    workbook.set_modified(true);
+    workbook.save().unwrap();
+    std::fs::remove_file(&source).unwrap();
+
+    let saved_path = find_temp_output(prefix);
+    let reopened = open_workbook(&saved_path, false).unwrap();
+    assert_eq!(tab_color(&reopened, "Sales"), Some(0x00B050));
+    assert_eq!(tab_color(&reopened, "Costs"), Some(0xC0504D));
+    assert_eq!(tab_color(&reopened, "Notes"), None);
+    remove_temp_outputs(prefix);
+}
+
+#[test]
+fn cell_fill_and_font_colors_round_trip_through_xlsx() {
+    use rust_xlsxwriter::{Color, Format, Workbook as XlsxWorkbook};
+
+    let prefix = "excel_cli_cell_colors_";
+    remove_temp_outputs(prefix);
+    let source = temp_path(&format!("{prefix}source.xlsx"));
+    let mut xlsx = XlsxWorkbook::new();
+    let done = Format::new().set_background_color(Color::RGB(0x00B050));
+    let late = Format::new().set_font_color(Color::RGB(0xC00000));
+    let sheet = xlsx.add_worksheet();
+    sheet.write_string(0, 0, "Status").unwrap();
+    sheet.write_string_with_format(1, 0, "Done", &done).unwrap();
+    sheet.write_number_with_format(2, 0, 12.5, &late).unwrap();
+    xlsx.save(&source).unwrap();
+
+    let mut workbook = open_workbook(&source, false).unwrap();
+    let data = &workbook.get_current_sheet().data;
+    assert_eq!(
+        (data.cell(1, 1).fill_color, data.cell(1, 1).font_color),
+        (None, None)
+    );
+    assert_eq!(data.cell(2, 1).fill_color, Some(0x00B050));
+    assert_eq!(data.cell(3, 1).font_color, Some(0xC00000));
+
+    workbook
+        .set_cell_value(2, 1, "Shipped".to_string())
+        .unwrap();
+    workbook.save().unwrap();
+    std::fs::remove_file(&source).unwrap();
+
+    let reopened = open_workbook(find_temp_output(prefix), false).unwrap();
+    let data = &reopened.get_current_sheet().data;
+    assert_eq!(data.cell(2, 1).value, "Shipped");
+    assert_eq!(data.cell(2, 1).fill_color, Some(0x00B050));
+    assert_eq!(data.cell(3, 1).font_color, Some(0xC00000));
+    remove_temp_outputs(prefix);
+}
+
+#[test]
+fn cell_value_conditional_formats_round_trip_through_xlsx() {
+    use crate::excel::{CfOperator, ConditionalFormat, EXCEL_MAX_ROWS};
+    use rust_xlsxwriter::{
+        Color, ConditionalFormatCell, ConditionalFormatCellRule, ConditionalFormatFormula, Format,
+        Workbook as XlsxWorkbook,
+    };
+
+    let prefix = "excel_cli_conditional_formats_";
+    remove_temp_outputs(prefix);
+    let source = temp_path(&format!("{prefix}source.xlsx"));
+    let mut xlsx = XlsxWorkbook::new();
+    let sheet = xlsx.add_worksheet();
+    sheet.write_number(0, 0, 150).unwrap();
+    let over = ConditionalFormatCell::new()
+        .set_rule(ConditionalFormatCellRule::GreaterThan(100))
+        .set_format(Format::new().set_background_color(Color::RGB(0xFFC7CE)));
+    sheet.add_conditional_format(1, 0, 9, 0, &over).unwrap();
+    let done = ConditionalFormatCell::new()
+        .set_rule(ConditionalFormatCellRule::EqualTo("Done"))
+        .set_format(Format::new().set_font_color(Color::RGB(0x006100)));
+    sheet
+        .add_conditional_format(0, 2, 1_048_575, 3, &done)
+        .unwrap();
+    // Rules comparing with a formula are not read
+    let formula = ConditionalFormatFormula::new()
+        .set_rule("=$A1>$B1")
+        .set_format(Format::new().set_bold());
+    sheet.add_conditional_format(0, 0, 9, 1, &formula).unwrap();
+    xlsx.save(&source).unwrap();
+
+    let expected = vec![
+        ConditionalFormat {
+            start: (2, 1),
+            end: (10, 1),
+            operator: CfOperator::Greater,
+            value: "100".to_string(),
+            fill: Some(0xFFC7CE),
+            font: None,
+        },
+        ConditionalFormat {
+            start: (1, 3),
+            end: (EXCEL_MAX_ROWS, 4),
+            operator: CfOperator::Equal,
+            value: "Done".to_string(),
+            fill: None,
+            font: Some(0x006100),
+        },
+    ];
+    let mut workbook = open_workbook(&source, false).unwrap();
+    assert_eq!(workbook.get_current_sheet().conditional_formats, expected);
+
+    workbook.set_modified(true);
+    workbook.save().unwrap();
+    std::fs::remove_file(&source).unwrap();
+
+    let reopened = open_workbook(find_temp_output(prefix), false).unwrap();
+    assert_eq!(reopened.get_current_sheet().conditional_formats, expected);
+    remove_temp_outputs(prefix);
+}
+
+#[test]
+fn unchanged_sheets_are_copied_from_the_file_with_what_is_not_read() {
+    use rust_xlsxwriter::{Chart, ChartType, Color, Format, Note, Workbook as XlsxWorkbook};
+
+    let prefix = "excel_cli_kept_sheets_";
+    remove_temp_outputs(prefix);
+    let source = temp_path(&format!("{prefix}source.xlsx"));
+    let mut xlsx = XlsxWorkbook::new();
+    let report = xlsx.add_worksheet().set_name("Report").unwrap();
+    let shaded = Format::new()
+        .set_background_color(Color::RGB(0x00B050))
+        .set_num_format("0.0%");
+    report.write_string(0, 0, "Quarter").unwrap();
+    report.write_string(0, 1, "Growth").unwrap();
+    report.write_string(1, 0, "Q1").unwrap();
+    report
+        .write_number_with_format(1, 1, 0.25, &shaded)
+        .unwrap();
+    report.set_column_width(0, 30).unwrap();
+    report
+        .merge_range(4, 0, 4, 2, "Merged", &Format::new())
+        .unwrap();
+    report
+        .insert_note(0, 0, &Note::new("check").add_author_prefix(false))
+        .unwrap();
+    let mut chart = Chart::new(ChartType::Column);
+    chart.add_series().set_values("Report!$B$2:$B$2");
+    report.insert_chart(6, 0, &chart).unwrap();
+    let data = xlsx.add_worksheet().set_name("Data").unwrap();
+    data.write_string(0, 0, "old").unwrap();
+    xlsx.save(&source).unwrap();
+
+    let mut workbook = open_workbook(&source, true).unwrap();
+    workbook.switch_sheet(1).unwrap();
+    workbook.ensure_sheet_loaded(1, "Data").unwrap();
+    workbook.set_cell_value(1, 1, "new".to_string()).unwrap();
+    workbook.set_cell_comment(1, 1, Some("edited".to_string()));
+    workbook.save().unwrap();
+    // The unchanged sheet was never read
+    assert!(!workbook.get_sheet_by_index(0).unwrap().is_loaded);
+    std::fs::remove_file(&source).unwrap();
+
+    let saved = find_temp_output(prefix);
+    let report_xml = worksheet_xml(&saved, "xl/worksheets/sheet1.xml");
+    assert!(report_xml.contains(r#"<mergeCell ref="A5:C5"/>"#));
+    assert!(report_xml.contains("<drawing r:id="));
+    assert!(report_xml.contains(r#"t="inlineStr"><is><t>Quarter</t></is>"#));
+    assert!(worksheet_xml(&saved, "[Content_Types].xml").contains("/xl/charts/chart1.xml"));
+    worksheet_xml(&saved, "xl/charts/chart1.xml");
+
+    let reopened = open_workbook(&saved, false).unwrap();
+    let report = reopened.get_sheet_by_name("Report").unwrap();
+    assert_eq!(report.data.cell(1, 1).value, "Quarter");
+    assert_eq!(report.data.cell(1, 1).comment.as_deref(), Some("check"));
+    assert_eq!(report.data.cell(2, 2).value, "0.25");
+    assert_eq!(report.data.cell(2, 2).fill_color, Some(0x00B050));
+    assert_eq!(report.data.cell(5, 1).value, "Merged");
+    let data = reopened.get_sheet_by_name("Data").unwrap();
+    assert_eq!(data.data.cell(1, 1).value, "new");
+    assert_eq!(data.data.cell(1, 1).comment.as_deref(), Some("edited"));
+    remove_temp_outputs(prefix);
+}
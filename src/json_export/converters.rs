@@ -2,9 +2,21 @@ use chrono::{Duration, NaiveDate, NaiveDateTime};
 use serde_json::{json, Value};
 
 use crate::excel::{Cell, CellType, DataTypeInfo};
+use crate::json_export::types::{DurationFormat, ExportOptions};
 
-// Convert Excel date number to ISO date string
+// Convert Excel date number to ISO date string. Serials below 1 hold only a time of day and
+// become "HH:MM:SS".
 pub fn excel_date_to_iso_string(excel_date: f64) -> String {
+    if excel_date < 1.0 {
+        let seconds = (excel_date * 24.0 * 60.0 * 60.0).round() as u32 % 86_400;
+        return format!(
+            "{:02}:{:02}:{:02}",
+            seconds / 3600,
+            (seconds % 3600) / 60,
+            seconds % 60
+        );
+    }
+
     let days = if excel_date > 59.0 {
         excel_date - 1.0
     } else {
@@ -38,6 +50,25 @@ pub fn excel_date_to_iso_string(excel_date: f64) -> String {
 
 // Process cell value based on its type
 pub fn process_cell_value(cell: &Cell) -> Value {
+    process_cell_value_with_options(cell, &ExportOptions::default())
+}
+
+// Append the configured offset to strings that carry both a date and a time
+fn zoned_datetime(datetime: String, options: &ExportOptions) -> Value {
+    match options.timezone_offset {
+        Some(offset) if datetime.contains('T') && !has_zone_suffix(&datetime) => {
+            json!(format!("{datetime}{offset}"))
+        }
+        _ => json!(datetime),
+    }
+}
+
+fn has_zone_suffix(datetime: &str) -> bool {
+    let time = datetime.split_once('T').map_or("", |(_, time)| time);
+    time.ends_with('Z') || time.contains('+') || time.contains('-')
+}
+
+pub fn process_cell_value_with_options(cell: &Cell, options: &ExportOptions) -> Value {
     if cell.value.is_empty() {
         return Value::Null;
     }
@@ -54,12 +85,19 @@ pub fn process_cell_value(cell: &Cell) -> Value {
             DataTypeInfo::Int(i) => json!(i),
             DataTypeInfo::DateTime(dt) => {
                 if *dt >= 0.0 {
-                    json!(excel_date_to_iso_string(*dt))
+                    zoned_datetime(excel_date_to_iso_string(*dt), options)
                 } else {
                     json!(cell.value)
                 }
             }
-            DataTypeInfo::DateTimeIso(s) => json!(s),
+            DataTypeInfo::DateTimeIso(s) => zoned_datetime(s.clone(), options),
+            DataTypeInfo::DurationIso(duration) => match options.duration_format {
+                DurationFormat::Iso => json!(duration.to_iso()),
+                DurationFormat::Seconds if duration.total_seconds.fract() == 0.0 => {
+                    json!(duration.total_seconds as i64)
+                }
+                DurationFormat::Seconds => json!(duration.total_seconds),
+            },
             DataTypeInfo::Bool(b) => json!(b),
             DataTypeInfo::Empty => Value::Null,
             _ => json!(cell.value),
@@ -89,7 +127,7 @@ pub fn process_cell_value(cell: &Cell) -> Value {
             CellType::Date => {
                 if let Ok(excel_date) = cell.value.parse::<f64>() {
                     if excel_date >= 0.0 {
-                        json!(excel_date_to_iso_string(excel_date))
+                        zoned_datetime(excel_date_to_iso_string(excel_date), options)
                     } else {
                         json!(cell.value)
                     }
@@ -102,3 +140,93 @@ pub fn process_cell_value(cell: &Cell) -> Value {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{excel_date_to_iso_string, process_cell_value_with_options};
+    use crate::excel::{Cell, CellType, DataTypeInfo, IsoDuration};
+    use crate::json_export::{DurationFormat, ExportOptions};
+
+    fn date_cell(serial: f64) -> Cell {
+        Cell::new_with_type(
+            serial.to_string(),
+            false,
+            CellType::Date,
+            Some(DataTypeInfo::DateTime(serial)),
+        )
+    }
+
+    #[test]
+    fn time_only_serials_export_as_clock_times() {
+        assert_eq!(excel_date_to_iso_string(0.5), "12:00:00");
+        assert_eq!(excel_date_to_iso_string(0.0), "00:00:00");
+        assert_eq!(excel_date_to_iso_string(45943.75), "2025-10-13T18:00:00");
+    }
+
+    #[test]
+    fn timezone_offset_is_appended_to_datetimes_only() {
+        let options = ExportOptions {
+            timezone_offset: ExportOptions::parse_timezone_offset("+0530"),
+            ..ExportOptions::default()
+        };
+
+        assert_eq!(
+            process_cell_value_with_options(&date_cell(45943.75), &options),
+            "2025-10-13T18:00:00+05:30"
+        );
+        assert_eq!(
+            process_cell_value_with_options(&date_cell(45943.0), &options),
+            "2025-10-13"
+        );
+        assert_eq!(
+            process_cell_value_with_options(&date_cell(0.25), &options),
+            "06:00:00"
+        );
+
+        let zoned = Cell::new_with_type(
+            "2025-10-13T18:00:00Z".to_string(),
+            false,
+            CellType::Date,
+            Some(DataTypeInfo::DateTimeIso(
+                "2025-10-13T18:00:00Z".to_string(),
+            )),
+        );
+        assert_eq!(
+            process_cell_value_with_options(&zoned, &options),
+            "2025-10-13T18:00:00Z"
+        );
+    }
+
+    #[test]
+    fn durations_export_as_iso_or_total_seconds() {
+        let duration = IsoDuration::parse("PT2H30M").unwrap();
+        let cell = Cell::new_with_type(
+            duration.to_string(),
+            false,
+            CellType::Text,
+            Some(DataTypeInfo::DurationIso(duration)),
+        );
+        let seconds = ExportOptions {
+            duration_format: DurationFormat::Seconds,
+            ..ExportOptions::default()
+        };
+
+        assert_eq!(cell.value, "2h 30m");
+        assert_eq!(
+            process_cell_value_with_options(&cell, &ExportOptions::default()),
+            "PT2H30M"
+        );
+        assert_eq!(process_cell_value_with_options(&cell, &seconds), 9000);
+    }
+
+    #[test]
+    fn parses_common_offset_spellings() {
+        let parse = |input| ExportOptions::parse_timezone_offset(input).map(|o| o.to_string());
+
+        assert_eq!(parse("+08:00").as_deref(), Some("+08:00"));
+        assert_eq!(parse("-5").as_deref(), Some("-05:00"));
+        assert_eq!(parse("UTC").as_deref(), Some("+00:00"));
+        assert_eq!(parse("08:00"), None);
+        assert_eq!(parse("+25:00"), None);
+    }
+}
@@ -4,12 +4,16 @@ use serde::Serialize;
 
 use std::fs::File;
 use std::io::Write;
+use std::num::NonZeroUsize;
+use std::panic;
 use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
 
 use crate::excel::{Sheet, Workbook};
-use crate::json_export::converters::process_cell_value;
+use crate::json_export::converters::process_cell_value_with_options;
 use crate::json_export::extractors::{extract_horizontal_headers, extract_vertical_headers};
-use crate::json_export::types::{HeaderDirection, OrderedSheetData};
+use crate::json_export::types::{ExportOptions, HeaderDirection, OrderedSheetData};
 
 pub fn serialize_to_json<T: Serialize>(data: &T) -> Result<String> {
     serde_json::to_string_pretty(data).context("Failed to serialize data to JSON")
@@ -32,16 +36,17 @@ pub fn process_sheet_for_json(
     sheet: &Sheet,
     direction: HeaderDirection,
     header_count: usize,
+    options: &ExportOptions,
 ) -> Result<OrderedSheetData> {
     match direction {
         HeaderDirection::Horizontal => {
-            if header_count == 0 || header_count >= sheet.data.len() {
+            if header_count == 0 || header_count > sheet.max_rows {
                 anyhow::bail!("Invalid header rows: {}", header_count);
             }
 
             let headers = extract_horizontal_headers(sheet, header_count)?;
 
-            let row_count = sheet.data.len().saturating_sub(header_count + 1);
+            let row_count = sheet.max_rows.saturating_sub(header_count);
             let mut sheet_data = Vec::with_capacity(row_count);
 
             let mut ordered_headers: Vec<(usize, &String)> = headers
@@ -51,17 +56,14 @@ pub fn process_sheet_for_json(
             ordered_headers.sort_by_key(|(col_idx, _)| *col_idx);
 
             // Process each data row
-            for row_idx in (header_count + 1)..sheet.data.len() {
+            for row_idx in (header_count + 1)..=sheet.max_rows {
                 let mut row_data = IndexMap::with_capacity(ordered_headers.len());
 
                 for (col_idx, header) in &ordered_headers {
-                    if row_idx < sheet.data.len() && *col_idx < sheet.data[row_idx].len() {
-                        let cell = &sheet.data[row_idx][*col_idx];
-
-                        if !header.is_empty() {
-                            let json_value = process_cell_value(cell);
-                            row_data.insert((*header).clone(), json_value);
-                        }
+                    if !header.is_empty() {
+                        let cell = sheet.data.cell(row_idx, *col_idx);
+                        let json_value = process_cell_value_with_options(cell, options);
+                        row_data.insert((*header).clone(), json_value);
                     }
                 }
 
@@ -73,13 +75,13 @@ pub fn process_sheet_for_json(
             Ok(sheet_data)
         }
         HeaderDirection::Vertical => {
-            if header_count == 0 || header_count >= sheet.data[0].len() {
+            if header_count == 0 || header_count > sheet.max_cols {
                 anyhow::bail!("Invalid header columns: {}", header_count);
             }
 
             let headers = extract_vertical_headers(sheet, header_count)?;
 
-            let col_count = sheet.data[0].len().saturating_sub(header_count + 1);
+            let col_count = sheet.max_cols.saturating_sub(header_count);
             let mut sheet_data = Vec::with_capacity(col_count);
 
             let mut ordered_headers: Vec<(usize, &String)> = headers
@@ -89,17 +91,14 @@ pub fn process_sheet_for_json(
             ordered_headers.sort_by_key(|(row_idx, _)| *row_idx);
 
             // Process each data column
-            for col_idx in (header_count + 1)..sheet.data[0].len() {
+            for col_idx in (header_count + 1)..=sheet.max_cols {
                 let mut obj = IndexMap::with_capacity(ordered_headers.len());
 
                 for (row_idx, header) in &ordered_headers {
-                    if *row_idx < sheet.data.len() && col_idx < sheet.data[*row_idx].len() {
-                        let cell = &sheet.data[*row_idx][col_idx];
-
-                        if !header.is_empty() {
-                            let json_value = process_cell_value(cell);
-                            obj.insert((*header).clone(), json_value);
-                        }
+                    if !header.is_empty() {
+                        let cell = sheet.data.cell(*row_idx, col_idx);
+                        let json_value = process_cell_value_with_options(cell, options);
+                        obj.insert((*header).clone(), json_value);
                     }
                 }
 
@@ -118,35 +117,55 @@ pub fn export_json(
     sheet: &Sheet,
     direction: HeaderDirection,
     header_count: usize,
+    options: &ExportOptions,
     path: &Path,
 ) -> Result<()> {
-    let sheet_data = process_sheet_for_json(sheet, direction, header_count)?;
+    let sheet_data = process_sheet_for_json(sheet, direction, header_count, options)?;
     write_json_to_file(&sheet_data, path)
 }
 
+// Converts every sheet, several at a time as each is independent of the others
 pub fn generate_all_sheets_json(
     workbook: &Workbook,
     direction: HeaderDirection,
     header_count: usize,
+    options: &ExportOptions,
 ) -> Result<IndexMap<String, OrderedSheetData>> {
-    let sheet_names = workbook.get_sheet_names();
-
-    let mut all_sheets = IndexMap::with_capacity(sheet_names.len());
-
-    let current_sheet_index = workbook.get_current_sheet_index();
-
-    // Process each sheet
-    for (index, sheet_name) in sheet_names.iter().enumerate() {
-        let sheet_data = if index == current_sheet_index {
-            process_sheet_for_json(workbook.get_current_sheet(), direction, header_count)?
-        } else {
-            // Need to switch sheets - create a clone and process
-            let mut wb_clone = workbook.clone();
-            wb_clone.switch_sheet(index)?;
-            process_sheet_for_json(wb_clone.get_current_sheet(), direction, header_count)?
-        };
-
-        all_sheets.insert(sheet_name.clone(), sheet_data);
+    let sheets = workbook.sheets();
+    let next_sheet = AtomicUsize::new(0);
+    // Each worker takes the next sheet left until none are
+    let convert = || {
+        let mut converted = Vec::new();
+        loop {
+            let index = next_sheet.fetch_add(1, Ordering::Relaxed);
+            let Some(sheet) = sheets.get(index) else {
+                break converted;
+            };
+            let sheet_data = process_sheet_for_json(sheet, direction, header_count, options);
+            converted.push((index, sheet_data));
+        }
+    };
+
+    let num_workers = thread::available_parallelism()
+        .map_or(4, NonZeroUsize::get)
+        .min(sheets.len());
+    let mut converted = if num_workers > 1 {
+        thread::scope(|scope| {
+            let workers: Vec<_> = (0..num_workers).map(|_| scope.spawn(convert)).collect();
+            workers
+                .into_iter()
+                .flat_map(|worker| worker.join().unwrap_or_else(|e| panic::resume_unwind(e)))
+                .collect()
+        })
+    } else {
+        convert()
+    };
+
+    // Sheets keep the workbook's order, and the first one failing is the error reported
+    converted.sort_unstable_by_key(|(index, _)| *index);
+    let mut all_sheets = IndexMap::with_capacity(sheets.len());
+    for (index, sheet_data) in converted {
+        all_sheets.insert(sheets[index].name.clone(), sheet_data?);
     }
 
     Ok(all_sheets)
@@ -157,9 +176,92 @@ pub fn export_all_sheets_json(
     workbook: &Workbook,
     direction: HeaderDirection,
     header_count: usize,
+    options: &ExportOptions,
     path: &Path,
 ) -> Result<()> {
-    let all_sheets = generate_all_sheets_json(workbook, direction, header_count)?;
+    let all_sheets = generate_all_sheets_json(workbook, direction, header_count, options)?;
 
     write_json_to_file(&all_sheets, path)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::generate_all_sheets_json;
+    use crate::excel::{Cell, Sheet, Workbook};
+    use crate::json_export::{ExportOptions, HeaderDirection};
+
+    fn sheet(name: &str, rows: &[[&str; 2]]) -> Sheet {
+        let mut sheet = Sheet::blank(name.to_string());
+        for (row, values) in rows.iter().enumerate() {
+            for (col, value) in values.iter().enumerate() {
+                sheet
+                    .data
+                    .set(row + 1, col + 1, Cell::new((*value).to_string(), false));
+            }
+        }
+        sheet.max_rows = rows.len();
+        sheet.max_cols = 2;
+        sheet
+    }
+
+    #[test]
+    fn every_sheet_is_exported_under_its_name_in_tab_order() {
+        let mut workbook = Workbook::from_sheets_for_test(vec![
+            sheet("People", &[["Name", "Age"], ["Ada", "36"]]),
+            sheet(
+                "Cities",
+                &[["City", "Country"], ["Oslo", "NO"], ["Lima", "PE"]],
+            ),
+        ]);
+        workbook.switch_sheet(1).unwrap();
+
+        let all = generate_all_sheets_json(
+            &workbook,
+            HeaderDirection::Horizontal,
+            1,
+            &ExportOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(all.keys().collect::<Vec<_>>(), ["People", "Cities"]);
+        assert_eq!(all["People"][0]["Name"], "Ada");
+        assert_eq!(all["Cities"].len(), 2);
+        assert_eq!(all["Cities"][1]["Country"], "PE");
+        assert_eq!(workbook.get_current_sheet_index(), 1);
+    }
+
+    #[test]
+    fn sheets_converted_at_once_keep_their_order_and_a_failing_one_fails_the_export() {
+        let names: Vec<String> = (1..=24).map(|n| format!("Month{n}")).collect();
+        let mut sheets: Vec<Sheet> = names
+            .iter()
+            .map(|name| sheet(name, &[["Name", "Total"], [name, "1"]]))
+            .collect();
+        let workbook = Workbook::from_sheets_for_test(sheets.clone());
+
+        let all = generate_all_sheets_json(
+            &workbook,
+            HeaderDirection::Horizontal,
+            1,
+            &ExportOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            all.keys().collect::<Vec<_>>(),
+            names.iter().collect::<Vec<_>>()
+        );
+        assert_eq!(all["Month17"][0]["Name"], "Month17");
+
+        // A sheet too short for its header row
+        sheets[9].max_rows = 0;
+        let workbook = Workbook::from_sheets_for_test(sheets);
+        let error = generate_all_sheets_json(
+            &workbook,
+            HeaderDirection::Horizontal,
+            1,
+            &ExportOptions::default(),
+        )
+        .unwrap_err();
+        assert_eq!(error.to_string(), "Invalid header rows: 1");
+    }
+}
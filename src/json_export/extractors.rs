@@ -10,12 +10,12 @@ pub fn extract_horizontal_headers(
     let mut headers = HashMap::new();
     let mut last_values_by_row: HashMap<usize, String> = HashMap::new();
 
-    for col_idx in 1..sheet.data[0].len() {
+    for col_idx in 1..=sheet.max_cols {
         let mut header_parts = Vec::new();
 
         for row_idx in 1..=header_rows {
-            if row_idx < sheet.data.len() && col_idx < sheet.data[row_idx].len() {
-                let cell_value = &sheet.data[row_idx][col_idx].value;
+            if row_idx <= sheet.max_rows {
+                let cell_value = &sheet.data.cell(row_idx, col_idx).value;
 
                 if cell_value.is_empty() {
                     if let Some(last_value) = last_values_by_row.get(&row_idx) {
@@ -52,12 +52,12 @@ pub fn extract_vertical_headers(
     let mut headers = HashMap::new();
     let mut last_values_by_col: HashMap<usize, String> = HashMap::new();
 
-    for row_idx in 1..sheet.data.len() {
+    for row_idx in 1..=sheet.max_rows {
         let mut header_parts = Vec::new();
 
         for col_idx in 1..=header_cols {
-            if col_idx < sheet.data[0].len() && row_idx < sheet.data.len() {
-                let cell_value = &sheet.data[row_idx][col_idx].value;
+            if col_idx <= sheet.max_cols {
+                let cell_value = &sheet.data.cell(row_idx, col_idx).value;
 
                 if cell_value.is_empty() {
                     if let Some(last_value) = last_values_by_col.get(&col_idx) {
@@ -3,9 +3,11 @@ mod exporters;
 mod extractors;
 mod types;
 
-pub use converters::process_cell_value;
+pub use converters::{
+    excel_date_to_iso_string, process_cell_value, process_cell_value_with_options,
+};
 pub use exporters::{
     export_all_sheets_json, export_json, generate_all_sheets_json, process_sheet_for_json,
     serialize_to_json,
 };
-pub use types::{HeaderDirection, OrderedSheetData};
+pub use types::{DurationFormat, ExportOptions, HeaderDirection, OrderedSheetData};
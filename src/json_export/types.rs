@@ -1,3 +1,4 @@
+use chrono::FixedOffset;
 use indexmap::IndexMap;
 use serde_json::Value;
 use std::str::FromStr;
@@ -21,3 +22,80 @@ impl FromStr for HeaderDirection {
 }
 
 pub type OrderedSheetData = Vec<IndexMap<String, Value>>;
+
+/// How duration cells are written to JSON
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum DurationFormat {
+    /// ISO-8601 duration strings such as `PT2H30M`
+    #[default]
+    Iso,
+    /// Total seconds as a number
+    Seconds,
+}
+
+impl DurationFormat {
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            DurationFormat::Iso => "iso",
+            DurationFormat::Seconds => "seconds",
+        }
+    }
+}
+
+impl FromStr for DurationFormat {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "iso" => Ok(DurationFormat::Iso),
+            "seconds" | "secs" | "s" => Ok(DurationFormat::Seconds),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Options controlling how cell values are written to JSON
+#[derive(Debug, Clone, Default)]
+pub struct ExportOptions {
+    /// Offset appended to datetime strings, for consumers that require zoned timestamps
+    pub timezone_offset: Option<FixedOffset>,
+    pub duration_format: DurationFormat,
+}
+
+impl ExportOptions {
+    /// Parses offsets such as `+08:00`, `-0530`, `+8`, `Z` or `UTC`
+    #[must_use]
+    pub fn parse_timezone_offset(input: &str) -> Option<FixedOffset> {
+        let input = input.trim();
+        if input.eq_ignore_ascii_case("z") || input.eq_ignore_ascii_case("utc") {
+            return FixedOffset::east_opt(0);
+        }
+
+        let (sign, rest) = match input.chars().next()? {
+            '+' => (1, &input[1..]),
+            '-' => (-1, &input[1..]),
+            _ => return None,
+        };
+        let (hours, minutes) = match rest.split_once(':') {
+            Some((hours, minutes)) => (hours, minutes),
+            None if rest.len() > 2 => rest.split_at(rest.len() - 2),
+            None => (rest, "0"),
+        };
+        if hours.is_empty()
+            || !hours
+                .chars()
+                .chain(minutes.chars())
+                .all(|c| c.is_ascii_digit())
+        {
+            return None;
+        }
+
+        let hours: i32 = hours.parse().ok()?;
+        let minutes: i32 = minutes.parse().ok()?;
+        if hours > 14 || minutes > 59 {
+            return None;
+        }
+        FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+    }
+}
@@ -1,8 +1,14 @@
+#[cfg(feature = "tui")]
 pub mod actions;
+#[cfg(feature = "tui")]
 pub mod app;
+pub mod build_info;
 pub mod cli;
+#[cfg(feature = "tui")]
 pub mod commands;
+pub mod config;
 pub mod excel;
 pub mod json_export;
+#[cfg(feature = "tui")]
 pub mod ui;
 pub mod utils;
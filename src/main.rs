@@ -24,6 +24,11 @@ fn main() {
         },
     };
 
+    if cli.build_info {
+        print!("{}", excel_cli::build_info::report());
+        std::process::exit(0);
+    }
+
     let result = dispatch::dispatch(cli);
 
     match result {
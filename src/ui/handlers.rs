@@ -1,15 +1,32 @@
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
-use tui_textarea::{Input, Key, TextArea};
+use tui_textarea::{Input, Key};
 
-use crate::app::{help_reference_line_count, AppState, InputMode};
+use crate::ui::keymap::{CommandAction, NormalAction};
+
+use crate::app::{
+    help_reference_line_count, AppState, BufferRequest, InputMode, MarkAction, SplitDirection,
+    ViewPosition,
+};
 
 pub fn handle_key_event(app_state: &mut AppState, key: KeyEvent) {
     match app_state.input_mode {
+        InputMode::Normal if app_state.window_key_pending => handle_window_key(app_state, key.code),
         InputMode::Normal => {
+            // Shift with a movement key grows a selection from the cursor, spreadsheet style
+            if key.modifiers.contains(KeyModifiers::SHIFT) && is_selection_movement_key(key.code) {
+                app_state.anchor_selection();
+            }
+
             if key.modifiers.contains(KeyModifiers::CONTROL)
                 || key.modifiers.contains(KeyModifiers::SUPER)
             {
-                handle_ctrl_key(app_state, key.code);
+                let repeat = app_state.pending_count.take().unwrap_or(1) as isize;
+                app_state.pending_mark = None;
+                handle_ctrl_key(app_state, key.code, repeat);
+            } else if key.modifiers.contains(KeyModifiers::ALT) {
+                app_state.pending_count = None;
+                app_state.pending_mark = None;
+                handle_alt_key(app_state, key.code);
             } else {
                 handle_normal_mode(app_state, key.code);
             }
@@ -21,11 +38,51 @@ pub fn handle_key_event(app_state: &mut AppState, key: KeyEvent) {
         InputMode::SearchBackward => handle_search_mode(app_state, key.code),
         InputMode::Help => handle_help_mode(app_state, key.code),
         InputMode::LazyLoading => handle_lazy_loading_mode(app_state, key.code),
+        InputMode::FixedWidthImport => handle_fixed_width_import_mode(app_state, key.code),
+        InputMode::ConfirmPaste => handle_confirm_paste_mode(app_state, key.code),
+        InputMode::Onboarding => handle_onboarding_mode(app_state, key.code),
+        InputMode::Pager => handle_pager_mode(app_state, key.code),
+        InputMode::Results => handle_results_mode(app_state, key.code),
+    }
+}
+
+fn is_selection_movement_key(key_code: KeyCode) -> bool {
+    matches!(
+        key_code,
+        KeyCode::Left | KeyCode::Right | KeyCode::Up | KeyCode::Down | KeyCode::Home | KeyCode::End
+    )
+}
+
+/// Handles text delivered through the terminal's bracketed paste
+pub fn handle_paste_event(app_state: &mut AppState, text: &str) {
+    match app_state.input_mode {
+        InputMode::Normal => app_state.paste_text(text),
+        InputMode::Editing => {
+            app_state.text_area.insert_str(text);
+        }
+        InputMode::Command | InputMode::CommandInLazyLoading => {
+            for c in text.lines().next().unwrap_or_default().chars() {
+                app_state.add_char_to_input(c);
+            }
+        }
+        InputMode::SearchForward | InputMode::SearchBackward => {
+            app_state
+                .text_area
+                .insert_str(text.lines().next().unwrap_or_default());
+            app_state.search_query_changed();
+        }
+        InputMode::Help
+        | InputMode::LazyLoading
+        | InputMode::FixedWidthImport
+        | InputMode::ConfirmPaste
+        | InputMode::Onboarding
+        | InputMode::Pager
+        | InputMode::Results => {}
     }
 }
 
 // Handles both Ctrl+key and Command+key (on Mac) combinations
-fn handle_ctrl_key(app_state: &mut AppState, key_code: KeyCode) {
+fn handle_ctrl_key(app_state: &mut AppState, key_code: KeyCode, repeat: isize) {
     match key_code {
         KeyCode::Left => {
             app_state.jump_to_prev_non_empty_cell_left();
@@ -39,28 +96,64 @@ fn handle_ctrl_key(app_state: &mut AppState, key_code: KeyCode) {
         KeyCode::Down => {
             app_state.jump_to_prev_non_empty_cell_down();
         }
+        KeyCode::Home => app_state.jump_to_first_cell(),
+        KeyCode::End => app_state.jump_to_last_used_cell(),
         KeyCode::Char('r') => {
             if let Err(e) = app_state.redo() {
-                app_state.add_notification(format!("Redo failed: {e}"));
+                app_state.add_error(format!("Redo failed: {e}"));
             }
         }
+        KeyCode::Char('w') => app_state.window_key_pending = true,
+        KeyCode::Char('d') => app_state.scroll_half_pages(repeat),
+        KeyCode::Char('u') => app_state.scroll_half_pages(-repeat),
+        KeyCode::Char('f') => app_state.scroll_pages(repeat),
+        KeyCode::Char('b') => app_state.scroll_pages(-repeat),
         _ => {}
     }
 }
 
-fn handle_command_mode(app_state: &mut AppState, key_code: KeyCode) {
+/// Handles the key after `Ctrl+W`, with or without Ctrl held
+fn handle_window_key(app_state: &mut AppState, key_code: KeyCode) {
+    app_state.window_key_pending = false;
     match key_code {
-        KeyCode::Enter => app_state.execute_command(),
-        KeyCode::Esc => app_state.cancel_input(),
-        KeyCode::Backspace => app_state.delete_char_from_input(),
-        KeyCode::Char(c) => app_state.add_char_to_input(c),
+        KeyCode::Char('w' | 'W' | 'p') => app_state.focus_other_pane(),
+        KeyCode::Char('h' | 'k') | KeyCode::Left | KeyCode::Up => app_state.focus_pane(false),
+        KeyCode::Char('j' | 'l') | KeyCode::Right | KeyCode::Down => app_state.focus_pane(true),
+        KeyCode::Char('c' | 'q') => app_state.close_pane(),
+        KeyCode::Char('o') => app_state.close_other_pane(),
+        KeyCode::Char('s') => app_state.split_view(SplitDirection::Horizontal, None),
+        KeyCode::Char('v') => app_state.split_view(SplitDirection::Vertical, None),
         _ => {}
     }
 }
 
-fn handle_command_in_lazy_loading_mode(app_state: &mut AppState, key_code: KeyCode) {
+fn handle_alt_key(app_state: &mut AppState, key_code: KeyCode) {
     match key_code {
-        KeyCode::Enter => {
+        KeyCode::Char('j') | KeyCode::Down => app_state.move_rows(1),
+        KeyCode::Char('k') | KeyCode::Up => app_state.move_rows(-1),
+        KeyCode::Char('h') | KeyCode::Left => app_state.move_columns(-1),
+        KeyCode::Char('l') | KeyCode::Right => app_state.move_columns(1),
+        _ => {}
+    }
+}
+
+fn handle_command_mode(app_state: &mut AppState, key_code: KeyCode) {
+    match app_state.keymap.command(key_code) {
+        Some(CommandAction::Execute) => app_state.execute_command(),
+        Some(CommandAction::Cancel) => app_state.cancel_input(),
+        Some(CommandAction::DeleteChar) => app_state.delete_char_from_input(),
+        None => match key_code {
+            KeyCode::Char(c) => app_state.add_char_to_input(c),
+            KeyCode::Up => app_state.recall_command_history(true),
+            KeyCode::Down => app_state.recall_command_history(false),
+            _ => {}
+        },
+    }
+}
+
+fn handle_command_in_lazy_loading_mode(app_state: &mut AppState, key_code: KeyCode) {
+    match app_state.keymap.command(key_code) {
+        Some(CommandAction::Execute) => {
             // Execute the command but stay in lazy loading mode if needed
             let current_index = app_state.workbook.get_current_sheet_index();
             let is_sheet_loaded = app_state.workbook.is_sheet_loaded(current_index);
@@ -78,177 +171,221 @@ fn handle_command_in_lazy_loading_mode(app_state: &mut AppState, key_code: KeyCo
                 app_state.input_mode = InputMode::LazyLoading;
             }
         }
-        KeyCode::Esc => {
+        Some(CommandAction::Cancel) => {
             // Return to LazyLoading mode
             app_state.input_mode = InputMode::LazyLoading;
             app_state.input_buffer = String::new();
         }
-        KeyCode::Backspace => app_state.delete_char_from_input(),
-        KeyCode::Char(c) => app_state.add_char_to_input(c),
-        _ => {}
+        Some(CommandAction::DeleteChar) => app_state.delete_char_from_input(),
+        None => match key_code {
+            KeyCode::Char(c) => app_state.add_char_to_input(c),
+            KeyCode::Up => app_state.recall_command_history(true),
+            KeyCode::Down => app_state.recall_command_history(false),
+            _ => {}
+        },
     }
 }
 
 fn handle_normal_mode(app_state: &mut AppState, key_code: KeyCode) {
-    match key_code {
-        KeyCode::Enter => {
-            app_state.g_pressed = false;
-
-            // Check if the current sheet is loaded
-            let index = app_state.workbook.get_current_sheet_index();
-            let sheet_name = app_state.workbook.get_current_sheet_name();
-
-            if app_state.workbook.is_lazy_loading() && !app_state.workbook.is_sheet_loaded(index) {
-                // If the sheet is not loaded, load it first
-                if let Err(e) = app_state.workbook.ensure_sheet_loaded(index, &sheet_name) {
-                    app_state.add_notification(format!("Failed to load sheet: {e}"));
-                } else {
-                    app_state.start_editing();
-                }
-            } else {
-                app_state.start_editing();
-            }
+    if let Some(action) = app_state.pending_mark.take() {
+        app_state.pending_count = None;
+        if let KeyCode::Char(key) = key_code {
+            app_state.apply_mark_key(action, key);
         }
-        KeyCode::Char('h') => {
-            app_state.g_pressed = false;
-            app_state.move_cursor(0, -1);
+        return;
+    }
+
+    if std::mem::take(&mut app_state.register_key_pending) {
+        app_state.pending_count = None;
+        if let KeyCode::Char(digit @ '0'..='9') = key_code {
+            app_state.pending_register = digit.to_digit(10).map(|digit| digit as usize);
         }
-        KeyCode::Char('j') => {
+        return;
+    }
+    let register = app_state.pending_register.take();
+
+    // Digits build up a count for the next motion; 0 alone still jumps to the first column
+    if let KeyCode::Char(digit @ '0'..='9') = key_code {
+        if digit != '0' || app_state.pending_count.is_some() {
             app_state.g_pressed = false;
-            app_state.move_cursor(1, 0);
+            app_state.push_count_digit(digit.to_digit(10).unwrap_or(0));
+            return;
         }
-        KeyCode::Char('k') => {
-            app_state.g_pressed = false;
-            app_state.move_cursor(-1, 0);
+    }
+    let count = app_state.pending_count.take();
+
+    let after_y = std::mem::take(&mut app_state.y_pressed);
+    if std::mem::take(&mut app_state.z_pressed) {
+        app_state.g_pressed = false;
+        match key_code {
+            KeyCode::Char('z' | '.') => app_state.recenter_view(ViewPosition::Center),
+            KeyCode::Char('t') | KeyCode::Enter => app_state.recenter_view(ViewPosition::Top),
+            KeyCode::Char('b' | '-') => app_state.recenter_view(ViewPosition::Bottom),
+            _ => {}
         }
-        KeyCode::Char('l') => {
-            app_state.g_pressed = false;
-            app_state.move_cursor(0, 1);
+        return;
+    }
+    if std::mem::take(&mut app_state.g_pressed) && handle_g_key(app_state, key_code, count) {
+        return;
+    }
+    if after_y && handle_y_key(app_state, key_code, count) {
+        return;
+    }
+
+    if let Some(action) = app_state.keymap.normal(key_code) {
+        run_normal_action(app_state, action, count, register);
+    }
+}
+
+/// Handles the key completing `gg`, `gt`, `gT`, `g;`, `gr` or `gx`. Other keys after `g` are
+/// handled as usual
+fn handle_g_key(app_state: &mut AppState, key_code: KeyCode, count: Option<usize>) -> bool {
+    match key_code {
+        KeyCode::Char('g') => match count {
+            Some(row) => app_state.jump_to_row(row),
+            None => app_state.jump_to_first_row(),
+        },
+        KeyCode::Char('t') => {
+            app_state.buffer_request = Some(match count {
+                Some(number) => BufferRequest::Goto(number - 1),
+                None => BufferRequest::Next,
+            });
         }
-        KeyCode::Char('u') => {
-            app_state.g_pressed = false;
-            if let Err(e) = app_state.undo() {
-                app_state.add_notification(format!("Undo failed: {e}"));
+        KeyCode::Char('T') => app_state.buffer_request = Some(BufferRequest::Prev),
+        KeyCode::Char(';') => app_state.jump_to_last_edit(),
+        KeyCode::Char('r') => app_state.toggle_results(),
+        KeyCode::Char('x') => app_state.open_link_under_cursor(),
+        _ => return false,
+    }
+    true
+}
+
+/// Handles the key completing `yy` or `yc`. Other keys after `y` are handled as usual
+fn handle_y_key(app_state: &mut AppState, key_code: KeyCode, count: Option<usize>) -> bool {
+    match key_code {
+        KeyCode::Char('y') => app_state.yank_rows(count.unwrap_or(1)),
+        KeyCode::Char('c') => app_state.yank_columns(count.unwrap_or(1)),
+        _ => return false,
+    }
+    true
+}
+
+fn run_normal_action(
+    app_state: &mut AppState,
+    action: NormalAction,
+    count: Option<usize>,
+    register: Option<usize>,
+) {
+    let repeat = count.unwrap_or(1) as isize;
+    match action {
+        NormalAction::Open => {
+            if ensure_current_sheet_loaded(app_state) && !app_state.follow_sheet_link() {
+                app_state.start_editing();
             }
         }
-        KeyCode::Char('=' | '+') => {
-            app_state.g_pressed = false;
-            app_state.adjust_info_panel_height(1);
-        }
-        KeyCode::Char('-') => {
-            app_state.g_pressed = false;
-            app_state.adjust_info_panel_height(-1);
-        }
-        KeyCode::Char('[') => {
-            app_state.g_pressed = false;
-            if let Err(e) = app_state.prev_sheet() {
-                app_state.add_notification(format!("Failed to switch to previous sheet: {e}"));
+        NormalAction::Edit => {
+            if ensure_current_sheet_loaded(app_state) {
+                app_state.start_editing();
             }
         }
-        KeyCode::Char(']') => {
-            app_state.g_pressed = false;
-            if let Err(e) = app_state.next_sheet() {
-                app_state.add_notification(format!("Failed to switch to next sheet: {e}"));
+        NormalAction::MoveLeft => app_state.move_cursor(0, -repeat),
+        NormalAction::MoveDown => app_state.move_cursor(repeat, 0),
+        NormalAction::MoveUp => app_state.move_cursor(-repeat, 0),
+        NormalAction::MoveRight => app_state.move_cursor(0, repeat),
+        NormalAction::Undo => {
+            if let Err(e) = app_state.undo() {
+                app_state.add_error(format!("Undo failed: {e}"));
             }
         }
-        KeyCode::Char('g') => {
-            if app_state.g_pressed {
-                app_state.jump_to_first_row();
-                app_state.g_pressed = false;
-            } else {
-                app_state.g_pressed = true;
+        NormalAction::GrowInfoPanel => app_state.adjust_info_panel_size(1),
+        NormalAction::ShrinkInfoPanel => app_state.adjust_info_panel_size(-1),
+        NormalAction::ToggleInfoPanel => app_state.toggle_info_panel(),
+        NormalAction::ShowCell => app_state.show_cell_content(),
+        NormalAction::PrevSheet => {
+            if let Err(e) = app_state.step_sheets(count.unwrap_or(1), false) {
+                app_state.add_error(format!("Failed to switch to previous sheet: {e}"));
             }
         }
-        KeyCode::Char('G') => {
-            app_state.g_pressed = false;
-            app_state.jump_to_last_row();
-        }
-        KeyCode::Char('0') => {
-            app_state.g_pressed = false;
-            app_state.jump_to_first_column();
+        NormalAction::NextSheet => {
+            if let Err(e) = app_state.step_sheets(count.unwrap_or(1), true) {
+                app_state.add_error(format!("Failed to switch to next sheet: {e}"));
+            }
         }
-        KeyCode::Char('^') => {
-            app_state.g_pressed = false;
-            app_state.jump_to_first_non_empty_column();
+        NormalAction::ScrollTabsLeft => app_state.scroll_tabs(-repeat),
+        NormalAction::ScrollTabsRight => app_state.scroll_tabs(repeat),
+        NormalAction::GPrefix => {
+            // Keep the count for the key that completes the `g` command, as in 5gg
+            app_state.pending_count = count;
+            app_state.g_pressed = true;
         }
-        KeyCode::Char('$') => {
-            app_state.g_pressed = false;
-            app_state.jump_to_last_column();
-        }
-        KeyCode::Char('y') => {
-            app_state.g_pressed = false;
+        NormalAction::ZPrefix => app_state.z_pressed = true,
+        NormalAction::LastRow => match count {
+            Some(row) => app_state.jump_to_row(row),
+            None => app_state.jump_to_last_row(),
+        },
+        NormalAction::FirstColumn => app_state.jump_to_first_column(),
+        NormalAction::FirstNonEmptyColumn => app_state.jump_to_first_non_empty_column(),
+        NormalAction::LastColumn => app_state.jump_to_last_column(),
+        NormalAction::SetMark => app_state.pending_mark = Some(MarkAction::Set),
+        NormalAction::JumpToMark => app_state.pending_mark = Some(MarkAction::Jump),
+        NormalAction::Yank => {
+            // Without a selection, keep the count for the key that completes yy or yc, as in 3yy
+            app_state.y_pressed = app_state.selection_anchor.is_none();
+            if app_state.y_pressed {
+                app_state.pending_count = count;
+            }
             app_state.copy_cell();
         }
-        KeyCode::Char('d') => {
-            app_state.g_pressed = false;
+        NormalAction::Cut => {
             if let Err(e) = app_state.cut_cell() {
-                app_state.add_notification(format!("Cut failed: {e}"));
+                app_state.add_error(format!("Cut failed: {e}"));
             }
         }
-        KeyCode::Char('p') => {
-            app_state.g_pressed = false;
-            if let Err(e) = app_state.paste_cell() {
-                app_state.add_notification(format!("Paste failed: {e}"));
+        NormalAction::Paste => {
+            if let Some(index) = register {
+                app_state.paste_register(index);
+            } else if let Err(e) = app_state.paste_cell() {
+                app_state.add_error(format!("Paste failed: {e}"));
             }
         }
-        KeyCode::Char(':') => {
-            app_state.g_pressed = false;
-            app_state.start_command_mode();
-        }
-        KeyCode::Char('/') => {
-            app_state.g_pressed = false;
-            app_state.start_search_forward();
-        }
-        KeyCode::Char('?') => {
-            app_state.g_pressed = false;
-            app_state.start_search_backward();
-        }
-        KeyCode::Char('n') => {
-            app_state.g_pressed = false;
-            if !app_state.search_results.is_empty() {
-                app_state.jump_to_next_search_result();
-            } else if !app_state.search_query.is_empty() {
+        NormalAction::Register => app_state.register_key_pending = true,
+        NormalAction::Visual => app_state.toggle_visual_selection(),
+        NormalAction::ClearSelection => app_state.clear_selection(),
+        NormalAction::Command => app_state.start_command_mode(),
+        NormalAction::SearchForward => app_state.start_search_forward(),
+        NormalAction::SearchBackward => app_state.start_search_backward(),
+        NormalAction::NextMatch | NormalAction::PrevMatch => {
+            if app_state.search_results.is_empty() && !app_state.search_query.is_empty() {
                 // Re-run the last search if we have a query but no results
                 app_state.search_results = app_state.find_all_matches(&app_state.search_query);
-                if !app_state.search_results.is_empty() {
-                    app_state.jump_to_next_search_result();
-                }
             }
-        }
-
-        KeyCode::Char('N') => {
-            app_state.g_pressed = false;
             if !app_state.search_results.is_empty() {
-                app_state.jump_to_prev_search_result();
-            } else if !app_state.search_query.is_empty() {
-                // Re-run the last search if we have a query but no results
-                app_state.search_results = app_state.find_all_matches(&app_state.search_query);
-                if !app_state.search_results.is_empty() {
-                    app_state.jump_to_prev_search_result();
+                for _ in 0..repeat {
+                    if action == NormalAction::NextMatch {
+                        app_state.jump_to_next_search_result();
+                    } else {
+                        app_state.jump_to_prev_search_result();
+                    }
                 }
             }
         }
+        NormalAction::PageUp => app_state.scroll_pages(-repeat),
+        NormalAction::PageDown => app_state.scroll_pages(repeat),
+    }
+}
 
-        KeyCode::Left => {
-            app_state.g_pressed = false;
-            app_state.move_cursor(0, -1);
-        }
-        KeyCode::Right => {
-            app_state.g_pressed = false;
-            app_state.move_cursor(0, 1);
-        }
-        KeyCode::Up => {
-            app_state.g_pressed = false;
-            app_state.move_cursor(-1, 0);
-        }
-        KeyCode::Down => {
-            app_state.g_pressed = false;
-            app_state.move_cursor(1, 0);
-        }
-        _ => {
-            app_state.g_pressed = false;
+/// Loads the current sheet first if lazy loading left it unread
+fn ensure_current_sheet_loaded(app_state: &mut AppState) -> bool {
+    let index = app_state.workbook.get_current_sheet_index();
+    let sheet_name = app_state.workbook.get_current_sheet_name();
+
+    if app_state.workbook.is_lazy_loading() && !app_state.workbook.is_sheet_loaded(index) {
+        if let Err(e) = app_state.workbook.ensure_sheet_loaded(index, &sheet_name) {
+            app_state.add_error(format!("Failed to load sheet: {e}"));
+            return false;
         }
     }
+    true
 }
 
 fn handle_editing_mode(app_state: &mut AppState, key: KeyEvent) {
@@ -261,18 +398,16 @@ fn handle_editing_mode(app_state: &mut AppState, key: KeyEvent) {
     };
 
     if let Err(e) = app_state.handle_vim_input(input) {
-        app_state.add_notification(format!("Vim input error: {e}"));
+        app_state.add_error(format!("Vim input error: {e}"));
     }
 }
 
 fn handle_search_mode(app_state: &mut AppState, key_code: KeyCode) {
     match key_code {
         KeyCode::Enter => app_state.execute_search(),
-        KeyCode::Esc => {
-            app_state.input_mode = InputMode::Normal;
-            app_state.input_buffer = String::new();
-            app_state.text_area = TextArea::default();
-        }
+        KeyCode::Esc => app_state.cancel_search(),
+        KeyCode::Up => app_state.recall_search_history(true),
+        KeyCode::Down => app_state.recall_search_history(false),
         _ => {
             let input = Input {
                 key: key_code_to_tui_key(key_code),
@@ -280,7 +415,10 @@ fn handle_search_mode(app_state: &mut AppState, key_code: KeyCode) {
                 alt: false,
                 shift: false,
             };
-            app_state.text_area.input(input);
+            if app_state.text_area.input(input) {
+                app_state.search_history.reset();
+                app_state.search_query_changed();
+            }
         }
     }
 }
@@ -311,42 +449,18 @@ fn key_code_to_tui_key(key_code: KeyCode) -> Key {
 
 fn handle_lazy_loading_mode(app_state: &mut AppState, key_code: KeyCode) {
     match key_code {
-        KeyCode::Enter => {
-            let index = app_state.workbook.get_current_sheet_index();
-            let sheet_name = app_state.workbook.get_current_sheet_name();
-
-            // Load the sheet
-            if let Err(e) = app_state.workbook.ensure_sheet_loaded(index, &sheet_name) {
-                app_state.add_notification(format!("Failed to load sheet: {e}"));
-            }
-
-            app_state.input_mode = InputMode::Normal;
-        }
+        // The sheet loads in the background; Enter tries again after a failed read
+        KeyCode::Enter => app_state.retry_sheet_load(),
         KeyCode::Char('[') => {
-            // Switch to previous sheet
-            let current_index = app_state.workbook.get_current_sheet_index();
-
-            if current_index == 0 {
-                app_state.add_notification("Already at the first sheet".to_string());
-            } else {
-                // The method will automatically set the input mode to LazyLoading if the sheet is not loaded
-                if let Err(e) = app_state.switch_sheet_by_index(current_index - 1) {
-                    app_state.add_notification(format!("Failed to switch to previous sheet: {e}"));
-                }
+            // The method will automatically set the input mode to LazyLoading if the sheet is not loaded
+            if let Err(e) = app_state.prev_sheet() {
+                app_state.add_error(format!("Failed to switch to previous sheet: {e}"));
             }
         }
         KeyCode::Char(']') => {
-            // Switch to next sheet
-            let current_index = app_state.workbook.get_current_sheet_index();
-            let sheet_count = app_state.workbook.get_sheet_names().len();
-
-            if current_index >= sheet_count - 1 {
-                app_state.add_notification("Already at the last sheet".to_string());
-            } else {
-                // The method will automatically set the input mode to LazyLoading if the sheet is not loaded
-                if let Err(e) = app_state.switch_sheet_by_index(current_index + 1) {
-                    app_state.add_notification(format!("Failed to switch to next sheet: {e}"));
-                }
+            // The method will automatically set the input mode to LazyLoading if the sheet is not loaded
+            if let Err(e) = app_state.next_sheet() {
+                app_state.add_error(format!("Failed to switch to next sheet: {e}"));
             }
         }
         KeyCode::Char(':') => {
@@ -355,21 +469,36 @@ fn handle_lazy_loading_mode(app_state: &mut AppState, key_code: KeyCode) {
         }
         _ => {
             app_state.add_notification(
-                "Press Enter to load the sheet data, or use [ and ] to switch sheets".to_string(),
+                "The sheet is still loading; use [ and ] to switch sheets".to_string(),
             );
         }
     }
 }
 
 fn handle_help_mode(app_state: &mut AppState, key_code: KeyCode) {
-    let line_count = app_state.help_total_lines.max(help_reference_line_count());
+    if app_state.help_filter_typing {
+        handle_help_filter_input(app_state, key_code);
+        return;
+    }
+
+    // A filtered list is shorter than the reference, and drawn before any key scrolls it
+    let line_count = if app_state.help_filter.is_empty() {
+        app_state.help_total_lines.max(help_reference_line_count())
+    } else {
+        app_state.help_total_lines
+    };
     let visible_lines = app_state.help_visible_lines.max(1);
     let max_scroll = line_count.saturating_sub(visible_lines);
 
     match key_code {
+        KeyCode::Esc if !app_state.help_filter.is_empty() => {
+            app_state.help_filter.clear();
+            app_state.help_scroll = 0;
+        }
         KeyCode::Enter | KeyCode::Esc | KeyCode::Char('q') => {
             app_state.input_mode = InputMode::Normal;
         }
+        KeyCode::Char('/') => app_state.help_filter_typing = true,
         KeyCode::Char('j') | KeyCode::Down => {
             app_state.help_scroll = (app_state.help_scroll + 1).min(max_scroll);
         }
@@ -392,6 +521,142 @@ fn handle_help_mode(app_state: &mut AppState, key_code: KeyCode) {
     }
 }
 
+/// Keys typed after `/` in the help overlay, narrowing it to the entries that match
+fn handle_help_filter_input(app_state: &mut AppState, key_code: KeyCode) {
+    match key_code {
+        KeyCode::Enter => app_state.help_filter_typing = false,
+        KeyCode::Esc => {
+            app_state.help_filter.clear();
+            app_state.help_filter_typing = false;
+        }
+        KeyCode::Backspace => {
+            if app_state.help_filter.pop().is_none() {
+                app_state.help_filter_typing = false;
+            }
+        }
+        KeyCode::Char(ch) => app_state.help_filter.push(ch),
+        _ => return,
+    }
+    app_state.help_scroll = 0;
+}
+
+fn handle_results_mode(app_state: &mut AppState, key_code: KeyCode) {
+    let Some(panel) = app_state.results.as_mut() else {
+        app_state.input_mode = InputMode::Normal;
+        return;
+    };
+
+    let page = panel.visible_lines.max(1) as isize;
+    match key_code {
+        KeyCode::Down | KeyCode::Char('j') => panel.move_selection(1),
+        KeyCode::Up | KeyCode::Char('k') => panel.move_selection(-1),
+        KeyCode::PageDown => panel.move_selection(page),
+        KeyCode::PageUp => panel.move_selection(-page),
+        KeyCode::Home | KeyCode::Char('g') => panel.select(0),
+        KeyCode::End | KeyCode::Char('G') => panel.select(usize::MAX),
+        KeyCode::Enter => {
+            app_state.jump_to_selected_result();
+            app_state.unfocus_results();
+        }
+        KeyCode::Char('o') | KeyCode::Char(' ') => app_state.jump_to_selected_result(),
+        KeyCode::Tab | KeyCode::Esc => app_state.unfocus_results(),
+        KeyCode::Char('q') => app_state.close_results(),
+        _ => {}
+    }
+}
+
+fn handle_pager_mode(app_state: &mut AppState, key_code: KeyCode) {
+    let Some(pager) = app_state.pager.as_mut() else {
+        app_state.input_mode = InputMode::Normal;
+        return;
+    };
+
+    // Typing a search query after '/'
+    if let Some(input) = pager.search_input.as_mut() {
+        match key_code {
+            KeyCode::Enter => {
+                let query = std::mem::take(input);
+                pager.search_input = None;
+                pager.search(&query);
+            }
+            KeyCode::Esc => pager.search_input = None,
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Char(c) => input.push(c),
+            _ => {}
+        }
+        return;
+    }
+
+    let page = pager.visible_lines.max(1) as isize;
+    match key_code {
+        KeyCode::Esc | KeyCode::Char('q') => app_state.close_pager(),
+        KeyCode::Down | KeyCode::Char('j') | KeyCode::Enter => pager.scroll_by(1),
+        KeyCode::Up | KeyCode::Char('k') => pager.scroll_by(-1),
+        KeyCode::PageDown | KeyCode::Char(' ') | KeyCode::Char('f') => pager.scroll_by(page),
+        KeyCode::PageUp | KeyCode::Char('b') => pager.scroll_by(-page),
+        KeyCode::Home | KeyCode::Char('g') => pager.scroll_to_top(),
+        KeyCode::End | KeyCode::Char('G') => pager.scroll_to_bottom(),
+        KeyCode::Char('/') => pager.search_input = Some(String::new()),
+        KeyCode::Char('n') => pager.next_match(true),
+        KeyCode::Char('N') => pager.next_match(false),
+        _ => {}
+    }
+}
+
+fn handle_onboarding_mode(app_state: &mut AppState, key_code: KeyCode) {
+    match key_code {
+        KeyCode::Char('s') => app_state.open_sample_workbook(),
+        KeyCode::Char('?') => {
+            app_state.finish_onboarding();
+            app_state.show_help();
+        }
+        KeyCode::Enter | KeyCode::Esc | KeyCode::Char('q') => app_state.finish_onboarding(),
+        _ => {}
+    }
+}
+
+fn handle_confirm_paste_mode(app_state: &mut AppState, key_code: KeyCode) {
+    match key_code {
+        KeyCode::Enter | KeyCode::Char('y') => app_state.confirm_block_paste(),
+        KeyCode::Char('s') => app_state.paste_pending_into_one_cell(),
+        KeyCode::Esc | KeyCode::Char('n' | 'q') => app_state.cancel_block_paste(),
+        _ => {}
+    }
+}
+
+fn handle_fixed_width_import_mode(app_state: &mut AppState, key_code: KeyCode) {
+    if matches!(key_code, KeyCode::Enter) {
+        app_state.confirm_fixed_width_import();
+        return;
+    }
+    if matches!(key_code, KeyCode::Esc | KeyCode::Char('q')) {
+        app_state.cancel_fixed_width_import();
+        return;
+    }
+
+    let Some(import) = app_state.fixed_width_import.as_mut() else {
+        app_state.input_mode = InputMode::Normal;
+        return;
+    };
+
+    match key_code {
+        KeyCode::Left | KeyCode::Char('h') => import.move_cursor(-1),
+        KeyCode::Right | KeyCode::Char('l') => import.move_cursor(1),
+        KeyCode::Char('w') | KeyCode::Tab => import.jump_to_break(true),
+        KeyCode::Char('b') | KeyCode::BackTab => import.jump_to_break(false),
+        KeyCode::Char('0') | KeyCode::Home => import.cursor = 0,
+        KeyCode::Char('$') | KeyCode::End => import.cursor = import.width(),
+        KeyCode::Char(' ') | KeyCode::Char('x') => import.toggle_break(),
+        KeyCode::Down | KeyCode::Char('j') => {
+            import.scroll = (import.scroll + 1).min(import.lines.len().saturating_sub(1));
+        }
+        KeyCode::Up | KeyCode::Char('k') => import.scroll = import.scroll.saturating_sub(1),
+        _ => {}
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
@@ -399,15 +664,20 @@ mod tests {
 
     use super::handle_key_event;
     use crate::app::{AppState, InputMode};
-    use crate::excel::{Cell, FreezePanes, Sheet, Workbook, EXCEL_MAX_COLS, EXCEL_MAX_ROWS};
+    use crate::config::KeySettings;
+    use crate::excel::{
+        Cell, CellGrid, ColumnFormats, FreezePanes, Sheet, SheetProtection, SheetVisibility,
+        Workbook, EXCEL_MAX_COLS, EXCEL_MAX_ROWS,
+    };
+    use crate::ui::keymap::Keymap;
     use crate::utils::index_to_col_name;
 
     fn app_with_sheet() -> AppState<'static> {
-        let mut data = vec![vec![Cell::empty(); 3]; 3];
-        data[1][1] = Cell::new("Name".to_string(), false);
-        data[1][2] = Cell::new("Name".to_string(), false);
-        data[2][1] = Cell::new("Ada".to_string(), false);
-        data[2][2] = Cell::new("10".to_string(), false);
+        let mut data = CellGrid::new();
+        data.set(1, 1, Cell::new("Name".to_string(), false));
+        data.set(1, 2, Cell::new("Name".to_string(), false));
+        data.set(2, 1, Cell::new("Ada".to_string(), false));
+        data.set(2, 2, Cell::new("10".to_string(), false));
         let sheet = Sheet {
             name: "Data".to_string(),
             data,
@@ -415,6 +685,11 @@ mod tests {
             max_cols: 2,
             is_loaded: true,
             freeze_panes: FreezePanes::none(),
+            column_formats: ColumnFormats::new(),
+            protection: SheetProtection::Unprotected,
+            visibility: SheetVisibility::Visible,
+            tab_color: None,
+            conditional_formats: Vec::new(),
         };
         let app = AppState::new(
             Workbook::from_sheets_for_test(vec![sheet]),
@@ -503,6 +778,294 @@ mod tests {
         assert_eq!(index_to_col_name(app.selected_cell.1), "XFD");
     }
 
+    #[test]
+    fn counts_repeat_motions_and_pick_rows() {
+        let mut app = app_with_sheet();
+        let type_keys = |app: &mut AppState, keys: &str| {
+            for key in keys.chars() {
+                handle_key_event(
+                    app,
+                    KeyEvent::new(KeyCode::Char(key), KeyModifiers::empty()),
+                );
+            }
+        };
+
+        type_keys(&mut app, "10j");
+        assert_eq!(app.selected_cell, (11, 1));
+        assert_eq!(app.pending_count, None);
+
+        type_keys(&mut app, "3l2h");
+        assert_eq!(app.selected_cell, (11, 2));
+
+        type_keys(&mut app, "25G");
+        assert_eq!(app.selected_cell, (25, 2));
+        type_keys(&mut app, "4gg");
+        assert_eq!(app.selected_cell, (4, 2));
+
+        // 0 without a count still jumps to the first column
+        type_keys(&mut app, "0");
+        assert_eq!(app.selected_cell, (4, 1));
+        type_keys(&mut app, "2");
+        assert_eq!(app.pending_count, Some(2));
+        handle_key_event(&mut app, KeyEvent::new(KeyCode::Esc, KeyModifiers::empty()));
+        assert_eq!(app.pending_count, None);
+        type_keys(&mut app, "j");
+        assert_eq!(app.selected_cell, (5, 1));
+    }
+
+    #[test]
+    fn m_sets_a_mark_that_quote_jumps_back_to() {
+        let mut app = app_with_sheet();
+        let type_keys = |app: &mut AppState, keys: &str| {
+            for key in keys.chars() {
+                handle_key_event(
+                    app,
+                    KeyEvent::new(KeyCode::Char(key), KeyModifiers::empty()),
+                );
+            }
+        };
+
+        type_keys(&mut app, "mq5000G'q");
+        assert_eq!(app.selected_cell, (1, 1));
+        assert_eq!(app.pending_mark, None);
+
+        // The letter after m is not taken as a motion
+        type_keys(&mut app, "mjj");
+        assert_eq!(app.selected_cell, (2, 1));
+        type_keys(&mut app, "'j");
+        assert_eq!(app.selected_cell, (1, 1));
+    }
+
+    #[test]
+    fn quote_digit_pastes_an_earlier_yank() {
+        let mut app = app_with_sheet();
+        let type_keys = |app: &mut AppState, keys: &str| {
+            for key in keys.chars() {
+                handle_key_event(
+                    app,
+                    KeyEvent::new(KeyCode::Char(key), KeyModifiers::empty()),
+                );
+            }
+        };
+
+        // Yank A2, then B2, so B2 is in "0 and A2 in "1
+        type_keys(&mut app, "jylyh");
+        type_keys(&mut app, "k\"1p");
+        assert_eq!(app.get_cell_content(1, 1), "Ada");
+        assert_eq!(app.pending_register, None);
+        type_keys(&mut app, "l\"0p");
+        assert_eq!(app.get_cell_content(1, 2), "10");
+        type_keys(&mut app, "\"5p");
+        assert_eq!(
+            app.notification_messages.last().unwrap().text,
+            "Register \"5 is empty"
+        );
+
+        app.show_registers();
+        let pager = app.pager.as_ref().unwrap();
+        assert_eq!(pager.lines[0], "\"0  cells       1x1  10");
+        assert_eq!(pager.lines[1], "\"1  cells       1x1  Ada");
+    }
+
+    #[test]
+    fn remapped_keys_drive_normal_and_command_mode() {
+        let mut settings = KeySettings::default();
+        for (key, action) in [("n", "move_down"), ("e", "move_up"), ("j", "none")] {
+            settings.normal.insert(key.to_string(), action.to_string());
+        }
+        settings
+            .command
+            .insert("tab".to_string(), "execute".to_string());
+        let mut app = app_with_sheet();
+        app.keymap = Keymap::from_settings(&settings).unwrap();
+        let press = |app: &mut AppState, code| {
+            handle_key_event(app, KeyEvent::new(code, KeyModifiers::empty()));
+        };
+        press(&mut app, KeyCode::Char('n'));
+        assert_eq!(app.selected_cell, (2, 1));
+        press(&mut app, KeyCode::Char('j'));
+        assert_eq!(app.selected_cell, (2, 1));
+        press(&mut app, KeyCode::Char('e'));
+        assert_eq!(app.selected_cell, (1, 1));
+        press(&mut app, KeyCode::Down);
+        assert_eq!(app.selected_cell, (2, 1));
+
+        for code in [':', 'B', '1'].map(KeyCode::Char) {
+            press(&mut app, code);
+        }
+        press(&mut app, KeyCode::Tab);
+        assert_eq!(app.selected_cell, (1, 2));
+        assert!(matches!(app.input_mode, InputMode::Normal));
+    }
+
+    #[test]
+    fn yy_and_yc_put_whole_rows_and_columns() {
+        let mut app = app_with_sheet();
+        let type_keys = |app: &mut AppState, keys: &str| {
+            for key in keys.chars() {
+                handle_key_event(
+                    app,
+                    KeyEvent::new(KeyCode::Char(key), KeyModifiers::empty()),
+                );
+            }
+        };
+
+        type_keys(&mut app, "jyyp");
+        assert_eq!(app.workbook.get_current_sheet().max_rows, 3);
+        assert_eq!(app.get_cell_content(3, 1), "Ada");
+        assert_eq!(app.get_cell_content(3, 2), "10");
+        assert_eq!(app.selected_cell, (3, 1));
+        app.undo().unwrap();
+        assert_eq!(app.workbook.get_current_sheet().max_rows, 2);
+
+        type_keys(&mut app, "gg2yyGp");
+        assert_eq!(app.workbook.get_current_sheet().max_rows, 4);
+        assert_eq!(app.get_cell_content(3, 1), "Name");
+        assert_eq!(app.get_cell_content(4, 1), "Ada");
+        app.undo().unwrap();
+        assert_eq!(app.workbook.get_current_sheet().max_rows, 2);
+
+        app.selected_cell = (1, 1);
+        type_keys(&mut app, "ycp");
+        assert_eq!(app.workbook.get_current_sheet().max_cols, 3);
+        assert_eq!(app.get_cell_content(1, 2), "Name");
+        assert_eq!(app.get_cell_content(2, 2), "Ada");
+        assert_eq!(app.get_cell_content(2, 3), "10");
+        assert_eq!(app.selected_cell, (1, 2));
+        app.undo().unwrap();
+        assert_eq!(app.get_cell_content(2, 2), "10");
+
+        app.show_registers();
+        let pager = app.pager.as_ref().unwrap();
+        assert_eq!(pager.lines[0], "\"0  columns     2x1  Name Ada");
+    }
+
+    #[test]
+    fn ctrl_scroll_keys_take_a_count() {
+        let mut app = app_with_sheet();
+        app.visible_rows = 10;
+        let ctrl = |key| KeyEvent::new(KeyCode::Char(key), KeyModifiers::CONTROL);
+
+        handle_key_event(&mut app, ctrl('d'));
+        assert_eq!(app.selected_cell, (6, 1));
+        handle_key_event(&mut app, ctrl('f'));
+        assert_eq!(app.selected_cell, (16, 1));
+        handle_key_event(
+            &mut app,
+            KeyEvent::new(KeyCode::Char('2'), KeyModifiers::empty()),
+        );
+        handle_key_event(&mut app, ctrl('u'));
+        assert_eq!(app.selected_cell, (6, 1));
+        assert_eq!(app.pending_count, None);
+        handle_key_event(&mut app, ctrl('b'));
+        assert_eq!(app.selected_cell, (1, 1));
+    }
+
+    #[test]
+    fn z_commands_scroll_without_moving_the_cursor() {
+        let mut app = app_with_sheet();
+        app.visible_rows = 10;
+        app.selected_cell = (40, 1);
+        app.handle_scrolling();
+        let type_keys = |app: &mut AppState, keys: &str| {
+            for key in keys.chars() {
+                handle_key_event(
+                    app,
+                    KeyEvent::new(KeyCode::Char(key), KeyModifiers::empty()),
+                );
+            }
+        };
+
+        type_keys(&mut app, "zt");
+        assert_eq!((app.selected_cell, app.start_row), ((40, 1), 40));
+        type_keys(&mut app, "zz");
+        assert_eq!((app.selected_cell, app.start_row), ((40, 1), 36));
+        type_keys(&mut app, "zb");
+        assert_eq!((app.selected_cell, app.start_row), ((40, 1), 31));
+        assert!(!app.z_pressed);
+    }
+
+    #[test]
+    fn ctrl_w_prefixes_pane_commands() {
+        let mut app = app_with_sheet();
+        let ctrl_w = KeyEvent::new(KeyCode::Char('w'), KeyModifiers::CONTROL);
+
+        handle_key_event(&mut app, ctrl_w);
+        handle_key_event(
+            &mut app,
+            KeyEvent::new(KeyCode::Char('v'), KeyModifiers::empty()),
+        );
+        assert!(app.split.is_some());
+        assert!(!app.window_key_pending);
+
+        app.selected_cell = (2, 2);
+        handle_key_event(&mut app, ctrl_w);
+        handle_key_event(&mut app, ctrl_w);
+        assert_eq!(app.selected_cell, (1, 1));
+
+        handle_key_event(&mut app, ctrl_w);
+        handle_key_event(
+            &mut app,
+            KeyEvent::new(KeyCode::Char('o'), KeyModifiers::empty()),
+        );
+        assert!(app.split.is_none());
+        assert_eq!(app.selected_cell, (1, 1));
+    }
+
+    #[test]
+    fn shift_arrows_extend_selection_and_ctrl_jumps_to_region_edge() {
+        let mut app = app_with_sheet();
+        app.selected_cell = (1, 1);
+
+        handle_key_event(&mut app, KeyEvent::new(KeyCode::Right, KeyModifiers::SHIFT));
+        assert_eq!(app.selection_text().as_deref(), Some("A1:B1"));
+
+        handle_key_event(
+            &mut app,
+            KeyEvent::new(KeyCode::Down, KeyModifiers::SHIFT | KeyModifiers::CONTROL),
+        );
+        assert_eq!(app.selection_text().as_deref(), Some("A1:B2"));
+
+        // Unshifted movement keeps the selection, as with `v`
+        handle_key_event(
+            &mut app,
+            KeyEvent::new(KeyCode::Left, KeyModifiers::empty()),
+        );
+        assert_eq!(app.selection_text().as_deref(), Some("A1:A2"));
+
+        handle_key_event(&mut app, KeyEvent::new(KeyCode::Esc, KeyModifiers::empty()));
+        assert_eq!(app.selection_range(), None);
+    }
+
+    #[test]
+    fn alt_keys_move_the_current_row_and_column() {
+        let mut app = app_with_sheet();
+        app.selected_cell = (1, 1);
+
+        handle_key_event(
+            &mut app,
+            KeyEvent::new(KeyCode::Char('j'), KeyModifiers::ALT),
+        );
+        assert_eq!(app.get_cell_content(1, 1), "Ada");
+        assert_eq!(app.get_cell_content(2, 1), "Name");
+        assert_eq!(app.selected_cell, (2, 1));
+
+        handle_key_event(
+            &mut app,
+            KeyEvent::new(KeyCode::Char('k'), KeyModifiers::ALT),
+        );
+        assert_eq!(app.get_cell_content(1, 1), "Name");
+        assert_eq!(app.selected_cell, (1, 1));
+
+        handle_key_event(
+            &mut app,
+            KeyEvent::new(KeyCode::Char('l'), KeyModifiers::ALT),
+        );
+        assert_eq!(app.get_cell_content(2, 2), "Ada");
+        assert_eq!(app.get_cell_content(2, 1), "10");
+        assert_eq!(app.selected_cell, (1, 2));
+    }
+
     #[test]
     fn q_closes_help_overlay_without_quitting() {
         let mut app = app_with_sheet();
@@ -517,6 +1080,32 @@ mod tests {
         assert!(!app.should_quit);
     }
 
+    #[test]
+    fn slash_filters_the_help_overlay_until_esc_clears_it() {
+        let mut app = app_with_sheet();
+        app.show_help();
+        let press = |app: &mut AppState, code: KeyCode| {
+            handle_key_event(app, KeyEvent::new(code, KeyModifiers::empty()));
+        };
+
+        press(&mut app, KeyCode::Char('/'));
+        for ch in "qx".chars() {
+            press(&mut app, KeyCode::Char(ch));
+        }
+        assert_eq!(app.help_filter, "qx");
+        press(&mut app, KeyCode::Backspace);
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.help_filter, "q");
+        assert!(!app.help_filter_typing);
+
+        // q and Esc still work once the filter is kept; Esc clears it first
+        press(&mut app, KeyCode::Esc);
+        assert!(app.help_filter.is_empty());
+        assert!(matches!(app.input_mode, InputMode::Help));
+        press(&mut app, KeyCode::Esc);
+        assert!(matches!(app.input_mode, InputMode::Normal));
+    }
+
     #[test]
     fn page_keys_scroll_help_overlay_by_visible_page() {
         let mut app = app_with_sheet();
@@ -558,10 +1147,10 @@ mod tests {
         let mut app = app_with_sheet();
         app.show_help();
         app.help_visible_lines = 8;
-        app.help_total_lines = 120;
+        app.help_total_lines = 200;
 
         handle_key_event(&mut app, KeyEvent::new(KeyCode::End, KeyModifiers::empty()));
 
-        assert_eq!(app.help_scroll, 112);
+        assert_eq!(app.help_scroll, 192);
     }
 }
@@ -0,0 +1,482 @@
+use std::collections::HashMap;
+
+use crossterm::event::KeyCode;
+
+use crate::config::KeySettings;
+
+/// What a key does in normal mode. Keys after a prefix, like the second `g` of `gg`, are not
+/// remapped
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NormalAction {
+    /// Follow the sheet link under the cursor, or else edit the cell
+    Open,
+    Edit,
+    MoveLeft,
+    MoveDown,
+    MoveUp,
+    MoveRight,
+    Undo,
+    GrowInfoPanel,
+    ShrinkInfoPanel,
+    ToggleInfoPanel,
+    /// Opens the current cell's whole value in a pager
+    ShowCell,
+    PrevSheet,
+    NextSheet,
+    /// Scroll the tab bar without switching sheets
+    ScrollTabsLeft,
+    ScrollTabsRight,
+    /// Starts `gg`, `gt`, `gT`, `g;`, `gr` and `gx`
+    GPrefix,
+    /// Starts `zz`, `zt` and `zb`
+    ZPrefix,
+    LastRow,
+    FirstColumn,
+    FirstNonEmptyColumn,
+    LastColumn,
+    SetMark,
+    JumpToMark,
+    /// Copies the cell or selection, and starts `yy` and `yc`
+    Yank,
+    Cut,
+    Paste,
+    Register,
+    Visual,
+    ClearSelection,
+    Command,
+    SearchForward,
+    SearchBackward,
+    NextMatch,
+    PrevMatch,
+    PageUp,
+    PageDown,
+}
+
+impl NormalAction {
+    const ALL: [Self; 36] = [
+        Self::Open,
+        Self::Edit,
+        Self::MoveLeft,
+        Self::MoveDown,
+        Self::MoveUp,
+        Self::MoveRight,
+        Self::Undo,
+        Self::GrowInfoPanel,
+        Self::ShrinkInfoPanel,
+        Self::ToggleInfoPanel,
+        Self::ShowCell,
+        Self::PrevSheet,
+        Self::NextSheet,
+        Self::ScrollTabsLeft,
+        Self::ScrollTabsRight,
+        Self::GPrefix,
+        Self::ZPrefix,
+        Self::LastRow,
+        Self::FirstColumn,
+        Self::FirstNonEmptyColumn,
+        Self::LastColumn,
+        Self::SetMark,
+        Self::JumpToMark,
+        Self::Yank,
+        Self::Cut,
+        Self::Paste,
+        Self::Register,
+        Self::Visual,
+        Self::ClearSelection,
+        Self::Command,
+        Self::SearchForward,
+        Self::SearchBackward,
+        Self::NextMatch,
+        Self::PrevMatch,
+        Self::PageUp,
+        Self::PageDown,
+    ];
+
+    /// The name the config file's `[keys.normal]` section uses
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Open => "open",
+            Self::Edit => "edit",
+            Self::MoveLeft => "move_left",
+            Self::MoveDown => "move_down",
+            Self::MoveUp => "move_up",
+            Self::MoveRight => "move_right",
+            Self::Undo => "undo",
+            Self::GrowInfoPanel => "grow_info_panel",
+            Self::ShrinkInfoPanel => "shrink_info_panel",
+            Self::ToggleInfoPanel => "toggle_info_panel",
+            Self::ShowCell => "show_cell",
+            Self::PrevSheet => "prev_sheet",
+            Self::NextSheet => "next_sheet",
+            Self::ScrollTabsLeft => "scroll_tabs_left",
+            Self::ScrollTabsRight => "scroll_tabs_right",
+            Self::GPrefix => "g_prefix",
+            Self::ZPrefix => "z_prefix",
+            Self::LastRow => "last_row",
+            Self::FirstColumn => "first_column",
+            Self::FirstNonEmptyColumn => "first_non_empty_column",
+            Self::LastColumn => "last_column",
+            Self::SetMark => "set_mark",
+            Self::JumpToMark => "jump_to_mark",
+            Self::Yank => "yank",
+            Self::Cut => "cut",
+            Self::Paste => "paste",
+            Self::Register => "register",
+            Self::Visual => "visual",
+            Self::ClearSelection => "clear_selection",
+            Self::Command => "command",
+            Self::SearchForward => "search_forward",
+            Self::SearchBackward => "search_backward",
+            Self::NextMatch => "next_match",
+            Self::PrevMatch => "prev_match",
+            Self::PageUp => "page_up",
+            Self::PageDown => "page_down",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|action| action.name() == name)
+    }
+}
+
+/// What a key does while typing a `:` command
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CommandAction {
+    Execute,
+    Cancel,
+    DeleteChar,
+}
+
+impl CommandAction {
+    const ALL: [Self; 3] = [Self::Execute, Self::Cancel, Self::DeleteChar];
+
+    /// The name the config file's `[keys.command]` section uses
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Execute => "execute",
+            Self::Cancel => "cancel",
+            Self::DeleteChar => "delete_char",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|action| action.name() == name)
+    }
+}
+
+/// A key the config file maps differently from the defaults
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeyOverride {
+    /// Whether the key is pressed at the `:` prompt rather than in normal mode
+    pub at_prompt: bool,
+    /// The key as the config file names it
+    pub key: String,
+    /// The action the key now does, or `none`
+    pub action: &'static str,
+}
+
+const DEFAULT_NORMAL_KEYS: &[(KeyCode, NormalAction)] = &[
+    (KeyCode::Enter, NormalAction::Open),
+    (KeyCode::Char('i'), NormalAction::Edit),
+    (KeyCode::Char('h'), NormalAction::MoveLeft),
+    (KeyCode::Left, NormalAction::MoveLeft),
+    (KeyCode::Char('j'), NormalAction::MoveDown),
+    (KeyCode::Down, NormalAction::MoveDown),
+    (KeyCode::Char('k'), NormalAction::MoveUp),
+    (KeyCode::Up, NormalAction::MoveUp),
+    (KeyCode::Char('l'), NormalAction::MoveRight),
+    (KeyCode::Right, NormalAction::MoveRight),
+    (KeyCode::Char('u'), NormalAction::Undo),
+    (KeyCode::Char('='), NormalAction::GrowInfoPanel),
+    (KeyCode::Char('+'), NormalAction::GrowInfoPanel),
+    (KeyCode::Char('-'), NormalAction::ShrinkInfoPanel),
+    (KeyCode::Char('_'), NormalAction::ToggleInfoPanel),
+    (KeyCode::Char('K'), NormalAction::ShowCell),
+    (KeyCode::Char('['), NormalAction::PrevSheet),
+    (KeyCode::Char(']'), NormalAction::NextSheet),
+    (KeyCode::Char('{'), NormalAction::ScrollTabsLeft),
+    (KeyCode::Char('}'), NormalAction::ScrollTabsRight),
+    (KeyCode::Char('g'), NormalAction::GPrefix),
+    (KeyCode::Char('z'), NormalAction::ZPrefix),
+    (KeyCode::Char('G'), NormalAction::LastRow),
+    (KeyCode::Char('0'), NormalAction::FirstColumn),
+    (KeyCode::Home, NormalAction::FirstColumn),
+    (KeyCode::Char('^'), NormalAction::FirstNonEmptyColumn),
+    (KeyCode::Char('$'), NormalAction::LastColumn),
+    (KeyCode::End, NormalAction::LastColumn),
+    (KeyCode::Char('m'), NormalAction::SetMark),
+    (KeyCode::Char('\''), NormalAction::JumpToMark),
+    (KeyCode::Char('y'), NormalAction::Yank),
+    (KeyCode::Char('d'), NormalAction::Cut),
+    (KeyCode::Char('p'), NormalAction::Paste),
+    (KeyCode::Char('"'), NormalAction::Register),
+    (KeyCode::Char('v'), NormalAction::Visual),
+    (KeyCode::Esc, NormalAction::ClearSelection),
+    (KeyCode::Char(':'), NormalAction::Command),
+    (KeyCode::Char('/'), NormalAction::SearchForward),
+    (KeyCode::Char('?'), NormalAction::SearchBackward),
+    (KeyCode::Char('n'), NormalAction::NextMatch),
+    (KeyCode::Char('N'), NormalAction::PrevMatch),
+    (KeyCode::PageUp, NormalAction::PageUp),
+    (KeyCode::PageDown, NormalAction::PageDown),
+];
+
+const DEFAULT_COMMAND_KEYS: &[(KeyCode, CommandAction)] = &[
+    (KeyCode::Enter, CommandAction::Execute),
+    (KeyCode::Esc, CommandAction::Cancel),
+    (KeyCode::Backspace, CommandAction::DeleteChar),
+];
+
+/// Which key does what in normal mode and at the `:` prompt
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Keymap {
+    normal: HashMap<KeyCode, NormalAction>,
+    command: HashMap<KeyCode, CommandAction>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self {
+            normal: DEFAULT_NORMAL_KEYS.iter().copied().collect(),
+            command: DEFAULT_COMMAND_KEYS.iter().copied().collect(),
+        }
+    }
+}
+
+impl Keymap {
+    /// The default keys with the `[keys.normal]` and `[keys.command]` sections of the config
+    /// file applied. A key mapped to `"none"` does nothing
+    pub fn from_settings(settings: &KeySettings) -> Result<Self, String> {
+        let mut keymap = Self::default();
+        for (key, action) in &settings.normal {
+            let code = parse_key(key).ok_or_else(|| format!("Unknown key {key} in keys.normal"))?;
+            if action == "none" {
+                keymap.normal.remove(&code);
+                continue;
+            }
+            let action = NormalAction::from_name(action)
+                .ok_or_else(|| format!("Unknown normal-mode action {action} for key {key}"))?;
+            keymap.normal.insert(code, action);
+        }
+        for (key, action) in &settings.command {
+            let code =
+                parse_key(key).ok_or_else(|| format!("Unknown key {key} in keys.command"))?;
+            if action == "none" {
+                keymap.command.remove(&code);
+                continue;
+            }
+            let action = CommandAction::from_name(action)
+                .ok_or_else(|| format!("Unknown command-mode action {action} for key {key}"))?;
+            keymap.command.insert(code, action);
+        }
+        Ok(keymap)
+    }
+
+    #[must_use]
+    pub fn normal(&self, key_code: KeyCode) -> Option<NormalAction> {
+        self.normal.get(&key_code).copied()
+    }
+
+    #[must_use]
+    pub fn command(&self, key_code: KeyCode) -> Option<CommandAction> {
+        self.command.get(&key_code).copied()
+    }
+
+    /// The keys mapped unlike the defaults, normal-mode keys first, each sorted by name
+    #[must_use]
+    pub fn overrides(&self) -> Vec<KeyOverride> {
+        let mut overrides = changed_keys(&self.normal, DEFAULT_NORMAL_KEYS, NormalAction::name)
+            .into_iter()
+            .map(|(key, action)| KeyOverride {
+                at_prompt: false,
+                key,
+                action,
+            })
+            .collect::<Vec<_>>();
+        overrides.extend(
+            changed_keys(&self.command, DEFAULT_COMMAND_KEYS, CommandAction::name)
+                .into_iter()
+                .map(|(key, action)| KeyOverride {
+                    at_prompt: true,
+                    key,
+                    action,
+                }),
+        );
+        overrides
+    }
+}
+
+/// The names of the keys `keys` maps unlike `defaults`, with what each now does
+fn changed_keys<A: Copy + PartialEq>(
+    keys: &HashMap<KeyCode, A>,
+    defaults: &[(KeyCode, A)],
+    name: fn(A) -> &'static str,
+) -> Vec<(String, &'static str)> {
+    let default_action = |code: KeyCode| {
+        defaults
+            .iter()
+            .find(|&&(default, _)| default == code)
+            .map(|&(_, action)| action)
+    };
+    let mut changed: Vec<(String, &'static str)> = keys
+        .keys()
+        .copied()
+        .chain(defaults.iter().map(|&(code, _)| code))
+        .filter(|&code| keys.get(&code).copied() != default_action(code))
+        .map(|code| {
+            (
+                key_name(code),
+                keys.get(&code).map_or("none", |&action| name(action)),
+            )
+        })
+        .collect();
+    changed.sort();
+    changed.dedup();
+    changed
+}
+
+/// A key as the config file names it: one character, or `enter`, `esc`, `tab`, `space`,
+/// `backspace`, `delete`, `left`, `right`, `up`, `down`, `home`, `end`, `pageup`, `pagedown`
+/// or `f1` to `f12`
+fn parse_key(name: &str) -> Option<KeyCode> {
+    let mut chars = name.chars();
+    if let (Some(ch), None) = (chars.next(), chars.next()) {
+        return Some(KeyCode::Char(ch));
+    }
+
+    let code = match name.to_lowercase().as_str() {
+        "enter" | "return" => KeyCode::Enter,
+        "esc" | "escape" => KeyCode::Esc,
+        "tab" => KeyCode::Tab,
+        "space" => KeyCode::Char(' '),
+        "backspace" => KeyCode::Backspace,
+        "delete" | "del" => KeyCode::Delete,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        other => {
+            let number = other.strip_prefix('f')?.parse().ok()?;
+            if !(1..=12).contains(&number) {
+                return None;
+            }
+            KeyCode::F(number)
+        }
+    };
+    Some(code)
+}
+
+/// The name [`parse_key`] reads `code` from
+fn key_name(code: KeyCode) -> String {
+    let name = match code {
+        KeyCode::Char(' ') => "space",
+        KeyCode::Char(ch) => return ch.to_string(),
+        KeyCode::F(number) => return format!("f{number}"),
+        KeyCode::Enter => "enter",
+        KeyCode::Esc => "esc",
+        KeyCode::Tab => "tab",
+        KeyCode::Backspace => "backspace",
+        KeyCode::Delete => "delete",
+        KeyCode::Left => "left",
+        KeyCode::Right => "right",
+        KeyCode::Up => "up",
+        KeyCode::Down => "down",
+        KeyCode::Home => "home",
+        KeyCode::End => "end",
+        KeyCode::PageUp => "pageup",
+        KeyCode::PageDown => "pagedown",
+        _ => "?",
+    };
+    name.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_key, CommandAction, Keymap, NormalAction};
+    use crate::config::KeySettings;
+    use crossterm::event::KeyCode;
+
+    fn settings(normal: &[(&str, &str)], command: &[(&str, &str)]) -> KeySettings {
+        let map = |pairs: &[(&str, &str)]| {
+            pairs
+                .iter()
+                .map(|&(key, action)| (key.to_string(), action.to_string()))
+                .collect()
+        };
+        KeySettings {
+            normal: map(normal),
+            command: map(command),
+        }
+    }
+
+    #[test]
+    fn settings_remap_and_unbind_keys() {
+        let keymap = Keymap::from_settings(&settings(
+            &[
+                ("n", "move_down"),
+                ("e", "move_up"),
+                ("k", "next_match"),
+                ("j", "none"),
+            ],
+            &[("tab", "execute")],
+        ))
+        .unwrap();
+        assert_eq!(
+            keymap.normal(KeyCode::Char('n')),
+            Some(NormalAction::MoveDown)
+        );
+        assert_eq!(
+            keymap.normal(KeyCode::Char('e')),
+            Some(NormalAction::MoveUp)
+        );
+        assert_eq!(
+            keymap.normal(KeyCode::Char('k')),
+            Some(NormalAction::NextMatch)
+        );
+        assert_eq!(keymap.normal(KeyCode::Char('j')), None);
+        assert_eq!(keymap.normal(KeyCode::Down), Some(NormalAction::MoveDown));
+        assert_eq!(keymap.command(KeyCode::Tab), Some(CommandAction::Execute));
+        assert_eq!(keymap.command(KeyCode::Enter), Some(CommandAction::Execute));
+        let overrides = keymap.overrides();
+        let overrides: Vec<(bool, &str, &str)> = overrides
+            .iter()
+            .map(|key| (key.at_prompt, key.key.as_str(), key.action))
+            .collect();
+        assert_eq!(
+            overrides,
+            vec![
+                (false, "e", "move_up"),
+                (false, "j", "none"),
+                (false, "k", "next_match"),
+                (false, "n", "move_down"),
+                (true, "tab", "execute"),
+            ]
+        );
+        assert!(Keymap::default().overrides().is_empty());
+
+        assert_eq!(
+            Keymap::from_settings(&settings(&[("ctrl-x", "undo")], &[])),
+            Err("Unknown key ctrl-x in keys.normal".to_string())
+        );
+        assert_eq!(
+            Keymap::from_settings(&settings(&[("x", "explode")], &[])),
+            Err("Unknown normal-mode action explode for key x".to_string())
+        );
+    }
+
+    #[test]
+    fn keys_are_named_like_the_config_file_writes_them() {
+        assert_eq!(parse_key("q"), Some(KeyCode::Char('q')));
+        assert_eq!(parse_key("space"), Some(KeyCode::Char(' ')));
+        assert_eq!(parse_key("PageDown"), Some(KeyCode::PageDown));
+        assert_eq!(parse_key("f5"), Some(KeyCode::F(5)));
+        assert_eq!(parse_key("f13"), None);
+        assert_eq!(parse_key("hyper"), None);
+    }
+}
@@ -1,5 +1,8 @@
 mod handlers;
+mod keymap;
 mod render;
 mod theme;
 
+pub use crate::ui::keymap::Keymap;
 pub use crate::ui::render::run_app;
+pub use crate::ui::theme::use_theme_settings;
@@ -6,14 +6,24 @@ use ratatui::{
     Frame,
 };
 
-use crate::app::{AppState, HelpEntry, HelpSection, LEFT_HELP_SECTIONS, RIGHT_HELP_SECTIONS};
+use crate::app::{
+    help_text_matches, AppState, HelpSection, LEFT_HELP_SECTIONS, RIGHT_HELP_SECTIONS,
+};
+use crate::ui::keymap::KeyOverride;
 use crate::ui::theme;
 
-use super::{display_width, line_display_width};
+use super::{display_width, line_display_width, wrap_text};
 
 const HELP_ENTRY_INDENT: u16 = 2;
 const HELP_ENTRY_GAP: u16 = 1;
 
+/// A section as drawn: only the entries matching the filter, keys and descriptions owned so
+/// the keys of the config file can join them
+struct ShownSection {
+    title: &'static str,
+    entries: Vec<(String, String)>,
+}
+
 pub(super) fn draw_help_popup(f: &mut Frame, app_state: &mut AppState, area: Rect) {
     let popup_area = help_popup_area(area);
     let block = Block::default()
@@ -21,11 +31,11 @@ pub(super) fn draw_help_popup(f: &mut Frame, app_state: &mut AppState, area: Rec
         .title_alignment(Alignment::Center)
         .title_style(
             Style::default()
-                .fg(theme::ACCENT)
+                .fg(theme::accent())
                 .add_modifier(Modifier::BOLD),
         )
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(theme::TEXT_SECONDARY))
+        .border_style(Style::default().fg(theme::text_secondary()))
         .style(theme::surface());
     let inner = block.inner(popup_area);
 
@@ -38,7 +48,8 @@ pub(super) fn draw_help_popup(f: &mut Frame, app_state: &mut AppState, area: Rec
         return;
     };
 
-    let lines = help_overlay_lines(content_area.width);
+    let filter = app_state.help_filter.to_lowercase();
+    let lines = help_lines(content_area.width, &filter, &app_state.keymap.overrides());
     let visible_lines = content_area.height.max(1) as usize;
     app_state.help_visible_lines = visible_lines;
     app_state.help_total_lines = lines.len();
@@ -52,12 +63,16 @@ pub(super) fn draw_help_popup(f: &mut Frame, app_state: &mut AppState, area: Rec
 
     let divider = Paragraph::new("-".repeat(inner.width as usize)).style(theme::surface());
     f.render_widget(divider, divider_area);
-    render_help_footer(
-        f,
+    let footer = if app_state.help_filter_typing || !app_state.help_filter.is_empty() {
+        help_filter_footer_line(&app_state.help_filter, app_state.help_filter_typing)
+    } else {
+        help_footer_line(app_state.help_scroll, visible_lines, max_scroll)
+    };
+    f.render_widget(
+        Paragraph::new(footer)
+            .style(theme::surface())
+            .alignment(Alignment::Center),
         footer_area,
-        app_state.help_scroll,
-        visible_lines,
-        max_scroll,
     );
 }
 
@@ -93,33 +108,98 @@ fn help_popup_inner_areas(inner: Rect) -> Option<(Rect, Rect, Rect)> {
     Some((content_area, divider_area, footer_area))
 }
 
-fn render_help_footer(
-    f: &mut Frame,
-    area: Rect,
-    scroll: usize,
-    visible_lines: usize,
-    max_scroll: usize,
-) {
-    let footer = help_footer_line(scroll, visible_lines, max_scroll);
-    let footer_widget = Paragraph::new(footer)
-        .style(theme::surface())
-        .alignment(Alignment::Center);
-    f.render_widget(footer_widget, area);
+/// The overlay's lines: the keys the config file changed, then the reference, keeping only
+/// entries that match `filter` unless it is empty. A filtered list is one column
+pub(super) fn help_lines(
+    width: u16,
+    filter: &str,
+    overrides: &[KeyOverride],
+) -> Vec<Line<'static>> {
+    let mut left: Vec<ShownSection> = override_section(overrides).into_iter().collect();
+    left.extend(LEFT_HELP_SECTIONS.iter().map(ShownSection::from));
+    let mut right: Vec<ShownSection> = RIGHT_HELP_SECTIONS.iter().map(ShownSection::from).collect();
+
+    if filter.is_empty() {
+        return if width >= 82 {
+            two_column_help_lines(&left, &right, width)
+        } else {
+            one_column_help_lines(&left, &right, width)
+        };
+    }
+
+    left.append(&mut right);
+    let sections: Vec<ShownSection> = left
+        .into_iter()
+        .filter_map(|section| section.filtered(filter))
+        .collect();
+    if sections.is_empty() {
+        return vec![Line::from(Span::styled(
+            format!("No help entries match \"{filter}\""),
+            Style::default().fg(theme::text_secondary()),
+        ))];
+    }
+    help_column_lines(&sections, width)
 }
 
-pub(super) fn help_overlay_lines(width: u16) -> Vec<Line<'static>> {
-    if width >= 82 {
-        two_column_help_lines(width)
-    } else {
-        one_column_help_lines(width)
+impl From<&HelpSection> for ShownSection {
+    fn from(section: &HelpSection) -> Self {
+        Self {
+            title: section.title,
+            entries: section
+                .entries
+                .iter()
+                .map(|entry| (entry.keys.to_string(), entry.description.to_string()))
+                .collect(),
+        }
     }
 }
 
-fn two_column_help_lines(width: u16) -> Vec<Line<'static>> {
+impl ShownSection {
+    /// The section with only the entries matching `filter`, or all of them when the title
+    /// does. `None` when nothing matches
+    fn filtered(mut self, filter: &str) -> Option<Self> {
+        if !help_text_matches(self.title, filter) {
+            self.entries.retain(|(keys, description)| {
+                help_text_matches(keys, filter) || help_text_matches(description, filter)
+            });
+        }
+        (!self.entries.is_empty()).then_some(self)
+    }
+}
+
+/// The keys the config file maps unlike the defaults, listed first since the reference
+/// below describes the default keys
+fn override_section(overrides: &[KeyOverride]) -> Option<ShownSection> {
+    if overrides.is_empty() {
+        return None;
+    }
+    let entries = overrides
+        .iter()
+        .map(|key| {
+            let action = key.action.replace('_', " ");
+            let description = if key.at_prompt {
+                format!("{action} at the : prompt")
+            } else {
+                action
+            };
+            (key.key.clone(), description)
+        })
+        .collect();
+    Some(ShownSection {
+        title: "YOUR KEYS",
+        entries,
+    })
+}
+
+fn two_column_help_lines(
+    left: &[ShownSection],
+    right: &[ShownSection],
+    width: u16,
+) -> Vec<Line<'static>> {
     let gap = 4;
     let column_width = width.saturating_sub(gap) / 2;
-    let left = help_column_lines(LEFT_HELP_SECTIONS, column_width);
-    let right = help_column_lines(RIGHT_HELP_SECTIONS, column_width);
+    let left = help_column_lines(left, column_width);
+    let right = help_column_lines(right, column_width);
     let row_count = left.len().max(right.len());
     let mut rows = Vec::with_capacity(row_count);
 
@@ -136,14 +216,18 @@ fn two_column_help_lines(width: u16) -> Vec<Line<'static>> {
     rows
 }
 
-fn one_column_help_lines(width: u16) -> Vec<Line<'static>> {
-    let mut lines = help_column_lines(LEFT_HELP_SECTIONS, width);
+fn one_column_help_lines(
+    left: &[ShownSection],
+    right: &[ShownSection],
+    width: u16,
+) -> Vec<Line<'static>> {
+    let mut lines = help_column_lines(left, width);
     lines.push(Line::default());
-    lines.extend(help_column_lines(RIGHT_HELP_SECTIONS, width));
+    lines.extend(help_column_lines(right, width));
     lines
 }
 
-fn help_column_lines(sections: &[HelpSection], width: u16) -> Vec<Line<'static>> {
+fn help_column_lines(sections: &[ShownSection], width: u16) -> Vec<Line<'static>> {
     let mut lines = Vec::new();
 
     for (index, section) in sections.iter().enumerate() {
@@ -151,8 +235,8 @@ fn help_column_lines(sections: &[HelpSection], width: u16) -> Vec<Line<'static>>
             lines.push(Line::default());
         }
         lines.push(section_title_line(section.title));
-        for entry in section.entries {
-            lines.extend(help_entry_lines(entry, width));
+        for (keys, description) in &section.entries {
+            lines.extend(entry_lines(keys, description, width));
         }
     }
 
@@ -163,16 +247,21 @@ fn section_title_line(title: &'static str) -> Line<'static> {
     Line::from(Span::styled(
         title,
         Style::default()
-            .fg(theme::WARNING)
+            .fg(theme::warning())
             .add_modifier(Modifier::BOLD),
     ))
 }
 
-pub(super) fn help_entry_lines(entry: &HelpEntry, width: u16) -> Vec<Line<'static>> {
-    let prefix = help_entry_prefix(entry.keys);
+#[cfg(test)]
+pub(super) fn help_entry_lines(entry: &crate::app::HelpEntry, width: u16) -> Vec<Line<'static>> {
+    entry_lines(entry.keys, entry.description, width)
+}
+
+fn entry_lines(keys: &str, description: &str, width: u16) -> Vec<Line<'static>> {
+    let prefix = help_entry_prefix(keys);
     let prefix_width = spans_display_width(&prefix);
     let description_width = width.saturating_sub(prefix_width + HELP_ENTRY_GAP).max(1);
-    let mut chunks = wrap_text(entry.description, description_width);
+    let mut chunks = wrap_text(description, description_width);
 
     if chunks.is_empty() {
         return vec![Line::from(prefix)];
@@ -192,7 +281,10 @@ fn help_entry_prefix(keys: &str) -> Vec<Span<'static>> {
 
     for (index, chip) in key_chips(keys).into_iter().enumerate() {
         if index > 0 {
-            spans.push(Span::styled("/", Style::default().fg(theme::TEXT_DISABLED)));
+            spans.push(Span::styled(
+                "/",
+                Style::default().fg(theme::text_disabled()),
+            ));
         }
         spans.extend(key_chip_spans(chip));
     }
@@ -204,8 +296,8 @@ fn key_chip_spans(label: String) -> Vec<Span<'static>> {
     vec![Span::styled(
         format!(" {label} "),
         Style::default()
-            .bg(theme::SURFACE_MUTED)
-            .fg(theme::ACCENT)
+            .bg(theme::surface_muted())
+            .fg(theme::accent())
             .add_modifier(Modifier::BOLD),
     )]
 }
@@ -253,81 +345,7 @@ fn right_aligned_description_line(description: String, width: u16) -> Line<'stat
 }
 
 fn description_span(description: String) -> Span<'static> {
-    Span::styled(description, Style::default().fg(theme::TEXT_SECONDARY))
-}
-
-fn wrap_text(text: &str, width: u16) -> Vec<String> {
-    if width == 0 {
-        return Vec::new();
-    }
-
-    let mut lines = Vec::new();
-    let mut current = String::new();
-
-    for word in text.split_whitespace() {
-        append_wrapped_word(&mut lines, &mut current, word, width);
-    }
-
-    if !current.is_empty() {
-        lines.push(current);
-    }
-
-    lines
-}
-
-fn append_wrapped_word(lines: &mut Vec<String>, current: &mut String, word: &str, width: u16) {
-    let word_width = display_width(word);
-    let current_width = display_width(current);
-
-    if current.is_empty() && word_width <= width {
-        current.push_str(word);
-    } else if !current.is_empty() && current_width + 1 + word_width <= width {
-        current.push(' ');
-        current.push_str(word);
-    } else {
-        if !current.is_empty() {
-            lines.push(std::mem::take(current));
-        }
-        append_word_chunks(lines, current, word, width);
-    }
-}
-
-fn append_word_chunks(lines: &mut Vec<String>, current: &mut String, word: &str, width: u16) {
-    if display_width(word) <= width {
-        current.push_str(word);
-        return;
-    }
-
-    for chunk in split_word_to_width(word, width) {
-        if current.is_empty() {
-            current.push_str(&chunk);
-        } else {
-            lines.push(std::mem::take(current));
-            current.push_str(&chunk);
-        }
-    }
-}
-
-fn split_word_to_width(word: &str, width: u16) -> Vec<String> {
-    let mut chunks = Vec::new();
-    let mut current = String::new();
-    let mut used = 0;
-
-    for ch in word.chars() {
-        let char_width = if ch.is_ascii() { 1 } else { 2 };
-        if used + char_width > width && !current.is_empty() {
-            chunks.push(std::mem::take(&mut current));
-            used = 0;
-        }
-        current.push(ch);
-        used += char_width;
-    }
-
-    if !current.is_empty() {
-        chunks.push(current);
-    }
-
-    chunks
+    Span::styled(description, Style::default().fg(theme::text_secondary()))
 }
 
 fn should_split_shortcut_group(group: &str) -> bool {
@@ -347,6 +365,23 @@ fn pad_line(line: &mut Line<'static>, width: u16) {
     }
 }
 
+/// The footer while filtering: the filter, with a cursor while it is typed
+fn help_filter_footer_line(filter: &str, typing: bool) -> Line<'static> {
+    let hint = if typing {
+        "  |  Enter keep  |  Esc clear"
+    } else {
+        "  |  / change  |  Esc clear"
+    };
+    let cursor = if typing { "_" } else { "" };
+    Line::from(vec![
+        Span::styled(
+            format!("Filter: {filter}{cursor}"),
+            Style::default().fg(theme::accent()),
+        ),
+        Span::styled(hint, Style::default().fg(theme::text_secondary())),
+    ])
+}
+
 fn help_footer_line(scroll: usize, visible_lines: usize, max_scroll: usize) -> Line<'static> {
     let total_pages = if max_scroll == 0 {
         1
@@ -356,14 +391,17 @@ fn help_footer_line(scroll: usize, visible_lines: usize, max_scroll: usize) -> L
     let current_page = (scroll / visible_lines).saturating_add(1).min(total_pages);
 
     Line::from(vec![
-        Span::styled("Press ESC or q to close", Style::default().fg(theme::TEXT)),
         Span::styled(
-            "  |  j/k scroll  |  ",
-            Style::default().fg(theme::TEXT_SECONDARY),
+            "Press ESC or q to close",
+            Style::default().fg(theme::text()),
+        ),
+        Span::styled(
+            "  |  j/k scroll  |  / filter  |  ",
+            Style::default().fg(theme::text_secondary()),
         ),
         Span::styled(
             format!("Page {current_page}/{total_pages}"),
-            Style::default().fg(theme::ACCENT),
+            Style::default().fg(theme::accent()),
         ),
     ])
 }
@@ -0,0 +1,190 @@
+use anyhow::{anyhow, Result};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout, Margin, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Gauge, Paragraph},
+    Frame, Terminal,
+};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+use crate::excel::{open_workbook_reporting, LoadProgress, Workbook};
+use crate::ui::theme;
+
+const SPINNER: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+const LOADING_WIDTH: u16 = 56;
+const LOADING_HEIGHT: u16 = 7;
+
+/// What the thread reading workbooks sends back to the UI
+enum LoadEvent {
+    Progress(LoadProgress),
+    Opened(Result<Workbook>),
+}
+
+/// Reads `files` on a background thread while drawing their progress, so a large workbook
+/// does not leave the terminal blank. `None` when the user cancels with Esc or Ctrl+C
+pub(super) fn open_workbooks<B: Backend>(
+    terminal: &mut Terminal<B>,
+    files: &[PathBuf],
+    lazy_loading: bool,
+) -> Result<Option<Vec<Workbook>>> {
+    let (sender, receiver) = mpsc::channel();
+    let paths = files.to_vec();
+    thread::spawn(move || {
+        for path in paths {
+            let progress_sender = sender.clone();
+            let result = open_workbook_reporting(&path, lazy_loading, |progress| {
+                let _ = progress_sender.send(LoadEvent::Progress(progress.clone()));
+            });
+            if sender.send(LoadEvent::Opened(result)).is_err() {
+                return;
+            }
+        }
+    });
+
+    let mut workbooks = Vec::with_capacity(files.len());
+    let mut progress = None;
+    let mut tick = 0;
+    while let Some(file) = files.get(workbooks.len()) {
+        terminal.draw(|f| draw_loading_screen(f, file, progress.as_ref(), tick))?;
+        tick += 1;
+
+        let mut next = receiver.recv_timeout(Duration::from_millis(80));
+        loop {
+            match next {
+                Ok(LoadEvent::Progress(latest)) => progress = Some(latest),
+                Ok(LoadEvent::Opened(result)) => {
+                    workbooks.push(result?);
+                    progress = None;
+                }
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => {
+                    return Err(anyhow!("Stopped reading {}", file.display()));
+                }
+            }
+            // Only the latest report is drawn, so catch up on any that queued meanwhile
+            next = receiver.try_recv().map_err(|_| RecvTimeoutError::Timeout);
+        }
+
+        while event::poll(Duration::ZERO)? {
+            if let Event::Key(key) = event::read()? {
+                let is_ctrl_c =
+                    key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL);
+                if key.kind == KeyEventKind::Press && (key.code == KeyCode::Esc || is_ctrl_c) {
+                    return Ok(None);
+                }
+            }
+        }
+    }
+    Ok(Some(workbooks))
+}
+
+/// A box naming the file and sheet being read, with a bar of the rows read so far, or a
+/// spinner while the sheet's size is unknown
+pub(super) fn draw_loading_screen(
+    f: &mut Frame,
+    file: &Path,
+    progress: Option<&LoadProgress>,
+    tick: usize,
+) {
+    let area = f.area();
+    f.render_widget(Clear, area);
+    f.render_widget(Block::default().style(theme::base()), area);
+
+    let width = LOADING_WIDTH.min(area.width);
+    let height = LOADING_HEIGHT.min(area.height);
+    let popup = Rect {
+        x: area.x + area.width.saturating_sub(width) / 2,
+        y: area.y + area.height.saturating_sub(height) / 2,
+        width,
+        height,
+    };
+    let file_name = file.file_name().map_or_else(
+        || file.display().to_string(),
+        |name| name.to_string_lossy().to_string(),
+    );
+    let block = Block::default()
+        .title(format!(" Opening {file_name} "))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme::accent()))
+        .style(theme::surface());
+    let inner = block.inner(popup);
+    f.render_widget(block, popup);
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Min(0),
+        ])
+        .split(inner.inner(Margin::new(1, 0)));
+
+    let sheet = match progress {
+        Some(progress) => format!(
+            "Sheet {}/{}: {}",
+            progress.sheet_index + 1,
+            progress.sheet_count,
+            progress.sheet_name
+        ),
+        None => "Reading workbook".to_string(),
+    };
+    f.render_widget(
+        Paragraph::new(Line::from(vec![
+            Span::styled(
+                format!("{} ", spinner(tick)),
+                Style::default().fg(theme::accent()),
+            ),
+            Span::styled(sheet, Style::default().add_modifier(Modifier::BOLD)),
+        ])),
+        rows[0],
+    );
+
+    if let Some(progress) = progress {
+        draw_rows_read(f, progress, rows[2]);
+    }
+
+    f.render_widget(
+        Paragraph::new("Esc cancel").style(Style::default().fg(theme::text_disabled())),
+        rows[3],
+    );
+}
+
+/// The spinner frame for the `tick`th redraw
+pub(super) fn spinner(tick: usize) -> &'static str {
+    SPINNER[tick % SPINNER.len()]
+}
+
+/// A bar of the rows read out of the rows the sheet declares, or just the count when it
+/// declares none
+pub(super) fn draw_rows_read(f: &mut Frame, progress: &LoadProgress, area: Rect) {
+    let rows_read = progress.rows_read;
+    match progress.total_rows.filter(|&total| total >= rows_read) {
+        Some(total_rows) => {
+            let gauge = Gauge::default()
+                .gauge_style(
+                    Style::default()
+                        .fg(theme::accent())
+                        .bg(theme::surface_muted()),
+                )
+                .ratio(rows_read as f64 / total_rows.max(1) as f64)
+                .label(format!("{rows_read} / {total_rows} rows"));
+            f.render_widget(gauge, area);
+        }
+        None if rows_read > 0 => {
+            f.render_widget(
+                Paragraph::new(format!("{rows_read} rows read"))
+                    .style(Style::default().fg(theme::text_secondary())),
+                area,
+            );
+        }
+        None => {}
+    }
+}
@@ -0,0 +1,110 @@
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+
+use crate::app::AppState;
+use crate::ui::theme;
+
+use super::panel_block;
+
+/// Width of the minimap including borders
+const MINIMAP_WIDTH: u16 = 22;
+
+/// Glyphs for bins from empty to completely filled
+const DENSITY: [char; 5] = [' ', '░', '▒', '▓', '█'];
+
+/// Width the minimap takes from the grid, or 0 when it is off or the grid would get too narrow
+pub(super) fn minimap_width(app_state: &AppState, available: u16) -> u16 {
+    if app_state.show_minimap && available >= MINIMAP_WIDTH * 3 {
+        MINIMAP_WIDTH
+    } else {
+        0
+    }
+}
+
+/// Draws the sheet scaled down to `area`: each character stands for a block of cells, shaded
+/// by how many of them hold data, with search hits, the viewport and the cursor marked
+pub(super) fn draw_minimap(f: &mut Frame, app_state: &AppState, area: Rect) {
+    let block = panel_block(" Map ".to_string(), theme::text_secondary());
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    if inner.width == 0 || inner.height == 0 {
+        return;
+    }
+
+    let sheet = app_state.workbook.get_current_sheet();
+    let (cursor_row, cursor_col) = app_state.selected_cell;
+    let view_end_row = app_state.start_row + app_state.visible_rows.max(1) - 1;
+    let view_end_col = app_state.start_col + app_state.visible_cols.max(1) - 1;
+    let rows = sheet.max_rows.max(view_end_row).max(cursor_row);
+    let cols = sheet.max_cols.max(view_end_col).max(cursor_col);
+
+    let (bin_rows, bin_cols) = (inner.height as usize, inner.width as usize);
+    let rows_per_bin = rows.div_ceil(bin_rows).max(1);
+    let cols_per_bin = cols.div_ceil(bin_cols).max(1);
+    let bin_of = |row: usize, col: usize| {
+        let bin_row = (row - 1) / rows_per_bin;
+        let bin_col = (col - 1) / cols_per_bin;
+        (bin_row < bin_rows && bin_col < bin_cols).then_some(bin_row * bin_cols + bin_col)
+    };
+
+    let mut filled = vec![0usize; bin_rows * bin_cols];
+    for ((row, col), cell) in sheet.data.iter() {
+        if !cell.value.is_empty() {
+            if let Some(bin) = bin_of(row, col) {
+                filled[bin] += 1;
+            }
+        }
+    }
+
+    let mut hits = vec![false; bin_rows * bin_cols];
+    if app_state.highlight_enabled {
+        for &(row, col) in &app_state.search_results {
+            if let Some(bin) = bin_of(row, col) {
+                hits[bin] = true;
+            }
+        }
+    }
+
+    let view_rows = (app_state.start_row - 1) / rows_per_bin..=(view_end_row - 1) / rows_per_bin;
+    let view_cols = (app_state.start_col - 1) / cols_per_bin..=(view_end_col - 1) / cols_per_bin;
+    let cursor_bin = bin_of(cursor_row, cursor_col);
+    let bin_size = rows_per_bin * cols_per_bin;
+
+    let lines: Vec<Line> = (0..bin_rows)
+        .map(|bin_row| {
+            let spans: Vec<Span> = (0..bin_cols)
+                .map(|bin_col| {
+                    let bin = bin_row * bin_cols + bin_col;
+                    let level = if filled[bin] == 0 {
+                        0
+                    } else {
+                        (filled[bin] * 4).div_ceil(bin_size).clamp(1, 4)
+                    };
+
+                    let mut style = Style::default().fg(theme::text_secondary());
+                    if view_rows.contains(&bin_row) && view_cols.contains(&bin_col) {
+                        style = style.bg(theme::selection());
+                    }
+                    if hits[bin] {
+                        style = style.fg(theme::search());
+                    }
+                    let glyph = if cursor_bin == Some(bin) {
+                        style = style.fg(theme::accent()).add_modifier(Modifier::BOLD);
+                        '+'
+                    } else {
+                        DENSITY[level]
+                    };
+                    Span::styled(glyph.to_string(), style)
+                })
+                .collect();
+            Line::from(spans)
+        })
+        .collect();
+
+    f.render_widget(Paragraph::new(lines).style(theme::surface()), inner);
+}
@@ -1,57 +1,108 @@
 use anyhow::Result;
 use crossterm::{
-    event::{self, Event, KeyEventKind},
+    event::{self, DisableBracketedPaste, EnableBracketedPaste, Event, KeyEventKind},
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
     ExecutableCommand,
 };
 use ratatui::{
     backend::CrosstermBackend,
-    layout::{Constraint, Direction, Layout, Rect},
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
     widgets::{Block, Borders, Clear, Paragraph},
     Frame, Terminal,
 };
-use std::{io, time::Duration};
+use std::{io, path::PathBuf, time::Duration};
 
 mod help_overlay;
+mod loading;
+mod minimap;
+mod onboarding;
+mod pager;
+mod paste_dialog;
+mod results_panel;
 mod spreadsheet;
 mod status;
 
 use help_overlay::draw_help_popup;
+use loading::{draw_rows_read, open_workbooks, spinner};
+use minimap::{draw_minimap, minimap_width};
+use onboarding::draw_onboarding_popup;
+use pager::draw_pager;
+use paste_dialog::draw_fixed_width_import_popup;
+use results_panel::{draw_results_panel, results_panel_height};
 use spreadsheet::{draw_spreadsheet, draw_title_with_tabs, update_visible_area};
 use status::{draw_status_bar, status_bar_height};
 
 #[cfg(test)]
-use help_overlay::{help_entry_lines, help_overlay_lines};
+use help_overlay::{help_entry_lines, help_lines};
 
 use crate::app::AppState;
+use crate::app::InfoPanelPosition;
 use crate::app::InputMode;
+use crate::app::MessageLevel;
+use crate::app::Session;
+use crate::app::SplitDirection;
+use crate::app::Syntax;
+use crate::app::TokenKind;
 use crate::app::VimMode;
-use crate::ui::handlers::handle_key_event;
+use crate::excel::Workbook;
+use crate::ui::handlers::{handle_key_event, handle_paste_event};
 use crate::ui::theme;
-use crate::utils::cell_reference;
-
-pub fn run_app(mut app_state: AppState) -> Result<()> {
+use crate::utils::{cell_reference, split_to_width, text_width};
+
+/// Widest the message over a sheet still loading is drawn
+const LAZY_LOADING_WIDTH: u16 = 64;
+
+/// Opens `files` behind a progress screen, then runs the session `start` makes of them.
+/// With `lazy_loading` only the sheet names are read up front
+pub fn run_app(
+    files: &[PathBuf],
+    lazy_loading: bool,
+    start: impl FnOnce(Vec<Workbook>) -> Result<Session<'static>>,
+) -> Result<()> {
     // Setup terminal
     let mut terminal = setup_terminal()?;
 
+    let result = match open_workbooks(&mut terminal, files, lazy_loading) {
+        Ok(Some(workbooks)) => {
+            start(workbooks).and_then(|session| run_session(&mut terminal, session))
+        }
+        Ok(None) => Ok(()),
+        Err(e) => Err(e),
+    };
+
+    // Restore terminal
+    restore_terminal(&mut terminal)?;
+
+    result
+}
+
+fn run_session(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    mut session: Session,
+) -> Result<()> {
     // Main event loop
-    while !app_state.should_quit {
-        terminal.draw(|f| ui(f, &mut app_state))?;
+    while !session.is_finished() {
+        terminal.draw(|f| ui(f, session.current_mut()))?;
 
         if event::poll(Duration::from_millis(50))? {
-            if let Event::Key(key) = event::read()? {
-                if key.kind == KeyEventKind::Press {
-                    handle_key_event(&mut app_state, key);
+            match event::read()? {
+                Event::Key(key) if key.kind == KeyEventKind::Press => {
+                    handle_key_event(session.current_mut(), key);
                 }
+                Event::Paste(text) => handle_paste_event(session.current_mut(), &text),
+                _ => {}
             }
+            session.apply_requests();
+        }
+        if !session.is_finished() {
+            session.current_mut().run_pending_incremental_search();
+            session.current_mut().poll_sheet_loads();
+            session.current_mut().poll_search_index();
         }
     }
 
-    // Restore terminal
-    restore_terminal(&mut terminal)?;
-
     Ok(())
 }
 
@@ -60,6 +111,7 @@ fn setup_terminal() -> Result<Terminal<CrosstermBackend<io::Stdout>>> {
     enable_raw_mode()?;
     let mut stdout = io::stdout();
     stdout.execute(EnterAlternateScreen)?;
+    stdout.execute(EnableBracketedPaste)?;
 
     let backend = CrosstermBackend::new(stdout);
     let terminal = Terminal::new(backend)?;
@@ -70,6 +122,7 @@ fn setup_terminal() -> Result<Terminal<CrosstermBackend<io::Stdout>>> {
 /// Restore the terminal to its original state
 fn restore_terminal(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> Result<()> {
     disable_raw_mode()?;
+    terminal.backend_mut().execute(DisableBracketedPaste)?;
     terminal.backend_mut().execute(LeaveAlternateScreen)?;
     terminal.show_cursor()?;
 
@@ -80,22 +133,65 @@ fn ui(f: &mut Frame, app_state: &mut AppState) {
     let area = f.area();
     f.render_widget(Clear, area);
     let status_bar_height = status_bar_height(app_state, area.width);
+    let info_panel = app_state
+        .info_panel_visible()
+        .then_some(app_state.info_panel_position);
+    let info_panel_height = match info_panel {
+        Some(InfoPanelPosition::Bottom) => app_state.info_panel_height as u16,
+        _ => 0,
+    };
 
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Length(1),
             Constraint::Min(1),
-            Constraint::Length(app_state.info_panel_height as u16),
+            Constraint::Length(info_panel_height),
             Constraint::Length(status_bar_height),
         ])
         .split(area);
 
     draw_title_with_tabs(f, app_state, chunks[0]);
 
-    update_visible_area(app_state, chunks[1]);
-    draw_spreadsheet(f, app_state, chunks[1]);
-    draw_info_panel(f, app_state, chunks[2]);
+    // Beside the grid the panel takes at most half the width
+    let (content, info_area) = match info_panel {
+        Some(InfoPanelPosition::Bottom) => (chunks[1], Some(chunks[2])),
+        Some(InfoPanelPosition::Right) => {
+            let width = (app_state.info_panel_width as u16).min(chunks[1].width / 2);
+            let columns = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Min(1), Constraint::Length(width)])
+                .split(chunks[1]);
+            (columns[0], Some(columns[1]))
+        }
+        None => (chunks[1], None),
+    };
+
+    let results_height = results_panel_height(app_state.results.as_ref(), content.height);
+    let grid_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(results_height)])
+        .split(content);
+
+    let minimap_width = minimap_width(app_state, grid_chunks[0].width);
+    let map_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Min(1), Constraint::Length(minimap_width)])
+        .split(grid_chunks[0]);
+
+    draw_panes(f, app_state, map_chunks[0]);
+    if minimap_width > 0 {
+        draw_minimap(f, app_state, map_chunks[1]);
+    }
+    if results_height > 0 {
+        let focused = matches!(app_state.input_mode, InputMode::Results);
+        if let Some(panel) = app_state.results.as_mut() {
+            draw_results_panel(f, panel, focused, grid_chunks[1]);
+        }
+    }
+    if let Some(info_area) = info_area {
+        draw_info_panel(f, app_state, info_area);
+    }
     if status_bar_height > 0 {
         draw_status_bar(f, app_state, chunks[3]);
     }
@@ -105,12 +201,26 @@ fn ui(f: &mut Frame, app_state: &mut AppState) {
         draw_help_popup(f, app_state, area);
     }
 
+    if let (InputMode::Pager, Some(pager)) = (&app_state.input_mode, app_state.pager.as_mut()) {
+        draw_pager(f, pager, content);
+    }
+
+    if let InputMode::Onboarding = app_state.input_mode {
+        draw_onboarding_popup(f, area);
+    }
+
+    if let (InputMode::FixedWidthImport, Some(import)) =
+        (&app_state.input_mode, &app_state.fixed_width_import)
+    {
+        draw_fixed_width_import_popup(f, import, content);
+    }
+
     // If in lazy loading mode or CommandInLazyLoading mode and the current sheet is not loaded, draw the lazy loading overlay
     match app_state.input_mode {
         InputMode::LazyLoading | InputMode::CommandInLazyLoading => {
             let current_index = app_state.workbook.get_current_sheet_index();
             if !app_state.workbook.is_sheet_loaded(current_index) {
-                draw_lazy_loading_overlay(f, app_state, chunks[1]);
+                draw_lazy_loading_overlay(f, app_state, content);
             } else if matches!(app_state.input_mode, InputMode::LazyLoading) {
                 // If the sheet is loaded, switch back to Normal mode
                 app_state.input_mode = crate::app::InputMode::Normal;
@@ -120,9 +230,38 @@ fn ui(f: &mut Frame, app_state: &mut AppState) {
     }
 }
 
+/// Draws the grid, or both panes of a split with the focused one in its place
+fn draw_panes(f: &mut Frame, app_state: &mut AppState, area: Rect) {
+    let Some(split) = app_state.split.as_ref() else {
+        update_visible_area(app_state, area);
+        draw_spreadsheet(f, app_state, area, true);
+        return;
+    };
+
+    let direction = match split.direction {
+        SplitDirection::Horizontal => Direction::Vertical,
+        SplitDirection::Vertical => Direction::Horizontal,
+    };
+    let panes = Layout::default()
+        .direction(direction)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(area);
+    let (focused_area, other_area) = if split.focus_second {
+        (panes[1], panes[0])
+    } else {
+        (panes[0], panes[1])
+    };
+
+    update_visible_area(app_state, focused_area);
+    draw_spreadsheet(f, app_state, focused_area, true);
+    app_state.with_other_pane(|app_state| {
+        update_visible_area(app_state, other_area);
+        draw_spreadsheet(f, app_state, other_area, false);
+    });
+}
+
 pub(super) fn display_width(text: &str) -> u16 {
-    text.chars()
-        .fold(0, |acc, ch| acc + if ch.is_ascii() { 1 } else { 2 })
+    u16::try_from(text_width(text)).unwrap_or(u16::MAX)
 }
 
 pub(super) fn line_display_width(line: &Line<'_>) -> u16 {
@@ -132,6 +271,67 @@ pub(super) fn line_display_width(line: &Line<'_>) -> u16 {
         .sum()
 }
 
+/// Breaks text into lines of at most `width` columns at spaces, splitting words too long
+/// for a line
+pub(super) fn wrap_text(text: &str, width: u16) -> Vec<String> {
+    if width == 0 {
+        return Vec::new();
+    }
+
+    let mut lines = Vec::new();
+    let mut current = String::new();
+
+    for word in text.split_whitespace() {
+        append_wrapped_word(&mut lines, &mut current, word, width);
+    }
+
+    if !current.is_empty() {
+        lines.push(current);
+    }
+
+    lines
+}
+
+fn append_wrapped_word(lines: &mut Vec<String>, current: &mut String, word: &str, width: u16) {
+    let word_width = display_width(word);
+    let current_width = display_width(current);
+
+    if current.is_empty() && word_width <= width {
+        current.push_str(word);
+    } else if !current.is_empty() && current_width + 1 + word_width <= width {
+        current.push(' ');
+        current.push_str(word);
+    } else {
+        if !current.is_empty() {
+            lines.push(std::mem::take(current));
+        }
+        append_word_chunks(lines, current, word, width);
+    }
+}
+
+fn append_word_chunks(lines: &mut Vec<String>, current: &mut String, word: &str, width: u16) {
+    if display_width(word) <= width {
+        current.push_str(word);
+        return;
+    }
+
+    for chunk in split_word_to_width(word, width) {
+        if current.is_empty() {
+            current.push_str(&chunk);
+        } else {
+            lines.push(std::mem::take(current));
+            current.push_str(&chunk);
+        }
+    }
+}
+
+fn split_word_to_width(word: &str, width: u16) -> Vec<String> {
+    split_to_width(word, usize::from(width))
+        .into_iter()
+        .map(str::to_string)
+        .collect()
+}
+
 fn draw_info_panel(f: &mut Frame, app_state: &mut AppState, area: Rect) {
     if area.height < 4 {
         if matches!(app_state.input_mode, InputMode::Editing) {
@@ -156,14 +356,45 @@ fn draw_info_panel(f: &mut Frame, app_state: &mut AppState, area: Rect) {
 }
 
 fn draw_cell_details(f: &mut Frame, app_state: &AppState, area: Rect) {
-    let content = app_state.get_cell_content(app_state.selected_cell.0, app_state.selected_cell.1);
+    let (row, col) = app_state.selected_cell;
+    let content = app_state.get_cell_content(row, col);
     let cell_ref = cell_reference(app_state.selected_cell);
-    let value_type = cell_value_type(&content);
+    let structured = Syntax::pretty_print(&content);
+    let value_type = structured
+        .as_ref()
+        .map_or_else(|| cell_value_type(&content), |(syntax, _)| syntax.name());
     let length = content.chars().count();
 
     let title = format!(" Cell {cell_ref}  {value_type}  Len {length} ");
-    let block = panel_block(title, theme::TEXT);
-    let paragraph = Paragraph::new(content)
+    let block = panel_block(title, theme::text());
+    // JSON and XML payloads are indented and colored
+    let mut lines: Vec<Line> = match &structured {
+        Some((syntax, pretty)) => syntax
+            .highlight(&pretty.lines().collect::<Vec<_>>())
+            .iter()
+            .map(|pieces| highlighted_line(pieces))
+            .collect(),
+        None => content.lines().map(Line::from).collect(),
+    };
+    if let Some(comment) = app_state.cell_comment(row, col) {
+        lines.push(Line::from(vec![
+            Span::styled("Comment: ", Style::default().fg(theme::warning())),
+            Span::styled(comment.to_string(), Style::default().fg(theme::text())),
+        ]));
+    }
+    let hyperlink = app_state
+        .workbook
+        .get_current_sheet()
+        .data
+        .get(row, col)
+        .and_then(|cell| cell.hyperlink.as_deref());
+    if let Some(link) = hyperlink {
+        lines.push(Line::from(vec![
+            Span::styled("Link: ", Style::default().fg(theme::accent())),
+            Span::styled(link.to_string(), Style::default().fg(theme::text())),
+        ]));
+    }
+    let paragraph = Paragraph::new(lines)
         .block(block)
         .style(theme::surface())
         .wrap(ratatui::widgets::Wrap { trim: false });
@@ -173,7 +404,7 @@ fn draw_cell_details(f: &mut Frame, app_state: &AppState, area: Rect) {
 fn draw_editing_panel(f: &mut Frame, app_state: &AppState, area: Rect) {
     let cell_ref = cell_reference(app_state.selected_cell);
     let mode = app_state.vim_state.as_ref().map(|state| state.mode);
-    let input_block = panel_block_line(editing_title_line(cell_ref, mode), theme::ACCENT);
+    let input_block = panel_block_line(editing_title_line(cell_ref, mode), theme::accent());
     let inner_area = input_block.inner(area);
     let padded_area = Rect {
         x: inner_area.x.saturating_add(1),
@@ -190,7 +421,7 @@ fn draw_notifications(f: &mut Frame, app_state: &AppState, area: Rect) {
     let lines = if app_state.notification_messages.is_empty() {
         vec![Line::from(Span::styled(
             "No notifications",
-            Style::default().fg(theme::TEXT_SECONDARY),
+            Style::default().fg(theme::text_secondary()),
         ))]
     } else {
         app_state
@@ -199,19 +430,24 @@ fn draw_notifications(f: &mut Frame, app_state: &AppState, area: Rect) {
             .rev()
             .take(4)
             .enumerate()
-            .map(|(index, message)| {
-                let color = if index == 0 {
-                    theme::TEXT
-                } else {
-                    theme::TEXT_SECONDARY
+            .map(|(index, notification)| {
+                // Errors and warnings keep their color after newer messages arrive
+                let color = match notification.level {
+                    MessageLevel::Error => theme::error(),
+                    MessageLevel::Warning => theme::warning(),
+                    MessageLevel::Info if index == 0 => theme::text(),
+                    MessageLevel::Info => theme::text_secondary(),
                 };
-                Line::from(Span::styled(message.clone(), Style::default().fg(color)))
+                Line::from(Span::styled(
+                    notification.text.clone(),
+                    Style::default().fg(color),
+                ))
             })
             .collect()
     };
 
     let paragraph = Paragraph::new(lines)
-        .block(panel_block(" NOTIFICATIONS ".to_string(), theme::TEXT))
+        .block(panel_block(" NOTIFICATIONS ".to_string(), theme::text()))
         .style(theme::surface())
         .wrap(ratatui::widgets::Wrap { trim: false });
     f.render_widget(paragraph, area);
@@ -222,7 +458,7 @@ fn panel_block(title: String, border_color: Color) -> Block<'static> {
         Line::from(Span::styled(
             title,
             Style::default()
-                .fg(theme::TEXT)
+                .fg(theme::text())
                 .add_modifier(Modifier::BOLD),
         )),
         border_color,
@@ -242,13 +478,13 @@ fn editing_title_line(cell_ref: String, mode: Option<VimMode>) -> Line<'static>
         Span::styled(
             " Editing Cell ",
             Style::default()
-                .fg(theme::TEXT)
+                .fg(theme::text())
                 .add_modifier(Modifier::BOLD),
         ),
         Span::styled(
             cell_ref,
             Style::default()
-                .fg(theme::TEXT)
+                .fg(theme::text())
                 .add_modifier(Modifier::BOLD),
         ),
     ];
@@ -257,7 +493,7 @@ fn editing_title_line(cell_ref: String, mode: Option<VimMode>) -> Line<'static>
         spans.push(Span::styled(
             " - ",
             Style::default()
-                .fg(theme::TEXT)
+                .fg(theme::text())
                 .add_modifier(Modifier::BOLD),
         ));
         spans.push(Span::styled(
@@ -271,7 +507,7 @@ fn editing_title_line(cell_ref: String, mode: Option<VimMode>) -> Line<'static>
     spans.push(Span::styled(
         " ",
         Style::default()
-            .fg(theme::TEXT)
+            .fg(theme::text())
             .add_modifier(Modifier::BOLD),
     ));
 
@@ -280,10 +516,32 @@ fn editing_title_line(cell_ref: String, mode: Option<VimMode>) -> Line<'static>
 
 fn vim_mode_color(mode: VimMode) -> Color {
     match mode {
-        VimMode::Normal => theme::SUCCESS,
-        VimMode::Insert => theme::ACCENT,
-        VimMode::Visual => theme::SEARCH,
-        VimMode::Operator(_) => theme::WARNING,
+        VimMode::Normal => theme::success(),
+        VimMode::Insert => theme::accent(),
+        VimMode::Visual => theme::search(),
+        VimMode::Operator(_) => theme::warning(),
+    }
+}
+
+/// A line of JSON or XML with each piece in its color
+pub(super) fn highlighted_line(pieces: &[(TokenKind, String)]) -> Line<'static> {
+    Line::from(
+        pieces
+            .iter()
+            .map(|(kind, text)| Span::styled(text.clone(), Style::default().fg(token_color(*kind))))
+            .collect::<Vec<_>>(),
+    )
+}
+
+fn token_color(kind: TokenKind) -> Color {
+    match kind {
+        TokenKind::Plain => theme::text(),
+        TokenKind::Punctuation => theme::text_secondary(),
+        TokenKind::Key | TokenKind::Tag => theme::accent(),
+        TokenKind::String => theme::success(),
+        TokenKind::Number => theme::search(),
+        TokenKind::Keyword | TokenKind::Attribute => theme::warning(),
+        TokenKind::Comment => theme::text_disabled(),
     }
 }
 
@@ -299,37 +557,73 @@ fn cell_value_type(content: &str) -> &'static str {
     }
 }
 
-fn draw_lazy_loading_overlay(f: &mut Frame, _app_state: &AppState, area: Rect) {
-    // Create a semi-transparent overlay
+fn draw_lazy_loading_overlay(f: &mut Frame, app_state: &AppState, area: Rect) {
     let overlay = Block::default()
         .style(theme::surface())
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(theme::ACCENT));
-
+        .border_style(Style::default().fg(theme::accent()));
+    let inner = overlay.inner(area);
     f.render_widget(Clear, area);
     f.render_widget(overlay, area);
 
-    // Calculate center position for the message
-    let message = "Sheet not loaded   Enter load   [ ] switch sheet   : command";
-    let width = message.len() as u16;
-    let x = area.x + (area.width.saturating_sub(width)) / 2;
-    let y = area.y + area.height / 2;
-
-    if x < area.width && y < area.height {
-        let message_area = Rect {
-            x,
-            y,
-            width: width.min(area.width),
-            height: 1,
-        };
-
-        let message_widget = Paragraph::new(message).style(
-            Style::default()
-                .fg(theme::WARNING)
-                .add_modifier(Modifier::BOLD),
-        );
-
-        f.render_widget(message_widget, message_area);
+    // The message, and under it the rows read so far, centered where the grid goes
+    let width = inner.width.min(LAZY_LOADING_WIDTH);
+    let rows = Rect {
+        x: inner.x + inner.width.saturating_sub(width) / 2,
+        y: inner.y + inner.height.saturating_sub(2) / 2,
+        width,
+        height: inner.height.min(2),
+    };
+    let sheet_name = app_state.workbook.get_current_sheet_name();
+    let load = app_state.current_sheet_load();
+    let message = match load {
+        Some(load) if load.error.is_some() => Line::from(vec![
+            Span::styled(
+                format!("Could not load {sheet_name}"),
+                Style::default()
+                    .fg(theme::warning())
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                "   Enter retry   [ ] switch sheet",
+                Style::default().fg(theme::text_secondary()),
+            ),
+        ]),
+        _ => {
+            let tick = load.map_or(0, |load| load.started.elapsed().as_millis() as usize / 80);
+            Line::from(vec![
+                Span::styled(
+                    format!("{} ", spinner(tick)),
+                    Style::default().fg(theme::accent()),
+                ),
+                Span::styled(
+                    format!("Loading {sheet_name}…"),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(
+                    "   [ ] switch sheet",
+                    Style::default().fg(theme::text_secondary()),
+                ),
+            ])
+        }
+    };
+    f.render_widget(
+        Paragraph::new(message).alignment(Alignment::Center),
+        Rect { height: 1, ..rows },
+    );
+
+    if let Some(progress) = load.and_then(|load| load.progress.as_ref()) {
+        if rows.height > 1 {
+            draw_rows_read(
+                f,
+                progress,
+                Rect {
+                    y: rows.y + 1,
+                    height: 1,
+                    ..rows
+                },
+            );
+        }
     }
 }
 
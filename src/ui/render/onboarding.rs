@@ -0,0 +1,103 @@
+use ratatui::{
+    layout::{Alignment, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::ui::theme;
+
+/// Keys worth knowing on day one, as `(keys, what they do)`
+const ONBOARDING_KEYS: [(&str, &str); 8] = [
+    ("h j k l / arrows", "Move around the grid"),
+    (
+        "Enter or i",
+        "Edit the cell with vim keys: i to type, Esc to save",
+    ),
+    ("[ and ]", "Previous / next sheet"),
+    ("/ then n N", "Search and step through matches"),
+    ("v / Shift+arrows", "Select a block of cells"),
+    ("u / Ctrl+r", "Undo / redo"),
+    (":w  :q", "Save / quit (commands start with :)"),
+    (":help", "Full key reference (also ? on this screen)"),
+];
+
+pub(super) fn draw_onboarding_popup(f: &mut Frame, area: Rect) {
+    let popup_width = area.width.saturating_sub(4).min(72);
+    let popup_height = area.height.saturating_sub(2).min(19);
+    let popup_area = Rect::new(
+        area.x + area.width.saturating_sub(popup_width) / 2,
+        area.y + area.height.saturating_sub(popup_height) / 2,
+        popup_width,
+        popup_height,
+    );
+
+    let block = Block::default()
+        .title(" WELCOME TO EXCEL-CLI ")
+        .title_alignment(Alignment::Center)
+        .title_style(
+            Style::default()
+                .fg(theme::accent())
+                .add_modifier(Modifier::BOLD),
+        )
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme::text_secondary()))
+        .style(theme::surface());
+    let inner = block.inner(popup_area);
+
+    f.render_widget(Clear, popup_area);
+    f.render_widget(block, popup_area);
+
+    let paragraph = Paragraph::new(onboarding_lines())
+        .style(theme::surface())
+        .wrap(Wrap { trim: false });
+    f.render_widget(paragraph, inner);
+}
+
+fn onboarding_lines() -> Vec<Line<'static>> {
+    let mut lines = vec![
+        Line::from(Span::styled(
+            "Keys act on the grid (normal mode) until you start editing, like in vim.",
+            Style::default().fg(theme::text()),
+        )),
+        Line::default(),
+    ];
+
+    let key_width = ONBOARDING_KEYS
+        .iter()
+        .map(|(keys, _)| keys.len())
+        .max()
+        .unwrap_or(0);
+    lines.extend(ONBOARDING_KEYS.iter().map(|(keys, description)| {
+        Line::from(vec![
+            Span::styled(
+                format!("  {keys:<key_width$}  "),
+                Style::default()
+                    .fg(theme::accent())
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(*description, Style::default().fg(theme::text())),
+        ])
+    }));
+
+    lines.extend([
+        Line::default(),
+        Line::from(vec![
+            Span::styled("s", Style::default().fg(theme::warning())),
+            Span::styled(
+                " open a sample workbook with sheets, dates, formulas and wide columns",
+                Style::default().fg(theme::text()),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("Enter", Style::default().fg(theme::warning())),
+            Span::styled(
+                " start with your file (this screen is shown only once)",
+                Style::default().fg(theme::text()),
+            ),
+        ]),
+    ]);
+
+    lines
+}
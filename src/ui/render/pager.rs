@@ -0,0 +1,94 @@
+use ratatui::{
+    layout::{Alignment, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use super::highlighted_line;
+use crate::app::PagerView;
+use crate::ui::theme;
+
+pub(super) fn draw_pager(f: &mut Frame, pager: &mut PagerView, area: Rect) {
+    let block = Block::default()
+        .title(format!(" {} ", pager.title))
+        .title_alignment(Alignment::Center)
+        .title_style(
+            Style::default()
+                .fg(theme::accent())
+                .add_modifier(Modifier::BOLD),
+        )
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme::text_secondary()))
+        .style(theme::surface());
+    let inner = block.inner(area);
+
+    f.render_widget(Clear, area);
+    f.render_widget(block, area);
+
+    if inner.height < 2 || inner.width < 10 {
+        return;
+    }
+
+    let content_area = Rect {
+        x: inner.x.saturating_add(1),
+        y: inner.y,
+        width: inner.width.saturating_sub(2),
+        height: inner.height - 1,
+    };
+    let footer_area = Rect::new(inner.x, inner.y + inner.height - 1, inner.width, 1);
+
+    pager.wrap_to(content_area.width as usize);
+    pager.visible_lines = content_area.height.max(1) as usize;
+    pager.scroll = pager.scroll.min(pager.max_scroll());
+
+    let current_line = pager
+        .current_match
+        .and_then(|index| pager.matches.get(index))
+        .copied();
+    let lines: Vec<Line> = pager
+        .lines
+        .iter()
+        .enumerate()
+        .skip(pager.scroll)
+        .take(pager.visible_lines)
+        .map(|(index, line)| {
+            let style = if Some(index) == current_line {
+                Style::default().bg(theme::search()).fg(theme::background())
+            } else if pager.matches.binary_search(&index).is_ok() {
+                Style::default().fg(theme::search())
+            } else if let Some(pieces) = pager.highlighted_line(index) {
+                return highlighted_line(pieces);
+            } else {
+                Style::default().fg(theme::text())
+            };
+            Line::from(Span::styled(line.clone(), style))
+        })
+        .collect();
+    f.render_widget(Paragraph::new(lines).style(theme::surface()), content_area);
+
+    let last_visible = (pager.scroll + pager.visible_lines).min(pager.lines.len());
+    let mut footer = format!(
+        "{}-{} of {}",
+        (pager.scroll + 1).min(pager.lines.len()),
+        last_visible,
+        pager.lines.len()
+    );
+    if !pager.query.is_empty() {
+        footer.push_str(&format!(
+            "  /{}  {} matches",
+            pager.query,
+            pager.matches.len()
+        ));
+    }
+    f.render_widget(
+        Paragraph::new(Span::styled(
+            footer,
+            Style::default().fg(theme::text_secondary()),
+        ))
+        .alignment(Alignment::Center)
+        .style(theme::surface()),
+        footer_area,
+    );
+}
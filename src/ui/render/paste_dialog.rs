@@ -0,0 +1,112 @@
+use ratatui::{
+    layout::{Alignment, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use crate::app::FixedWidthImport;
+use crate::ui::theme;
+
+pub(super) fn draw_fixed_width_import_popup(f: &mut Frame, import: &FixedWidthImport, area: Rect) {
+    let popup_width = area.width.saturating_sub(4).clamp(40, 120);
+    let popup_height = area.height.saturating_sub(4).clamp(8, 24);
+    let popup_area = Rect::new(
+        area.x + area.width.saturating_sub(popup_width) / 2,
+        area.y + area.height.saturating_sub(popup_height) / 2,
+        popup_width.min(area.width),
+        popup_height.min(area.height),
+    );
+
+    let block = Block::default()
+        .title(" FIXED-WIDTH PASTE ")
+        .title_alignment(Alignment::Center)
+        .title_style(
+            Style::default()
+                .fg(theme::accent())
+                .add_modifier(Modifier::BOLD),
+        )
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme::text_secondary()))
+        .style(theme::surface());
+    let inner = block.inner(popup_area);
+
+    f.render_widget(Clear, popup_area);
+    f.render_widget(block, popup_area);
+
+    if inner.height < 3 || inner.width < 10 {
+        return;
+    }
+
+    let lines = fixed_width_import_lines(import, inner.width as usize, inner.height as usize);
+    f.render_widget(Paragraph::new(lines).style(theme::surface()), inner);
+}
+
+/// Ruler, preview rows and footer for the dialog, clipped to `width` x `height`
+fn fixed_width_import_lines(
+    import: &FixedWidthImport,
+    width: usize,
+    height: usize,
+) -> Vec<Line<'static>> {
+    // Scroll horizontally so the cursor stays on screen
+    let offset = (import.cursor + 1).saturating_sub(width);
+    let visible = offset..offset + width;
+
+    let ruler: String = visible
+        .clone()
+        .map(|pos| {
+            if pos == import.cursor {
+                '^'
+            } else if import.breaks.binary_search(&pos).is_ok() {
+                '|'
+            } else {
+                ' '
+            }
+        })
+        .collect();
+
+    let mut lines = vec![Line::from(Span::styled(
+        ruler,
+        Style::default().fg(theme::accent()),
+    ))];
+
+    let preview_height = height.saturating_sub(3);
+    for line in import.lines.iter().skip(import.scroll).take(preview_height) {
+        let chars: Vec<char> = line.chars().collect();
+        let spans = visible
+            .clone()
+            .map(|pos| {
+                let ch = chars.get(pos).copied().unwrap_or(' ');
+                let style = if pos == import.cursor {
+                    Style::default().bg(theme::grid()).fg(theme::text())
+                } else if import.breaks.binary_search(&pos).is_ok() {
+                    Style::default()
+                        .bg(theme::surface_muted())
+                        .fg(theme::accent())
+                } else {
+                    Style::default().fg(theme::text())
+                };
+                Span::styled(ch.to_string(), style)
+            })
+            .collect::<Vec<_>>();
+        lines.push(Line::from(spans));
+    }
+
+    while lines.len() < height.saturating_sub(1) {
+        lines.push(Line::default());
+    }
+
+    lines.push(Line::from(vec![
+        Span::styled(
+            format!("{} columns  ", import.breaks.len() + 1),
+            Style::default().fg(theme::warning()),
+        ),
+        Span::styled(
+            "Space toggle  h/l move  w/b jump  Enter import  Esc cancel",
+            Style::default().fg(theme::text_secondary()),
+        ),
+    ]));
+
+    lines
+}
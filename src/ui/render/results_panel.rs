@@ -0,0 +1,77 @@
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+
+use crate::app::ResultsPanel;
+use crate::ui::theme;
+
+use super::panel_block;
+
+/// Height of the results panel including borders, or 0 when it is closed
+pub(super) fn results_panel_height(panel: Option<&ResultsPanel>, available: u16) -> u16 {
+    match panel {
+        Some(panel) if panel.is_open => {
+            let wanted = panel.items.len().min(8) as u16 + 2;
+            wanted.min(available / 2)
+        }
+        _ => 0,
+    }
+}
+
+pub(super) fn draw_results_panel(
+    f: &mut Frame,
+    panel: &mut ResultsPanel,
+    focused: bool,
+    area: Rect,
+) {
+    let border_color = if focused {
+        theme::accent()
+    } else {
+        theme::text_secondary()
+    };
+    let title = format!(
+        " {}  ({}/{}) ",
+        panel.title,
+        (panel.selected + 1).min(panel.items.len()),
+        panel.items.len()
+    );
+    let block = panel_block(title, border_color);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    panel.visible_lines = inner.height.max(1) as usize;
+    panel.select(panel.selected);
+
+    let lines: Vec<Line> = panel
+        .items
+        .iter()
+        .enumerate()
+        .skip(panel.scroll)
+        .take(panel.visible_lines)
+        .map(|(index, item)| {
+            let selected = index == panel.selected;
+            let base = if selected && focused {
+                Style::default().bg(theme::selection()).fg(theme::text())
+            } else if selected {
+                Style::default()
+                    .fg(theme::text())
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(theme::text())
+            };
+            Line::from(vec![
+                Span::styled(
+                    format!(" {:<16} ", item.location()),
+                    base.fg(theme::accent()),
+                ),
+                Span::styled(item.text.clone(), base),
+            ])
+        })
+        .collect();
+
+    f.render_widget(Paragraph::new(lines).style(theme::surface()), inner);
+}
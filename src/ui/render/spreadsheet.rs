@@ -1,24 +1,32 @@
 use ratatui::{
-    layout::{Constraint, Direction, Layout, Rect},
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
-    text::{Line, Span},
+    text::{Line, Span, Text},
     widgets::{Block, Borders, Cell, Paragraph, Row, Table},
     Frame,
 };
+use std::ops::Range;
 
 use crate::app::{AppState, InputMode};
-use crate::excel::{EXCEL_MAX_COLS, EXCEL_MAX_ROWS};
+use crate::excel::{CellType, EXCEL_MAX_COLS, EXCEL_MAX_ROWS};
 use crate::ui::theme;
-use crate::utils::index_to_col_name;
+use crate::utils::{
+    detect_url, index_to_col_name, parse_sheet_link, text_width, truncate_start_to_width,
+    truncate_to_width,
+};
 
-use super::display_width;
+use super::{display_width, wrap_text};
 
 const TABLE_COLUMN_SPACING: usize = 1;
+const COMMENT_MARKER: &str = "◥";
+/// Columns of the `<` and `>` marking tabs scrolled out of the tab bar
+const TAB_OVERFLOW_WIDTH: u16 = 2;
 
 /// Update the visible area of the spreadsheet based on the available space
 pub(super) fn update_visible_area(app_state: &mut AppState, area: Rect) {
     // Calculate visible rows based on available height (subtract header and borders)
-    app_state.visible_rows = (area.height as usize).saturating_sub(3);
+    let border_rows = if has_border(app_state) { 2 } else { 0 };
+    app_state.visible_rows = (area.height as usize).saturating_sub(1 + border_rows);
 
     // Ensure the selected column is visible
     app_state.ensure_column_visible(app_state.selected_cell.1);
@@ -29,14 +37,99 @@ pub(super) fn update_visible_area(app_state: &mut AppState, area: Rect) {
     // Calculate available width for columns (subtract row numbers and borders)
     let available_width = data_columns_available_width(app_state, area);
     ensure_selected_column_fully_visible(app_state, available_width);
-    let visible_cols = visible_data_columns(app_state, available_width).len();
+    let data_columns = visible_data_columns(app_state, available_width);
+    ensure_selected_row_fully_visible(app_state, &data_columns);
 
     // Ensure at least one column is visible
-    app_state.visible_cols = visible_cols.max(1);
+    app_state.visible_cols = data_columns.len().max(1);
+}
+
+/// With `:set wrap`, scrolls down until the rows above the cursor leave room for all of its row
+fn ensure_selected_row_fully_visible(app_state: &mut AppState, data_columns: &[(usize, usize)]) {
+    let selected_row = app_state.selected_cell.0;
+    let frozen_rows = app_state.workbook.get_current_sheet().freeze_panes.rows;
+    if !app_state.wrap_cells || selected_row <= frozen_rows || selected_row < app_state.start_row {
+        return;
+    }
+
+    let frozen_height: usize = (1..=frozen_rows)
+        .chain(app_state.pinned_header_row())
+        .map(|row| row_height(app_state, row, data_columns))
+        .sum();
+    while app_state.start_row < selected_row {
+        let scroll_rows: Vec<usize> = match app_state.filtered_rows_from(app_state.start_row) {
+            Some(shown) => shown
+                .iter()
+                .copied()
+                .take_while(|&row| row <= selected_row)
+                .collect(),
+            None => (app_state.start_row..=selected_row).collect(),
+        };
+        let height: usize = scroll_rows
+            .iter()
+            .map(|&row| row_height(app_state, row, data_columns))
+            .sum();
+        if frozen_height + height <= app_state.visible_rows {
+            break;
+        }
+        app_state.start_row = app_state.step_shown_rows(app_state.start_row, 1);
+    }
+}
+
+/// Terminal lines `row` takes: one, or with `:set wrap` as many as its tallest visible cell
+/// needs, up to the grid's height
+fn row_height(app_state: &AppState, row: usize, data_columns: &[(usize, usize)]) -> usize {
+    if !app_state.wrap_cells {
+        return 1;
+    }
+    data_columns
+        .iter()
+        .map(|&(col, _)| wrapped_cell_lines(app_state, row, col).len())
+        .max()
+        .unwrap_or(1)
+        .clamp(1, app_state.visible_rows.max(1))
+}
+
+/// A cell's content broken into lines of its column's width, leaving room for the comment
+/// marker
+fn wrapped_cell_lines(app_state: &AppState, row: usize, col: usize) -> Vec<String> {
+    let has_comment = app_state.cell_comment(row, col).is_some();
+    let width = app_state
+        .get_column_width(col)
+        .saturating_sub(usize::from(has_comment));
+    wrapped_lines(&app_state.grid_cell_content(row, col), width)
+}
+
+/// Content broken into lines of `width` columns, keeping its own line breaks
+fn wrapped_lines(content: &str, width: usize) -> Vec<String> {
+    let width = u16::try_from(width).unwrap_or(u16::MAX);
+    let mut lines: Vec<String> = content
+        .lines()
+        .flat_map(|line| {
+            let wrapped = wrap_text(line, width);
+            if wrapped.is_empty() {
+                vec![String::new()]
+            } else {
+                wrapped
+            }
+        })
+        .collect();
+    if lines.is_empty() {
+        lines.push(String::new());
+    }
+    lines
 }
 
 fn data_columns_available_width(app_state: &AppState, area: Rect) -> usize {
-    (area.width as usize).saturating_sub(app_state.row_number_width + 2 + TABLE_COLUMN_SPACING)
+    let border_cols = if has_border(app_state) { 2 } else { 0 };
+    (area.width as usize)
+        .saturating_sub(app_state.row_number_width + border_cols + TABLE_COLUMN_SPACING)
+}
+
+/// Whether the grid is drawn in a border: unless `:set nogridlines`, and always in a split,
+/// where the border shows which pane has focus
+fn has_border(app_state: &AppState) -> bool {
+    app_state.gridlines || app_state.split.is_some()
 }
 
 fn ensure_selected_column_fully_visible(app_state: &mut AppState, available_width: usize) {
@@ -150,7 +243,27 @@ fn push_visible_column(
     render_width == col_width
 }
 
-fn visible_data_rows(app_state: &AppState) -> Vec<usize> {
+/// The rows the grid shows, with the terminal lines each takes
+fn visible_data_rows(app_state: &AppState, data_columns: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    let rows = visible_row_numbers(app_state);
+    if !app_state.wrap_cells {
+        return rows.into_iter().map(|row| (row, 1)).collect();
+    }
+
+    let mut shown = Vec::with_capacity(rows.len());
+    let mut lines_left = app_state.visible_rows;
+    for row in rows {
+        if lines_left == 0 {
+            break;
+        }
+        let height = row_height(app_state, row, data_columns).min(lines_left);
+        lines_left -= height;
+        shown.push((row, height));
+    }
+    shown
+}
+
+fn visible_row_numbers(app_state: &AppState) -> Vec<usize> {
     let sheet = app_state.workbook.get_current_sheet();
     let max_row = EXCEL_MAX_ROWS;
     let frozen_rows = sheet.freeze_panes.rows.min(sheet.max_rows);
@@ -165,9 +278,17 @@ fn visible_data_rows(app_state: &AppState) -> Vec<usize> {
 
     let mut rows = Vec::with_capacity(available_rows);
     rows.extend(1..=frozen_rows_visible);
+    if let Some(header_row) = app_state.pinned_header_row() {
+        if header_row < scroll_start && rows.len() + 1 < available_rows {
+            rows.push(header_row);
+        }
+    }
 
     let scroll_rows_available = available_rows.saturating_sub(rows.len());
-    rows.extend((scroll_start..=max_row).take(scroll_rows_available));
+    match app_state.filtered_rows_from(scroll_start) {
+        Some(shown) => rows.extend(shown.iter().take(scroll_rows_available)),
+        None => rows.extend((scroll_start..=max_row).take(scroll_rows_available)),
+    }
 
     if rows.is_empty() && available_rows > 0 {
         rows.push(scroll_start);
@@ -176,11 +297,13 @@ fn visible_data_rows(app_state: &AppState) -> Vec<usize> {
     rows
 }
 
-pub(super) fn draw_spreadsheet(f: &mut Frame, app_state: &AppState, area: Rect) {
+/// Draws the grid of the focused pane, or with `focused` false the other pane of a split,
+/// whose border is dimmed
+pub(super) fn draw_spreadsheet(f: &mut Frame, app_state: &AppState, area: Rect, focused: bool) {
     // Calculate visible row and column ranges
     let data_columns =
         visible_data_columns(app_state, data_columns_available_width(app_state, area));
-    let visible_rows = visible_data_rows(app_state);
+    let visible_rows = visible_data_rows(app_state, &data_columns);
     let visible_cols = data_columns.len().max(1);
 
     let mut constraints = Vec::with_capacity(visible_cols + 1);
@@ -192,31 +315,64 @@ pub(super) fn draw_spreadsheet(f: &mut Frame, app_state: &AppState, area: Rect)
 
     // Set table style based on current mode
     let is_editing = matches!(app_state.input_mode, InputMode::Editing);
-    let table_block = Block::default()
+    let mut table_block = Block::default()
         .style(theme::base())
-        .borders(Borders::ALL)
-        .border_style(if is_editing {
-            Style::default().fg(theme::GRID)
+        .borders(if has_border(app_state) {
+            Borders::ALL
+        } else {
+            Borders::NONE
+        })
+        .border_style(if is_editing || !focused {
+            Style::default().fg(theme::grid())
         } else {
-            Style::default().fg(theme::ACCENT)
+            Style::default().fg(theme::accent())
         });
+    if app_state.split.is_some() {
+        // Panes may show different sheets, so each names its own
+        table_block =
+            table_block.title(format!(" {} ", app_state.workbook.get_current_sheet_name()));
+    }
     let header_style = if is_editing {
         Style::default()
-            .bg(theme::SURFACE_MUTED)
-            .fg(theme::TEXT_DISABLED)
+            .bg(theme::surface_muted())
+            .fg(theme::text_disabled())
     } else {
         theme::muted()
     };
     let cell_style = if is_editing {
         Style::default()
-            .bg(theme::BACKGROUND)
-            .fg(theme::TEXT_DISABLED)
+            .bg(theme::background())
+            .fg(theme::text_disabled())
     } else {
         theme::base()
     };
     let sheet = app_state.workbook.get_current_sheet();
     let frozen_rows = sheet.freeze_panes.rows.min(sheet.max_rows);
     let frozen_cols = sheet.freeze_panes.cols.min(sheet.max_cols);
+    let header_row = app_state.designated_header_row();
+    let is_frozen_row = |row: usize| row <= frozen_rows || Some(row) == header_row;
+    let formula_references = app_state.editing_formula_references();
+    let changed_cells = app_state.undo_history.changed_cells();
+    let sheet_name = app_state.workbook.get_current_sheet_name();
+    let (selected_row, selected_col) = app_state.selected_cell;
+    // The cursor's row and column headers stand out with `:set crosshair`
+    let crosshair_header = |style: Style, is_current: bool| {
+        if app_state.crosshair && is_current && !is_editing {
+            style.fg(theme::accent()).add_modifier(Modifier::BOLD)
+        } else {
+            style
+        }
+    };
+    let sheet_names = app_state.workbook.get_sheet_names();
+    let is_link = |row: usize, col: usize| {
+        sheet.data.get(row, col).is_some_and(|cell| {
+            cell.hyperlink.is_some()
+                || (!cell.is_formula
+                    && !cell.value.is_empty()
+                    && (detect_url(&cell.value).is_some()
+                        || parse_sheet_link(&cell.value, &sheet_names).is_some()))
+        })
+    };
     // Create header row
     let mut header_cells = Vec::with_capacity(app_state.visible_cols + 1);
     header_cells.push(Cell::from("").style(frozen_header_style(
@@ -227,30 +383,45 @@ pub(super) fn draw_spreadsheet(f: &mut Frame, app_state: &AppState, area: Rect)
 
     // Add column headers
     for (col, _) in &data_columns {
-        let col_name = index_to_col_name(*col);
-        header_cells.push(Cell::from(col_name).style(frozen_header_style(
-            header_style,
-            is_editing,
-            *col <= frozen_cols,
+        let mut col_name = index_to_col_name(*col);
+        if let Some(header_row) = header_row {
+            let label = app_state.get_cell_content(header_row, *col);
+            if !label.is_empty() {
+                col_name = format!("{col_name} {label}");
+            }
+        }
+        header_cells.push(Cell::from(col_name).style(crosshair_header(
+            frozen_header_style(header_style, is_editing, *col <= frozen_cols),
+            *col == selected_col,
         )));
     }
 
     let header = Row::new(header_cells).height(1);
 
     // Create data rows
-    let rows = visible_rows.into_iter().map(|row| {
+    let rows = visible_rows.into_iter().map(|(row, height)| {
         let mut cells = Vec::with_capacity(app_state.visible_cols + 1);
 
         // Add row header
-        cells.push(Cell::from(row.to_string()).style(frozen_header_style(
-            header_style,
-            is_editing,
-            row <= frozen_rows,
+        cells.push(Cell::from(row.to_string()).style(crosshair_header(
+            frozen_header_style(header_style, is_editing, is_frozen_row(row)),
+            row == selected_row,
         )));
 
+        let row_style = if app_state.crosshair && row == selected_row {
+            cell_style.bg(theme::crosshair())
+        } else if app_state.stripes && row % 2 == 0 {
+            cell_style.bg(theme::stripe())
+        } else {
+            cell_style
+        };
+
         // Add cells for this row
         for (col, _) in &data_columns {
             let col = *col;
+            let is_editing_cell = app_state.selected_cell == (row, col)
+                && matches!(app_state.input_mode, InputMode::Editing);
+            let has_comment = !is_editing_cell && app_state.cell_comment(row, col).is_some();
             let content = if app_state.selected_cell == (row, col)
                 && matches!(app_state.input_mode, InputMode::Editing)
             {
@@ -258,83 +429,99 @@ pub(super) fn draw_spreadsheet(f: &mut Frame, app_state: &AppState, area: Rect)
                 let current_content = app_state.text_area.lines().join("\n");
                 let col_width = app_state.get_column_width(col);
 
-                // Calculate display width
-                let display_width = current_content
-                    .chars()
-                    .fold(0, |acc, c| acc + if c.is_ascii() { 1 } else { 2 });
-
-                if display_width > col_width.saturating_sub(2) {
-                    // Truncate content if it's too wide
-                    let mut result = String::with_capacity(col_width);
-                    let mut cumulative_width = 0;
-
-                    // Process characters from the end to show the most recent input
-                    for c in current_content.chars().rev().take(col_width * 2) {
-                        let char_width = if c.is_ascii() { 1 } else { 2 };
-                        if cumulative_width + char_width <= col_width.saturating_sub(2) {
-                            cumulative_width += char_width;
-                            result.push(c);
-                        } else {
-                            break;
-                        }
-                    }
-
-                    // Reverse the characters to get the correct order
-                    result.chars().rev().collect::<String>()
-                } else {
-                    current_content
-                }
+                // Show the end of the input, where the cursor usually is
+                truncate_start_to_width(&current_content, col_width.saturating_sub(2)).to_string()
+            } else if app_state.wrap_cells {
+                wrapped_cell_lines(app_state, row, col).join("\n")
             } else {
-                // Handle normal cell content
-                let content = app_state.get_cell_content(row, col);
-                let col_width = app_state.get_column_width(col);
-
-                // Calculate display width
-                let display_width = content
-                    .chars()
-                    .fold(0, |acc, c| acc + if c.is_ascii() { 1 } else { 2 });
-
-                if display_width > col_width {
-                    // Truncate content if it's too wide
-                    let mut result = String::with_capacity(col_width);
-                    let mut current_width = 0;
-
-                    for c in content.chars() {
-                        let char_width = if c.is_ascii() { 1 } else { 2 };
-                        if current_width + char_width < col_width {
-                            result.push(c);
-                            current_width += char_width;
-                        } else {
-                            break;
-                        }
-                    }
-
-                    if !content.is_empty() && result.len() < content.len() {
-                        result.push('…');
-                    }
-
-                    result
+                // Handle normal cell content, leaving room for the comment marker
+                let content = app_state.grid_cell_content(row, col);
+                let col_width = app_state
+                    .get_column_width(col)
+                    .saturating_sub(usize::from(has_comment));
+
+                if text_width(&content) > col_width {
+                    // Leave a column for the ellipsis
+                    format!(
+                        "{}…",
+                        truncate_to_width(&content, col_width.saturating_sub(1))
+                    )
                 } else {
                     content
                 }
             };
 
+            // Cells changed since the last save are tinted, so they stand out before saving
+            let row_style = if changed_cells.contains(&sheet_name, (row, col)) {
+                cell_style.bg(theme::changed())
+            } else if app_state.crosshair && col == selected_col {
+                cell_style.bg(theme::crosshair())
+            } else {
+                row_style
+            };
+
             // Determine cell style
             let style = if app_state.selected_cell == (row, col) {
-                Style::default().bg(Color::White).fg(Color::Black)
+                Style::default()
+                    .bg(theme::cursor())
+                    .fg(theme::cursor_text())
+            } else if let Some(index) = formula_references
+                .iter()
+                .position(|reference| reference.contains((row, col)))
+            {
+                let color = theme::reference_color(index);
+                Style::default().bg(color).fg(theme::highlight_text())
+            } else if app_state.is_cell_selected(row, col) {
+                Style::default().bg(theme::selection()).fg(theme::text())
             } else if app_state.highlight_enabled && app_state.search_results.contains(&(row, col))
             {
-                Style::default().bg(theme::SEARCH).fg(Color::Black)
-            } else if row <= frozen_rows || col <= frozen_cols {
+                Style::default()
+                    .bg(theme::search())
+                    .fg(theme::highlight_text())
+            } else if let Some(style) = conditional_style(app_state, row, col, row_style)
+                .or_else(|| cell_color_style(app_state, row, col, row_style))
+            {
+                style
+            } else if is_frozen_row(row) || col <= frozen_cols {
                 frozen_cell_style(is_editing)
+            } else if !is_editing && is_link(row, col) {
+                row_style
+                    .fg(theme::accent())
+                    .add_modifier(Modifier::UNDERLINED)
             } else {
-                cell_style
+                row_style
             };
 
-            cells.push(Cell::from(content).style(style));
+            // Figures line up on the right edge of their column, as in Excel
+            let alignment = if !is_editing_cell && is_figure(app_state, row, col) {
+                Alignment::Right
+            } else {
+                Alignment::Left
+            };
+            if has_comment {
+                // A wrapped cell shows the marker at the end of its first line
+                let col_width = app_state.get_column_width(col).saturating_sub(1);
+                let mut lines = content.split('\n');
+                let first = lines.next().unwrap_or_default();
+                let padding =
+                    " ".repeat(col_width.saturating_sub(usize::from(display_width(first))));
+                let first = match alignment {
+                    Alignment::Right => format!("{padding}{first}"),
+                    _ => format!("{first}{padding}"),
+                };
+                let mut text = vec![Line::from(vec![
+                    Span::raw(first),
+                    Span::styled(COMMENT_MARKER, Style::default().fg(theme::warning())),
+                ])];
+                text.extend(lines.map(|line| Line::from(line.to_string())));
+                let text = Text::from(text).alignment(alignment);
+                cells.push(Cell::from(text).style(style));
+            } else {
+                cells.push(Cell::from(Text::from(content).alignment(alignment)).style(style));
+            }
         }
 
-        Row::new(cells)
+        Row::new(cells).height(height as u16)
     });
 
     // Create table with header and rows
@@ -350,14 +537,28 @@ pub(super) fn draw_spreadsheet(f: &mut Frame, app_state: &AppState, area: Rect)
     f.render_widget(table, area);
 }
 
+/// Whether the cell holds a number or date, drawn right-aligned. Formulas shown with
+/// `:set showformulas` are text
+fn is_figure(app_state: &AppState, row: usize, col: usize) -> bool {
+    let Some(cell) = app_state.workbook.get_current_sheet().data.get(row, col) else {
+        return false;
+    };
+    if app_state.show_formulas && app_state.cell_formula(row, col).is_some() {
+        return false;
+    }
+    matches!(cell.cell_type, CellType::Number | CellType::Date)
+}
+
 fn frozen_cell_style(is_editing: bool) -> Style {
     let foreground = if is_editing {
-        theme::TEXT_DISABLED
+        theme::text_disabled()
     } else {
-        theme::TEXT
+        theme::text()
     };
 
-    Style::default().bg(theme::FROZEN_BACKGROUND).fg(foreground)
+    Style::default()
+        .bg(theme::frozen_background())
+        .fg(foreground)
 }
 
 fn frozen_header_style(base_style: Style, is_editing: bool, is_frozen: bool) -> Style {
@@ -366,15 +567,85 @@ fn frozen_header_style(base_style: Style, is_editing: bool, is_frozen: bool) ->
     }
 
     let foreground = if is_editing {
-        theme::TEXT_DISABLED
+        theme::text_disabled()
+    } else {
+        theme::text_secondary()
+    };
+
+    Style::default()
+        .bg(theme::frozen_background())
+        .fg(foreground)
+}
+
+/// The fill and font colors the cell has in Excel, with `:set cellcolors`
+fn cell_color_style(app_state: &AppState, row: usize, col: usize, base: Style) -> Option<Style> {
+    if !app_state.cell_colors {
+        return None;
+    }
+    let cell = app_state.workbook.get_current_sheet().data.get(row, col)?;
+    colored_style(cell.fill_color, cell.font_color, base)
+}
+
+/// The colors of the first of the sheet's conditional formats the cell meets, which win over
+/// its own
+fn conditional_style(app_state: &AppState, row: usize, col: usize, base: Style) -> Option<Style> {
+    let sheet = app_state.workbook.get_current_sheet();
+    let cell = sheet.data.get(row, col)?;
+    let rule = sheet
+        .conditional_formats
+        .iter()
+        .find(|rule| rule.covers(row, col) && rule.matches(cell))?;
+    colored_style(rule.fill, rule.font, base)
+}
+
+/// `base` with a fill and font color. A font color too close to the background to read is
+/// left out when there is no fill
+fn colored_style(fill: Option<u32>, font: Option<u32>, base: Style) -> Option<Style> {
+    let font = font.map(rgb_color);
+    match (fill, font) {
+        (Some(fill), Some(font)) => Some(filled_style(fill).fg(font)),
+        (Some(fill), None) => Some(filled_style(fill)),
+        (None, Some(font)) if contrasts(font, theme::background()) => Some(base.fg(font)),
+        _ => None,
+    }
+}
+
+fn rgb_color(rgb: u32) -> Color {
+    let [_, red, green, blue] = rgb.to_be_bytes();
+    Color::Rgb(red, green, blue)
+}
+
+/// Perceived brightness from 0 to 255, for the colors it can be worked out for
+fn luminance(color: Color) -> Option<f64> {
+    let (red, green, blue) = match color {
+        Color::Rgb(red, green, blue) => (red, green, blue),
+        Color::Black => (0, 0, 0),
+        Color::White => (255, 255, 255),
+        _ => return None,
+    };
+    Some(0.299 * f64::from(red) + 0.587 * f64::from(green) + 0.114 * f64::from(blue))
+}
+
+fn contrasts(foreground: Color, background: Color) -> bool {
+    match (luminance(foreground), luminance(background)) {
+        (Some(foreground), Some(background)) => (foreground - background).abs() >= 64.0,
+        _ => true,
+    }
+}
+
+/// A background of `rgb` with black or white text, whichever reads better on it
+fn filled_style(rgb: u32) -> Style {
+    let background = rgb_color(rgb);
+    let foreground = if luminance(background).unwrap_or_default() > 140.0 {
+        Color::Black
     } else {
-        theme::TEXT_SECONDARY
+        Color::White
     };
 
-    Style::default().bg(theme::FROZEN_BACKGROUND).fg(foreground)
+    Style::default().bg(background).fg(foreground)
 }
 
-pub(super) fn draw_title_with_tabs(f: &mut Frame, app_state: &AppState, area: Rect) {
+pub(super) fn draw_title_with_tabs(f: &mut Frame, app_state: &mut AppState, area: Rect) {
     let is_editing = matches!(app_state.input_mode, InputMode::Editing);
     let sheet_names = app_state.workbook.get_sheet_names();
     let current_index = app_state.workbook.get_current_sheet_index();
@@ -386,16 +657,15 @@ pub(super) fn draw_title_with_tabs(f: &mut Frame, app_state: &AppState, area: Re
         .unwrap_or("Untitled");
 
     let brand_content = " EXCEL-CLI ";
-    let title_content = format!(" {file_name} ");
+    let title_content = match app_state.buffer_position {
+        Some((index, count)) => format!(" {file_name} [{index}/{count}] "),
+        None => format!(" {file_name} "),
+    };
 
     let brand_width = display_width(brand_content);
     let title_width = display_width(&title_content);
     let max_title_width = (area.width / 3).min(title_width);
 
-    let mut tab_widths = Vec::new();
-    let mut total_width = 0;
-    let mut visible_tabs = Vec::new();
-
     let horizontal_layout = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([
@@ -406,13 +676,15 @@ pub(super) fn draw_title_with_tabs(f: &mut Frame, app_state: &AppState, area: Re
         .split(area);
 
     let title_style = if is_editing {
-        Style::default().bg(Color::Black).fg(theme::TEXT_DISABLED)
+        Style::default().bg(theme::bar()).fg(theme::text_disabled())
     } else {
-        Style::default().bg(Color::Black).fg(theme::TEXT_SECONDARY)
+        Style::default()
+            .bg(theme::bar())
+            .fg(theme::text_secondary())
     };
     let brand_style = Style::default()
-        .bg(Color::Black)
-        .fg(theme::ACCENT)
+        .bg(theme::bar())
+        .fg(theme::accent())
         .add_modifier(Modifier::BOLD);
 
     let brand_widget = Paragraph::new(brand_content).style(brand_style);
@@ -423,80 +695,115 @@ pub(super) fn draw_title_with_tabs(f: &mut Frame, app_state: &AppState, area: Re
 
     let tabs_area = horizontal_layout[2];
     let rows_cols = sheet_rows_cols(app_state);
-    let rows_cols_plain = format!("Rows/Cols: {rows_cols}");
-    let base_rows_width = display_width(&rows_cols_plain);
-    let total_tab_width: u16 = sheet_names.iter().map(|name| display_width(name)).sum();
-    let visible_tabs_width = tabs_area.width.saturating_sub(base_rows_width);
-    let tabs_overflow = total_tab_width > visible_tabs_width;
-    let rows_cols_plain = if tabs_overflow {
-        format!("... {rows_cols_plain}")
+    let rows_cols_width = display_width(&format!("Rows/Cols: {rows_cols}"));
+    let shown_sheets: Vec<usize> = (0..sheet_names.len())
+        .filter(|&index| app_state.is_sheet_shown(index))
+        .collect();
+    let tab_widths: Vec<u16> = shown_sheets
+        .iter()
+        .map(|&index| display_width(&sheet_names[index]))
+        .collect();
+    // Leave a space before Rows/Cols
+    let tabs_room = tabs_area.width.saturating_sub(rows_cols_width + 1);
+    let tabs_overflow = tab_widths
+        .iter()
+        .map(|&width| u32::from(width))
+        .sum::<u32>()
+        > u32::from(tabs_room);
+
+    let visible = if tabs_overflow {
+        // Scrolling with { } keeps the strip in place until another sheet becomes current
+        let current = (app_state.tabs_scrolled_on != Some(current_index))
+            .then(|| {
+                shown_sheets
+                    .iter()
+                    .position(|&index| index == current_index)
+            })
+            .flatten();
+        let room = tabs_room.saturating_sub(2 * TAB_OVERFLOW_WIDTH);
+        tab_window(&tab_widths, app_state.first_tab, current, room)
     } else {
-        rows_cols_plain
+        0..shown_sheets.len()
     };
-    let rows_cols_width = display_width(&rows_cols_plain);
-    let available_width = tabs_area.width as usize;
-
-    for (i, name) in sheet_names.iter().enumerate() {
-        let tab_width = display_width(name) as usize;
-
-        if total_width + tab_width <= available_width {
-            tab_widths.push(tab_width as u16);
-            total_width += tab_width;
-            visible_tabs.push(i);
-        } else {
-            if !visible_tabs.contains(&current_index) {
-                while !visible_tabs.is_empty() && total_width + tab_width > available_width {
-                    let removed_width = tab_widths.remove(0) as usize;
-                    visible_tabs.remove(0);
-                    total_width -= removed_width;
-                }
-
-                if total_width + tab_width <= available_width {
-                    tab_widths.push(tab_width as u16);
-                    visible_tabs.push(current_index);
-                }
-            }
-            break;
-        }
-    }
+    app_state.first_tab = visible.start;
+    let earlier_hidden = visible.start > 0;
+    let later_hidden = visible.end < shown_sheets.len();
+    let visible_tabs = &shown_sheets[visible.clone()];
 
-    // Create constraints for tab layout
     let mut tab_constraints = Vec::new();
-    for &width in &tab_widths {
-        tab_constraints.push(Constraint::Length(width));
+    if earlier_hidden {
+        tab_constraints.push(Constraint::Length(TAB_OVERFLOW_WIDTH));
     }
+    tab_constraints.extend(
+        tab_widths[visible]
+            .iter()
+            .map(|&width| Constraint::Length(width)),
+    );
     tab_constraints.push(Constraint::Min(0)); // Filler space
 
     let tab_layout = Layout::default()
         .direction(Direction::Horizontal)
         .constraints(tab_constraints)
         .split(tabs_area);
+    let overflow_style = Style::default().bg(theme::bar()).fg(theme::accent());
+    let first_tab_slot = usize::from(earlier_hidden);
+    if earlier_hidden {
+        f.render_widget(Paragraph::new("<").style(overflow_style), tab_layout[0]);
+    }
+    // At the end of the strip, next to Rows/Cols
+    if later_hidden {
+        let marker_area = Rect {
+            x: tabs_area.x + tabs_room.saturating_sub(TAB_OVERFLOW_WIDTH),
+            width: TAB_OVERFLOW_WIDTH.min(tabs_room),
+            ..tabs_area
+        };
+        f.render_widget(
+            Paragraph::new(">")
+                .style(overflow_style)
+                .alignment(Alignment::Right),
+            marker_area,
+        );
+    }
 
     // Render each visible tab
-    for (layout_idx, &sheet_idx) in visible_tabs.iter().enumerate() {
-        if layout_idx >= tab_layout.len() - 1 {
-            break;
-        }
-
+    for (position, &sheet_idx) in visible_tabs.iter().enumerate() {
         let name = &sheet_names[sheet_idx];
         let is_current = sheet_idx == current_index;
+        let sheet = app_state.workbook.get_sheet_by_index(sheet_idx);
+        let is_hidden = sheet.is_some_and(|sheet| sheet.visibility.is_hidden());
+        let tab_color = sheet.and_then(|sheet| sheet.tab_color);
 
         let style = if is_editing {
-            Style::default().bg(Color::Black).fg(theme::TEXT_DISABLED)
+            Style::default().bg(theme::bar()).fg(theme::text_disabled())
+        } else if let Some(rgb) = tab_color {
+            let style = filled_style(rgb);
+            if is_current {
+                style.add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
+            } else {
+                style
+            }
         } else if is_current {
             Style::default()
-                .bg(Color::Black)
-                .fg(theme::ACCENT)
+                .bg(theme::bar())
+                .fg(theme::accent())
                 .add_modifier(Modifier::BOLD)
         } else {
-            Style::default().bg(Color::Black).fg(theme::TEXT_SECONDARY)
+            Style::default()
+                .bg(theme::bar())
+                .fg(theme::text_secondary())
+        };
+        // Hidden sheets revealed with :showhidden are set apart from the ones Excel shows
+        let style = if is_hidden {
+            style.add_modifier(Modifier::ITALIC)
+        } else {
+            style
         };
 
         let tab_widget = Paragraph::new(name.to_string())
             .style(style)
-            .alignment(ratatui::layout::Alignment::Center);
+            .alignment(Alignment::Center);
 
-        f.render_widget(tab_widget, tab_layout[layout_idx]);
+        f.render_widget(tab_widget, tab_layout[first_tab_slot + position]);
     }
 
     let rows_cols_rect = Rect {
@@ -508,38 +815,71 @@ pub(super) fn draw_title_with_tabs(f: &mut Frame, app_state: &AppState, area: Re
         width: rows_cols_width.min(tabs_area.width),
         height: 1,
     };
-    let mut rows_cols_spans = Vec::new();
-    if tabs_overflow {
-        rows_cols_spans.push(Span::styled(
-            "... ",
-            Style::default().bg(Color::Black).fg(theme::TEXT_SECONDARY),
-        ));
-    }
-    rows_cols_spans.push(Span::styled(
-        "Rows/Cols: ",
-        Style::default().bg(Color::Black).fg(theme::TEXT_SECONDARY),
-    ));
-    rows_cols_spans.push(Span::styled(
-        rows_cols,
-        Style::default().bg(Color::Black).fg(theme::ACCENT),
-    ));
+    let rows_cols_spans = vec![
+        Span::styled(
+            "Rows/Cols: ",
+            Style::default()
+                .bg(theme::bar())
+                .fg(theme::text_secondary()),
+        ),
+        Span::styled(
+            rows_cols,
+            Style::default().bg(theme::bar()).fg(theme::accent()),
+        ),
+    ];
 
     let rows_cols_widget = Paragraph::new(Line::from(rows_cols_spans))
-        .style(Style::default().bg(Color::Black))
-        .alignment(ratatui::layout::Alignment::Right);
+        .style(Style::default().bg(theme::bar()))
+        .alignment(Alignment::Right);
     f.render_widget(rows_cols_widget, rows_cols_rect);
 }
 
+/// Which of the tabs, `widths` wide, fit in `room` columns, as positions among them. The
+/// strip starts at `first` unless that leaves out `current`, in which case it scrolls just
+/// far enough to show it. It never scrolls past showing the last tab
+fn tab_window(widths: &[u16], first: usize, current: Option<usize>, room: u16) -> Range<usize> {
+    let fits = |tabs: Range<usize>| {
+        widths[tabs]
+            .iter()
+            .map(|&width| u32::from(width))
+            .sum::<u32>()
+            <= u32::from(room)
+    };
+    let last_start = (0..widths.len())
+        .find(|&start| fits(start..widths.len()))
+        .unwrap_or(widths.len().saturating_sub(1));
+
+    let mut start = first.min(last_start);
+    if let Some(current) = current {
+        start = start.min(current);
+        while start < current && !fits(start..current + 1) {
+            start += 1;
+        }
+    }
+    // A tab too wide for the room still gets drawn, cut short
+    let mut end = (start + 1).min(widths.len());
+    while end < widths.len() && fits(start..end + 1) {
+        end += 1;
+    }
+    start..end
+}
+
 fn sheet_rows_cols(app_state: &AppState) -> String {
     let sheet = app_state.workbook.get_current_sheet();
+    // More rows are on their way while a sheet streams in
+    let streaming = app_state
+        .workbook
+        .is_sheet_streaming(app_state.workbook.get_current_sheet_index());
+    let more = if streaming { "+" } else { "" };
+    let mut text = format!("{}{more} x {}", sheet.max_rows, sheet.max_cols);
+    if let Some(rows) = app_state.filtered_rows_from(1) {
+        text.push_str(&format!("  Filtered: {} rows", rows.len()));
+    }
     if sheet.freeze_panes.is_frozen() {
-        format!(
-            "{} x {}  Frozen: {}",
-            sheet.max_rows,
-            sheet.max_cols,
+        text.push_str(&format!(
+            "  Frozen: {}",
             sheet.freeze_panes.split_cell_ref()
-        )
-    } else {
-        format!("{} x {}", sheet.max_rows, sheet.max_cols)
+        ));
     }
+    text
 }
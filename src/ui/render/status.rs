@@ -8,6 +8,7 @@ use ratatui::{
 
 use crate::app::{AppState, InputMode};
 use crate::ui::theme;
+use crate::utils::cell_reference;
 
 use super::line_display_width;
 
@@ -22,8 +23,32 @@ pub(super) fn status_bar_height(app_state: &AppState, width: u16) -> u16 {
 
 pub(super) fn draw_status_bar(f: &mut Frame, app_state: &AppState, area: Rect) {
     match app_state.input_mode {
+        InputMode::Normal if app_state.selection_anchor.is_some() => {
+            let mut left_spans = vec![
+                status_badge("VISUAL", theme::success()),
+                Span::raw("  "),
+                Span::styled(
+                    app_state.selection_text().unwrap_or_default(),
+                    Style::default().fg(theme::text()),
+                ),
+            ];
+            left_spans.extend(selection_stats_spans(app_state));
+            let left = Line::from(left_spans);
+            let right = Line::from(shortcut_spans(&[(":", "Command"), ("v/Esc", "Clear")]));
+            render_status_sections(f, area, left, Some(right));
+        }
+
         InputMode::Normal => {
-            let left = Line::from(vec![status_badge("NORMAL", theme::ACCENT)]);
+            let mut left_spans = vec![status_badge("NORMAL", theme::accent())];
+            if let Some(count) = app_state.pending_count {
+                left_spans.push(Span::raw("  "));
+                left_spans.push(Span::styled(
+                    count.to_string(),
+                    Style::default().fg(theme::warning()),
+                ));
+            }
+            left_spans.extend(match_counter_spans(app_state));
+            let left = Line::from(left_spans);
             let right = Line::from(shortcut_spans(&[
                 ("Enter", "Edit"),
                 (":", "Command"),
@@ -34,7 +59,7 @@ pub(super) fn draw_status_bar(f: &mut Frame, app_state: &AppState, area: Rect) {
         }
 
         InputMode::Editing => {
-            let left = Line::from(vec![status_badge("EDIT", theme::SUCCESS)]);
+            let left = Line::from(vec![status_badge("EDIT", theme::success())]);
             let right = Line::from(shortcut_spans(&[
                 ("Enter", "Save"),
                 ("Esc", "Normal"),
@@ -46,9 +71,9 @@ pub(super) fn draw_status_bar(f: &mut Frame, app_state: &AppState, area: Rect) {
 
         InputMode::Command | InputMode::CommandInLazyLoading => {
             let mut left_spans = vec![
-                status_badge("COMMAND", theme::WARNING),
+                status_badge("COMMAND", theme::warning()),
                 Span::raw("  "),
-                Span::styled(":", Style::default().fg(theme::TEXT)),
+                Span::styled(":", Style::default().fg(theme::text())),
             ];
             left_spans.extend(parse_command(&app_state.input_buffer));
             let right = Line::from(shortcut_spans(&[
@@ -66,12 +91,13 @@ pub(super) fn draw_status_bar(f: &mut Frame, app_state: &AppState, area: Rect) {
                 "?"
             };
             let query = app_state.text_area.lines().join("\n");
-            let left_spans = vec![
-                status_badge("SEARCH", theme::SEARCH),
+            let mut left_spans = vec![
+                status_badge("SEARCH", theme::search()),
                 Span::raw("  "),
-                Span::styled(prefix.to_string(), Style::default().fg(theme::TEXT)),
-                Span::styled(query, Style::default().fg(theme::TEXT)),
+                Span::styled(prefix.to_string(), Style::default().fg(theme::text())),
+                Span::styled(query, Style::default().fg(theme::text())),
             ];
+            left_spans.extend(match_counter_spans(app_state));
             let right = Line::from(shortcut_spans(&[
                 ("Enter", "Apply"),
                 ("Esc", "Cancel"),
@@ -84,12 +110,84 @@ pub(super) fn draw_status_bar(f: &mut Frame, app_state: &AppState, area: Rect) {
             // No status bar in help mode
         }
 
+        InputMode::Results => {
+            let left = Line::from(vec![status_badge("RESULTS", theme::accent())]);
+            let right = Line::from(shortcut_spans(&[
+                ("j/k", "Move"),
+                ("Enter", "Jump"),
+                ("Esc", "Grid"),
+                ("q", "Close"),
+            ]));
+            render_status_sections(f, area, left, Some(right));
+        }
+
+        InputMode::Pager => {
+            let left = match app_state.pager.as_ref() {
+                Some(pager) if pager.search_input.is_some() => Line::from(vec![
+                    status_badge("PAGER", theme::accent()),
+                    Span::raw("  "),
+                    Span::styled(
+                        format!("/{}", pager.search_input.as_deref().unwrap_or_default()),
+                        Style::default().fg(theme::text()),
+                    ),
+                ]),
+                _ => Line::from(vec![status_badge("PAGER", theme::accent())]),
+            };
+            let right = Line::from(shortcut_spans(&[
+                ("j/k", "Scroll"),
+                ("/", "Search"),
+                ("n/N", "Next"),
+                ("q", "Close"),
+            ]));
+            render_status_sections(f, area, left, Some(right));
+        }
+
+        InputMode::FixedWidthImport => {
+            let left = Line::from(vec![status_badge("PASTE", theme::success())]);
+            let right = Line::from(shortcut_spans(&[
+                ("Space", "Break"),
+                ("Enter", "Import"),
+                ("Esc", "Cancel"),
+            ]));
+            render_status_sections(f, area, left, Some(right));
+        }
+
+        InputMode::Onboarding => {
+            let left = Line::from(vec![status_badge("WELCOME", theme::accent())]);
+            let right = Line::from(shortcut_spans(&[
+                ("s", "Sample"),
+                ("?", "Help"),
+                ("Enter", "Start"),
+            ]));
+            render_status_sections(f, area, left, Some(right));
+        }
+
+        InputMode::ConfirmPaste => {
+            let mut left_spans = vec![status_badge("PASTE", theme::success()), Span::raw("  ")];
+            if let Some(paste) = app_state.pending_paste.as_ref() {
+                let (rows, cols) = paste.size();
+                left_spans.push(Span::styled(
+                    format!(
+                        "Spread {rows}x{cols} block from {}?",
+                        cell_reference(paste.target)
+                    ),
+                    Style::default().fg(theme::text()),
+                ));
+            }
+            let right = Line::from(shortcut_spans(&[
+                ("y/Enter", "Cells"),
+                ("s", "One cell"),
+                ("n/Esc", "Cancel"),
+            ]));
+            render_status_sections(f, area, Line::from(left_spans), Some(right));
+        }
+
         InputMode::LazyLoading => {
             let left = Line::from(vec![
-                status_badge("LAZY", theme::WARNING),
+                status_badge("LAZY", theme::warning()),
                 Span::raw("  "),
                 subtle_span("State "),
-                Span::styled("not loaded", Style::default().fg(theme::WARNING)),
+                Span::styled("not loaded", Style::default().fg(theme::warning())),
             ]);
             let right = Line::from(shortcut_spans(&[
                 ("Enter", "Load"),
@@ -101,6 +199,56 @@ pub(super) fn draw_status_bar(f: &mut Frame, app_state: &AppState, area: Rect) {
     }
 }
 
+/// "Count 3  Sum 60  Avg 20  Min 10  Max 30" over the numbers in the selection
+fn selection_stats_spans(app_state: &AppState) -> Vec<Span<'static>> {
+    let Some(stats) = app_state.selection_stats() else {
+        return Vec::new();
+    };
+    let figures = [
+        ("Count", stats.count.to_string()),
+        ("Sum", format_stat(stats.sum)),
+        ("Avg", format_stat(stats.average())),
+        ("Min", format_stat(stats.min)),
+        ("Max", format_stat(stats.max)),
+    ];
+    let mut spans = Vec::with_capacity(figures.len() * 3);
+    for (label, value) in figures {
+        spans.push(Span::raw("  "));
+        spans.push(subtle_span(format!("{label} ")));
+        spans.push(Span::styled(value, Style::default().fg(theme::text())));
+    }
+    spans
+}
+
+/// A figure rounded to four decimals, without trailing zeros
+fn format_stat(value: f64) -> String {
+    let rounded = (value * 10_000.0).round() / 10_000.0;
+    if rounded.fract() == 0.0 {
+        format!("{rounded:.0}")
+    } else {
+        format!("{rounded:.4}")
+            .trim_end_matches('0')
+            .trim_end_matches('.')
+            .to_string()
+    }
+}
+
+/// "match 7/42" while search matches are highlighted, or "42 matches" off them
+fn match_counter_spans(app_state: &AppState) -> Vec<Span<'static>> {
+    let Some((number, total)) = app_state.search_match_position() else {
+        return Vec::new();
+    };
+    let text = match number {
+        Some(number) => format!("match {number}/{total}"),
+        None if total == 1 => "1 match".to_string(),
+        None => format!("{total} matches"),
+    };
+    vec![
+        Span::raw("  "),
+        Span::styled(text, Style::default().fg(theme::search())),
+    ]
+}
+
 // Parse command input and identify keywords and parameters for highlighting
 fn parse_command(input: &str) -> Vec<Span<'_>> {
     if input.is_empty() {
@@ -114,25 +262,168 @@ fn parse_command(input: &str) -> Vec<Span<'_>> {
         "q!",
         "x",
         "y",
+        "yref",
+        "yformula",
         "d",
         "put",
         "pu",
+        "paste",
         "nohlsearch",
         "noh",
         "help",
         "addsheet",
+        "newsheet",
+        "dupsheet",
+        "copysheet",
         "delsheet",
         "freeze",
         "unfreeze",
+        "transpose",
+        "sortmode",
+        "dedupe",
+        "trim",
+        "upper",
+        "lower",
+        "title",
+        "headers",
+        "show",
+        "errors",
+        "freq",
+        "copen",
+        "results",
+        "cclose",
+        "ctoggle",
+        "reg",
+        "registers",
+        "only",
+        "nofilter",
+        "headerrow",
+        "noheaderrow",
+        "cn",
+        "cnext",
+        "cp",
+        "cprev",
+        "dupes",
+        "exporttz",
+        "exportdur",
+        "comment",
+        "delcomment",
+        "fmt",
+        "version",
+        "dupr",
+        "dupc",
+        "name",
+        "unlock",
+        "showhidden",
+        "minimap",
+        "set",
+        "se",
+        "find",
+        "buffer",
+        "buffers",
+        "ls",
+        "bn",
+        "bnext",
+        "bp",
+        "bprev",
+        "split",
+        "sp",
+        "vsplit",
+        "vs",
+        "close",
+        "clo",
+        "cf",
+        "nocf",
+        "messages",
+        "mes",
+        "changes",
+        "mem",
     ];
 
-    let commands_with_params = ["cw", "ej", "eja", "sheet", "dr", "dc", "addsheet", "freeze"];
+    let commands_with_params = [
+        "yank",
+        "set",
+        "se",
+        "find",
+        "cw",
+        "ej",
+        "eja",
+        "sheet",
+        "dr",
+        "dc",
+        "mr",
+        "mc",
+        "joincols",
+        "addsheet",
+        "newsheet",
+        "dupsheet",
+        "copysheet",
+        "importsheet",
+        "rename",
+        "freeze",
+        "headerrow",
+        "transpose",
+        "sortmode",
+        "dedupe",
+        "trim",
+        "upper",
+        "lower",
+        "title",
+        "headers",
+        "errors",
+        "freq",
+        "dupes",
+        "exporttz",
+        "exportdur",
+        "comment",
+        "fmt",
+        "paste",
+        "name",
+        "goto",
+        "buffer",
+        "b",
+        "split",
+        "sp",
+        "vsplit",
+        "vs",
+        "col",
+        "cf",
+        "nocf",
+        "messages",
+    ];
 
-    let special_keywords = ["fit", "min", "all", "h", "v", "horizontal", "vertical"];
+    let special_keywords = [
+        "md",
+        "csv",
+        "fit",
+        "min",
+        "all",
+        "exact",
+        "h",
+        "v",
+        "horizontal",
+        "vertical",
+        "clear",
+        "new",
+        "natural",
+        "lexical",
+        "off",
+        "iso",
+        "seconds",
+        "col",
+        "thousands",
+        "fixed",
+        "percent",
+        "currency",
+        "date",
+        "values",
+        "transpose",
+        "skipblanks",
+    ];
 
     // Check if input is a simple command without parameters
     if known_commands.contains(&input) {
-        return vec![Span::styled(input, Style::default().fg(theme::WARNING))];
+        return vec![Span::styled(input, Style::default().fg(theme::warning()))];
     }
 
     // Extract command and parameters
@@ -147,7 +438,7 @@ fn parse_command(input: &str) -> Vec<Span<'_>> {
     if commands_with_params.contains(&cmd) || (cmd.starts_with("ej") && cmd.len() <= 3) {
         let mut spans = Vec::new();
 
-        spans.push(Span::styled(cmd, Style::default().fg(theme::WARNING)));
+        spans.push(Span::styled(cmd, Style::default().fg(theme::warning())));
 
         // Add parameters if they exist
         if parts.len() > 1 {
@@ -156,9 +447,9 @@ fn parse_command(input: &str) -> Vec<Span<'_>> {
             for i in 1..parts.len() {
                 // Determine style based on whether it's a special keyword
                 let style = if special_keywords.contains(&parts[i]) {
-                    Style::default().fg(theme::WARNING)
+                    Style::default().fg(theme::warning())
                 } else {
-                    Style::default().fg(theme::ACCENT)
+                    Style::default().fg(theme::accent())
                 };
 
                 spans.push(Span::styled(parts[i], style));
@@ -178,7 +469,7 @@ fn parse_command(input: &str) -> Vec<Span<'_>> {
 }
 
 fn status_bar_style() -> Style {
-    Style::default().bg(Color::Black).fg(theme::TEXT)
+    Style::default().bg(theme::bar()).fg(theme::text())
 }
 
 fn status_badge(label: &'static str, color: Color) -> Span<'static> {
@@ -186,21 +477,21 @@ fn status_badge(label: &'static str, color: Color) -> Span<'static> {
         format!(" {label} "),
         Style::default()
             .bg(color)
-            .fg(Color::Black)
+            .fg(theme::highlight_text())
             .add_modifier(Modifier::BOLD),
     )
 }
 
 fn subtle_span(text: impl Into<String>) -> Span<'static> {
-    Span::styled(text.into(), Style::default().fg(theme::TEXT_SECONDARY))
+    Span::styled(text.into(), Style::default().fg(theme::text_secondary()))
 }
 
 fn shortcut_key(key: &str) -> Span<'static> {
     Span::styled(
         format!("[{key}]"),
         Style::default()
-            .bg(theme::SURFACE_MUTED)
-            .fg(theme::ACCENT)
+            .bg(theme::surface_muted())
+            .fg(theme::accent())
             .add_modifier(Modifier::BOLD),
     )
 }
@@ -216,7 +507,7 @@ fn shortcut_spans(entries: &[(&str, &str)]) -> Vec<Span<'static>> {
         spans.push(Span::raw(" "));
         spans.push(Span::styled(
             (*label).to_string(),
-            Style::default().fg(theme::TEXT),
+            Style::default().fg(theme::text()),
         ));
     }
 
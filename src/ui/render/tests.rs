@@ -1,16 +1,20 @@
 use ratatui::{backend::TestBackend, style::Color, Terminal};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
+use super::loading::draw_loading_screen;
 use super::{theme, ui};
 use crate::app::{AppState, HelpEntry, InputMode};
-use crate::excel::{Cell, FreezePanes, Sheet, Workbook, EXCEL_MAX_ROWS};
+use crate::excel::{
+    Cell, CellGrid, ColumnFormats, FreezePanes, LoadProgress, Sheet, SheetProtection,
+    SheetVisibility, Workbook, EXCEL_MAX_ROWS,
+};
 
 fn app_with_sheet() -> AppState<'static> {
-    let mut data = vec![vec![Cell::empty(); 3]; 3];
-    data[1][1] = Cell::new("Name".to_string(), false);
-    data[1][2] = Cell::new("Name".to_string(), false);
-    data[2][1] = Cell::new("Ada".to_string(), false);
-    data[2][2] = Cell::new("10".to_string(), false);
+    let mut data = CellGrid::new();
+    data.set(1, 1, Cell::new("Name".to_string(), false));
+    data.set(1, 2, Cell::new("Name".to_string(), false));
+    data.set(2, 1, Cell::new("Ada".to_string(), false));
+    data.set(2, 2, Cell::new("10".to_string(), false));
 
     let sheet = Sheet {
         name: "Data".to_string(),
@@ -19,6 +23,11 @@ fn app_with_sheet() -> AppState<'static> {
         max_cols: 2,
         is_loaded: true,
         freeze_panes: FreezePanes::none(),
+        column_formats: ColumnFormats::new(),
+        protection: SheetProtection::Unprotected,
+        visibility: SheetVisibility::Visible,
+        tab_color: None,
+        conditional_formats: Vec::new(),
     };
     let app = AppState::new(
         Workbook::from_sheets_for_test(vec![sheet]),
@@ -31,11 +40,16 @@ fn app_with_sheet() -> AppState<'static> {
 fn app_with_many_sheets() -> AppState<'static> {
     let make_sheet = |name: &str| Sheet {
         name: name.to_string(),
-        data: vec![vec![Cell::empty(); 2]; 2],
+        data: CellGrid::new(),
         max_rows: 1,
         max_cols: 1,
         is_loaded: true,
         freeze_panes: FreezePanes::none(),
+        column_formats: ColumnFormats::new(),
+        protection: SheetProtection::Unprotected,
+        visibility: SheetVisibility::Visible,
+        tab_color: None,
+        conditional_formats: Vec::new(),
     };
 
     AppState::new(
@@ -53,33 +67,69 @@ fn app_with_many_sheets() -> AppState<'static> {
 }
 
 fn app_with_long_c22_cell() -> AppState<'static> {
-    let mut data = vec![vec![Cell::empty(); 5]; 24];
-    data[20][1] = Cell::new("分类甲".to_string(), false);
-    data[20][2] = Cell::new("示例能源服务股份有限公司".to_string(), false);
-    data[20][3] = Cell::new("Example Energy Services Company Limited".to_string(), false);
-    data[20][4] = Cell::new(
-        "示例省示例市示例区示例路100号示例大厦A座10层、20层、30层".to_string(),
-        false,
+    let mut data = CellGrid::new();
+    data.set(20, 1, Cell::new("分类甲".to_string(), false));
+    data.set(
+        20,
+        2,
+        Cell::new("示例能源服务股份有限公司".to_string(), false),
     );
-    data[21][1] = Cell::new("分类甲".to_string(), false);
-    data[21][2] = Cell::new("示例一致服务集团股份有限公司".to_string(), false);
-    data[21][3] = Cell::new(
-        "Example Unified Services Corporation Limited".to_string(),
-        false,
+    data.set(
+        20,
+        3,
+        Cell::new("Example Energy Services Company Limited".to_string(), false),
     );
-    data[21][4] = Cell::new(
-        "示例省示例市示例区样例四路15号示例服务大厦".to_string(),
-        false,
+    data.set(
+        20,
+        4,
+        Cell::new(
+            "示例省示例市示例区示例路100号示例大厦A座10层、20层、30层".to_string(),
+            false,
+        ),
     );
-    data[22][1] = Cell::new("分类甲".to_string(), false);
-    data[22][2] = Cell::new("示例跨区域资产服务集团股份有限公司".to_string(), false);
-    data[22][3] = Cell::new(
-        "Example International Research Operations and Holdings Company Limited".to_string(),
-        false,
+    data.set(21, 1, Cell::new("分类甲".to_string(), false));
+    data.set(
+        21,
+        2,
+        Cell::new("示例一致服务集团股份有限公司".to_string(), false),
     );
-    data[22][4] = Cell::new(
-        "示例省示例市示例区样例南路示例广场45-48楼".to_string(),
-        false,
+    data.set(
+        21,
+        3,
+        Cell::new(
+            "Example Unified Services Corporation Limited".to_string(),
+            false,
+        ),
+    );
+    data.set(
+        21,
+        4,
+        Cell::new(
+            "示例省示例市示例区样例四路15号示例服务大厦".to_string(),
+            false,
+        ),
+    );
+    data.set(22, 1, Cell::new("分类甲".to_string(), false));
+    data.set(
+        22,
+        2,
+        Cell::new("示例跨区域资产服务集团股份有限公司".to_string(), false),
+    );
+    data.set(
+        22,
+        3,
+        Cell::new(
+            "Example International Research Operations and Holdings Company Limited".to_string(),
+            false,
+        ),
+    );
+    data.set(
+        22,
+        4,
+        Cell::new(
+            "示例省示例市示例区样例南路示例广场45-48楼".to_string(),
+            false,
+        ),
     );
 
     let sheet = Sheet {
@@ -89,6 +139,11 @@ fn app_with_long_c22_cell() -> AppState<'static> {
         max_cols: 4,
         is_loaded: true,
         freeze_panes: FreezePanes::none(),
+        column_formats: ColumnFormats::new(),
+        protection: SheetProtection::Unprotected,
+        visibility: SheetVisibility::Visible,
+        tab_color: None,
+        conditional_formats: Vec::new(),
     };
 
     AppState::new(
@@ -99,10 +154,10 @@ fn app_with_long_c22_cell() -> AppState<'static> {
 }
 
 fn app_with_frozen_grid() -> AppState<'static> {
-    let mut data = vec![vec![Cell::empty(); 9]; 9];
-    for (row_idx, row) in data.iter_mut().enumerate().take(9).skip(1) {
-        for (col_idx, cell) in row.iter_mut().enumerate().take(9).skip(1) {
-            *cell = Cell::new(format!("R{row_idx}C{col_idx}"), false);
+    let mut data = CellGrid::new();
+    for row in 1..=8 {
+        for col in 1..=8 {
+            data.set(row, col, Cell::new(format!("R{row}C{col}"), false));
         }
     }
 
@@ -113,6 +168,11 @@ fn app_with_frozen_grid() -> AppState<'static> {
         max_cols: 8,
         is_loaded: true,
         freeze_panes: FreezePanes { rows: 1, cols: 1 },
+        column_formats: ColumnFormats::new(),
+        protection: SheetProtection::Unprotected,
+        visibility: SheetVisibility::Visible,
+        tab_color: None,
+        conditional_formats: Vec::new(),
     };
 
     AppState::new(
@@ -234,10 +294,10 @@ fn frozen_panes_style_frozen_regions_while_scrolled() {
 
     terminal.draw(|frame| ui(frame, &mut app)).unwrap();
 
-    assert_eq!(text_bg_at(&terminal, "R1C1"), theme::FROZEN_BACKGROUND);
-    assert_eq!(text_bg_at(&terminal, "R1C6"), theme::FROZEN_BACKGROUND);
-    assert_eq!(text_bg_at(&terminal, "R6C1"), theme::FROZEN_BACKGROUND);
-    assert_eq!(text_bg_at(&terminal, "R6C6"), theme::BACKGROUND);
+    assert_eq!(text_bg_at(&terminal, "R1C1"), theme::frozen_background());
+    assert_eq!(text_bg_at(&terminal, "R1C6"), theme::frozen_background());
+    assert_eq!(text_bg_at(&terminal, "R6C1"), theme::frozen_background());
+    assert_eq!(text_bg_at(&terminal, "R6C6"), theme::background());
 }
 
 #[test]
@@ -253,7 +313,7 @@ fn selected_and_search_styles_override_frozen_region_style() {
     terminal.draw(|frame| ui(frame, &mut app)).unwrap();
 
     assert_eq!(text_bg_at(&terminal, "R1C1"), Color::White);
-    assert_eq!(text_bg_at(&terminal, "R1C6"), theme::SEARCH);
+    assert_eq!(text_bg_at(&terminal, "R1C6"), theme::search());
 }
 
 #[test]
@@ -308,7 +368,7 @@ fn line_index(lines: &[String], needle: &str) -> usize {
 }
 
 fn help_overlay_text(width: u16) -> String {
-    super::help_overlay_lines(width)
+    super::help_lines(width, "", &[])
         .iter()
         .map(|line| {
             line.spans
@@ -354,7 +414,7 @@ fn help_overlay_uses_solid_backdrop_to_hide_underlying_sheet() {
     terminal.draw(|frame| ui(frame, &mut app)).unwrap();
 
     assert_eq!(symbol_at(&terminal, 0, 0), " ");
-    assert_eq!(bg_at(&terminal, 0, 0), theme::BACKGROUND);
+    assert_eq!(bg_at(&terminal, 0, 0), theme::background());
 }
 
 #[test]
@@ -493,13 +553,61 @@ fn help_overlay_model_lists_complete_command_reference() {
     assert!(!help_text.contains("findings"));
 }
 
+#[test]
+fn help_filter_keeps_matching_entries_and_remapped_keys_come_first() {
+    let text = |lines: Vec<ratatui::text::Line<'static>>| {
+        lines
+            .iter()
+            .map(|line| {
+                line.spans
+                    .iter()
+                    .map(|span| span.content.as_ref())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    let overrides = [
+        crate::ui::keymap::KeyOverride {
+            at_prompt: false,
+            key: "x".to_string(),
+            action: "scroll_tabs_right",
+        },
+        crate::ui::keymap::KeyOverride {
+            at_prompt: true,
+            key: "tab".to_string(),
+            action: "execute",
+        },
+    ];
+
+    let full = text(super::help_lines(112, "", &overrides));
+    assert!(full.starts_with("YOUR KEYS"), "{full}");
+    assert!(full.contains(" x "));
+    assert!(full.contains("scroll tabs right"));
+    assert!(full.contains("execute at the : prompt"));
+
+    let filtered = text(super::help_lines(112, "freeze", &overrides));
+    assert!(filtered.contains(":freeze"), "{filtered}");
+    assert!(!filtered.contains("YOUR KEYS"), "{filtered}");
+    assert!(!filtered.contains(":help"), "{filtered}");
+
+    // A section title matching keeps its whole section
+    let section = text(super::help_lines(112, "help controls", &[]));
+    assert!(section.contains("Scroll one page"), "{section}");
+
+    assert_eq!(
+        text(super::help_lines(112, "zzzz", &[])),
+        "No help entries match \"zzzz\""
+    );
+}
+
 #[test]
 fn renders_help_overlay_later_command_sections_when_scrolled() {
     let backend = TestBackend::new(120, 24);
     let mut terminal = Terminal::new(backend).unwrap();
     let mut app = app_with_sheet();
     app.show_help();
-    app.help_scroll = 17;
+    app.help_scroll = 33;
 
     terminal.draw(|frame| ui(frame, &mut app)).unwrap();
 
@@ -672,9 +780,9 @@ fn renders_editing_panel_with_vim_mode_in_title_and_without_status_mode() {
     assert!(!full_text.contains("INPUT BUFFER [EDITING]"));
     assert_eq!(
         fg_at(&terminal, line_index(&lines, " Editing Cell A1 "), 0),
-        theme::ACCENT
+        theme::accent()
     );
-    assert_eq!(text_fg_at(&terminal, "NORMAL"), theme::SUCCESS);
+    assert_eq!(text_fg_at(&terminal, "NORMAL"), theme::success());
     assert!(status_row.contains(" EDIT "));
     assert!(status_row.contains("[Enter] Save"));
     assert!(status_row.trim_end().ends_with("[v] Visual"));
@@ -709,7 +817,7 @@ fn removed_analysis_modes_do_not_appear_in_rendered_ui() {
 }
 
 #[test]
-fn renders_rows_cols_in_top_right_with_overflow_hint_when_tabs_exceed_space() {
+fn renders_rows_cols_in_top_right_with_overflow_marker_when_tabs_exceed_space() {
     let backend = TestBackend::new(60, 20);
     let mut terminal = Terminal::new(backend).unwrap();
     let mut app = app_with_many_sheets();
@@ -720,11 +828,98 @@ fn renders_rows_cols_in_top_right_with_overflow_hint_when_tabs_exceed_space() {
     let title_row = &lines[0];
 
     assert!(title_row.contains("Rows/Cols: 1 x 1"));
-    assert!(title_row.trim_end().ends_with("... Rows/Cols: 1 x 1"));
+    assert!(title_row.trim_end().ends_with("> Rows/Cols: 1 x 1"));
     assert!(title_row.contains("Alpha"));
     assert!(!title_row.contains("Zeta"));
 }
 
+#[test]
+fn scrolling_the_tab_bar_shows_overflow_markers_on_both_sides() {
+    let backend = TestBackend::new(60, 20);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let mut app = app_with_many_sheets();
+    let title_row = |terminal: &Terminal<TestBackend>| rendered_lines(terminal)[0].clone();
+
+    terminal.draw(|frame| ui(frame, &mut app)).unwrap();
+    let first = title_row(&terminal);
+    assert!(first.contains(" many.xlsx AlphaBetaGamma"), "{first}");
+    assert!(!first.contains('<'), "{first}");
+
+    app.scroll_tabs(2);
+    terminal.draw(|frame| ui(frame, &mut app)).unwrap();
+    let scrolled = title_row(&terminal);
+    assert!(scrolled.contains("< GammaDeltaEpsilon"), "{scrolled}");
+    assert!(scrolled.contains('>'), "{scrolled}");
+    assert_eq!(app.workbook.get_current_sheet_name(), "Alpha");
+
+    // Past the end the strip stops with the last tab in view
+    app.scroll_tabs(10);
+    terminal.draw(|frame| ui(frame, &mut app)).unwrap();
+    let last = title_row(&terminal);
+    assert!(last.contains("< DeltaEpsilonZeta"), "{last}");
+    assert!(!last.contains('>'), "{last}");
+    assert_eq!(app.first_tab, 3);
+
+    // Switching sheets brings the current tab back
+    app.next_sheet().unwrap();
+    terminal.draw(|frame| ui(frame, &mut app)).unwrap();
+    let switched = title_row(&terminal);
+    assert!(switched.contains("< BetaGammaDelta"), "{switched}");
+    assert!(switched.contains("> Rows/Cols"), "{switched}");
+}
+
+#[test]
+fn renders_sheet_tabs_in_their_excel_tab_colors() {
+    let backend = TestBackend::new(100, 20);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let mut app = app_with_many_sheets();
+    app.workbook.get_current_sheet_mut().tab_color = Some(0x1F4E79);
+    app.next_sheet().unwrap();
+    app.workbook.get_current_sheet_mut().tab_color = Some(0xFFC000);
+    app.prev_sheet().unwrap();
+
+    terminal.draw(|frame| ui(frame, &mut app)).unwrap();
+
+    assert_eq!(text_bg_at(&terminal, "Alpha"), Color::Rgb(0x1F, 0x4E, 0x79));
+    assert_eq!(text_fg_at(&terminal, "Alpha"), Color::White);
+    assert_eq!(text_bg_at(&terminal, "Beta"), Color::Rgb(0xFF, 0xC0, 0x00));
+    assert_eq!(text_fg_at(&terminal, "Beta"), Color::Black);
+    assert_eq!(text_bg_at(&terminal, "Gamma"), Color::Black);
+}
+
+#[test]
+fn renders_both_panes_of_a_split_with_their_own_sheets() {
+    let backend = TestBackend::new(100, 32);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let mut app = app_with_many_sheets();
+    app.split_view(crate::app::SplitDirection::Vertical, Some("Gamma"));
+    app.workbook
+        .get_current_sheet_mut()
+        .data
+        .set(1, 1, Cell::new("gamma-cell".to_string(), false));
+
+    terminal.draw(|frame| ui(frame, &mut app)).unwrap();
+
+    let lines = rendered_lines(&terminal);
+    let rendered = lines.join("\n");
+    let pane_titles = lines
+        .iter()
+        .find(|line| line.contains(" Gamma ") && line.contains(" Alpha "))
+        .unwrap_or_else(|| panic!("expected both pane titles on one row:\n{rendered}"));
+    assert!(
+        pane_titles.find(" Gamma ") < pane_titles.find(" Alpha "),
+        "{pane_titles}"
+    );
+    let first_row = lines
+        .iter()
+        .find(|line| line.contains("gamma-cell"))
+        .unwrap_or_else(|| panic!("expected the Gamma cell in the grid:\n{rendered}"));
+    let (left_pane, right_pane) = first_row.split_once("││").unwrap();
+    assert!(left_pane.contains("gamma-cell"), "{first_row}");
+    assert!(!right_pane.contains("gamma-cell"), "{first_row}");
+    assert_eq!(app.workbook.get_current_sheet_name(), "Gamma");
+}
+
 #[test]
 fn renders_blank_columns_beyond_used_range_to_fill_viewport() {
     let backend = TestBackend::new(100, 32);
@@ -799,3 +994,642 @@ fn renders_excel_max_row_number_when_selected_at_bottom_boundary() {
     );
     assert!(rendered.contains("1048576"), "{rendered}");
 }
+
+#[test]
+fn renders_fixed_width_paste_dialog_with_guessed_breaks() {
+    let backend = TestBackend::new(80, 24);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let mut app = app_with_sheet();
+
+    app.paste_text("ID  NAME\n 1  Ada\n22  Grace");
+    assert!(matches!(app.input_mode, InputMode::FixedWidthImport));
+
+    terminal.draw(|f| ui(f, &mut app)).unwrap();
+    let lines = rendered_lines(&terminal);
+
+    assert!(lines.iter().any(|line| line.contains("FIXED-WIDTH PASTE")));
+    assert!(lines.iter().any(|line| line.contains("2 columns")));
+    assert!(lines.iter().any(|line| line.contains("22  Grace")));
+}
+
+#[test]
+fn highlights_visual_selection_and_shows_range_in_status_bar() {
+    let backend = TestBackend::new(100, 24);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let mut app = app_with_frozen_grid();
+    app.workbook.clear_freeze_panes();
+    app.selected_cell = (6, 6);
+    app.toggle_visual_selection();
+    app.selected_cell = (7, 7);
+
+    terminal.draw(|f| ui(f, &mut app)).unwrap();
+
+    assert_eq!(text_bg_at(&terminal, "R6C6"), theme::selection());
+    assert_eq!(text_bg_at(&terminal, "R7C7"), Color::White);
+    assert_eq!(text_bg_at(&terminal, "R5C5"), theme::background());
+    let lines = rendered_lines(&terminal);
+    assert!(lines
+        .iter()
+        .any(|line| line.contains("VISUAL") && line.contains("F6:G7")));
+}
+
+#[test]
+fn renders_pager_with_position_footer() {
+    let backend = TestBackend::new(80, 24);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let mut app = app_with_sheet();
+
+    app.input_buffer = "headers".to_string();
+    app.execute_command();
+    terminal.draw(|f| ui(f, &mut app)).unwrap();
+
+    let lines = rendered_lines(&terminal);
+    assert!(matches!(app.input_mode, InputMode::Pager));
+    assert!(lines.iter().any(|line| line.contains("Headers: Data")));
+    assert!(lines.iter().any(|line| line.contains("B    Name")));
+    assert!(lines.iter().any(|line| line.contains("1-2 of 2")));
+    assert!(lines.iter().any(|line| line.contains("PAGER")));
+}
+
+#[test]
+fn colors_cells_referenced_by_the_formula_being_edited() {
+    let backend = TestBackend::new(100, 24);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let mut app = app_with_frozen_grid();
+    app.workbook.clear_freeze_panes();
+    app.selected_cell = (6, 1);
+    app.start_editing();
+    app.text_area = tui_textarea::TextArea::from(["=SUM(B2:C3)+d5*Other!E6"]);
+
+    terminal.draw(|f| ui(f, &mut app)).unwrap();
+
+    assert_eq!(text_bg_at(&terminal, "R2C2"), theme::reference_color(0));
+    assert_eq!(text_bg_at(&terminal, "R3C3"), theme::reference_color(0));
+    assert_eq!(text_bg_at(&terminal, "R5C4"), theme::reference_color(1));
+    assert_eq!(text_bg_at(&terminal, "R6C5"), theme::background());
+}
+
+#[test]
+fn date_cells_render_as_readable_dates_in_grid_and_info_panel() {
+    use crate::excel::{CellType, DataTypeInfo};
+
+    let mut app = app_with_sheet();
+    let date = Cell::new_with_type(
+        "45943.75".to_string(),
+        false,
+        CellType::Date,
+        Some(DataTypeInfo::DateTime(45943.75)),
+    );
+    app.workbook.get_current_sheet_mut().data.set(2, 2, date);
+    app.input_buffer = "cw fit all".to_string();
+    app.execute_command();
+    app.selected_cell = (2, 2);
+
+    let backend = TestBackend::new(100, 30);
+    let mut terminal = Terminal::new(backend).unwrap();
+    terminal.draw(|frame| ui(frame, &mut app)).unwrap();
+    let screen = rendered_lines(&terminal).join("\n");
+
+    assert!(screen.contains("2025-10-13T18:00:00"), "{screen}");
+    assert!(!screen.contains("45943.75"), "{screen}");
+    assert_eq!(app.raw_cell_content(2, 2), "45943.75");
+}
+
+#[test]
+fn block_paste_prompt_shows_size_and_target_in_status_bar() {
+    let backend = TestBackend::new(100, 24);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let mut app = app_with_sheet();
+    app.selected_cell = (2, 2);
+
+    app.paste_text("a\tb\tc\nd\te\tf\n");
+    terminal.draw(|f| ui(f, &mut app)).unwrap();
+    let lines = rendered_lines(&terminal);
+
+    assert!(lines
+        .iter()
+        .any(|line| line.contains("PASTE") && line.contains("Spread 2x3 block from B2?")));
+}
+
+#[test]
+fn onboarding_overlay_lists_modal_keys_and_sample_option() {
+    let backend = TestBackend::new(100, 30);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let mut app = app_with_sheet();
+    app.input_mode = InputMode::Onboarding;
+
+    terminal.draw(|f| ui(f, &mut app)).unwrap();
+    let rendered = rendered_lines(&terminal).join("\n");
+
+    assert!(rendered.contains("WELCOME TO EXCEL-CLI"), "{rendered}");
+    assert!(rendered.contains("Move around the grid"), "{rendered}");
+    assert!(rendered.contains("open a sample workbook"), "{rendered}");
+}
+
+#[test]
+fn renders_minimap_with_data_search_hits_and_cursor() {
+    let backend = TestBackend::new(100, 32);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let mut data = CellGrid::new();
+    data.set(1, 1, Cell::new("Name".to_string(), false));
+    data.set(1, 2, Cell::new("Total".to_string(), false));
+    data.set(1000, 2, Cell::new("needle".to_string(), false));
+    let mut sheet = Sheet::blank("Sparse".to_string());
+    sheet.data = data;
+    sheet.max_rows = 1000;
+    sheet.max_cols = 2;
+    let mut app = AppState::new(
+        Workbook::from_sheets_for_test(vec![sheet]),
+        PathBuf::from("sparse.xlsx"),
+    )
+    .unwrap();
+    app.toggle_minimap();
+    app.search_results = vec![(1000, 2)];
+    app.highlight_enabled = true;
+
+    terminal.draw(|frame| ui(frame, &mut app)).unwrap();
+
+    let lines = rendered_lines(&terminal);
+    let rendered = lines.join("\n");
+    let title_row = line_index(&lines, " Map ");
+    let title: Vec<char> = lines[title_row].chars().collect();
+    let map_col = (0..title.len())
+        .rev()
+        .find(|&col| title[col] == '┌')
+        .unwrap_or_else(|| panic!("expected a bordered minimap:\n{rendered}"));
+
+    assert_eq!(symbol_at(&terminal, title_row + 1, map_col + 1), "+");
+    assert_eq!(
+        fg_at(&terminal, title_row + 1, map_col + 1),
+        theme::accent()
+    );
+    assert_eq!(
+        bg_at(&terminal, title_row + 1, map_col + 1),
+        theme::selection()
+    );
+
+    let last_row = (title_row + 1..lines.len())
+        .take_while(|&row| symbol_at(&terminal, row, map_col) == "│")
+        .last()
+        .unwrap();
+    let hit = (map_col + 1..title.len())
+        .find(|&col| fg_at(&terminal, last_row, col) == theme::search())
+        .unwrap_or_else(|| panic!("expected the search hit at the bottom:\n{rendered}"));
+    assert_ne!(symbol_at(&terminal, last_row, hit), " ");
+    assert_ne!(bg_at(&terminal, last_row, hit), theme::selection());
+}
+
+#[test]
+fn status_bar_counts_search_matches_as_n_moves_between_them() {
+    let backend = TestBackend::new(140, 32);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let mut app = app_with_sheet();
+    app.search_results = vec![(1, 1), (1, 2), (2, 1)];
+    app.selected_cell = (2, 2);
+
+    terminal.draw(|frame| ui(frame, &mut app)).unwrap();
+    let lines = rendered_lines(&terminal);
+    assert!(lines[lines.len() - 1].contains("NORMAL   3 matches"));
+
+    app.jump_to_next_search_result();
+    terminal.draw(|frame| ui(frame, &mut app)).unwrap();
+    let lines = rendered_lines(&terminal);
+    assert!(lines[lines.len() - 1].contains("match 1/3"));
+    assert_eq!(text_fg_at(&terminal, "match 1/3"), theme::search());
+
+    app.jump_to_prev_search_result();
+    terminal.draw(|frame| ui(frame, &mut app)).unwrap();
+    let lines = rendered_lines(&terminal);
+    assert!(lines[lines.len() - 1].contains("match 3/3"));
+
+    app.disable_search_highlight();
+    terminal.draw(|frame| ui(frame, &mut app)).unwrap();
+    let lines = rendered_lines(&terminal);
+    assert!(!lines[lines.len() - 1].contains("match"));
+}
+
+#[test]
+fn set_wrap_spreads_long_cells_over_several_lines() {
+    let backend = TestBackend::new(60, 24);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let mut app = app_with_sheet();
+    let note = "the quick brown fox jumps over the lazy dog";
+    app.workbook.set_cell_value(1, 2, note.to_string()).unwrap();
+
+    terminal.draw(|frame| ui(frame, &mut app)).unwrap();
+    let rendered = rendered_lines(&terminal).join("\n");
+    assert!(!rendered.contains("lazy"), "{rendered}");
+
+    app.handle_set_command("wrap");
+    terminal.draw(|frame| ui(frame, &mut app)).unwrap();
+    let lines = rendered_lines(&terminal);
+    let first = line_index(&lines, "the quick");
+    let last = line_index(&lines, "dog");
+    assert!(last > first, "{}", lines.join("\n"));
+    assert!(line_index(&lines, "Ada") > last, "{}", lines.join("\n"));
+}
+
+#[test]
+fn set_wrap_scrolls_until_the_cursor_row_fits() {
+    let backend = TestBackend::new(60, 24);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let mut app = app_with_sheet();
+    for row in 1..=30 {
+        app.workbook
+            .set_cell_value(row, 1, format!("R{row} one two three four five six"))
+            .unwrap();
+    }
+    app.handle_set_command("wrap");
+    terminal.draw(|frame| ui(frame, &mut app)).unwrap();
+
+    app.jump_to_row(12);
+    terminal.draw(|frame| ui(frame, &mut app)).unwrap();
+    let lines = rendered_lines(&terminal);
+    let row = line_index(&lines, "R12 ");
+    assert!(lines[row + 1].contains("four"), "{}", lines.join("\n"));
+    assert_eq!(text_bg_at(&terminal, "R12 "), theme::cursor());
+}
+
+#[test]
+fn stripes_shade_even_rows_and_nogridlines_drops_the_border() {
+    let backend = TestBackend::new(60, 20);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let mut app = app_with_sheet();
+    app.selected_cell = (1, 2);
+
+    app.handle_set_command("stripes");
+    terminal.draw(|frame| ui(frame, &mut app)).unwrap();
+    assert_eq!(text_bg_at(&terminal, "Ada"), theme::stripe());
+    assert_eq!(text_bg_at(&terminal, "Name "), theme::background());
+    let lines = rendered_lines(&terminal);
+    assert!(lines[line_index(&lines, "Ada")].starts_with("│2"));
+
+    app.handle_set_command("nogridlines");
+    terminal.draw(|frame| ui(frame, &mut app)).unwrap();
+    let lines = rendered_lines(&terminal);
+    let ada = line_index(&lines, "Ada");
+    assert!(lines[ada].starts_with('2'), "{}", lines.join("\n"));
+    assert!(!lines[ada].contains('│'), "{}", lines.join("\n"));
+}
+
+#[test]
+fn set_showformulas_shows_formula_text_in_the_grid() {
+    let backend = TestBackend::new(60, 24);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let mut app = app_with_sheet();
+    app.workbook
+        .get_current_sheet_mut()
+        .data
+        .update_cell(2, 2, |cell| cell.formula = Some("=5+5".to_string()));
+
+    terminal.draw(|frame| ui(frame, &mut app)).unwrap();
+    let rendered = rendered_lines(&terminal).join("\n");
+    assert!(!rendered.contains("=5+5"), "{rendered}");
+
+    app.handle_set_command("showformulas");
+    terminal.draw(|frame| ui(frame, &mut app)).unwrap();
+    let grid_row = rendered_lines(&terminal)
+        .into_iter()
+        .find(|line| line.contains("Ada"))
+        .unwrap();
+    assert!(grid_row.contains("=5+5"), "{grid_row}");
+    assert!(!grid_row.contains("10"), "{grid_row}");
+}
+
+#[test]
+fn headerrow_stays_in_view_and_labels_the_column_headers() {
+    let backend = TestBackend::new(60, 24);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let mut app = app_with_sheet();
+    for row in 3..=60 {
+        app.workbook
+            .set_cell_value(row, 1, format!("Item{row}"))
+            .unwrap();
+    }
+    app.set_header_row("1");
+    terminal.draw(|frame| ui(frame, &mut app)).unwrap();
+    app.jump_to_row(50);
+    terminal.draw(|frame| ui(frame, &mut app)).unwrap();
+
+    let lines = rendered_lines(&terminal);
+    let strip = line_index(&lines, "A Name");
+    assert!(lines[strip].contains("B Name"), "{}", lines.join("\n"));
+    assert!(lines[strip + 1].contains("Name"), "{}", lines.join("\n"));
+    assert!(!lines[strip + 1].contains("Item"), "{}", lines.join("\n"));
+    let cursor_row = line_index(&lines, "Item50");
+    assert!(
+        lines[cursor_row + 1].starts_with('└'),
+        "{}",
+        lines.join("\n")
+    );
+}
+
+#[test]
+fn status_bar_sums_the_numbers_in_a_visual_selection() {
+    let backend = TestBackend::new(100, 24);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let mut app = app_with_sheet();
+    app.workbook
+        .set_cell_value(3, 2, "2.5".to_string())
+        .unwrap();
+    app.selected_cell = (1, 1);
+    app.toggle_visual_selection();
+    app.selected_cell = (3, 2);
+
+    terminal.draw(|f| ui(f, &mut app)).unwrap();
+
+    let lines = rendered_lines(&terminal);
+    let status = &lines[line_index(&lines, "VISUAL")];
+    assert!(
+        status.contains("A1:B3  Count 2  Sum 12.5  Avg 6.25  Min 2.5  Max 10"),
+        "{status}"
+    );
+
+    app.selected_cell = (2, 1);
+    terminal.draw(|f| ui(f, &mut app)).unwrap();
+    let lines = rendered_lines(&terminal);
+    assert!(!lines[line_index(&lines, "VISUAL")].contains("Count"));
+}
+
+#[test]
+fn loading_screen_shows_the_sheet_and_rows_read() {
+    let backend = TestBackend::new(80, 20);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let progress = LoadProgress {
+        sheet_name: "Sales".to_string(),
+        sheet_index: 1,
+        sheet_count: 3,
+        rows_read: 1200,
+        total_rows: Some(4800),
+    };
+
+    terminal
+        .draw(|f| draw_loading_screen(f, Path::new("data/big.xlsx"), Some(&progress), 0))
+        .unwrap();
+    let rendered = rendered_lines(&terminal).join("\n");
+    assert!(rendered.contains("Opening big.xlsx"), "{rendered}");
+    assert!(rendered.contains("Sheet 2/3: Sales"), "{rendered}");
+    assert!(rendered.contains("1200 / 4800 rows"), "{rendered}");
+
+    terminal
+        .draw(|f| draw_loading_screen(f, Path::new("big.xlsx"), None, 1))
+        .unwrap();
+    let rendered = rendered_lines(&terminal).join("\n");
+    assert!(rendered.contains("Reading workbook"), "{rendered}");
+    assert!(!rendered.contains("rows"), "{rendered}");
+}
+
+#[test]
+fn lazy_sheet_shows_a_placeholder_while_it_loads() {
+    use rust_xlsxwriter::Workbook as XlsxWorkbook;
+
+    let path = std::env::temp_dir().join("excel_cli_lazy_placeholder.xlsx");
+    let mut xlsx = XlsxWorkbook::new();
+    let sheet = xlsx.add_worksheet();
+    sheet.set_name("First").unwrap();
+    sheet.write_string(0, 0, "alpha").unwrap();
+    xlsx.save(&path).unwrap();
+
+    let workbook = crate::excel::open_workbook(&path, true).unwrap();
+    let mut app = AppState::new(workbook, path).unwrap();
+    app.poll_sheet_loads();
+    let backend = TestBackend::new(100, 20);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    terminal.draw(|f| ui(f, &mut app)).unwrap();
+    let rendered = rendered_lines(&terminal).join("\n");
+    assert!(rendered.contains("Loading First…"), "{rendered}");
+    assert!(!rendered.contains("alpha"), "{rendered}");
+}
+
+#[test]
+fn info_panel_hides_and_moves_beside_the_grid() {
+    let mut app = app_with_sheet();
+    let backend = TestBackend::new(100, 30);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    terminal.draw(|f| ui(f, &mut app)).unwrap();
+    let lines = rendered_lines(&terminal);
+    let details = line_index(&lines, "Cell A1");
+    assert!(details > 15, "{}", lines.join("\n"));
+    let last_row_shown = app.visible_rows;
+
+    app.toggle_info_panel();
+    terminal.draw(|f| ui(f, &mut app)).unwrap();
+    let rendered = rendered_lines(&terminal).join("\n");
+    assert!(!rendered.contains("Cell A1"), "{rendered}");
+    assert!(app.visible_rows > last_row_shown);
+
+    app.toggle_info_panel();
+    app.handle_set_command("infopanelpos=right");
+    terminal.draw(|f| ui(f, &mut app)).unwrap();
+    let lines = rendered_lines(&terminal);
+    let details = line_index(&lines, "Cell A1");
+    assert!(details < 5, "{}", lines.join("\n"));
+    assert!(lines[details].find("Cell A1").unwrap() > 50);
+}
+
+#[test]
+fn numbers_are_right_aligned_and_text_left_aligned() {
+    let mut app = app_with_sheet();
+    app.selected_cell = (1, 1);
+    let backend = TestBackend::new(60, 24);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    terminal.draw(|f| ui(f, &mut app)).unwrap();
+    let lines = rendered_lines(&terminal);
+    let header = &lines[line_index(&lines, "Name")];
+    let row = &lines[line_index(&lines, "Ada")];
+    let column_b = header.rfind("Name").unwrap();
+    let column_end = column_b + app.get_column_width(2);
+    assert_eq!(row.find("Ada"), header.find("Name"));
+    assert_eq!(row.find("10").map(|start| start + 2), Some(column_end));
+}
+
+#[test]
+fn cells_keep_their_excel_fill_and_font_colors_until_nocellcolors() {
+    let mut app = app_with_sheet();
+    {
+        let data = &mut app.workbook.get_current_sheet_mut().data;
+        data.update_cell(2, 1, |cell| cell.fill_color = Some(0xFFFF00));
+        data.update_cell(2, 2, |cell| cell.font_color = Some(0xFF4040));
+    }
+    app.selected_cell = (1, 1);
+    let backend = TestBackend::new(60, 24);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    terminal.draw(|f| ui(f, &mut app)).unwrap();
+    assert_eq!(text_bg_at(&terminal, "Ada"), Color::Rgb(0xFF, 0xFF, 0x00));
+    assert_eq!(text_fg_at(&terminal, "Ada"), Color::Black);
+    // The number sits at the right edge of its column, after the multi-byte border
+    let lines = rendered_lines(&terminal);
+    let row = line_index(&lines, "Ada");
+    let col = lines[row].chars().position(|ch| ch == '1').unwrap();
+    assert_eq!(fg_at(&terminal, row, col), Color::Rgb(0xFF, 0x40, 0x40));
+
+    // Dark text on the dark background would vanish
+    app.workbook
+        .get_current_sheet_mut()
+        .data
+        .update_cell(2, 2, |cell| cell.font_color = Some(0x101010));
+    terminal.draw(|f| ui(f, &mut app)).unwrap();
+    assert_eq!(fg_at(&terminal, row, col), theme::text());
+
+    app.handle_set_command("nocellcolors");
+    terminal.draw(|f| ui(f, &mut app)).unwrap();
+    assert_ne!(text_bg_at(&terminal, "Ada"), Color::Rgb(0xFF, 0xFF, 0x00));
+}
+
+#[test]
+fn conditional_formats_fill_the_cells_that_meet_them_over_their_own_colors() {
+    let mut app = app_with_sheet();
+    app.workbook
+        .get_current_sheet_mut()
+        .data
+        .update_cell(2, 2, |cell| cell.fill_color = Some(0x00B0F0));
+    app.selected_cell = (1, 1);
+    let backend = TestBackend::new(60, 24);
+    let mut terminal = Terminal::new(backend).unwrap();
+    terminal.draw(|f| ui(f, &mut app)).unwrap();
+    let lines = rendered_lines(&terminal);
+    let row = line_index(&lines, "Ada");
+    let col = lines[row].chars().position(|ch| ch == '1').unwrap();
+    assert_eq!(bg_at(&terminal, row, col), Color::Rgb(0x00, 0xB0, 0xF0));
+
+    app.input_buffer = "cf B:C \">5\" red".to_string();
+    app.execute_command();
+    terminal.draw(|f| ui(f, &mut app)).unwrap();
+    assert_eq!(bg_at(&terminal, row, col), Color::Rgb(0xFF, 0x00, 0x00));
+    assert_eq!(fg_at(&terminal, row, col), Color::White);
+    // Text never meets a numeric condition
+    assert_ne!(text_bg_at(&terminal, "Ada"), Color::Rgb(0xFF, 0x00, 0x00));
+
+    app.workbook
+        .get_current_sheet_mut()
+        .data
+        .set(2, 2, Cell::new("3".to_string(), false));
+    terminal.draw(|f| ui(f, &mut app)).unwrap();
+    assert_ne!(bg_at(&terminal, row, col), Color::Rgb(0xFF, 0x00, 0x00));
+}
+
+#[test]
+fn combining_marks_and_wide_characters_take_their_terminal_widths() {
+    let mut app = app_with_sheet();
+    let accented = "e\u{301}".repeat(20);
+    app.workbook
+        .get_current_sheet_mut()
+        .data
+        .set(2, 1, Cell::new(accented, false));
+    app.workbook
+        .get_current_sheet_mut()
+        .data
+        .set(1, 2, Cell::new("名前🎉".to_string(), false));
+    app.set_column_width(1, 15);
+    app.set_column_width(2, 15);
+    app.selected_cell = (1, 1);
+    let backend = TestBackend::new(60, 24);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    terminal.draw(|f| ui(f, &mut app)).unwrap();
+    let lines = rendered_lines(&terminal);
+    // Fourteen one-column accented letters and the ellipsis fill the column
+    let row = line_index(&lines, "10");
+    assert!(lines[row].contains(&format!("{}…", "e\u{301}".repeat(14))));
+    // Wide characters take two buffer cells, so column B lines up on both rows
+    let x_of = |row: usize, symbol: &str| {
+        let buffer = terminal.backend().buffer();
+        (0..buffer.area.width)
+            .rev()
+            .find(|&x| buffer[(x, row as u16)].symbol() == symbol)
+            .unwrap()
+    };
+    let header = line_index(&lines, "名 前 🎉");
+    assert_eq!(x_of(header, "🎉"), x_of(header, "名") + 4);
+    assert_eq!(x_of(row, "0"), x_of(header, "名") + 14);
+}
+
+#[test]
+fn crosshair_shades_the_cursor_row_and_column_and_marks_their_headers() {
+    let mut app = app_with_sheet();
+    app.selected_cell = (2, 1);
+    let backend = TestBackend::new(60, 24);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    terminal.draw(|f| ui(f, &mut app)).unwrap();
+    assert_ne!(text_bg_at(&terminal, "10"), theme::crosshair());
+
+    app.handle_set_command("crosshair");
+    terminal.draw(|f| ui(f, &mut app)).unwrap();
+    let lines = rendered_lines(&terminal);
+    let header = line_index(&lines, "Name");
+    let row = line_index(&lines, "Ada");
+    let name_col = lines[header].chars().position(|ch| ch == 'N').unwrap();
+    let number_col = lines[row].chars().position(|ch| ch == '1').unwrap();
+    // Same column as the cursor, then same row
+    assert_eq!(bg_at(&terminal, header, name_col), theme::crosshair());
+    assert_eq!(bg_at(&terminal, row, number_col), theme::crosshair());
+    assert_eq!(bg_at(&terminal, header, number_col), theme::background());
+    assert_eq!(text_bg_at(&terminal, "Ada"), theme::cursor());
+
+    let letters = line_index(&lines, "A ");
+    let letter_col = |letter: char| lines[letters].chars().position(|ch| ch == letter).unwrap();
+    assert_eq!(fg_at(&terminal, letters, letter_col('A')), theme::accent());
+    assert_ne!(fg_at(&terminal, letters, letter_col('B')), theme::accent());
+    let row_number = lines[row].chars().position(|ch| ch == '2').unwrap();
+    assert_eq!(fg_at(&terminal, row, row_number), theme::accent());
+}
+
+#[test]
+fn notifications_are_colored_by_severity() {
+    let mut app = app_with_sheet();
+    app.add_warning("Usage: :nocf [n]".to_string());
+    app.add_error("Save failed: disk full".to_string());
+    app.add_notification("Copied A1".to_string());
+    let backend = TestBackend::new(140, 32);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    terminal.draw(|f| ui(f, &mut app)).unwrap();
+    assert_eq!(text_fg_at(&terminal, "Save failed"), theme::error());
+    assert_eq!(text_fg_at(&terminal, "Usage: :nocf"), theme::warning());
+    assert_eq!(text_fg_at(&terminal, "Copied A1"), theme::text());
+
+    // Older info dims, while the error keeps its color
+    app.add_notification("Saved to test.xlsx".to_string());
+    terminal.draw(|f| ui(f, &mut app)).unwrap();
+    assert_eq!(text_fg_at(&terminal, "Copied A1"), theme::text_secondary());
+    assert_eq!(text_fg_at(&terminal, "Save failed"), theme::error());
+}
+
+#[test]
+fn cells_changed_since_opening_are_tinted() {
+    let mut app = app_with_sheet();
+    app.input_buffer = "lower".to_string();
+    app.execute_command();
+    app.selected_cell = (1, 2);
+    let backend = TestBackend::new(100, 24);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    terminal.draw(|f| ui(f, &mut app)).unwrap();
+    assert_eq!(text_bg_at(&terminal, "ada"), theme::changed());
+    assert_ne!(text_bg_at(&terminal, "10"), theme::changed());
+
+    app.undo().unwrap();
+    terminal.draw(|f| ui(f, &mut app)).unwrap();
+    assert_ne!(text_bg_at(&terminal, "Ada"), theme::changed());
+}
+
+#[test]
+fn info_panel_indents_and_colors_json_cells() {
+    let mut app = app_with_sheet();
+    app.workbook
+        .set_cell_value(1, 1, r#"{"id":7,"ok":true}"#.to_string())
+        .unwrap();
+    let backend = TestBackend::new(100, 32);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    terminal.draw(|f| ui(f, &mut app)).unwrap();
+    let rendered = rendered_lines(&terminal).join("\n");
+    assert!(rendered.contains("Cell A1  JSON"));
+    assert_eq!(text_fg_at(&terminal, "\"id\": 7,"), theme::accent());
+    assert_eq!(text_fg_at(&terminal, " true"), theme::warning());
+}
@@ -1,26 +1,355 @@
+use std::cell::Cell;
+use std::str::FromStr;
+
 use ratatui::style::{Color, Style};
 
-pub const BACKGROUND: Color = Color::Rgb(11, 16, 32);
-pub const SURFACE: Color = Color::Rgb(17, 24, 39);
-pub const SURFACE_MUTED: Color = Color::Rgb(31, 41, 55);
-pub const GRID: Color = Color::Rgb(55, 65, 81);
-pub const FROZEN_BACKGROUND: Color = Color::Rgb(20, 54, 78);
-pub const TEXT: Color = Color::Rgb(229, 231, 235);
-pub const TEXT_SECONDARY: Color = Color::Rgb(156, 163, 175);
-pub const TEXT_DISABLED: Color = Color::Rgb(107, 114, 128);
-pub const ACCENT: Color = Color::Rgb(56, 189, 248);
-pub const SEARCH: Color = Color::Rgb(250, 204, 21);
-pub const WARNING: Color = Color::Rgb(245, 158, 11);
-pub const SUCCESS: Color = Color::Rgb(34, 197, 94);
+use crate::config::ThemeSettings;
+
+/// The colors the interface is drawn with
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Theme {
+    pub background: Color,
+    pub surface: Color,
+    pub surface_muted: Color,
+    pub grid: Color,
+    pub frozen_background: Color,
+    /// Behind every other row with `:set stripes`
+    pub stripe: Color,
+    /// Behind the row and column of the cursor with `:set crosshair`
+    pub crosshair: Color,
+    /// Behind cells changed since the workbook was opened or saved
+    pub changed: Color,
+    /// Behind the title bar, sheet tabs and status bar
+    pub bar: Color,
+    pub text: Color,
+    pub text_secondary: Color,
+    pub text_disabled: Color,
+    /// Headers of the current row and column, the current sheet tab and popup borders
+    pub accent: Color,
+    pub selection: Color,
+    pub search: Color,
+    pub warning: Color,
+    /// Notifications of things that failed
+    pub error: Color,
+    pub success: Color,
+    pub cursor: Color,
+    pub cursor_text: Color,
+    /// Text on search matches, formula references and mode badges
+    pub highlight_text: Color,
+    /// Colors cycled through for cells referenced by the formula being edited
+    pub reference_colors: [Color; 6],
+}
+
+impl Theme {
+    pub const DARK: Self = Self {
+        background: Color::Rgb(11, 16, 32),
+        surface: Color::Rgb(17, 24, 39),
+        surface_muted: Color::Rgb(31, 41, 55),
+        grid: Color::Rgb(55, 65, 81),
+        frozen_background: Color::Rgb(20, 54, 78),
+        stripe: Color::Rgb(20, 28, 46),
+        crosshair: Color::Rgb(28, 40, 66),
+        changed: Color::Rgb(50, 40, 18),
+        bar: Color::Black,
+        text: Color::Rgb(229, 231, 235),
+        text_secondary: Color::Rgb(156, 163, 175),
+        text_disabled: Color::Rgb(107, 114, 128),
+        accent: Color::Rgb(56, 189, 248),
+        selection: Color::Rgb(30, 64, 125),
+        search: Color::Rgb(250, 204, 21),
+        warning: Color::Rgb(245, 158, 11),
+        error: Color::Rgb(248, 113, 113),
+        success: Color::Rgb(34, 197, 94),
+        cursor: Color::White,
+        cursor_text: Color::Black,
+        highlight_text: Color::Black,
+        reference_colors: [
+            Color::Rgb(96, 165, 250),
+            Color::Rgb(248, 113, 113),
+            Color::Rgb(192, 132, 252),
+            Color::Rgb(74, 222, 128),
+            Color::Rgb(251, 146, 60),
+            Color::Rgb(45, 212, 191),
+        ],
+    };
+
+    pub const LIGHT: Self = Self {
+        background: Color::Rgb(255, 255, 255),
+        surface: Color::Rgb(243, 244, 246),
+        surface_muted: Color::Rgb(229, 231, 235),
+        grid: Color::Rgb(209, 213, 219),
+        frozen_background: Color::Rgb(219, 234, 254),
+        stripe: Color::Rgb(243, 244, 246),
+        crosshair: Color::Rgb(232, 240, 254),
+        changed: Color::Rgb(254, 243, 199),
+        bar: Color::Rgb(229, 231, 235),
+        text: Color::Rgb(17, 24, 39),
+        text_secondary: Color::Rgb(75, 85, 99),
+        text_disabled: Color::Rgb(156, 163, 175),
+        accent: Color::Rgb(3, 105, 161),
+        selection: Color::Rgb(191, 219, 254),
+        search: Color::Rgb(253, 224, 71),
+        warning: Color::Rgb(180, 83, 9),
+        error: Color::Rgb(185, 28, 28),
+        success: Color::Rgb(21, 128, 61),
+        cursor: Color::Rgb(17, 24, 39),
+        cursor_text: Color::White,
+        highlight_text: Color::Black,
+        reference_colors: [
+            Color::Rgb(147, 197, 253),
+            Color::Rgb(252, 165, 165),
+            Color::Rgb(216, 180, 254),
+            Color::Rgb(134, 239, 172),
+            Color::Rgb(253, 186, 116),
+            Color::Rgb(94, 234, 212),
+        ],
+    };
+
+    /// Plain terminal colors with the strongest contrast, for low-vision users and terminals
+    /// without true color
+    pub const HIGH_CONTRAST: Self = Self {
+        background: Color::Black,
+        surface: Color::Black,
+        surface_muted: Color::Black,
+        grid: Color::White,
+        frozen_background: Color::Blue,
+        stripe: Color::DarkGray,
+        crosshair: Color::DarkGray,
+        changed: Color::Magenta,
+        bar: Color::Black,
+        text: Color::White,
+        text_secondary: Color::White,
+        text_disabled: Color::Gray,
+        accent: Color::LightCyan,
+        selection: Color::Blue,
+        search: Color::LightYellow,
+        warning: Color::LightYellow,
+        error: Color::LightRed,
+        success: Color::LightGreen,
+        cursor: Color::White,
+        cursor_text: Color::Black,
+        highlight_text: Color::Black,
+        reference_colors: [
+            Color::LightCyan,
+            Color::LightRed,
+            Color::LightMagenta,
+            Color::LightGreen,
+            Color::LightYellow,
+            Color::White,
+        ],
+    };
+
+    /// The built-in theme called `name`
+    #[must_use]
+    pub fn preset(name: &str) -> Option<Self> {
+        match name {
+            "dark" => Some(Self::DARK),
+            "light" => Some(Self::LIGHT),
+            "high-contrast" => Some(Self::HIGH_CONTRAST),
+            _ => None,
+        }
+    }
+
+    /// The theme the `[theme]` section of the config file describes: a preset, `dark` unless
+    /// named, with any colors it sets replaced
+    pub fn from_settings(settings: &ThemeSettings) -> Result<Self, String> {
+        let preset = settings.preset.as_deref().unwrap_or("dark");
+        let mut theme = Self::preset(preset).ok_or_else(|| {
+            format!("Unknown theme preset {preset}; use dark, light or high-contrast")
+        })?;
+
+        for (name, value) in &settings.colors {
+            let color = Color::from_str(value)
+                .map_err(|_| format!("Invalid color {value} for theme.{name}"))?;
+            let slot = match name.as_str() {
+                "background" => &mut theme.background,
+                "surface" => &mut theme.surface,
+                "surface_muted" => &mut theme.surface_muted,
+                "grid" => &mut theme.grid,
+                "frozen_background" => &mut theme.frozen_background,
+                "stripe" => &mut theme.stripe,
+                "crosshair" => &mut theme.crosshair,
+                "changed" => &mut theme.changed,
+                "bar" => &mut theme.bar,
+                "text" => &mut theme.text,
+                "text_secondary" => &mut theme.text_secondary,
+                "text_disabled" => &mut theme.text_disabled,
+                "accent" => &mut theme.accent,
+                "selection" => &mut theme.selection,
+                "search" => &mut theme.search,
+                "warning" => &mut theme.warning,
+                "error" => &mut theme.error,
+                "success" => &mut theme.success,
+                "cursor" => &mut theme.cursor,
+                "cursor_text" => &mut theme.cursor_text,
+                "highlight_text" => &mut theme.highlight_text,
+                _ => return Err(format!("Unknown theme color {name}")),
+            };
+            *slot = color;
+        }
+        Ok(theme)
+    }
+}
+
+thread_local! {
+    /// The theme the interface is drawn with; the TUI draws on one thread
+    static CURRENT: Cell<Theme> = const { Cell::new(Theme::DARK) };
+}
+
+/// Draws the interface with `theme` from now on
+pub fn set_theme(theme: Theme) {
+    CURRENT.with(|current| current.set(theme));
+}
+
+/// Uses the theme the `[theme]` section of the config file describes
+pub fn use_theme_settings(settings: &ThemeSettings) -> Result<(), String> {
+    set_theme(Theme::from_settings(settings)?);
+    Ok(())
+}
+
+#[must_use]
+pub fn current() -> Theme {
+    CURRENT.with(Cell::get)
+}
+
+pub fn background() -> Color {
+    current().background
+}
+
+pub fn surface_muted() -> Color {
+    current().surface_muted
+}
+
+pub fn grid() -> Color {
+    current().grid
+}
+
+pub fn frozen_background() -> Color {
+    current().frozen_background
+}
+
+pub fn stripe() -> Color {
+    current().stripe
+}
+
+pub fn crosshair() -> Color {
+    current().crosshair
+}
+
+pub fn changed() -> Color {
+    current().changed
+}
+
+pub fn bar() -> Color {
+    current().bar
+}
+
+pub fn text() -> Color {
+    current().text
+}
+
+pub fn text_secondary() -> Color {
+    current().text_secondary
+}
+
+pub fn text_disabled() -> Color {
+    current().text_disabled
+}
+
+pub fn accent() -> Color {
+    current().accent
+}
+
+pub fn selection() -> Color {
+    current().selection
+}
+
+pub fn search() -> Color {
+    current().search
+}
+
+pub fn warning() -> Color {
+    current().warning
+}
+
+pub fn error() -> Color {
+    current().error
+}
+
+pub fn success() -> Color {
+    current().success
+}
+
+pub fn cursor() -> Color {
+    current().cursor
+}
+
+pub fn cursor_text() -> Color {
+    current().cursor_text
+}
+
+pub fn highlight_text() -> Color {
+    current().highlight_text
+}
+
+/// The color marking the `index`th reference of the formula being edited
+pub fn reference_color(index: usize) -> Color {
+    let colors = current().reference_colors;
+    colors[index % colors.len()]
+}
 
 pub fn base() -> Style {
-    Style::default().bg(BACKGROUND).fg(TEXT)
+    Style::default().bg(background()).fg(text())
 }
 
 pub fn surface() -> Style {
-    Style::default().bg(SURFACE).fg(TEXT)
+    Style::default().bg(current().surface).fg(text())
 }
 
 pub fn muted() -> Style {
-    Style::default().bg(SURFACE_MUTED).fg(TEXT_SECONDARY)
+    Style::default().bg(surface_muted()).fg(text_secondary())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Theme;
+    use crate::config::ThemeSettings;
+    use ratatui::style::Color;
+
+    #[test]
+    fn settings_pick_a_preset_and_override_colors() {
+        let settings = |preset: Option<&str>, colors: &[(&str, &str)]| ThemeSettings {
+            preset: preset.map(String::from),
+            colors: colors
+                .iter()
+                .map(|&(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+        };
+
+        assert_eq!(
+            Theme::from_settings(&ThemeSettings::default()),
+            Ok(Theme::DARK)
+        );
+        let theme = Theme::from_settings(&settings(
+            Some("light"),
+            &[("accent", "#ff8800"), ("selection", "light blue")],
+        ))
+        .unwrap();
+        assert_eq!(theme.background, Theme::LIGHT.background);
+        assert_eq!(theme.accent, Color::Rgb(0xff, 0x88, 0x00));
+        assert_eq!(theme.selection, Color::LightBlue);
+
+        assert_eq!(
+            Theme::from_settings(&settings(Some("solarized"), &[])),
+            Err("Unknown theme preset solarized; use dark, light or high-contrast".to_string())
+        );
+        assert_eq!(
+            Theme::from_settings(&settings(None, &[("accent", "#12")])),
+            Err("Invalid color #12 for theme.accent".to_string())
+        );
+        assert_eq!(
+            Theme::from_settings(&settings(None, &[("header", "red")])),
+            Err("Unknown theme color header".to_string())
+        );
+    }
 }
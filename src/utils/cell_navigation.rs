@@ -9,9 +9,12 @@ pub enum Direction {
     Down,
 }
 
-/// Find non-empty cell in specified direction
+/// Finds where `Ctrl+arrow` lands, as Excel does: from inside a block of filled cells, its
+/// last filled cell in `direction`; from the edge of a block or an empty cell, the first filled
+/// cell of the next block, or the boundary when no data follows. `max_bounds` is the
+/// `(row, col)` limit to stop at.
 ///
-/// Returns the position of found cell, or None if already at boundary
+/// Returns `None` when the cell is already at the boundary
 #[must_use]
 pub fn find_non_empty_cell(
     sheet: &Sheet,
@@ -19,142 +22,82 @@ pub fn find_non_empty_cell(
     direction: Direction,
     max_bounds: (usize, usize),
 ) -> Option<(usize, usize)> {
-    let (row, col) = current_pos;
     let (max_row, max_col) = max_bounds;
+    let step = |(row, col): (usize, usize)| match direction {
+        Direction::Left if col > 1 => Some((row, col - 1)),
+        Direction::Right if col < max_col => Some((row, col + 1)),
+        Direction::Up if row > 1 => Some((row - 1, col)),
+        Direction::Down if row < max_row => Some((row + 1, col)),
+        _ => None,
+    };
+    let is_filled = |(row, col): (usize, usize)| !sheet.data.cell(row, col).value.is_empty();
 
-    // Check if already at boundary
-    match direction {
-        Direction::Left if col <= 1 => return None,
-        Direction::Right if col >= max_col => return None,
-        Direction::Up if row <= 1 => return None,
-        Direction::Down if row >= max_row => return None,
-        _ => {}
+    let mut pos = step(current_pos)?;
+    if is_filled(current_pos) && is_filled(pos) {
+        // Inside a block: stop on its last filled cell
+        while let Some(next) = step(pos).filter(|&next| is_filled(next)) {
+            pos = next;
+        }
+        return Some(pos);
     }
 
-    // Check if current cell is empty
-    let current_cell_is_empty = row >= sheet.data.len()
-        || col >= sheet.data[row].len()
-        || sheet.data[row][col].value.is_empty();
-
-    if current_cell_is_empty {
-        // Current cell is empty, find first non-empty cell
-        match direction {
-            Direction::Left => {
-                for c in (1..col).rev() {
-                    if row < sheet.data.len()
-                        && c < sheet.data[row].len()
-                        && !sheet.data[row][c].value.is_empty()
-                    {
-                        return Some((row, c));
-                    }
-                }
-                // Return boundary if no non-empty cell found
-                Some((row, 1))
-            }
-            Direction::Right => {
-                for c in (col + 1)..=max_col {
-                    if row < sheet.data.len()
-                        && c < sheet.data[row].len()
-                        && !sheet.data[row][c].value.is_empty()
-                    {
-                        return Some((row, c));
-                    }
-                }
-                // Return boundary if no non-empty cell found
-                Some((row, max_col))
-            }
-            Direction::Up => {
-                for r in (1..row).rev() {
-                    if r < sheet.data.len()
-                        && col < sheet.data[r].len()
-                        && !sheet.data[r][col].value.is_empty()
-                    {
-                        return Some((r, col));
-                    }
-                }
-                // Return boundary if no non-empty cell found
-                Some((1, col))
-            }
-            Direction::Down => {
-                for r in (row + 1)..=max_row {
-                    if r < sheet.data.len()
-                        && col < sheet.data[r].len()
-                        && !sheet.data[r][col].value.is_empty()
-                    {
-                        return Some((r, col));
-                    }
-                }
-                // Return boundary if no non-empty cell found
-                Some((max_row, col))
-            }
+    // On the edge of a block or in a gap: cross to the next block
+    while !is_filled(pos) {
+        match step(pos) {
+            Some(next) => pos = next,
+            None => break,
         }
-    } else {
-        // Current cell is non-empty, find boundary
-        match direction {
-            Direction::Left => {
-                let mut last_non_empty = col;
-
-                for c in (1..col).rev() {
-                    if row < sheet.data.len() && c < sheet.data[row].len() {
-                        if sheet.data[row][c].value.is_empty() {
-                            return Some((row, c + 1));
-                        }
-                        last_non_empty = c;
-                    } else {
-                        return Some((row, c + 1));
-                    }
-                }
+    }
+    Some(pos)
+}
 
-                Some((row, last_non_empty))
-            }
-            Direction::Right => {
-                let mut last_non_empty = col;
+#[cfg(test)]
+mod tests {
+    use super::{find_non_empty_cell, Direction};
+    use crate::excel::{Cell, CellGrid, Sheet};
 
-                for c in (col + 1)..=max_col {
-                    if row < sheet.data.len() && c < sheet.data[row].len() {
-                        if sheet.data[row][c].value.is_empty() {
-                            return Some((row, c - 1));
-                        }
-                        last_non_empty = c;
-                    } else {
-                        return Some((row, c - 1));
-                    }
-                }
+    /// One row laid out like `"xx..x."`, where `x` is a filled cell
+    fn row_sheet(layout: &str) -> Sheet {
+        let mut sheet = Sheet::blank("Data".to_string());
+        let mut row = vec![Cell::empty()];
+        row.extend(layout.chars().map(|c| match c {
+            'x' => Cell::new("v".to_string(), false),
+            _ => Cell::empty(),
+        }));
+        sheet.data = CellGrid::from(vec![Vec::new(), row]);
+        sheet.max_rows = 1;
+        sheet.max_cols = layout.len();
+        sheet
+    }
 
-                Some((row, last_non_empty))
-            }
-            Direction::Up => {
-                let mut last_non_empty = row;
+    fn jump(sheet: &Sheet, col: usize, direction: Direction) -> Option<usize> {
+        find_non_empty_cell(sheet, (1, col), direction, (1, sheet.max_cols)).map(|(_, col)| col)
+    }
 
-                for r in (1..row).rev() {
-                    if r < sheet.data.len() && col < sheet.data[r].len() {
-                        if sheet.data[r][col].value.is_empty() {
-                            return Some((r + 1, col));
-                        }
-                        last_non_empty = r;
-                    } else {
-                        return Some((r + 1, col));
-                    }
-                }
+    #[test]
+    fn ctrl_arrows_move_between_block_edges_like_excel() {
+        let sheet = row_sheet("xxx..xx...");
 
-                Some((last_non_empty, col))
-            }
-            Direction::Down => {
-                let mut last_non_empty = row;
+        // Inside a block: to its end
+        assert_eq!(jump(&sheet, 1, Direction::Right), Some(3));
+        // On its edge: across the gap to the next block
+        assert_eq!(jump(&sheet, 3, Direction::Right), Some(6));
+        assert_eq!(jump(&sheet, 6, Direction::Right), Some(7));
+        // Past the last block: to the boundary
+        assert_eq!(jump(&sheet, 7, Direction::Right), Some(10));
+        assert_eq!(jump(&sheet, 10, Direction::Right), None);
 
-                for r in (row + 1)..=max_row {
-                    if r < sheet.data.len() && col < sheet.data[r].len() {
-                        if sheet.data[r][col].value.is_empty() {
-                            return Some((r - 1, col));
-                        }
-                        last_non_empty = r;
-                    } else {
-                        return Some((r - 1, col));
-                    }
-                }
+        // From an empty cell: to the next filled one
+        assert_eq!(jump(&sheet, 9, Direction::Left), Some(7));
+        assert_eq!(jump(&sheet, 6, Direction::Left), Some(3));
+        assert_eq!(jump(&sheet, 3, Direction::Left), Some(1));
+    }
 
-                Some((last_non_empty, col))
-            }
-        }
+    #[test]
+    fn single_filled_cells_are_blocks_of_their_own() {
+        let sheet = row_sheet("x.x.x");
+        assert_eq!(jump(&sheet, 1, Direction::Right), Some(3));
+        assert_eq!(jump(&sheet, 3, Direction::Right), Some(5));
+        assert_eq!(jump(&sheet, 5, Direction::Left), Some(3));
     }
 }
@@ -88,6 +88,66 @@ pub fn parse_range(range: &str) -> Option<((usize, usize), (usize, usize))> {
     Some((start, end))
 }
 
+/// Parse cell text that points at a sheet, such as `Summary`, `Data!B2` or `'Q1 Sales'!A1:C5`,
+/// into a sheet index and optional target cell. A leading `#`, as in hyperlink targets, is
+/// allowed and sheet names match case-insensitively.
+#[must_use]
+pub fn parse_sheet_link(
+    text: &str,
+    sheet_names: &[String],
+) -> Option<(usize, Option<(usize, usize)>)> {
+    let text = text.trim();
+    let text = text.strip_prefix('#').unwrap_or(text);
+    let find_sheet = |name: &str| {
+        sheet_names
+            .iter()
+            .position(|sheet| sheet.eq_ignore_ascii_case(name))
+    };
+
+    let Some((sheet_part, cell_part)) = text.rsplit_once('!') else {
+        return find_sheet(text).map(|index| (index, None));
+    };
+
+    let sheet_name = match sheet_part
+        .strip_prefix('\'')
+        .and_then(|name| name.strip_suffix('\''))
+    {
+        Some(quoted) => quoted.replace("''", "'"),
+        None => sheet_part.to_string(),
+    };
+    let cell_part = cell_part.replace('$', "");
+    let cell = match cell_part.split_once(':') {
+        Some(_) => parse_range(&cell_part)?.0,
+        None => parse_cell_reference(&cell_part)?,
+    };
+
+    find_sheet(&sheet_name).map(|index| (index, Some(cell)))
+}
+
+/// Returns the URL when the whole text looks like a web or mail link. Bare `www.` hosts get an
+/// `https://` scheme.
+#[must_use]
+pub fn detect_url(text: &str) -> Option<String> {
+    const SCHEMES: [&str; 4] = ["http://", "https://", "ftp://", "mailto:"];
+
+    let text = text.trim();
+    if text.is_empty() || text.chars().any(char::is_whitespace) {
+        return None;
+    }
+
+    let lower = text.to_ascii_lowercase();
+    if SCHEMES
+        .iter()
+        .any(|scheme| lower.starts_with(scheme) && lower.len() > scheme.len())
+    {
+        Some(text.to_string())
+    } else if lower.starts_with("www.") && text[4..].contains('.') {
+        Some(format!("https://{text}"))
+    } else {
+        None
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -131,4 +191,54 @@ mod tests {
         assert_eq!(parse_range("A:B2"), None);
         assert_eq!(parse_range("A1:B2:C3"), None);
     }
+
+    #[test]
+    fn test_parse_sheet_link() {
+        let sheets = vec![
+            "Index".to_string(),
+            "Q1 Sales".to_string(),
+            "Data".to_string(),
+        ];
+
+        assert_eq!(parse_sheet_link("data", &sheets), Some((2, None)));
+        assert_eq!(
+            parse_sheet_link(" Data!B2 ", &sheets),
+            Some((2, Some((2, 2))))
+        );
+        assert_eq!(
+            parse_sheet_link("#Data!$C$4", &sheets),
+            Some((2, Some((4, 3))))
+        );
+        assert_eq!(
+            parse_sheet_link("'Q1 Sales'!A1:C5", &sheets),
+            Some((1, Some((1, 1))))
+        );
+        assert_eq!(
+            parse_sheet_link("Q1 Sales!D9", &sheets),
+            Some((1, Some((9, 4))))
+        );
+        assert_eq!(parse_sheet_link("Missing!A1", &sheets), None);
+        assert_eq!(parse_sheet_link("Data!Total", &sheets), None);
+        assert_eq!(parse_sheet_link("Data sheet", &sheets), None);
+    }
+
+    #[test]
+    fn test_detect_url() {
+        assert_eq!(
+            detect_url(" https://example.com/a?b=1 "),
+            Some("https://example.com/a?b=1".to_string())
+        );
+        assert_eq!(
+            detect_url("mailto:ops@example.com"),
+            Some("mailto:ops@example.com".to_string())
+        );
+        assert_eq!(
+            detect_url("www.example.com"),
+            Some("https://www.example.com".to_string())
+        );
+        assert_eq!(detect_url("http://"), None);
+        assert_eq!(detect_url("see https://example.com"), None);
+        assert_eq!(detect_url("www.example"), None);
+        assert_eq!(detect_url("example.com"), None);
+    }
 }
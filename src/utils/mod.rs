@@ -1,5 +1,9 @@
 mod cell_navigation;
 mod helpers;
+mod natural_sort;
+mod text_width;
 
 pub use cell_navigation::*;
 pub use helpers::*;
+pub use natural_sort::*;
+pub use text_width::*;
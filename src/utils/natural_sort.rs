@@ -0,0 +1,171 @@
+use std::cmp::Ordering;
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// How cell values are compared when ordering or grouping them
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SortMode {
+    /// Plain string comparison, so "item10" sorts before "item2"
+    #[default]
+    Lexical,
+    /// Digit runs compare by numeric value and letters ignore case, so "item2" sorts before "item10"
+    Natural,
+}
+
+impl SortMode {
+    #[must_use]
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "lexical" | "lex" => Some(Self::Lexical),
+            "natural" | "nat" => Some(Self::Natural),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Lexical => "lexical",
+            Self::Natural => "natural",
+        }
+    }
+
+    #[must_use]
+    pub fn compare(self, a: &str, b: &str) -> Ordering {
+        match self {
+            Self::Lexical => a.cmp(b),
+            Self::Natural => natural_cmp(a, b),
+        }
+    }
+
+    /// Key under which values that compare equal in this mode collapse into one group
+    #[must_use]
+    pub fn group_key(self, value: &str) -> String {
+        match self {
+            Self::Lexical => value.to_string(),
+            Self::Natural => natural_key(value),
+        }
+    }
+}
+
+/// Compare two strings treating each run of ASCII digits as a number and ignoring letter case.
+/// Leading zeros are not significant, so "A007" and "a7" compare equal.
+#[must_use]
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let mut left = a.chars().peekable();
+    let mut right = b.chars().peekable();
+
+    loop {
+        match (left.peek().copied(), right.peek().copied()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(l), Some(r)) if l.is_ascii_digit() && r.is_ascii_digit() => {
+                let left_digits = take_digits(&mut left);
+                let right_digits = take_digits(&mut right);
+                let ordering = left_digits
+                    .len()
+                    .cmp(&right_digits.len())
+                    .then_with(|| left_digits.cmp(&right_digits));
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            (Some(l), Some(r)) => {
+                let ordering = l.to_lowercase().cmp(r.to_lowercase());
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+                left.next();
+                right.next();
+            }
+        }
+    }
+}
+
+/// Normalized form of `value` such that two values have the same key exactly when
+/// [`natural_cmp`] considers them equal
+#[must_use]
+pub fn natural_key(value: &str) -> String {
+    let mut key = String::with_capacity(value.len());
+    let mut chars = value.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_ascii_digit() {
+            key.push_str(&take_digits(&mut chars));
+        } else {
+            key.extend(c.to_lowercase());
+            chars.next();
+        }
+    }
+
+    key
+}
+
+/// Consume a run of ASCII digits and return it without leading zeros ("0" stays "0")
+fn take_digits(chars: &mut Peekable<Chars<'_>>) -> String {
+    let mut digits = String::new();
+    while let Some(&c) = chars.peek() {
+        if !c.is_ascii_digit() {
+            break;
+        }
+        if !(digits.is_empty() && c == '0') {
+            digits.push(c);
+        }
+        chars.next();
+    }
+
+    if digits.is_empty() {
+        digits.push('0');
+    }
+    digits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn natural_cmp_orders_digit_runs_by_value() {
+        let mut ids = vec![
+            "item10",
+            "item2",
+            "Item1",
+            "item02b",
+            "host-9.lan",
+            "host-10.lan",
+        ];
+        ids.sort_by(|a, b| natural_cmp(a, b));
+
+        assert_eq!(
+            ids,
+            vec![
+                "host-9.lan",
+                "host-10.lan",
+                "Item1",
+                "item2",
+                "item02b",
+                "item10"
+            ]
+        );
+    }
+
+    #[test]
+    fn natural_key_matches_natural_equality() {
+        assert_eq!(natural_cmp("SKU-007", "sku-7"), Ordering::Equal);
+        assert_eq!(natural_key("SKU-007"), natural_key("sku-7"));
+        assert_eq!(natural_key("v0.00"), "v0.0");
+        assert_ne!(natural_key("a1b"), natural_key("a1c"));
+    }
+
+    #[test]
+    fn lexical_mode_keeps_plain_string_order() {
+        assert_eq!(SortMode::Lexical.compare("item10", "item2"), Ordering::Less);
+        assert_eq!(
+            SortMode::Natural.compare("item10", "item2"),
+            Ordering::Greater
+        );
+        assert_eq!(SortMode::parse("NATURAL"), Some(SortMode::Natural));
+        assert_eq!(SortMode::parse("random"), None);
+    }
+}
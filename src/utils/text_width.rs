@@ -0,0 +1,85 @@
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Columns `text` takes up in a terminal: two for wide CJK characters and emoji, none for
+/// combining marks and zero-width joiners
+#[must_use]
+pub fn text_width(text: &str) -> usize {
+    text.width()
+}
+
+/// The longest start of `text` at most `width` columns wide. Characters a terminal draws as
+/// one, such as a letter and its accents or a joined emoji, are kept together
+#[must_use]
+pub fn truncate_to_width(text: &str, width: usize) -> &str {
+    let mut used = 0;
+    for (index, grapheme) in text.grapheme_indices(true) {
+        used += grapheme.width();
+        if used > width {
+            return &text[..index];
+        }
+    }
+    text
+}
+
+/// The longest end of `text` at most `width` columns wide, keeping graphemes together
+#[must_use]
+pub fn truncate_start_to_width(text: &str, width: usize) -> &str {
+    let mut used = 0;
+    for (index, grapheme) in text.grapheme_indices(true).rev() {
+        used += grapheme.width();
+        if used > width {
+            return &text[index + grapheme.len()..];
+        }
+    }
+    text
+}
+
+/// `text` cut into pieces at most `width` columns wide between graphemes. A grapheme wider
+/// than `width` gets a piece of its own
+#[must_use]
+pub fn split_to_width(text: &str, width: usize) -> Vec<&str> {
+    let mut pieces = Vec::new();
+    let mut start = 0;
+    let mut used = 0;
+    for (index, grapheme) in text.grapheme_indices(true) {
+        let grapheme_width = grapheme.width();
+        if used + grapheme_width > width && index > start {
+            pieces.push(&text[start..index]);
+            start = index;
+            used = 0;
+        }
+        used += grapheme_width;
+    }
+    if start < text.len() {
+        pieces.push(&text[start..]);
+    }
+    pieces
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{split_to_width, text_width, truncate_start_to_width, truncate_to_width};
+
+    #[test]
+    fn widths_follow_the_terminal_and_cuts_keep_graphemes_whole() {
+        assert_eq!(text_width("abc"), 3);
+        assert_eq!(text_width("名前"), 4);
+        // `e` with a combining acute accent takes one column
+        assert_eq!(text_width("cafe\u{301}"), 4);
+        assert_eq!(text_width("，。"), 4);
+        assert_eq!(text_width("🎉"), 2);
+
+        assert_eq!(truncate_to_width("cafe\u{301}s", 4), "cafe\u{301}");
+        assert_eq!(truncate_to_width("名前abc", 3), "名");
+        assert_eq!(truncate_to_width("abc", 5), "abc");
+        assert_eq!(truncate_start_to_width("ab名前", 3), "前");
+
+        assert_eq!(split_to_width("a名前b", 2), vec!["a", "名", "前", "b"]);
+        assert_eq!(
+            split_to_width("e\u{301}e\u{301}e", 2),
+            vec!["e\u{301}e\u{301}", "e"]
+        );
+        assert_eq!(split_to_width("名", 1), vec!["名"]);
+    }
+}
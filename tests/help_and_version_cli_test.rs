@@ -42,7 +42,11 @@ fn subcommand_help_prints_to_stdout_and_exits_zero() {
         "unexpected stdout: {stdout}"
     );
     assert!(
-        stdout.contains("Usage: excel-cli ui <FILE>"),
+        stdout.contains("Usage: excel-cli ui [OPTIONS] <FILES>..."),
+        "unexpected stdout: {stdout}"
+    );
+    assert!(
+        stdout.contains("--lazy-loading"),
         "unexpected stdout: {stdout}"
     );
 }
@@ -147,3 +151,30 @@ fn version_prints_to_stdout_and_exits_zero() {
         format!("excel-cli {}", env!("CARGO_PKG_VERSION"))
     );
 }
+
+#[test]
+fn build_info_prints_version_commit_and_features() {
+    let stdout = assert_successful_help(&["--build-info"]);
+
+    assert!(
+        stdout.starts_with(&format!("excel-cli {}\n", env!("CARGO_PKG_VERSION"))),
+        "unexpected stdout: {stdout}"
+    );
+    for expected in ["git: ", "target: ", "profile: ", "features: "] {
+        assert!(
+            stdout.contains(expected),
+            "expected {expected:?} in stdout: {stdout}"
+        );
+    }
+}
+
+#[test]
+fn build_info_cannot_be_combined_with_a_subcommand() {
+    let output = Command::new(excel_cli_bin())
+        .args(["--build-info", "ui", "book.xlsx"])
+        .output()
+        .expect("Failed to execute excel-cli --build-info ui");
+
+    assert_ne!(output.status.code(), Some(0));
+    assert!(output.stdout.is_empty());
+}